@@ -0,0 +1,383 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use kernel::{Object, Operation, SystemError, SystemResult};
+use representation::Binary;
+use sql_model::Id;
+
+use crate::{
+    catalog_export::validate, row_ids_key, sequence::Sequence, sequence_key, CatalogExport, DataManager, TableLock,
+};
+
+/// version of the backup format, backups of other versions are not restored
+pub const BACKUP_VERSION: u32 = 2;
+
+/// rows of a table are streamed in batches, a table is never kept in memory as a whole
+const ROWS_BATCH: usize = 1024;
+
+/// a backup starts with its version and the catalog, entries follow them until the end of the backup.
+/// Rows are kept with their keys as bytes, the table continues to generate row ids after the kept state
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Entry {
+    Table {
+        schema_name: String,
+        table_name: String,
+        row_ids: Option<Vec<u8>>,
+    },
+    /// rows of the table of the latest `Table` entry
+    Rows(Vec<(Vec<u8>, Vec<u8>)>),
+    Sequence {
+        schema_name: String,
+        sequence_name: String,
+        state: Vec<u8>,
+    },
+    End,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BackupError {
+    /// tables could not be locked as a transaction that holds one of them waits for the backup
+    Deadlock,
+    Io(String),
+    Malformed(String),
+    NotEmpty,
+}
+
+/// streaming is stopped by the first error of the backup
+macro_rules! streamed {
+    ($result:expr) => {
+        if let Err(error) = $result? {
+            return Ok(Err(error));
+        }
+    };
+}
+
+/// backups are written into files and migrated data managers entry by entry
+trait BackupSink {
+    fn catalog(&mut self, catalog: CatalogExport) -> SystemResult<Result<(), BackupError>>;
+
+    fn entry(&mut self, entry: Entry) -> SystemResult<Result<(), BackupError>>;
+}
+
+struct BackupFile<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> BackupSink for BackupFile<W> {
+    fn catalog(&mut self, catalog: CatalogExport) -> SystemResult<Result<(), BackupError>> {
+        Ok(bincode::serialize_into(&mut self.writer, &BACKUP_VERSION)
+            .and_then(|()| bincode::serialize_into(&mut self.writer, &catalog))
+            .map_err(|error| BackupError::Io(error.to_string())))
+    }
+
+    fn entry(&mut self, entry: Entry) -> SystemResult<Result<(), BackupError>> {
+        let end = entry == Entry::End;
+        streamed!(Ok(
+            bincode::serialize_into(&mut self.writer, &entry).map_err(|error| BackupError::Io(error.to_string()))
+        ));
+        if end {
+            streamed!(Ok(self
+                .writer
+                .flush()
+                .map_err(|error| BackupError::Io(error.to_string()))));
+        }
+        Ok(Ok(()))
+    }
+}
+
+/// the whole backup is read before anything is restored from it, rows have to follow a table listed in the catalog
+#[derive(Default)]
+struct BackupCheck {
+    catalog: Option<CatalogExport>,
+    has_table: bool,
+}
+
+impl BackupSink for BackupCheck {
+    fn catalog(&mut self, catalog: CatalogExport) -> SystemResult<Result<(), BackupError>> {
+        if let Err(message) = validate(&catalog) {
+            return Ok(Err(BackupError::Malformed(message)));
+        }
+        self.catalog = Some(catalog);
+        Ok(Ok(()))
+    }
+
+    fn entry(&mut self, entry: Entry) -> SystemResult<Result<(), BackupError>> {
+        match entry {
+            Entry::Table {
+                schema_name,
+                table_name,
+                ..
+            } => {
+                let listed = self
+                    .catalog
+                    .iter()
+                    .flat_map(|catalog| catalog.schemas.iter())
+                    .any(|schema| {
+                        schema.name == schema_name && schema.tables.iter().any(|listed| listed.name == table_name)
+                    });
+                if !listed {
+                    return Ok(Err(BackupError::Malformed(format!(
+                        "rows of \"{}.{}\" are kept but the table is not listed",
+                        schema_name, table_name
+                    ))));
+                }
+                self.has_table = true;
+            }
+            Entry::Rows(_) if !self.has_table => {
+                return Ok(Err(BackupError::Malformed(
+                    "rows are kept before their table".to_owned(),
+                )));
+            }
+            Entry::Rows(_) | Entry::Sequence { .. } | Entry::End => {}
+        }
+        Ok(Ok(()))
+    }
+}
+
+/// entries are restored into the data manager as they are streamed
+struct Restore<'d> {
+    data_manager: &'d DataManager,
+    table_id: Option<(Id, Id)>,
+}
+
+impl BackupSink for Restore<'_> {
+    fn catalog(&mut self, catalog: CatalogExport) -> SystemResult<Result<(), BackupError>> {
+        if !self.data_manager.schema_names().is_empty() {
+            return Ok(Err(BackupError::NotEmpty));
+        }
+        self.data_manager.import(&catalog).map(Ok)
+    }
+
+    fn entry(&mut self, entry: Entry) -> SystemResult<Result<(), BackupError>> {
+        let data_manager = self.data_manager;
+        match entry {
+            Entry::Table {
+                schema_name,
+                table_name,
+                row_ids,
+            } => {
+                let table_id = data_manager.imported_table(&schema_name, &table_name);
+                if let Some(row_ids) = row_ids.map(Binary::with_data) {
+                    if let Some(sequence) = Sequence::from_values(&row_ids) {
+                        data_manager.persist_sequence(row_ids_key(&table_id), row_ids)?;
+                        data_manager
+                            .record_id_generators
+                            .write()
+                            .expect("to acquire write lock")
+                            .insert(table_id, sequence);
+                    }
+                }
+                self.table_id = Some(table_id);
+            }
+            Entry::Rows(rows) => {
+                if let Some(table_id) = self.table_id {
+                    data_manager.write_into(
+                        &Box::new(table_id),
+                        rows.into_iter()
+                            .map(|(key, values)| (Binary::with_data(key), Binary::with_data(values)))
+                            .collect(),
+                    )?;
+                }
+            }
+            Entry::Sequence {
+                schema_name,
+                sequence_name,
+                state,
+            } => {
+                if let Some(schema_id) = data_manager.schema_exists(&schema_name) {
+                    let state = Binary::with_data(state);
+                    if let Some(restored) = Sequence::from_values(&state) {
+                        data_manager.persist_sequence(sequence_key(schema_id, &sequence_name), state)?;
+                        data_manager
+                            .sequences
+                            .write()
+                            .expect("to acquire write lock")
+                            .insert((schema_id, sequence_name), restored);
+                    }
+                }
+            }
+            Entry::End => {}
+        }
+        Ok(Ok(()))
+    }
+}
+
+/// entries of the backup are sent into the sink one by one
+fn read_backup<R: Read>(mut reader: R, sink: &mut dyn BackupSink) -> SystemResult<Result<(), BackupError>> {
+    let malformed = |error: bincode::Error| BackupError::Malformed(error.to_string());
+    match bincode::deserialize_from::<_, u32>(&mut reader).map_err(malformed) {
+        Ok(BACKUP_VERSION) => {}
+        Ok(version) => {
+            return Ok(Err(BackupError::Malformed(format!(
+                "unsupported backup version {}",
+                version
+            ))))
+        }
+        Err(error) => return Ok(Err(error)),
+    }
+    match bincode::deserialize_from(&mut reader).map_err(malformed) {
+        Ok(catalog) => streamed!(sink.catalog(catalog)),
+        Err(error) => return Ok(Err(error)),
+    }
+    loop {
+        match bincode::deserialize_from(&mut reader).map_err(malformed) {
+            Ok(Entry::End) => return sink.entry(Entry::End),
+            Ok(entry) => streamed!(sink.entry(entry)),
+            Err(error) => return Ok(Err(error)),
+        }
+    }
+}
+
+impl DataManager {
+    /// streams tables into the file one by one, the file is replaced only when the backup is completely written.
+    /// Tables are read locked, so the backup has no partial writes of statements and transactions, and each of
+    /// them is unlocked for writers as soon as its rows are written
+    pub fn backup(&self, path: &Path) -> SystemResult<Result<(), BackupError>> {
+        let partial = path.with_extension("partial");
+        let writer = match File::create(&partial) {
+            Ok(file) => BufWriter::new(file),
+            Err(error) => return Ok(Err(BackupError::Io(error.to_string()))),
+        };
+        match self.stream_backup(&mut BackupFile { writer })? {
+            Ok(()) => Ok(fs::rename(&partial, path).map_err(|error| BackupError::Io(error.to_string()))),
+            Err(error) => {
+                // the partial file is only removed, the error of the backup is reported
+                let _ = fs::remove_file(&partial);
+                Ok(Err(error))
+            }
+        }
+    }
+
+    /// recreates schemas, tables and sequences from the backup file,
+    /// it can be restored only while there are no user schemas. The file is read through before it is
+    /// restored, so a malformed backup restores nothing
+    pub fn restore_backup(&self, path: &Path) -> SystemResult<Result<(), BackupError>> {
+        let open = || File::open(path).map(BufReader::new);
+        match open() {
+            Ok(reader) => streamed!(read_backup(reader, &mut BackupCheck::default())),
+            Err(error) => return Ok(Err(BackupError::Io(error.to_string()))),
+        }
+        match open() {
+            Ok(reader) => read_backup(
+                reader,
+                &mut Restore {
+                    data_manager: self,
+                    table_id: None,
+                },
+            ),
+            Err(error) => Ok(Err(BackupError::Io(error.to_string()))),
+        }
+    }
+
+    /// copies schemas, tables, rows and sequences into the target data manager that can be kept by another
+    /// storage backend, tables are locked while they are read as they are for a backup
+    pub fn migrate(&self, target: &DataManager) -> SystemResult<Result<(), BackupError>> {
+        self.stream_backup(&mut Restore {
+            data_manager: target,
+            table_id: None,
+        })
+    }
+
+    fn stream_backup(&self, sink: &mut dyn BackupSink) -> SystemResult<Result<(), BackupError>> {
+        let owner = self.lock_owner();
+        // tables are locked in order of their ids, read locks of backups don't wait for each other
+        // and backups are not canceled
+        let table_ids = self.table_ids();
+        for table_id in table_ids.iter() {
+            if self.table_locks.lock_shared(*table_id, owner, &|| false) != TableLock::Acquired {
+                self.unlock_tables(owner);
+                return Ok(Err(BackupError::Deadlock));
+            }
+        }
+        let streamed = self.stream_tables(table_ids, sink, owner);
+        self.unlock_tables(owner);
+        streamed
+    }
+
+    fn stream_tables(
+        &self,
+        table_ids: Vec<(Id, Id)>,
+        sink: &mut dyn BackupSink,
+        owner: u64,
+    ) -> SystemResult<Result<(), BackupError>> {
+        streamed!(sink.catalog(self.catalog()?));
+        for table_id in table_ids {
+            let (schema_name, table_name) = match self.table_name(&Box::new(table_id)) {
+                Some(full_name) => full_name,
+                None => continue,
+            };
+            let row_ids = self
+                .record_id_generators
+                .read()
+                .expect("to acquire read lock")
+                .get(&table_id)
+                .map(|sequence| sequence.to_values().to_bytes().to_vec());
+            streamed!(sink.entry(Entry::Table {
+                schema_name,
+                table_name,
+                row_ids,
+            }));
+            let mut rows = Vec::with_capacity(ROWS_BATCH);
+            for row in self.full_scan(&Box::new(table_id))? {
+                let (key, values) = match row {
+                    Ok(Ok(row)) => row,
+                    Ok(Err(_storage_error)) => {
+                        let (schema_id, table_id) = table_id;
+                        return Err(SystemError::bug_in_sql_engine(
+                            Operation::Access,
+                            Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                        ));
+                    }
+                    Err(io_error) => return Err(SystemError::io(io_error)),
+                };
+                rows.push((key.to_bytes().to_vec(), values.to_bytes().to_vec()));
+                if rows.len() == ROWS_BATCH {
+                    streamed!(sink.entry(Entry::Rows(std::mem::take(&mut rows))));
+                }
+            }
+            if !rows.is_empty() {
+                streamed!(sink.entry(Entry::Rows(rows)));
+            }
+            // rows of the table are in the backup, writers don't wait for the rest of it
+            self.table_locks.unlock(&table_id, owner);
+        }
+        let sequences = {
+            let schemas = self.schemas.read().expect("to acquire read lock");
+            self.sequences
+                .read()
+                .expect("to acquire read lock")
+                .iter()
+                .filter_map(|((schema_id, sequence_name), sequence)| {
+                    schemas.get(schema_id).map(|schema_name| Entry::Sequence {
+                        schema_name: schema_name.clone(),
+                        sequence_name: sequence_name.clone(),
+                        state: sequence.to_values().to_bytes().to_vec(),
+                    })
+                })
+                .collect::<Vec<Entry>>()
+        };
+        for sequence in sequences {
+            streamed!(sink.entry(sequence));
+        }
+        sink.entry(Entry::End)
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sql_model::sql_types::SqlType;
+
+use crate::{CatalogExport, ColumnDefinition, PartitionExport, TableExport, SUPERUSER};
+
+type TableName<'c> = (&'c str, &'c str);
+
+/// tables of a catalog by their qualified names, partitions know their partitioned tables
+struct Tables<'c> {
+    tables: BTreeMap<TableName<'c>, &'c TableExport>,
+    parents: BTreeMap<TableName<'c>, (TableName<'c>, &'c PartitionExport)>,
+}
+
+impl<'c> Tables<'c> {
+    fn new(catalog: &'c CatalogExport) -> Tables<'c> {
+        let mut tables = BTreeMap::new();
+        let mut parents = BTreeMap::new();
+        for schema in catalog.schemas.iter() {
+            for table in schema.tables.iter() {
+                tables.insert((schema.name.as_str(), table.name.as_str()), table);
+                for partition in table.partitions.iter() {
+                    parents.insert(
+                        (partition.schema_name.as_str(), partition.table_name.as_str()),
+                        ((schema.name.as_str(), table.name.as_str()), partition),
+                    );
+                }
+            }
+        }
+        Tables { tables, parents }
+    }
+
+    fn parent(&self, name: &TableName<'c>) -> Option<(TableName<'c>, Option<i64>, Option<i64>)> {
+        self.parents
+            .get(name)
+            .map(|(parent, partition)| (*parent, partition.from, partition.to))
+    }
+
+    fn dependencies(&self, name: &TableName<'c>) -> Vec<TableName<'c>> {
+        let mut dependencies = self.tables[name]
+            .columns
+            .iter()
+            .filter_map(ColumnDefinition::referenced_column)
+            .map(|reference| (reference.schema_name(), reference.table_name()))
+            .collect::<Vec<_>>();
+        dependencies.extend(self.parents.get(name).map(|(parent, _)| *parent));
+        dependencies
+    }
+
+    /// tables come after tables they reference and partitions after their partitioned tables,
+    /// tables of a reference cycle are ordered by name
+    fn dependency_order(&self, names: &BTreeSet<TableName<'c>>) -> Vec<TableName<'c>> {
+        let mut ordered = vec![];
+        let mut pending = names.iter().cloned().collect::<Vec<_>>();
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|name| {
+                self.dependencies(name)
+                    .iter()
+                    .all(|dependency| dependency == name || !names.contains(dependency) || ordered.contains(dependency))
+            });
+            ordered.push(pending.remove(ready.unwrap_or(0)));
+        }
+        ordered
+    }
+}
+
+/// statements that migrate the `from` catalog to the `to` one, tables are matched by qualified names
+/// and columns by names. Changes of a partition key recreate the table, other changes of tables that
+/// exist in both catalogs are made with `ALTER TABLE` and keep their data
+pub fn catalog_migration(from: &CatalogExport, to: &CatalogExport) -> Vec<String> {
+    let source = Tables::new(from);
+    let target = Tables::new(to);
+    let source_schemas = from
+        .schemas
+        .iter()
+        .map(|schema| schema.name.as_str())
+        .collect::<BTreeSet<_>>();
+    let target_schemas = to
+        .schemas
+        .iter()
+        .map(|schema| schema.name.as_str())
+        .collect::<BTreeSet<_>>();
+
+    // a table is dropped with its schema and a partition with its partitioned table
+    let mut recreated = source
+        .tables
+        .iter()
+        .filter(|(name, table)| {
+            target
+                .tables
+                .get(name)
+                .map(|target_table| target_table.partition_key != table.partition_key)
+                .unwrap_or(false)
+        })
+        .map(|(name, _)| *name)
+        .collect::<BTreeSet<_>>();
+    let dropped_parents = source
+        .tables
+        .keys()
+        .filter(|name| recreated.contains(name) || !target.tables.contains_key(name))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    recreated.extend(source.tables.keys().cloned().filter(|name| {
+        target.tables.contains_key(name)
+            && source
+                .parent(name)
+                .map(|(parent, _, _)| dropped_parents.contains(&parent))
+                .unwrap_or(false)
+    }));
+    let dropped = source
+        .tables
+        .keys()
+        .filter(|name| {
+            target_schemas.contains(name.0) && (recreated.contains(name) || !target.tables.contains_key(name))
+        })
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let created = target
+        .tables
+        .keys()
+        .filter(|name| recreated.contains(name) || !source.tables.contains_key(name))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let kept = target
+        .tables
+        .keys()
+        .filter(|name| !created.contains(name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut statements = vec![];
+    for name in kept.iter() {
+        if let Some((parent, _, _)) = source.parent(name) {
+            if source.parent(name) != target.parent(name) {
+                statements.push(format!(
+                    "ALTER TABLE {} DETACH PARTITION {};",
+                    qualified(&parent),
+                    qualified(name)
+                ));
+            }
+        }
+        if is_partition(&source, &target, name) {
+            continue;
+        }
+        let (source_table, target_table) = (source.tables[name], target.tables[name]);
+        if primary_key(source_table) != primary_key(target_table) && !primary_key(source_table).is_empty() {
+            statements.push(format!(
+                "ALTER TABLE {} DROP CONSTRAINT {}_pkey;",
+                qualified(name),
+                name.1
+            ));
+        }
+        for column in source_table.columns.iter() {
+            let target_column = target_table
+                .columns
+                .iter()
+                .find(|target_column| target_column.name == column.name);
+            let readded = target_column.map(|target_column| is_readded(column, target_column));
+            if column.references.is_some()
+                && (readded != Some(false)
+                    || target_column.and_then(ColumnDefinition::referenced_column) != column.referenced_column())
+            {
+                statements.push(format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {}_{}_fkey;",
+                    qualified(name),
+                    name.1,
+                    column.name
+                ));
+            }
+        }
+        for column in source_table.columns.iter() {
+            let target_column = target_table
+                .columns
+                .iter()
+                .find(|target_column| target_column.name == column.name);
+            if target_column.map(|target_column| is_readded(column, target_column)) != Some(false) {
+                statements.push(format!("ALTER TABLE {} DROP COLUMN {};", qualified(name), column.name));
+            }
+        }
+    }
+
+    for name in source.dependency_order(&dropped).into_iter().rev() {
+        statements.push(format!("DROP TABLE {};", qualified(&name)));
+    }
+    for schema_name in source_schemas.difference(&target_schemas) {
+        statements.push(format!("DROP SCHEMA {} CASCADE;", schema_name));
+    }
+
+    for schema in to.schemas.iter() {
+        let source_owner = from
+            .schemas
+            .iter()
+            .find(|source_schema| source_schema.name == schema.name)
+            .map(|source_schema| source_schema.owner.as_str());
+        if source_owner.is_none() {
+            statements.push(format!("CREATE SCHEMA {};", schema.name));
+        }
+        let changed_owner = match source_owner {
+            Some(source_owner) => source_owner != schema.owner,
+            None => schema.owner != SUPERUSER,
+        };
+        if changed_owner && !schema.owner.is_empty() {
+            statements.push(format!("ALTER SCHEMA {} OWNER TO {};", schema.name, schema.owner));
+        }
+    }
+
+    for name in target.dependency_order(&created) {
+        match target.parent(&name) {
+            Some((parent, from, to)) => statements.push(format!(
+                "CREATE TABLE {} PARTITION OF {} FOR VALUES FROM ({}) TO ({});",
+                qualified(&name),
+                qualified(&parent),
+                bound(from, "MINVALUE"),
+                bound(to, "MAXVALUE")
+            )),
+            None => statements.push(create_table(&name, target.tables[&name])),
+        }
+    }
+
+    for name in kept.iter() {
+        if !is_partition(&source, &target, name) {
+            let (source_table, target_table) = (source.tables[name], target.tables[name]);
+            for column in target_table.columns.iter() {
+                let source_column = source_table
+                    .columns
+                    .iter()
+                    .find(|source_column| source_column.name == column.name);
+                let source_column = match source_column {
+                    Some(source_column) if !is_readded(source_column, column) => source_column,
+                    _ => {
+                        statements.push(format!(
+                            "ALTER TABLE {} ADD COLUMN {};",
+                            qualified(name),
+                            column_definition(column)
+                        ));
+                        continue;
+                    }
+                };
+                if source_column.sql_type != column.sql_type || source_column.enum_type != column.enum_type {
+                    statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+                        qualified(name),
+                        column.name,
+                        type_name(column, None)
+                    ));
+                }
+                if source_column.not_null && !column.not_null {
+                    statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} DROP NOT NULL;",
+                        qualified(name),
+                        column.name
+                    ));
+                }
+                if !source_column.not_null && column.not_null && !column.primary_key {
+                    statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} SET NOT NULL;",
+                        qualified(name),
+                        column.name
+                    ));
+                }
+                if let Some(reference) = column.referenced_column() {
+                    if source_column.referenced_column() != Some(reference) {
+                        statements.push(format!(
+                            "ALTER TABLE {} ADD CONSTRAINT {}_{}_fkey FOREIGN KEY ({}) REFERENCES {}.{} ({}) ON DELETE {};",
+                            qualified(name),
+                            name.1,
+                            column.name,
+                            column.name,
+                            reference.schema_name(),
+                            reference.table_name(),
+                            reference.column_name(),
+                            reference.on_delete().as_str()
+                        ));
+                    }
+                }
+            }
+            let target_primary_key = primary_key(target_table);
+            if primary_key(source_table) != target_primary_key && !target_primary_key.is_empty() {
+                statements.push(format!(
+                    "ALTER TABLE {} ADD PRIMARY KEY ({});",
+                    qualified(name),
+                    target_primary_key.join(", ")
+                ));
+            }
+        }
+        if let Some((parent, from, to)) = target.parent(name) {
+            if source.parent(name) != target.parent(name) {
+                statements.push(format!(
+                    "ALTER TABLE {} ATTACH PARTITION {} FOR VALUES FROM ({}) TO ({});",
+                    qualified(&parent),
+                    qualified(name),
+                    bound(from, "MINVALUE"),
+                    bound(to, "MAXVALUE")
+                ));
+            }
+        }
+    }
+    statements
+}
+
+/// columns of partitions are defined by their partitioned tables
+fn is_partition(source: &Tables, target: &Tables, name: &TableName) -> bool {
+    source.parents.contains_key(name) || target.parents.contains_key(name)
+}
+
+/// serial and generated columns can't be altered into or out of, they are dropped and added again
+fn is_readded(source_column: &ColumnDefinition, target_column: &ColumnDefinition) -> bool {
+    source_column.serial_sequence != target_column.serial_sequence
+        || source_column.generation_expression != target_column.generation_expression
+}
+
+fn primary_key(table: &TableExport) -> Vec<&str> {
+    table
+        .columns
+        .iter()
+        .filter(|column| column.primary_key)
+        .map(|column| column.name.as_str())
+        .collect()
+}
+
+fn qualified(name: &TableName) -> String {
+    format!("{}.{}", name.0, name.1)
+}
+
+fn bound(value: Option<i64>, unbounded: &str) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| unbounded.to_owned())
+}
+
+fn create_table(name: &TableName, table: &TableExport) -> String {
+    let mut definitions = table.columns.iter().map(column_definition).collect::<Vec<_>>();
+    let primary_key = primary_key(table);
+    if !primary_key.is_empty() {
+        definitions.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+    let partition_by = match &table.partition_key {
+        Some(partition_key) => format!(" PARTITION BY RANGE ({})", partition_key),
+        None => String::new(),
+    };
+    format!(
+        "CREATE TABLE {} ({}){};",
+        qualified(name),
+        definitions.join(", "),
+        partition_by
+    )
+}
+
+/// primary key is a table constraint, `serial` columns are implicitly NOT NULL
+fn column_definition(column: &ColumnDefinition) -> String {
+    let mut definition = format!("{} {}", column.name, type_name(column, column.serial_sequence()));
+    if column.not_null && column.serial_sequence.is_none() && !column.primary_key {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(reference) = column.referenced_column() {
+        definition.push_str(
+            format!(
+                " REFERENCES {}.{} ({}) ON DELETE {}",
+                reference.schema_name(),
+                reference.table_name(),
+                reference.column_name(),
+                reference.on_delete().as_str()
+            )
+            .as_str(),
+        );
+    }
+    if let Some(expression) = column.generation_expression() {
+        definition.push_str(format!(" GENERATED ALWAYS AS ({}) STORED", expression).as_str());
+    }
+    definition
+}
+
+/// enum columns are created with the name of their type
+fn type_name(column: &ColumnDefinition, serial_sequence: Option<&str>) -> String {
+    if let Some(enum_type) = column.enum_type() {
+        return enum_type.name().to_owned();
+    }
+    match (column.sql_type, serial_sequence) {
+        (SqlType::SmallInt(_), Some(_)) => "smallserial".to_owned(),
+        (SqlType::Integer(_), Some(_)) => "serial".to_owned(),
+        (SqlType::BigInt(_), Some(_)) => "bigserial".to_owned(),
+        (SqlType::Char(length), _) => format!("char({})", length),
+        (SqlType::VarChar(length), _) => format!("varchar({})", length),
+        (sql_type, _) => sql_type.to_string(),
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use kernel::SystemResult;
+use sql_model::Id;
+
+use crate::{ColumnDefinition, DataManager, RangePartition};
+
+/// version of the export format, catalogs of other versions are not imported
+pub const CATALOG_EXPORT_VERSION: u32 = 1;
+
+/// portable description of user schemas and tables, objects are referenced by names and not by ids
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CatalogExport {
+    pub version: u32,
+    pub schemas: Vec<SchemaExport>,
+}
+
+impl CatalogExport {
+    /// parses and validates exported catalog
+    pub fn from_json(json: &str) -> Result<CatalogExport, String> {
+        let catalog = serde_json::from_str::<CatalogExport>(json).map_err(|error| error.to_string())?;
+        validate(&catalog)?;
+        Ok(catalog)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SchemaExport {
+    pub name: String,
+    pub owner: String,
+    pub tables: Vec<TableExport>,
+}
+
+/// columns carry their constraints, a partitioned table lists its partitions in order of attachment
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TableExport {
+    pub name: String,
+    pub columns: Vec<ColumnDefinition>,
+    #[serde(default)]
+    pub partition_key: Option<String>,
+    #[serde(default)]
+    pub partitions: Vec<PartitionExport>,
+}
+
+/// `None` bounds stand for `MINVALUE` and `MAXVALUE`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PartitionExport {
+    pub schema_name: String,
+    pub table_name: String,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CatalogImportError {
+    Malformed(String),
+    NotEmpty,
+}
+
+impl DataManager {
+    /// describes user schemas and tables, schemas and tables are ordered by name
+    pub fn catalog(&self) -> SystemResult<CatalogExport> {
+        let mut schemas = vec![];
+        for schema_name in self.schema_names() {
+            let schema_id = match self.schema_exists(&schema_name) {
+                Some(schema_id) => schema_id,
+                None => continue,
+            };
+            let mut tables = vec![];
+            for (_, table_name) in self
+                .table_names()
+                .into_iter()
+                .filter(|(table_schema, _)| table_schema == &schema_name)
+            {
+                let table_id = match self.table_exists(&schema_name.as_str(), &table_name.as_str()) {
+                    Some((schema_id, Some(table_id))) => (schema_id, table_id),
+                    _ => continue,
+                };
+                let (partition_key, partitions) = match self.range_partitioning(&Box::new(table_id)) {
+                    Some(partitioning) => (
+                        Some(partitioning.column_name().to_owned()),
+                        partitioning
+                            .partitions()
+                            .iter()
+                            .filter_map(|partition| {
+                                self.table_name(&Box::new(partition.table_id()))
+                                    .map(|(schema_name, table_name)| PartitionExport {
+                                        schema_name,
+                                        table_name,
+                                        from: partition.from(),
+                                        to: partition.to(),
+                                    })
+                            })
+                            .collect(),
+                    ),
+                    None => (None, vec![]),
+                };
+                tables.push(TableExport {
+                    name: table_name,
+                    columns: self.table_columns(&Box::new(table_id))?,
+                    partition_key,
+                    partitions,
+                });
+            }
+            schemas.push(SchemaExport {
+                owner: self.schema_owner(schema_id).unwrap_or_default(),
+                name: schema_name,
+                tables,
+            });
+        }
+        Ok(CatalogExport {
+            version: CATALOG_EXPORT_VERSION,
+            schemas,
+        })
+    }
+
+    pub fn export_catalog(&self) -> SystemResult<String> {
+        Ok(serde_json::to_string(&self.catalog()?).expect("catalog is serializable"))
+    }
+
+    /// recreates exported schemas and tables, the catalog is validated before anything is created
+    /// and can be imported only while there are no user schemas
+    pub fn import_catalog(&self, json: &str) -> SystemResult<Result<(), CatalogImportError>> {
+        let catalog = match CatalogExport::from_json(json) {
+            Ok(catalog) => catalog,
+            Err(message) => return Ok(Err(CatalogImportError::Malformed(message))),
+        };
+        if !self.schema_names().is_empty() {
+            return Ok(Err(CatalogImportError::NotEmpty));
+        }
+        self.import(&catalog).map(Ok)
+    }
+
+    /// creates schemas and tables of the validated catalog
+    pub(crate) fn import(&self, catalog: &CatalogExport) -> SystemResult<()> {
+        for schema in catalog.schemas.iter() {
+            let schema_id = self.create_schema(&schema.name)?;
+            if !schema.owner.is_empty() {
+                self.alter_schema_owner(schema_id, &schema.owner)?;
+            }
+            for table in schema.tables.iter() {
+                self.create_table(schema_id, &table.name, &table.columns)?;
+            }
+        }
+        for schema in catalog.schemas.iter() {
+            for table in schema.tables.iter() {
+                let partition_key = match &table.partition_key {
+                    Some(partition_key) => partition_key,
+                    None => continue,
+                };
+                let table_id = self.imported_table(&schema.name, &table.name);
+                self.partition_by_range(&Box::new(table_id), partition_key)?;
+                for partition in table.partitions.iter() {
+                    let partition_id = self.imported_table(&partition.schema_name, &partition.table_name);
+                    self.attach_range_partition(
+                        &Box::new(table_id),
+                        RangePartition::new(partition_id, partition.from, partition.to),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn imported_table(&self, schema_name: &str, table_name: &str) -> (Id, Id) {
+        match self.table_exists(&schema_name, &table_name) {
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+            _ => panic!("table {}.{} is imported", schema_name, table_name),
+        }
+    }
+}
+
+/// names are unique and partitions refer to exported tables
+pub(crate) fn validate(catalog: &CatalogExport) -> Result<(), String> {
+    if catalog.version != CATALOG_EXPORT_VERSION {
+        return Err(format!("unsupported catalog version {}", catalog.version));
+    }
+    let mut schemas = BTreeSet::new();
+    let mut tables = BTreeSet::new();
+    for schema in catalog.schemas.iter() {
+        if !schemas.insert(schema.name.as_str()) {
+            return Err(format!("schema \"{}\" is listed more than once", schema.name));
+        }
+        for table in schema.tables.iter() {
+            if !tables.insert((schema.name.as_str(), table.name.as_str())) {
+                return Err(format!(
+                    "table \"{}.{}\" is listed more than once",
+                    schema.name, table.name
+                ));
+            }
+        }
+    }
+    for schema in catalog.schemas.iter() {
+        for table in schema.tables.iter() {
+            match &table.partition_key {
+                Some(partition_key) if !table.columns.iter().any(|column| column.has_name(partition_key)) => {
+                    return Err(format!(
+                        "partition key \"{}\" is not a column of \"{}.{}\"",
+                        partition_key, schema.name, table.name
+                    ))
+                }
+                None if !table.partitions.is_empty() => {
+                    return Err(format!(
+                        "table \"{}.{}\" has partitions but no partition key",
+                        schema.name, table.name
+                    ))
+                }
+                _ => {}
+            }
+            if let Some(partition) = table
+                .partitions
+                .iter()
+                .find(|partition| !tables.contains(&(partition.schema_name.as_str(), partition.table_name.as_str())))
+            {
+                return Err(format!(
+                    "partition \"{}.{}\" of \"{}.{}\" is not listed",
+                    partition.schema_name, partition.table_name, schema.name, table.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
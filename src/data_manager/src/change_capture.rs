@@ -0,0 +1,132 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use crate::{journal::Writes, ColumnDefinition, Key, Values};
+
+/// changes of tables made by a committed transaction block or by a statement outside of it
+#[derive(Debug, PartialEq)]
+pub struct CommittedChanges {
+    pub transaction: u64,
+    pub tables: Vec<TableChanges>,
+}
+
+/// net changes of rows of a table, a row which key is changed is deleted and inserted again
+#[derive(Debug, PartialEq)]
+pub struct TableChanges {
+    pub schema_name: String,
+    pub table_name: String,
+    pub columns: Vec<ColumnDefinition>,
+    pub rows: Vec<RowChange>,
+}
+
+/// inserted rows don't have the `before` image and deleted rows don't have the `after` one
+#[derive(Debug, PartialEq)]
+pub struct RowChange {
+    pub key: Key,
+    pub before: Option<Values>,
+    pub after: Option<Values>,
+}
+
+/// named queues of committed changes, a slot receives changes committed after it is created
+/// and keeps them until they are taken
+#[derive(Debug, Default)]
+pub(crate) struct ChangeSlots {
+    slots: RwLock<BTreeMap<String, Vec<Arc<CommittedChanges>>>>,
+    transactions: AtomicU64,
+}
+
+impl ChangeSlots {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slots.read().expect("to acquire read lock").is_empty()
+    }
+
+    /// Returns `false` if there is a slot with the name
+    pub(crate) fn create(&self, name: &str) -> bool {
+        let mut slots = self.slots.write().expect("to acquire write lock");
+        if slots.contains_key(name) {
+            return false;
+        }
+        slots.insert(name.to_owned(), vec![]);
+        true
+    }
+
+    /// Returns `false` if there is no slot with the name
+    pub(crate) fn remove(&self, name: &str) -> bool {
+        self.slots
+            .write()
+            .expect("to acquire write lock")
+            .remove(name)
+            .is_some()
+    }
+
+    /// changes are taken in the order they were committed, `None` if there is no slot with the name
+    pub(crate) fn take(&self, name: &str) -> Option<Vec<Arc<CommittedChanges>>> {
+        self.slots
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(name)
+            .map(std::mem::take)
+    }
+
+    /// transactions that didn't change any row are not numbered
+    pub(crate) fn publish(&self, tables: Vec<TableChanges>) {
+        if tables.is_empty() {
+            return;
+        }
+        let changes = Arc::new(CommittedChanges {
+            transaction: self.transactions.fetch_add(1, Ordering::SeqCst) + 1,
+            tables,
+        });
+        for queue in self.slots.write().expect("to acquire write lock").values_mut() {
+            queue.push(changes.clone());
+        }
+    }
+}
+
+/// compares rows written by a transaction with the committed ones they replace, updated and inserted rows
+/// are in the order of the table and deleted rows follow them
+pub(crate) fn row_changes(mut committed: Writes, written: Writes) -> Vec<RowChange> {
+    let mut changes = vec![];
+    let mut deleted = vec![];
+    for (key, values) in written {
+        let before = committed.remove(&key).flatten();
+        match values {
+            Some(after) if before.as_ref() != Some(&after) => changes.push(RowChange {
+                key,
+                before,
+                after: Some(after),
+            }),
+            Some(_) => {}
+            None => {
+                if let Some(before) = before {
+                    deleted.push(RowChange {
+                        key,
+                        before: Some(before),
+                        after: None,
+                    });
+                }
+            }
+        }
+    }
+    changes.extend(deleted);
+    changes
+}
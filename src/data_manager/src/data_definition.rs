@@ -25,7 +25,10 @@ use kernel::{SystemError, SystemResult};
 use representation::{Binary, Datum};
 use sql_model::sql_types::SqlType;
 
-use crate::{ColumnDefinition, Database, DropSchemaError, DropStrategy, InitStatus, PersistentDatabase};
+use crate::{
+    ColumnDefinition, ColumnReference, Database, DropSchemaError, DropStrategy, EnumType, InitStatus,
+    PersistentDatabase, ReferentialAction,
+};
 use sql_model::Id;
 
 const SYSTEM_CATALOG: &'_ str = "system";
@@ -312,6 +315,47 @@ fn columns_table_types() -> [ColumnDefinition; 5] {
     ]
 }
 
+/// restores column definition from a **COLUMNS_TABLE** record.
+/// Flags that were added later are optional to read catalogs created by earlier versions
+fn restore_column_definition(data: &[Datum]) -> ColumnDefinition {
+    let column = ColumnDefinition::new(data[3].as_str(), data[4].as_sql_type());
+    let column = match data.get(7) {
+        Some(Datum::True) => column.not_null(),
+        _ => column,
+    };
+    let column = match (data.get(8), data.get(9), data.get(10), data.get(11)) {
+        (
+            Some(Datum::String(schema_name)),
+            Some(Datum::String(table_name)),
+            Some(Datum::String(column_name)),
+            Some(Datum::String(on_delete)),
+        ) => match ReferentialAction::from_name(on_delete) {
+            Some(on_delete) => column.references(ColumnReference::new(schema_name, table_name, column_name, on_delete)),
+            None => column,
+        },
+        _ => column,
+    };
+    let column = match data.get(12) {
+        Some(Datum::String(sequence_name)) => column.serial(sequence_name),
+        _ => column,
+    };
+    let column = match data.get(13) {
+        Some(Datum::String(expression)) => column.generated_always_as(expression),
+        _ => column,
+    };
+    let column = match data.get(14) {
+        Some(Datum::String(type_name)) => column.with_enum_type(EnumType::new(
+            type_name,
+            data[15..].iter().map(|label| label.as_str().to_owned()).collect(),
+        )),
+        _ => column,
+    };
+    match data.get(6) {
+        Some(Datum::True) => column.primary_key(),
+        _ => column,
+    }
+}
+
 type InnerCatalogId = Option<Id>;
 type InnerFullSchemaId = Option<(Id, Option<Id>)>;
 type InnerFullTableId = Option<(Id, Option<(Id, Option<Id>)>)>;
@@ -353,7 +397,9 @@ impl Catalog {
         schema_id
     }
 
+    /// schema restored from the system catalog, new schemas get ids after it
     fn add_schema(&self, schema_id: Id, schema_name: &str) -> Arc<Schema> {
+        self.schema_id_generator.fetch_max(schema_id + 1, Ordering::SeqCst);
         let schema = Arc::new(Schema::new(schema_id));
         self.schemas
             .write()
@@ -428,6 +474,7 @@ impl Schema {
         column_definitions: BTreeMap<Id, ColumnDefinition>,
         max_id: Id,
     ) {
+        self.table_id_generator.fetch_max(table_id + 1, Ordering::SeqCst);
         self.tables.write().expect("to acquire write lock").insert(
             table_name.to_owned(),
             Arc::new(Table::restore(table_id, column_definitions, max_id)),
@@ -543,9 +590,22 @@ impl DataDefinition {
     }
 
     pub(crate) fn persistent(path: &PathBuf) -> SystemResult<DataDefinition> {
-        let system_catalog = PersistentDatabase::new(path.join(SYSTEM_CATALOG));
+        Self::with_system_catalog(Box::new(PersistentDatabase::new(path.join(SYSTEM_CATALOG))))
+    }
+
+    #[cfg(feature = "rocksdb")]
+    pub(crate) fn rocks_db(path: &PathBuf) -> SystemResult<DataDefinition> {
+        Self::with_system_catalog(Box::new(crate::rocks::RocksDatabase::new(path.join(SYSTEM_CATALOG))))
+    }
+
+    /// definitions are loaded from the system catalog or it is initialized when it is opened the first time
+    pub(crate) fn with_system_catalog(system_catalog: Box<dyn Database>) -> SystemResult<DataDefinition> {
         let (catalogs, catalog_ids) = match system_catalog.init(DEFINITION_SCHEMA) {
             Ok(Ok(InitStatus::Loaded)) => {
+                // a crash could happen before every table of a new system catalog was created
+                for table in &[CATALOG_NAMES_TABLE, SCHEMATA_TABLE, TABLES_TABLE, COLUMNS_TABLE] {
+                    system_catalog.open_object(DEFINITION_SCHEMA, table);
+                }
                 let mut max_id = 0;
                 let catalogs = system_catalog
                     .read(DEFINITION_SCHEMA, CATALOG_NAMES_TABLE)
@@ -593,7 +653,7 @@ impl DataDefinition {
         Ok(DataDefinition {
             catalog_ids: AtomicU64::new(catalog_ids),
             catalogs: RwLock::new(catalogs),
-            system_catalog: Some(Box::new(system_catalog)),
+            system_catalog: Some(system_catalog),
         })
     }
 
@@ -968,7 +1028,7 @@ impl DataDefinition {
                         .map(Result::unwrap)
                         .map(Result::unwrap)
                         .map(|(record_id, columns)| {
-                            let id = record_id.unpack()[0].as_u64();
+                            let id = record_id.unpack()[1].as_u64();
                             let name = columns.unpack()[1].as_str().to_owned();
                             (id, name)
                         })
@@ -995,7 +1055,7 @@ impl DataDefinition {
                         .map(Result::unwrap)
                         .map(Result::unwrap)
                         .map(|(record_id, data)| {
-                            let id = record_id.unpack()[0].as_u64();
+                            let id = record_id.unpack()[2].as_u64();
                             let data = data.unpack();
                             let schema = data[1].as_str().to_owned();
                             let table = data[2].as_str().to_owned();
@@ -1019,17 +1079,12 @@ impl DataDefinition {
                                     let data = data.unpack();
                                     let schema = data[1].as_str().to_owned();
                                     let table = data[2].as_str().to_owned();
-                                    let column = data[3].as_str().to_owned();
-                                    let sql_type = data[4].as_sql_type();
+                                    let column = restore_column_definition(&data);
                                     max_id = max_id.max(id);
-                                    (id, schema, table, column, sql_type)
-                                })
-                                .filter(|(_id, schema, table, _column, _sql_type)| {
-                                    schema == schema_name && table == table_name
-                                })
-                                .map(|(id, _schema, _table, column, sql_type)| {
-                                    (id, ColumnDefinition::new(column.as_str(), sql_type))
+                                    (id, schema, table, column)
                                 })
+                                .filter(|(_id, schema, table, _column)| schema == schema_name && table == table_name)
+                                .map(|(id, _schema, _table, column)| (id, column))
                                 .collect::<BTreeMap<_, _>>();
                             schema.add_table(table_id, table_name, table_columns, max_id);
                             Some(table_id)
@@ -1085,6 +1140,39 @@ impl DataDefinition {
                 .expect("no platform error")
                 .expect("to save table info");
             for (id, column) in created_table.columns() {
+                let name = column.name();
+                let mut column_info = vec![
+                    Datum::from_str(catalog_name),
+                    Datum::from_str(schema_name),
+                    Datum::from_str(table_name),
+                    Datum::from_str(name.as_str()),
+                    Datum::from_sql_type(column.sql_type()),
+                    Datum::UInt64(id),
+                    Datum::from_bool(column.is_primary_key()),
+                    Datum::from_bool(!column.is_nullable()),
+                ];
+                match column.referenced_column() {
+                    Some(reference) => column_info.extend(vec![
+                        Datum::from_str(reference.schema_name()),
+                        Datum::from_str(reference.table_name()),
+                        Datum::from_str(reference.column_name()),
+                        Datum::from_str(reference.on_delete().as_str()),
+                    ]),
+                    None => column_info.extend(vec![Datum::from_null(); 4]),
+                }
+                match column.serial_sequence() {
+                    Some(sequence_name) => column_info.push(Datum::from_str(sequence_name)),
+                    None => column_info.push(Datum::from_null()),
+                }
+                match column.generation_expression() {
+                    Some(expression) => column_info.push(Datum::from_str(expression)),
+                    None if column.enum_type().is_some() => column_info.push(Datum::from_null()),
+                    None => {}
+                }
+                if let Some(enum_type) = column.enum_type() {
+                    column_info.push(Datum::from_str(enum_type.name()));
+                    column_info.extend(enum_type.labels().iter().map(|label| Datum::from_str(label.as_str())));
+                }
                 system_catalog
                     .write(
                         DEFINITION_SCHEMA,
@@ -1096,14 +1184,7 @@ impl DataDefinition {
                                 Datum::from_u64(created_table.id()),
                                 Datum::from_u64(id),
                             ]),
-                            Binary::pack(&[
-                                Datum::from_str(catalog_name),
-                                Datum::from_str(schema_name),
-                                Datum::from_str(table_name),
-                                Datum::from_str(column.name().as_str()),
-                                Datum::from_sql_type(column.sql_type()),
-                                Datum::UInt64(id),
-                            ]),
+                            Binary::pack(&column_info),
                         )],
                     )
                     .expect("no io error")
@@ -1161,7 +1242,7 @@ impl DataDefinition {
                 .map(Result::unwrap)
                 .map(Result::unwrap)
                 .map(|(record_id, columns)| {
-                    let id = record_id.unpack()[1].as_u64();
+                    let id = record_id.unpack()[2].as_u64();
                     let columns = columns.unpack();
                     let catalog = columns[0].as_str().to_owned();
                     let schema = columns[1].as_str().to_owned();
@@ -1183,15 +1264,14 @@ impl DataDefinition {
                         let data = data.unpack();
                         let schema = data[1].as_str().to_owned();
                         let table = data[2].as_str().to_owned();
-                        let column = data[3].as_str().to_owned();
-                        let sql_type = data[4].as_sql_type();
+                        let column = restore_column_definition(&data);
                         max_id = max_id.max(id);
-                        (id, schema, table, column, sql_type)
+                        (id, schema, table, column)
                     })
-                    .filter(|(_id, schema, _table, _column, _sql_type)| schema == schema_name)
-                    .map(|(id, _schema, _table, column, sql_type)| {
-                        (id, ColumnDefinition::new(column.as_str(), sql_type))
+                    .filter(|(_id, column_schema, column_table, _column)| {
+                        column_schema == schema_name && column_table == &table
                     })
+                    .map(|(id, _schema, _table, column)| (id, column))
                     .collect::<BTreeMap<_, _>>();
                 schema.add_table(table_id, table.as_str(), table_columns, max_id);
             }
@@ -0,0 +1,67 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+use representation::{unpack_raw, Binary, Datum};
+
+/// user-defined type created by `CREATE TYPE ... AS ENUM`, its values are ordered by positions of their labels
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct EnumType {
+    name: String,
+    labels: Vec<String>,
+}
+
+impl EnumType {
+    pub fn new(name: &str, labels: Vec<String>) -> EnumType {
+        EnumType {
+            name: name.to_owned(),
+            labels,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn labels(&self) -> &[String] {
+        self.labels.as_slice()
+    }
+
+    /// `None` if the label is not one of the type
+    pub fn position(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|known| known == label)
+    }
+
+    pub(crate) fn to_values(&self) -> Binary {
+        Binary::pack(
+            &self
+                .labels
+                .iter()
+                .map(|label| Datum::from_str(label.as_str()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub(crate) fn from_values(name: &str, values: &Binary) -> Option<EnumType> {
+        let mut labels = vec![];
+        for datum in unpack_raw(values.to_bytes()) {
+            match datum {
+                Datum::String(label) => labels.push(label.to_owned()),
+                _ => return None,
+            }
+        }
+        Some(EnumType::new(name, labels))
+    }
+}
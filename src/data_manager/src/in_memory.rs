@@ -18,7 +18,10 @@ use std::{
     sync::RwLock,
 };
 
-use crate::{Database, DefinitionError, Key, ObjectName, ReadCursor, RowResult, SchemaName, StorageError, Values};
+use crate::{
+    Database, DefinitionError, InitStatus, Key, KeyRange, ObjectName, ReadCursor, RowResult, SchemaName, StorageError,
+    Values,
+};
 
 type Name = String;
 
@@ -38,6 +41,18 @@ pub struct InMemoryDatabase {
 }
 
 impl Database for InMemoryDatabase {
+    /// nothing is kept between restarts, schemas are always created
+    fn init(&self, schema_name: SchemaName) -> io::Result<Result<InitStatus, StorageError>> {
+        self.schemas
+            .write()
+            .expect("to acquire write lock")
+            .entry(schema_name.to_owned())
+            .or_default();
+        Ok(Ok(InitStatus::Created))
+    }
+
+    fn open_object(&self, _schema_name: SchemaName, _object_name: ObjectName) {}
+
     fn create_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
         if self
             .schemas
@@ -152,6 +167,30 @@ impl Database for InMemoryDatabase {
         }
     }
 
+    fn range(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        keys: KeyRange,
+        limit: usize,
+    ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.objects.get(object_name) {
+                Some(object) => Ok(Ok(Ok(Box::new(
+                    object
+                        .records
+                        .range(keys)
+                        .take(limit)
+                        .map(|(key, values)| Ok(Ok((key.clone(), values.clone()))))
+                        .collect::<Vec<RowResult>>()
+                        .into_iter(),
+                )))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
     fn delete(
         &self,
         schema_name: SchemaName,
@@ -179,4 +218,17 @@ impl Database for InMemoryDatabase {
             None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
         }
     }
+
+    /// rows are removed from the map as they are deleted, there is nothing left to reclaim
+    fn compact(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) if schema.objects.contains_key(object_name) => Ok(Ok(Ok(()))),
+            Some(_) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
 }
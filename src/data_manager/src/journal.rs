@@ -0,0 +1,294 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use representation::Binary;
+use sql_model::Id;
+
+use crate::{Key, Row, Values};
+
+/// written rows of a table by their keys, a deleted row has no values
+pub(crate) type Writes = BTreeMap<Key, Option<Values>>;
+
+/// rows written and tables defined by a transaction, they are kept apart from the catalog and the tables and are
+/// seen only by the transaction until it is committed, when the transaction is rolled back they are dropped
+#[derive(Debug)]
+pub struct Journal {
+    savepoints: Mutex<Vec<Savepoint>>,
+    /// rows as they were committed before the transaction wrote them, kept while changes are captured
+    committed: Mutex<BTreeMap<(Id, Id), Writes>>,
+}
+
+/// the unnamed savepoint is the start of the transaction
+#[derive(Debug, Default)]
+struct Savepoint {
+    name: Option<String>,
+    writes: BTreeMap<(Id, Id), Writes>,
+    definitions: Vec<Definition>,
+}
+
+/// tables created and dropped in a transaction, created tables are seen only by the transaction and dropped
+/// tables by everyone else until it is committed
+#[derive(Debug)]
+pub(crate) enum Definition {
+    Created((Id, Id)),
+    Dropped((Id, Id)),
+}
+
+/// changes of the transaction or of its savepoint, writes and dropped tables are applied on commit and
+/// created tables are dropped on roll back
+#[derive(Debug)]
+pub(crate) struct Changes {
+    pub(crate) writes: BTreeMap<(Id, Id), Writes>,
+    pub(crate) committed: BTreeMap<(Id, Id), Writes>,
+    pub(crate) definitions: Vec<Definition>,
+}
+
+/// rows of a committed transaction by storage names of their tables, the record is stored before the rows are written
+/// into the tables and is removed after, a commit cut by a crash is finished when the storage is opened
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CommitRecord {
+    tables: Vec<(String, String, RecordedRows)>,
+}
+
+/// bytes of keys and of values of written rows, deleted rows have no values
+type RecordedRows = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+impl CommitRecord {
+    pub(crate) fn from_values(values: &Values) -> Option<CommitRecord> {
+        bincode::deserialize(values.to_bytes()).ok()
+    }
+
+    pub(crate) fn to_values(&self) -> Values {
+        Binary::with_data(bincode::serialize(self).expect("commit record is serializable"))
+    }
+
+    pub(crate) fn add(&mut self, schema_name: String, table_name: String, writes: &Writes) {
+        let rows = writes
+            .iter()
+            .map(|(key, values)| {
+                (
+                    key.to_bytes().to_vec(),
+                    values.as_ref().map(|values| values.to_bytes().to_vec()),
+                )
+            })
+            .collect();
+        self.tables.push((schema_name, table_name, rows));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// deleted keys and written rows of every table of the commit
+    pub(crate) fn tables(&self) -> impl Iterator<Item = (&str, &str, Vec<Key>, Vec<Row>)> {
+        self.tables.iter().map(|(schema_name, table_name, rows)| {
+            let mut deleted = vec![];
+            let mut written = vec![];
+            for (key, values) in rows {
+                let key = Binary::with_data(key.clone());
+                match values {
+                    Some(values) => written.push((key, Binary::with_data(values.clone()))),
+                    None => deleted.push(key),
+                }
+            }
+            (schema_name.as_str(), table_name.as_str(), deleted, written)
+        })
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Journal {
+        Journal {
+            savepoints: Mutex::new(vec![Savepoint::default()]),
+            committed: Mutex::default(),
+        }
+    }
+}
+
+impl Journal {
+    /// rows written after the savepoint are dropped when the transaction is rolled back to it,
+    /// savepoints with the same name are hidden until it is released
+    pub fn savepoint(&self, name: &str) {
+        self.savepoints.lock().expect("to acquire lock").push(Savepoint {
+            name: Some(name.to_owned()),
+            ..Savepoint::default()
+        });
+    }
+
+    /// the latest savepoint with the name and savepoints after it are destroyed, their writes are kept.
+    /// Returns `false` if there is no savepoint with the name
+    pub fn release(&self, name: &str) -> bool {
+        let mut savepoints = self.savepoints.lock().expect("to acquire lock");
+        match position(&savepoints, name) {
+            Some(position) => {
+                let definitions = drain_definitions(&mut savepoints[position..]);
+                let writes = drain_writes(&mut savepoints[position..]);
+                savepoints.truncate(position);
+                savepoints[position - 1].definitions.extend(definitions);
+                merge_writes(&mut savepoints[position - 1].writes, writes);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn write(&self, table_id: (Id, Id), rows: Vec<Row>) {
+        if let Some(savepoint) = self.savepoints.lock().expect("to acquire lock").last_mut() {
+            let writes = savepoint.writes.entry(table_id).or_default();
+            for (key, values) in rows {
+                writes.insert(key, Some(values));
+            }
+        }
+    }
+
+    pub(crate) fn delete(&self, table_id: (Id, Id), keys: Vec<Key>) {
+        if let Some(savepoint) = self.savepoints.lock().expect("to acquire lock").last_mut() {
+            let writes = savepoint.writes.entry(table_id).or_default();
+            for key in keys {
+                writes.insert(key, None);
+            }
+        }
+    }
+
+    pub(crate) fn keeps_committed(&self, table_id: &(Id, Id), key: &Key) -> bool {
+        self.committed
+            .lock()
+            .expect("to acquire lock")
+            .get(table_id)
+            .map(|committed| committed.contains_key(key))
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn keep_committed(&self, table_id: (Id, Id), key: Key, values: Option<Values>) {
+        self.committed
+            .lock()
+            .expect("to acquire lock")
+            .entry(table_id)
+            .or_default()
+            .insert(key, values);
+    }
+
+    /// the latest write of the row by the transaction, `Some(None)` if it deleted the row
+    pub(crate) fn written(&self, table_id: &(Id, Id), key: &Key) -> Option<Option<Values>> {
+        self.savepoints
+            .lock()
+            .expect("to acquire lock")
+            .iter()
+            .rev()
+            .find_map(|savepoint| savepoint.writes.get(table_id).and_then(|writes| writes.get(key)))
+            .cloned()
+    }
+
+    /// rows of the table written since the start of the transaction, later writes replace earlier ones
+    pub(crate) fn writes(&self, table_id: &(Id, Id)) -> Writes {
+        let mut writes = Writes::new();
+        for savepoint in self.savepoints.lock().expect("to acquire lock").iter() {
+            if let Some(written) = savepoint.writes.get(table_id) {
+                writes.extend(written.iter().map(|(key, values)| (key.clone(), values.clone())));
+            }
+        }
+        writes
+    }
+
+    pub(crate) fn define(&self, definition: Definition) {
+        if let Some(savepoint) = self.savepoints.lock().expect("to acquire lock").last_mut() {
+            savepoint.definitions.push(definition);
+        }
+    }
+
+    /// plans made in a transaction that creates or drops tables are not shared with other sessions
+    pub fn defines_tables(&self) -> bool {
+        self.savepoints
+            .lock()
+            .expect("to acquire lock")
+            .iter()
+            .any(|savepoint| !savepoint.definitions.is_empty())
+    }
+
+    pub(crate) fn creates(&self, table_id: &(Id, Id)) -> bool {
+        self.defines(|definition| matches!(definition, Definition::Created(id) if id == table_id))
+    }
+
+    pub(crate) fn drops(&self, table_id: &(Id, Id)) -> bool {
+        self.defines(|definition| matches!(definition, Definition::Dropped(id) if id == table_id))
+    }
+
+    fn defines<P: Fn(&Definition) -> bool>(&self, predicate: P) -> bool {
+        self.savepoints
+            .lock()
+            .expect("to acquire lock")
+            .iter()
+            .any(|savepoint| savepoint.definitions.iter().any(&predicate))
+    }
+
+    pub(crate) fn take_changes(&self) -> Changes {
+        let mut savepoints = self.savepoints.lock().expect("to acquire lock");
+        let definitions = drain_definitions(&mut savepoints);
+        let writes = drain_writes(&mut savepoints);
+        savepoints.truncate(1);
+        Changes {
+            writes,
+            committed: std::mem::take(&mut self.committed.lock().expect("to acquire lock")),
+            definitions,
+        }
+    }
+
+    /// savepoints after the latest one with the name are destroyed, the savepoint itself is kept
+    pub(crate) fn take_changes_since(&self, name: &str) -> Option<Changes> {
+        let mut savepoints = self.savepoints.lock().expect("to acquire lock");
+        let position = position(&savepoints, name)?;
+        let definitions = drain_definitions(&mut savepoints[position..]);
+        let writes = drain_writes(&mut savepoints[position..]);
+        savepoints.truncate(position + 1);
+        // committed rows are the same until the transaction is ended
+        Some(Changes {
+            writes,
+            committed: BTreeMap::new(),
+            definitions,
+        })
+    }
+}
+
+fn position(savepoints: &[Savepoint], name: &str) -> Option<usize> {
+    savepoints
+        .iter()
+        .rposition(|savepoint| savepoint.name.as_deref() == Some(name))
+}
+
+/// definitions of the savepoints in the order they were made
+fn drain_definitions(savepoints: &mut [Savepoint]) -> Vec<Definition> {
+    savepoints
+        .iter_mut()
+        .flat_map(|savepoint| savepoint.definitions.drain(..))
+        .collect()
+}
+
+/// writes of the savepoints merged in the order they were made
+fn drain_writes(savepoints: &mut [Savepoint]) -> BTreeMap<(Id, Id), Writes> {
+    let mut writes = BTreeMap::new();
+    for savepoint in savepoints.iter_mut() {
+        merge_writes(&mut writes, std::mem::take(&mut savepoint.writes));
+    }
+    writes
+}
+
+fn merge_writes(writes: &mut BTreeMap<(Id, Id), Writes>, later: BTreeMap<(Id, Id), Writes>) {
+    for (table_id, written) in later {
+        writes.entry(table_id).or_default().extend(written);
+    }
+}
@@ -13,12 +13,16 @@
 // limitations under the License.
 
 use std::{
-    collections::HashMap,
+    cmp::Ordering as CmpOrdering,
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryInto,
     io::{self},
+    iter::Peekable,
+    ops::Bound,
     path::PathBuf,
     sync::{
         atomic::{AtomicU64, Ordering},
-        RwLock,
+        Arc, RwLock,
     },
 };
 
@@ -28,18 +32,56 @@ use kernel::{Object, Operation, SystemError, SystemResult};
 use representation::Binary;
 use sql_model::sql_types::SqlType;
 
-use crate::{data_definition::DataDefinition, in_memory::InMemoryDatabase, persistent::PersistentDatabase};
+use crate::{
+    change_capture::{row_changes, ChangeSlots},
+    data_definition::DataDefinition,
+    in_memory::InMemoryDatabase,
+    journal::{Changes, CommitRecord, Definition, Writes},
+    locks::TableLocks,
+    persistent::PersistentDatabase,
+    sequence::Sequence,
+};
+use representation::{unpack_raw, Datum};
 use sql_model::{sql_errors::DefinitionError, Id};
 
+pub use backup::{BackupError, BACKUP_VERSION};
+pub use catalog_diff::catalog_migration;
+pub use catalog_export::{
+    CatalogExport, CatalogImportError, PartitionExport, SchemaExport, TableExport, CATALOG_EXPORT_VERSION,
+};
+pub use change_capture::{CommittedChanges, RowChange, TableChanges};
+pub use enum_type::EnumType;
+pub use journal::Journal;
+pub use locks::TableLock;
+pub use sequence::{SequenceError, SequenceOptions};
+pub use sketch::{DistinctSketch, QuantileSketch};
+pub use sql_function::SqlFunction;
+pub use statistics::{ExtendedStatistics, FunctionalDependency, StatisticsKind, TableStatistics};
+
+mod backup;
+mod catalog_diff;
+mod catalog_export;
+mod change_capture;
 mod data_definition;
+mod enum_type;
 mod in_memory;
+mod journal;
+mod locks;
 pub mod persistent;
+#[cfg(feature = "rocksdb")]
+pub mod rocks;
+mod schema_copy;
+mod sequence;
+mod sketch;
+mod sql_function;
+mod statistics;
 
 pub type Row = (Key, Values);
 pub type Key = Binary;
 pub type Values = Binary;
 pub type RowResult = io::Result<Result<Row, StorageError>>;
 pub type ReadCursor = Box<dyn Iterator<Item = RowResult>>;
+pub type KeyRange = (Bound<Key>, Bound<Key>);
 
 pub enum InitStatus {
     Created,
@@ -56,7 +98,14 @@ pub enum StorageError {
 pub type SchemaName<'s> = &'s str;
 pub type ObjectName<'o> = &'o str;
 
+/// storage engine that keeps rows of objects grouped by schemas, rows are ordered by their keys
 pub trait Database {
+    /// opens the schema kept by the storage or creates it when it is opened the first time
+    fn init(&self, schema_name: SchemaName) -> io::Result<Result<InitStatus, StorageError>>;
+
+    /// opens the object of the schema loaded by `init`
+    fn open_object(&self, schema_name: SchemaName, object_name: ObjectName);
+
     fn create_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>>;
 
     fn drop_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>>;
@@ -86,12 +135,53 @@ pub trait Database {
         object_name: ObjectName,
     ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>>;
 
+    /// first `limit` rows of the object with keys in the range in the order of their keys, the range is never empty
+    fn range(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        keys: KeyRange,
+        limit: usize,
+    ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>>;
+
     fn delete(
         &self,
         schema_name: SchemaName,
         object_name: ObjectName,
         keys: Vec<Key>,
     ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>>;
+
+    /// gives the space of deleted and overwritten rows of the object back to the storage
+    fn compact(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>>;
+}
+
+/// storage engine that definitions and rows are kept in, selected by the server configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageBackend {
+    /// nothing is kept after the node is stopped
+    InMemory,
+    /// sled databases under the directory
+    Sled(PathBuf),
+    /// RocksDB databases under the directory, available when the crate is built with the `rocksdb` feature
+    #[cfg(feature = "rocksdb")]
+    RocksDb(PathBuf),
+}
+
+impl StorageBackend {
+    /// `None` if there is no backend with the name, persistent backends keep their data under the path
+    pub fn from_name(name: &str, path: PathBuf) -> Option<StorageBackend> {
+        match name.to_lowercase().as_str() {
+            "in-memory" | "in_memory" => Some(StorageBackend::InMemory),
+            "sled" => Some(StorageBackend::Sled(path)),
+            #[cfg(feature = "rocksdb")]
+            "rocksdb" => Some(StorageBackend::RocksDb(path)),
+            _ => None,
+        }
+    }
 }
 
 pub type FullSchemaId = Option<Id>;
@@ -101,6 +191,18 @@ pub type FullTableId = Option<(Id, Option<Id>)>;
 pub struct ColumnDefinition {
     name: String,
     sql_type: SqlType,
+    #[serde(default)]
+    primary_key: bool,
+    #[serde(default)]
+    not_null: bool,
+    #[serde(default)]
+    references: Option<ColumnReference>,
+    #[serde(default)]
+    serial_sequence: Option<String>,
+    #[serde(default)]
+    generation_expression: Option<String>,
+    #[serde(default)]
+    enum_type: Option<EnumType>,
 }
 
 impl ColumnDefinition {
@@ -108,6 +210,88 @@ impl ColumnDefinition {
         Self {
             name: name.to_string(),
             sql_type,
+            primary_key: false,
+            not_null: false,
+            references: None,
+            serial_sequence: None,
+            generation_expression: None,
+            enum_type: None,
+        }
+    }
+
+    /// primary key columns are implicitly NOT NULL
+    pub fn primary_key(self) -> Self {
+        Self {
+            primary_key: true,
+            not_null: true,
+            ..self
+        }
+    }
+
+    pub fn is_primary_key(&self) -> bool {
+        self.primary_key
+    }
+
+    pub fn not_null(self) -> Self {
+        Self { not_null: true, ..self }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        !self.not_null
+    }
+
+    pub fn references(self, reference: ColumnReference) -> Self {
+        Self {
+            references: Some(reference),
+            ..self
+        }
+    }
+
+    pub fn referenced_column(&self) -> Option<&ColumnReference> {
+        self.references.as_ref()
+    }
+
+    /// values of the column default to `nextval` of the sequence in the schema of the table
+    pub fn serial(self, sequence_name: &str) -> Self {
+        Self {
+            serial_sequence: Some(sequence_name.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn serial_sequence(&self) -> Option<&str> {
+        self.serial_sequence.as_deref()
+    }
+
+    /// values of the column are computed from other columns of the same row by the expression
+    pub fn generated_always_as(self, expression: &str) -> Self {
+        Self {
+            generation_expression: Some(expression.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn generation_expression(&self) -> Option<&str> {
+        self.generation_expression.as_deref()
+    }
+
+    /// labels of the enum type are kept with the column to validate and order its values
+    pub fn with_enum_type(self, enum_type: EnumType) -> Self {
+        Self {
+            enum_type: Some(enum_type),
+            ..self
+        }
+    }
+
+    pub fn enum_type(&self) -> Option<&EnumType> {
+        self.enum_type.as_ref()
+    }
+
+    /// column of a partition, rows inserted through the partitioned table take `serial` defaults from it
+    pub fn without_serial(self) -> Self {
+        Self {
+            serial_sequence: None,
+            ..self
         }
     }
 
@@ -124,6 +308,188 @@ impl ColumnDefinition {
     }
 }
 
+/// What happens to referencing rows when referenced row is deleted
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+}
+
+impl ReferentialAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+        }
+    }
+
+    pub fn from_name(action: &str) -> Option<ReferentialAction> {
+        match action {
+            "NO ACTION" => Some(ReferentialAction::NoAction),
+            "RESTRICT" => Some(ReferentialAction::Restrict),
+            "CASCADE" => Some(ReferentialAction::Cascade),
+            "SET NULL" => Some(ReferentialAction::SetNull),
+            _ => None,
+        }
+    }
+}
+
+/// Primary key column of another table that a foreign key column references
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ColumnReference {
+    schema_name: String,
+    table_name: String,
+    column_name: String,
+    on_delete: ReferentialAction,
+}
+
+impl ColumnReference {
+    pub fn new(schema_name: &str, table_name: &str, column_name: &str, on_delete: ReferentialAction) -> Self {
+        Self {
+            schema_name: schema_name.to_owned(),
+            table_name: table_name.to_owned(),
+            column_name: column_name.to_owned(),
+            on_delete,
+        }
+    }
+
+    pub fn schema_name(&self) -> &str {
+        self.schema_name.as_str()
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table_name.as_str()
+    }
+
+    pub fn column_name(&self) -> &str {
+        self.column_name.as_str()
+    }
+
+    pub fn on_delete(&self) -> ReferentialAction {
+        self.on_delete
+    }
+}
+
+/// table that holds rows with partition key values in `[from, to)`,
+/// `None` bounds stand for `MINVALUE` and `MAXVALUE`
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangePartition {
+    table_id: (Id, Id),
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+impl RangePartition {
+    pub fn new(table_id: (Id, Id), from: Option<i64>, to: Option<i64>) -> RangePartition {
+        RangePartition { table_id, from, to }
+    }
+
+    pub fn table_id(&self) -> (Id, Id) {
+        self.table_id
+    }
+
+    pub fn from(&self) -> Option<i64> {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<i64> {
+        self.to
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.from.map(|from| from <= value).unwrap_or(true) && self.to.map(|to| value < to).unwrap_or(true)
+    }
+
+    /// whether the partition can hold any value of the inclusive `[lower, upper]` range
+    pub fn intersects(&self, lower: Option<i64>, upper: Option<i64>) -> bool {
+        let below_upper = match (self.from, upper) {
+            (Some(from), Some(upper)) => from <= upper,
+            _ => true,
+        };
+        let above_lower = match (self.to, lower) {
+            (Some(to), Some(lower)) => lower < to,
+            _ => true,
+        };
+        below_upper && above_lower
+    }
+
+    /// whether the partition shares any value with the `[from, to)` range
+    pub fn overlaps(&self, from: Option<i64>, to: Option<i64>) -> bool {
+        let starts_before_end = match (self.from, to) {
+            (Some(self_from), Some(to)) => self_from < to,
+            _ => true,
+        };
+        let ends_after_start = match (self.to, from) {
+            (Some(self_to), Some(from)) => from < self_to,
+            _ => true,
+        };
+        starts_before_end && ends_after_start
+    }
+}
+
+/// partition key and partitions of a table created with `PARTITION BY RANGE (column)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangePartitioning {
+    column_name: String,
+    partitions: Vec<RangePartition>,
+}
+
+impl RangePartitioning {
+    pub fn column_name(&self) -> &str {
+        self.column_name.as_str()
+    }
+
+    pub fn partitions(&self) -> &[RangePartition] {
+        self.partitions.as_slice()
+    }
+
+    pub fn partition_for(&self, value: i64) -> Option<&RangePartition> {
+        self.partitions.iter().find(|partition| partition.contains(value))
+    }
+
+    /// the partition key is followed by ids and bounds of every partition, NULL bounds are unbounded
+    fn to_values(&self) -> Binary {
+        let mut values = vec![Datum::from_str(self.column_name.as_str())];
+        for partition in self.partitions.iter() {
+            values.push(Datum::from_u64(partition.table_id.0));
+            values.push(Datum::from_u64(partition.table_id.1));
+            values.push(partition.from.map(Datum::from_i64).unwrap_or_else(Datum::from_null));
+            values.push(partition.to.map(Datum::from_i64).unwrap_or_else(Datum::from_null));
+        }
+        Binary::pack(&values)
+    }
+
+    fn from_values(values: &Binary) -> Option<RangePartitioning> {
+        let values = unpack_raw(values.to_bytes());
+        let column_name = match values.first() {
+            Some(Datum::String(column_name)) => (*column_name).to_owned(),
+            _ => return None,
+        };
+        let bound = |datum: &Datum| match datum {
+            Datum::Int64(bound) => Some(Some(*bound)),
+            Datum::Null => Some(None),
+            _ => None,
+        };
+        let mut partitions = vec![];
+        for partition in values[1..].chunks(4) {
+            match partition {
+                [Datum::UInt64(schema_id), Datum::UInt64(table_id), from, to] => {
+                    partitions.push(RangePartition::new((*schema_id, *table_id), bound(from)?, bound(to)?))
+                }
+                _ => return None,
+            }
+        }
+        Some(RangePartitioning {
+            column_name,
+            partitions,
+        })
+    }
+}
+
 pub enum DropStrategy {
     Restrict,
     Cascade,
@@ -136,12 +502,85 @@ pub enum DropSchemaError {
     HasDependentObjects,
 }
 
+/// Role that owns system objects and bypasses privilege checks
+pub const SUPERUSER: &str = "postgres";
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SchemaPrivilege {
+    Usage,
+    Create,
+}
+
+#[derive(Debug)]
+struct SchemaAcl {
+    owner: String,
+    grants: HashMap<String, HashSet<SchemaPrivilege>>,
+}
+
+impl SchemaAcl {
+    fn owned_by(owner: &str) -> SchemaAcl {
+        SchemaAcl {
+            owner: owner.to_owned(),
+            grants: HashMap::new(),
+        }
+    }
+
+    /// the owner is followed by a role and a privilege for every grant
+    fn to_values(&self) -> Binary {
+        let mut values = vec![Datum::from_str(self.owner.as_str())];
+        for (role, privileges) in self.grants.iter() {
+            for privilege in privileges {
+                values.push(Datum::from_str(role.as_str()));
+                values.push(Datum::from_str(match privilege {
+                    SchemaPrivilege::Usage => "USAGE",
+                    SchemaPrivilege::Create => "CREATE",
+                }));
+            }
+        }
+        Binary::pack(&values)
+    }
+
+    fn from_values(values: &Binary) -> Option<SchemaAcl> {
+        let values = unpack_raw(values.to_bytes());
+        let mut acl = match values.first() {
+            Some(Datum::String(owner)) => SchemaAcl::owned_by(owner),
+            _ => return None,
+        };
+        for grant in values[1..].chunks(2) {
+            let (role, privilege) = match grant {
+                [Datum::String(role), Datum::String("USAGE")] => (role, SchemaPrivilege::Usage),
+                [Datum::String(role), Datum::String("CREATE")] => (role, SchemaPrivilege::Create),
+                _ => return None,
+            };
+            acl.grants.entry((*role).to_owned()).or_default().insert(privilege);
+        }
+        Some(acl)
+    }
+}
+
 pub struct DataManager {
     data_storage: Box<dyn Database>,
     data_definition: DataDefinition,
     schemas: RwLock<HashMap<Id, String>>,
+    schema_acls: RwLock<HashMap<Id, SchemaAcl>>,
     tables: RwLock<HashMap<(Id, Id), Vec<String>>>,
-    record_id_generators: RwLock<HashMap<(Id, Id), AtomicU64>>,
+    record_id_generators: RwLock<HashMap<(Id, Id), Sequence>>,
+    sequences: RwLock<HashMap<(Id, String), Sequence>>,
+    large_object_ids: AtomicU64,
+    partitioned_tables: RwLock<HashMap<(Id, Id), RangePartitioning>>,
+    statistics: RwLock<HashMap<(Id, String), ExtendedStatistics>>,
+    row_statistics: RwLock<HashMap<(Id, Id), TableStatistics>>,
+    column_filters: RwLock<HashMap<(Id, Id), HashMap<String, u64>>>,
+    table_versions: RwLock<HashMap<(Id, Id), u64>>,
+    enum_types: RwLock<HashMap<(Id, String), EnumType>>,
+    functions: RwLock<HashMap<(Id, String), SqlFunction>>,
+    table_locks: TableLocks,
+    lock_owners: AtomicU64,
+    /// keys of records of commits that are being written, records left by a crash are finished on open
+    commit_ids: AtomicU64,
+    change_slots: ChangeSlots,
+    /// tables created by transactions that are not committed yet, only their transactions see them
+    uncommitted_tables: RwLock<HashSet<(Id, Id)>>,
 }
 
 impl Default for DataManager {
@@ -155,320 +594,2319 @@ unsafe impl Send for DataManager {}
 unsafe impl Sync for DataManager {}
 
 const DEFAULT_CATALOG: &'_ str = "public";
+const LARGE_OBJECTS_SCHEMA: &'_ str = "pg_largeobject";
+const FIRST_LARGE_OBJECT_ID: Id = 16384;
+const SEQUENCES_SCHEMA: &'_ str = "pg_sequence";
+const SEQUENCES_OBJECT: &'_ str = "sequences";
+const TYPES_SCHEMA: &'_ str = "pg_type";
+const TYPES_OBJECT: &'_ str = "enums";
+const FUNCTIONS_SCHEMA: &'_ str = "pg_proc";
+const FUNCTIONS_OBJECT: &'_ str = "functions";
+const PARTITIONS_SCHEMA: &'_ str = "pg_partitioned_table";
+const PARTITIONS_OBJECT: &'_ str = "partitions";
+const ACLS_SCHEMA: &'_ str = "pg_namespace";
+const ACLS_OBJECT: &'_ str = "acls";
+const STATISTICS_SCHEMA: &'_ str = "pg_statistic";
+const EXTENDED_STATISTICS_OBJECT: &'_ str = "extended";
+const TABLE_STATISTICS_OBJECT: &'_ str = "tables";
+const COMMITS_SCHEMA: &'_ str = "pg_xact";
+const COMMITS_OBJECT: &'_ str = "commits";
 
-impl DataManager {
-    pub fn in_memory() -> SystemResult<DataManager> {
-        let data_definition = DataDefinition::in_memory();
-        data_definition.create_catalog(DEFAULT_CATALOG);
-        Ok(Self {
-            data_storage: Box::new(InMemoryDatabase::default()),
-            data_definition,
-            schemas: RwLock::default(),
-            tables: RwLock::default(),
-            record_id_generators: RwLock::default(),
-        })
-    }
+type Sequences = (HashMap<(Id, Id), Sequence>, HashMap<(Id, String), Sequence>);
 
-    pub fn persistent(path: PathBuf) -> SystemResult<DataManager> {
-        let data_definition = DataDefinition::persistent(&path)?;
-        let catalog = PersistentDatabase::new(path.join(DEFAULT_CATALOG));
-        let schemas = RwLock::new(HashMap::new());
-        let tables = RwLock::new(HashMap::new());
-        match data_definition.catalog_exists(DEFAULT_CATALOG) {
-            Some(_id) => {
-                for (schema_id, schema_name) in data_definition.schemas(DEFAULT_CATALOG) {
-                    schemas
-                        .write()
-                        .expect("to acquire write lock")
-                        .insert(schema_id, schema_name.clone());
-                    match catalog.init(schema_name.as_str()) {
-                        Ok(Ok(InitStatus::Loaded)) => {
-                            for (table_id, table_name) in data_definition.tables(DEFAULT_CATALOG, schema_name.as_str())
-                            {
-                                tables
-                                    .write()
-                                    .expect("to acquire write lock")
-                                    .insert((schema_id, table_id), vec![schema_name.clone(), table_name.clone()]);
-                                catalog.open_object(schema_name.as_str(), table_name.as_str());
-                            }
-                        }
-                        Ok(Ok(InitStatus::Created)) => {
-                            log::error!("Schema {:?} should have been already created", schema_name);
-                            return Err(SystemError::bug_in_sql_engine(
-                                Operation::Access,
-                                Object::Schema(schema_name.as_str()),
-                            ));
-                        }
-                        Ok(Err(error)) => {
-                            log::error!("Error during schema {:?} initialization {:?}", schema_name, error);
-                            return Err(SystemError::bug_in_sql_engine(
-                                Operation::Access,
-                                Object::Schema(schema_name.as_str()),
-                            ));
-                        }
-                        Err(io_error) => return Err(SystemError::io(io_error)),
+/// row id generators and named sequences are stored in the same object,
+/// the first are keyed by table id and the second by schema id and sequence name
+fn load_sequences(data_storage: &dyn Database) -> SystemResult<Sequences> {
+    match data_storage.create_object(SEQUENCES_SCHEMA, SEQUENCES_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(SEQUENCES_SCHEMA, SEQUENCES_OBJECT),
+            ))
+        }
+    }
+    let mut record_id_generators = HashMap::new();
+    let mut sequences = HashMap::new();
+    match data_storage.read(SEQUENCES_SCHEMA, SEQUENCES_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                let sequence = match Sequence::from_values(&values) {
+                    Some(sequence) => sequence,
+                    None => continue,
+                };
+                match unpack_raw(key.to_bytes()).as_slice() {
+                    [Datum::UInt64(schema_id), Datum::UInt64(table_id)] => {
+                        record_id_generators.insert((*schema_id, *table_id), sequence);
+                    }
+                    [Datum::UInt64(schema_id), Datum::String(sequence_name)] => {
+                        sequences.insert((*schema_id, (*sequence_name).to_owned()), sequence);
                     }
+                    _ => {}
                 }
             }
-            None => {
-                data_definition.create_catalog(DEFAULT_CATALOG);
-            }
+            Ok((record_id_generators, sequences))
         }
-        Ok(Self {
-            data_storage: Box::new(catalog),
-            data_definition,
-            schemas,
-            tables,
-            record_id_generators: RwLock::default(),
-        })
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(SEQUENCES_SCHEMA, SEQUENCES_OBJECT),
+        )),
     }
+}
 
-    pub fn next_key_id<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Id {
-        match self
-            .record_id_generators
-            .read()
-            .expect("to acquire read lock")
-            .get(table_id.as_ref())
-        {
-            Some(id_generator) => id_generator.fetch_add(1, Ordering::SeqCst),
-            None => panic!(),
+/// enum types are keyed by schema id and type name, labels are stored in their order
+fn load_enum_types(data_storage: &dyn Database) -> SystemResult<HashMap<(Id, String), EnumType>> {
+    match data_storage.create_object(TYPES_SCHEMA, TYPES_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(TYPES_SCHEMA, TYPES_OBJECT),
+            ))
         }
     }
-
-    pub fn create_schema(&self, schema_name: &str) -> SystemResult<Id> {
-        match self.data_definition.create_schema(DEFAULT_CATALOG, schema_name) {
-            Some((_, Some(schema_id))) => {
-                self.schemas
-                    .write()
-                    .expect("to acquire write lock")
-                    .insert(schema_id, schema_name.to_owned());
-                match self.data_storage.create_schema(schema_name) {
-                    Ok(Ok(Ok(()))) => Ok(schema_id),
-                    _ => Err(SystemError::bug_in_sql_engine(
-                        Operation::Create,
-                        Object::Schema(schema_name),
-                    )),
+    match data_storage.read(TYPES_SCHEMA, TYPES_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut enum_types = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id), Datum::String(type_name)] = unpack_raw(key.to_bytes()).as_slice() {
+                    if let Some(enum_type) = EnumType::from_values(type_name, &values) {
+                        enum_types.insert((*schema_id, (*type_name).to_owned()), enum_type);
+                    }
                 }
             }
-            Some((_, None)) => Err(SystemError::bug_in_sql_engine(
-                Operation::Create,
-                Object::Schema(schema_name),
-            )),
-            None => Err(SystemError::bug_in_sql_engine(
-                Operation::Create,
-                Object::Schema(schema_name),
-            )),
+            Ok(enum_types)
         }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(TYPES_SCHEMA, TYPES_OBJECT),
+        )),
     }
+}
 
-    pub fn drop_schema<I: AsRef<Id>>(
-        &self,
-        schema_id: &I,
-        strategy: DropStrategy,
-    ) -> SystemResult<Result<(), DropSchemaError>> {
-        match self
-            .schemas
-            .write()
-            .expect("to acquire write lock")
-            .remove(schema_id.as_ref())
-        {
-            None => Ok(Err(DropSchemaError::DoesNotExist)),
-            Some(schema_name) => {
-                match self
-                    .data_definition
-                    .drop_schema(DEFAULT_CATALOG, schema_name.as_str(), strategy)
+/// functions are keyed by schema id and function name
+fn load_functions(data_storage: &dyn Database) -> SystemResult<HashMap<(Id, String), SqlFunction>> {
+    match data_storage.create_object(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT),
+            ))
+        }
+    }
+    match data_storage.read(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut functions = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id), Datum::String(function_name)] = unpack_raw(key.to_bytes()).as_slice()
                 {
-                    Ok(()) => match self.data_storage.drop_schema(schema_name.as_str()) {
-                        Ok(Ok(Ok(()))) => Ok(Ok(())),
-                        _ => Err(SystemError::bug_in_sql_engine(
-                            Operation::Drop,
-                            Object::Schema(schema_name.as_str()),
-                        )),
-                    },
-                    Err(error) => Ok(Err(error)),
+                    if let Some(function) = SqlFunction::from_values(function_name, &values) {
+                        functions.insert((*schema_id, (*function_name).to_owned()), function);
+                    }
                 }
             }
+            Ok(functions)
         }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT),
+        )),
     }
+}
 
-    pub fn create_table(
-        &self,
-        schema_id: Id,
-        table_name: &str,
-        column_definitions: &[ColumnDefinition],
-    ) -> SystemResult<Id> {
-        match self.schemas.read().expect("to acquire read lock").get(&schema_id) {
-            Some(schema_name) => {
-                match self
-                    .data_definition
-                    .create_table(DEFAULT_CATALOG, schema_name, table_name, column_definitions)
-                {
-                    Some((_, Some((_, Some(table_id))))) => {
-                        self.tables.write().expect("to acquire write lock").insert(
-                            (schema_id, table_id),
-                            vec![schema_name.to_owned(), table_name.to_owned()],
-                        );
-                        self.record_id_generators
-                            .write()
-                            .expect("to acquire write lock")
-                            .insert((schema_id, table_id), AtomicU64::default());
-                        match self.data_storage.create_object(schema_name, table_name) {
-                            Ok(Ok(Ok(()))) => Ok(table_id),
-                            _ => Err(SystemError::bug_in_sql_engine(
-                                Operation::Create,
-                                Object::Table(schema_name, table_name),
-                            )),
-                        }
+/// partitionings are keyed by ids of partitioned tables
+fn load_partitions(data_storage: &dyn Database) -> SystemResult<HashMap<(Id, Id), RangePartitioning>> {
+    match data_storage.create_object(PARTITIONS_SCHEMA, PARTITIONS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(PARTITIONS_SCHEMA, PARTITIONS_OBJECT),
+            ))
+        }
+    }
+    match data_storage.read(PARTITIONS_SCHEMA, PARTITIONS_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut partitioned_tables = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id), Datum::UInt64(table_id)] = unpack_raw(key.to_bytes()).as_slice() {
+                    if let Some(partitioning) = RangePartitioning::from_values(&values) {
+                        partitioned_tables.insert((*schema_id, *table_id), partitioning);
                     }
-                    _ => Err(SystemError::bug_in_sql_engine(
-                        Operation::Create,
-                        Object::Table(schema_id.to_string().as_str(), table_name),
-                    )),
                 }
             }
-            None => Err(SystemError::bug_in_sql_engine(
-                Operation::Create,
-                Object::Table(schema_id.to_string().as_str(), table_name),
-            )),
+            Ok(partitioned_tables)
         }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(PARTITIONS_SCHEMA, PARTITIONS_OBJECT),
+        )),
     }
+}
 
-    pub fn table_columns<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<Vec<ColumnDefinition>> {
-        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
-            Some(full_name) => {
-                Ok(self
-                    .data_definition
-                    .table_columns(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str()))
-            }
-            _ => {
-                let (schema_id, table_id) = table_id.as_ref();
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Access,
-                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                ))
+/// owners and grants are keyed by schema ids
+fn load_schema_acls(data_storage: &dyn Database) -> SystemResult<HashMap<Id, SchemaAcl>> {
+    match data_storage.create_object(ACLS_SCHEMA, ACLS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(ACLS_SCHEMA, ACLS_OBJECT),
+            ))
+        }
+    }
+    match data_storage.read(ACLS_SCHEMA, ACLS_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut schema_acls = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id)] = unpack_raw(key.to_bytes()).as_slice() {
+                    if let Some(acl) = SchemaAcl::from_values(&values) {
+                        schema_acls.insert(*schema_id, acl);
+                    }
+                }
             }
+            Ok(schema_acls)
         }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(ACLS_SCHEMA, ACLS_OBJECT),
+        )),
     }
+}
 
-    pub fn drop_table<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<()> {
-        match self
-            .tables
-            .write()
-            .expect("to acquire write lock")
-            .remove(table_id.as_ref())
-        {
-            None => {
-                let (schema_id, table_id) = table_id.as_ref();
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Drop,
-                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                ))
-            }
-            Some(full_name) => {
-                self.data_definition
-                    .drop_table(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str());
-                match self
-                    .data_storage
-                    .drop_object(full_name[0].as_str(), full_name[1].as_str())
+/// extended statistics are keyed by schema id and statistics name
+fn load_extended_statistics(data_storage: &dyn Database) -> SystemResult<HashMap<(Id, String), ExtendedStatistics>> {
+    match data_storage.create_object(STATISTICS_SCHEMA, EXTENDED_STATISTICS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(STATISTICS_SCHEMA, EXTENDED_STATISTICS_OBJECT),
+            ))
+        }
+    }
+    match data_storage.read(STATISTICS_SCHEMA, EXTENDED_STATISTICS_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut all_statistics = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id), Datum::String(statistics_name)] =
+                    unpack_raw(key.to_bytes()).as_slice()
                 {
-                    Ok(Ok(Ok(()))) => Ok(()),
-                    _ => {
-                        let (schema_id, table_id) = table_id.as_ref();
-                        Err(SystemError::bug_in_sql_engine(
-                            Operation::Drop,
-                            Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                        ))
+                    if let Some(statistics) = statistics::from_values(&values) {
+                        all_statistics.insert((*schema_id, (*statistics_name).to_owned()), statistics);
                     }
                 }
             }
+            Ok(all_statistics)
         }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(STATISTICS_SCHEMA, EXTENDED_STATISTICS_OBJECT),
+        )),
     }
+}
 
-    pub fn write_into<I: AsRef<(Id, Id)>>(&self, table_id: &I, values: Vec<(Key, Values)>) -> SystemResult<usize> {
-        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
-            Some(full_name) => {
-                log::debug!("{:#?}", values);
-                match self
-                    .data_storage
-                    .write(full_name[0].as_str(), full_name[1].as_str(), values)
-                {
-                    Ok(Ok(Ok(size))) => Ok(size),
-                    _ => {
-                        let (schema_id, table_id) = table_id.as_ref();
-                        Err(SystemError::bug_in_sql_engine(
-                            Operation::Access,
-                            Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                        ))
+/// statistics that `ANALYZE` gathered are keyed by ids of analyzed tables
+fn load_table_statistics(data_storage: &dyn Database) -> SystemResult<HashMap<(Id, Id), TableStatistics>> {
+    match data_storage.create_object(STATISTICS_SCHEMA, TABLE_STATISTICS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(STATISTICS_SCHEMA, TABLE_STATISTICS_OBJECT),
+            ))
+        }
+    }
+    match data_storage.read(STATISTICS_SCHEMA, TABLE_STATISTICS_OBJECT) {
+        Ok(Ok(Ok(read))) => {
+            let mut table_statistics = HashMap::new();
+            for (key, values) in read.filter_map(|row| row.ok().and_then(Result::ok)) {
+                if let [Datum::UInt64(schema_id), Datum::UInt64(table_id)] = unpack_raw(key.to_bytes()).as_slice() {
+                    if let Some(statistics) = statistics::from_values(&values) {
+                        table_statistics.insert((*schema_id, *table_id), statistics);
                     }
                 }
             }
-            None => {
-                let (schema_id, table_id) = table_id.as_ref();
-                Err(SystemError::bug_in_sql_engine(
+            Ok(table_statistics)
+        }
+        _ => Err(SystemError::bug_in_sql_engine(
+            Operation::Access,
+            Object::Table(STATISTICS_SCHEMA, TABLE_STATISTICS_OBJECT),
+        )),
+    }
+}
+
+/// commits cut by a crash are finished in the order they were made and their records are removed
+fn finish_commits(data_storage: &dyn Database) -> SystemResult<()> {
+    match data_storage.create_object(COMMITS_SCHEMA, COMMITS_OBJECT) {
+        Ok(Ok(Ok(()))) | Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => {}
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT),
+            ))
+        }
+    }
+    let mut records = match data_storage.read(COMMITS_SCHEMA, COMMITS_OBJECT) {
+        Ok(Ok(Ok(read))) => read
+            .map(row_or_storage_error)
+            .collect::<Result<Vec<Row>, StorageError>>()
+            .map_err(|_error| {
+                SystemError::bug_in_sql_engine(Operation::Access, Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT))
+            })?,
+        _ => {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT),
+            ))
+        }
+    };
+    records.sort_by_key(|(key, _values)| match unpack_raw(key.to_bytes()).as_slice() {
+        [Datum::UInt64(commit_id)] => *commit_id,
+        _ => 0,
+    });
+    for (key, values) in records {
+        if let Some(record) = CommitRecord::from_values(&values) {
+            write_commit(data_storage, &record)?;
+        }
+        match data_storage.delete(COMMITS_SCHEMA, COMMITS_OBJECT, vec![key]) {
+            Ok(Ok(Ok(_))) => {}
+            _ => {
+                return Err(SystemError::bug_in_sql_engine(
                     Operation::Access,
-                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT),
                 ))
             }
         }
     }
+    Ok(())
+}
 
-    pub fn full_scan<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<ReadCursor> {
-        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
-            Some(full_name) => match self.data_storage.read(full_name[0].as_str(), full_name[1].as_str()) {
-                Ok(Ok(Ok(read))) => Ok(read),
+/// rows of the commit are deleted and written table by table, rows of tables dropped since are gone with them
+fn write_commit(data_storage: &dyn Database, record: &CommitRecord) -> SystemResult<()> {
+    for (schema_name, table_name, deleted, written) in record.tables() {
+        let deleted = data_storage.delete(schema_name, table_name, deleted);
+        let written = data_storage.write(schema_name, table_name, written);
+        for result in &[deleted, written] {
+            match result {
+                Ok(Ok(Ok(_)))
+                | Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                | Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))) => {}
                 _ => {
-                    let (schema_id, table_id) = table_id.as_ref();
-                    Err(SystemError::bug_in_sql_engine(
+                    return Err(SystemError::bug_in_sql_engine(
                         Operation::Access,
-                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                        Object::Table(schema_name, table_name),
                     ))
                 }
-            },
-            None => {
-                let (schema_id, table_id) = table_id.as_ref();
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Access,
-                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                ))
             }
         }
     }
+    Ok(())
+}
 
-    pub fn delete_from<I: AsRef<(Id, Id)>>(&self, table_id: &I, keys: Vec<Key>) -> SystemResult<usize> {
-        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
-            Some(full_name) => match self
-                .data_storage
-                .delete(full_name[0].as_str(), full_name[1].as_str(), keys)
-            {
-                Ok(Ok(Ok(len))) => Ok(len),
-                _ => {
-                    let (schema_id, table_id) = table_id.as_ref();
-                    Err(SystemError::bug_in_sql_engine(
-                        Operation::Access,
-                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                    ))
+fn commit_key(commit_id: u64) -> Binary {
+    Binary::pack(&[Datum::from_u64(commit_id)])
+}
+
+fn schema_acl_key(schema_id: Id) -> Binary {
+    Binary::pack(&[Datum::from_u64(schema_id)])
+}
+
+fn row_ids_key(table_id: &(Id, Id)) -> Binary {
+    Binary::pack(&[Datum::from_u64(table_id.0), Datum::from_u64(table_id.1)])
+}
+
+fn sequence_key(schema_id: Id, sequence_name: &str) -> Binary {
+    Binary::pack(&[Datum::from_u64(schema_id), Datum::from_str(sequence_name)])
+}
+
+fn enum_type_key(schema_id: Id, type_name: &str) -> Binary {
+    Binary::pack(&[Datum::from_u64(schema_id), Datum::from_str(type_name)])
+}
+
+fn function_key(schema_id: Id, function_name: &str) -> Binary {
+    Binary::pack(&[Datum::from_u64(schema_id), Datum::from_str(function_name)])
+}
+
+fn statistics_key(schema_id: Id, statistics_name: &str) -> Binary {
+    Binary::pack(&[Datum::from_u64(schema_id), Datum::from_str(statistics_name)])
+}
+
+/// sequences of serial columns are bounded by the column type
+fn serial_sequence_options(sql_type: SqlType) -> SequenceOptions {
+    match sql_type {
+        SqlType::SmallInt(_) => SequenceOptions::default().max_value(i16::max_value() as i64),
+        SqlType::Integer(_) => SequenceOptions::default().max_value(i32::max_value() as i64),
+        _ => SequenceOptions::default(),
+    }
+}
+
+/// storages can't scan ranges that start after their end
+fn is_empty_range(keys: &KeyRange) -> bool {
+    match keys {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end))
+        | (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+        _ => false,
+    }
+}
+
+/// io errors of read rows are errors of the storage as much as its own errors
+fn row_or_storage_error(row: RowResult) -> Result<Row, StorageError> {
+    match row {
+        Ok(row) => row,
+        Err(_io_error) => Err(StorageError::Io),
+    }
+}
+
+/// rows of a table are read from the storage by batches of this size
+pub const SCAN_BATCH_SIZE: usize = 1024;
+
+/// batches of rows of a table that are read one after another, see `DataManager::scan`
+pub struct TableScan<'d> {
+    data_manager: &'d DataManager,
+    table_id: (Id, Id),
+    keys: Option<KeyRange>,
+    batch_size: usize,
+    writes: Writes,
+}
+
+impl<'d> TableScan<'d> {
+    /// rows written by the transaction of the journal replace rows read from the table
+    pub fn with_journal(mut self, journal: Option<&Journal>) -> TableScan<'d> {
+        if let Some(journal) = journal {
+            self.writes = journal.writes(&self.table_id);
+        }
+        self
+    }
+}
+
+impl<'d> Iterator for TableScan<'d> {
+    type Item = SystemResult<Result<Vec<Row>, StorageError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let keys = self.keys.take()?;
+            let (start, end) = keys.clone();
+            match self.data_manager.read_batch(&self.table_id, keys, self.batch_size) {
+                Ok(Ok(rows)) => {
+                    // the next batch starts after the last read key, a shorter batch is the last one
+                    let last = match rows.last() {
+                        Some((key, _values)) if rows.len() == self.batch_size => Some(key.clone()),
+                        _ => None,
+                    };
+                    let written = match last.as_ref() {
+                        Some(last) => (start, Bound::Included(last.clone())),
+                        None => (start, end.clone()),
+                    };
+                    if let Some(last) = last {
+                        self.keys = Some((Bound::Excluded(last), end));
+                    }
+                    let rows = if self.writes.is_empty() || is_empty_range(&written) {
+                        Ok(rows)
+                    } else {
+                        JournaledRows {
+                            rows: rows.into_iter().map(|row| Ok(Ok(row))).peekable(),
+                            writes: self
+                                .writes
+                                .range(written)
+                                .map(|(key, values)| (key.clone(), values.clone()))
+                                .peekable(),
+                        }
+                        .map(row_or_storage_error)
+                        .collect()
+                    };
+                    match rows {
+                        Ok(rows) if rows.is_empty() => {}
+                        rows => return Some(Ok(rows)),
+                    }
                 }
-            },
-            None => {
-                let (schema_id, table_id) = table_id.as_ref();
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Access,
-                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                ))
+                Ok(Err(error)) => return Some(Ok(Err(error))),
+                Err(error) => return Some(Err(error)),
             }
         }
     }
+}
 
-    pub fn schema_exists<S: AsRef<str>>(&self, schema_name: &S) -> FullSchemaId {
-        self.data_definition
-            .schema_exists(DEFAULT_CATALOG, schema_name.as_ref())
-            .and_then(|(_catalog, schema)| schema)
+/// rows of a table merged with rows written into it by a transaction, both are ordered by their keys
+struct JournaledRows<R: Iterator<Item = RowResult>, W: Iterator<Item = (Key, Option<Values>)>> {
+    rows: Peekable<R>,
+    writes: Peekable<W>,
+}
+
+impl<R: Iterator<Item = RowResult>, W: Iterator<Item = (Key, Option<Values>)>> Iterator for JournaledRows<R, W> {
+    type Item = RowResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let written = match (self.rows.peek(), self.writes.peek()) {
+                (None, None) => return None,
+                (Some(Ok(Ok((key, _values)))), Some((written_key, _written_values))) => match key.cmp(written_key) {
+                    CmpOrdering::Less => return self.rows.next(),
+                    CmpOrdering::Equal => {
+                        self.rows.next();
+                        self.writes.next()
+                    }
+                    CmpOrdering::Greater => self.writes.next(),
+                },
+                (Some(_), _) => return self.rows.next(),
+                (None, Some(_)) => self.writes.next(),
+            };
+            // deleted rows are skipped
+            if let Some((key, Some(values))) = written {
+                return Some(Ok(Ok((key, values))));
+            }
+        }
     }
+}
 
-    pub fn table_exists<S: AsRef<str>>(&self, schema_name: &S, table_name: &S) -> FullTableId {
-        self.data_definition
-            .table_exists(DEFAULT_CATALOG, schema_name.as_ref(), table_name.as_ref())
-            .and_then(|(_catalog, full_table)| full_table)
+/// Large objects are stored and read by pages of this size
+pub const LARGE_OBJECT_PAGE_SIZE: usize = 2048;
+
+impl DataManager {
+    pub fn in_memory() -> SystemResult<DataManager> {
+        Self::open(StorageBackend::InMemory)
+    }
+
+    pub fn persistent(path: PathBuf) -> SystemResult<DataManager> {
+        Self::open(StorageBackend::Sled(path))
+    }
+
+    /// definitions and rows are kept by the backend, schemas and tables that it kept are loaded
+    pub fn open(backend: StorageBackend) -> SystemResult<DataManager> {
+        match backend {
+            StorageBackend::InMemory => Self::with_storage(DataDefinition::in_memory(), InMemoryDatabase::default()),
+            StorageBackend::Sled(path) => Self::with_storage(
+                DataDefinition::persistent(&path)?,
+                PersistentDatabase::new(path.join(DEFAULT_CATALOG)),
+            ),
+            #[cfg(feature = "rocksdb")]
+            StorageBackend::RocksDb(path) => Self::with_storage(
+                DataDefinition::rocks_db(&path)?,
+                rocks::RocksDatabase::new(path.join(DEFAULT_CATALOG)),
+            ),
+        }
+    }
+
+    fn with_storage<D: Database + 'static>(data_definition: DataDefinition, catalog: D) -> SystemResult<DataManager> {
+        let schemas = RwLock::new(HashMap::new());
+        let tables = RwLock::new(HashMap::new());
+        match data_definition.catalog_exists(DEFAULT_CATALOG) {
+            Some(_id) => {
+                for (schema_id, schema_name) in data_definition.schemas(DEFAULT_CATALOG) {
+                    schemas
+                        .write()
+                        .expect("to acquire write lock")
+                        .insert(schema_id, schema_name.clone());
+                    // storage of a schema is created after its definition is saved and could be missing after a crash
+                    match catalog.init(schema_name.as_str()) {
+                        Ok(Ok(InitStatus::Loaded)) | Ok(Ok(InitStatus::Created)) => {
+                            for (table_id, table_name) in data_definition.tables(DEFAULT_CATALOG, schema_name.as_str())
+                            {
+                                tables
+                                    .write()
+                                    .expect("to acquire write lock")
+                                    .insert((schema_id, table_id), vec![schema_name.clone(), table_name.clone()]);
+                                catalog.open_object(schema_name.as_str(), table_name.as_str());
+                            }
+                        }
+                        Ok(Err(error)) => {
+                            log::error!("Error during schema {:?} initialization {:?}", schema_name, error);
+                            return Err(SystemError::bug_in_sql_engine(
+                                Operation::Access,
+                                Object::Schema(schema_name.as_str()),
+                            ));
+                        }
+                        Err(io_error) => return Err(SystemError::io(io_error)),
+                    }
+                }
+            }
+            None => {
+                data_definition.create_catalog(DEFAULT_CATALOG);
+            }
+        }
+        for schema_name in &[
+            LARGE_OBJECTS_SCHEMA,
+            SEQUENCES_SCHEMA,
+            TYPES_SCHEMA,
+            FUNCTIONS_SCHEMA,
+            PARTITIONS_SCHEMA,
+            ACLS_SCHEMA,
+            STATISTICS_SCHEMA,
+            COMMITS_SCHEMA,
+        ] {
+            match catalog.init(schema_name) {
+                Ok(Ok(_)) => {}
+                Ok(Err(error)) => {
+                    log::error!("Error during {:?} initialization {:?}", schema_name, error);
+                    return Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Schema(schema_name),
+                    ));
+                }
+                Err(io_error) => return Err(SystemError::io(io_error)),
+            }
+        }
+        finish_commits(&catalog)?;
+        let (record_id_generators, sequences) = load_sequences(&catalog)?;
+        let enum_types = load_enum_types(&catalog)?;
+        let functions = load_functions(&catalog)?;
+        let partitioned_tables = load_partitions(&catalog)?;
+        let statistics = load_extended_statistics(&catalog)?;
+        let row_statistics = load_table_statistics(&catalog)?;
+        // schemas created before their acls were persisted are owned by superuser
+        let mut stored_acls = load_schema_acls(&catalog)?;
+        let schema_acls = schemas
+            .read()
+            .expect("to acquire read lock")
+            .keys()
+            .map(|schema_id| {
+                let acl = stored_acls
+                    .remove(schema_id)
+                    .unwrap_or_else(|| SchemaAcl::owned_by(SUPERUSER));
+                (*schema_id, acl)
+            })
+            .collect();
+        Ok(Self {
+            data_storage: Box::new(catalog),
+            data_definition,
+            schemas,
+            schema_acls: RwLock::new(schema_acls),
+            tables,
+            record_id_generators: RwLock::new(record_id_generators),
+            sequences: RwLock::new(sequences),
+            large_object_ids: AtomicU64::new(FIRST_LARGE_OBJECT_ID),
+            partitioned_tables: RwLock::new(partitioned_tables),
+            statistics: RwLock::new(statistics),
+            row_statistics: RwLock::new(row_statistics),
+            column_filters: RwLock::default(),
+            table_versions: RwLock::default(),
+            enum_types: RwLock::new(enum_types),
+            functions: RwLock::new(functions),
+            table_locks: TableLocks::default(),
+            lock_owners: AtomicU64::new(1),
+            commit_ids: AtomicU64::default(),
+            change_slots: ChangeSlots::default(),
+            uncommitted_tables: RwLock::default(),
+        })
+    }
+
+    /// next row id of a table without primary key, generators are persisted
+    /// and the one missing for a table continues after its largest stored row id
+    pub fn next_key_id<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Id {
+        if !self
+            .record_id_generators
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(table_id.as_ref())
+        {
+            let start = self
+                .full_scan(table_id)
+                .map(|reads| {
+                    reads
+                        .filter_map(|row| row.ok().and_then(Result::ok))
+                        .filter_map(|(key, _values)| key.to_bytes().try_into().ok().map(u64::from_be_bytes))
+                        .max()
+                        .map(|max| max as i64 + 1)
+                        .unwrap_or(0)
+                })
+                .unwrap_or(0);
+            self.record_id_generators
+                .write()
+                .expect("to acquire write lock")
+                .entry(*table_id.as_ref())
+                .or_insert_with(|| Sequence::row_ids(start));
+        }
+        let key = row_ids_key(table_id.as_ref());
+        match self
+            .record_id_generators
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .map(|generator| generator.next_value(|values| self.persist_sequence(key, values)))
+        {
+            Some(Ok(Ok(id))) => id as Id,
+            _ => panic!(
+                "row ids of {:?} table are exhausted or can't be persisted",
+                table_id.as_ref()
+            ),
+        }
+    }
+
+    /// creates a sequence in the schema, its initial state is persisted
+    pub fn create_sequence(
+        &self,
+        schema_id: Id,
+        sequence_name: &str,
+        options: SequenceOptions,
+    ) -> SystemResult<Result<(), SequenceError>> {
+        let sequence = match Sequence::new(options) {
+            Ok(sequence) => sequence,
+            Err(error) => return Ok(Err(error)),
+        };
+        let mut sequences = self.sequences.write().expect("to acquire write lock");
+        if sequences.contains_key(&(schema_id, sequence_name.to_owned())) {
+            return Ok(Err(SequenceError::AlreadyExists));
+        }
+        self.persist_sequence(sequence_key(schema_id, sequence_name), sequence.to_values())?;
+        sequences.insert((schema_id, sequence_name.to_owned()), sequence);
+        Ok(Ok(()))
+    }
+
+    pub fn sequence_exists(&self, schema_id: Id, sequence_name: &str) -> bool {
+        self.sequences
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(&(schema_id, sequence_name.to_owned()))
+    }
+
+    /// tables and columns of the schema whose defaults take values of the sequence
+    pub fn sequence_dependents(&self, schema_id: Id, sequence_name: &str) -> Vec<(String, String)> {
+        let mut dependents = vec![];
+        for ((table_schema_id, _), full_name) in self.tables.read().expect("to acquire read lock").iter() {
+            if *table_schema_id != schema_id {
+                continue;
+            }
+            for column in
+                self.data_definition
+                    .table_columns(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str())
+            {
+                if column.serial_sequence() == Some(sequence_name) {
+                    dependents.push((full_name[1].clone(), column.name()));
+                }
+            }
+        }
+        dependents.sort();
+        dependents
+    }
+
+    /// removes the sequence, returns `false` if it does not exist
+    pub fn drop_sequence(&self, schema_id: Id, sequence_name: &str) -> SystemResult<bool> {
+        match self
+            .sequences
+            .write()
+            .expect("to acquire write lock")
+            .remove(&(schema_id, sequence_name.to_owned()))
+        {
+            Some(_sequence) => {
+                self.remove_sequences(vec![sequence_key(schema_id, sequence_name)])?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// advances the sequence, the value is persisted before it is returned
+    pub fn next_sequence_value(&self, schema_id: Id, sequence_name: &str) -> SystemResult<Result<i64, SequenceError>> {
+        match self
+            .sequences
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, sequence_name.to_owned()))
+        {
+            Some(sequence) => {
+                sequence.next_value(|values| self.persist_sequence(sequence_key(schema_id, sequence_name), values))
+            }
+            None => Ok(Err(SequenceError::DoesNotExist)),
+        }
+    }
+
+    /// sets the current value of the sequence, `nextval` returns `value` itself if `is_called` is `false`
+    pub fn set_sequence_value(
+        &self,
+        schema_id: Id,
+        sequence_name: &str,
+        value: i64,
+        is_called: bool,
+    ) -> SystemResult<Result<i64, SequenceError>> {
+        match self
+            .sequences
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, sequence_name.to_owned()))
+        {
+            Some(sequence) => sequence.set_value(value, is_called, |values| {
+                self.persist_sequence(sequence_key(schema_id, sequence_name), values)
+            }),
+            None => Ok(Err(SequenceError::DoesNotExist)),
+        }
+    }
+
+    fn drop_schema_sequences(&self, schema_id: Id) -> SystemResult<()> {
+        let mut keys = vec![];
+        self.record_id_generators
+            .write()
+            .expect("to acquire write lock")
+            .retain(|table_id, _generator| {
+                if table_id.0 == schema_id {
+                    keys.push(row_ids_key(table_id));
+                }
+                table_id.0 != schema_id
+            });
+        self.sequences.write().expect("to acquire write lock").retain(
+            |(sequence_schema_id, sequence_name), _sequence| {
+                if *sequence_schema_id == schema_id {
+                    keys.push(sequence_key(schema_id, sequence_name));
+                }
+                *sequence_schema_id != schema_id
+            },
+        );
+        self.remove_sequences(keys)
+    }
+
+    /// row id generator and sequences of serial columns are owned by the table
+    fn drop_table_sequences(&self, table_id: &(Id, Id), columns: &[ColumnDefinition]) -> SystemResult<()> {
+        let (schema_id, _) = table_id;
+        let mut keys = vec![];
+        if self
+            .record_id_generators
+            .write()
+            .expect("to acquire write lock")
+            .remove(table_id)
+            .is_some()
+        {
+            keys.push(row_ids_key(table_id));
+        }
+        let mut sequences = self.sequences.write().expect("to acquire write lock");
+        for sequence_name in columns.iter().filter_map(ColumnDefinition::serial_sequence) {
+            if sequences.remove(&(*schema_id, sequence_name.to_owned())).is_some() {
+                keys.push(sequence_key(*schema_id, sequence_name));
+            }
+        }
+        self.remove_sequences(keys)
+    }
+
+    fn table_changed(&self, table_id: (Id, Id)) {
+        *self
+            .table_versions
+            .write()
+            .expect("to acquire write lock")
+            .entry(table_id)
+            .or_default() += 1;
+    }
+
+    fn schema_tables_changed(&self, schema_id: Id) {
+        let table_ids = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .keys()
+            .filter(|(table_schema_id, _)| *table_schema_id == schema_id)
+            .copied()
+            .collect::<Vec<(Id, Id)>>();
+        for table_id in table_ids {
+            self.table_changed(table_id);
+        }
+    }
+
+    fn forget_schema_partitions(&self, schema_id: Id) -> SystemResult<()> {
+        let mut changed = vec![];
+        {
+            let mut partitioned_tables = self.partitioned_tables.write().expect("to acquire write lock");
+            partitioned_tables.retain(|table_id, _| {
+                if table_id.0 == schema_id {
+                    changed.push(*table_id);
+                }
+                table_id.0 != schema_id
+            });
+            for (table_id, partitioning) in partitioned_tables.iter_mut() {
+                let partitions = partitioning.partitions.len();
+                partitioning
+                    .partitions
+                    .retain(|partition| partition.table_id.0 != schema_id);
+                if partitioning.partitions.len() != partitions {
+                    changed.push(*table_id);
+                }
+            }
+        }
+        self.persist_partitionings(&changed)
+    }
+
+    /// partitionings of the tables are written, the ones of tables that are not partitioned anymore are deleted
+    fn persist_partitionings(&self, table_ids: &[(Id, Id)]) -> SystemResult<()> {
+        let (written, deleted) = {
+            let partitioned_tables = self.partitioned_tables.read().expect("to acquire read lock");
+            let mut written = vec![];
+            let mut deleted = vec![];
+            for table_id in table_ids {
+                match partitioned_tables.get(table_id) {
+                    Some(partitioning) => written.push((row_ids_key(table_id), partitioning.to_values())),
+                    None => deleted.push(row_ids_key(table_id)),
+                }
+            }
+            (written, deleted)
+        };
+        let persisted = match self.data_storage.write(PARTITIONS_SCHEMA, PARTITIONS_OBJECT, written) {
+            Ok(Ok(Ok(_))) => matches!(
+                self.data_storage.delete(PARTITIONS_SCHEMA, PARTITIONS_OBJECT, deleted),
+                Ok(Ok(Ok(_)))
+            ),
+            _ => false,
+        };
+        if persisted {
+            Ok(())
+        } else {
+            Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(PARTITIONS_SCHEMA, PARTITIONS_OBJECT),
+            ))
+        }
+    }
+
+    fn persist_sequence(&self, key: Binary, values: Binary) -> SystemResult<()> {
+        match self
+            .data_storage
+            .write(SEQUENCES_SCHEMA, SEQUENCES_OBJECT, vec![(key, values)])
+        {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(SEQUENCES_SCHEMA, SEQUENCES_OBJECT),
+            )),
+        }
+    }
+
+    fn remove_sequences(&self, keys: Vec<Binary>) -> SystemResult<()> {
+        match self.data_storage.delete(SEQUENCES_SCHEMA, SEQUENCES_OBJECT, keys) {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(SEQUENCES_SCHEMA, SEQUENCES_OBJECT),
+            )),
+        }
+    }
+
+    /// creates an enum type in the schema and persists its labels, `false` if the type already exists
+    pub fn create_enum_type(&self, schema_id: Id, enum_type: EnumType) -> SystemResult<bool> {
+        let mut enum_types = self.enum_types.write().expect("to acquire write lock");
+        let key = (schema_id, enum_type.name().to_owned());
+        if enum_types.contains_key(&key) {
+            return Ok(false);
+        }
+        match self.data_storage.write(
+            TYPES_SCHEMA,
+            TYPES_OBJECT,
+            vec![(enum_type_key(schema_id, enum_type.name()), enum_type.to_values())],
+        ) {
+            Ok(Ok(Ok(_))) => {
+                enum_types.insert(key, enum_type);
+                Ok(true)
+            }
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(TYPES_SCHEMA, TYPES_OBJECT),
+            )),
+        }
+    }
+
+    pub fn enum_type(&self, schema_id: Id, type_name: &str) -> Option<EnumType> {
+        self.enum_types
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, type_name.to_owned()))
+            .cloned()
+    }
+
+    fn drop_schema_enum_types(&self, schema_id: Id) -> SystemResult<()> {
+        let mut keys = vec![];
+        self.enum_types
+            .write()
+            .expect("to acquire write lock")
+            .retain(|(type_schema_id, type_name), _enum_type| {
+                if *type_schema_id == schema_id {
+                    keys.push(enum_type_key(schema_id, type_name));
+                }
+                *type_schema_id != schema_id
+            });
+        match self.data_storage.delete(TYPES_SCHEMA, TYPES_OBJECT, keys) {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(TYPES_SCHEMA, TYPES_OBJECT),
+            )),
+        }
+    }
+
+    /// creates or replaces a function in the schema and persists it, `false` if the function exists and is not replaced
+    pub fn create_function(&self, schema_id: Id, function: SqlFunction, or_replace: bool) -> SystemResult<bool> {
+        let mut functions = self.functions.write().expect("to acquire write lock");
+        let key = (schema_id, function.name().to_owned());
+        if functions.contains_key(&key) && !or_replace {
+            return Ok(false);
+        }
+        match self.data_storage.write(
+            FUNCTIONS_SCHEMA,
+            FUNCTIONS_OBJECT,
+            vec![(function_key(schema_id, function.name()), function.to_values())],
+        ) {
+            Ok(Ok(Ok(_))) => {
+                functions.insert(key, function);
+                Ok(true)
+            }
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT),
+            )),
+        }
+    }
+
+    pub fn function(&self, schema_id: Id, function_name: &str) -> Option<SqlFunction> {
+        self.functions
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, function_name.to_owned()))
+            .cloned()
+    }
+
+    /// `false` if the function does not exist
+    pub fn drop_function(&self, schema_id: Id, function_name: &str) -> SystemResult<bool> {
+        let mut functions = self.functions.write().expect("to acquire write lock");
+        if functions.remove(&(schema_id, function_name.to_owned())).is_none() {
+            return Ok(false);
+        }
+        match self.data_storage.delete(
+            FUNCTIONS_SCHEMA,
+            FUNCTIONS_OBJECT,
+            vec![function_key(schema_id, function_name)],
+        ) {
+            Ok(Ok(Ok(_))) => Ok(true),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT),
+            )),
+        }
+    }
+
+    fn drop_schema_functions(&self, schema_id: Id) -> SystemResult<()> {
+        let mut keys = vec![];
+        self.functions.write().expect("to acquire write lock").retain(
+            |(function_schema_id, function_name), _function| {
+                if *function_schema_id == schema_id {
+                    keys.push(function_key(schema_id, function_name));
+                }
+                *function_schema_id != schema_id
+            },
+        );
+        match self.data_storage.delete(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT, keys) {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(FUNCTIONS_SCHEMA, FUNCTIONS_OBJECT),
+            )),
+        }
+    }
+
+    pub fn create_schema(&self, schema_name: &str) -> SystemResult<Id> {
+        match self.data_definition.create_schema(DEFAULT_CATALOG, schema_name) {
+            Some((_, Some(schema_id))) => {
+                self.schemas
+                    .write()
+                    .expect("to acquire write lock")
+                    .insert(schema_id, schema_name.to_owned());
+                let acl = SchemaAcl::owned_by(SUPERUSER);
+                self.persist_schema_acl(schema_id, Some(&acl))?;
+                self.schema_acls
+                    .write()
+                    .expect("to acquire write lock")
+                    .insert(schema_id, acl);
+                match self.data_storage.create_schema(schema_name) {
+                    Ok(Ok(Ok(()))) => Ok(schema_id),
+                    _ => Err(SystemError::bug_in_sql_engine(
+                        Operation::Create,
+                        Object::Schema(schema_name),
+                    )),
+                }
+            }
+            Some((_, None)) => Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Schema(schema_name),
+            )),
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Schema(schema_name),
+            )),
+        }
+    }
+
+    pub fn drop_schema<I: AsRef<Id>>(
+        &self,
+        schema_id: &I,
+        strategy: DropStrategy,
+    ) -> SystemResult<Result<(), DropSchemaError>> {
+        match self
+            .schemas
+            .write()
+            .expect("to acquire write lock")
+            .remove(schema_id.as_ref())
+        {
+            None => Ok(Err(DropSchemaError::DoesNotExist)),
+            Some(schema_name) => {
+                self.schema_tables_changed(*schema_id.as_ref());
+                match self
+                    .data_definition
+                    .drop_schema(DEFAULT_CATALOG, schema_name.as_str(), strategy)
+                {
+                    Ok(()) => match self.data_storage.drop_schema(schema_name.as_str()) {
+                        Ok(Ok(Ok(()))) => {
+                            self.schema_acls
+                                .write()
+                                .expect("to acquire write lock")
+                                .remove(schema_id.as_ref());
+                            self.persist_schema_acl(*schema_id.as_ref(), None)?;
+                            self.drop_schema_sequences(*schema_id.as_ref())?;
+                            self.drop_schema_enum_types(*schema_id.as_ref())?;
+                            self.drop_schema_functions(*schema_id.as_ref())?;
+                            self.forget_schema_partitions(*schema_id.as_ref())?;
+                            self.forget_statistics(
+                                |(statistics_schema_id, _), statistics| {
+                                    *statistics_schema_id == *schema_id.as_ref()
+                                        || statistics.table_id().0 == *schema_id.as_ref()
+                                },
+                                |(table_schema_id, _)| *table_schema_id == *schema_id.as_ref(),
+                            )?;
+                            self.column_filters
+                                .write()
+                                .expect("to acquire write lock")
+                                .retain(|(table_schema_id, _), _| *table_schema_id != *schema_id.as_ref());
+                            Ok(Ok(()))
+                        }
+                        _ => Err(SystemError::bug_in_sql_engine(
+                            Operation::Drop,
+                            Object::Schema(schema_name.as_str()),
+                        )),
+                    },
+                    Err(error) => Ok(Err(error)),
+                }
+            }
+        }
+    }
+
+    pub fn create_table(
+        &self,
+        schema_id: Id,
+        table_name: &str,
+        column_definitions: &[ColumnDefinition],
+    ) -> SystemResult<Id> {
+        match self.schemas.read().expect("to acquire read lock").get(&schema_id) {
+            Some(schema_name) => {
+                match self
+                    .data_definition
+                    .create_table(DEFAULT_CATALOG, schema_name, table_name, column_definitions)
+                {
+                    Some((_, Some((_, Some(table_id))))) => {
+                        self.tables.write().expect("to acquire write lock").insert(
+                            (schema_id, table_id),
+                            vec![schema_name.to_owned(), table_name.to_owned()],
+                        );
+                        let row_ids = Sequence::row_ids(0);
+                        self.persist_sequence(row_ids_key(&(schema_id, table_id)), row_ids.to_values())?;
+                        self.record_id_generators
+                            .write()
+                            .expect("to acquire write lock")
+                            .insert((schema_id, table_id), row_ids);
+                        for column in column_definitions {
+                            if let Some(sequence_name) = column.serial_sequence() {
+                                let options = serial_sequence_options(column.sql_type());
+                                if let Err(error) = self.create_sequence(schema_id, sequence_name, options)? {
+                                    log::warn!("sequence {:?} is not created: {:?}", sequence_name, error);
+                                }
+                            }
+                        }
+                        match self.data_storage.create_object(schema_name, table_name) {
+                            Ok(Ok(Ok(()))) => Ok(table_id),
+                            _ => Err(SystemError::bug_in_sql_engine(
+                                Operation::Create,
+                                Object::Table(schema_name, table_name),
+                            )),
+                        }
+                    }
+                    _ => Err(SystemError::bug_in_sql_engine(
+                        Operation::Create,
+                        Object::Table(schema_id.to_string().as_str(), table_name),
+                    )),
+                }
+            }
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Create,
+                Object::Table(schema_id.to_string().as_str(), table_name),
+            )),
+        }
+    }
+
+    pub fn table_columns<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<Vec<ColumnDefinition>> {
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => {
+                Ok(self
+                    .data_definition
+                    .table_columns(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str()))
+            }
+            _ => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    /// partitions of a partitioned table are dropped with it
+    pub fn drop_table<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<()> {
+        self.table_changed(*table_id.as_ref());
+        let partitioning = self
+            .partitioned_tables
+            .write()
+            .expect("to acquire write lock")
+            .remove(table_id.as_ref());
+        let mut changed = vec![];
+        if let Some(partitioning) = partitioning {
+            changed.push(*table_id.as_ref());
+            for partition in partitioning.partitions {
+                self.drop_table(&Box::new(partition.table_id))?;
+            }
+        }
+        for (parent_id, partitioning) in self
+            .partitioned_tables
+            .write()
+            .expect("to acquire write lock")
+            .iter_mut()
+        {
+            let partitions = partitioning.partitions.len();
+            partitioning
+                .partitions
+                .retain(|partition| &partition.table_id != table_id.as_ref());
+            if partitioning.partitions.len() != partitions {
+                changed.push(*parent_id);
+            }
+        }
+        self.persist_partitionings(&changed)?;
+        self.forget_statistics(
+            |_, statistics| &statistics.table_id() == table_id.as_ref(),
+            |analyzed_id| analyzed_id == table_id.as_ref(),
+        )?;
+        self.column_filters
+            .write()
+            .expect("to acquire write lock")
+            .remove(table_id.as_ref());
+        match self
+            .tables
+            .write()
+            .expect("to acquire write lock")
+            .remove(table_id.as_ref())
+        {
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Drop,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+            Some(full_name) => {
+                // data is dropped before the definition, a crash in between leaves the table empty and not lost
+                match self
+                    .data_storage
+                    .drop_object(full_name[0].as_str(), full_name[1].as_str())
+                {
+                    Ok(Ok(Ok(()))) => {}
+                    _ => {
+                        let (schema_id, table_id) = table_id.as_ref();
+                        return Err(SystemError::bug_in_sql_engine(
+                            Operation::Drop,
+                            Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                        ));
+                    }
+                }
+                let columns =
+                    self.data_definition
+                        .table_columns(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str());
+                self.data_definition
+                    .drop_table(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str());
+                self.drop_table_sequences(table_id.as_ref(), &columns)
+            }
+        }
+    }
+
+    pub fn write_into<I: AsRef<(Id, Id)>>(&self, table_id: &I, values: Vec<(Key, Values)>) -> SystemResult<usize> {
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => {
+                log::debug!("{:#?}", values);
+                match self
+                    .data_storage
+                    .write(full_name[0].as_str(), full_name[1].as_str(), values)
+                {
+                    Ok(Ok(Ok(size))) => Ok(size),
+                    _ => {
+                        let (schema_id, table_id) = table_id.as_ref();
+                        Err(SystemError::bug_in_sql_engine(
+                            Operation::Access,
+                            Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                        ))
+                    }
+                }
+            }
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    pub fn full_scan<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<ReadCursor> {
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => match self.data_storage.read(full_name[0].as_str(), full_name[1].as_str()) {
+                Ok(Ok(Ok(read))) => Ok(read),
+                _ => {
+                    let (schema_id, table_id) = table_id.as_ref();
+                    Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+            },
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    /// rows of the table with keys in the range are read by batches of `batch_size` rows in the order of their keys,
+    /// a batch is read when the previous one is taken, so rows are not read after the scan is dropped
+    pub fn scan<I: AsRef<(Id, Id)>>(&self, table_id: &I, keys: KeyRange, batch_size: usize) -> TableScan<'_> {
+        TableScan {
+            data_manager: self,
+            table_id: *table_id.as_ref(),
+            keys: Some(keys),
+            batch_size,
+            writes: Writes::new(),
+        }
+    }
+
+    /// row with the key is looked up in the storage of the table without scanning it,
+    /// a row written by the transaction of the journal replaces the stored one
+    pub fn read_row<I: AsRef<(Id, Id)>>(
+        &self,
+        journal: Option<&Journal>,
+        table_id: &I,
+        key: Key,
+    ) -> SystemResult<Option<RowResult>> {
+        if let Some(values) = journal.and_then(|journal| journal.written(table_id.as_ref(), &key)) {
+            return Ok(values.map(|values| Ok(Ok((key, values)))));
+        }
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => match self.data_storage.range(
+                full_name[0].as_str(),
+                full_name[1].as_str(),
+                (Bound::Included(key.clone()), Bound::Included(key)),
+                1,
+            ) {
+                Ok(Ok(Ok(mut read))) => Ok(read.next()),
+                _ => {
+                    let (schema_id, table_id) = table_id.as_ref();
+                    Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+            },
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    fn read_batch(
+        &self,
+        table_id: &(Id, Id),
+        keys: KeyRange,
+        limit: usize,
+    ) -> SystemResult<Result<Vec<Row>, StorageError>> {
+        if is_empty_range(&keys) {
+            return Ok(Ok(vec![]));
+        }
+        match self.tables.read().expect("to acquire read lock").get(table_id) {
+            Some(full_name) => match self
+                .data_storage
+                .range(full_name[0].as_str(), full_name[1].as_str(), keys, limit)
+            {
+                Ok(Ok(Ok(read))) => Ok(read.map(row_or_storage_error).collect()),
+                _ => {
+                    let (schema_id, table_id) = table_id;
+                    Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+            },
+            None => {
+                let (schema_id, table_id) = table_id;
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    pub fn delete_from<I: AsRef<(Id, Id)>>(&self, table_id: &I, keys: Vec<Key>) -> SystemResult<usize> {
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => match self
+                .data_storage
+                .delete(full_name[0].as_str(), full_name[1].as_str(), keys)
+            {
+                Ok(Ok(Ok(len))) => Ok(len),
+                _ => {
+                    let (schema_id, table_id) = table_id.as_ref();
+                    Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+            },
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    /// compacts the storage of the table, rows stay as they are
+    pub fn vacuum<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<()> {
+        match self.tables.read().expect("to acquire read lock").get(table_id.as_ref()) {
+            Some(full_name) => match self.data_storage.compact(full_name[0].as_str(), full_name[1].as_str()) {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => {
+                    let (schema_id, table_id) = table_id.as_ref();
+                    Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+            },
+            None => {
+                let (schema_id, table_id) = table_id.as_ref();
+                Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        }
+    }
+
+    /// sessions lock tables that they write on behalf of the owner returned here
+    pub fn lock_owner(&self) -> u64 {
+        self.lock_owners.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// waits until the table is not written by other owners or until `canceled` returns `true`, it is checked
+    /// while the owner waits. Doesn't wait if that would deadlock, as the owner that holds the table waits for this one
+    pub fn lock_table<I: AsRef<(Id, Id)>>(&self, table_id: &I, owner: u64, canceled: &dyn Fn() -> bool) -> TableLock {
+        self.table_locks.lock(*table_id.as_ref(), owner, canceled)
+    }
+
+    pub fn unlock_tables(&self, owner: u64) {
+        self.table_locks.unlock_all(owner)
+    }
+
+    /// rows are kept in the journal and are written into the table when the transaction is committed
+    pub fn journal_write<I: AsRef<(Id, Id)>>(
+        &self,
+        journal: &Journal,
+        table_id: &I,
+        values: Vec<(Key, Values)>,
+    ) -> SystemResult<usize> {
+        self.journaled_table(table_id)?;
+        for (key, _values) in values.iter() {
+            self.keep_committed(journal, table_id, key)?;
+        }
+        let size = values.len();
+        journal.write(*table_id.as_ref(), values);
+        Ok(size)
+    }
+
+    /// rows are kept deleted in the journal and are deleted from the table when the transaction is committed
+    pub fn journal_delete<I: AsRef<(Id, Id)>>(
+        &self,
+        journal: &Journal,
+        table_id: &I,
+        keys: Vec<Key>,
+    ) -> SystemResult<usize> {
+        self.journaled_table(table_id)?;
+        for key in keys.iter() {
+            self.keep_committed(journal, table_id, key)?;
+        }
+        let size = keys.len();
+        journal.delete(*table_id.as_ref(), keys);
+        Ok(size)
+    }
+
+    /// the committed row is looked up when the transaction writes its key the first time while changes are
+    /// captured, it is sent to change slots as the row before the change
+    fn keep_committed<I: AsRef<(Id, Id)>>(&self, journal: &Journal, table_id: &I, key: &Key) -> SystemResult<()> {
+        if !self.captures_changes() || journal.keeps_committed(table_id.as_ref(), key) {
+            return Ok(());
+        }
+        let values = match self.read_row(None, table_id, key.clone())? {
+            None => None,
+            Some(Ok(Ok((_key, values)))) => Some(values),
+            Some(Ok(Err(_storage_error))) => {
+                let (schema_id, table_id) = table_id.as_ref();
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ));
+            }
+            Some(Err(io_error)) => return Err(SystemError::io(io_error)),
+        };
+        journal.keep_committed(*table_id.as_ref(), key.clone(), values);
+        Ok(())
+    }
+
+    fn journaled_table<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> SystemResult<()> {
+        if self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(table_id.as_ref())
+        {
+            Ok(())
+        } else {
+            let (schema_id, table_id) = table_id.as_ref();
+            Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            ))
+        }
+    }
+
+    /// rows of the table as the transaction of the journal sees them, its writes replace committed rows
+    pub fn journaled_scan<I: AsRef<(Id, Id)>>(&self, journal: &Journal, table_id: &I) -> SystemResult<ReadCursor> {
+        let rows = self.full_scan(table_id)?;
+        Ok(Box::new(JournaledRows {
+            rows: rows.peekable(),
+            writes: journal.writes(table_id.as_ref()).into_iter().peekable(),
+        }))
+    }
+
+    /// the table created in a transaction is not seen by other sessions until the transaction is committed,
+    /// it is dropped when the transaction is rolled back
+    pub fn journal_created_table<I: AsRef<(Id, Id)>>(&self, journal: &Journal, table_id: &I) {
+        self.uncommitted_tables
+            .write()
+            .expect("to acquire write lock")
+            .insert(*table_id.as_ref());
+        journal.define(Definition::Created(*table_id.as_ref()));
+    }
+
+    /// the table and its partitions are dropped when the transaction is committed, they are not seen by the
+    /// transaction until then and are kept as they are when it is rolled back
+    pub fn journal_dropped_table<I: AsRef<(Id, Id)>>(&self, journal: &Journal, table_id: &I) {
+        if let Some(partitioning) = self.range_partitioning(table_id) {
+            for partition in partitioning.partitions() {
+                self.journal_dropped_table(journal, &Box::new(partition.table_id()));
+            }
+        }
+        journal.define(Definition::Dropped(*table_id.as_ref()));
+    }
+
+    /// rows kept in the journal are written into tables, they are compared with the committed rows that the journal
+    /// kept when they were written and sent to change slots
+    pub fn commit(&self, journal: &Journal) -> SystemResult<()> {
+        let Changes {
+            writes,
+            mut committed,
+            definitions,
+        } = journal.take_changes();
+        for definition in definitions {
+            match definition {
+                Definition::Created(table_id) => {
+                    self.uncommitted_tables
+                        .write()
+                        .expect("to acquire write lock")
+                        .remove(&table_id);
+                }
+                Definition::Dropped(table_id) => self.drop_uncommitted_table(table_id)?,
+            }
+        }
+        let mut record = CommitRecord::default();
+        let mut captured = vec![];
+        for (table_id, writes) in writes {
+            let table_id = Box::new(table_id);
+            // rows of tables dropped by the transaction are gone with them
+            let (schema_name, table_name) = match self.table_name(&table_id) {
+                Some(full_name) => full_name,
+                None => continue,
+            };
+            record.add(schema_name.clone(), table_name.clone(), &writes);
+            if self.captures_changes() {
+                captured.push((table_id, schema_name, table_name, writes));
+            }
+        }
+        self.write_commit(record)?;
+        let mut tables = vec![];
+        for (table_id, schema_name, table_name, writes) in captured {
+            let committed = committed.remove(table_id.as_ref()).unwrap_or_default();
+            let rows = row_changes(committed, writes);
+            if !rows.is_empty() {
+                tables.push(TableChanges {
+                    schema_name,
+                    table_name,
+                    columns: self.table_columns(&table_id)?,
+                    rows,
+                });
+            }
+        }
+        self.change_slots.publish(tables);
+        Ok(())
+    }
+
+    /// rows of all tables of the commit are applied at once, its record is stored before they are written
+    /// and is removed after they are flushed
+    fn write_commit(&self, record: CommitRecord) -> SystemResult<()> {
+        if record.is_empty() {
+            return Ok(());
+        }
+        let key = commit_key(self.commit_ids.fetch_add(1, Ordering::SeqCst));
+        match self
+            .data_storage
+            .write(COMMITS_SCHEMA, COMMITS_OBJECT, vec![(key.clone(), record.to_values())])
+        {
+            Ok(Ok(Ok(_))) => {}
+            _ => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT),
+                ))
+            }
+        }
+        write_commit(self.data_storage.as_ref(), &record)?;
+        match self.data_storage.delete(COMMITS_SCHEMA, COMMITS_OBJECT, vec![key]) {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(COMMITS_SCHEMA, COMMITS_OBJECT),
+            )),
+        }
+    }
+
+    /// writes have to be journaled to be captured while there is a change slot
+    pub fn captures_changes(&self) -> bool {
+        !self.change_slots.is_empty()
+    }
+
+    /// Returns `false` if there is a slot with the name
+    pub fn create_change_slot(&self, slot_name: &str) -> bool {
+        self.change_slots.create(slot_name)
+    }
+
+    /// changes that are not taken from the slot are dropped with it.
+    /// Returns `false` if there is no slot with the name
+    pub fn drop_change_slot(&self, slot_name: &str) -> bool {
+        self.change_slots.remove(slot_name)
+    }
+
+    /// changes committed since the slot was created or since they were taken the last time,
+    /// `None` if there is no slot with the name
+    pub fn take_slot_changes(&self, slot_name: &str) -> Option<Vec<Arc<CommittedChanges>>> {
+        self.change_slots.take(slot_name)
+    }
+
+    /// rows kept in the journal are dropped, tables created in the transaction are dropped
+    /// and tables dropped in it are kept
+    pub fn roll_back(&self, journal: &Journal) -> SystemResult<()> {
+        self.undo(journal.take_changes())
+    }
+
+    /// drops tables created and rows written after the savepoint.
+    /// Returns `false` if the journal has no savepoint with the name
+    pub fn roll_back_to_savepoint(&self, journal: &Journal, name: &str) -> SystemResult<bool> {
+        match journal.take_changes_since(name) {
+            Some(changes) => self.undo(changes).map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    fn undo(&self, changes: Changes) -> SystemResult<()> {
+        for definition in changes.definitions.into_iter().rev() {
+            if let Definition::Created(table_id) = definition {
+                self.drop_uncommitted_table(table_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// a table created and dropped by the same transaction is already gone when its creation is undone
+    fn drop_uncommitted_table(&self, table_id: (Id, Id)) -> SystemResult<()> {
+        let exists = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(&table_id);
+        if exists {
+            self.drop_table(&Box::new(table_id))?;
+        }
+        self.uncommitted_tables
+            .write()
+            .expect("to acquire write lock")
+            .remove(&table_id);
+        Ok(())
+    }
+
+    /// creates an empty large object with the given or the next free id.
+    /// Returns `None` if the given id is already taken
+    pub fn create_large_object(&self, large_object_id: Option<Id>) -> SystemResult<Option<Id>> {
+        loop {
+            let id = match large_object_id {
+                Some(id) => id,
+                None => self.large_object_ids.fetch_add(1, Ordering::SeqCst),
+            };
+            match self
+                .data_storage
+                .create_object(LARGE_OBJECTS_SCHEMA, id.to_string().as_str())
+            {
+                Ok(Ok(Ok(()))) => return Ok(Some(id)),
+                Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) if large_object_id.is_none() => continue,
+                Ok(Ok(Err(DefinitionError::ObjectAlreadyExists))) => return Ok(None),
+                _ => {
+                    return Err(SystemError::bug_in_sql_engine(
+                        Operation::Create,
+                        Object::Table(LARGE_OBJECTS_SCHEMA, id.to_string().as_str()),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// writes data at the offset of the large object, only the pages that
+    /// the data spans are read and rewritten. Returns `None` if the large object does not exist
+    pub fn write_large_object(&self, large_object_id: Id, offset: u64, data: &[u8]) -> SystemResult<Option<usize>> {
+        let page_size = LARGE_OBJECT_PAGE_SIZE as u64;
+        let end = offset + data.len() as u64;
+        let first_page = offset / page_size;
+        let last_page = if data.is_empty() {
+            first_page
+        } else {
+            (end - 1) / page_size
+        };
+        let mut pages = match self.large_object_pages(large_object_id)? {
+            None => return Ok(None),
+            Some(pages) => pages
+                .filter(|(page, _data)| *page >= first_page && *page <= last_page)
+                .collect::<BTreeMap<u64, Vec<u8>>>(),
+        };
+        if data.is_empty() {
+            return Ok(Some(0));
+        }
+        let mut to_write = vec![];
+        for page in first_page..=last_page {
+            let mut page_data = pages.remove(&page).unwrap_or_default();
+            let page_start = page * page_size;
+            let from = offset.max(page_start);
+            let to = end.min(page_start + page_size);
+            if page_data.len() < (to - page_start) as usize {
+                page_data.resize((to - page_start) as usize, 0);
+            }
+            page_data[(from - page_start) as usize..(to - page_start) as usize]
+                .copy_from_slice(&data[(from - offset) as usize..(to - offset) as usize]);
+            to_write.push((
+                Binary::with_data(page.to_be_bytes().to_vec()),
+                Binary::with_data(page_data),
+            ));
+        }
+        match self
+            .data_storage
+            .write(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str(), to_write)
+        {
+            Ok(Ok(Ok(_pages))) => Ok(Some(data.len())),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    /// reads `length` bytes, or everything till the end if it is not specified, starting from the offset
+    /// of the large object. Returns `None` if the large object does not exist
+    pub fn read_large_object(
+        &self,
+        large_object_id: Id,
+        offset: u64,
+        length: Option<u64>,
+    ) -> SystemResult<Option<Vec<u8>>> {
+        let page_size = LARGE_OBJECT_PAGE_SIZE as u64;
+        let first_page = offset / page_size;
+        let last_page = length.map(|length| (offset + length) / page_size);
+        let mut object_size = 0;
+        let mut pages = BTreeMap::new();
+        match self.large_object_pages(large_object_id)? {
+            None => return Ok(None),
+            Some(all_pages) => {
+                for (page, data) in all_pages {
+                    object_size = object_size.max(page * page_size + data.len() as u64);
+                    if page >= first_page && last_page.map(|last_page| page <= last_page).unwrap_or(true) {
+                        pages.insert(page, data);
+                    }
+                }
+            }
+        }
+        let end = match length {
+            Some(length) => object_size.min(offset + length),
+            None => object_size,
+        };
+        if offset >= end {
+            return Ok(Some(vec![]));
+        }
+        let mut result = vec![0; (end - offset) as usize];
+        for (page, data) in pages {
+            let page_start = page * page_size;
+            let from = offset.max(page_start);
+            let to = end.min(page_start + data.len() as u64);
+            if from < to {
+                result[(from - offset) as usize..(to - offset) as usize]
+                    .copy_from_slice(&data[(from - page_start) as usize..(to - page_start) as usize]);
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// removes the large object, returns `false` if it does not exist
+    pub fn unlink_large_object(&self, large_object_id: Id) -> SystemResult<bool> {
+        match self
+            .data_storage
+            .drop_object(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str())
+        {
+            Ok(Ok(Ok(()))) => Ok(true),
+            Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))) => Ok(false),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Drop,
+                Object::Table(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    fn large_object_pages(&self, large_object_id: Id) -> SystemResult<Option<impl Iterator<Item = (u64, Vec<u8>)>>> {
+        match self
+            .data_storage
+            .read(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str())
+        {
+            Ok(Ok(Ok(read))) => Ok(Some(read.filter_map(|row| match row {
+                Ok(Ok((key, data))) => match key.to_bytes().try_into() {
+                    Ok(page) => Some((u64::from_be_bytes(page), data.to_bytes().to_vec())),
+                    Err(_) => None,
+                },
+                _ => None,
+            }))),
+            Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))) => Ok(None),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(LARGE_OBJECTS_SCHEMA, large_object_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    pub fn schema_exists<S: AsRef<str>>(&self, schema_name: &S) -> FullSchemaId {
+        self.data_definition
+            .schema_exists(DEFAULT_CATALOG, schema_name.as_ref())
+            .and_then(|(_catalog, schema)| schema)
+    }
+
+    pub fn table_exists<S: AsRef<str>>(&self, schema_name: &S, table_name: &S) -> FullTableId {
+        self.data_definition
+            .table_exists(DEFAULT_CATALOG, schema_name.as_ref(), table_name.as_ref())
+            .and_then(|(_catalog, full_table)| full_table)
+    }
+
+    /// the table as the transaction of the journal sees it, tables dropped by the transaction and
+    /// tables created by other transactions that are not committed yet do not exist
+    pub fn table_exists_in<S: AsRef<str>>(
+        &self,
+        journal: Option<&Journal>,
+        schema_name: &S,
+        table_name: &S,
+    ) -> FullTableId {
+        match self.table_exists(schema_name, table_name) {
+            Some((schema_id, Some(table_id))) => {
+                let full_table_id = (schema_id, table_id);
+                let seen = match journal {
+                    Some(journal) if journal.drops(&full_table_id) => false,
+                    Some(journal) if journal.creates(&full_table_id) => true,
+                    _ => !self
+                        .uncommitted_tables
+                        .read()
+                        .expect("to acquire read lock")
+                        .contains(&full_table_id),
+                };
+                Some((schema_id, if seen { Some(table_id) } else { None }))
+            }
+            full_table_id => full_table_id,
+        }
+    }
+
+    pub fn schema_names(&self) -> Vec<String> {
+        let mut names = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .values()
+            .cloned()
+            .collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+
+    pub fn table_names(&self) -> Vec<(String, String)> {
+        let mut names = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .values()
+            .map(|full_name| (full_name[0].clone(), full_name[1].clone()))
+            .collect::<Vec<(String, String)>>();
+        names.sort();
+        names
+    }
+
+    pub fn table_ids(&self) -> Vec<(Id, Id)> {
+        let mut ids = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .keys()
+            .cloned()
+            .collect::<Vec<(Id, Id)>>();
+        ids.sort();
+        ids
+    }
+
+    pub fn table_name<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Option<(String, String)> {
+        self.tables
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .map(|full_name| (full_name[0].clone(), full_name[1].clone()))
+    }
+
+    pub fn schema_owner(&self, schema_id: Id) -> Option<String> {
+        self.schema_acls
+            .read()
+            .expect("to acquire read lock")
+            .get(&schema_id)
+            .map(|acl| acl.owner.clone())
+    }
+
+    pub fn alter_schema_owner(&self, schema_id: Id, new_owner: &str) -> SystemResult<()> {
+        match self
+            .schema_acls
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&schema_id)
+        {
+            Some(acl) => {
+                acl.owner = new_owner.to_owned();
+                self.schema_tables_changed(schema_id);
+                self.persist_schema_acl(schema_id, Some(acl))
+            }
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Schema(schema_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    pub fn grant_schema_privileges(
+        &self,
+        schema_id: Id,
+        role: &str,
+        privileges: &[SchemaPrivilege],
+    ) -> SystemResult<()> {
+        match self
+            .schema_acls
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&schema_id)
+        {
+            Some(acl) => {
+                acl.grants
+                    .entry(role.to_owned())
+                    .or_default()
+                    .extend(privileges.iter().copied());
+                self.schema_tables_changed(schema_id);
+                self.persist_schema_acl(schema_id, Some(acl))
+            }
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Schema(schema_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    pub fn revoke_schema_privileges(
+        &self,
+        schema_id: Id,
+        role: &str,
+        privileges: &[SchemaPrivilege],
+    ) -> SystemResult<()> {
+        match self
+            .schema_acls
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&schema_id)
+        {
+            Some(acl) => {
+                if let Some(granted) = acl.grants.get_mut(role) {
+                    for privilege in privileges {
+                        granted.remove(privilege);
+                    }
+                }
+                self.schema_tables_changed(schema_id);
+                self.persist_schema_acl(schema_id, Some(acl))
+            }
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Schema(schema_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    /// owner and grants of the schema are written, `None` deletes them with the schema
+    fn persist_schema_acl(&self, schema_id: Id, acl: Option<&SchemaAcl>) -> SystemResult<()> {
+        let key = schema_acl_key(schema_id);
+        let persisted = match acl {
+            Some(acl) => self
+                .data_storage
+                .write(ACLS_SCHEMA, ACLS_OBJECT, vec![(key, acl.to_values())]),
+            None => self.data_storage.delete(ACLS_SCHEMA, ACLS_OBJECT, vec![key]),
+        };
+        match persisted {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(ACLS_SCHEMA, ACLS_OBJECT),
+            )),
+        }
+    }
+
+    /// superuser and schema owner implicitly hold every privilege on a schema
+    pub fn has_schema_privilege(&self, schema_id: Id, role: &str, privilege: SchemaPrivilege) -> bool {
+        if role == SUPERUSER {
+            return true;
+        }
+        match self.schema_acls.read().expect("to acquire read lock").get(&schema_id) {
+            Some(acl) => {
+                acl.owner == role
+                    || acl
+                        .grants
+                        .get(role)
+                        .map(|granted| granted.contains(&privilege))
+                        .unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+
+    /// partitioning is persisted with ids and bounds of partitions and loaded when the storage is opened
+    pub fn partition_by_range<I: AsRef<(Id, Id)>>(&self, table_id: &I, column_name: &str) -> SystemResult<()> {
+        self.table_changed(*table_id.as_ref());
+        self.partitioned_tables.write().expect("to acquire write lock").insert(
+            *table_id.as_ref(),
+            RangePartitioning {
+                column_name: column_name.to_owned(),
+                partitions: vec![],
+            },
+        );
+        self.persist_partitionings(&[*table_id.as_ref()])
+    }
+
+    pub fn attach_range_partition<I: AsRef<(Id, Id)>>(
+        &self,
+        table_id: &I,
+        partition: RangePartition,
+    ) -> SystemResult<()> {
+        let attached = match self
+            .partitioned_tables
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(table_id.as_ref())
+        {
+            Some(partitioning) => {
+                partitioning.partitions.push(partition);
+                true
+            }
+            None => false,
+        };
+        if attached {
+            self.table_changed(*table_id.as_ref());
+            self.persist_partitionings(&[*table_id.as_ref()])
+        } else {
+            let (schema_id, table_id) = table_id.as_ref();
+            Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            ))
+        }
+    }
+
+    pub fn range_partitioning<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Option<RangePartitioning> {
+        self.partitioned_tables
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .cloned()
+    }
+
+    /// partition key column and bounds of a table that is a partition
+    pub fn partition_bounds<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Option<(String, RangePartition)> {
+        self.partitioned_tables
+            .read()
+            .expect("to acquire read lock")
+            .values()
+            .find_map(|partitioning| {
+                partitioning
+                    .partitions
+                    .iter()
+                    .find(|partition| &partition.table_id == table_id.as_ref())
+                    .map(|partition| (partitioning.column_name.clone(), partition.clone()))
+            })
+    }
+    /// statistics are persisted as they were built and loaded when the storage is opened,
+    /// `false` if statistics with the name already exist
+    pub fn create_statistics(
+        &self,
+        schema_id: Id,
+        statistics_name: &str,
+        statistics: ExtendedStatistics,
+    ) -> SystemResult<bool> {
+        let table_id = statistics.table_id();
+        let key = (schema_id, statistics_name.to_owned());
+        {
+            let mut all_statistics = self.statistics.write().expect("to acquire write lock");
+            if all_statistics.contains_key(&key) {
+                return Ok(false);
+            }
+            all_statistics.insert(key.clone(), statistics);
+        }
+        self.table_changed(table_id);
+        self.persist_statistics(&[key])?;
+        Ok(true)
+    }
+
+    pub fn statistics_exists(&self, schema_id: Id, statistics_name: &str) -> bool {
+        self.statistics
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(&(schema_id, statistics_name.to_owned()))
+    }
+
+    pub fn drop_statistics(&self, schema_id: Id, statistics_name: &str) -> SystemResult<bool> {
+        let key = (schema_id, statistics_name.to_owned());
+        let dropped = self.statistics.write().expect("to acquire write lock").remove(&key);
+        match dropped {
+            Some(statistics) => {
+                self.table_changed(statistics.table_id());
+                self.persist_statistics(&[key])?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// statistics of the table by `(schema id, statistics name)`
+    pub fn table_statistics<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Vec<((Id, String), ExtendedStatistics)> {
+        let mut statistics = self
+            .statistics
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .filter(|(_, statistics)| &statistics.table_id() == table_id.as_ref())
+            .map(|(name, statistics)| (name.clone(), statistics.clone()))
+            .collect::<Vec<_>>();
+        statistics.sort_by(|(left, _), (right, _)| left.cmp(right));
+        statistics
+    }
+
+    /// rebuilt statistics replace the existing ones, `false` if they were dropped in the meantime
+    pub fn update_statistics(
+        &self,
+        schema_id: Id,
+        statistics_name: &str,
+        statistics: ExtendedStatistics,
+    ) -> SystemResult<bool> {
+        let table_id = statistics.table_id();
+        let key = (schema_id, statistics_name.to_owned());
+        let updated = match self.statistics.write().expect("to acquire write lock").get_mut(&key) {
+            Some(existing) => {
+                *existing = statistics;
+                true
+            }
+            None => false,
+        };
+        if updated {
+            self.table_changed(table_id);
+            self.persist_statistics(&[key])?;
+        }
+        Ok(updated)
+    }
+
+    /// statistics of rows that `ANALYZE` gathered last time, `None` for tables that were never analyzed
+    pub fn analyzed<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Option<TableStatistics> {
+        self.row_statistics
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .cloned()
+    }
+
+    /// statistics of rows are persisted as extended statistics are
+    pub fn set_analyzed<I: AsRef<(Id, Id)>>(&self, table_id: &I, statistics: TableStatistics) -> SystemResult<()> {
+        self.row_statistics
+            .write()
+            .expect("to acquire write lock")
+            .insert(*table_id.as_ref(), statistics);
+        self.table_changed(*table_id.as_ref());
+        self.persist_table_statistics(&[*table_id.as_ref()])
+    }
+
+    /// extended statistics and statistics of analyzed tables that match the predicates are dropped
+    fn forget_statistics<E, T>(&self, extended: E, analyzed: T) -> SystemResult<()>
+    where
+        E: Fn(&(Id, String), &ExtendedStatistics) -> bool,
+        T: Fn(&(Id, Id)) -> bool,
+    {
+        let mut keys = vec![];
+        self.statistics
+            .write()
+            .expect("to acquire write lock")
+            .retain(|key, statistics| {
+                let forgotten = extended(key, statistics);
+                if forgotten {
+                    keys.push(key.clone());
+                }
+                !forgotten
+            });
+        let mut table_ids = vec![];
+        self.row_statistics
+            .write()
+            .expect("to acquire write lock")
+            .retain(|table_id, _| {
+                let forgotten = analyzed(table_id);
+                if forgotten {
+                    table_ids.push(*table_id);
+                }
+                !forgotten
+            });
+        self.persist_statistics(&keys)?;
+        self.persist_table_statistics(&table_ids)
+    }
+
+    /// statistics with the names are written, the ones that were dropped are deleted
+    fn persist_statistics(&self, keys: &[(Id, String)]) -> SystemResult<()> {
+        let (written, deleted) = {
+            let all_statistics = self.statistics.read().expect("to acquire read lock");
+            let mut written = vec![];
+            let mut deleted = vec![];
+            for key @ (schema_id, statistics_name) in keys {
+                let stored_key = statistics_key(*schema_id, statistics_name);
+                match all_statistics.get(key) {
+                    Some(statistics) => written.push((stored_key, statistics::to_values(statistics))),
+                    None => deleted.push(stored_key),
+                }
+            }
+            (written, deleted)
+        };
+        self.persist_statistics_rows(EXTENDED_STATISTICS_OBJECT, written, deleted)
+    }
+
+    /// statistics of the analyzed tables are written, the ones of dropped tables are deleted
+    fn persist_table_statistics(&self, table_ids: &[(Id, Id)]) -> SystemResult<()> {
+        let (written, deleted) = {
+            let row_statistics = self.row_statistics.read().expect("to acquire read lock");
+            let mut written = vec![];
+            let mut deleted = vec![];
+            for table_id in table_ids {
+                match row_statistics.get(table_id) {
+                    Some(statistics) => written.push((row_ids_key(table_id), statistics::to_values(statistics))),
+                    None => deleted.push(row_ids_key(table_id)),
+                }
+            }
+            (written, deleted)
+        };
+        self.persist_statistics_rows(TABLE_STATISTICS_OBJECT, written, deleted)
+    }
+
+    fn persist_statistics_rows(
+        &self,
+        object_name: ObjectName,
+        written: Vec<Row>,
+        deleted: Vec<Key>,
+    ) -> SystemResult<()> {
+        let persisted = match self.data_storage.write(STATISTICS_SCHEMA, object_name, written) {
+            Ok(Ok(Ok(_))) => matches!(
+                self.data_storage.delete(STATISTICS_SCHEMA, object_name, deleted),
+                Ok(Ok(Ok(_)))
+            ),
+            _ => false,
+        };
+        if persisted {
+            Ok(())
+        } else {
+            Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(STATISTICS_SCHEMA, object_name),
+            ))
+        }
+    }
+
+    /// version of what plans of statements over the table are made from, it changes when the table is dropped,
+    /// partitioned or analyzed, when its statistics are created or dropped and when privileges on its schema change
+    pub fn table_version<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> u64 {
+        self.table_versions
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// statements that filtered rows of a table by a column are counted in memory for index advice
+    pub fn record_column_filter<I: AsRef<(Id, Id)>>(&self, table_id: &I, column_name: &str) {
+        *self
+            .column_filters
+            .write()
+            .expect("to acquire write lock")
+            .entry(*table_id.as_ref())
+            .or_default()
+            .entry(column_name.to_owned())
+            .or_default() += 1;
+    }
+
+    /// filtered columns of the table with number of statements that filtered by them, most filtered first
+    pub fn column_filters<I: AsRef<(Id, Id)>>(&self, table_id: &I) -> Vec<(String, u64)> {
+        let mut filters = self
+            .column_filters
+            .read()
+            .expect("to acquire read lock")
+            .get(table_id.as_ref())
+            .map(|filters| filters.iter().map(|(name, count)| (name.clone(), *count)).collect())
+            .unwrap_or_else(Vec::new);
+        filters.sort_by(|(left_name, left_count), (right_name, right_count)| {
+            right_count.cmp(left_count).then_with(|| left_name.cmp(right_name))
+        });
+        filters
     }
 }
 
@@ -0,0 +1,169 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use sql_model::Id;
+
+/// tables that are written or read by lock owners, an owner waits until the table is unlocked by the others.
+/// A table is written by one owner at a time and is read by any number of owners while nobody writes it
+#[derive(Debug, Default)]
+pub(crate) struct TableLocks {
+    graph: Mutex<WaitsFor>,
+    unlocked: Condvar,
+}
+
+/// waiting owners check this often whether they are canceled
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// outcome of waiting for a table
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TableLock {
+    Acquired,
+    /// the owner that holds the table waits for the one that asks for it
+    Deadlock,
+    /// the owner stopped waiting as it was canceled
+    Canceled,
+}
+
+/// holders of tables and tables that owners wait for, waiting owners are kept with whether they wait to read
+#[derive(Debug, Default)]
+struct WaitsFor {
+    holders: HashMap<(Id, Id), u64>,
+    readers: HashMap<(Id, Id), HashSet<u64>>,
+    waiting: HashMap<u64, ((Id, Id), bool)>,
+}
+
+impl WaitsFor {
+    /// owners that hold the table, apart from the given one
+    fn blockers(&self, table_id: &(Id, Id), owner: u64, shared: bool) -> Vec<u64> {
+        let mut blockers = self.holders.get(table_id).copied().into_iter().collect::<Vec<u64>>();
+        if !shared {
+            blockers.extend(self.readers.get(table_id).into_iter().flatten().copied());
+        }
+        blockers.retain(|holder| *holder != owner);
+        blockers
+    }
+
+    /// follows owners that wait for tables held by other waiting owners
+    fn waits_for(&self, owner: u64, other: u64) -> bool {
+        let mut visited = HashSet::new();
+        let mut next = vec![owner];
+        while let Some(current) = next.pop() {
+            if current == other {
+                return true;
+            }
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some((table_id, shared)) = self.waiting.get(&current) {
+                next.extend(self.blockers(table_id, current, *shared));
+            }
+        }
+        false
+    }
+}
+
+impl TableLocks {
+    /// blocks until no other owner holds the table or until the owner is canceled,
+    /// the owner can lock a table it holds again. Doesn't wait if a holder of the table waits for the owner
+    pub(crate) fn lock(&self, table_id: (Id, Id), owner: u64, canceled: &dyn Fn() -> bool) -> TableLock {
+        match self.wait(table_id, owner, false, canceled) {
+            Ok(mut graph) => {
+                graph.holders.insert(table_id, owner);
+                TableLock::Acquired
+            }
+            Err(failure) => failure,
+        }
+    }
+
+    /// blocks until no other owner writes the table or until the owner is canceled.
+    /// Doesn't wait if the writer of the table waits for the owner
+    pub(crate) fn lock_shared(&self, table_id: (Id, Id), owner: u64, canceled: &dyn Fn() -> bool) -> TableLock {
+        match self.wait(table_id, owner, true, canceled) {
+            Ok(mut graph) => {
+                graph.readers.entry(table_id).or_default().insert(owner);
+                TableLock::Acquired
+            }
+            Err(failure) => failure,
+        }
+    }
+
+    fn wait(
+        &self,
+        table_id: (Id, Id),
+        owner: u64,
+        shared: bool,
+        canceled: &dyn Fn() -> bool,
+    ) -> Result<MutexGuard<'_, WaitsFor>, TableLock> {
+        let mut graph = self.graph.lock().expect("to acquire lock");
+        loop {
+            let blockers = graph.blockers(&table_id, owner, shared);
+            if blockers.is_empty() {
+                break;
+            }
+            if blockers.iter().any(|holder| graph.waits_for(*holder, owner)) {
+                graph.waiting.remove(&owner);
+                return Err(TableLock::Deadlock);
+            }
+            if canceled() {
+                graph.waiting.remove(&owner);
+                return Err(TableLock::Canceled);
+            }
+            graph.waiting.insert(owner, (table_id, shared));
+            graph = self
+                .unlocked
+                .wait_timeout(graph, WAIT_INTERVAL)
+                .expect("to acquire lock")
+                .0;
+        }
+        graph.waiting.remove(&owner);
+        Ok(graph)
+    }
+
+    /// the table is unlocked before other tables of the owner
+    pub(crate) fn unlock(&self, table_id: &(Id, Id), owner: u64) {
+        let mut graph = self.graph.lock().expect("to acquire lock");
+        let written = graph.holders.get(table_id) == Some(&owner);
+        if written {
+            graph.holders.remove(table_id);
+        }
+        let read = graph
+            .readers
+            .get_mut(table_id)
+            .map(|readers| readers.remove(&owner))
+            .unwrap_or(false);
+        if written || read {
+            self.unlocked.notify_all();
+        }
+    }
+
+    pub(crate) fn unlock_all(&self, owner: u64) {
+        let mut graph = self.graph.lock().expect("to acquire lock");
+        let held = graph.holders.len();
+        graph.holders.retain(|_, holder| *holder != owner);
+        let mut unlocked = graph.holders.len() != held;
+        for readers in graph.readers.values_mut() {
+            unlocked |= readers.remove(&owner);
+        }
+        graph.readers.retain(|_, readers| !readers.is_empty());
+        if unlocked {
+            self.unlocked.notify_all();
+        }
+    }
+}
@@ -24,7 +24,8 @@ use sled::{Db as Schema, DiskPtr, Error as SledError, IVec, Tree};
 use representation::Binary;
 
 use crate::{
-    Database, DefinitionError, InitStatus, Key, ObjectName, ReadCursor, RowResult, SchemaName, StorageError, Values,
+    Database, DefinitionError, InitStatus, Key, KeyRange, ObjectName, ReadCursor, RowResult, SchemaName, StorageError,
+    Values,
 };
 
 pub struct PersistentDatabase {
@@ -40,35 +41,6 @@ impl PersistentDatabase {
         }
     }
 
-    pub fn init(&self, schema_name: SchemaName) -> io::Result<Result<InitStatus, StorageError>> {
-        let path_to_schema = PathBuf::from(&self.path).join(schema_name);
-        log::info!("path to schema {:?}", path_to_schema);
-        self.open_database(path_to_schema).map(|storage| {
-            storage.map(|schema| {
-                let recovered = schema.was_recovered();
-                self.schemas
-                    .write()
-                    .expect("to acquire write lock")
-                    .insert(schema_name.to_owned(), Arc::new(schema));
-                log::debug!("schemas after initialization {:?}", self.schemas);
-                if recovered {
-                    InitStatus::Loaded
-                } else {
-                    InitStatus::Created
-                }
-            })
-        })
-    }
-
-    pub fn open_object(&self, schema_name: SchemaName, object_name: ObjectName) {
-        if let Some(schema) = self.schemas.read().expect("to acquire write lock").get(schema_name) {
-            self.open_tree(schema.clone(), object_name)
-                .expect("no io error")
-                .expect("no platform error")
-                .expect("no definition error");
-        }
-    }
-
     fn open_database(&self, path_to_schema: PathBuf) -> io::Result<Result<Schema, StorageError>> {
         match self.open_database_with_failpoint(path_to_schema) {
             Ok(schema) => Ok(Ok(schema)),
@@ -191,6 +163,17 @@ impl PersistentDatabase {
         ));
         Box::new(object.iter())
     }
+
+    fn range_over_tree_with_failpoint(
+        &self,
+        object: Tree,
+        keys: KeyRange,
+    ) -> Box<dyn Iterator<Item = sled::Result<(IVec, IVec)>>> {
+        fail::fail_point!("sled-fail-range-over-tree", |kind| Box::new(
+            vec![Err(sled_error(kind))].into_iter()
+        ));
+        Box::new(object.range(keys))
+    }
     fn remove_fro_tree_with_failpoint(&self, object: &Tree, key: Binary) -> Result<Option<IVec>, SledError> {
         fail::fail_point!("sled-fail-to-remove-from-tree", |kind| Err(sled_error(kind)));
         object.remove(key.to_bytes())
@@ -202,6 +185,35 @@ impl PersistentDatabase {
 }
 
 impl Database for PersistentDatabase {
+    fn init(&self, schema_name: SchemaName) -> io::Result<Result<InitStatus, StorageError>> {
+        let path_to_schema = PathBuf::from(&self.path).join(schema_name);
+        log::info!("path to schema {:?}", path_to_schema);
+        self.open_database(path_to_schema).map(|storage| {
+            storage.map(|schema| {
+                let recovered = schema.was_recovered();
+                self.schemas
+                    .write()
+                    .expect("to acquire write lock")
+                    .insert(schema_name.to_owned(), Arc::new(schema));
+                log::debug!("schemas after initialization {:?}", self.schemas);
+                if recovered {
+                    InitStatus::Loaded
+                } else {
+                    InitStatus::Created
+                }
+            })
+        })
+    }
+
+    fn open_object(&self, schema_name: SchemaName, object_name: ObjectName) {
+        if let Some(schema) = self.schemas.read().expect("to acquire write lock").get(schema_name) {
+            self.open_tree(schema.clone(), object_name)
+                .expect("no io error")
+                .expect("no platform error")
+                .expect("no definition error");
+        }
+    }
+
     fn create_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
         if self
             .schemas
@@ -318,21 +330,9 @@ impl Database for PersistentDatabase {
             Some(schema) => {
                 if schema.tree_names().contains(&(object_name.into())) {
                     match self.open_tree(schema.clone(), object_name) {
-                        Ok(Ok(Ok(object))) => Ok(Ok(Ok(Box::new(self.iterator_over_tree_with_failpoint(object).map(
-                            |item| match item {
-                                Ok((key, values)) => Ok(Ok((
-                                    Binary::with_data(key.to_vec()),
-                                    Binary::with_data(values.to_vec()),
-                                ))),
-                                Err(error) => match error {
-                                    SledError::Io(io_error) => Err(io_error),
-                                    SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
-                                    SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
-                                    SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
-                                    SledError::CollectionNotFound(_) => Ok(Err(StorageError::Storage)),
-                                },
-                            },
-                        ))))),
+                        Ok(Ok(Ok(object))) => Ok(Ok(Ok(Box::new(
+                            self.iterator_over_tree_with_failpoint(object).map(sled_row),
+                        )))),
                         otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| self.empty_iterator()))),
                     }
                 } else {
@@ -351,6 +351,32 @@ impl Database for PersistentDatabase {
         }
     }
 
+    fn range(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        keys: KeyRange,
+        limit: usize,
+    ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => {
+                if schema.tree_names().contains(&(object_name.into())) {
+                    match self.open_tree(schema.clone(), object_name) {
+                        Ok(Ok(Ok(object))) => Ok(Ok(Ok(Box::new(
+                            self.range_over_tree_with_failpoint(object, keys)
+                                .take(limit)
+                                .map(sled_row),
+                        )))),
+                        otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| self.empty_iterator()))),
+                    }
+                } else {
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                }
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
     fn delete(
         &self,
         schema_name: SchemaName,
@@ -388,6 +414,45 @@ impl Database for PersistentDatabase {
             None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
         }
     }
+
+    /// sled cleans segments of flushed trees and reuses space of the ones that are left without live pages
+    fn compact(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => {
+                if schema.tree_names().contains(&(object_name.into())) {
+                    match self.open_tree(schema.clone(), object_name) {
+                        Ok(Ok(Ok(object))) => self
+                            .tree_flush(object, 0)
+                            .map(|io| io.map(|storage| storage.map(|_flushed| ()))),
+                        otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| ()))),
+                    }
+                } else {
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                }
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+}
+
+fn sled_row(item: sled::Result<(IVec, IVec)>) -> RowResult {
+    match item {
+        Ok((key, values)) => Ok(Ok((
+            Binary::with_data(key.to_vec()),
+            Binary::with_data(values.to_vec()),
+        ))),
+        Err(error) => match error {
+            SledError::Io(io_error) => Err(io_error),
+            SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
+            SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
+            SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
+            SledError::CollectionNotFound(_) => Ok(Err(StorageError::Storage)),
+        },
+    }
 }
 
 fn sled_error(kind: Option<String>) -> SledError {
@@ -0,0 +1,323 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, io, ops::Bound, path::PathBuf, sync::RwLock};
+
+use rocksdb::{Direction, Error as RocksError, IteratorMode, Options, WriteBatch, WriteOptions, DB};
+
+use representation::Binary;
+
+use crate::{
+    Database, DefinitionError, InitStatus, Key, KeyRange, ObjectName, ReadCursor, SchemaName, StorageError, Values,
+};
+
+/// every schema is a RocksDB database under the path and every object is a column family of it
+pub struct RocksDatabase {
+    path: PathBuf,
+    schemas: RwLock<HashMap<String, DB>>,
+}
+
+impl RocksDatabase {
+    pub fn new(path: PathBuf) -> RocksDatabase {
+        RocksDatabase {
+            path,
+            schemas: RwLock::default(),
+        }
+    }
+
+    fn options() -> Options {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        options
+    }
+
+    /// writes are synced to the log before they are acknowledged, as sled flushes its trees
+    fn write_options() -> WriteOptions {
+        let mut options = WriteOptions::default();
+        options.set_sync(true);
+        options
+    }
+
+    fn open_database(&self, path_to_schema: PathBuf) -> Result<DB, RocksError> {
+        let options = Self::options();
+        match DB::list_cf(&options, &path_to_schema) {
+            Ok(column_families) => DB::open_cf(&options, path_to_schema, column_families),
+            Err(_) => DB::open(&options, path_to_schema),
+        }
+    }
+}
+
+impl Database for RocksDatabase {
+    fn init(&self, schema_name: SchemaName) -> io::Result<Result<InitStatus, StorageError>> {
+        let path_to_schema = PathBuf::from(&self.path).join(schema_name);
+        log::info!("path to schema {:?}", path_to_schema);
+        let existed = path_to_schema.exists();
+        match self.open_database(path_to_schema) {
+            Ok(schema) => {
+                self.schemas
+                    .write()
+                    .expect("to acquire write lock")
+                    .insert(schema_name.to_owned(), schema);
+                if existed {
+                    Ok(Ok(InitStatus::Loaded))
+                } else {
+                    Ok(Ok(InitStatus::Created))
+                }
+            }
+            Err(error) => Ok(Err(storage_error(error))),
+        }
+    }
+
+    fn open_object(&self, _schema_name: SchemaName, _object_name: ObjectName) {}
+
+    fn create_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        if self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(schema_name)
+        {
+            Ok(Ok(Err(DefinitionError::SchemaAlreadyExists)))
+        } else {
+            let path_to_schema = PathBuf::from(&self.path).join(schema_name);
+            log::info!("path to schema {:?}", path_to_schema);
+            match self.open_database(path_to_schema) {
+                Ok(schema) => {
+                    self.schemas
+                        .write()
+                        .expect("to acquire write lock")
+                        .insert(schema_name.to_owned(), schema);
+                    Ok(Ok(Ok(())))
+                }
+                Err(error) => Ok(Err(storage_error(error))),
+            }
+        }
+    }
+
+    fn drop_schema(&self, schema_name: SchemaName) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self.schemas.write().expect("to acquire write lock").remove(schema_name) {
+            Some(schema) => {
+                drop(schema);
+                match DB::destroy(&Options::default(), PathBuf::from(&self.path).join(schema_name)) {
+                    Ok(()) => Ok(Ok(Ok(()))),
+                    Err(error) => Ok(Err(StorageError::CascadeIo(vec![error.into_string()]))),
+                }
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn create_object(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self
+            .schemas
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(schema_name)
+        {
+            Some(schema) => {
+                if schema.cf_handle(object_name).is_some() {
+                    Ok(Ok(Err(DefinitionError::ObjectAlreadyExists)))
+                } else {
+                    match schema.create_cf(object_name, &Self::options()) {
+                        Ok(()) => Ok(Ok(Ok(()))),
+                        Err(error) => Ok(Err(storage_error(error))),
+                    }
+                }
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn drop_object(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self
+            .schemas
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(schema_name)
+        {
+            Some(schema) => {
+                if schema.cf_handle(object_name).is_none() {
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                } else {
+                    match schema.drop_cf(object_name) {
+                        Ok(()) => Ok(Ok(Ok(()))),
+                        Err(error) => Ok(Err(storage_error(error))),
+                    }
+                }
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn write(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        rows: Vec<(Key, Values)>,
+    ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.cf_handle(object_name) {
+                Some(object) => {
+                    let mut batch = WriteBatch::default();
+                    for (key, values) in rows.iter() {
+                        batch.put_cf(object, key.to_bytes(), values.to_bytes());
+                    }
+                    match schema.write_opt(batch, &Self::write_options()) {
+                        Ok(()) => Ok(Ok(Ok(rows.len()))),
+                        Err(error) => Ok(Err(storage_error(error))),
+                    }
+                }
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn read(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.cf_handle(object_name) {
+                // iterator borrows the database that is guarded by the lock, rows are collected before it is released
+                Some(object) => Ok(Ok(Ok(Box::new(
+                    schema
+                        .iterator_cf(object, IteratorMode::Start)
+                        .map(|(key, values)| {
+                            Ok(Ok((
+                                Binary::with_data(key.to_vec()),
+                                Binary::with_data(values.to_vec()),
+                            )))
+                        })
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                )))),
+                None => {
+                    log::error!(
+                        "No namespace with {:?} doesn't contain {:?} object",
+                        schema_name,
+                        object_name
+                    );
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                }
+            },
+            None => {
+                log::error!("No schema with {:?} name found", schema_name);
+                Ok(Ok(Err(DefinitionError::SchemaDoesNotExist)))
+            }
+        }
+    }
+
+    fn range(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        keys: KeyRange,
+        limit: usize,
+    ) -> io::Result<Result<Result<ReadCursor, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.cf_handle(object_name) {
+                Some(object) => {
+                    let (start, end) = keys;
+                    let mode = match &start {
+                        Bound::Included(key) | Bound::Excluded(key) => {
+                            IteratorMode::From(key.to_bytes(), Direction::Forward)
+                        }
+                        Bound::Unbounded => IteratorMode::Start,
+                    };
+                    Ok(Ok(Ok(Box::new(
+                        schema
+                            .iterator_cf(object, mode)
+                            .skip_while(|(key, _values)| match &start {
+                                Bound::Excluded(start) => start.to_bytes() == &key[..],
+                                _ => false,
+                            })
+                            .take_while(|(key, _values)| match &end {
+                                Bound::Included(end) => &key[..] <= end.to_bytes(),
+                                Bound::Excluded(end) => &key[..] < end.to_bytes(),
+                                Bound::Unbounded => true,
+                            })
+                            .take(limit)
+                            .map(|(key, values)| {
+                                Ok(Ok((
+                                    Binary::with_data(key.to_vec()),
+                                    Binary::with_data(values.to_vec()),
+                                )))
+                            })
+                            .collect::<Vec<_>>()
+                            .into_iter(),
+                    ))))
+                }
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn delete(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+        keys: Vec<Key>,
+    ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.cf_handle(object_name) {
+                Some(object) => {
+                    let mut batch = WriteBatch::default();
+                    for key in keys.iter() {
+                        batch.delete_cf(object, key.to_bytes());
+                    }
+                    match schema.write_opt(batch, &Self::write_options()) {
+                        Ok(()) => Ok(Ok(Ok(keys.len()))),
+                        Err(error) => Ok(Err(storage_error(error))),
+                    }
+                }
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+
+    fn compact(
+        &self,
+        schema_name: SchemaName,
+        object_name: ObjectName,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.cf_handle(object_name) {
+                Some(object) => {
+                    schema.compact_range_cf::<&[u8], &[u8]>(object, None, None);
+                    Ok(Ok(Ok(())))
+                }
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+        }
+    }
+}
+
+fn storage_error(error: RocksError) -> StorageError {
+    log::error!("rocksdb error {}", error);
+    StorageError::Storage
+}
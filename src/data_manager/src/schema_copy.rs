@@ -0,0 +1,152 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use kernel::{Object, Operation, SystemError, SystemResult};
+use representation::Binary;
+use sql_model::Id;
+
+use crate::{sequence::Sequence, sequence_key, ColumnDefinition, ColumnReference, DataManager, RangePartition};
+
+impl DataManager {
+    /// creates a schema with tables of the source schema, enum types of their columns and partitions that are in the
+    /// source schema too, foreign keys to tables of the source schema refer to the same tables of the new schema.
+    /// Rows and states of serial sequences are copied `with_data`
+    pub fn copy_schema(&self, source_schema_id: Id, schema_name: &str, with_data: bool) -> SystemResult<Id> {
+        let source_schema_name = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .get(&source_schema_id)
+            .cloned();
+        let source = match self
+            .catalog()?
+            .schemas
+            .into_iter()
+            .find(|schema| Some(&schema.name) == source_schema_name.as_ref())
+        {
+            Some(source) => source,
+            None => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Schema(source_schema_id.to_string().as_str()),
+                ))
+            }
+        };
+        let schema_id = self.create_schema(schema_name)?;
+        for table in source.tables.iter() {
+            let columns = table
+                .columns
+                .iter()
+                .map(|column| copied_column(column, &source.name, schema_name))
+                .collect::<Vec<ColumnDefinition>>();
+            for enum_type in columns.iter().filter_map(ColumnDefinition::enum_type) {
+                self.create_enum_type(schema_id, enum_type.clone())?;
+            }
+            self.create_table(schema_id, &table.name, &columns)?;
+        }
+        for table in source.tables.iter() {
+            let partition_key = match &table.partition_key {
+                Some(partition_key) => partition_key,
+                None => continue,
+            };
+            let table_id = self.imported_table(schema_name, &table.name);
+            self.partition_by_range(&Box::new(table_id), partition_key)?;
+            for partition in table
+                .partitions
+                .iter()
+                .filter(|partition| partition.schema_name == source.name)
+            {
+                let partition_id = self.imported_table(schema_name, &partition.table_name);
+                self.attach_range_partition(
+                    &Box::new(table_id),
+                    RangePartition::new(partition_id, partition.from, partition.to),
+                )?;
+            }
+        }
+        if with_data {
+            for table in source.tables.iter() {
+                let source_table_id = self.imported_table(&source.name, &table.name);
+                let table_id = self.imported_table(schema_name, &table.name);
+                self.copy_rows(source_table_id, table_id, &table.columns)?;
+                for sequence_name in table.columns.iter().filter_map(ColumnDefinition::serial_sequence) {
+                    self.copy_sequence(source_schema_id, schema_id, sequence_name)?;
+                }
+            }
+        }
+        Ok(schema_id)
+    }
+
+    /// rows of tables without primary key get row ids of the target table
+    fn copy_rows(
+        &self,
+        source_table_id: (Id, Id),
+        table_id: (Id, Id),
+        columns: &[ColumnDefinition],
+    ) -> SystemResult<()> {
+        let has_primary_key = columns.iter().any(ColumnDefinition::is_primary_key);
+        let rows = self
+            .full_scan(&Box::new(source_table_id))?
+            .filter_map(|row| row.ok().and_then(Result::ok))
+            .collect::<Vec<_>>();
+        let rows = rows
+            .into_iter()
+            .map(|(key, values)| {
+                if has_primary_key {
+                    (key, values)
+                } else {
+                    let row_id = self.next_key_id(&Box::new(table_id));
+                    (Binary::with_data(row_id.to_be_bytes().to_vec()), values)
+                }
+            })
+            .collect::<Vec<_>>();
+        if !rows.is_empty() {
+            self.write_into(&Box::new(table_id), rows)?;
+        }
+        Ok(())
+    }
+
+    fn copy_sequence(&self, source_schema_id: Id, schema_id: Id, sequence_name: &str) -> SystemResult<()> {
+        let values = match self
+            .sequences
+            .read()
+            .expect("to acquire read lock")
+            .get(&(source_schema_id, sequence_name.to_owned()))
+        {
+            Some(sequence) => sequence.to_values(),
+            None => return Ok(()),
+        };
+        if let Some(sequence) = Sequence::from_values(&values) {
+            self.persist_sequence(sequence_key(schema_id, sequence_name), values)?;
+            self.sequences
+                .write()
+                .expect("to acquire write lock")
+                .insert((schema_id, sequence_name.to_owned()), sequence);
+        }
+        Ok(())
+    }
+}
+
+fn copied_column(column: &ColumnDefinition, source_schema_name: &str, schema_name: &str) -> ColumnDefinition {
+    match column.referenced_column() {
+        Some(reference) if reference.schema_name() == source_schema_name => {
+            column.clone().references(ColumnReference::new(
+                schema_name,
+                reference.table_name(),
+                reference.column_name(),
+                reference.on_delete(),
+            ))
+        }
+        _ => column.clone(),
+    }
+}
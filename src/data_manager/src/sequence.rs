@@ -0,0 +1,212 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+
+use representation::{unpack_raw, Binary, Datum};
+
+/// Options of a sequence that are not given take PostgreSQL defaults:
+/// ascending sequences start at their minimum value, descending ones at their maximum
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct SequenceOptions {
+    increment: Option<i64>,
+    min_value: Option<i64>,
+    max_value: Option<i64>,
+    start: Option<i64>,
+}
+
+impl SequenceOptions {
+    pub fn increment(self, increment: i64) -> Self {
+        Self {
+            increment: Some(increment),
+            ..self
+        }
+    }
+
+    pub fn min_value(self, min_value: i64) -> Self {
+        Self {
+            min_value: Some(min_value),
+            ..self
+        }
+    }
+
+    pub fn max_value(self, max_value: i64) -> Self {
+        Self {
+            max_value: Some(max_value),
+            ..self
+        }
+    }
+
+    pub fn start(self, start: i64) -> Self {
+        Self {
+            start: Some(start),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SequenceError {
+    AlreadyExists,
+    DoesNotExist,
+    ZeroIncrement,
+    InvalidBounds { min_value: i64, max_value: i64 },
+    StartOutOfBounds { start: i64, min_value: i64, max_value: i64 },
+    MaxValueReached(i64),
+    MinValueReached(i64),
+    ValueOutOfBounds { value: i64, min_value: i64, max_value: i64 },
+}
+
+#[derive(Debug)]
+struct SequenceState {
+    last_value: i64,
+    is_called: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Sequence {
+    increment: i64,
+    min_value: i64,
+    max_value: i64,
+    state: Mutex<SequenceState>,
+}
+
+impl Sequence {
+    pub(crate) fn new(options: SequenceOptions) -> Result<Sequence, SequenceError> {
+        let increment = options.increment.unwrap_or(1);
+        if increment == 0 {
+            return Err(SequenceError::ZeroIncrement);
+        }
+        let min_value = options
+            .min_value
+            .unwrap_or(if increment > 0 { 1 } else { i64::min_value() });
+        let max_value = options
+            .max_value
+            .unwrap_or(if increment > 0 { i64::max_value() } else { -1 });
+        if min_value >= max_value {
+            return Err(SequenceError::InvalidBounds { min_value, max_value });
+        }
+        let start = options
+            .start
+            .unwrap_or(if increment > 0 { min_value } else { max_value });
+        if start < min_value || start > max_value {
+            return Err(SequenceError::StartOutOfBounds {
+                start,
+                min_value,
+                max_value,
+            });
+        }
+        Ok(Sequence {
+            increment,
+            min_value,
+            max_value,
+            state: Mutex::new(SequenceState {
+                last_value: start,
+                is_called: false,
+            }),
+        })
+    }
+
+    /// row ids of tables without primary key start from zero
+    pub(crate) fn row_ids(start: i64) -> Sequence {
+        Sequence {
+            increment: 1,
+            min_value: 0,
+            max_value: i64::max_value(),
+            state: Mutex::new(SequenceState {
+                last_value: start,
+                is_called: false,
+            }),
+        }
+    }
+
+    /// advances the sequence, the new state is passed to `persist` before the next call can proceed
+    pub(crate) fn next_value<E, P: FnOnce(Binary) -> Result<(), E>>(
+        &self,
+        persist: P,
+    ) -> Result<Result<i64, SequenceError>, E> {
+        let mut state = self.state.lock().expect("to acquire sequence lock");
+        let next = if !state.is_called {
+            Some(state.last_value)
+        } else {
+            state
+                .last_value
+                .checked_add(self.increment)
+                .filter(|next| *next >= self.min_value && *next <= self.max_value)
+        };
+        match next {
+            Some(next) => {
+                persist(self.values(next, true))?;
+                state.last_value = next;
+                state.is_called = true;
+                Ok(Ok(next))
+            }
+            None if self.increment > 0 => Ok(Err(SequenceError::MaxValueReached(self.max_value))),
+            None => Ok(Err(SequenceError::MinValueReached(self.min_value))),
+        }
+    }
+
+    /// sets the current value, the next value is `value` itself if `is_called` is `false`
+    pub(crate) fn set_value<E, P: FnOnce(Binary) -> Result<(), E>>(
+        &self,
+        value: i64,
+        is_called: bool,
+        persist: P,
+    ) -> Result<Result<i64, SequenceError>, E> {
+        if value < self.min_value || value > self.max_value {
+            return Ok(Err(SequenceError::ValueOutOfBounds {
+                value,
+                min_value: self.min_value,
+                max_value: self.max_value,
+            }));
+        }
+        let mut state = self.state.lock().expect("to acquire sequence lock");
+        persist(self.values(value, is_called))?;
+        state.last_value = value;
+        state.is_called = is_called;
+        Ok(Ok(value))
+    }
+
+    pub(crate) fn to_values(&self) -> Binary {
+        let state = self.state.lock().expect("to acquire sequence lock");
+        self.values(state.last_value, state.is_called)
+    }
+
+    pub(crate) fn from_values(values: &Binary) -> Option<Sequence> {
+        match unpack_raw(values.to_bytes()).as_slice() {
+            [Datum::Int64(increment), Datum::Int64(min_value), Datum::Int64(max_value), Datum::Int64(last_value), is_called] => {
+                Some(Sequence {
+                    increment: *increment,
+                    min_value: *min_value,
+                    max_value: *max_value,
+                    state: Mutex::new(SequenceState {
+                        last_value: *last_value,
+                        is_called: matches!(is_called, Datum::True),
+                    }),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn values(&self, last_value: i64, is_called: bool) -> Binary {
+        Binary::pack(&[
+            Datum::from_i64(self.increment),
+            Datum::from_i64(self.min_value),
+            Datum::from_i64(self.max_value),
+            Datum::from_i64(last_value),
+            Datum::from_bool(is_called),
+        ])
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! sketches summarize values in a bounded space and their states can be merged, so they can be
+//! built over parts of a table or kept by statistics and combined with each other later
+
+use serde::{Deserialize, Serialize};
+
+use representation::{Binary, Datum};
+
+/// registers of a distinct sketch are addressed by the high `PRECISION` bits of value hashes
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+
+/// HyperLogLog estimate of the number of distinct values, the standard error is about 1.6%
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DistinctSketch {
+    registers: Vec<u8>,
+}
+
+impl Default for DistinctSketch {
+    fn default() -> DistinctSketch {
+        DistinctSketch::new()
+    }
+}
+
+impl DistinctSketch {
+    pub fn new() -> DistinctSketch {
+        DistinctSketch {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    /// values of a group of columns are added as a single combination
+    pub fn add(&mut self, values: &[Datum]) {
+        let hash = hash(values);
+        let register = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        if self.registers[register] < rank {
+            self.registers[register] = rank;
+        }
+    }
+
+    pub fn merge(&mut self, other: &DistinctSketch) {
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            if *register < *other {
+                *register = *other;
+            }
+        }
+    }
+
+    /// small numbers of values are counted by empty registers, that is exact in practice
+    pub fn estimate(&self) -> u64 {
+        let registers = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / registers);
+        let sum = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-i32::from(*rank)))
+            .sum::<f64>();
+        let raw = alpha * registers * registers / sum;
+        let empty = self.registers.iter().filter(|rank| **rank == 0).count();
+        if raw <= 2.5 * registers && empty > 0 {
+            (registers * (registers / empty as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+/// FNV-1a of packed values with a final mix, so equal values hash equally between runs
+fn hash(values: &[Datum]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for byte in Binary::pack(values).to_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// centroids that a quantile sketch keeps at most, values are exact until there are more distinct ones
+const CENTROIDS: usize = 100;
+
+/// streaming histogram of numbers, the closest centroids are merged when there are too many of them
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct QuantileSketch {
+    /// mean and number of values sorted by means
+    centroids: Vec<(f64, u64)>,
+}
+
+impl QuantileSketch {
+    pub fn new() -> QuantileSketch {
+        QuantileSketch::default()
+    }
+
+    /// NaN has no place among other numbers and is skipped
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.insert(value, 1);
+        self.compress();
+    }
+
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        for (mean, count) in other.centroids.iter() {
+            self.insert(*mean, *count);
+        }
+        self.compress();
+    }
+
+    /// value below which the `fraction` of values lie, interpolated between neighbours as `percentile_cont` does
+    pub fn quantile(&self, fraction: f64) -> Option<f64> {
+        let total = self.centroids.iter().map(|(_, count)| count).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let rank = fraction * (total - 1) as f64;
+        // centroids are placed in the middle of ranks of their values
+        let mut before = 0;
+        let mut previous: Option<(f64, f64)> = None;
+        for (mean, count) in self.centroids.iter() {
+            let position = before as f64 + (*count - 1) as f64 / 2.0;
+            if rank <= position {
+                return Some(match previous {
+                    Some((previous_position, previous_mean)) if rank > previous_position => {
+                        let weight = (rank - previous_position) / (position - previous_position);
+                        previous_mean + (mean - previous_mean) * weight
+                    }
+                    _ => *mean,
+                });
+            }
+            previous = Some((position, *mean));
+            before += count;
+        }
+        previous.map(|(_, mean)| mean)
+    }
+
+    fn insert(&mut self, value: f64, count: u64) {
+        match self
+            .centroids
+            .binary_search_by(|(mean, _)| mean.partial_cmp(&value).expect("value is not NaN"))
+        {
+            Ok(index) => self.centroids[index].1 += count,
+            Err(index) => self.centroids.insert(index, (value, count)),
+        }
+    }
+
+    fn compress(&mut self) {
+        while self.centroids.len() > CENTROIDS {
+            let closest = (0..self.centroids.len() - 1)
+                .min_by(|left, right| {
+                    let left = self.centroids[left + 1].0 - self.centroids[*left].0;
+                    let right = self.centroids[right + 1].0 - self.centroids[*right].0;
+                    left.partial_cmp(&right).expect("value is not NaN")
+                })
+                .expect("more than one centroid");
+            let (left_mean, left_count) = self.centroids[closest];
+            let (right_mean, right_count) = self.centroids.remove(closest + 1);
+            let count = left_count + right_count;
+            self.centroids[closest] = (
+                (left_mean * left_count as f64 + right_mean * right_count as f64) / count as f64,
+                count,
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use representation::{unpack_raw, Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+/// user-defined function created by `CREATE FUNCTION ... LANGUAGE SQL`, its body is a single `SELECT <expression>`
+#[derive(Debug, PartialEq, Clone)]
+pub struct SqlFunction {
+    name: String,
+    parameters: Vec<(String, SqlType)>,
+    return_type: SqlType,
+    body: String,
+}
+
+impl SqlFunction {
+    pub fn new(name: &str, parameters: Vec<(String, SqlType)>, return_type: SqlType, body: &str) -> SqlFunction {
+        SqlFunction {
+            name: name.to_owned(),
+            parameters,
+            return_type,
+            body: body.to_owned(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn parameters(&self) -> &[(String, SqlType)] {
+        self.parameters.as_slice()
+    }
+
+    pub fn return_type(&self) -> SqlType {
+        self.return_type
+    }
+
+    pub fn body(&self) -> &str {
+        self.body.as_str()
+    }
+
+    /// body and return type are followed by names and types of parameters
+    pub(crate) fn to_values(&self) -> Binary {
+        let mut values = vec![
+            Datum::from_str(self.body.as_str()),
+            Datum::from_sql_type(self.return_type),
+        ];
+        for (name, sql_type) in self.parameters.iter() {
+            values.push(Datum::from_str(name.as_str()));
+            values.push(Datum::from_sql_type(*sql_type));
+        }
+        Binary::pack(&values)
+    }
+
+    pub(crate) fn from_values(name: &str, values: &Binary) -> Option<SqlFunction> {
+        let values = unpack_raw(values.to_bytes());
+        let (body, return_type) = match values.as_slice() {
+            [Datum::String(body), Datum::SqlType(return_type), ..] => (*body, *return_type),
+            _ => return None,
+        };
+        let mut parameters = vec![];
+        for parameter in values[2..].chunks(2) {
+            match parameter {
+                [Datum::String(name), Datum::SqlType(sql_type)] => parameters.push(((*name).to_owned(), *sql_type)),
+                _ => return None,
+            }
+        }
+        Some(SqlFunction::new(name, parameters, return_type, body))
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use representation::{Binary, Datum};
+use sql_model::Id;
+
+use crate::sketch::DistinctSketch;
+
+/// kinds of statistics built for a column group, all of them when `CREATE STATISTICS` lists none
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum StatisticsKind {
+    NDistinct,
+    Dependencies,
+}
+
+/// fraction of rows in which values of `determinant` column decide the value of `dependent` column
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct FunctionalDependency {
+    determinant: String,
+    dependent: String,
+    degree: f64,
+}
+
+impl FunctionalDependency {
+    pub fn determinant(&self) -> &str {
+        self.determinant.as_str()
+    }
+
+    pub fn dependent(&self) -> &str {
+        self.dependent.as_str()
+    }
+
+    pub fn degree(&self) -> f64 {
+        self.degree
+    }
+}
+
+/// statistics of a group of columns that are correlated and should not be estimated as independent ones
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ExtendedStatistics {
+    table_id: (Id, Id),
+    columns: Vec<String>,
+    kinds: Vec<StatisticsKind>,
+    n_distinct: Option<usize>,
+    distinct_sketch: Option<DistinctSketch>,
+    dependencies: Vec<FunctionalDependency>,
+}
+
+impl ExtendedStatistics {
+    /// statistics are empty until they are built from rows of the table
+    pub fn new(table_id: (Id, Id), columns: Vec<String>, kinds: Vec<StatisticsKind>) -> ExtendedStatistics {
+        ExtendedStatistics {
+            table_id,
+            columns,
+            kinds,
+            n_distinct: None,
+            distinct_sketch: None,
+            dependencies: vec![],
+        }
+    }
+
+    pub fn table_id(&self) -> (Id, Id) {
+        self.table_id
+    }
+
+    pub fn columns(&self) -> &[String] {
+        self.columns.as_slice()
+    }
+
+    pub fn kinds(&self) -> &[StatisticsKind] {
+        self.kinds.as_slice()
+    }
+
+    /// number of distinct combinations of the column values, NULL is counted as a value
+    pub fn n_distinct(&self) -> Option<usize> {
+        self.n_distinct
+    }
+
+    /// sketch of the column value combinations that is kept with `ndistinct` statistics, it is merged with
+    /// sketches of other rows to estimate distinct values without scanning the table again
+    pub fn distinct_sketch(&self) -> Option<&DistinctSketch> {
+        self.distinct_sketch.as_ref()
+    }
+
+    pub fn dependencies(&self) -> &[FunctionalDependency] {
+        self.dependencies.as_slice()
+    }
+
+    /// every row holds values of the group columns in the order of `columns`
+    pub fn build(self, rows: &[Vec<Datum>]) -> ExtendedStatistics {
+        let (n_distinct, distinct_sketch) = if self.kinds.contains(&StatisticsKind::NDistinct) {
+            let mut sketch = DistinctSketch::new();
+            for row in rows {
+                sketch.add(row);
+            }
+            (Some(rows.iter().collect::<HashSet<_>>().len()), Some(sketch))
+        } else {
+            (None, None)
+        };
+        let mut dependencies = vec![];
+        if self.kinds.contains(&StatisticsKind::Dependencies) && !rows.is_empty() {
+            for determinant in 0..self.columns.len() {
+                for dependent in 0..self.columns.len() {
+                    if determinant != dependent {
+                        dependencies.push(FunctionalDependency {
+                            determinant: self.columns[determinant].clone(),
+                            dependent: self.columns[dependent].clone(),
+                            degree: dependency_degree(rows, determinant, dependent),
+                        });
+                    }
+                }
+            }
+        }
+        ExtendedStatistics {
+            n_distinct,
+            distinct_sketch,
+            dependencies,
+            ..self
+        }
+    }
+
+    /// selectivity of equality conditions on columns given selectivity of each condition on its own;
+    /// a condition on a dependent column is implied by the one on its determinant to the degree of the dependency
+    /// instead of the selectivities being multiplied as if the columns were independent
+    pub fn selectivity(&self, conditions: &[(&str, f64)]) -> f64 {
+        let mut selectivities = conditions
+            .iter()
+            .map(|(column, selectivity)| (column.to_string(), *selectivity))
+            .collect::<HashMap<_, _>>();
+        let mut dependencies = self.dependencies.iter().collect::<Vec<_>>();
+        dependencies.sort_by(|left, right| right.degree.partial_cmp(&left.degree).expect("degree is not NaN"));
+        let mut implied = HashSet::new();
+        for dependency in dependencies {
+            if implied.contains(&dependency.determinant) || implied.contains(&dependency.dependent) {
+                continue;
+            }
+            if !selectivities.contains_key(&dependency.determinant) {
+                continue;
+            }
+            if let Some(selectivity) = selectivities.get_mut(&dependency.dependent) {
+                *selectivity = dependency.degree + (1.0 - dependency.degree) * *selectivity;
+                implied.insert(dependency.dependent.clone());
+            }
+        }
+        selectivities.values().product()
+    }
+}
+
+/// number of rows of a table and bounds of values of its integer columns as they were when the table was analyzed
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct TableStatistics {
+    rows: usize,
+    bounds: HashMap<String, (i64, i64)>,
+}
+
+impl TableStatistics {
+    /// every row holds values of `columns` in their order, NULLs and values of other types have no bounds
+    pub fn build(columns: &[String], rows: &[Vec<Datum>]) -> TableStatistics {
+        let mut bounds = HashMap::new();
+        for (index, column) in columns.iter().enumerate() {
+            let values = rows.iter().filter_map(|row| integer(&row[index]));
+            if let Some(bound) = values.fold(None, |bound: Option<(i64, i64)>, value| match bound {
+                Some((min, max)) => Some((min.min(value), max.max(value))),
+                None => Some((value, value)),
+            }) {
+                bounds.insert(column.clone(), bound);
+            }
+        }
+        TableStatistics {
+            rows: rows.len(),
+            bounds,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// fraction of rows with values of the column within inclusive bounds, values are taken as spread evenly
+    /// between the smallest and the largest ones. `None` if there were no integer values in the column
+    pub fn range_selectivity(&self, column: &str, lower: Option<i64>, upper: Option<i64>) -> Option<f64> {
+        let (min, max) = *self.bounds.get(column)?;
+        let lower = lower.unwrap_or(min).max(min);
+        let upper = upper.unwrap_or(max).min(max);
+        if lower > upper {
+            Some(0.0)
+        } else {
+            Some((upper as f64 - lower as f64 + 1.0) / (max as f64 - min as f64 + 1.0))
+        }
+    }
+}
+
+/// statistics are stored as they were built, so they are not built again when the storage is opened
+pub(crate) fn to_values<S: Serialize>(statistics: &S) -> Binary {
+    Binary::with_data(bincode::serialize(statistics).expect("statistics are serializable"))
+}
+
+pub(crate) fn from_values<S: DeserializeOwned>(values: &Binary) -> Option<S> {
+    bincode::deserialize(values.to_bytes()).ok()
+}
+
+fn integer(datum: &Datum) -> Option<i64> {
+    match datum {
+        Datum::Int16(value) => Some(*value as i64),
+        Datum::Int32(value) => Some(*value as i64),
+        Datum::Int64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// rows in groups of the same determinant value that all have the same dependent value support the dependency
+fn dependency_degree(rows: &[Vec<Datum>], determinant: usize, dependent: usize) -> f64 {
+    let mut groups: HashMap<&Datum, (usize, &Datum, bool)> = HashMap::new();
+    for row in rows {
+        let group = groups.entry(&row[determinant]).or_insert((0, &row[dependent], true));
+        group.0 += 1;
+        group.2 = group.2 && group.1 == &row[dependent];
+    }
+    let supporting = groups
+        .values()
+        .filter(|(_, _, consistent)| *consistent)
+        .map(|(count, _, _)| count)
+        .sum::<usize>();
+    supporting as f64 / rows.len() as f64
+}
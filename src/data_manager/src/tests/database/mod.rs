@@ -18,6 +18,8 @@ use super::*;
 mod in_memory;
 #[cfg(test)]
 mod persistent;
+#[cfg(all(test, feature = "rocksdb"))]
+mod rocks;
 
 #[rstest::fixture]
 fn schema_name() -> SchemaName<'static> {
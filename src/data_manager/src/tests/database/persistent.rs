@@ -425,4 +425,86 @@ mod operations_on_object {
             .collect()),
         );
     }
+
+    #[rstest::rstest]
+    fn read_range_of_rows_from_object(with_object: Storage, schema_name: SchemaName, object_name: ObjectName) {
+        with_object
+            .write(
+                schema_name,
+                object_name,
+                as_rows(vec![
+                    (1u8, vec!["123"]),
+                    (2u8, vec!["456"]),
+                    (3u8, vec!["789"]),
+                    (4u8, vec!["012"]),
+                ]),
+            )
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are written");
+
+        let mut keys = as_keys(vec![2u8, 4u8]).into_iter();
+        let (start, end) = (keys.next().expect("key"), keys.next().expect("key"));
+        assert_eq!(
+            with_object
+                .range(
+                    schema_name,
+                    object_name,
+                    (Bound::Included(start), Bound::Excluded(end)),
+                    10
+                )
+                .expect("no io error")
+                .expect("no platform error")
+                .map(|iter| iter
+                    .map(|ok| ok.expect("no io error"))
+                    .collect::<Vec<Result<Row, StorageError>>>()),
+            Ok(as_read_cursor(vec![(2u8, vec!["456"]), (3u8, vec!["789"])])
+                .map(|ok| ok.expect("no io error"))
+                .collect())
+        );
+    }
+
+    #[rstest::rstest]
+    fn compact_object_that_does_not_exist(with_schema: Storage, schema_name: SchemaName, object_name: ObjectName) {
+        assert_eq!(
+            with_schema.compact(schema_name, object_name).expect("no io error"),
+            Ok(Err(DefinitionError::ObjectDoesNotExist))
+        );
+    }
+
+    #[rstest::rstest]
+    fn compact_object_keeps_its_rows(with_object: Storage, schema_name: SchemaName, object_name: ObjectName) {
+        with_object
+            .write(
+                schema_name,
+                object_name,
+                as_rows(vec![(1u8, vec!["123"]), (2u8, vec!["456"]), (3u8, vec!["789"])]),
+            )
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are written");
+        with_object
+            .delete(schema_name, object_name, as_keys(vec![2u8]))
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are deleted");
+
+        assert_eq!(
+            with_object.compact(schema_name, object_name).expect("no io error"),
+            Ok(Ok(()))
+        );
+
+        assert_eq!(
+            with_object
+                .read(schema_name, object_name)
+                .expect("no io error")
+                .expect("no platform error")
+                .map(|iter| iter
+                    .map(|ok| ok.expect("no io error"))
+                    .collect::<Vec<Result<Row, StorageError>>>()),
+            Ok(as_read_cursor(vec![(1u8, vec!["123"]), (3u8, vec!["789"])])
+                .map(|ok| ok.expect("no io error"))
+                .collect())
+        );
+    }
 }
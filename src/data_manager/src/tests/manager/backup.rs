@@ -0,0 +1,278 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+
+use representation::{Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn restored_backup_has_tables_rows_and_sequences(data_manager_with_schema: DataManager) {
+    let directory = tempfile::tempdir().expect("to create temp folder");
+    let path = directory.path().join("node.backup");
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value())).serial("table_name_id_seq")],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let key = Binary::pack(&[Datum::from_u64(data_manager_with_schema.next_key_id(&table_id))]);
+    let values = Binary::pack(&[Datum::from_i32(1)]);
+    data_manager_with_schema
+        .write_into(&table_id, vec![(key.clone(), values.clone())])
+        .expect("values are inserted");
+    assert_eq!(
+        data_manager_with_schema.next_sequence_value(schema_id, "table_name_id_seq"),
+        Ok(Ok(1))
+    );
+
+    assert_eq!(data_manager_with_schema.backup(&path), Ok(Ok(())));
+
+    let restored = DataManager::default();
+    assert_eq!(restored.restore_backup(&path), Ok(Ok(())));
+    let table_id = match restored.table_exists(&SCHEMA, &"table_name") {
+        Some((schema_id, Some(table_id))) => Box::new((schema_id, table_id)),
+        other => panic!("table is not restored: {:?}", other),
+    };
+    assert_eq!(
+        restored
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![(key.clone(), values)])
+    );
+    assert_ne!(Binary::pack(&[Datum::from_u64(restored.next_key_id(&table_id))]), key);
+    assert_eq!(restored.next_sequence_value(table_id.0, "table_name_id_seq"), Ok(Ok(2)));
+    assert_eq!(restored.restore_backup(&path), Ok(Err(BackupError::NotEmpty)));
+}
+
+#[rstest::rstest]
+fn backup_of_other_version_is_not_restored(data_manager: DataManager) {
+    let directory = tempfile::tempdir().expect("to create temp folder");
+    let path = directory.path().join("node.backup");
+    std::fs::write(&path, bincode::serialize(&0u32).expect("version is serializable")).expect("to write backup");
+
+    assert_eq!(
+        data_manager.restore_backup(&path),
+        Ok(Err(BackupError::Malformed("unsupported backup version 0".to_owned())))
+    );
+}
+
+#[rstest::rstest]
+fn tables_are_migrated_to_other_backend(data_manager_with_schema: DataManager) {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let key = Binary::pack(&[Datum::from_u64(data_manager_with_schema.next_key_id(&table_id))]);
+    let values = Binary::pack(&[Datum::from_i32(1)]);
+    data_manager_with_schema
+        .write_into(&table_id, vec![(key.clone(), values.clone())])
+        .expect("values are inserted");
+
+    let target = DataManager::open(StorageBackend::Sled(root_path.path().to_path_buf())).expect("to open storage");
+    assert_eq!(data_manager_with_schema.migrate(&target), Ok(Ok(())));
+    drop(target);
+
+    let migrated = DataManager::open(StorageBackend::Sled(root_path.into_path())).expect("to open storage");
+    let table_id = match migrated.table_exists(&SCHEMA, &"table_name") {
+        Some((schema_id, Some(table_id))) => Box::new((schema_id, table_id)),
+        other => panic!("table is not migrated: {:?}", other),
+    };
+    assert_eq!(
+        migrated
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![(key, values)])
+    );
+    assert_eq!(
+        data_manager_with_schema.migrate(&migrated),
+        Ok(Err(BackupError::NotEmpty))
+    );
+}
+
+#[cfg(feature = "rocksdb")]
+#[rstest::rstest]
+fn tables_are_migrated_from_sled_to_rocks_db() {
+    let sled_path = tempfile::tempdir().expect("to create temp folder");
+    let rocks_db_path = tempfile::tempdir().expect("to create temp folder");
+    let source = DataManager::open(StorageBackend::Sled(sled_path.path().to_path_buf())).expect("to open storage");
+    let schema_id = source.create_schema(SCHEMA).expect("schema is created");
+    let table_id = source
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let rows = (0..3)
+        .map(|id| {
+            (
+                Binary::pack(&[Datum::from_u64(source.next_key_id(&table_id))]),
+                Binary::pack(&[Datum::from_i32(id)]),
+            )
+        })
+        .collect::<Vec<_>>();
+    source.write_into(&table_id, rows.clone()).expect("values are inserted");
+
+    let target =
+        DataManager::open(StorageBackend::RocksDb(rocks_db_path.path().to_path_buf())).expect("to open storage");
+    assert_eq!(source.migrate(&target), Ok(Ok(())));
+    drop(target);
+
+    let migrated = DataManager::open(StorageBackend::RocksDb(rocks_db_path.into_path())).expect("to open storage");
+    let table_id = match migrated.table_exists(&SCHEMA, &"table_name") {
+        Some((schema_id, Some(table_id))) => Box::new((schema_id, table_id)),
+        other => panic!("table is not migrated: {:?}", other),
+    };
+    assert_eq!(
+        migrated
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(rows)
+    );
+}
+
+#[rstest::rstest]
+fn rows_of_table_are_backed_up_in_batches(data_manager_with_schema: DataManager) {
+    let directory = tempfile::tempdir().expect("to create temp folder");
+    let path = directory.path().join("node.backup");
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let mut rows = (0..2500)
+        .map(|id| {
+            (
+                Binary::pack(&[Datum::from_u64(data_manager_with_schema.next_key_id(&table_id))]),
+                Binary::pack(&[Datum::from_i32(id)]),
+            )
+        })
+        .collect::<Vec<_>>();
+    data_manager_with_schema
+        .write_into(&table_id, rows.clone())
+        .expect("values are inserted");
+    rows.sort();
+
+    assert_eq!(data_manager_with_schema.backup(&path), Ok(Ok(())));
+
+    let restored = DataManager::default();
+    assert_eq!(restored.restore_backup(&path), Ok(Ok(())));
+    let table_id = match restored.table_exists(&SCHEMA, &"table_name") {
+        Some((schema_id, Some(table_id))) => Box::new((schema_id, table_id)),
+        other => panic!("table is not restored: {:?}", other),
+    };
+    assert_eq!(
+        restored
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(rows)
+    );
+}
+
+#[rstest::rstest]
+fn truncated_backup_restores_nothing(data_manager_with_schema: DataManager) {
+    let directory = tempfile::tempdir().expect("to create temp folder");
+    let path = directory.path().join("node.backup");
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    data_manager_with_schema
+        .write_into(
+            &table_id,
+            vec![(
+                Binary::pack(&[Datum::from_u64(data_manager_with_schema.next_key_id(&table_id))]),
+                Binary::pack(&[Datum::from_i32(1)]),
+            )],
+        )
+        .expect("values are inserted");
+    assert_eq!(data_manager_with_schema.backup(&path), Ok(Ok(())));
+    let backup = std::fs::read(&path).expect("to read backup");
+    std::fs::write(&path, &backup[..backup.len() - 8]).expect("to write backup");
+
+    let restored = DataManager::default();
+    assert!(matches!(
+        restored.restore_backup(&path),
+        Ok(Err(BackupError::Malformed(_)))
+    ));
+    assert_eq!(restored.schema_exists(&SCHEMA), None);
+}
+
+#[rstest::rstest]
+fn backup_waits_for_writers_of_tables(data_manager_with_schema: DataManager) {
+    let directory = tempfile::tempdir().expect("to create temp folder");
+    let path = directory.path().join("node.backup");
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let data_manager = Arc::new(data_manager_with_schema);
+    let owner = data_manager.lock_owner();
+    assert_eq!(
+        data_manager.lock_table(&table_id, owner, &|| false),
+        TableLock::Acquired
+    );
+
+    let (backed_up, waiting) = mpsc::channel();
+    let backup = {
+        let data_manager = data_manager.clone();
+        thread::spawn(move || {
+            backed_up
+                .send(data_manager.backup(&path))
+                .expect("to send backup result");
+        })
+    };
+
+    assert!(waiting.recv_timeout(Duration::from_millis(100)).is_err());
+    data_manager.unlock_tables(owner);
+    assert_eq!(waiting.recv_timeout(Duration::from_secs(10)), Ok(Ok(Ok(()))));
+    backup.join().expect("thread is finished");
+
+    let owner = data_manager.lock_owner();
+    assert_eq!(
+        data_manager.lock_table(&table_id, owner, &|| false),
+        TableLock::Acquired
+    );
+}
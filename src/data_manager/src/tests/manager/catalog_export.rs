@@ -0,0 +1,105 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn columns() -> Vec<ColumnDefinition> {
+    vec![
+        ColumnDefinition::new("id", SqlType::Integer(i32::min_value())).primary_key(),
+        ColumnDefinition::new("name", SqlType::VarChar(20)).not_null(),
+    ]
+}
+
+#[rstest::rstest]
+fn exported_catalog_is_imported_into_empty_instance(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let parent = data_manager_with_schema
+        .create_table(schema_id, "parent", &columns())
+        .expect("table is created");
+    let partition = data_manager_with_schema
+        .create_table(schema_id, "partition", &columns())
+        .expect("table is created");
+    data_manager_with_schema
+        .partition_by_range(&Box::new((schema_id, parent)), "id")
+        .expect("table is partitioned");
+    data_manager_with_schema
+        .attach_range_partition(
+            &Box::new((schema_id, parent)),
+            RangePartition::new((schema_id, partition), None, Some(10)),
+        )
+        .expect("partition is attached");
+    let exported = data_manager_with_schema.export_catalog().expect("no system errors");
+
+    let data_manager = DataManager::default();
+    assert_eq!(
+        data_manager.import_catalog(&exported).expect("no system errors"),
+        Ok(())
+    );
+
+    assert_eq!(
+        data_manager.catalog().expect("no system errors"),
+        data_manager_with_schema.catalog().expect("no system errors")
+    );
+    let schema_id = data_manager.schema_exists(&SCHEMA).expect("schema exists");
+    let parent = match data_manager.table_exists(&SCHEMA, &"parent") {
+        Some((_, Some(table_id))) => (schema_id, table_id),
+        _ => panic!("table is imported"),
+    };
+    assert_eq!(
+        data_manager.table_columns(&Box::new(parent)).expect("no system errors"),
+        columns()
+    );
+    assert_eq!(
+        data_manager
+            .range_partitioning(&Box::new(parent))
+            .map(|partitioning| partitioning.partitions().len()),
+        Some(1)
+    );
+}
+
+#[rstest::rstest]
+fn catalog_is_not_imported_into_instance_with_schemas(data_manager_with_schema: DataManager) {
+    let exported = data_manager_with_schema.export_catalog().expect("no system errors");
+
+    assert_eq!(
+        data_manager_with_schema
+            .import_catalog(&exported)
+            .expect("no system errors"),
+        Err(CatalogImportError::NotEmpty)
+    );
+}
+
+#[rstest::rstest]
+fn malformed_catalog_is_not_imported(data_manager: DataManager) {
+    assert!(matches!(
+        data_manager
+            .import_catalog("{\"schemas\": 1}")
+            .expect("no system errors"),
+        Err(CatalogImportError::Malformed(_))
+    ));
+    assert_eq!(
+        data_manager
+            .import_catalog(
+                "{\"version\": 1, \"schemas\": [{\"name\": \"s\", \"owner\": \"\", \"tables\": []}, \
+                 {\"name\": \"s\", \"owner\": \"\", \"tables\": []}]}"
+            )
+            .expect("no system errors"),
+        Err(CatalogImportError::Malformed(
+            "schema \"s\" is listed more than once".to_owned()
+        ))
+    );
+    assert_eq!(data_manager.schema_names(), Vec::<String>::new());
+}
@@ -0,0 +1,198 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn catalog(schemas: Vec<SchemaExport>) -> CatalogExport {
+    CatalogExport {
+        version: CATALOG_EXPORT_VERSION,
+        schemas,
+    }
+}
+
+fn schema(name: &str, tables: Vec<TableExport>) -> SchemaExport {
+    SchemaExport {
+        name: name.to_owned(),
+        owner: SUPERUSER.to_owned(),
+        tables,
+    }
+}
+
+fn table(name: &str, columns: Vec<ColumnDefinition>) -> TableExport {
+    TableExport {
+        name: name.to_owned(),
+        columns,
+        partition_key: None,
+        partitions: vec![],
+    }
+}
+
+fn id() -> ColumnDefinition {
+    ColumnDefinition::new("id", SqlType::Integer(i32::min_value())).primary_key()
+}
+
+fn partitioned(key: &str, partitions: &[(&str, Option<i64>, Option<i64>)]) -> TableExport {
+    TableExport {
+        partition_key: Some(key.to_owned()),
+        partitions: partitions
+            .iter()
+            .map(|(table_name, from, to)| PartitionExport {
+                schema_name: SCHEMA.to_owned(),
+                table_name: (*table_name).to_owned(),
+                from: *from,
+                to: *to,
+            })
+            .collect(),
+        ..table("parent", vec![id()])
+    }
+}
+
+#[rstest::rstest]
+fn same_catalogs() {
+    let catalog = catalog(vec![schema(SCHEMA, vec![table("table_name", vec![id()])])]);
+
+    assert_eq!(catalog_migration(&catalog, &catalog), Vec::<String>::new());
+}
+
+#[rstest::rstest]
+fn referenced_tables_are_created_first() {
+    let from = catalog(vec![]);
+    let to = catalog(vec![schema(
+        SCHEMA,
+        vec![
+            table(
+                "a_orders",
+                vec![
+                    id(),
+                    ColumnDefinition::new("customer_id", SqlType::Integer(i32::min_value())).references(
+                        ColumnReference::new(SCHEMA, "customers", "id", ReferentialAction::Cascade),
+                    ),
+                ],
+            ),
+            table(
+                "customers",
+                vec![id(), ColumnDefinition::new("name", SqlType::VarChar(20)).not_null()],
+            ),
+        ],
+    )]);
+
+    assert_eq!(
+        catalog_migration(&from, &to),
+        vec![
+            "CREATE SCHEMA schema_name;",
+            "CREATE TABLE schema_name.customers (id integer, name varchar(20) NOT NULL, PRIMARY KEY (id));",
+            "CREATE TABLE schema_name.a_orders (id integer, customer_id integer REFERENCES schema_name.customers (id) ON DELETE CASCADE, PRIMARY KEY (id));",
+        ]
+    );
+    assert_eq!(
+        catalog_migration(&to, &catalog(vec![schema(SCHEMA, vec![])])),
+        vec!["DROP TABLE schema_name.a_orders;", "DROP TABLE schema_name.customers;"]
+    );
+    assert_eq!(catalog_migration(&to, &from), vec!["DROP SCHEMA schema_name CASCADE;"]);
+}
+
+#[rstest::rstest]
+fn columns_are_altered() {
+    let from = catalog(vec![schema(
+        SCHEMA,
+        vec![table(
+            "table_name",
+            vec![
+                id(),
+                ColumnDefinition::new("kept", SqlType::SmallInt(i16::min_value())),
+                ColumnDefinition::new("removed", SqlType::Text),
+                ColumnDefinition::new("counter", SqlType::Integer(i32::min_value())),
+            ],
+        )],
+    )]);
+    let to = catalog(vec![schema(
+        SCHEMA,
+        vec![table(
+            "table_name",
+            vec![
+                id(),
+                ColumnDefinition::new("kept", SqlType::BigInt(i64::min_value())).not_null(),
+                ColumnDefinition::new("counter", SqlType::Integer(i32::min_value()))
+                    .not_null()
+                    .serial("table_name_counter_seq"),
+                ColumnDefinition::new("added", SqlType::Text),
+            ],
+        )],
+    )]);
+
+    assert_eq!(
+        catalog_migration(&from, &to),
+        vec![
+            "ALTER TABLE schema_name.table_name DROP COLUMN removed;",
+            "ALTER TABLE schema_name.table_name DROP COLUMN counter;",
+            "ALTER TABLE schema_name.table_name ALTER COLUMN kept TYPE bigint;",
+            "ALTER TABLE schema_name.table_name ALTER COLUMN kept SET NOT NULL;",
+            "ALTER TABLE schema_name.table_name ADD COLUMN counter serial;",
+            "ALTER TABLE schema_name.table_name ADD COLUMN added text;",
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn partitions_are_detached_and_attached() {
+    let from = catalog(vec![schema(
+        SCHEMA,
+        vec![
+            partitioned("id", &[("low", None, Some(10))]),
+            table("low", vec![id()]),
+            table("high", vec![id()]),
+        ],
+    )]);
+    let to = catalog(vec![schema(
+        SCHEMA,
+        vec![
+            partitioned("id", &[("low", None, Some(20)), ("high", Some(20), None)]),
+            table("low", vec![id()]),
+            table("high", vec![id()]),
+        ],
+    )]);
+
+    assert_eq!(
+        catalog_migration(&from, &to),
+        vec![
+            "ALTER TABLE schema_name.parent DETACH PARTITION schema_name.low;",
+            "ALTER TABLE schema_name.parent ATTACH PARTITION schema_name.high FOR VALUES FROM (20) TO (MAXVALUE);",
+            "ALTER TABLE schema_name.parent ATTACH PARTITION schema_name.low FOR VALUES FROM (MINVALUE) TO (20);",
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn partitioned_table_is_recreated_with_new_key() {
+    let from = catalog(vec![schema(
+        SCHEMA,
+        vec![partitioned("id", &[("low", None, Some(10))]), table("low", vec![id()])],
+    )]);
+    let to = catalog(vec![schema(
+        SCHEMA,
+        vec![table("parent", vec![id()]), table("low", vec![id()])],
+    )]);
+
+    assert_eq!(
+        catalog_migration(&from, &to),
+        vec![
+            "DROP TABLE schema_name.low;",
+            "DROP TABLE schema_name.parent;",
+            "CREATE TABLE schema_name.low (id integer, PRIMARY KEY (id));",
+            "CREATE TABLE schema_name.parent (id integer, PRIMARY KEY (id));",
+        ]
+    );
+}
@@ -0,0 +1,84 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use representation::{Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn row(id: u64, value: i32) -> (Binary, Binary) {
+    (
+        Binary::pack(&[Datum::from_u64(id)]),
+        Binary::pack(&[Datum::from_i32(value)]),
+    )
+}
+
+#[rstest::rstest]
+fn committed_changes_are_taken_from_slot(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let columns = vec![ColumnDefinition::new("col", SqlType::Integer(i32::min_value()))];
+    let table_id = data_manager_with_schema
+        .create_table(schema_id, "table_name", &columns)
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    data_manager_with_schema
+        .write_into(&table_id, vec![row(1, 1), row(2, 2)])
+        .expect("values are inserted");
+
+    assert!(!data_manager_with_schema.captures_changes());
+    assert!(data_manager_with_schema.create_change_slot("slot_name"));
+    assert!(!data_manager_with_schema.create_change_slot("slot_name"));
+    assert!(data_manager_with_schema.captures_changes());
+
+    let journal = Journal::default();
+    data_manager_with_schema
+        .journal_write(&journal, &table_id, vec![row(1, 10), row(3, 3)])
+        .expect("values are written");
+    data_manager_with_schema
+        .journal_delete(&journal, &table_id, vec![row(2, 2).0])
+        .expect("values are deleted");
+    data_manager_with_schema.commit(&journal).expect("no system errors");
+
+    assert_eq!(
+        data_manager_with_schema.take_slot_changes("slot_name"),
+        Some(vec![Arc::new(CommittedChanges {
+            transaction: 1,
+            tables: vec![TableChanges {
+                schema_name: SCHEMA.to_owned(),
+                table_name: "table_name".to_owned(),
+                columns,
+                rows: vec![
+                    RowChange {
+                        key: row(1, 10).0,
+                        before: Some(row(1, 1).1),
+                        after: Some(row(1, 10).1),
+                    },
+                    RowChange {
+                        key: row(3, 3).0,
+                        before: None,
+                        after: Some(row(3, 3).1),
+                    },
+                    RowChange {
+                        key: row(2, 2).0,
+                        before: Some(row(2, 2).1),
+                        after: None,
+                    },
+                ],
+            }],
+        })])
+    );
+    assert_eq!(data_manager_with_schema.take_slot_changes("slot_name"), Some(vec![]));
+    assert!(data_manager_with_schema.drop_change_slot("slot_name"));
+    assert_eq!(data_manager_with_schema.take_slot_changes("slot_name"), None);
+}
@@ -0,0 +1,169 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::rstest]
+fn create_large_objects_with_generated_ids(data_manager: DataManager) {
+    let first = data_manager.create_large_object(None).expect("no system errors");
+    let second = data_manager.create_large_object(None).expect("no system errors");
+
+    assert!(matches!(first, Some(_)));
+    assert!(matches!(second, Some(_)));
+    assert_ne!(first, second);
+}
+
+#[rstest::rstest]
+fn create_large_object_with_taken_id(data_manager: DataManager) {
+    assert_eq!(
+        data_manager.create_large_object(Some(100)).expect("no system errors"),
+        Some(100)
+    );
+    assert_eq!(
+        data_manager.create_large_object(Some(100)).expect("no system errors"),
+        None
+    );
+}
+
+#[rstest::rstest]
+fn generated_id_skips_taken_ids(data_manager: DataManager) {
+    let taken = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    data_manager.unlink_large_object(taken).expect("no system errors");
+    data_manager
+        .create_large_object(Some(taken + 1))
+        .expect("no system errors");
+
+    assert_eq!(
+        data_manager.create_large_object(None).expect("no system errors"),
+        Some(taken + 2)
+    );
+}
+
+#[rstest::rstest]
+fn write_and_read_data_spanning_several_pages(data_manager: DataManager) {
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    let data = (0..LARGE_OBJECT_PAGE_SIZE * 3 + 10)
+        .map(|index| (index % 256) as u8)
+        .collect::<Vec<u8>>();
+
+    assert_eq!(
+        data_manager.write_large_object(id, 0, &data).expect("no system errors"),
+        Some(data.len())
+    );
+    assert_eq!(
+        data_manager.read_large_object(id, 0, None).expect("no system errors"),
+        Some(data.clone())
+    );
+    assert_eq!(
+        data_manager
+            .read_large_object(id, LARGE_OBJECT_PAGE_SIZE as u64 - 2, Some(4))
+            .expect("no system errors"),
+        Some(data[LARGE_OBJECT_PAGE_SIZE - 2..LARGE_OBJECT_PAGE_SIZE + 2].to_vec())
+    );
+}
+
+#[rstest::rstest]
+fn overwrite_part_of_large_object(data_manager: DataManager) {
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    data_manager
+        .write_large_object(id, 0, b"hello world")
+        .expect("no system errors");
+    data_manager
+        .write_large_object(id, 6, b"there")
+        .expect("no system errors");
+
+    assert_eq!(
+        data_manager.read_large_object(id, 0, None).expect("no system errors"),
+        Some(b"hello there".to_vec())
+    );
+}
+
+#[rstest::rstest]
+fn gaps_are_read_as_zeros(data_manager: DataManager) {
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    let offset = LARGE_OBJECT_PAGE_SIZE as u64 * 2;
+    data_manager
+        .write_large_object(id, offset, &[1, 2])
+        .expect("no system errors");
+
+    assert_eq!(
+        data_manager
+            .read_large_object(id, offset - 2, None)
+            .expect("no system errors"),
+        Some(vec![0, 0, 1, 2])
+    );
+}
+
+#[rstest::rstest]
+fn read_beyond_the_end(data_manager: DataManager) {
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    data_manager
+        .write_large_object(id, 0, &[1, 2, 3])
+        .expect("no system errors");
+
+    assert_eq!(
+        data_manager
+            .read_large_object(id, 2, Some(10))
+            .expect("no system errors"),
+        Some(vec![3])
+    );
+    assert_eq!(
+        data_manager
+            .read_large_object(id, 5, Some(10))
+            .expect("no system errors"),
+        Some(vec![])
+    );
+}
+
+#[rstest::rstest]
+fn access_nonexistent_large_object(data_manager: DataManager) {
+    assert_eq!(
+        data_manager.write_large_object(1, 0, &[1]).expect("no system errors"),
+        None
+    );
+    assert_eq!(
+        data_manager.read_large_object(1, 0, None).expect("no system errors"),
+        None
+    );
+    assert!(!data_manager.unlink_large_object(1).expect("no system errors"));
+}
+
+#[rstest::rstest]
+fn unlinked_large_object_does_not_exist(data_manager: DataManager) {
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+
+    assert!(data_manager.unlink_large_object(id).expect("no system errors"));
+    assert_eq!(
+        data_manager.read_large_object(id, 0, None).expect("no system errors"),
+        None
+    );
+}
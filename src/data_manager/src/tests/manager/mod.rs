@@ -14,13 +14,33 @@
 
 use super::*;
 
+#[cfg(test)]
+mod backup;
+#[cfg(test)]
+mod catalog_export;
+#[cfg(test)]
+mod catalog_migration;
+#[cfg(test)]
+mod change_capture;
+#[cfg(test)]
+mod large_objects;
+#[cfg(test)]
+mod partitions;
 #[cfg(test)]
 mod persistence;
 #[cfg(test)]
+mod privileges;
+#[cfg(test)]
 mod queries;
 #[cfg(test)]
 mod schema;
 #[cfg(test)]
+mod schema_copy;
+#[cfg(test)]
+mod sequences;
+#[cfg(test)]
+mod statistics;
+#[cfg(test)]
 mod table;
 
 const SCHEMA: &str = "schema_name";
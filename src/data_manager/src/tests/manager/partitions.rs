@@ -0,0 +1,108 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn table(data_manager: &DataManager, schema_id: Id, table_name: &str) -> (Id, Id) {
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            table_name,
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))],
+        )
+        .expect("table is created");
+    (schema_id, table_id)
+}
+
+#[rstest::rstest]
+fn rows_are_routed_by_partition_bounds(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let parent = table(&data_manager_with_schema, schema_id, "parent");
+    let low = table(&data_manager_with_schema, schema_id, "low");
+    let high = table(&data_manager_with_schema, schema_id, "high");
+
+    data_manager_with_schema
+        .partition_by_range(&Box::new(parent), "id")
+        .expect("table is partitioned");
+    data_manager_with_schema
+        .attach_range_partition(&Box::new(parent), RangePartition::new(low, None, Some(10)))
+        .expect("partition is attached");
+    data_manager_with_schema
+        .attach_range_partition(&Box::new(parent), RangePartition::new(high, Some(10), Some(20)))
+        .expect("partition is attached");
+
+    let partitioning = data_manager_with_schema
+        .range_partitioning(&Box::new(parent))
+        .expect("table is partitioned");
+    assert_eq!(partitioning.column_name(), "id");
+    assert_eq!(
+        partitioning.partition_for(-100).map(RangePartition::table_id),
+        Some(low)
+    );
+    assert_eq!(partitioning.partition_for(10).map(RangePartition::table_id), Some(high));
+    assert_eq!(partitioning.partition_for(20).map(RangePartition::table_id), None);
+    assert_eq!(
+        data_manager_with_schema.partition_bounds(&Box::new(high)),
+        Some(("id".to_owned(), RangePartition::new(high, Some(10), Some(20))))
+    );
+}
+
+#[rstest::rstest]
+fn partitions_are_dropped_with_partitioned_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let parent = table(&data_manager_with_schema, schema_id, "parent");
+    let partition = table(&data_manager_with_schema, schema_id, "partition");
+    data_manager_with_schema
+        .partition_by_range(&Box::new(parent), "id")
+        .expect("table is partitioned");
+    data_manager_with_schema
+        .attach_range_partition(&Box::new(parent), RangePartition::new(partition, None, None))
+        .expect("partition is attached");
+
+    data_manager_with_schema
+        .drop_table(&Box::new(parent))
+        .expect("table is dropped");
+
+    assert_eq!(
+        data_manager_with_schema.table_exists(&SCHEMA, &"partition"),
+        Some((schema_id, None))
+    );
+    assert_eq!(data_manager_with_schema.partition_bounds(&Box::new(partition)), None);
+}
+
+#[rstest::rstest]
+fn dropped_partition_is_detached(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let parent = table(&data_manager_with_schema, schema_id, "parent");
+    let partition = table(&data_manager_with_schema, schema_id, "partition");
+    data_manager_with_schema
+        .partition_by_range(&Box::new(parent), "id")
+        .expect("table is partitioned");
+    data_manager_with_schema
+        .attach_range_partition(&Box::new(parent), RangePartition::new(partition, Some(0), None))
+        .expect("partition is attached");
+
+    data_manager_with_schema
+        .drop_table(&Box::new(partition))
+        .expect("table is dropped");
+
+    assert_eq!(
+        data_manager_with_schema
+            .range_partitioning(&Box::new(parent))
+            .map(|partitioning| partitioning.partitions().to_vec()),
+        Some(vec![])
+    );
+}
@@ -123,3 +123,600 @@ fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
         )],
     );
 }
+
+#[rstest::rstest]
+fn committed_rows_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let columns = [ColumnDefinition::new("col_test", SqlType::Bool)];
+    let first = Box::new((
+        schema_id,
+        data_manager
+            .create_table(schema_id, "first", &columns)
+            .expect("to create a table"),
+    ));
+    let second = Box::new((
+        schema_id,
+        data_manager
+            .create_table(schema_id, "second", &columns)
+            .expect("to create a table"),
+    ));
+    let row = |id: u64| {
+        (
+            Binary::pack(&[Datum::from_u64(id)]),
+            Binary::pack(&[Datum::from_bool(id > 0)]),
+        )
+    };
+    data_manager
+        .write_into(&first, vec![row(0), row(1)])
+        .expect("values are inserted");
+    let journal = Journal::default();
+    data_manager
+        .journal_delete(&journal, &first, vec![row(0).0])
+        .expect("row is journaled");
+    data_manager
+        .journal_write(&journal, &second, vec![row(2)])
+        .expect("row is journaled");
+    data_manager.commit(&journal).expect("rows are committed");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    let rows = |table_id: (Id, Id)| {
+        data_manager
+            .full_scan(&Box::new(table_id))
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>()
+    };
+    assert_eq!(rows(*first), vec![row(1)]);
+    assert_eq!(rows(*second), vec![row(2)]);
+}
+
+#[rstest::rstest]
+fn commit_cut_by_crash_is_finished_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = Box::new((
+        schema_id,
+        data_manager
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("col_test", SqlType::Bool)],
+            )
+            .expect("to create a table"),
+    ));
+    let row = |id: u64| {
+        (
+            Binary::pack(&[Datum::from_u64(id)]),
+            Binary::pack(&[Datum::from_bool(id > 0)]),
+        )
+    };
+    data_manager
+        .write_into(&table_id, vec![row(0)])
+        .expect("values are inserted");
+
+    drop(data_manager);
+
+    // the process stopped after the record of the commit was stored and before its rows were written
+    let root_path = root_path.into_path();
+    let storage = PersistentDatabase::new(root_path.join(DEFAULT_CATALOG));
+    assert!(matches!(storage.init(COMMITS_SCHEMA), Ok(Ok(_))));
+    let mut record = CommitRecord::default();
+    record.add(
+        SCHEMA.to_owned(),
+        "table_name".to_owned(),
+        &vec![(row(0).0, None), (row(1).0, Some(row(1).1))].into_iter().collect(),
+    );
+    assert!(matches!(
+        storage.write(
+            COMMITS_SCHEMA,
+            COMMITS_OBJECT,
+            vec![(commit_key(0), record.to_values())]
+        ),
+        Ok(Ok(Ok(1)))
+    ));
+    drop(storage);
+
+    let data_manager = DataManager::persistent(root_path.clone()).expect("to create catalog manager");
+
+    assert_eq!(
+        data_manager
+            .full_scan(&table_id)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>(),
+        vec![row(1)]
+    );
+    drop(data_manager);
+
+    let storage = PersistentDatabase::new(root_path.join(DEFAULT_CATALOG));
+    assert!(matches!(storage.init(COMMITS_SCHEMA), Ok(Ok(_))));
+    assert!(matches!(
+        storage
+            .read(COMMITS_SCHEMA, COMMITS_OBJECT)
+            .map(|read| read.map(|read| read.map(|mut read| read.next().is_none()))),
+        Ok(Ok(Ok(true)))
+    ));
+}
+
+#[rstest::rstest]
+fn large_object_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let id = data_manager
+        .create_large_object(None)
+        .expect("no system errors")
+        .expect("large object created");
+    data_manager
+        .write_large_object(id, 0, &[1, 2, 3])
+        .expect("no system errors");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(
+        data_manager.read_large_object(id, 0, None).expect("no system errors"),
+        Some(vec![1, 2, 3])
+    );
+    assert_ne!(
+        data_manager.create_large_object(None).expect("no system errors"),
+        Some(id)
+    );
+}
+
+#[rstest::rstest]
+fn sequences_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    data_manager
+        .create_sequence(schema_id, "sequence_name", SequenceOptions::default())
+        .expect("no system errors")
+        .expect("sequence created");
+    data_manager
+        .next_sequence_value(schema_id, "sequence_name")
+        .expect("no system errors")
+        .expect("sequence advanced");
+    data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(1))
+                .not_null()
+                .serial("table_name_id_seq")],
+        )
+        .expect("to create a table");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+    let schema_id = data_manager.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager
+        .table_exists(&SCHEMA, &"table_name")
+        .and_then(|(_, table_id)| table_id)
+        .expect("table exists");
+
+    assert_eq!(
+        data_manager
+            .next_sequence_value(schema_id, "sequence_name")
+            .expect("no system errors"),
+        Ok(2)
+    );
+    assert_eq!(
+        data_manager
+            .table_columns(&Box::new((schema_id, table_id)))
+            .expect("no system errors")
+            .into_iter()
+            .map(|column| column.serial_sequence().map(ToOwned::to_owned))
+            .collect::<Vec<_>>(),
+        vec![Some("table_name_id_seq".to_owned())]
+    );
+    assert_eq!(
+        data_manager
+            .next_sequence_value(schema_id, "table_name_id_seq")
+            .expect("no system errors"),
+        Ok(1)
+    );
+}
+
+#[rstest::rstest]
+fn generation_expressions_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("price", SqlType::Integer(1)),
+                ColumnDefinition::new("total", SqlType::Integer(1)).generated_always_as("price * 2"),
+            ],
+        )
+        .expect("to create a table");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+    let (schema_id, table_id) = data_manager
+        .table_exists(&SCHEMA, &"table_name")
+        .and_then(|(schema_id, table_id)| table_id.map(|table_id| (schema_id, table_id)))
+        .expect("table exists");
+
+    assert_eq!(
+        data_manager
+            .table_columns(&Box::new((schema_id, table_id)))
+            .expect("no system errors")
+            .into_iter()
+            .map(|column| column.generation_expression().map(ToOwned::to_owned))
+            .collect::<Vec<_>>(),
+        vec![None, Some("price * 2".to_owned())]
+    );
+}
+
+#[rstest::rstest]
+fn enum_types_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let mood = EnumType::new("mood", vec!["sad".to_owned(), "happy".to_owned()]);
+    assert!(data_manager
+        .create_enum_type(schema_id, mood.clone())
+        .expect("no system errors"));
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("mood", SqlType::Enum).with_enum_type(mood.clone())],
+        )
+        .expect("to create a table");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.enum_type(schema_id, "mood"), Some(mood.clone()));
+    assert_eq!(
+        data_manager
+            .table_columns(&Box::new((schema_id, table_id)))
+            .expect("no system errors"),
+        vec![ColumnDefinition::new("mood", SqlType::Enum).with_enum_type(mood)]
+    );
+}
+
+#[rstest::rstest]
+fn functions_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let add = SqlFunction::new(
+        "add",
+        vec![
+            ("a".to_owned(), SqlType::Integer(i32::min_value())),
+            ("b".to_owned(), SqlType::Integer(i32::min_value())),
+        ],
+        SqlType::Integer(i32::min_value()),
+        "select a + b",
+    );
+    assert!(data_manager
+        .create_function(schema_id, add.clone(), false)
+        .expect("no system errors"));
+    assert!(!data_manager
+        .create_function(schema_id, add.clone(), false)
+        .expect("no system errors"));
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.function(schema_id, "add"), Some(add));
+    assert!(data_manager.drop_function(schema_id, "add").expect("no system errors"));
+    assert_eq!(data_manager.function(schema_id, "add"), None);
+}
+
+#[rstest::rstest]
+fn range_partitions_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let columns = [ColumnDefinition::new("id", SqlType::Integer(i32::MIN))];
+    let table = |table_name: &str| {
+        let table_id = data_manager
+            .create_table(schema_id, table_name, &columns)
+            .expect("to create a table");
+        (schema_id, table_id)
+    };
+    let (parent, low, high, dropped) = (table("parent"), table("low"), table("high"), table("dropped"));
+    data_manager
+        .partition_by_range(&Box::new(parent), "id")
+        .expect("table is partitioned");
+    for partition in [
+        RangePartition::new(low, None, Some(10)),
+        RangePartition::new(high, Some(10), None),
+        RangePartition::new(dropped, Some(-20), Some(-10)),
+    ] {
+        data_manager
+            .attach_range_partition(&Box::new(parent), partition)
+            .expect("partition is attached");
+    }
+    data_manager
+        .drop_table(&Box::new(dropped))
+        .expect("partition is dropped");
+
+    drop(data_manager);
+
+    let root_path = root_path.into_path();
+    let data_manager = DataManager::persistent(root_path.clone()).expect("to create catalog manager");
+
+    let partitioning = data_manager
+        .range_partitioning(&Box::new(parent))
+        .expect("table is partitioned");
+    assert_eq!(partitioning.column_name(), "id");
+    assert_eq!(
+        partitioning.partitions(),
+        &[
+            RangePartition::new(low, None, Some(10)),
+            RangePartition::new(high, Some(10), None)
+        ]
+    );
+    assert_eq!(
+        data_manager.partition_bounds(&Box::new(high)),
+        Some(("id".to_owned(), RangePartition::new(high, Some(10), None)))
+    );
+
+    data_manager.drop_table(&Box::new(parent)).expect("table is dropped");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path).expect("to create catalog manager");
+    assert_eq!(data_manager.range_partitioning(&Box::new(parent)), None);
+}
+
+#[rstest::rstest]
+fn statistics_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "addresses",
+            &[
+                ColumnDefinition::new("city", SqlType::VarChar(255)),
+                ColumnDefinition::new("zip", SqlType::Integer(i32::MIN)),
+            ],
+        )
+        .expect("to create a table");
+    let table_id = (schema_id, table_id);
+    let columns = ["city".to_owned(), "zip".to_owned()];
+    let rows = vec![
+        vec![Datum::from_str("Kyiv"), Datum::from_i32(1)],
+        vec![Datum::from_str("Lviv"), Datum::from_i32(2)],
+    ];
+    let statistics = ExtendedStatistics::new(
+        table_id,
+        columns.to_vec(),
+        vec![StatisticsKind::NDistinct, StatisticsKind::Dependencies],
+    )
+    .build(&rows);
+    let analyzed = TableStatistics::build(&columns, &rows);
+    assert!(data_manager
+        .create_statistics(schema_id, "city_zip", statistics.clone())
+        .expect("statistics are created"));
+    data_manager
+        .set_analyzed(&Box::new(table_id), analyzed.clone())
+        .expect("table is analyzed");
+
+    drop(data_manager);
+
+    let root_path = root_path.into_path();
+    let data_manager = DataManager::persistent(root_path.clone()).expect("to create catalog manager");
+
+    assert_eq!(
+        data_manager.table_statistics(&Box::new(table_id)),
+        vec![((schema_id, "city_zip".to_owned()), statistics)]
+    );
+    assert_eq!(data_manager.analyzed(&Box::new(table_id)), Some(analyzed));
+
+    assert!(data_manager
+        .drop_statistics(schema_id, "city_zip")
+        .expect("statistics are dropped"));
+    data_manager.drop_table(&Box::new(table_id)).expect("table is dropped");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path).expect("to create catalog manager");
+    assert_eq!(data_manager.table_statistics(&Box::new(table_id)), vec![]);
+    assert_eq!(data_manager.analyzed(&Box::new(table_id)), None);
+}
+
+#[rstest::rstest]
+fn schema_owner_and_privileges_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let other_schema_id = data_manager.create_schema("other_schema").expect("to create a schema");
+    data_manager
+        .alter_schema_owner(schema_id, "owner")
+        .expect("owner is changed");
+    data_manager
+        .grant_schema_privileges(schema_id, "reader", &[SchemaPrivilege::Usage, SchemaPrivilege::Create])
+        .expect("privileges are granted");
+    data_manager
+        .revoke_schema_privileges(schema_id, "reader", &[SchemaPrivilege::Create])
+        .expect("privilege is revoked");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.schema_owner(schema_id), Some("owner".to_owned()));
+    assert!(data_manager.has_schema_privilege(schema_id, "reader", SchemaPrivilege::Usage));
+    assert!(!data_manager.has_schema_privilege(schema_id, "reader", SchemaPrivilege::Create));
+    assert_eq!(data_manager.schema_owner(other_schema_id), Some(SUPERUSER.to_owned()));
+    assert!(!data_manager.has_schema_privilege(other_schema_id, "reader", SchemaPrivilege::Usage));
+}
+
+#[rstest::rstest]
+fn backend_is_selected_by_name() {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    assert_eq!(
+        StorageBackend::from_name("in-memory", root_path.path().to_path_buf()),
+        Some(StorageBackend::InMemory)
+    );
+    assert_eq!(
+        StorageBackend::from_name("Sled", root_path.path().to_path_buf()),
+        Some(StorageBackend::Sled(root_path.path().to_path_buf()))
+    );
+    assert_eq!(
+        StorageBackend::from_name("unknown", root_path.path().to_path_buf()),
+        None
+    );
+
+    let data_manager =
+        DataManager::open(StorageBackend::Sled(root_path.path().to_path_buf())).expect("to create catalog manager");
+    data_manager.create_schema(SCHEMA).expect("to create a schema");
+    drop(data_manager);
+
+    let data_manager =
+        DataManager::open(StorageBackend::Sled(root_path.into_path())).expect("to create catalog manager");
+    assert!(matches!(data_manager.schema_exists(&SCHEMA), Some(_)));
+}
+
+#[cfg(feature = "rocksdb")]
+mod rocks_db {
+    use super::*;
+
+    #[rstest::fixture]
+    fn rocks_db() -> (DataManager, TempDir) {
+        let root_path = tempfile::tempdir().expect("to create temp folder");
+        (
+            DataManager::open(StorageBackend::RocksDb(PathBuf::from(root_path.path())))
+                .expect("to create catalog manager"),
+            root_path,
+        )
+    }
+
+    #[rstest::rstest]
+    fn backend_is_selected_by_name() {
+        let root_path = tempfile::tempdir().expect("to create temp folder");
+        assert_eq!(
+            StorageBackend::from_name("RocksDB", root_path.path().to_path_buf()),
+            Some(StorageBackend::RocksDb(root_path.path().to_path_buf()))
+        );
+    }
+
+    #[rstest::rstest]
+    fn created_schema_is_preserved_after_restart(rocks_db: (DataManager, TempDir)) {
+        let (data_manager, root_path) = rocks_db;
+        data_manager.create_schema(SCHEMA).expect("to create a schema");
+        assert!(data_manager.schema_exists(&SCHEMA).is_some());
+
+        drop(data_manager);
+
+        let data_manager =
+            DataManager::open(StorageBackend::RocksDb(root_path.into_path())).expect("to create catalog manager");
+
+        assert!(data_manager.schema_exists(&SCHEMA).is_some());
+    }
+
+    #[rstest::rstest]
+    fn created_table_is_preserved_after_restart(rocks_db: (DataManager, TempDir)) {
+        let (data_manager, root_path) = rocks_db;
+        let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+        let table_id = data_manager
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("col_test", SqlType::Bool)],
+            )
+            .expect("to create a table");
+
+        drop(data_manager);
+
+        let data_manager =
+            DataManager::open(StorageBackend::RocksDb(root_path.into_path())).expect("to create catalog manager");
+
+        assert!(matches!(
+            data_manager.table_exists(&SCHEMA, &"table_name"),
+            Some((_, Some(_)))
+        ));
+        assert_eq!(
+            data_manager
+                .table_columns(&Box::new((schema_id, table_id)))
+                .expect("to have a columns"),
+            vec![ColumnDefinition::new("col_test", SqlType::Bool)]
+        )
+    }
+
+    #[rstest::rstest]
+    fn stored_data_is_preserved_after_restart(rocks_db: (DataManager, TempDir)) {
+        let (data_manager, root_path) = rocks_db;
+        let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+        let table_id = data_manager
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("col_test", SqlType::Bool)],
+            )
+            .expect("to create a table");
+        data_manager
+            .write_into(
+                &Box::new((schema_id, table_id)),
+                vec![
+                    (
+                        Binary::pack(&[Datum::from_u64(0)]),
+                        Binary::pack(&[Datum::from_bool(true)]),
+                    ),
+                    (
+                        Binary::pack(&[Datum::from_u64(1)]),
+                        Binary::pack(&[Datum::from_bool(false)]),
+                    ),
+                ],
+            )
+            .expect("values are inserted");
+        data_manager
+            .delete_from(
+                &Box::new((schema_id, table_id)),
+                vec![Binary::pack(&[Datum::from_u64(1)])],
+            )
+            .expect("values are deleted");
+
+        drop(data_manager);
+
+        let data_manager =
+            DataManager::open(StorageBackend::RocksDb(root_path.into_path())).expect("to create catalog manager");
+
+        assert_eq!(
+            data_manager
+                .full_scan(&Box::new((schema_id, table_id)))
+                .expect("to scan a table")
+                .map(|item| item.expect("no io error").expect("no platform error"))
+                .collect::<Vec<Row>>(),
+            vec![(
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_bool(true)]),
+            )],
+        );
+    }
+
+    #[rstest::rstest]
+    fn dropped_table_is_not_loaded_after_restart(rocks_db: (DataManager, TempDir)) {
+        let (data_manager, root_path) = rocks_db;
+        let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+        let table_id = data_manager
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("col_test", SqlType::Bool)],
+            )
+            .expect("to create a table");
+        data_manager
+            .drop_table(&Box::new((schema_id, table_id)))
+            .expect("to drop a table");
+
+        drop(data_manager);
+
+        let data_manager =
+            DataManager::open(StorageBackend::RocksDb(root_path.into_path())).expect("to create catalog manager");
+
+        assert!(matches!(
+            data_manager.table_exists(&SCHEMA, &"table_name"),
+            Some((_, None))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+const ROLE: &str = "some_role";
+
+#[rstest::rstest]
+fn schema_is_owned_by_superuser_by_default(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+
+    assert_eq!(data_manager.schema_owner(schema_id), Some(SUPERUSER.to_owned()));
+}
+
+#[rstest::rstest]
+fn superuser_has_all_privileges(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+
+    assert!(data_manager.has_schema_privilege(schema_id, SUPERUSER, SchemaPrivilege::Usage));
+    assert!(data_manager.has_schema_privilege(schema_id, SUPERUSER, SchemaPrivilege::Create));
+}
+
+#[rstest::rstest]
+fn role_without_grants_has_no_privileges(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+
+    assert!(!data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Usage));
+    assert!(!data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Create));
+}
+
+#[rstest::rstest]
+fn owner_has_all_privileges(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+
+    data_manager
+        .alter_schema_owner(schema_id, ROLE)
+        .expect("owner is changed");
+
+    assert_eq!(data_manager.schema_owner(schema_id), Some(ROLE.to_owned()));
+    assert!(data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Usage));
+    assert!(data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Create));
+}
+
+#[rstest::rstest]
+fn grant_and_revoke_privileges(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+
+    data_manager
+        .grant_schema_privileges(schema_id, ROLE, &[SchemaPrivilege::Usage, SchemaPrivilege::Create])
+        .expect("privileges are granted");
+    assert!(data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Usage));
+    assert!(data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Create));
+
+    data_manager
+        .revoke_schema_privileges(schema_id, ROLE, &[SchemaPrivilege::Create])
+        .expect("privileges are revoked");
+    assert!(data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Usage));
+    assert!(!data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Create));
+}
+
+#[rstest::rstest]
+fn privileges_of_dropped_schema_are_forgotten(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("schema is created");
+    data_manager
+        .grant_schema_privileges(schema_id, ROLE, &[SchemaPrivilege::Usage])
+        .expect("privileges are granted");
+
+    data_manager
+        .drop_schema(&Box::new(schema_id), DropStrategy::Restrict)
+        .expect("no system errors")
+        .expect("schema is dropped");
+
+    assert_eq!(data_manager.schema_owner(schema_id), None);
+    assert!(!data_manager.has_schema_privilege(schema_id, ROLE, SchemaPrivilege::Usage));
+}
@@ -12,6 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
 use representation::{Binary, Datum};
 use sql_model::sql_types::SqlType;
 
@@ -128,3 +137,448 @@ fn select_all_from_table_with_many_columns(with_small_ints_table: DataManager) {
         ])])
     );
 }
+
+#[rstest::rstest]
+fn read_row_by_its_key(with_small_ints_table: DataManager) {
+    let (schema_id, table_id) = with_small_ints_table
+        .table_exists(&SCHEMA, &"table_name")
+        .and_then(|(schema_id, table_id)| table_id.map(|table_id| (schema_id, table_id)))
+        .expect("table exists");
+    let table_id = Box::new((schema_id, table_id));
+    with_small_ints_table
+        .write_into(
+            &table_id,
+            vec![
+                (
+                    Binary::pack(&[Datum::from_u64(1)]),
+                    Binary::pack(&[Datum::from_i16(1), Datum::from_i16(2), Datum::from_i16(3)]),
+                ),
+                (
+                    Binary::pack(&[Datum::from_u64(3)]),
+                    Binary::pack(&[Datum::from_i16(4), Datum::from_i16(5), Datum::from_i16(6)]),
+                ),
+            ],
+        )
+        .expect("values are inserted");
+
+    assert_eq!(
+        with_small_ints_table
+            .read_row(None, &table_id, Binary::pack(&[Datum::from_u64(3)]))
+            .map(|row| row.map(Result::unwrap).map(Result::unwrap)),
+        Ok(Some((
+            Binary::pack(&[Datum::from_u64(3)]),
+            Binary::pack(&[Datum::from_i16(4), Datum::from_i16(5), Datum::from_i16(6)])
+        )))
+    );
+    assert_eq!(
+        with_small_ints_table
+            .read_row(None, &table_id, Binary::pack(&[Datum::from_u64(2)]))
+            .map(|row| row.map(Result::unwrap).map(Result::unwrap)),
+        Ok(None)
+    );
+}
+
+#[rstest::rstest]
+fn rows_written_into_journal_are_committed(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new(
+                "column_test",
+                SqlType::SmallInt(i16::min_value()),
+            )],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let row = |key: u64, value: i16| {
+        (
+            Binary::pack(&[Datum::from_u64(key)]),
+            Binary::pack(&[Datum::from_i16(value)]),
+        )
+    };
+    data_manager_with_schema
+        .write_into(&table_id, vec![row(1, 123), row(2, 456)])
+        .expect("values are inserted");
+
+    let journal = Journal::default();
+    data_manager_with_schema
+        .journal_write(&journal, &table_id, vec![row(1, 789), row(3, 101)])
+        .expect("values are written into journal");
+    data_manager_with_schema
+        .journal_delete(&journal, &table_id, vec![row(2, 456).0])
+        .expect("values are deleted in journal");
+
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 123), row(2, 456)])
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .journaled_scan(&journal, &table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 789), row(3, 101)])
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .scan(&table_id, (Bound::Unbounded, Bound::Unbounded), 1)
+            .with_journal(Some(&journal))
+            .collect::<SystemResult<Vec<Result<Vec<Row>, StorageError>>>>(),
+        Ok(vec![Ok(vec![row(1, 789)]), Ok(vec![row(3, 101)])])
+    );
+
+    data_manager_with_schema.commit(&journal).expect("rows are written");
+
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 789), row(3, 101)])
+    );
+}
+
+#[rstest::rstest]
+fn roll_back_rows_written_after_savepoint(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new(
+                "column_test",
+                SqlType::SmallInt(i16::min_value()),
+            )],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let row = |key: u64, value: i16| {
+        (
+            Binary::pack(&[Datum::from_u64(key)]),
+            Binary::pack(&[Datum::from_i16(value)]),
+        )
+    };
+
+    let journal = Journal::default();
+    data_manager_with_schema
+        .journal_write(&journal, &table_id, vec![row(1, 123)])
+        .expect("values are inserted");
+    journal.savepoint("savepoint_name");
+    data_manager_with_schema
+        .journal_write(&journal, &table_id, vec![row(2, 456)])
+        .expect("values are inserted");
+
+    assert_eq!(
+        data_manager_with_schema.roll_back_to_savepoint(&journal, "savepoint_name"),
+        Ok(true)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .journaled_scan(&journal, &table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 123)])
+    );
+
+    assert!(journal.release("savepoint_name"));
+    assert_eq!(
+        data_manager_with_schema.roll_back_to_savepoint(&journal, "savepoint_name"),
+        Ok(false)
+    );
+
+    data_manager_with_schema.roll_back(&journal).expect("rows are dropped");
+    assert_eq!(
+        data_manager_with_schema
+            .journaled_scan(&journal, &table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![])
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(&table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![])
+    );
+}
+
+#[rstest::rstest]
+fn table_is_locked_until_owner_unlocks_it(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new(
+                "column_test",
+                SqlType::SmallInt(i16::min_value()),
+            )],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let data_manager = Arc::new(data_manager_with_schema);
+    let owner = data_manager.lock_owner();
+    assert_eq!(
+        data_manager.lock_table(&table_id, owner, &|| false),
+        TableLock::Acquired
+    );
+    assert_eq!(
+        data_manager.lock_table(&table_id, owner, &|| false),
+        TableLock::Acquired
+    );
+
+    let (locked, waiting) = mpsc::channel();
+    let other = {
+        let data_manager = data_manager.clone();
+        let table_id = table_id.clone();
+        thread::spawn(move || {
+            let owner = data_manager.lock_owner();
+            assert_eq!(
+                data_manager.lock_table(&table_id, owner, &|| false),
+                TableLock::Acquired
+            );
+            locked.send(owner).expect("to send owner");
+        })
+    };
+
+    assert!(waiting.recv_timeout(Duration::from_millis(100)).is_err());
+    data_manager.unlock_tables(owner);
+    assert!(waiting.recv_timeout(Duration::from_secs(10)).is_ok());
+    other.join().expect("thread is finished");
+}
+
+#[rstest::rstest]
+fn lock_that_waits_for_itself_is_not_acquired(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_ids = ["first_table", "second_table"]
+        .iter()
+        .map(|table_name| {
+            let table_id = data_manager_with_schema
+                .create_table(
+                    schema_id,
+                    table_name,
+                    &[ColumnDefinition::new(
+                        "column_test",
+                        SqlType::SmallInt(i16::min_value()),
+                    )],
+                )
+                .expect("table is created");
+            Box::new((schema_id, table_id))
+        })
+        .collect::<Vec<_>>();
+    let data_manager = Arc::new(data_manager_with_schema);
+    let owner = data_manager.lock_owner();
+    let other_owner = data_manager.lock_owner();
+    assert_eq!(
+        data_manager.lock_table(&table_ids[0], owner, &|| false),
+        TableLock::Acquired
+    );
+    assert_eq!(
+        data_manager.lock_table(&table_ids[1], other_owner, &|| false),
+        TableLock::Acquired
+    );
+
+    let (locked, waiting) = mpsc::channel();
+    let other = {
+        let data_manager = data_manager.clone();
+        let table_id = table_ids[0].clone();
+        thread::spawn(move || {
+            let acquired = data_manager.lock_table(&table_id, other_owner, &|| false);
+            locked.send(acquired).expect("to send lock result");
+        })
+    };
+    assert!(waiting.recv_timeout(Duration::from_millis(100)).is_err());
+
+    assert_eq!(
+        data_manager.lock_table(&table_ids[1], owner, &|| false),
+        TableLock::Deadlock
+    );
+    data_manager.unlock_tables(owner);
+    assert_eq!(waiting.recv_timeout(Duration::from_secs(10)), Ok(TableLock::Acquired));
+    other.join().expect("thread is finished");
+}
+
+#[rstest::rstest]
+fn owner_stops_waiting_for_lock_when_it_is_canceled(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let data_manager = Arc::new(data_manager_with_schema);
+    let owner = data_manager.lock_owner();
+    assert_eq!(
+        data_manager.lock_table(&table_id, owner, &|| false),
+        TableLock::Acquired
+    );
+
+    let canceled = Arc::new(AtomicBool::new(false));
+    let (locked, waiting) = mpsc::channel();
+    let other = {
+        let data_manager = data_manager.clone();
+        let table_id = table_id.clone();
+        let canceled = canceled.clone();
+        thread::spawn(move || {
+            let other_owner = data_manager.lock_owner();
+            let acquired = data_manager.lock_table(&table_id, other_owner, &|| canceled.load(Ordering::SeqCst));
+            locked.send(acquired).expect("to send lock result");
+        })
+    };
+    assert!(waiting.recv_timeout(Duration::from_millis(100)).is_err());
+
+    canceled.store(true, Ordering::SeqCst);
+    assert_eq!(waiting.recv_timeout(Duration::from_secs(10)), Ok(TableLock::Canceled));
+    other.join().expect("thread is finished");
+    data_manager.unlock_tables(owner);
+}
+
+#[rstest::rstest]
+fn roll_back_created_and_dropped_tables(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let columns = [ColumnDefinition::new(
+        "column_test",
+        SqlType::SmallInt(i16::min_value()),
+    )];
+    let dropped_id = data_manager_with_schema
+        .create_table(schema_id, "dropped_table", &columns)
+        .expect("table is created");
+    data_manager_with_schema
+        .write_into(
+            &Box::new((schema_id, dropped_id)),
+            vec![(
+                Binary::pack(&[Datum::from_u64(1)]),
+                Binary::pack(&[Datum::from_i16(123)]),
+            )],
+        )
+        .expect("values are inserted");
+
+    let journal = Journal::default();
+    let created_id = data_manager_with_schema
+        .create_table(schema_id, "created_table", &columns)
+        .expect("table is created");
+    data_manager_with_schema.journal_created_table(&journal, &Box::new((schema_id, created_id)));
+    data_manager_with_schema.journal_dropped_table(&journal, &Box::new((schema_id, dropped_id)));
+
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(Some(&journal), &SCHEMA, &"created_table"),
+        Some((schema_id, Some(created_id)))
+    );
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(None, &SCHEMA, &"created_table"),
+        Some((schema_id, None))
+    );
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(Some(&journal), &SCHEMA, &"dropped_table"),
+        Some((schema_id, None))
+    );
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(None, &SCHEMA, &"dropped_table"),
+        Some((schema_id, Some(dropped_id)))
+    );
+
+    data_manager_with_schema
+        .roll_back(&journal)
+        .expect("tables are restored");
+
+    assert_eq!(
+        data_manager_with_schema.table_exists(&SCHEMA, &"created_table"),
+        Some((schema_id, None))
+    );
+    assert_eq!(
+        data_manager_with_schema.table_exists(&SCHEMA, &"dropped_table"),
+        Some((schema_id, Some(dropped_id)))
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(&Box::new((schema_id, dropped_id)))
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![(
+            Binary::pack(&[Datum::from_u64(1)]),
+            Binary::pack(&[Datum::from_i16(123)]),
+        )])
+    );
+}
+
+#[rstest::rstest]
+fn commit_created_and_dropped_tables(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let columns = [ColumnDefinition::new(
+        "column_test",
+        SqlType::SmallInt(i16::min_value()),
+    )];
+    let dropped_id = data_manager_with_schema
+        .create_table(schema_id, "dropped_table", &columns)
+        .expect("table is created");
+
+    let journal = Journal::default();
+    let created_id = data_manager_with_schema
+        .create_table(schema_id, "created_table", &columns)
+        .expect("table is created");
+    data_manager_with_schema.journal_created_table(&journal, &Box::new((schema_id, created_id)));
+    data_manager_with_schema.journal_dropped_table(&journal, &Box::new((schema_id, dropped_id)));
+
+    data_manager_with_schema.commit(&journal).expect("tables are committed");
+
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(None, &SCHEMA, &"created_table"),
+        Some((schema_id, Some(created_id)))
+    );
+    assert_eq!(
+        data_manager_with_schema.table_exists_in(None, &SCHEMA, &"dropped_table"),
+        Some((schema_id, None))
+    );
+}
+
+#[rstest::rstest]
+fn scan_reads_rows_with_keys_in_range_by_batches(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value())).primary_key()],
+        )
+        .expect("table is created");
+    let table_id = Box::new((schema_id, table_id));
+    let key = |id: i32| Binary::pack_key(&[Datum::from_i32(id)]);
+    data_manager_with_schema
+        .write_into(
+            &table_id,
+            (-2..=2)
+                .map(|id| (key(id), Binary::pack_row(&[Datum::from_i32(id)])))
+                .collect(),
+        )
+        .expect("values are inserted");
+    let batches = |range: KeyRange| {
+        data_manager_with_schema
+            .scan(&table_id, range, 2)
+            .map(|batch| {
+                batch
+                    .expect("no system errors")
+                    .expect("no storage errors")
+                    .into_iter()
+                    .map(|(_key, values)| values.unpack()[0].as_i32())
+                    .collect::<Vec<i32>>()
+            })
+            .collect::<Vec<Vec<i32>>>()
+    };
+
+    assert_eq!(
+        batches((Bound::Unbounded, Bound::Unbounded)),
+        vec![vec![-2, -1], vec![0, 1], vec![2]]
+    );
+    assert_eq!(
+        batches((Bound::Included(key(-1)), Bound::Excluded(key(2)))),
+        vec![vec![-1, 0], vec![1]]
+    );
+    assert_eq!(batches((Bound::Excluded(key(0)), Bound::Unbounded)), vec![vec![1, 2]]);
+    assert_eq!(batches((Bound::Unbounded, Bound::Included(key(-2)))), vec![vec![-2]]);
+    assert_eq!(
+        batches((Bound::Included(key(1)), Bound::Excluded(key(-1)))),
+        Vec::<Vec<i32>>::new()
+    );
+}
@@ -143,3 +143,22 @@ fn cascade_drop_schema_drops_tables_in_it(data_manager_with_schema: DataManager)
         Ok(_)
     ));
 }
+
+#[rstest::rstest]
+fn schema_and_table_names(data_manager: DataManager) {
+    let schema_2_id = data_manager.create_schema(SCHEMA_2).expect("schema is created");
+    data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    let table_id = data_manager
+        .create_table(schema_2_id, "table_name", &[])
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager.schema_names(),
+        vec![SCHEMA_1.to_owned(), SCHEMA_2.to_owned()]
+    );
+    assert_eq!(
+        data_manager.table_names(),
+        vec![(SCHEMA_2.to_owned(), "table_name".to_owned())]
+    );
+    assert_eq!(data_manager.table_ids(), vec![(schema_2_id, table_id)]);
+}
@@ -0,0 +1,128 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use representation::{Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn with_template(data_manager: &DataManager) -> Id {
+    let schema_id = data_manager.schema_exists(&SCHEMA).expect("schema exists");
+    let customers = data_manager
+        .create_table(
+            schema_id,
+            "customers",
+            &[ColumnDefinition::new("id", SqlType::Integer(i32::min_value()))
+                .primary_key()
+                .serial("customers_id_seq")],
+        )
+        .expect("table is created");
+    data_manager
+        .create_table(
+            schema_id,
+            "orders",
+            &[
+                ColumnDefinition::new("customer_id", SqlType::Integer(i32::min_value())).references(
+                    ColumnReference::new(SCHEMA, "customers", "id", ReferentialAction::Cascade),
+                ),
+            ],
+        )
+        .expect("table is created");
+    for _ in 0..2 {
+        let id = data_manager
+            .next_sequence_value(schema_id, "customers_id_seq")
+            .expect("no system errors")
+            .expect("sequence is advanced");
+        data_manager
+            .write_into(
+                &Box::new((schema_id, customers)),
+                vec![(
+                    Binary::pack(&[Datum::from_i32(id as i32)]),
+                    Binary::pack(&[Datum::from_i32(id as i32)]),
+                )],
+            )
+            .expect("values are inserted");
+    }
+    schema_id
+}
+
+fn rows(data_manager: &DataManager, schema_name: &str, table_name: &str) -> Vec<Row> {
+    let table_id = match data_manager.table_exists(&schema_name, &table_name) {
+        Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        _ => panic!("table {}.{} exists", schema_name, table_name),
+    };
+    data_manager
+        .full_scan(&Box::new(table_id))
+        .expect("no system errors")
+        .map(|row| row.expect("no io error").expect("no platform error"))
+        .collect()
+}
+
+#[rstest::rstest]
+fn tables_are_copied_with_references_to_new_schema(data_manager_with_schema: DataManager) {
+    let template_id = with_template(&data_manager_with_schema);
+
+    let schema_id = data_manager_with_schema
+        .copy_schema(template_id, SCHEMA_1, false)
+        .expect("schema is copied");
+
+    assert_eq!(data_manager_with_schema.schema_exists(&SCHEMA_1), Some(schema_id));
+    let orders = match data_manager_with_schema.table_exists(&SCHEMA_1, &"orders") {
+        Some((_, Some(table_id))) => table_id,
+        _ => panic!("orders table is copied"),
+    };
+    let columns = data_manager_with_schema
+        .table_columns(&Box::new((schema_id, orders)))
+        .expect("no system errors");
+    assert_eq!(
+        columns[0].referenced_column().map(ColumnReference::schema_name),
+        Some(SCHEMA_1)
+    );
+    assert_eq!(rows(&data_manager_with_schema, SCHEMA_1, "customers"), vec![]);
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, "customers_id_seq")
+            .expect("no system errors"),
+        Ok(1)
+    );
+}
+
+#[rstest::rstest]
+fn rows_and_sequences_are_copied_with_data(data_manager_with_schema: DataManager) {
+    let template_id = with_template(&data_manager_with_schema);
+
+    let schema_id = data_manager_with_schema
+        .copy_schema(template_id, SCHEMA_1, true)
+        .expect("schema is copied");
+
+    assert_eq!(
+        rows(&data_manager_with_schema, SCHEMA_1, "customers"),
+        vec![
+            (Binary::pack(&[Datum::from_i32(1)]), Binary::pack(&[Datum::from_i32(1)])),
+            (Binary::pack(&[Datum::from_i32(2)]), Binary::pack(&[Datum::from_i32(2)])),
+        ]
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, "customers_id_seq")
+            .expect("no system errors"),
+        Ok(3)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(template_id, "customers_id_seq")
+            .expect("no system errors"),
+        Ok(3)
+    );
+}
@@ -0,0 +1,265 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+const SEQUENCE: &str = "sequence_name";
+
+#[rstest::rstest]
+fn next_values_of_created_sequence(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    assert_eq!(
+        data_manager_with_schema
+            .create_sequence(schema_id, SEQUENCE, SequenceOptions::default().start(10).increment(5))
+            .expect("no system errors"),
+        Ok(())
+    );
+
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(10)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(15)
+    );
+}
+
+#[rstest::rstest]
+fn create_sequence_with_the_same_name(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_sequence(schema_id, SEQUENCE, SequenceOptions::default())
+        .expect("no system errors")
+        .expect("sequence created");
+
+    assert_eq!(
+        data_manager_with_schema
+            .create_sequence(schema_id, SEQUENCE, SequenceOptions::default())
+            .expect("no system errors"),
+        Err(SequenceError::AlreadyExists)
+    );
+}
+
+#[rstest::rstest]
+fn create_sequence_with_invalid_options(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+
+    assert_eq!(
+        data_manager_with_schema
+            .create_sequence(schema_id, SEQUENCE, SequenceOptions::default().increment(0))
+            .expect("no system errors"),
+        Err(SequenceError::ZeroIncrement)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .create_sequence(
+                schema_id,
+                SEQUENCE,
+                SequenceOptions::default().min_value(5).max_value(5)
+            )
+            .expect("no system errors"),
+        Err(SequenceError::InvalidBounds {
+            min_value: 5,
+            max_value: 5
+        })
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .create_sequence(schema_id, SEQUENCE, SequenceOptions::default().max_value(10).start(11))
+            .expect("no system errors"),
+        Err(SequenceError::StartOutOfBounds {
+            start: 11,
+            min_value: 1,
+            max_value: 10
+        })
+    );
+}
+
+#[rstest::rstest]
+fn descending_sequence_reaches_min_value(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_sequence(
+            schema_id,
+            SEQUENCE,
+            SequenceOptions::default().increment(-1).min_value(-2).max_value(-1),
+        )
+        .expect("no system errors")
+        .expect("sequence created");
+
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(-1)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(-2)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Err(SequenceError::MinValueReached(-2))
+    );
+}
+
+#[rstest::rstest]
+fn set_sequence_value(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_sequence(schema_id, SEQUENCE, SequenceOptions::default().max_value(100))
+        .expect("no system errors")
+        .expect("sequence created");
+
+    assert_eq!(
+        data_manager_with_schema
+            .set_sequence_value(schema_id, SEQUENCE, 50, true)
+            .expect("no system errors"),
+        Ok(50)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(51)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .set_sequence_value(schema_id, SEQUENCE, 20, false)
+            .expect("no system errors"),
+        Ok(20)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Ok(20)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .set_sequence_value(schema_id, SEQUENCE, 101, true)
+            .expect("no system errors"),
+        Err(SequenceError::ValueOutOfBounds {
+            value: 101,
+            min_value: 1,
+            max_value: 100
+        })
+    );
+}
+
+#[rstest::rstest]
+fn dropped_sequence_does_not_exist(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_sequence(schema_id, SEQUENCE, SequenceOptions::default())
+        .expect("no system errors")
+        .expect("sequence created");
+
+    assert!(data_manager_with_schema
+        .drop_sequence(schema_id, SEQUENCE)
+        .expect("no system errors"));
+    assert!(!data_manager_with_schema.sequence_exists(schema_id, SEQUENCE));
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, SEQUENCE)
+            .expect("no system errors"),
+        Err(SequenceError::DoesNotExist)
+    );
+    assert!(!data_manager_with_schema
+        .drop_sequence(schema_id, SEQUENCE)
+        .expect("no system errors"));
+}
+
+#[rstest::rstest]
+fn serial_column_sequence_is_owned_by_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::SmallInt(1))
+                .not_null()
+                .serial("table_name_id_seq")],
+        )
+        .expect("table created");
+    assert!(data_manager_with_schema.sequence_exists(schema_id, "table_name_id_seq"));
+    assert_eq!(
+        data_manager_with_schema
+            .set_sequence_value(schema_id, "table_name_id_seq", i16::max_value() as i64, true)
+            .expect("no system errors"),
+        Ok(i16::max_value() as i64)
+    );
+    assert_eq!(
+        data_manager_with_schema
+            .next_sequence_value(schema_id, "table_name_id_seq")
+            .expect("no system errors"),
+        Err(SequenceError::MaxValueReached(i16::max_value() as i64))
+    );
+
+    data_manager_with_schema
+        .drop_table(&Box::new((schema_id, table_id)))
+        .expect("table dropped");
+
+    assert!(!data_manager_with_schema.sequence_exists(schema_id, "table_name_id_seq"));
+}
+
+#[rstest::rstest]
+fn defaults_of_serial_columns_depend_on_sequence(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_sequence(schema_id, SEQUENCE, SequenceOptions::default())
+        .expect("no system errors")
+        .expect("sequence created");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("id", SqlType::Integer(1))
+                    .not_null()
+                    .serial("table_name_id_seq"),
+                ColumnDefinition::new("name", SqlType::SmallInt(1)),
+            ],
+        )
+        .expect("table created");
+
+    assert_eq!(
+        data_manager_with_schema.sequence_dependents(schema_id, "table_name_id_seq"),
+        vec![("table_name".to_owned(), "id".to_owned())]
+    );
+    assert_eq!(
+        data_manager_with_schema.sequence_dependents(schema_id, SEQUENCE),
+        vec![]
+    );
+
+    data_manager_with_schema
+        .drop_table(&Box::new((schema_id, table_id)))
+        .expect("table dropped");
+
+    assert_eq!(
+        data_manager_with_schema.sequence_dependents(schema_id, "table_name_id_seq"),
+        vec![]
+    );
+}
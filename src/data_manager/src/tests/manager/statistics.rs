@@ -0,0 +1,217 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_model::sql_types::SqlType;
+
+use super::*;
+
+fn table(data_manager: &DataManager, schema_id: Id) -> (Id, Id) {
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "addresses",
+            &[
+                ColumnDefinition::new("city", SqlType::VarChar(255)),
+                ColumnDefinition::new("zip", SqlType::Integer(i32::min_value())),
+            ],
+        )
+        .expect("table is created");
+    (schema_id, table_id)
+}
+
+fn statistics(table_id: (Id, Id)) -> ExtendedStatistics {
+    ExtendedStatistics::new(
+        table_id,
+        vec!["city".to_owned(), "zip".to_owned()],
+        vec![StatisticsKind::NDistinct, StatisticsKind::Dependencies],
+    )
+}
+
+fn rows(values: Vec<(&'static str, i32)>) -> Vec<Vec<Datum<'static>>> {
+    values
+        .into_iter()
+        .map(|(city, zip)| vec![Datum::from_str(city), Datum::from_i32(zip)])
+        .collect()
+}
+
+#[rstest::rstest]
+fn dependencies_are_built_from_rows(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+
+    let statistics = statistics(table_id).build(&rows(vec![("Kyiv", 1), ("Kyiv", 2), ("Lviv", 3), ("Lviv", 3)]));
+
+    assert_eq!(statistics.n_distinct(), Some(3));
+    let degrees = statistics
+        .dependencies()
+        .iter()
+        .map(|dependency| (dependency.determinant(), dependency.dependent(), dependency.degree()))
+        .collect::<Vec<_>>();
+    assert_eq!(degrees, vec![("city", "zip", 0.5), ("zip", "city", 1.0)]);
+}
+
+#[rstest::rstest]
+fn distinct_sketch_is_kept_with_ndistinct(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+
+    let statistics = statistics(table_id).build(&rows(vec![("Kyiv", 1), ("Kyiv", 2), ("Lviv", 3), ("Lviv", 3)]));
+    let mut sketch = statistics.distinct_sketch().cloned().expect("ndistinct statistics");
+    assert_eq!(sketch.estimate(), 3);
+
+    let mut new_rows = DistinctSketch::new();
+    new_rows.add(&[Datum::from_str("Kyiv"), Datum::from_i32(1)]);
+    new_rows.add(&[Datum::from_str("Odesa"), Datum::from_i32(4)]);
+    sketch.merge(&new_rows);
+    assert_eq!(sketch.estimate(), 4);
+
+    let dependencies_only = ExtendedStatistics::new(
+        table_id,
+        vec!["city".to_owned(), "zip".to_owned()],
+        vec![StatisticsKind::Dependencies],
+    )
+    .build(&rows(vec![("Kyiv", 1)]));
+    assert_eq!(dependencies_only.distinct_sketch(), None);
+}
+
+#[rstest::rstest]
+fn dependent_condition_is_not_multiplied(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+
+    let statistics = statistics(table_id).build(&rows(vec![("Kyiv", 1), ("Kyiv", 1), ("Lviv", 2), ("Lviv", 2)]));
+
+    assert_eq!(statistics.selectivity(&[("city", 0.5), ("zip", 0.5)]), 0.5);
+    assert_eq!(statistics.selectivity(&[("zip", 0.5)]), 0.5);
+}
+
+#[rstest::rstest]
+fn statistics_are_dropped_with_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+
+    assert!(data_manager_with_schema
+        .create_statistics(schema_id, "city_zip", statistics(table_id))
+        .expect("no system errors"));
+    assert!(!data_manager_with_schema
+        .create_statistics(schema_id, "city_zip", statistics(table_id))
+        .expect("no system errors"));
+    assert_eq!(
+        data_manager_with_schema.table_statistics(&Box::new(table_id)),
+        vec![((schema_id, "city_zip".to_owned()), statistics(table_id))]
+    );
+
+    data_manager_with_schema
+        .drop_table(&Box::new(table_id))
+        .expect("table is dropped");
+
+    assert!(!data_manager_with_schema.statistics_exists(schema_id, "city_zip"));
+}
+
+#[rstest::rstest]
+fn range_selectivity_is_estimated_from_bounds_of_column() {
+    let statistics = TableStatistics::build(
+        &["city".to_owned(), "zip".to_owned()],
+        &rows(vec![("Kyiv", 1), ("Kyiv", 20), ("Lviv", 100), ("Lviv", 51)]),
+    );
+
+    assert_eq!(statistics.rows(), 4);
+    assert_eq!(statistics.range_selectivity("zip", Some(1), Some(100)), Some(1.0));
+    assert_eq!(statistics.range_selectivity("zip", None, Some(25)), Some(0.25));
+    assert_eq!(statistics.range_selectivity("zip", Some(200), None), Some(0.0));
+    assert_eq!(statistics.range_selectivity("city", Some(1), None), None);
+}
+
+#[rstest::rstest]
+fn analyzed_statistics_are_dropped_with_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+    let statistics = TableStatistics::build(
+        &["city".to_owned(), "zip".to_owned()],
+        &rows(vec![("Kyiv", 1), ("Lviv", 2)]),
+    );
+
+    assert_eq!(data_manager_with_schema.analyzed(&Box::new(table_id)), None);
+    data_manager_with_schema
+        .set_analyzed(&Box::new(table_id), statistics.clone())
+        .expect("no system errors");
+    assert_eq!(data_manager_with_schema.analyzed(&Box::new(table_id)), Some(statistics));
+
+    data_manager_with_schema
+        .drop_table(&Box::new(table_id))
+        .expect("table is dropped");
+
+    assert_eq!(data_manager_with_schema.analyzed(&Box::new(table_id)), None);
+}
+
+#[rstest::rstest]
+fn table_version_changes_when_table_is_analyzed_or_dropped(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+    let version = data_manager_with_schema.table_version(&Box::new(table_id));
+
+    data_manager_with_schema
+        .set_analyzed(
+            &Box::new(table_id),
+            TableStatistics::build(&["city".to_owned(), "zip".to_owned()], &rows(vec![("Kyiv", 1)])),
+        )
+        .expect("no system errors");
+    let analyzed = data_manager_with_schema.table_version(&Box::new(table_id));
+    assert_ne!(analyzed, version);
+
+    data_manager_with_schema
+        .grant_schema_privileges(schema_id, "reader", &[SchemaPrivilege::Usage])
+        .expect("privileges are granted");
+    let granted = data_manager_with_schema.table_version(&Box::new(table_id));
+    assert_ne!(granted, analyzed);
+
+    data_manager_with_schema
+        .drop_table(&Box::new(table_id))
+        .expect("table is dropped");
+    assert_ne!(data_manager_with_schema.table_version(&Box::new(table_id)), granted);
+}
+
+#[rstest::rstest]
+fn table_version_changes_when_statistics_are_created_rebuilt_or_dropped(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(&SCHEMA).expect("schema exists");
+    let table_id = table(&data_manager_with_schema, schema_id);
+    let version = data_manager_with_schema.table_version(&Box::new(table_id));
+
+    assert!(data_manager_with_schema
+        .create_statistics(schema_id, "city_zip", statistics(table_id))
+        .expect("no system errors"));
+    let created = data_manager_with_schema.table_version(&Box::new(table_id));
+    assert_ne!(created, version);
+
+    assert!(data_manager_with_schema
+        .update_statistics(
+            schema_id,
+            "city_zip",
+            statistics(table_id).build(&rows(vec![("Kyiv", 1)]))
+        )
+        .expect("no system errors"));
+    let rebuilt = data_manager_with_schema.table_version(&Box::new(table_id));
+    assert_ne!(rebuilt, created);
+
+    assert!(data_manager_with_schema
+        .drop_statistics(schema_id, "city_zip")
+        .expect("no system errors"));
+    let dropped = data_manager_with_schema.table_version(&Box::new(table_id));
+    assert_ne!(dropped, rebuilt);
+
+    assert!(!data_manager_with_schema
+        .drop_statistics(schema_id, "city_zip")
+        .expect("no system errors"));
+    assert_eq!(data_manager_with_schema.table_version(&Box::new(table_id)), dropped);
+}
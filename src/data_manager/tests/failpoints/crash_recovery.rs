@@ -0,0 +1,258 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Kills a child process running a workload at an injected point of the storage and checks that the database
+//! recovered after restart has every acknowledged change and no broken tables.
+//! The child is this test binary that runs only `crashing_workload` test with the injected point configured.
+
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use fail::FailScenario;
+
+use common::SCHEMA;
+use data_manager::{ColumnDefinition, DataManager, Row};
+use representation::{Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+mod common;
+
+const ROOT_PATH: &str = "CRASH_RECOVERY_ROOT_PATH";
+const TABLE: &str = "table_name";
+const DROPPED_TABLE: &str = "dropped_table";
+const ROWS: u64 = 5;
+const DELETED_ROWS: u64 = 2;
+
+fn columns() -> Vec<ColumnDefinition> {
+    vec![
+        ColumnDefinition::new("id", SqlType::BigInt(i64::min_value())).primary_key(),
+        ColumnDefinition::new("flag", SqlType::Bool),
+    ]
+}
+
+fn key(id: u64) -> Binary {
+    Binary::pack(&[Datum::from_u64(id)])
+}
+
+/// every step is written after the data manager has acknowledged it
+struct Progress(File);
+
+impl Progress {
+    fn acknowledge(&mut self, step: String) {
+        writeln!(self.0, "{}", step).expect("to write progress");
+        self.0.sync_all().expect("to sync progress");
+    }
+}
+
+fn progress_file(root_path: &Path) -> PathBuf {
+    root_path.join("progress")
+}
+
+#[test]
+fn crashing_workload() {
+    let root_path = match env::var(ROOT_PATH) {
+        Ok(root_path) => PathBuf::from(root_path),
+        Err(_) => return,
+    };
+    // nothing is flushed or dropped when the process is killed
+    std::panic::set_hook(Box::new(|_| process::abort()));
+    let _scenario = FailScenario::setup();
+
+    let mut progress = Progress(
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(progress_file(&root_path))
+            .expect("to open progress file"),
+    );
+    let data_manager = DataManager::persistent(root_path.join("root_directory")).expect("to create data manager");
+
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create schema");
+    progress.acknowledge("schema".to_owned());
+    let table_id = data_manager
+        .create_table(schema_id, TABLE, &columns())
+        .expect("to create table");
+    progress.acknowledge("table".to_owned());
+    let full_table_id = Box::new((schema_id, table_id));
+    for id in 0..ROWS {
+        data_manager
+            .write_into(
+                &full_table_id,
+                vec![(
+                    key(id),
+                    Binary::pack(&[Datum::from_i64(id as i64), Datum::from_bool(id % 2 == 0)]),
+                )],
+            )
+            .expect("to insert row");
+        progress.acknowledge(format!("inserted {}", id));
+    }
+    for id in 0..DELETED_ROWS {
+        data_manager
+            .delete_from(&full_table_id, vec![key(id)])
+            .expect("to delete row");
+        progress.acknowledge(format!("deleted {}", id));
+    }
+    let dropped_table_id = data_manager
+        .create_table(schema_id, DROPPED_TABLE, &columns())
+        .expect("to create table");
+    progress.acknowledge("created dropped table".to_owned());
+    data_manager
+        .drop_table(&Box::new((schema_id, dropped_table_id)))
+        .expect("to drop table");
+    progress.acknowledge("dropped table".to_owned());
+}
+
+/// runs the workload in a child process that is killed when `failpoint` is hit for `hits + 1` time
+fn crash_and_recover(failpoint: &str, hits: usize) {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let status = Command::new(env::current_exe().expect("path to test binary"))
+        .args(&["crashing_workload", "--exact", "--test-threads=1", "--nocapture"])
+        .env(ROOT_PATH, root_path.path())
+        .env("FAILPOINTS", format!("{}={}*off->panic", failpoint, hits))
+        .status()
+        .expect("to run workload");
+    log::debug!(
+        "workload with {:?} failing after {} hits exited with {:?}",
+        failpoint,
+        hits,
+        status
+    );
+
+    let acknowledged = fs::read_to_string(progress_file(root_path.path())).unwrap_or_default();
+    let acknowledged = acknowledged.lines().collect::<Vec<&str>>();
+    let data_manager = DataManager::persistent(root_path.path().join("root_directory"))
+        .unwrap_or_else(|error| panic!("{:?} after {} hits: failed to recover {:?}", failpoint, hits, error));
+
+    for table_id in data_manager.table_ids() {
+        let columns = data_manager.table_columns(&Box::new(table_id)).expect("table columns");
+        let rows = data_manager
+            .full_scan(&Box::new(table_id))
+            .expect("to scan table")
+            .map(|row| row.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>();
+        for (_key, values) in rows {
+            assert_eq!(
+                values.unpack().len(),
+                columns.len(),
+                "{:?} after {} hits: row of {:?} table does not match its columns",
+                failpoint,
+                hits,
+                data_manager.table_name(&Box::new(table_id))
+            );
+        }
+    }
+
+    if !acknowledged.contains(&"schema") {
+        return;
+    }
+    assert!(
+        data_manager.schema_exists(&SCHEMA).is_some(),
+        "{:?} after {} hits: acknowledged schema is lost",
+        failpoint,
+        hits
+    );
+    if !acknowledged.contains(&"table") {
+        return;
+    }
+    let table_id = data_manager
+        .table_exists(&SCHEMA, &TABLE)
+        .and_then(|(schema_id, table_id)| table_id.map(|table_id| (schema_id, table_id)))
+        .unwrap_or_else(|| panic!("{:?} after {} hits: acknowledged table is lost", failpoint, hits));
+    assert_eq!(
+        data_manager.table_columns(&Box::new(table_id)).expect("table columns"),
+        columns(),
+        "{:?} after {} hits: acknowledged table lost its columns",
+        failpoint,
+        hits
+    );
+
+    let keys = data_manager
+        .full_scan(&Box::new(table_id))
+        .expect("to scan table")
+        .map(|row| row.expect("no io error").expect("no platform error").0)
+        .collect::<Vec<Binary>>();
+    for id in 0..ROWS {
+        if acknowledged.contains(&format!("deleted {}", id).as_str()) {
+            assert!(
+                !keys.contains(&key(id)),
+                "{:?} after {} hits: deleted row {} came back",
+                failpoint,
+                hits,
+                id
+            );
+        } else if acknowledged.contains(&format!("inserted {}", id).as_str()) {
+            assert!(
+                keys.contains(&key(id)),
+                "{:?} after {} hits: inserted row {} is lost",
+                failpoint,
+                hits,
+                id
+            );
+        }
+    }
+
+    if acknowledged.contains(&"dropped table") {
+        assert!(
+            matches!(data_manager.table_exists(&SCHEMA, &DROPPED_TABLE), Some((_, None))),
+            "{:?} after {} hits: dropped table came back",
+            failpoint,
+            hits
+        );
+    } else if acknowledged.contains(&"created dropped table") {
+        assert!(
+            matches!(data_manager.table_exists(&SCHEMA, &DROPPED_TABLE), Some((_, Some(_)))),
+            "{:?} after {} hits: created table is lost",
+            failpoint,
+            hits
+        );
+    }
+}
+
+#[test]
+fn crash_on_insert() {
+    for hits in 0..16 {
+        crash_and_recover("sled-fail-to-insert-into-tree", hits);
+    }
+}
+
+#[test]
+fn crash_on_flush() {
+    for hits in 0..16 {
+        crash_and_recover("sled-fail-to-flush-tree", hits);
+    }
+}
+
+#[test]
+fn crash_on_create_table() {
+    for hits in 0..4 {
+        crash_and_recover("sled-fail-to-open-tree", hits);
+    }
+}
+
+#[test]
+fn crash_on_delete() {
+    for hits in 0..DELETED_ROWS as usize {
+        crash_and_recover("sled-fail-to-remove-from-tree", hits);
+    }
+}
+
+#[test]
+fn crash_on_drop_table() {
+    crash_and_recover("sled-fail-to-drop-tree", 0);
+}
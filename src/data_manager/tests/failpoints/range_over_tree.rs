@@ -0,0 +1,76 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Bound;
+
+use fail::FailScenario;
+
+use common::{scenario, OBJECT, SCHEMA};
+use data_manager::{ColumnDefinition, DataManager, StorageError};
+use representation::{Binary, Datum};
+use sql_model::sql_types::SqlType;
+
+mod common;
+
+#[rstest::fixture]
+fn table() -> (DataManager, Box<(u64, u64)>) {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create data manager");
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            OBJECT,
+            &[ColumnDefinition::new("id", SqlType::SmallInt(i16::MIN)).primary_key()],
+        )
+        .expect("to create table");
+    let full_table_id = Box::new((schema_id, table_id));
+    data_manager
+        .write_into(
+            &full_table_id,
+            vec![(Binary::pack(&[Datum::from_i16(1)]), Binary::pack(&[Datum::from_i16(1)]))],
+        )
+        .expect("to write row");
+    (data_manager, full_table_id)
+}
+
+#[rstest::rstest]
+fn io_error(table: (DataManager, Box<(u64, u64)>), scenario: FailScenario) {
+    let (data_manager, table_id) = table;
+    fail::cfg("sled-fail-range-over-tree", "return(io)").unwrap();
+
+    assert_eq!(
+        data_manager
+            .scan(&table_id, (Bound::Unbounded, Bound::Unbounded), 10)
+            .collect::<Vec<_>>(),
+        vec![Ok(Err(StorageError::Io))]
+    );
+
+    scenario.teardown();
+}
+
+#[rstest::rstest]
+fn corruption_error(table: (DataManager, Box<(u64, u64)>), scenario: FailScenario) {
+    let (data_manager, table_id) = table;
+    fail::cfg("sled-fail-range-over-tree", "return(corruption)").unwrap();
+
+    assert_eq!(
+        data_manager
+            .scan(&table_id, (Bound::Unbounded, Bound::Unbounded), 10)
+            .collect::<Vec<_>>(),
+        vec![Ok(Err(StorageError::Storage))]
+    );
+
+    scenario.teardown();
+}
@@ -0,0 +1,56 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate data_manager;
+
+use std::{env, path::PathBuf, process};
+
+use data_manager::{BackupError, DataManager, StorageBackend};
+
+/// copies everything kept by a stopped node into another storage backend,
+/// the node is then started on the target root path with the target `STORAGE`
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let (source, target) = match args.as_slice() {
+        [from, from_path, to, to_path] => (backend(from, from_path), backend(to, to_path)),
+        _ => {
+            eprintln!("usage: storage-migrate <from backend> <from root path> <to backend> <to root path>");
+            process::exit(2);
+        }
+    };
+
+    let source = DataManager::open(source).expect("to open source storage");
+    let target = DataManager::open(target).expect("to open target storage");
+    match source.migrate(&target).expect("no system errors") {
+        Ok(()) => println!("storage is migrated"),
+        Err(BackupError::NotEmpty) => {
+            eprintln!("target storage already has schemas");
+            process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("storage is not migrated: {:?}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn backend(name: &str, root_path: &str) -> StorageBackend {
+    match StorageBackend::from_name(name, PathBuf::from(root_path).join("root_directory")) {
+        Some(backend) => backend,
+        None => {
+            eprintln!("unknown storage backend {}", name);
+            process::exit(2);
+        }
+    }
+}
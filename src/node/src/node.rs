@@ -20,129 +20,211 @@ use std::{
         atomic::{AtomicU8, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
 use async_dup::Arc as AsyncArc;
 use async_io::Async;
 
-use data_manager::DataManager;
-use protocol::{Command, ProtocolConfiguration, Receiver};
-use sql_engine::QueryExecutor;
+use data_manager::{DataManager, StorageBackend};
+use protocol::{clock::FrozenClock, param, ClientRequest, Command, ProtocolConfiguration, Receiver, USER};
+use sql_engine::{activity::SessionRegistry, QueryExecutor};
+
+use crate::workload::{Capture, Entry, OutcomeSender};
 
 const PORT: u16 = 5432;
+/// 2000-01-01 00:00:00 UTC, time of every session in deterministic mode
+const DETERMINISTIC_TIME: u64 = 946_684_800;
 const HOST: [u8; 4] = [0, 0, 0, 0];
 
 pub const RUNNING: u8 = 0;
 pub const STOPPED: u8 = 1;
 
 pub fn start() {
-    let persistent = env::var("PERSISTENT").is_ok();
+    let deterministic = env::var("DETERMINISTIC").is_ok() || env::args().any(|arg| arg == "--deterministic");
     let root_path = env::var("ROOT_PATH").map(PathBuf::from).unwrap_or_default();
+    let backend = storage_backend(root_path.join("root_directory"));
+    let capture_path = env::var("WORKLOAD_CAPTURE").map(PathBuf::from).ok();
+    let restore_path = env::var("RESTORE_FROM").map(PathBuf::from).ok();
     smol::block_on(async {
-        let storage = if persistent {
-            Arc::new(DataManager::persistent(root_path.join("root_directory")).unwrap())
-        } else {
-            Arc::new(DataManager::in_memory().unwrap())
-        };
+        let storage = Arc::new(DataManager::open(backend).unwrap());
+        if let Some(path) = restore_path {
+            match storage.restore_backup(&path).expect("no system errors") {
+                Ok(()) => log::info!("restored backup {:?}", path),
+                Err(error) => panic!("could not restore backup {:?}: {:?}", path, error),
+            }
+        }
         let listener = Async::<TcpListener>::bind((HOST, PORT)).expect("OK");
 
+        let capture =
+            capture_path.map(|path| Arc::new(Capture::create(path).expect("to create workload capture file")));
+
+        let sessions = Arc::new(SessionRegistry::default());
         let state = Arc::new(AtomicU8::new(RUNNING));
-        let config = protocol_configuration();
+        let config = Arc::new(protocol_configuration());
 
         while let Ok((tcp_stream, address)) = listener.accept().await {
+            if state.load(Ordering::SeqCst) == STOPPED {
+                return;
+            }
             let tcp_stream = AsyncArc::new(tcp_stream);
-            if let Ok((mut receiver, sender)) = protocol::hand_shake(tcp_stream, address, &config)
-                .await
-                .expect("no io errors")
-            {
-                if state.load(Ordering::SeqCst) == STOPPED {
-                    return;
+            let state = state.clone();
+            let storage = storage.clone();
+            let capture = capture.clone();
+            let sessions = sessions.clone();
+            let config = config.clone();
+            // hand shake is run by the connection task so that slow clients don't hold back accepting others
+            smol::spawn(async move {
+                // the session is registered before hand shake as the client receives its process id and secret key
+                let activity = sessions.register();
+                let request =
+                    match protocol::hand_shake(tcp_stream, address, &config, (activity.pid(), activity.secret_key()))
+                        .await
+                    {
+                        Ok(request) => request,
+                        Err(error) => {
+                            sessions.unregister(activity.pid());
+                            log::debug!("connection is lost during hand shake: {:?}", error);
+                            return;
+                        }
+                    };
+                let (mut receiver, sender) = match request {
+                    Ok(ClientRequest::Connect(connection)) => connection,
+                    Ok(ClientRequest::QueryCancellation(pid, secret_key)) => {
+                        sessions.unregister(activity.pid());
+                        if !sessions.cancel(pid, secret_key) {
+                            log::debug!("no session {} to cancel statement of", pid);
+                        }
+                        return;
+                    }
+                    Err(error) => {
+                        sessions.unregister(activity.pid());
+                        log::debug!("hand shake failed: {:?}", error);
+                        return;
+                    }
+                };
+                let outcomes = Arc::new(OutcomeSender::new(Arc::new(sender)));
+                let mut query_executor = QueryExecutor::new(storage, outcomes.clone());
+                query_executor.set_session(sessions, activity);
+                if let Some(user) = param(&receiver.properties().1, USER) {
+                    query_executor.set_session_user(user);
+                }
+                if deterministic {
+                    make_deterministic(&mut query_executor);
                 }
-                let state = state.clone();
-                let storage = storage.clone();
-                let sender = Arc::new(sender);
-                let s = sender.clone();
-                let mut query_executor = QueryExecutor::new(storage.clone(), s);
                 log::debug!("ready to handle query");
 
-                smol::spawn(async move {
-                    loop {
-                        match receiver.receive().await {
-                            Err(e) => {
-                                log::error!("UNEXPECTED ERROR: {:?}", e);
-                                state.store(STOPPED, Ordering::SeqCst);
-                                return;
-                            }
-                            Ok(Err(e)) => {
-                                log::error!("UNEXPECTED ERROR: {:?}", e);
-                                state.store(STOPPED, Ordering::SeqCst);
-                                return;
-                            }
-                            Ok(Ok(Command::Bind {
-                                portal_name,
-                                statement_name,
-                                param_formats,
-                                raw_params,
-                                result_formats,
-                            })) => {
-                                match query_executor.bind_prepared_statement_to_portal(
-                                    portal_name.as_str(),
-                                    statement_name.as_str(),
-                                    param_formats.as_ref(),
-                                    raw_params.as_ref(),
-                                    result_formats.as_ref(),
-                                ) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Continue)) => {}
-                            Ok(Ok(Command::DescribeStatement { name })) => {
-                                match query_executor.describe_prepared_statement(name.as_str()) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Execute { portal_name, max_rows })) => {
-                                match query_executor.execute_portal(portal_name.as_str(), max_rows) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Flush)) => query_executor.flush(),
-                            Ok(Ok(Command::Parse {
-                                statement_name,
-                                sql,
-                                param_types,
-                            })) => {
-                                match query_executor.parse_prepared_statement(
-                                    statement_name.as_str(),
-                                    sql.as_str(),
-                                    param_types.as_ref(),
-                                ) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Query { sql })) => match query_executor.execute(sql.as_str()) {
-                                Ok(()) => {
-                                    query_executor.flush();
-                                }
-                                Err(error) => log::error!("{:?}", error),
-                            },
-                            Ok(Ok(Command::Terminate)) => {
-                                log::debug!("Closing connection with client");
-                                break;
-                            }
+                let session = capture
+                    .as_ref()
+                    .map(|capture| capture.next_session())
+                    .unwrap_or_default();
+                loop {
+                    let command = match receiver.receive().await {
+                        Err(e) => {
+                            log::error!("UNEXPECTED ERROR: {:?}", e);
+                            state.store(STOPPED, Ordering::SeqCst);
+                            return;
+                        }
+                        Ok(Err(e)) => {
+                            log::error!("UNEXPECTED ERROR: {:?}", e);
+                            state.store(STOPPED, Ordering::SeqCst);
+                            return;
+                        }
+                        Ok(Ok(command)) => command,
+                    };
+                    let terminate = command == Command::Terminate;
+                    let offset = capture.as_ref().map(|capture| capture.elapsed()).unwrap_or_default();
+                    let started = Instant::now();
+                    // statements are executed on the blocking pool, idle connections only wait on their sockets
+                    let (executor, command) = blocking::unblock(move || {
+                        handle_command(&mut query_executor, &command);
+                        (query_executor, command)
+                    })
+                    .await;
+                    query_executor = executor;
+                    let duration = started.elapsed();
+                    let errors = outcomes.take_errors();
+                    if let Some(capture) = capture.as_ref() {
+                        let entry = Entry {
+                            session,
+                            offset,
+                            duration,
+                            errors,
+                            command,
+                        };
+                        if let Err(error) = capture.record(&entry) {
+                            log::error!("failed to capture command: {:?}", error);
                         }
                     }
-                })
-                .detach();
-            }
+                    if terminate {
+                        log::debug!("Closing connection with client");
+                        break;
+                    }
+                }
+            })
+            .detach();
         }
     });
 }
 
+/// session clock is frozen and random generator is seeded so that sessions produce the same results
+pub fn make_deterministic(query_executor: &mut QueryExecutor) {
+    query_executor.set_clock(Arc::new(FrozenClock::at_unix_seconds(DETERMINISTIC_TIME)));
+    query_executor.set_random_seed(0);
+}
+
+/// Terminate command is not handled here as it closes connection
+pub fn handle_command(query_executor: &mut QueryExecutor, command: &Command) {
+    let result = match command {
+        Command::Bind {
+            portal_name,
+            statement_name,
+            param_formats,
+            raw_params,
+            result_formats,
+        } => query_executor.bind_prepared_statement_to_portal(
+            portal_name.as_str(),
+            statement_name.as_str(),
+            param_formats.as_ref(),
+            raw_params.as_ref(),
+            result_formats.as_ref(),
+        ),
+        Command::ClosePortal { name } => query_executor.close_portal(name.as_str()),
+        Command::CloseStatement { name } => query_executor.close_prepared_statement(name.as_str()),
+        Command::Continue | Command::Terminate => Ok(()),
+        Command::CopyData { data } => {
+            query_executor.copy_data(data.as_ref());
+            Ok(())
+        }
+        Command::CopyDone => query_executor.copy_done().map(|()| query_executor.flush()),
+        Command::CopyFail { message } => {
+            query_executor.copy_fail(message.as_str());
+            query_executor.flush();
+            Ok(())
+        }
+        Command::DescribePortal { name } => query_executor.describe_portal(name.as_str()),
+        Command::DescribeStatement { name } => query_executor.describe_prepared_statement(name.as_str()),
+        Command::Execute { portal_name, max_rows } => query_executor.execute_portal(portal_name.as_str(), *max_rows),
+        Command::Flush => {
+            query_executor.flush();
+            Ok(())
+        }
+        Command::Parse {
+            statement_name,
+            sql,
+            param_types,
+        } => query_executor.parse_prepared_statement(statement_name.as_str(), sql.as_str(), param_types.as_ref()),
+        Command::Query { sql } => query_executor.execute(sql.as_str()).map(|()| query_executor.flush()),
+        Command::Sync => {
+            query_executor.sync();
+            Ok(())
+        }
+    };
+    if let Err(error) = result {
+        log::error!("{:?}", error);
+    }
+}
+
 fn pfx_certificate_path() -> PathBuf {
     let file = env::var("PFX_CERTIFICATE_FILE").unwrap();
     let path = Path::new(&file);
@@ -158,6 +240,16 @@ fn pfx_certificate_password() -> String {
     env::var("PFX_CERTIFICATE_PASSWORD").unwrap()
 }
 
+/// `STORAGE` names the backend, `PERSISTENT` selects sled when it is not set
+fn storage_backend(path: PathBuf) -> StorageBackend {
+    match env::var("STORAGE") {
+        Ok(name) => StorageBackend::from_name(&name, path)
+            .unwrap_or_else(|| panic!("unknown storage backend {:?}, expected in-memory or sled", name)),
+        Err(_) if env::var("PERSISTENT").is_ok() => StorageBackend::Sled(path),
+        Err(_) => StorageBackend::InMemory,
+    }
+}
+
 fn protocol_configuration() -> ProtocolConfiguration {
     match env::var("SECURE") {
         Ok(s) => match s.to_lowercase().as_str() {
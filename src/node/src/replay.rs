@@ -0,0 +1,75 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate data_manager;
+extern crate node;
+
+use std::{env, fs, path::PathBuf, process, sync::Arc};
+
+use data_manager::{DataManager, StorageBackend};
+use node::workload::{self, Entry};
+
+fn main() {
+    let mut capture_file = None;
+    let mut root_path = None;
+    let mut storage = None;
+    let mut deterministic = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--deterministic" => deterministic = true,
+            "--root-path" => root_path = args.next().map(PathBuf::from),
+            "--storage" => storage = args.next(),
+            _ => capture_file = Some(PathBuf::from(arg)),
+        }
+    }
+    let capture_file = match capture_file {
+        Some(capture_file) => capture_file,
+        None => {
+            eprintln!(
+                "usage: workload-replay <capture file> [--root-path <directory>] [--storage <backend>] [--deterministic]"
+            );
+            process::exit(2);
+        }
+    };
+
+    let content = fs::read_to_string(&capture_file).expect("to read workload capture file");
+    let mut entries = vec![];
+    for (index, line) in content.lines().enumerate().filter(|(_index, line)| !line.is_empty()) {
+        match Entry::decode(line) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => {
+                eprintln!("line {}: {}", index + 1, error);
+                process::exit(1);
+            }
+        }
+    }
+
+    // captured workload is replayed into sled when the root path is given and the backend is not
+    let path = root_path.clone().unwrap_or_default().join("root_directory");
+    let backend = match (storage, root_path) {
+        (Some(name), _) => match StorageBackend::from_name(&name, path) {
+            Some(backend) => backend,
+            None => {
+                eprintln!("unknown storage backend {}, expected in-memory or sled", name);
+                process::exit(2);
+            }
+        },
+        (None, Some(_)) => StorageBackend::Sled(path),
+        (None, None) => StorageBackend::InMemory,
+    };
+    let storage = DataManager::open(backend).expect("to create data manager");
+
+    print!("{}", workload::replay(entries, Arc::new(storage), deterministic));
+}
@@ -0,0 +1,572 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Capture of client commands of every session into a file and their replay against another instance.
+//! Each captured command takes a line of tab separated fields: session, offset from the capture start and duration
+//! in microseconds, SQLSTATE codes of errors sent in response, kind of the command and its arguments
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use data_manager::DataManager;
+use protocol::{
+    pgsql_types::{PostgreSqlFormat, PostgreSqlType},
+    results::QueryResult,
+    Command, Sender,
+};
+use sql_engine::QueryExecutor;
+
+use crate::node::{handle_command, make_deterministic};
+
+/// sender that remembers SQLSTATE codes of errors sent in response to the current command
+pub struct OutcomeSender {
+    sender: Arc<dyn Sender>,
+    errors: Mutex<Vec<String>>,
+}
+
+impl OutcomeSender {
+    pub fn new(sender: Arc<dyn Sender>) -> OutcomeSender {
+        OutcomeSender {
+            sender,
+            errors: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut *self.errors.lock().expect("to acquire errors lock"))
+    }
+}
+
+impl Sender for OutcomeSender {
+    fn flush(&self) -> io::Result<()> {
+        self.sender.flush()
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        if let Err(error) = &query_result {
+            self.errors
+                .lock()
+                .expect("to acquire errors lock")
+                .push(error.sqlstate().to_owned());
+        }
+        self.sender.send(query_result)
+    }
+}
+
+/// results of replayed commands are not needed, only their errors are compared
+struct DiscardingSender;
+
+impl Sender for DiscardingSender {
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn send(&self, _query_result: QueryResult) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// captured client command
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub session: u64,
+    pub offset: Duration,
+    pub duration: Duration,
+    pub errors: Vec<String>,
+    pub command: Command,
+}
+
+impl Entry {
+    /// `None` for commands that are not handled by sessions
+    pub fn encode(&self) -> Option<String> {
+        let command = match &self.command {
+            Command::Query { sql } => vec!["query".to_owned(), escape(sql)],
+            Command::Parse {
+                statement_name,
+                sql,
+                param_types,
+            } => vec![
+                "parse".to_owned(),
+                escape(statement_name),
+                escape(sql),
+                join(param_types.iter().map(|param_type| {
+                    param_type
+                        .map(|param_type| param_type.pg_oid())
+                        .unwrap_or(0)
+                        .to_string()
+                })),
+            ],
+            Command::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            } => vec![
+                "bind".to_owned(),
+                escape(portal_name),
+                escape(statement_name),
+                encode_formats(param_formats),
+                join(raw_params.iter().map(encode_param)),
+                encode_formats(result_formats),
+            ],
+            Command::CopyData { data } => vec![
+                "copy_data".to_owned(),
+                data.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            ],
+            Command::CopyDone => vec!["copy_done".to_owned()],
+            Command::CopyFail { message } => vec!["copy_fail".to_owned(), escape(message)],
+            Command::ClosePortal { name } => vec!["close_portal".to_owned(), escape(name)],
+            Command::CloseStatement { name } => vec!["close_statement".to_owned(), escape(name)],
+            Command::DescribePortal { name } => vec!["describe_portal".to_owned(), escape(name)],
+            Command::DescribeStatement { name } => vec!["describe".to_owned(), escape(name)],
+            Command::Execute { portal_name, max_rows } => {
+                vec!["execute".to_owned(), escape(portal_name), max_rows.to_string()]
+            }
+            Command::Flush => vec!["flush".to_owned()],
+            Command::Sync => vec!["sync".to_owned()],
+            Command::Terminate => vec!["terminate".to_owned()],
+            Command::Continue => return None,
+        };
+        let mut fields = vec![
+            self.session.to_string(),
+            self.offset.as_micros().to_string(),
+            self.duration.as_micros().to_string(),
+            join(self.errors.iter().cloned()),
+        ];
+        fields.extend(command);
+        Some(fields.join("\t"))
+    }
+
+    pub fn decode(line: &str) -> Result<Entry, String> {
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        if fields.len() < 5 {
+            return Err(format!("expected at least 5 fields but got {}", fields.len()));
+        }
+        let session = number(fields[0])?;
+        let offset = Duration::from_micros(number(fields[1])?);
+        let duration = Duration::from_micros(number(fields[2])?);
+        let errors = split(fields[3]).map(ToOwned::to_owned).collect();
+        let command = match (fields[4], &fields[5..]) {
+            ("query", [sql]) => Command::Query { sql: unescape(sql)? },
+            ("parse", [statement_name, sql, param_types]) => Command::Parse {
+                statement_name: unescape(statement_name)?,
+                sql: unescape(sql)?,
+                param_types: split(param_types)
+                    .map(|oid| match number(oid)? {
+                        0 => Ok(None),
+                        oid => PostgreSqlType::try_from(oid as u32)
+                            .map(Some)
+                            .map_err(|()| format!("unknown type {}", oid)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            ("bind", [portal_name, statement_name, param_formats, raw_params, result_formats]) => Command::Bind {
+                portal_name: unescape(portal_name)?,
+                statement_name: unescape(statement_name)?,
+                param_formats: decode_formats(param_formats)?,
+                raw_params: split(raw_params).map(decode_param).collect::<Result<_, _>>()?,
+                result_formats: decode_formats(result_formats)?,
+            },
+            ("copy_data", [data]) => Command::CopyData {
+                data: decode_param(data)?.unwrap_or_default(),
+            },
+            ("copy_done", []) => Command::CopyDone,
+            ("copy_fail", [message]) => Command::CopyFail {
+                message: unescape(message)?,
+            },
+            ("close_portal", [name]) => Command::ClosePortal { name: unescape(name)? },
+            ("close_statement", [name]) => Command::CloseStatement { name: unescape(name)? },
+            ("describe_portal", [name]) => Command::DescribePortal { name: unescape(name)? },
+            ("describe", [name]) => Command::DescribeStatement { name: unescape(name)? },
+            ("execute", [portal_name, max_rows]) => Command::Execute {
+                portal_name: unescape(portal_name)?,
+                max_rows: max_rows
+                    .parse()
+                    .map_err(|_| format!("invalid number of rows {}", max_rows))?,
+            },
+            ("flush", []) => Command::Flush,
+            ("sync", []) => Command::Sync,
+            ("terminate", []) => Command::Terminate,
+            (kind, arguments) => return Err(format!("unknown command {} with {} arguments", kind, arguments.len())),
+        };
+        Ok(Entry {
+            session,
+            offset,
+            duration,
+            errors,
+            command,
+        })
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            other => return Err(format!("invalid escape sequence \\{}", other.unwrap_or_default())),
+        }
+    }
+    Ok(unescaped)
+}
+
+fn join<I: Iterator<Item = String>>(items: I) -> String {
+    items.collect::<Vec<String>>().join(",")
+}
+
+/// empty items are kept so that positions of the following items are preserved
+fn split(field: &str) -> impl Iterator<Item = &str> {
+    (!field.is_empty()).then(|| field.split(',')).into_iter().flatten()
+}
+
+fn number(field: &str) -> Result<u64, String> {
+    field.parse().map_err(|_| format!("invalid number {}", field))
+}
+
+/// `t` for text and `b` for binary format
+fn encode_formats(formats: &[PostgreSqlFormat]) -> String {
+    formats
+        .iter()
+        .map(|format| match format {
+            PostgreSqlFormat::Text => 't',
+            PostgreSqlFormat::Binary => 'b',
+        })
+        .collect()
+}
+
+fn decode_formats(field: &str) -> Result<Vec<PostgreSqlFormat>, String> {
+    field
+        .chars()
+        .map(|format| match format {
+            't' => Ok(PostgreSqlFormat::Text),
+            'b' => Ok(PostgreSqlFormat::Binary),
+            other => Err(format!("unknown format {}", other)),
+        })
+        .collect()
+}
+
+/// parameters are hex encoded, NULL parameters don't have a value and empty values are `-`
+fn encode_param(param: &Option<Vec<u8>>) -> String {
+    match param {
+        None => "NULL".to_owned(),
+        Some(bytes) if bytes.is_empty() => "-".to_owned(),
+        Some(bytes) => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+    }
+}
+
+fn decode_param(param: &str) -> Result<Option<Vec<u8>>, String> {
+    match param {
+        "NULL" => return Ok(None),
+        "-" => return Ok(Some(vec![])),
+        _ => {}
+    }
+    if param.len() % 2 != 0 {
+        return Err(format!("invalid parameter {}", param));
+    }
+    (0..param.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&param[index..index + 2], 16).map_err(|_| format!("invalid parameter {}", param))
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// file that commands of all sessions are captured into
+pub struct Capture {
+    writer: Mutex<BufWriter<File>>,
+    started: Instant,
+    sessions: AtomicU64,
+}
+
+impl Capture {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Capture> {
+        Ok(Capture {
+            writer: Mutex::new(BufWriter::new(File::create(path)?)),
+            started: Instant::now(),
+            sessions: AtomicU64::new(0),
+        })
+    }
+
+    pub fn next_session(&self) -> u64 {
+        self.sessions.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// every entry is flushed so that commands before a crash are not lost
+    pub fn record(&self, entry: &Entry) -> io::Result<()> {
+        if let Some(line) = entry.encode() {
+            let mut writer = self.writer.lock().expect("to acquire capture file lock");
+            writeln!(writer, "{}", line)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// command that has different errors when it is replayed
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub session: u64,
+    pub command: String,
+    pub captured: Vec<String>,
+    pub replayed: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    pub sessions: usize,
+    pub commands: usize,
+    pub captured: Duration,
+    pub replayed: Duration,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "replayed {} commands of {} sessions", self.commands, self.sessions)?;
+        writeln!(
+            f,
+            "captured time: {:.3} ms, replayed time: {:.3} ms",
+            self.captured.as_secs_f64() * 1_000.0,
+            self.replayed.as_secs_f64() * 1_000.0
+        )?;
+        writeln!(f, "commands with different errors: {}", self.mismatches.len())?;
+        for mismatch in self.mismatches.iter() {
+            writeln!(
+                f,
+                "  session {}: {} captured [{}] replayed [{}]",
+                mismatch.session,
+                mismatch.command,
+                mismatch.captured.join(", "),
+                mismatch.replayed.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reissues captured commands in the order they were captured, every captured session gets its own session
+pub fn replay(entries: Vec<Entry>, storage: Arc<DataManager>, deterministic: bool) -> ReplayReport {
+    let mut sessions: HashMap<u64, (QueryExecutor, Arc<OutcomeSender>)> = HashMap::new();
+    let mut report = ReplayReport::default();
+    for entry in entries {
+        let (query_executor, outcomes) = sessions.entry(entry.session).or_insert_with(|| {
+            let outcomes = Arc::new(OutcomeSender::new(Arc::new(DiscardingSender)));
+            let mut query_executor = QueryExecutor::new(storage.clone(), outcomes.clone());
+            if deterministic {
+                make_deterministic(&mut query_executor);
+            }
+            (query_executor, outcomes)
+        });
+        let started = Instant::now();
+        handle_command(query_executor, &entry.command);
+        report.replayed += started.elapsed();
+        report.captured += entry.duration;
+        report.commands += 1;
+        let replayed = outcomes.take_errors();
+        if replayed != entry.errors {
+            report.mismatches.push(Mismatch {
+                session: entry.session,
+                command: describe(&entry.command),
+                captured: entry.errors,
+                replayed,
+            });
+        }
+    }
+    report.sessions = sessions.len();
+    report
+}
+
+fn describe(command: &Command) -> String {
+    match command {
+        Command::Query { sql } | Command::Parse { sql, .. } => sql.clone(),
+        Command::Bind { statement_name, .. } => format!("bind of \"{}\"", statement_name),
+        Command::CopyData { data } => format!("copy of {} bytes", data.len()),
+        Command::CopyDone => "copy done".to_owned(),
+        Command::CopyFail { message } => format!("copy failure \"{}\"", message),
+        Command::ClosePortal { name } => format!("close of portal \"{}\"", name),
+        Command::CloseStatement { name } => format!("close of \"{}\"", name),
+        Command::DescribePortal { name } => format!("describe of portal \"{}\"", name),
+        Command::DescribeStatement { name } => format!("describe of \"{}\"", name),
+        Command::Execute { portal_name, .. } => format!("execute of \"{}\"", portal_name),
+        Command::Flush => "flush".to_owned(),
+        Command::Sync => "sync".to_owned(),
+        Command::Terminate => "terminate".to_owned(),
+        Command::Continue => "continue".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: Command) -> Entry {
+        Entry {
+            session: 2,
+            offset: Duration::from_micros(1_500),
+            duration: Duration::from_micros(250),
+            errors: vec!["42P01".to_owned()],
+            command,
+        }
+    }
+
+    fn round_trip(command: Command) {
+        let captured = entry(command);
+        let line = captured.encode().expect("encoded command");
+        assert_eq!(Entry::decode(&line), Ok(captured));
+    }
+
+    #[test]
+    fn query() {
+        round_trip(Command::Query {
+            sql: "select 'tab\tand\nnew line\\';".to_owned(),
+        });
+    }
+
+    #[test]
+    fn extended_query() {
+        round_trip(Command::Parse {
+            statement_name: "statement".to_owned(),
+            sql: "insert into schema_name.table_name values ($1, $2);".to_owned(),
+            param_types: vec![Some(PostgreSqlType::SmallInt), None, Some(PostgreSqlType::VarChar)],
+        });
+        round_trip(Command::Bind {
+            portal_name: "".to_owned(),
+            statement_name: "statement".to_owned(),
+            param_formats: vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text],
+            raw_params: vec![Some(vec![0, 1]), None, Some(vec![])],
+            result_formats: vec![],
+        });
+        round_trip(Command::Bind {
+            portal_name: "portal".to_owned(),
+            statement_name: "statement".to_owned(),
+            param_formats: vec![PostgreSqlFormat::Text],
+            raw_params: vec![Some(vec![]), None, Some(vec![]), Some(vec![0xff])],
+            result_formats: vec![PostgreSqlFormat::Text],
+        });
+        round_trip(Command::Bind {
+            portal_name: "".to_owned(),
+            statement_name: "".to_owned(),
+            param_formats: vec![],
+            raw_params: vec![],
+            result_formats: vec![],
+        });
+        round_trip(Command::DescribeStatement {
+            name: "statement".to_owned(),
+        });
+        round_trip(Command::Execute {
+            portal_name: "".to_owned(),
+            max_rows: 0,
+        });
+        round_trip(Command::DescribePortal { name: "".to_owned() });
+        round_trip(Command::ClosePortal { name: "".to_owned() });
+        round_trip(Command::CloseStatement {
+            name: "statement".to_owned(),
+        });
+        round_trip(Command::Flush);
+        round_trip(Command::Sync);
+        round_trip(Command::Terminate);
+    }
+
+    #[test]
+    fn copy() {
+        round_trip(Command::CopyData {
+            data: b"1\tone\n2\t\\N\n".to_vec(),
+        });
+        round_trip(Command::CopyDone);
+        round_trip(Command::CopyFail {
+            message: "canceled\tby user".to_owned(),
+        });
+    }
+
+    #[test]
+    fn continue_is_not_captured() {
+        assert_eq!(entry(Command::Continue).encode(), None);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        assert!(Entry::decode("1\t2\t3").is_err());
+        assert!(Entry::decode("1\t2\t3\t\tvacuum").is_err());
+        assert!(Entry::decode("1\t2\t3\t\tquery\tselect \\x").is_err());
+    }
+
+    #[test]
+    fn replay_reports_different_errors() {
+        let entries = vec![
+            Entry {
+                errors: vec![],
+                command: Command::Query {
+                    sql: "create schema schema_name;".to_owned(),
+                },
+                ..entry(Command::Flush)
+            },
+            Entry {
+                errors: vec![],
+                command: Command::Query {
+                    sql: "select * from schema_name.table_name;".to_owned(),
+                },
+                ..entry(Command::Flush)
+            },
+        ];
+        let report = replay(
+            entries,
+            Arc::new(DataManager::in_memory().expect("to create data manager")),
+            true,
+        );
+
+        assert_eq!(report.sessions, 1);
+        assert_eq!(report.commands, 2);
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                session: 2,
+                command: "select * from schema_name.table_name;".to_owned(),
+                captured: vec![],
+                replayed: vec!["42P01".to_owned()],
+            }]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text representation of one-dimensional arrays, e.g. `{1,NULL,"a b"}`
+
+/// elements of `{...}` separated by commas, unquoted `NULL` is a missing element
+pub fn parse(text: &str) -> Result<Vec<Option<String>>, String> {
+    let malformed = || format!("malformed array literal: \"{}\"", text);
+    let inner = match text.trim().strip_prefix('{').and_then(|text| text.strip_suffix('}')) {
+        Some(inner) => inner,
+        None => return Err(malformed()),
+    };
+    if inner.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let mut elements = vec![];
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        let element = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut element = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c) => element.push(c),
+                        None => return Err(malformed()),
+                    },
+                    Some(c) => element.push(c),
+                    None => return Err(malformed()),
+                }
+            }
+            while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                chars.next();
+            }
+            Some(element)
+        } else {
+            let mut element = String::new();
+            while let Some(c) = chars.peek().copied().filter(|c| *c != ',') {
+                match c {
+                    '{' | '}' | '"' => return Err("multidimensional arrays are not supported".to_owned()),
+                    '\\' => {
+                        chars.next();
+                        match chars.next() {
+                            Some(c) => element.push(c),
+                            None => return Err(malformed()),
+                        }
+                    }
+                    c => {
+                        element.push(c);
+                        chars.next();
+                    }
+                }
+            }
+            let element = element.trim();
+            if element.is_empty() {
+                return Err(malformed());
+            } else if element.eq_ignore_ascii_case("null") {
+                None
+            } else {
+                Some(element.to_owned())
+            }
+        };
+        elements.push(element);
+        match chars.next() {
+            Some(',') => {}
+            None => return Ok(elements),
+            Some(_) => return Err(malformed()),
+        }
+    }
+}
+
+/// elements that are empty, look like `NULL` or contain special characters are quoted
+pub fn render(elements: &[Option<String>]) -> String {
+    let elements = elements
+        .iter()
+        .map(|element| match element {
+            None => "NULL".to_owned(),
+            Some(element) if needs_quotes(element) => {
+                format!("\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Some(element) => element.clone(),
+        })
+        .collect::<Vec<_>>();
+    format!("{{{}}}", elements.join(","))
+}
+
+fn needs_quotes(element: &str) -> bool {
+    element.is_empty()
+        || element.eq_ignore_ascii_case("null")
+        || element
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '{' | '}' | ',' | '"' | '\\'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements() {
+        assert_eq!(parse("{}"), Ok(vec![]));
+        assert_eq!(
+            parse(" { 1, 2 ,NULL} "),
+            Ok(vec![Some("1".to_owned()), Some("2".to_owned()), None])
+        );
+        assert_eq!(
+            parse(r#"{"a b","","null",c\,d}"#),
+            Ok(vec![
+                Some("a b".to_owned()),
+                Some("".to_owned()),
+                Some("null".to_owned()),
+                Some("c,d".to_owned())
+            ])
+        );
+    }
+
+    #[test]
+    fn malformed_literals() {
+        assert_eq!(parse("1,2"), Err("malformed array literal: \"1,2\"".to_owned()));
+        assert_eq!(parse("{1,,2}"), Err("malformed array literal: \"{1,,2}\"".to_owned()));
+        assert_eq!(
+            parse("{{1},{2}}"),
+            Err("multidimensional arrays are not supported".to_owned())
+        );
+    }
+
+    #[test]
+    fn rendering() {
+        assert_eq!(render(&[]), "{}");
+        assert_eq!(
+            render(&[
+                Some("1".to_owned()),
+                None,
+                Some("a \"b\"".to_owned()),
+                Some("NULL".to_owned())
+            ]),
+            r#"{1,NULL,"a \"b\"","NULL"}"#
+        );
+    }
+}
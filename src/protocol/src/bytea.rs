@@ -0,0 +1,69 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text representation of BYTEA values in hex format, e.g. `\x0a0b`
+
+/// `\x` followed by two lowercase hex digits for every byte
+pub fn encode_hex(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(2 + data.len() * 2);
+    encoded.push_str("\\x");
+    for byte in data {
+        encoded.push_str(format!("{:02x}", byte).as_str());
+    }
+    encoded
+}
+
+/// `\x` followed by pairs of hex digits, otherwise the bytes of the text as they are
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let hex = match text.strip_prefix("\\x") {
+        Some(hex) => hex,
+        None => return Ok(text.as_bytes().to_vec()),
+    };
+    if let Some(invalid) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hexadecimal digit: \"{}\"", invalid));
+    }
+    if hex.len() % 2 != 0 {
+        return Err("invalid hexadecimal data: odd number of digits".to_owned());
+    }
+    Ok((0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("hex digits are validated"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(encode_hex(&[0, 10, 255]), "\\x000aff");
+        assert_eq!(decode("\\x000AfF"), Ok(vec![0, 10, 255]));
+        assert_eq!(decode("\\x"), Ok(vec![]));
+    }
+
+    #[test]
+    fn escape_format_is_taken_as_is() {
+        assert_eq!(decode("abc"), Ok(vec![97, 98, 99]));
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(decode("\\x0z"), Err("invalid hexadecimal digit: \"z\"".to_owned()));
+        assert_eq!(
+            decode("\\x012"),
+            Err("invalid hexadecimal data: odd number of digits".to_owned())
+        );
+    }
+}
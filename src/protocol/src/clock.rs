@@ -0,0 +1,541 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt::Debug,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub(crate) const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub(crate) const MICROS_PER_SECOND: i64 = 1_000_000;
+pub(crate) const MICROS_PER_DAY: i64 = SECONDS_PER_DAY * MICROS_PER_SECOND;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+/// time zones are at most 15 hours away from UTC
+pub const MAX_UTC_OFFSET_HOURS: i32 = 15;
+
+/// Source of the current time for `now()` and `current_timestamp`.
+/// Sessions use [SystemClock](SystemClock) unless a test or deterministic
+/// mode replaces it with [FrozenClock](FrozenClock) or its own implementation.
+pub trait Clock: Debug + Send + Sync {
+    /// current moment in time
+    fn now(&self) -> SystemTime;
+
+    /// offset of the clock time zone from UTC in seconds
+    fn utc_offset(&self) -> i32 {
+        0
+    }
+}
+
+/// Clock that reads operating system time in UTC
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that always returns the same moment in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrozenClock {
+    moment: SystemTime,
+    utc_offset: i32,
+}
+
+impl FrozenClock {
+    /// Creates clock frozen at `moment` in UTC
+    pub fn new(moment: SystemTime) -> FrozenClock {
+        FrozenClock { moment, utc_offset: 0 }
+    }
+
+    /// Creates clock frozen at `seconds` since Unix epoch in UTC
+    pub fn at_unix_seconds(seconds: u64) -> FrozenClock {
+        FrozenClock::new(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    /// Moves clock to the time zone that is `utc_offset` seconds east of UTC
+    pub fn with_utc_offset(self, utc_offset: i32) -> FrozenClock {
+        FrozenClock { utc_offset, ..self }
+    }
+}
+
+impl Clock for FrozenClock {
+    fn now(&self) -> SystemTime {
+        self.moment
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+}
+
+/// Renders current time of the clock as PostgreSQL renders `timestamp with time zone`,
+/// e.g. `2020-08-01 12:30:00.5+03`
+pub fn timestamp_with_time_zone(clock: &dyn Clock) -> String {
+    render_timestamp_with_time_zone(unix_micros(clock.now()), clock.utc_offset())
+}
+
+/// microseconds since Unix epoch, negative for moments before it
+pub fn unix_micros(moment: SystemTime) -> i64 {
+    match moment.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => since_epoch.as_micros() as i64,
+        Err(error) => -(error.duration().as_micros() as i64),
+    }
+}
+
+/// Renders microseconds since Unix epoch in the time zone that is `utc_offset` seconds east of UTC
+pub fn render_timestamp_with_time_zone(micros: i64, utc_offset: i32) -> String {
+    let local_seconds = micros.div_euclid(MICROS_PER_SECOND) + utc_offset as i64;
+    let micros = micros.rem_euclid(MICROS_PER_SECOND);
+    let (year, month, day) = civil_from_days(local_seconds.div_euclid(SECONDS_PER_DAY));
+    let time_of_day = local_seconds.rem_euclid(SECONDS_PER_DAY);
+
+    let mut rendered = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    );
+    if micros != 0 {
+        rendered.push_str(format!(".{:06}", micros).trim_end_matches('0'));
+    }
+    let sign = if utc_offset < 0 { '-' } else { '+' };
+    let offset_minutes = utc_offset.abs() / 60;
+    rendered.push_str(format!("{}{:02}", sign, offset_minutes / 60).as_str());
+    if offset_minutes % 60 != 0 {
+        rendered.push_str(format!(":{:02}", offset_minutes % 60).as_str());
+    }
+    rendered
+}
+
+/// Renders the date of microseconds since Unix epoch in the time zone that is `utc_offset` seconds east of UTC
+pub fn render_date(micros: i64, utc_offset: i32) -> String {
+    let (year, month, day) = civil_from_days(local_micros(micros, utc_offset).div_euclid(MICROS_PER_DAY));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Field of a timestamp that `date_part` extracts and `date_trunc` truncates to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Millennium,
+    Century,
+    Decade,
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Milliseconds,
+    Microseconds,
+    DayOfWeek,
+    IsoDayOfWeek,
+    DayOfYear,
+    Epoch,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Field> {
+        match name.to_lowercase().as_str() {
+            "millennium" | "millennia" => Some(Field::Millennium),
+            "century" | "centuries" => Some(Field::Century),
+            "decade" | "decades" => Some(Field::Decade),
+            "year" | "years" => Some(Field::Year),
+            "quarter" => Some(Field::Quarter),
+            "month" | "months" => Some(Field::Month),
+            "week" | "weeks" => Some(Field::Week),
+            "day" | "days" => Some(Field::Day),
+            "hour" | "hours" => Some(Field::Hour),
+            "minute" | "minutes" => Some(Field::Minute),
+            "second" | "seconds" => Some(Field::Second),
+            "millisecond" | "milliseconds" => Some(Field::Milliseconds),
+            "microsecond" | "microseconds" => Some(Field::Microseconds),
+            "dow" => Some(Field::DayOfWeek),
+            "isodow" => Some(Field::IsoDayOfWeek),
+            "doy" => Some(Field::DayOfYear),
+            "epoch" => Some(Field::Epoch),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts `field` of microseconds since Unix epoch in the time zone that is `utc_offset` seconds east of UTC,
+/// years before the first one are counted the way PostgreSQL does, i.e. there is no year 0
+pub fn date_part(field: &str, micros: i64, utc_offset: i32) -> Option<f64> {
+    let local = local_micros(micros, utc_offset);
+    let days = local.div_euclid(MICROS_PER_DAY);
+    let time_of_day = local.rem_euclid(MICROS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let micros_of_minute = time_of_day % MICROS_PER_MINUTE;
+    let value = match Field::parse(field)? {
+        Field::Millennium if year > 0 => (year + 999) / 1000,
+        Field::Millennium => -((999 - (year - 1)) / 1000),
+        Field::Century if year > 0 => (year + 99) / 100,
+        Field::Century => -((99 - (year - 1)) / 100),
+        Field::Decade => year.div_euclid(10),
+        Field::Year if year > 0 => year,
+        Field::Year => year - 1,
+        Field::Quarter => (month - 1) / 3 + 1,
+        Field::Month => month,
+        Field::Week => (days - first_iso_week_day(days, year)) / 7 + 1,
+        Field::Day => day,
+        Field::Hour => time_of_day / MICROS_PER_HOUR,
+        Field::Minute => time_of_day % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+        Field::Second => return Some(micros_of_minute as f64 / MICROS_PER_SECOND as f64),
+        Field::Milliseconds => return Some(micros_of_minute as f64 / 1_000.0),
+        Field::Microseconds => micros_of_minute,
+        Field::DayOfWeek => iso_day_of_week(days) % 7,
+        Field::IsoDayOfWeek => iso_day_of_week(days),
+        Field::DayOfYear => days - days_from_civil(year, 1, 1) + 1,
+        Field::Epoch => return Some(micros as f64 / MICROS_PER_SECOND as f64),
+    };
+    Some(value as f64)
+}
+
+/// Truncates microseconds since Unix epoch to the start of `field` in the time zone that is `utc_offset` seconds
+/// east of UTC, weeks start on Monday
+pub fn date_trunc(field: &str, micros: i64, utc_offset: i32) -> Option<i64> {
+    let local = local_micros(micros, utc_offset);
+    let days = local.div_euclid(MICROS_PER_DAY);
+    let (year, month, _day) = civil_from_days(days);
+    let truncated_days = match Field::parse(field)? {
+        Field::Millennium => days_from_civil((year + 999).div_euclid(1000) * 1000 - 999, 1, 1),
+        Field::Century => days_from_civil((year + 99).div_euclid(100) * 100 - 99, 1, 1),
+        Field::Decade => days_from_civil(year.div_euclid(10) * 10, 1, 1),
+        Field::Year => days_from_civil(year, 1, 1),
+        Field::Quarter => days_from_civil(year, (month - 1) / 3 * 3 + 1, 1),
+        Field::Month => days_from_civil(year, month, 1),
+        Field::Week => days - iso_day_of_week(days) + 1,
+        Field::Day => days,
+        Field::Hour => return Some(micros - local.rem_euclid(MICROS_PER_HOUR)),
+        Field::Minute => return Some(micros - local.rem_euclid(MICROS_PER_MINUTE)),
+        Field::Second => return Some(micros - local.rem_euclid(MICROS_PER_SECOND)),
+        Field::Milliseconds => return Some(micros - local.rem_euclid(1_000)),
+        Field::Microseconds => return Some(micros),
+        Field::DayOfWeek | Field::IsoDayOfWeek | Field::DayOfYear | Field::Epoch => return None,
+    };
+    Some(micros - (local - truncated_days * MICROS_PER_DAY))
+}
+
+fn local_micros(micros: i64, utc_offset: i32) -> i64 {
+    micros + utc_offset as i64 * MICROS_PER_SECOND
+}
+
+/// 1 for Monday through 7 for Sunday, Unix epoch is on Thursday
+fn iso_day_of_week(days: i64) -> i64 {
+    (days + 3).rem_euclid(7) + 1
+}
+
+/// Monday of the first ISO week that the day belongs to, the first week of a year is the one with its 4th of January
+fn first_iso_week_day(days: i64, year: i64) -> i64 {
+    [year + 1, year, year - 1]
+        .iter()
+        .map(|year| {
+            let fourth_of_january = days_from_civil(*year, 1, 4);
+            fourth_of_january - iso_day_of_week(fourth_of_january) + 1
+        })
+        .find(|monday| *monday <= days)
+        .unwrap_or(days)
+}
+
+/// Parses `timestamp with time zone` text, e.g. `2020-08-01 12:30:00.5+03`, into microseconds since Unix epoch.
+/// Text without a time zone is read in the time zone that is `utc_offset` seconds east of UTC
+pub fn parse_timestamp_with_time_zone(text: &str, utc_offset: i32) -> Option<i64> {
+    parse_timestamp_in_time_zone(text, utc_offset).map(|(micros, _utc_offset)| micros)
+}
+
+/// Same as [parse_timestamp_with_time_zone](parse_timestamp_with_time_zone) that also returns the time zone of the text
+pub fn parse_timestamp_in_time_zone(text: &str, utc_offset: i32) -> Option<(i64, i32)> {
+    let text = text.trim();
+    let (date, time) = match text.find(|c: char| c == ' ' || c == 'T') {
+        Some(position) => (&text[..position], text[position + 1..].trim_start()),
+        None => (text, ""),
+    };
+    let days = parse_date(date)?;
+    let time_end = time
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or_else(|| time.len());
+    let micros = if time_end == 0 {
+        0
+    } else {
+        parse_time(&time[..time_end])?
+    };
+    let utc_offset = match time[time_end..].trim() {
+        "" => utc_offset,
+        zone => parse_utc_offset(zone)?,
+    };
+    Some((
+        (days * SECONDS_PER_DAY - utc_offset as i64) * MICROS_PER_SECOND + micros,
+        utc_offset,
+    ))
+}
+
+/// Parses `Z`, `UTC`, `GMT` or ISO 8601 offsets such as `+03`, `-05:30` and `+0530` into seconds east of UTC
+pub fn parse_utc_offset(zone: &str) -> Option<i32> {
+    if ["z", "utc", "gmt"].iter().any(|name| zone.eq_ignore_ascii_case(name)) {
+        return Some(0);
+    }
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = zone[1..].replace(':', "");
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = if digits.len() <= 2 {
+        (digits.parse::<i32>().ok()?, 0)
+    } else {
+        let (hours, minutes) = digits.split_at(digits.len() - 2);
+        (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?)
+    };
+    if hours > MAX_UTC_OFFSET_HOURS || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<i64>().ok()?;
+    let day = parts.next()?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+fn parse_time(time: &str) -> Option<i64> {
+    let mut parts = time.splitn(3, ':');
+    let hours = parts.next()?.parse::<i64>().ok()?;
+    let minutes = parts.next()?.parse::<i64>().ok()?;
+    let (seconds, micros) = match parts.next() {
+        None => (0, 0),
+        Some(seconds) => match seconds.find('.') {
+            None => (seconds.parse::<i64>().ok()?, 0),
+            Some(dot) => {
+                let fraction = &seconds[dot + 1..];
+                if fraction.is_empty() || fraction.len() > 6 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                (
+                    seconds[..dot].parse::<i64>().ok()?,
+                    format!("{:0<6}", fraction).parse::<i64>().ok()?,
+                )
+            }
+        },
+    };
+    if hours > 24 || minutes > 59 || seconds > 59 || (hours == 24 && minutes + seconds + micros != 0) {
+        return None;
+    }
+    Some(((hours * 60 + minutes) * 60 + seconds) * MICROS_PER_SECOND + micros)
+}
+
+pub(crate) fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// converts (year, month, day) of the proleptic Gregorian calendar into days since Unix epoch
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// converts days since Unix epoch into (year, month, day) of the proleptic Gregorian calendar
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_epoch() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::at_unix_seconds(0)),
+            "1970-01-01 00:00:00+00"
+        );
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::at_unix_seconds(951_782_400)),
+            "2000-02-29 00:00:00+00"
+        );
+    }
+
+    #[test]
+    fn fraction_of_second() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::new(UNIX_EPOCH + Duration::from_millis(1_500))),
+            "1970-01-01 00:00:01.5+00"
+        );
+    }
+
+    #[test]
+    fn time_zone_east_of_utc() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::at_unix_seconds(0).with_utc_offset(3 * 3600 + 30 * 60)),
+            "1970-01-01 03:30:00+03:30"
+        );
+    }
+
+    #[test]
+    fn time_zone_west_of_utc() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::at_unix_seconds(0).with_utc_offset(-5 * 3600)),
+            "1969-12-31 19:00:00-05"
+        );
+    }
+
+    #[test]
+    fn before_unix_epoch() {
+        assert_eq!(
+            timestamp_with_time_zone(&FrozenClock::new(UNIX_EPOCH - Duration::from_millis(500))),
+            "1969-12-31 23:59:59.5+00"
+        );
+    }
+
+    #[test]
+    fn parse_with_time_zone() {
+        assert_eq!(
+            parse_timestamp_with_time_zone("2020-08-01 12:30:00.5+03", 0),
+            Some(1_596_274_200_500_000)
+        );
+    }
+
+    #[test]
+    fn parse_in_given_time_zone() {
+        assert_eq!(
+            parse_timestamp_with_time_zone("1970-01-01 03:30", 3 * 3600 + 30 * 60),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn parse_date_only() {
+        assert_eq!(
+            parse_timestamp_with_time_zone("2000-02-29", 0),
+            Some(951_782_400_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_utc_designators() {
+        assert_eq!(
+            parse_timestamp_with_time_zone("1970-01-01T00:00:01Z", -5 * 3600),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            parse_timestamp_with_time_zone("1970-01-01 00:00:01 UTC", 3600),
+            Some(1_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_timestamps() {
+        assert_eq!(parse_timestamp_with_time_zone("2019-02-29 00:00:00", 0), None);
+        assert_eq!(parse_timestamp_with_time_zone("2020-01-01 25:00:00", 0), None);
+        assert_eq!(parse_timestamp_with_time_zone("2020-01-01 10:00:00+16", 0), None);
+        assert_eq!(parse_timestamp_with_time_zone("yesterday", 0), None);
+    }
+
+    #[test]
+    fn render_parsed_timestamp() {
+        let micros = parse_timestamp_with_time_zone("1969-12-31 23:59:59.25-05:30", 0).expect("valid timestamp");
+        assert_eq!(
+            render_timestamp_with_time_zone(micros, -5 * 3600 - 30 * 60),
+            "1969-12-31 23:59:59.25-05:30"
+        );
+    }
+
+    #[test]
+    fn render_date_in_time_zone() {
+        let micros = parse_timestamp_with_time_zone("2020-08-01 22:30:00+00", 0).expect("valid timestamp");
+        assert_eq!(render_date(micros, 0), "2020-08-01");
+        assert_eq!(render_date(micros, 2 * 3600), "2020-08-02");
+    }
+
+    #[test]
+    fn date_parts() {
+        let micros = parse_timestamp_with_time_zone("2021-01-03 12:34:56.5+00", 0).expect("valid timestamp");
+        assert_eq!(date_part("year", micros, 0), Some(2021.0));
+        assert_eq!(date_part("QUARTER", micros, 0), Some(1.0));
+        assert_eq!(date_part("day", micros, 0), Some(3.0));
+        assert_eq!(date_part("hour", micros, 3 * 3600), Some(15.0));
+        assert_eq!(date_part("second", micros, 0), Some(56.5));
+        assert_eq!(date_part("milliseconds", micros, 0), Some(56_500.0));
+        assert_eq!(date_part("dow", micros, 0), Some(0.0));
+        assert_eq!(date_part("isodow", micros, 0), Some(7.0));
+        assert_eq!(date_part("doy", micros, 0), Some(3.0));
+        assert_eq!(date_part("week", micros, 0), Some(53.0));
+        assert_eq!(date_part("century", micros, 0), Some(21.0));
+        assert_eq!(date_part("epoch", micros, 0), Some(1_609_677_296.5));
+        assert_eq!(date_part("fortnight", micros, 0), None);
+    }
+
+    #[test]
+    fn truncate_timestamps() {
+        let micros = parse_timestamp_with_time_zone("2020-08-13 12:34:56.789+00", 0).expect("valid timestamp");
+        let truncated = |field: &str, utc_offset: i32| {
+            date_trunc(field, micros, utc_offset).map(|micros| render_timestamp_with_time_zone(micros, utc_offset))
+        };
+        assert_eq!(truncated("second", 0), Some("2020-08-13 12:34:56+00".to_owned()));
+        assert_eq!(truncated("hour", 0), Some("2020-08-13 12:00:00+00".to_owned()));
+        assert_eq!(truncated("day", 0), Some("2020-08-13 00:00:00+00".to_owned()));
+        assert_eq!(truncated("day", -14 * 3600), Some("2020-08-12 00:00:00-14".to_owned()));
+        assert_eq!(truncated("week", 0), Some("2020-08-10 00:00:00+00".to_owned()));
+        assert_eq!(truncated("quarter", 0), Some("2020-07-01 00:00:00+00".to_owned()));
+        assert_eq!(truncated("century", 0), Some("2001-01-01 00:00:00+00".to_owned()));
+        assert_eq!(
+            truncated("hour", 5 * 3600 + 30 * 60),
+            Some("2020-08-13 18:00:00+05:30".to_owned())
+        );
+        assert_eq!(truncated("dow", 0), None);
+    }
+}
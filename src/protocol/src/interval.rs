@@ -0,0 +1,509 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+};
+
+use crate::clock::{civil_from_days, days_from_civil, days_in_month, MICROS_PER_DAY, MICROS_PER_SECOND};
+
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+/// months and days are compared as if a month has 30 days
+const DAYS_PER_MONTH: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+}
+
+impl Unit {
+    fn parse(name: &str) -> Option<Unit> {
+        match name.to_lowercase().as_str() {
+            "year" | "years" | "yr" | "yrs" | "y" => Some(Unit::Year),
+            "month" | "months" | "mon" | "mons" => Some(Unit::Month),
+            "week" | "weeks" | "w" => Some(Unit::Week),
+            "day" | "days" | "d" => Some(Unit::Day),
+            "hour" | "hours" | "hr" | "hrs" | "h" => Some(Unit::Hour),
+            "minute" | "minutes" | "min" | "mins" | "m" => Some(Unit::Minute),
+            "second" | "seconds" | "sec" | "secs" | "s" => Some(Unit::Second),
+            "millisecond" | "milliseconds" | "msec" | "msecs" | "ms" => Some(Unit::Millisecond),
+            "microsecond" | "microseconds" | "usec" | "usecs" | "us" => Some(Unit::Microsecond),
+            _ => None,
+        }
+    }
+}
+
+/// Time span of `INTERVAL` type. Months, days and the time of a day are kept apart the same way PostgreSQL does,
+/// because the number of days in a month and the length of a day differ from one timestamp to another
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    /// interval of months, days and microseconds of a day
+    pub fn new(months: i32, days: i32, micros: i64) -> Interval {
+        Interval { months, days, micros }
+    }
+
+    /// number of months, years are kept as 12 months
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    /// number of days, they are not converted into months
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    /// time of a day in microseconds, hours are not converted into days
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    /// Parses PostgreSQL interval text, e.g. `1 year 2 mons 3 days 04:05:06.5`, `1.5 hours` or `2 days ago`.
+    /// A number without a unit at the end is a number of seconds
+    pub fn parse(text: &str) -> Option<Interval> {
+        let mut interval = Interval::default();
+        let mut tokens = text.split_whitespace().peekable();
+        let mut ago = false;
+        let mut parsed = false;
+        while let Some(token) = tokens.next() {
+            if ago {
+                return None;
+            }
+            if token.eq_ignore_ascii_case("ago") {
+                ago = true;
+            } else if token.contains(':') {
+                interval.micros = interval.micros.checked_add(parse_time(token)?)?;
+            } else {
+                let value = token.parse::<f64>().ok().filter(|value| value.is_finite())?;
+                let unit = match tokens.peek().and_then(|name| Unit::parse(name)) {
+                    Some(unit) => {
+                        tokens.next();
+                        unit
+                    }
+                    None if tokens.peek().is_none() => Unit::Second,
+                    None => return None,
+                };
+                interval.add(value, unit)?;
+            }
+            parsed = true;
+        }
+        if !parsed {
+            return None;
+        }
+        if ago {
+            interval.checked_neg()
+        } else {
+            Some(interval)
+        }
+    }
+
+    /// Parses text of `INTERVAL 'text' field` literals, a plain number is a number of `field`s, e.g. `INTERVAL '7' DAY`
+    pub fn parse_with_field(text: &str, field: &str) -> Option<Interval> {
+        match (text.trim().parse::<f64>(), Unit::parse(field)) {
+            (Ok(value), Some(unit)) if value.is_finite() => {
+                let mut interval = Interval::default();
+                interval.add(value, unit)?;
+                Some(interval)
+            }
+            _ => Interval::parse(text),
+        }
+    }
+
+    /// sum of months, days and microseconds apart, `None` on overflow
+    pub fn checked_add(self, other: Interval) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            micros: self.micros.checked_add(other.micros)?,
+        })
+    }
+
+    /// difference of months, days and microseconds apart, `None` on overflow
+    pub fn checked_sub(self, other: Interval) -> Option<Interval> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    /// interval with every part negated, `None` on overflow
+    pub fn checked_neg(self) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            micros: self.micros.checked_neg()?,
+        })
+    }
+
+    /// fractions of months and days cascade down to days and the time of a day
+    pub fn checked_mul(self, factor: f64) -> Option<Interval> {
+        if !factor.is_finite() {
+            return None;
+        }
+        let mut interval = Interval::default();
+        interval.add_months(self.months as f64 * factor)?;
+        interval.add_days(self.days as f64 * factor)?;
+        interval.add_micros(self.micros as f64 * factor)?;
+        Some(interval)
+    }
+
+    /// Adds the interval to microseconds since Unix epoch. Months are added to the calendar date in the time zone that
+    /// is `utc_offset` seconds east of UTC, the day of a month is clamped to the last day of the resulting month
+    pub fn add_to_timestamp(&self, micros: i64, utc_offset: i32) -> Option<i64> {
+        let offset = utc_offset as i64 * MICROS_PER_SECOND;
+        let mut local = micros.checked_add(offset)?;
+        if self.months != 0 {
+            let (year, month, day) = civil_from_days(local.div_euclid(MICROS_PER_DAY));
+            let months = year.checked_mul(12)? + month - 1 + self.months as i64;
+            let (year, month) = (months.div_euclid(12), months.rem_euclid(12) + 1);
+            let days = days_from_civil(year, month, day.min(days_in_month(year, month)));
+            local = days
+                .checked_mul(MICROS_PER_DAY)?
+                .checked_add(local.rem_euclid(MICROS_PER_DAY))?;
+        }
+        local
+            .checked_add((self.days as i64).checked_mul(MICROS_PER_DAY)?)?
+            .checked_add(self.micros)?
+            .checked_sub(offset)
+    }
+
+    /// Symbolic difference of two moments in microseconds since Unix epoch that PostgreSQL `age` returns. Dates and
+    /// times are subtracted in the time zone that is `utc_offset` seconds east of UTC, a negative time borrows a day
+    /// and negative days borrow a month that has as many days as the month of the earlier moment
+    pub fn age(micros: i64, since: i64, utc_offset: i32) -> Option<Interval> {
+        let (later, earlier) = if micros >= since {
+            (micros, since)
+        } else {
+            (since, micros)
+        };
+        let offset = utc_offset as i64 * MICROS_PER_SECOND;
+        let fields = |micros: i64| {
+            micros.checked_add(offset).map(|local| {
+                let (year, month, day) = civil_from_days(local.div_euclid(MICROS_PER_DAY));
+                (year, month, day, local.rem_euclid(MICROS_PER_DAY))
+            })
+        };
+        let (later_year, later_month, later_day, later_time) = fields(later)?;
+        let (earlier_year, earlier_month, earlier_day, earlier_time) = fields(earlier)?;
+        let mut months = (later_year - earlier_year) * 12 + later_month - earlier_month;
+        let mut days = later_day - earlier_day;
+        let mut micros_of_day = later_time - earlier_time;
+        if micros_of_day < 0 {
+            micros_of_day += MICROS_PER_DAY;
+            days -= 1;
+        }
+        if days < 0 {
+            days += days_in_month(earlier_year, earlier_month);
+            months -= 1;
+        }
+        let age = Interval::new(i32::try_from(months).ok()?, days as i32, micros_of_day);
+        if micros >= since {
+            Some(age)
+        } else {
+            age.checked_neg()
+        }
+    }
+
+    fn add(&mut self, value: f64, unit: Unit) -> Option<()> {
+        match unit {
+            Unit::Year => self.add_months(value * 12.0),
+            Unit::Month => self.add_months(value),
+            Unit::Week => self.add_days(value * 7.0),
+            Unit::Day => self.add_days(value),
+            Unit::Hour => self.add_micros(value * MICROS_PER_HOUR as f64),
+            Unit::Minute => self.add_micros(value * MICROS_PER_MINUTE as f64),
+            Unit::Second => self.add_micros(value * MICROS_PER_SECOND as f64),
+            Unit::Millisecond => self.add_micros(value * 1_000.0),
+            Unit::Microsecond => self.add_micros(value),
+        }
+    }
+
+    fn add_months(&mut self, months: f64) -> Option<()> {
+        let whole = months.trunc();
+        self.months = self.months.checked_add(to_i32(whole)?)?;
+        self.add_days((months - whole) * DAYS_PER_MONTH as f64)
+    }
+
+    fn add_days(&mut self, days: f64) -> Option<()> {
+        let whole = days.trunc();
+        self.days = self.days.checked_add(to_i32(whole)?)?;
+        self.add_micros((days - whole) * MICROS_PER_DAY as f64)
+    }
+
+    fn add_micros(&mut self, micros: f64) -> Option<()> {
+        let micros = micros.round();
+        if micros.abs() >= i64::max_value() as f64 {
+            return None;
+        }
+        self.micros = self.micros.checked_add(micros as i64)?;
+        Some(())
+    }
+
+    fn normalized(&self) -> i128 {
+        (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+}
+
+fn to_i32(value: f64) -> Option<i32> {
+    if value.abs() <= i32::max_value() as f64 {
+        Some(value as i32)
+    } else {
+        None
+    }
+}
+
+/// `[-]hours:minutes[:seconds[.fraction]]`, hours are not limited to a day
+fn parse_time(token: &str) -> Option<i64> {
+    let (sign, time) = match token.chars().next()? {
+        '-' => (-1, &token[1..]),
+        '+' => (1, &token[1..]),
+        _ => (1, token),
+    };
+    let mut parts = time.splitn(3, ':');
+    let hours = parts.next()?.parse::<u32>().ok()? as i64;
+    let minutes = parts.next()?.parse::<u32>().ok()? as i64;
+    let seconds = match parts.next() {
+        Some(seconds) => seconds
+            .parse::<f64>()
+            .ok()
+            .filter(|seconds| (0.0..60.0).contains(seconds))?,
+        None => 0.0,
+    };
+    if minutes > 59 {
+        return None;
+    }
+    let micros = hours
+        .checked_mul(MICROS_PER_HOUR)?
+        .checked_add(minutes * MICROS_PER_MINUTE + (seconds * MICROS_PER_SECOND as f64).round() as i64)?;
+    Some(sign * micros)
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Interval) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Interval) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Interval) -> Ordering {
+        self.normalized().cmp(&other.normalized())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state)
+    }
+}
+
+/// PostgreSQL `postgres` output style, e.g. `1 year 2 mons -3 days +04:05:06.5`
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        let mut negative = false;
+        for (value, unit) in [
+            (self.months / 12, "year"),
+            (self.months % 12, "mon"),
+            (self.days, "day"),
+        ]
+        .iter()
+        {
+            if *value != 0 {
+                let sign = if negative && *value > 0 { "+" } else { "" };
+                let plural = if *value == 1 { "" } else { "s" };
+                parts.push(format!("{}{} {}{}", sign, value, unit, plural));
+                negative = negative || *value < 0;
+            }
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = match self.micros.cmp(&0) {
+                Ordering::Less => "-",
+                Ordering::Greater if negative => "+",
+                _ => "",
+            };
+            let micros = (self.micros as i128).abs();
+            let seconds = micros / MICROS_PER_SECOND as i128;
+            let mut time = format!(
+                "{}{:02}:{:02}:{:02}",
+                sign,
+                seconds / 3600,
+                seconds % 3600 / 60,
+                seconds % 60
+            );
+            let fraction = micros % MICROS_PER_SECOND as i128;
+            if fraction != 0 {
+                time.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+            }
+            parts.push(time);
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(text: &str) -> Interval {
+        Interval::parse(text).expect("valid interval")
+    }
+
+    #[test]
+    fn parse_units() {
+        assert_eq!(
+            interval("1 year 2 months 3 days 4 hours 5 minutes 6 seconds"),
+            Interval::new(
+                14,
+                3,
+                4 * MICROS_PER_HOUR + 5 * MICROS_PER_MINUTE + 6 * MICROS_PER_SECOND
+            )
+        );
+        assert_eq!(interval("2 weeks"), Interval::new(0, 14, 0));
+        assert_eq!(interval("10"), Interval::new(0, 0, 10 * MICROS_PER_SECOND));
+        assert_eq!(interval("250 ms"), Interval::new(0, 0, 250_000));
+    }
+
+    #[test]
+    fn parse_time_of_day() {
+        assert_eq!(
+            interval("1 day -01:30:00.5"),
+            Interval::new(
+                0,
+                1,
+                -(MICROS_PER_HOUR + 30 * MICROS_PER_MINUTE + MICROS_PER_SECOND / 2)
+            )
+        );
+    }
+
+    #[test]
+    fn fractions_cascade_down() {
+        assert_eq!(interval("1.5 days").days(), 1);
+        assert_eq!(interval("1.5 days").micros(), 12 * MICROS_PER_HOUR);
+        assert_eq!(interval("1.5 months").days(), 15);
+    }
+
+    #[test]
+    fn ago_negates() {
+        assert_eq!(interval("2 days ago"), Interval::new(0, -2, 0));
+    }
+
+    #[test]
+    fn invalid_text() {
+        assert_eq!(Interval::parse(""), None);
+        assert_eq!(Interval::parse("1 fortnight"), None);
+        assert_eq!(Interval::parse("1:60"), None);
+        assert_eq!(Interval::parse("ago 1 day"), None);
+    }
+
+    #[test]
+    fn literal_with_field() {
+        assert_eq!(Interval::parse_with_field("7", "DAY"), Some(Interval::new(0, 7, 0)));
+        assert_eq!(
+            Interval::parse_with_field("1 hour", "DAY"),
+            Some(Interval::new(0, 0, MICROS_PER_HOUR))
+        );
+    }
+
+    #[test]
+    fn rendering() {
+        assert_eq!(interval("0 days").to_string(), "00:00:00");
+        assert_eq!(interval("1 day").to_string(), "1 day");
+        assert_eq!(
+            interval("14 months 2 days 3 hours").to_string(),
+            "1 year 2 mons 2 days 03:00:00"
+        );
+        assert_eq!(interval("-1 day 1 hour").to_string(), "-1 days +01:00:00");
+        assert_eq!(interval("1.25 seconds").to_string(), "00:00:01.25");
+        assert_eq!(interval("-3 years").to_string(), "-3 years");
+    }
+
+    #[test]
+    fn compared_by_normalized_length() {
+        assert_eq!(interval("1 day"), interval("24 hours"));
+        assert!(interval("1 month") > interval("29 days"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            interval("1 day").checked_add(interval("2 hours")),
+            Some(Interval::new(0, 1, 2 * MICROS_PER_HOUR))
+        );
+        assert_eq!(
+            interval("1 day").checked_sub(interval("2 days")),
+            Some(Interval::new(0, -1, 0))
+        );
+        assert_eq!(interval("1 month").checked_mul(1.5), Some(Interval::new(1, 15, 0)));
+    }
+
+    #[test]
+    fn added_to_timestamp() {
+        let january_31 = 18_293 * MICROS_PER_DAY;
+        let february_29 = 18_322 * MICROS_PER_DAY;
+        assert_eq!(interval("1 month").add_to_timestamp(january_31, 0), Some(february_29));
+        assert_eq!(
+            interval("1 day 1 hour").add_to_timestamp(0, 0),
+            Some(MICROS_PER_DAY + MICROS_PER_HOUR)
+        );
+    }
+
+    #[test]
+    fn months_are_added_in_time_zone() {
+        // 2020-01-31 23:00:00-01 is 2020-02-01 in UTC
+        let moment = 18_293 * MICROS_PER_DAY + 24 * MICROS_PER_HOUR;
+        assert_eq!(
+            interval("1 month").add_to_timestamp(moment, -3600),
+            Some(18_322 * MICROS_PER_DAY + 24 * MICROS_PER_HOUR)
+        );
+    }
+
+    #[test]
+    fn age_borrows_days_of_earlier_month() {
+        let moment = |text: &str| crate::clock::parse_timestamp_with_time_zone(text, 0).expect("valid timestamp");
+        let age = |micros: &str, since: &str| {
+            Interval::age(moment(micros), moment(since), 0)
+                .expect("age in range")
+                .to_string()
+        };
+        assert_eq!(age("2020-03-01", "2020-01-31"), "1 mon 1 day");
+        assert_eq!(age("2020-01-31", "2020-03-01"), "-1 mons -1 days");
+        assert_eq!(
+            age("2021-06-15 10:00:00", "2000-01-20 12:00:00"),
+            "21 years 4 mons 25 days 22:00:00"
+        );
+    }
+}
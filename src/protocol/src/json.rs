@@ -0,0 +1,444 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text representation of `JSON` and `JSONB` values and extraction of their parts
+
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::Peekable,
+    str::Chars,
+};
+
+/// parsed `JSON` value
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Bool(bool),
+    /// numbers are kept as they are written to not lose precision
+    Number(String),
+    /// unescaped text of a string
+    String(String),
+    /// elements in the order they are written
+    Array(Vec<JsonValue>),
+    /// keys are in the order they are written, duplicates are kept
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// whole text has to be a single JSON value surrounded by optional whitespaces
+pub fn parse(text: &str) -> Result<JsonValue, String> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    skip_whitespaces(&mut chars);
+    match chars.next() {
+        None => Ok(value),
+        Some(c) => Err(format!("Token \"{}\" is invalid.", c)),
+    }
+}
+
+/// path of `#>` operator written as a text array, e.g. `{a,0,"b c"}`
+pub fn parse_path(text: &str) -> Result<Vec<String>, String> {
+    let inner = match text.trim().strip_prefix('{').and_then(|text| text.strip_suffix('}')) {
+        Some(inner) => inner.trim(),
+        None => return Err(format!("malformed array literal: \"{}\"", text)),
+    };
+    if inner.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(inner
+        .split(',')
+        .map(str::trim)
+        .map(|element| {
+            element
+                .strip_prefix('"')
+                .and_then(|element| element.strip_suffix('"'))
+                .unwrap_or(element)
+                .to_owned()
+        })
+        .collect())
+}
+
+impl JsonValue {
+    /// representation of `JSONB` values: the last of duplicated keys wins, keys are ordered by length and then by bytes
+    pub fn normalized(self) -> JsonValue {
+        match self {
+            JsonValue::Array(elements) => JsonValue::Array(elements.into_iter().map(JsonValue::normalized).collect()),
+            JsonValue::Object(fields) => {
+                let mut normalized: Vec<(String, JsonValue)> = vec![];
+                for (key, value) in fields {
+                    normalized.retain(|(existing, _)| *existing != key);
+                    normalized.push((key, value.normalized()));
+                }
+                normalized.sort_by(|(left, _), (right, _)| left.len().cmp(&right.len()).then_with(|| left.cmp(right)));
+                JsonValue::Object(normalized)
+            }
+            value => value,
+        }
+    }
+
+    /// the last field with the `key` of an object
+    pub fn field(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// element of an array, negative indexes count from the end of the array
+    pub fn element(&self, index: i64) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Array(elements) => {
+                let index = if index < 0 {
+                    elements.len() as i64 + index
+                } else {
+                    index
+                };
+                if index < 0 {
+                    None
+                } else {
+                    elements.get(index as usize)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// path elements are keys of objects or indexes of arrays
+    pub fn path<S: AsRef<str>>(&self, path: &[S]) -> Option<&JsonValue> {
+        path.iter().try_fold(self, |value, step| match value {
+            JsonValue::Array(_) => step.as_ref().parse::<i64>().ok().and_then(|index| value.element(index)),
+            _ => value.field(step.as_ref()),
+        })
+    }
+
+    /// text of a value as `->>` returns it: strings without quotes and SQL NULL instead of JSON null
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            JsonValue::Null => None,
+            JsonValue::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+impl Display for JsonValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+            JsonValue::Number(value) => write!(f, "{}", value),
+            JsonValue::String(value) => write_string(f, value),
+            JsonValue::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(fields) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            '\u{8}' => write!(f, "\\b")?,
+            '\u{c}' => write!(f, "\\f")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+fn skip_whitespaces(chars: &mut Peekable<Chars>) {
+    while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = chars.peek() {
+        chars.next();
+    }
+}
+
+fn unexpected(c: Option<char>) -> String {
+    match c {
+        Some(c) => format!("Token \"{}\" is invalid.", c),
+        None => "The input string ended unexpectedly.".to_owned(),
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    skip_whitespaces(chars);
+    match chars.peek().copied() {
+        Some('{') => {
+            chars.next();
+            parse_object(chars)
+        }
+        Some('[') => {
+            chars.next();
+            parse_array(chars)
+        }
+        Some('"') => {
+            chars.next();
+            parse_string(chars).map(JsonValue::String)
+        }
+        Some(c) if c == '-' || c.is_ascii_digit() => parse_number(chars).map(JsonValue::Number),
+        Some(c) if c.is_ascii_alphabetic() => {
+            let mut word = String::new();
+            while let Some(c) = chars.peek().copied().filter(char::is_ascii_alphanumeric) {
+                word.push(c);
+                chars.next();
+            }
+            match word.as_str() {
+                "null" => Ok(JsonValue::Null),
+                "true" => Ok(JsonValue::Bool(true)),
+                "false" => Ok(JsonValue::Bool(false)),
+                _ => Err(format!("Token \"{}\" is invalid.", word)),
+            }
+        }
+        c => Err(unexpected(c)),
+    }
+}
+
+fn parse_object(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    let mut fields = vec![];
+    skip_whitespaces(chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return Ok(JsonValue::Object(fields));
+    }
+    loop {
+        skip_whitespaces(chars);
+        match chars.next() {
+            Some('"') => {}
+            c => return Err(unexpected(c)),
+        }
+        let key = parse_string(chars)?;
+        skip_whitespaces(chars);
+        match chars.next() {
+            Some(':') => {}
+            c => return Err(unexpected(c)),
+        }
+        fields.push((key, parse_value(chars)?));
+        skip_whitespaces(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => return Ok(JsonValue::Object(fields)),
+            c => return Err(unexpected(c)),
+        }
+    }
+}
+
+fn parse_array(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    let mut elements = vec![];
+    skip_whitespaces(chars);
+    if chars.peek() == Some(&']') {
+        chars.next();
+        return Ok(JsonValue::Array(elements));
+    }
+    loop {
+        elements.push(parse_value(chars)?);
+        skip_whitespaces(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some(']') => return Ok(JsonValue::Array(elements)),
+            c => return Err(unexpected(c)),
+        }
+    }
+}
+
+/// opening quote is already consumed
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('/') => value.push('/'),
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let high = parse_code_unit(chars)?;
+                    let code = if (0xd800..0xdc00).contains(&high) {
+                        match (chars.next(), chars.next()) {
+                            (Some('\\'), Some('u')) => {}
+                            _ => return Err("Unicode high surrogate must not follow a high surrogate.".to_owned()),
+                        }
+                        let low = parse_code_unit(chars)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err("Unicode low surrogate must follow a high surrogate.".to_owned());
+                        }
+                        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        high
+                    };
+                    match std::char::from_u32(code) {
+                        Some(c) => value.push(c),
+                        None => return Err("Unicode low surrogate must follow a high surrogate.".to_owned()),
+                    }
+                }
+                Some(c) => return Err(format!("Escape sequence \"\\{}\" is invalid.", c)),
+                None => return Err(unexpected(None)),
+            },
+            Some(c) if c.is_control() => {
+                return Err(format!("Character with value 0x{:02x} must be escaped.", c as u32))
+            }
+            Some(c) => value.push(c),
+            None => return Err(unexpected(None)),
+        }
+    }
+}
+
+fn parse_code_unit(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let mut code = 0;
+    for _ in 0..4 {
+        match chars.next().and_then(|c| c.to_digit(16)) {
+            Some(digit) => code = code * 16 + digit,
+            None => return Err("\"\\u\" must be followed by four hexadecimal digits.".to_owned()),
+        }
+    }
+    Ok(code)
+}
+
+/// `-?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?`
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut number = String::new();
+    if chars.peek() == Some(&'-') {
+        number.push('-');
+        chars.next();
+    }
+    match chars.next() {
+        Some('0') => number.push('0'),
+        Some(c) if c.is_ascii_digit() => {
+            number.push(c);
+            push_digits(chars, &mut number);
+        }
+        c => return Err(unexpected(c)),
+    }
+    if chars.peek() == Some(&'.') {
+        number.push('.');
+        chars.next();
+        if !push_digits(chars, &mut number) {
+            return Err(unexpected(chars.peek().copied()));
+        }
+    }
+    if let Some('e') | Some('E') = chars.peek() {
+        number.push('e');
+        chars.next();
+        if let Some(sign @ '+') | Some(sign @ '-') = chars.peek().copied() {
+            number.push(sign);
+            chars.next();
+        }
+        if !push_digits(chars, &mut number) {
+            return Err(unexpected(chars.peek().copied()));
+        }
+    }
+    match chars.peek() {
+        Some(c) if c.is_ascii_alphanumeric() => Err(format!("Token \"{}{}\" is invalid.", number, c)),
+        _ => Ok(number),
+    }
+}
+
+fn push_digits(chars: &mut Peekable<Chars>, number: &mut String) -> bool {
+    let mut pushed = false;
+    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+        number.push(c);
+        chars.next();
+        pushed = true;
+    }
+    pushed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(parse("null"), Ok(JsonValue::Null));
+        assert_eq!(parse(" true "), Ok(JsonValue::Bool(true)));
+        assert_eq!(parse("-1.5e3"), Ok(JsonValue::Number("-1.5e3".to_owned())));
+        assert_eq!(parse(r#""a\"b\u00e9""#), Ok(JsonValue::String("a\"bé".to_owned())));
+    }
+
+    #[test]
+    fn nested_values() {
+        let value = parse(r#"{"a": [1, {"b": null}], "c": "d"}"#).unwrap();
+        assert_eq!(value.to_string(), r#"{"a": [1, {"b": null}], "c": "d"}"#);
+        assert_eq!(value.path(&["a", "1", "b"]), Some(&JsonValue::Null));
+        assert_eq!(value.path(&["a", "-2"]), Some(&JsonValue::Number("1".to_owned())));
+        assert_eq!(value.path(&["c", "0"]), None);
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_eq!(parse("{\"a\" 1}"), Err("Token \"1\" is invalid.".to_owned()));
+        assert_eq!(parse("[1, 2"), Err("The input string ended unexpectedly.".to_owned()));
+        assert_eq!(parse("01"), Err("Token \"01\" is invalid.".to_owned()));
+        assert_eq!(parse("nul"), Err("Token \"nul\" is invalid.".to_owned()));
+        assert_eq!(parse("1 2"), Err("Token \"2\" is invalid.".to_owned()));
+    }
+
+    #[test]
+    fn normalization() {
+        let value = parse(r#"{"bb": 1, "a": {"c": 2, "c": 3}, "bb": 4}"#)
+            .unwrap()
+            .normalized();
+        assert_eq!(value.to_string(), r#"{"a": {"c": 3}, "bb": 4}"#);
+    }
+
+    #[test]
+    fn text_of_values() {
+        assert_eq!(JsonValue::String("a".to_owned()).to_text(), Some("a".to_owned()));
+        assert_eq!(JsonValue::Null.to_text(), None);
+        assert_eq!(parse("[1]").unwrap().to_text(), Some("[1]".to_owned()));
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(parse_path("{}"), Ok(vec![]));
+        assert_eq!(
+            parse_path("{a, 1,\"b c\"}"),
+            Ok(vec!["a".to_owned(), "1".to_owned(), "b c".to_owned()])
+        );
+        assert_eq!(parse_path("a"), Err("malformed array literal: \"a\"".to_owned()));
+    }
+}
@@ -37,16 +37,28 @@ use futures_lite::{
 use itertools::Itertools;
 
 use crate::{
-    messages::{BackendMessage, Encryption, FrontendMessage},
+    messages::{BackendMessage, Encryption, FrontendMessage, TransactionStatus},
     pgsql_types::{PostgreSqlFormat, PostgreSqlType},
-    results::QueryResult,
+    results::{QueryError, QueryResult},
 };
 
+/// Module contains functionality to represent one-dimensional arrays as text
+pub mod array;
+/// Module contains functionality to represent `BYTEA` values as text
+pub mod bytea;
+/// Module contains functionality to read current time of a client session
+pub mod clock;
+/// Module contains functionality to represent and compute time spans of `INTERVAL` type
+pub mod interval;
+/// Module contains functionality to validate and query `JSON` and `JSONB` values
+pub mod json;
 /// Module contains backend messages that could be send by server implementation
 /// to a client
 pub mod messages;
 /// Module contains functionality to represent SQL type system
 pub mod pgsql_types;
+/// Module contains pseudo-random number generator of a client session
+pub mod random;
 /// Module contains functionality to represent query result
 pub mod results;
 /// Module contains functionality to represent server side client session
@@ -58,6 +70,10 @@ pub mod statement;
 pub type Version = i32;
 /// Connection key-value params
 pub type Params = Vec<(String, String)>;
+/// Id of a backend process that serves a client connection
+pub type ProcessId = u32;
+/// Key that a client has to send along with `ProcessId` to cancel a running query
+pub type SecretKey = u32;
 /// Protocol operation result
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -74,6 +90,17 @@ pub const VERSION_SSL: Version = (1234 << 16) + 5679;
 /// Client initiate `gss` encrypted connection
 pub const VERSION_GSSENC: Version = (1234 << 16) + 5680;
 
+/// Version of PostgreSQL that the server reports to clients
+pub const SERVER_VERSION: &str = "12.4";
+/// Startup parameter with name of the database to connect to
+pub const DATABASE: &str = "database";
+/// Startup parameter with name of the role that the client connects as
+pub const USER: &str = "user";
+/// Startup parameter with name of the client application
+pub const APPLICATION_NAME: &str = "application_name";
+/// Startup parameter with character set encoding of the client
+pub const CLIENT_ENCODING: &str = "client_encoding";
+
 /// `Error` type in protocol `Result`. Indicates that something went not well
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -109,8 +136,37 @@ pub enum Command {
         /// The desired formats for the columns in the result set.
         result_formats: Vec<PostgreSqlFormat>,
     },
+    /// Client commands to close a portal
+    ClosePortal {
+        /// The name of the portal to close. An empty string selects the
+        /// unnamed portal.
+        name: String,
+    },
+    /// Client commands to close a prepared statement and portals bound to it
+    CloseStatement {
+        /// The name of the prepared statement to close. An empty string
+        /// selects the unnamed prepared statement.
+        name: String,
+    },
     /// Nothing needs to handle on client, just to receive next message
     Continue,
+    /// Client sends data of `COPY ... FROM STDIN`
+    CopyData {
+        /// The part of the data stream.
+        data: Vec<u8>,
+    },
+    /// Client finished sending data of `COPY ... FROM STDIN`
+    CopyDone,
+    /// Client aborts `COPY ... FROM STDIN`
+    CopyFail {
+        /// The reason of the failure.
+        message: String,
+    },
+    /// Client commands to describe rows that a portal returns
+    DescribePortal {
+        /// The name of the portal to describe.
+        name: String,
+    },
     /// Client commands to describe a prepared statement
     DescribeStatement {
         /// The name of the prepared statement to describe.
@@ -135,28 +191,42 @@ pub enum Command {
         /// The SQL to parse.
         sql: String,
         /// The number of specified parameter data types can be less than the
-        /// number of parameters specified in the query.
-        param_types: Vec<PostgreSqlType>,
+        /// number of parameters specified in the query, `None` leaves the
+        /// type unspecified (OID 0) to be inferred by the server.
+        param_types: Vec<Option<PostgreSqlType>>,
     },
     /// Client commands to execute a `Query`
     Query {
         /// The SQL to execute.
         sql: String,
     },
+    /// Client ends a sequence of extended query commands and waits for the
+    /// server to be ready for the next query
+    Sync,
     /// Client commands to terminate current connection
     Terminate,
 }
 
+/// Request of a client that opened a connection
+pub enum ClientRequest<R: Receiver, S: Sender> {
+    /// Connection is established, `R` and `S` have to be used to communicate
+    /// with the client on performing commands
+    Connect((R, S)),
+    /// Client asks to cancel a query that is running in the connection with
+    /// the given process id and secret key, the connection is not used after that
+    QueryCancellation(ProcessId, SecretKey),
+}
+
 /// Perform `PostgreSql` wire protocol hand shake to establish connection with
 /// a client based on `config` parameters and using `stream` as a medium to
 /// communicate
-/// As a result of operation returns tuple of `Receiver` and `Sender`
-/// that have to be used to communicate with the client on performing commands
+/// The client receives `key_data` to identify the connection in cancel requests
 pub async fn hand_shake<RW>(
     stream: RW,
     address: SocketAddr,
     config: &ProtocolConfiguration,
-) -> io::Result<Result<(impl Receiver, impl Sender)>>
+    key_data: (ProcessId, SecretKey),
+) -> io::Result<Result<ClientRequest<impl Receiver, impl Sender>>>
 where
     RW: AsyncRead + AsyncWrite + Unpin,
 {
@@ -177,6 +247,17 @@ where
 
         match decode_startup(message) {
             Ok(ClientHandshake::Startup(version, params)) => {
+                log::debug!(
+                    "database {:?} application {:?}",
+                    param(&params, DATABASE),
+                    param(&params, APPLICATION_NAME)
+                );
+                if let Some(encoding) = param(&params, CLIENT_ENCODING).filter(|encoding| !is_utf8(encoding)) {
+                    let message = format!("invalid value for parameter \"{}\": \"{}\"", CLIENT_ENCODING, encoding);
+                    let error: BackendMessage = QueryError::invalid_parameter_value(message.as_str()).into();
+                    channel.write_all(error.as_vec().as_slice()).await?;
+                    return Ok(Err(Error::InvalidInput(message)));
+                }
                 channel
                     .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
                     .await?;
@@ -197,40 +278,33 @@ where
                     .write_all(BackendMessage::AuthenticationOk.as_vec().as_slice())
                     .await?;
 
-                channel
-                    .write_all(
-                        BackendMessage::ParameterStatus("client_encoding".to_owned(), "UTF8".to_owned())
-                            .as_vec()
-                            .as_slice(),
-                    )
-                    .await?;
+                for message in parameter_status(&params) {
+                    channel.write_all(message.as_vec().as_slice()).await?;
+                }
 
+                let (process_id, secret_key) = key_data;
                 channel
                     .write_all(
-                        BackendMessage::ParameterStatus("DateStyle".to_owned(), "ISO".to_owned())
+                        BackendMessage::BackendKeyData(process_id, secret_key)
                             .as_vec()
                             .as_slice(),
                     )
                     .await?;
 
+                log::debug!("Send ready_for_query message");
                 channel
                     .write_all(
-                        BackendMessage::ParameterStatus("integer_datetimes".to_owned(), "off".to_owned())
+                        BackendMessage::ReadyForQuery(TransactionStatus::Idle)
                             .as_vec()
                             .as_slice(),
                     )
                     .await?;
 
-                log::debug!("Send ready_for_query message");
-                channel
-                    .write_all(BackendMessage::ReadyForQuery.as_vec().as_slice())
-                    .await?;
-
                 let channel = Arc::new(AsyncMutex::new(channel));
-                return Ok(Ok((
+                return Ok(Ok(ClientRequest::Connect((
                     RequestReceiver::new((version, params.clone()), channel.clone()),
                     ResponseSender::new((version, params), channel),
-                )));
+                ))));
             }
             Ok(ClientHandshake::SslRequest) => {
                 channel = match channel {
@@ -244,6 +318,9 @@ where
                     }
                 };
             }
+            Ok(ClientHandshake::CancelRequest(process_id, secret_key)) => {
+                return Ok(Ok(ClientRequest::QueryCancellation(process_id, secret_key)))
+            }
             Ok(ClientHandshake::GssEncryptRequest) => return Ok(Err(Error::UnsupportedRequest)),
             Err(error) => return Ok(Err(error)),
         }
@@ -275,19 +352,56 @@ fn decode_startup(message: Vec<u8>) -> Result<ClientHandshake> {
         VERSION_3 => {
             let params = message[4..]
                 .split(|b| *b == 0)
-                .filter(|b| !b.is_empty())
-                .map(|b| std::str::from_utf8(b).unwrap().to_owned())
+                .map(|b| std::str::from_utf8(b).map(ToOwned::to_owned))
+                .collect::<std::result::Result<Vec<String>, _>>()
+                .map_err(|_| Error::InvalidUtfString)?
+                .into_iter()
                 .tuples()
+                .take_while(|(name, _value)| !name.is_empty())
                 .collect::<Params>();
             Ok(ClientHandshake::Startup(version, params))
         }
-        VERSION_CANCEL => Err(Error::UnsupportedVersion),
+        VERSION_CANCEL if message.len() == 12 => Ok(ClientHandshake::CancelRequest(
+            NetworkEndian::read_u32(&message[4..8]),
+            NetworkEndian::read_u32(&message[8..12]),
+        )),
+        VERSION_CANCEL => Err(Error::InvalidInput("invalid length of cancel request".to_owned())),
         VERSION_GSSENC => Ok(ClientHandshake::GssEncryptRequest),
         VERSION_SSL => Ok(ClientHandshake::SslRequest),
         _ => Err(Error::UnrecognizedVersion),
     }
 }
 
+/// value of a startup parameter that the client sent, e.g. `database` or `application_name`
+pub fn param<'p>(params: &'p Params, name: &str) -> Option<&'p str> {
+    params
+        .iter()
+        .find(|(key, _value)| key == name)
+        .map(|(_key, value)| value.as_str())
+}
+
+fn is_utf8(encoding: &str) -> bool {
+    matches!(encoding.to_uppercase().as_str(), "UTF8" | "UTF-8" | "UNICODE")
+}
+
+/// backend parameters that are reported to a client after authentication
+fn parameter_status(params: &Params) -> Vec<BackendMessage> {
+    let mut statuses = vec![];
+    if let Some(application_name) = param(params, APPLICATION_NAME) {
+        statuses.push((APPLICATION_NAME, application_name));
+    }
+    statuses.extend(vec![
+        (CLIENT_ENCODING, "UTF8"),
+        ("DateStyle", "ISO, MDY"),
+        ("integer_datetimes", "on"),
+        ("server_version", SERVER_VERSION),
+    ]);
+    statuses
+        .into_iter()
+        .map(|(name, value)| BackendMessage::ParameterStatus(name.to_owned(), value.to_owned()))
+        .collect()
+}
+
 struct RequestReceiver<RW: AsyncRead + AsyncWrite + Unpin> {
     properties: (Version, Params),
     channel: Arc<AsyncMutex<Channel<RW>>>,
@@ -298,15 +412,14 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> RequestReceiver<RW> {
     pub(crate) fn new(properties: (Version, Params), channel: Arc<AsyncMutex<Channel<RW>>>) -> RequestReceiver<RW> {
         RequestReceiver { properties, channel }
     }
-
-    /// connection properties tuple
-    pub fn properties(&self) -> &(Version, Params) {
-        &(self.properties)
-    }
 }
 
 #[async_trait]
 impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
+    fn properties(&self) -> &(Version, Params) {
+        &(self.properties)
+    }
+
     async fn receive(&mut self) -> io::Result<Result<Command>> {
         // Parses the one-byte tag.
         let mut buffer = [0u8; 1];
@@ -354,6 +467,12 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
                 raw_params,
                 result_formats,
             })),
+            FrontendMessage::ClosePortal { name } => Ok(Ok(Command::ClosePortal { name })),
+            FrontendMessage::CloseStatement { name } => Ok(Ok(Command::CloseStatement { name })),
+            FrontendMessage::CopyData(data) => Ok(Ok(Command::CopyData { data })),
+            FrontendMessage::CopyDone => Ok(Ok(Command::CopyDone)),
+            FrontendMessage::CopyFail { message } => Ok(Ok(Command::CopyFail { message })),
+            FrontendMessage::DescribePortal { name } => Ok(Ok(Command::DescribePortal { name })),
             FrontendMessage::DescribeStatement { name } => Ok(Ok(Command::DescribeStatement { name })),
             FrontendMessage::Execute { portal_name, max_rows } => Ok(Ok(Command::Execute { portal_name, max_rows })),
             FrontendMessage::Flush => Ok(Ok(Command::Flush)),
@@ -367,8 +486,8 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
                 param_types,
             })),
             FrontendMessage::Query { sql } => Ok(Ok(Command::Query { sql })),
+            FrontendMessage::Sync => Ok(Ok(Command::Sync)),
             FrontendMessage::Terminate => Ok(Ok(Command::Terminate)),
-            _ => Ok(Ok(Command::Continue)),
         }
     }
 }
@@ -376,6 +495,9 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
 /// Trait to handle client to server commands for PostgreSQL Wire Protocol connection
 #[async_trait]
 pub trait Receiver: Send + Sync {
+    /// connection properties tuple
+    fn properties(&self) -> &(Version, Params);
+
     /// receives and decodes a command from remote client
     async fn receive(&mut self) -> io::Result<Result<Command>>;
 }
@@ -538,6 +660,7 @@ impl ProtocolConfiguration {
 }
 
 enum ClientHandshake {
+    CancelRequest(ProcessId, SecretKey),
     SslRequest,
     GssEncryptRequest,
     Startup(Version, Params),
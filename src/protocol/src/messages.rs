@@ -18,7 +18,7 @@ use byteorder::{ByteOrder, NetworkEndian};
 
 use crate::{
     pgsql_types::{PostgreSqlFormat, PostgreSqlType},
-    Error, Result,
+    Error, ProcessId, Result, SecretKey,
 };
 
 const COMMAND_COMPLETE: u8 = b'C';
@@ -27,6 +27,9 @@ const ERROR_RESPONSE: u8 = b'E';
 const SEVERITY: u8 = b'S';
 const CODE: u8 = b'C';
 const MESSAGE: u8 = b'M';
+const DETAIL: u8 = b'D';
+const HINT: u8 = b'H';
+const POSITION: u8 = b'P';
 const EMPTY_QUERY_RESPONSE: u8 = b'I';
 const NOTICE_RESPONSE: u8 = b'N';
 const AUTHENTICATION: u8 = b'R';
@@ -38,6 +41,13 @@ const NO_DATA: u8 = b'n';
 const PARSE_COMPLETE: u8 = b'1';
 const BIND_COMPLETE: u8 = b'2';
 const CLOSE_COMPLETE: u8 = b'3';
+const PORTAL_SUSPENDED: u8 = b's';
+const BACKEND_KEY_DATA: u8 = b'K';
+const COPY_IN_RESPONSE: u8 = b'G';
+const COPY_OUT_RESPONSE: u8 = b'H';
+const COPY_DATA: u8 = b'd';
+const COPY_DONE: u8 = b'c';
+const NOTIFICATION_RESPONSE: u8 = b'A';
 
 pub(crate) enum Encryption {
     AcceptSsl,
@@ -75,8 +85,9 @@ pub enum FrontendMessage {
         /// The SQL to parse.
         sql: String,
         /// The number of specified parameter data types can be less than the
-        /// number of parameters specified in the query.
-        param_types: Vec<PostgreSqlType>,
+        /// number of parameters specified in the query, `None` leaves the
+        /// type unspecified (OID 0) to be inferred by the server.
+        param_types: Vec<Option<PostgreSqlType>>,
     },
 
     /// Describe an existing prepared statement.
@@ -151,6 +162,24 @@ pub enum FrontendMessage {
         name: String,
     },
 
+    /// Data of `COPY ... FROM STDIN`, rows may be split across messages.
+    ///
+    /// This command is part of the copy flow.
+    CopyData(Vec<u8>),
+
+    /// All data of `COPY ... FROM STDIN` is sent.
+    ///
+    /// This command is part of the copy flow.
+    CopyDone,
+
+    /// `COPY ... FROM STDIN` failed on the client side.
+    ///
+    /// This command is part of the copy flow.
+    CopyFail {
+        /// The reason of the failure.
+        message: String,
+    },
+
     /// Terminate a connection.
     Terminate,
 }
@@ -178,6 +207,11 @@ impl FrontendMessage {
             b'P' => decode_parse(cursor),
             b'S' => decode_sync(cursor),
 
+            // Copy flow.
+            b'd' => decode_copy_data(cursor),
+            b'c' => decode_copy_done(cursor),
+            b'f' => decode_copy_fail(cursor),
+
             // Termination.
             b'X' => decode_terminate(cursor),
 
@@ -197,7 +231,7 @@ impl FrontendMessage {
 pub enum BackendMessage {
     /// A warning message has been issued. The frontend should display the message
     /// but continue listening for ReadyForQuery or ErrorResponse.
-    NoticeResponse,
+    NoticeResponse(Option<&'static str>, Option<&'static str>, Option<String>),
     /// The frontend must now send a PasswordMessage containing the password in
     /// clear-text form. If this is the correct password, the server responds
     /// with an AuthenticationOk, otherwise it responds with an ErrorResponse.
@@ -214,10 +248,15 @@ pub enum BackendMessage {
     AuthenticationMD5Password,
     /// The authentication exchange is successfully completed.
     AuthenticationOk,
-    /// Start-up is completed. The frontend can now issue commands.
-    ReadyForQuery,
-    /// One of the set of rows returned by a SELECT, FETCH, etc query.
-    DataRow(Vec<String>),
+    /// Identifies the connection with process id and secret key that the
+    /// frontend must send in `CancelRequest` to cancel a running query.
+    BackendKeyData(ProcessId, SecretKey),
+    /// Start-up or processing of a query is completed. The frontend can now issue
+    /// commands, the backend is in the transaction status.
+    ReadyForQuery(TransactionStatus),
+    /// One of the set of rows returned by a SELECT, FETCH, etc query, fields are
+    /// already encoded in the format requested by the frontend.
+    DataRow(Vec<Vec<u8>>),
     /// Indicates that rows are about to be returned in response to a SELECT, FETCH,
     /// etc query. The contents of this message describe the column layout of
     /// the rows. This will be followed by a DataRow message for each row being
@@ -226,11 +265,10 @@ pub enum BackendMessage {
     /// An SQL command completed normally.
     CommandComplete(String),
     /// An empty query string was recognized.
-    #[allow(dead_code)]
     EmptyQueryResponse,
-    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`)
+    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`, `Error Details`)
     /// all of them are optional
-    ErrorResponse(Option<&'static str>, Option<&'static str>, Option<String>),
+    ErrorResponse(Option<&'static str>, Option<&'static str>, Option<String>, ErrorDetails),
     /// This message informs the frontend about the current (initial) setting of
     /// backend parameters, such as client_encoding or DateStyle
     ///
@@ -250,22 +288,50 @@ pub enum BackendMessage {
     /// This message informs the frontend about the previous `Close` frontend
     /// message is successful.
     CloseComplete,
+    /// The row-count limit of the `Execute` frontend message was reached, the
+    /// portal can be executed again to fetch the rest of rows.
+    PortalSuspended,
+    /// The backend is ready to copy data from the frontend to a table, all the
+    /// given number of columns are expected in text format.
+    CopyInResponse(i16),
+    /// The backend starts to copy data of a table to the frontend, all the
+    /// given number of columns are sent in text format.
+    CopyOutResponse(i16),
+    /// One or more rows of `COPY ... TO STDOUT`.
+    CopyData(Vec<u8>),
+    /// All data of `COPY ... TO STDOUT` is sent.
+    CopyDone,
+    /// A notification with the process id of the notifying backend, the channel
+    /// name and the payload, it can be sent at any time between commands.
+    NotificationResponse(ProcessId, String, String),
 }
 
 impl BackendMessage {
     /// returns binary representation of a backend message
     pub fn as_vec(&self) -> Vec<u8> {
         match self {
-            BackendMessage::NoticeResponse => vec![NOTICE_RESPONSE],
+            BackendMessage::NoticeResponse(severity, code, message) => response_fields(
+                NOTICE_RESPONSE,
+                *severity,
+                *code,
+                message.as_deref(),
+                &ErrorDetails::default(),
+            ),
             BackendMessage::AuthenticationCleartextPassword => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 3],
             BackendMessage::AuthenticationMD5Password => vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1],
             BackendMessage::AuthenticationOk => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 0],
-            BackendMessage::ReadyForQuery => vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE],
+            BackendMessage::BackendKeyData(process_id, secret_key) => {
+                let mut buff = vec![BACKEND_KEY_DATA, 0, 0, 0, 12];
+                buff.extend_from_slice(&process_id.to_be_bytes());
+                buff.extend_from_slice(&secret_key.to_be_bytes());
+                buff
+            }
+            BackendMessage::ReadyForQuery(status) => vec![READY_FOR_QUERY, 0, 0, 0, 5, status.as_byte()],
             BackendMessage::DataRow(row) => {
                 let mut row_buff = Vec::new();
                 for field in row.iter() {
                     row_buff.extend_from_slice(&(field.len() as i32).to_be_bytes());
-                    row_buff.extend_from_slice(field.as_str().as_bytes());
+                    row_buff.extend_from_slice(field);
                 }
                 let mut len_buff = Vec::new();
                 len_buff.extend_from_slice(&[DATA_ROW]);
@@ -279,8 +345,8 @@ impl BackendMessage {
                 for field in description.iter() {
                     buff.extend_from_slice(field.name.as_str().as_bytes());
                     buff.extend_from_slice(&[0]); // end of c string
-                    buff.extend_from_slice(&(0i32).to_be_bytes()); // table id
-                    buff.extend_from_slice(&(0i16).to_be_bytes()); // column id
+                    buff.extend_from_slice(&field.table_id.to_be_bytes());
+                    buff.extend_from_slice(&field.column_id.to_be_bytes());
                     buff.extend_from_slice(&field.type_id.to_be_bytes());
                     buff.extend_from_slice(&field.type_size.to_be_bytes());
                     buff.extend_from_slice(&(-1i32).to_be_bytes()); // type modifier
@@ -302,29 +368,8 @@ impl BackendMessage {
                 command_buff
             }
             BackendMessage::EmptyQueryResponse => vec![EMPTY_QUERY_RESPONSE, 0, 0, 0, 4],
-            BackendMessage::ErrorResponse(severity, code, message) => {
-                let mut error_response_buff = Vec::new();
-                error_response_buff.extend_from_slice(&[ERROR_RESPONSE]);
-                let mut message_buff = Vec::new();
-                if let Some(severity) = severity.as_ref() {
-                    message_buff.extend_from_slice(&[SEVERITY]);
-                    message_buff.extend_from_slice(severity.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                if let Some(code) = code.as_ref() {
-                    message_buff.extend_from_slice(&[CODE]);
-                    message_buff.extend_from_slice(code.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                if let Some(message) = message.as_ref() {
-                    message_buff.extend_from_slice(&[MESSAGE]);
-                    message_buff.extend_from_slice(message.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                error_response_buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
-                error_response_buff.extend_from_slice(message_buff.as_ref());
-                error_response_buff.extend_from_slice(&[0]);
-                error_response_buff.to_vec()
+            BackendMessage::ErrorResponse(severity, code, message, details) => {
+                response_fields(ERROR_RESPONSE, *severity, *code, message.as_deref(), details)
             }
             BackendMessage::ParameterStatus(name, value) => {
                 let mut parameter_status_buff = Vec::new();
@@ -354,10 +399,44 @@ impl BackendMessage {
             BackendMessage::ParseComplete => vec![PARSE_COMPLETE, 0, 0, 0, 4],
             BackendMessage::BindComplete => vec![BIND_COMPLETE, 0, 0, 0, 4],
             BackendMessage::CloseComplete => vec![CLOSE_COMPLETE, 0, 0, 0, 4],
+            BackendMessage::PortalSuspended => vec![PORTAL_SUSPENDED, 0, 0, 0, 4],
+            BackendMessage::CopyInResponse(columns) => copy_response(COPY_IN_RESPONSE, *columns),
+            BackendMessage::CopyOutResponse(columns) => copy_response(COPY_OUT_RESPONSE, *columns),
+            BackendMessage::CopyData(data) => {
+                let mut buff = vec![COPY_DATA];
+                buff.extend_from_slice(&(4 + data.len() as i32).to_be_bytes());
+                buff.extend_from_slice(data);
+                buff
+            }
+            BackendMessage::CopyDone => vec![COPY_DONE, 0, 0, 0, 4],
+            BackendMessage::NotificationResponse(process_id, channel, payload) => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&process_id.to_be_bytes());
+                buff.extend_from_slice(channel.as_bytes());
+                buff.push(0);
+                buff.extend_from_slice(payload.as_bytes());
+                buff.push(0);
+                let mut len_buff = vec![NOTIFICATION_RESPONSE];
+                len_buff.extend_from_slice(&(4 + buff.len() as i32).to_be_bytes());
+                len_buff.extend_from_slice(&buff);
+                len_buff
+            }
         }
     }
 }
 
+/// overall text format and text format of every column
+fn copy_response(tag: u8, columns: i16) -> Vec<u8> {
+    let mut buff = vec![tag];
+    buff.extend_from_slice(&(7 + 2 * columns as i32).to_be_bytes());
+    buff.push(0);
+    buff.extend_from_slice(&columns.to_be_bytes());
+    for _ in 0..columns {
+        buff.extend_from_slice(&0i16.to_be_bytes());
+    }
+    buff
+}
+
 /// Struct description of metadata that describes how client should interpret
 /// outgoing selected data
 #[derive(Clone, Debug, PartialEq)]
@@ -368,6 +447,10 @@ pub struct ColumnMetadata {
     pub type_id: u32,
     /// PostgreSQL data type size
     pub type_size: i16,
+    /// OID of the table that column belongs to or zero if column is computed
+    pub table_id: u32,
+    /// attribute number of the column in the table or zero if column is computed
+    pub column_id: i16,
 }
 
 impl ColumnMetadata {
@@ -377,6 +460,18 @@ impl ColumnMetadata {
             name,
             type_id,
             type_size,
+            table_id: 0,
+            column_id: 0,
+        }
+    }
+
+    /// Sets table column that the column is read from, clients use it to
+    /// look up column nullability in `pg_attribute`
+    pub fn with_origin(self, table_id: u32, column_id: i16) -> Self {
+        Self {
+            table_id,
+            column_id,
+            ..self
         }
     }
 }
@@ -551,8 +646,7 @@ fn decode_parse(mut cursor: Cursor) -> Result<FrontendMessage> {
     let mut param_types = vec![];
     for _ in 0..cursor.read_i16()? {
         let oid = cursor.read_u32()?;
-        let sql_type = PostgreSqlType::try_from(oid).unwrap();
-        param_types.push(sql_type);
+        param_types.push(PostgreSqlType::try_from(oid).ok());
     }
 
     Ok(FrontendMessage::Parse {
@@ -571,10 +665,89 @@ fn decode_query(mut cursor: Cursor) -> Result<FrontendMessage> {
     Ok(FrontendMessage::Query { sql })
 }
 
+fn decode_copy_data(cursor: Cursor) -> Result<FrontendMessage> {
+    Ok(FrontendMessage::CopyData(cursor.buf.to_vec()))
+}
+
+fn decode_copy_done(_cursor: Cursor) -> Result<FrontendMessage> {
+    Ok(FrontendMessage::CopyDone)
+}
+
+fn decode_copy_fail(mut cursor: Cursor) -> Result<FrontendMessage> {
+    let message = cursor.read_cstr()?.to_owned();
+    Ok(FrontendMessage::CopyFail { message })
+}
+
 fn decode_terminate(_cursor: Cursor) -> Result<FrontendMessage> {
     Ok(FrontendMessage::Terminate)
 }
 
+/// Transaction status of the backend that is sent with `ReadyForQuery`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TransactionStatus {
+    /// not in a transaction block
+    Idle,
+    /// in a transaction block
+    InTransaction,
+    /// in a failed transaction block, queries are rejected until the block is ended
+    Failed,
+}
+
+impl TransactionStatus {
+    fn as_byte(self) -> u8 {
+        match self {
+            TransactionStatus::Idle => b'I',
+            TransactionStatus::InTransaction => b'T',
+            TransactionStatus::Failed => b'E',
+        }
+    }
+}
+
+/// optional fields of an error response that explain the error
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ErrorDetails {
+    /// secondary message that carries more details about the problem
+    pub detail: Option<String>,
+    /// suggestion what to do about the problem
+    pub hint: Option<String>,
+    /// 1-based index of the character in the query string where the error is found
+    pub position: Option<usize>,
+}
+
+/// encodes fields of error and notice responses
+fn response_fields(
+    tag: u8,
+    severity: Option<&str>,
+    code: Option<&str>,
+    message: Option<&str>,
+    details: &ErrorDetails,
+) -> Vec<u8> {
+    let mut response_buff = Vec::new();
+    response_buff.extend_from_slice(&[tag]);
+    let mut message_buff = Vec::new();
+    let position = details.position.map(|position| position.to_string());
+    for (field, value) in [
+        (SEVERITY, severity),
+        (CODE, code),
+        (MESSAGE, message),
+        (DETAIL, details.detail.as_deref()),
+        (HINT, details.hint.as_deref()),
+        (POSITION, position.as_deref()),
+    ]
+    .iter()
+    {
+        if let Some(value) = value {
+            message_buff.extend_from_slice(&[*field]);
+            message_buff.extend_from_slice(value.as_bytes());
+            message_buff.extend_from_slice(&[0]);
+        }
+    }
+    response_buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
+    response_buff.extend_from_slice(message_buff.as_ref());
+    response_buff.extend_from_slice(&[0]);
+    response_buff
+}
+
 #[cfg(test)]
 mod decoding_frontend_messages {
     use super::*;
@@ -693,7 +866,21 @@ mod decoding_frontend_messages {
             Ok(FrontendMessage::Parse {
                 statement_name: "".to_owned(),
                 sql: "select * from schema_name.table_name where si_column = $1;".to_owned(),
-                param_types: vec![PostgreSqlType::Integer],
+                param_types: vec![Some(PostgreSqlType::Integer)],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_with_unspecified_param_type() {
+        let buffer = [0, 115, 101, 108, 101, 99, 116, 32, 36, 49, 59, 0, 0, 1, 0, 0, 0, 0];
+        let message = FrontendMessage::decode(b'P', &buffer);
+        assert_eq!(
+            message,
+            Ok(FrontendMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "select $1;".to_owned(),
+                param_types: vec![None],
             })
         );
     }
@@ -704,6 +891,29 @@ mod decoding_frontend_messages {
         assert_eq!(message, Ok(FrontendMessage::Sync));
     }
 
+    #[test]
+    fn copy_data() {
+        let message = FrontendMessage::decode(b'd', b"1\ta\n2\t");
+        assert_eq!(message, Ok(FrontendMessage::CopyData(b"1\ta\n2\t".to_vec())));
+    }
+
+    #[test]
+    fn copy_done() {
+        let message = FrontendMessage::decode(b'c', &[]);
+        assert_eq!(message, Ok(FrontendMessage::CopyDone));
+    }
+
+    #[test]
+    fn copy_fail() {
+        let message = FrontendMessage::decode(b'f', b"canceled by user\0");
+        assert_eq!(
+            message,
+            Ok(FrontendMessage::CopyFail {
+                message: "canceled by user".to_owned()
+            })
+        );
+    }
+
     #[test]
     fn terminate() {
         let message = FrontendMessage::decode(b'X', &[]);
@@ -717,7 +927,44 @@ mod serializing_backend_messages {
 
     #[test]
     fn notice() {
-        assert_eq!(BackendMessage::NoticeResponse.as_vec(), vec![NOTICE_RESPONSE]);
+        assert_eq!(
+            BackendMessage::NoticeResponse(None, None, None).as_vec(),
+            vec![NOTICE_RESPONSE, 0, 0, 0, 5, 0]
+        )
+    }
+
+    #[test]
+    fn notice_with_fields() {
+        assert_eq!(
+            BackendMessage::NoticeResponse(Some("NOTICE"), Some("00000"), Some("ok".to_owned())).as_vec(),
+            vec![
+                NOTICE_RESPONSE,
+                0,
+                0,
+                0,
+                24,
+                SEVERITY,
+                b'N',
+                b'O',
+                b'T',
+                b'I',
+                b'C',
+                b'E',
+                0,
+                CODE,
+                b'0',
+                b'0',
+                b'0',
+                b'0',
+                b'0',
+                0,
+                MESSAGE,
+                b'o',
+                b'k',
+                0,
+                0
+            ]
+        )
     }
 
     #[test]
@@ -782,15 +1029,31 @@ mod serializing_backend_messages {
     #[test]
     fn ready_for_query() {
         assert_eq!(
-            BackendMessage::ReadyForQuery.as_vec(),
-            vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE]
+            BackendMessage::ReadyForQuery(TransactionStatus::Idle).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, b'I']
+        )
+    }
+
+    #[test]
+    fn ready_for_query_in_transaction() {
+        assert_eq!(
+            BackendMessage::ReadyForQuery(TransactionStatus::InTransaction).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, b'T']
+        )
+    }
+
+    #[test]
+    fn ready_for_query_in_failed_transaction() {
+        assert_eq!(
+            BackendMessage::ReadyForQuery(TransactionStatus::Failed).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, b'E']
         )
     }
 
     #[test]
     fn data_row() {
         assert_eq!(
-            BackendMessage::DataRow(vec!["1".to_owned(), "2".to_owned(), "3".to_owned()]).as_vec(),
+            BackendMessage::DataRow(vec![b"1".to_vec(), b"2".to_vec(), b"3".to_vec()]).as_vec(),
             vec![DATA_ROW, 0, 0, 0, 21, 0, 3, 0, 0, 0, 1, 49, 0, 0, 0, 1, 50, 0, 0, 0, 1, 51]
         )
     }
@@ -832,6 +1095,44 @@ mod serializing_backend_messages {
         );
     }
 
+    #[test]
+    fn row_description_with_column_origin() {
+        assert_eq!(
+            BackendMessage::RowDescription(vec![ColumnMetadata::new("c1".to_owned(), 23, 4).with_origin(16385, 1)])
+                .as_vec(),
+            vec![
+                ROW_DESCRIPTION,
+                0,
+                0,
+                0,
+                27,
+                0,
+                1,
+                99,
+                49,
+                0,
+                0,
+                0,
+                64,
+                1,
+                0,
+                1,
+                0,
+                0,
+                0,
+                23,
+                0,
+                4,
+                255,
+                255,
+                255,
+                255,
+                0,
+                0
+            ]
+        );
+    }
+
     #[test]
     fn command_complete() {
         assert_eq!(
@@ -851,11 +1152,53 @@ mod serializing_backend_messages {
     #[test]
     fn error_response() {
         assert_eq!(
-            BackendMessage::ErrorResponse(None, None, None).as_vec(),
+            BackendMessage::ErrorResponse(None, None, None, ErrorDetails::default()).as_vec(),
             vec![ERROR_RESPONSE, 0, 0, 0, 5, 0]
         )
     }
 
+    #[test]
+    fn error_response_with_details() {
+        assert_eq!(
+            BackendMessage::ErrorResponse(
+                Some("ERROR"),
+                None,
+                None,
+                ErrorDetails {
+                    detail: Some("d".to_owned()),
+                    hint: Some("h".to_owned()),
+                    position: Some(12),
+                }
+            )
+            .as_vec(),
+            vec![
+                ERROR_RESPONSE,
+                0,
+                0,
+                0,
+                22,
+                SEVERITY,
+                b'E',
+                b'R',
+                b'R',
+                b'O',
+                b'R',
+                0,
+                DETAIL,
+                b'd',
+                0,
+                HINT,
+                b'h',
+                0,
+                POSITION,
+                b'1',
+                b'2',
+                0,
+                0
+            ]
+        )
+    }
+
     #[test]
     fn parameter_description() {
         assert_eq!(
@@ -883,4 +1226,57 @@ mod serializing_backend_messages {
     fn close_complete() {
         assert_eq!(BackendMessage::CloseComplete.as_vec(), vec![CLOSE_COMPLETE, 0, 0, 0, 4])
     }
+
+    #[test]
+    fn portal_suspended() {
+        assert_eq!(
+            BackendMessage::PortalSuspended.as_vec(),
+            vec![PORTAL_SUSPENDED, 0, 0, 0, 4]
+        )
+    }
+
+    #[test]
+    fn backend_key_data() {
+        assert_eq!(
+            BackendMessage::BackendKeyData(1, 0x0102_0304).as_vec(),
+            vec![BACKEND_KEY_DATA, 0, 0, 0, 12, 0, 0, 0, 1, 1, 2, 3, 4]
+        )
+    }
+
+    #[test]
+    fn copy_in_response() {
+        assert_eq!(
+            BackendMessage::CopyInResponse(2).as_vec(),
+            vec![COPY_IN_RESPONSE, 0, 0, 0, 11, 0, 0, 2, 0, 0, 0, 0]
+        )
+    }
+
+    #[test]
+    fn copy_out_response() {
+        assert_eq!(
+            BackendMessage::CopyOutResponse(1).as_vec(),
+            vec![COPY_OUT_RESPONSE, 0, 0, 0, 9, 0, 0, 1, 0, 0]
+        )
+    }
+
+    #[test]
+    fn copy_data() {
+        assert_eq!(
+            BackendMessage::CopyData(b"1\ta\n".to_vec()).as_vec(),
+            vec![COPY_DATA, 0, 0, 0, 8, b'1', b'\t', b'a', b'\n']
+        )
+    }
+
+    #[test]
+    fn copy_done() {
+        assert_eq!(BackendMessage::CopyDone.as_vec(), vec![COPY_DONE, 0, 0, 0, 4])
+    }
+
+    #[test]
+    fn notification_response() {
+        assert_eq!(
+            BackendMessage::NotificationResponse(1, "ch".to_owned(), "p".to_owned()).as_vec(),
+            vec![NOTIFICATION_RESPONSE, 0, 0, 0, 13, 0, 0, 0, 1, b'c', b'h', 0, b'p', 0]
+        )
+    }
 }
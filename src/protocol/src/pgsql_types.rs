@@ -20,6 +20,15 @@ use std::{
 
 use byteorder::{BigEndian, ReadBytesExt};
 
+use crate::{
+    array, bytea,
+    clock::{render_date, render_timestamp_with_time_zone, MICROS_PER_DAY, MICROS_PER_SECOND},
+    interval::Interval,
+};
+
+/// days from Unix epoch to 2000-01-01 which binary dates and timestamps are counted from
+const POSTGRES_EPOCH_DAYS: i64 = 10_957;
+
 /// PostgreSQL Object Identifier
 pub type Oid = u32;
 
@@ -42,6 +51,14 @@ pub enum PostgreSqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    Text,
+    Bytea,
+    Json,
+    Jsonb,
+    SmallIntArray,
+    IntegerArray,
+    BigIntArray,
+    TextArray,
 }
 
 impl TryFrom<Oid> for PostgreSqlType {
@@ -51,13 +68,20 @@ impl TryFrom<Oid> for PostgreSqlType {
     fn try_from(oid: Oid) -> Result<Self, Self::Error> {
         match oid {
             16 => Ok(PostgreSqlType::Bool),
+            17 => Ok(PostgreSqlType::Bytea),
             18 => Ok(PostgreSqlType::Char),
             20 => Ok(PostgreSqlType::BigInt),
             21 => Ok(PostgreSqlType::SmallInt),
             23 => Ok(PostgreSqlType::Integer),
+            25 => Ok(PostgreSqlType::Text),
+            114 => Ok(PostgreSqlType::Json),
             700 => Ok(PostgreSqlType::Real),
             701 => Ok(PostgreSqlType::DoublePrecision),
             1043 => Ok(PostgreSqlType::VarChar),
+            1005 => Ok(PostgreSqlType::SmallIntArray),
+            1007 => Ok(PostgreSqlType::IntegerArray),
+            1009 => Ok(PostgreSqlType::TextArray),
+            1016 => Ok(PostgreSqlType::BigIntArray),
             1082 => Ok(PostgreSqlType::Date),
             1083 => Ok(PostgreSqlType::Time),
             1114 => Ok(PostgreSqlType::Timestamp),
@@ -65,6 +89,7 @@ impl TryFrom<Oid> for PostgreSqlType {
             1186 => Ok(PostgreSqlType::Interval),
             1266 => Ok(PostgreSqlType::TimeWithTimeZone),
             1700 => Ok(PostgreSqlType::Decimal),
+            3802 => Ok(PostgreSqlType::Jsonb),
             _ => Err(()),
         }
     }
@@ -75,12 +100,19 @@ impl PostgreSqlType {
     pub fn pg_oid(&self) -> Oid {
         match self {
             Self::Bool => 16,
+            Self::Bytea => 17,
             Self::Char => 18,
-            Self::BigInt => 20,           // PG int8
-            Self::SmallInt => 21,         // PG int2
-            Self::Integer => 23,          // PG int4
+            Self::BigInt => 20,   // PG int8
+            Self::SmallInt => 21, // PG int2
+            Self::Integer => 23,  // PG int4
+            Self::Text => 25,
+            Self::Json => 114,
             Self::Real => 700,            // PG float4
             Self::DoublePrecision => 701, // PG float8
+            Self::SmallIntArray => 1005,  // PG _int2
+            Self::IntegerArray => 1007,   // PG _int4
+            Self::TextArray => 1009,
+            Self::BigIntArray => 1016, // PG _int8
             Self::VarChar => 1043,
             Self::Date => 1082,
             Self::Time => 1083,
@@ -89,6 +121,7 @@ impl PostgreSqlType {
             Self::Interval => 1186,
             Self::TimeWithTimeZone => 1266, // PG Timetz
             Self::Decimal => 1700,          // PG Numeric & Decimal
+            Self::Jsonb => 3802,
         }
     }
 
@@ -103,6 +136,11 @@ impl PostgreSqlType {
             Self::Real => 4,
             Self::DoublePrecision => 8,
             Self::VarChar => -1,
+            Self::Text => -1,
+            Self::Bytea => -1,
+            Self::Json => -1,
+            Self::Jsonb => -1,
+            Self::SmallIntArray | Self::IntegerArray | Self::BigIntArray | Self::TextArray => -1,
             Self::Date => 4,
             Self::Time => 8,
             Self::Timestamp => 8,
@@ -113,6 +151,35 @@ impl PostgreSqlType {
         }
     }
 
+    /// PostgreSQL type name as it is stored in `pg_type.typname`
+    pub fn pg_type_name(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Char => "bpchar",
+            Self::BigInt => "int8",
+            Self::SmallInt => "int2",
+            Self::Integer => "int4",
+            Self::Real => "float4",
+            Self::DoublePrecision => "float8",
+            Self::VarChar => "varchar",
+            Self::Text => "text",
+            Self::Bytea => "bytea",
+            Self::Json => "json",
+            Self::Jsonb => "jsonb",
+            Self::SmallIntArray => "_int2",
+            Self::IntegerArray => "_int4",
+            Self::BigIntArray => "_int8",
+            Self::TextArray => "_text",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::Timestamp => "timestamp",
+            Self::TimestampWithTimeZone => "timestamptz",
+            Self::Interval => "interval",
+            Self::TimeWithTimeZone => "timetz",
+            Self::Decimal => "numeric",
+        }
+    }
+
     /// Deserializes a value of this type from `raw` using the specified `format`.
     pub fn decode(&self, format: &PostgreSqlFormat, raw: &[u8]) -> Result<PostgreSqlValue, String> {
         match format {
@@ -121,15 +188,58 @@ impl PostgreSqlType {
         }
     }
 
+    /// Serializes a value rendered as text into the specified `format`, values
+    /// of types without binary representation are sent as text.
+    pub fn encode(&self, format: &PostgreSqlFormat, value: &str) -> Vec<u8> {
+        match format {
+            PostgreSqlFormat::Binary => self.encode_binary(value).unwrap_or_else(|| value.as_bytes().to_vec()),
+            PostgreSqlFormat::Text => value.as_bytes().to_vec(),
+        }
+    }
+
+    fn encode_binary(&self, value: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Bool => Some(vec![(value == "t") as u8]),
+            Self::Char | Self::VarChar | Self::Text | Self::Json => Some(value.as_bytes().to_vec()),
+            Self::Jsonb => Some([&[1], value.as_bytes()].concat()),
+            Self::Bytea => bytea::decode(value).ok(),
+            Self::SmallInt => value.parse::<i16>().ok().map(|v| v.to_be_bytes().to_vec()),
+            Self::Integer => value.parse::<i32>().ok().map(|v| v.to_be_bytes().to_vec()),
+            Self::BigInt => value.parse::<i64>().ok().map(|v| v.to_be_bytes().to_vec()),
+            Self::Real => value.parse::<f32>().ok().map(|v| v.to_be_bytes().to_vec()),
+            Self::DoublePrecision => value.parse::<f64>().ok().map(|v| v.to_be_bytes().to_vec()),
+            _ => None,
+        }
+    }
+
     fn decode_binary(&self, raw: &[u8]) -> Result<PostgreSqlValue, String> {
         match self {
             Self::Bool => parse_bool_from_binary(raw),
             Self::Char => parse_char_from_binary(raw),
-            Self::VarChar => parse_varchar_from_binary(raw),
+            Self::VarChar | Self::Text | Self::Json => parse_varchar_from_binary(raw),
+            // binary JSONB is its text prefixed with a version number
+            Self::Jsonb => match raw.split_first() {
+                Some((1, text)) => parse_varchar_from_binary(text),
+                _ => Err("unsupported jsonb version number".to_owned()),
+            },
+            Self::Bytea => Ok(PostgreSqlValue::String(bytea::encode_hex(raw))),
             Self::SmallInt => parse_smallint_from_binary(raw),
             Self::Integer => parse_integer_from_binary(raw),
             Self::BigInt => parse_bigint_from_binary(raw),
-            other => Err(format!("Unsupported Postgres type: {:?}", other)),
+            // values of the rest types are read as literals of the type when they are bound
+            Self::Real => parse_real_from_binary(raw),
+            Self::DoublePrecision => parse_double_precision_from_binary(raw),
+            Self::Decimal => parse_numeric_from_binary(raw),
+            Self::Date => parse_date_from_binary(raw),
+            Self::Time => parse_time_from_binary(raw),
+            Self::TimeWithTimeZone => parse_time_with_time_zone_from_binary(raw),
+            Self::Timestamp => parse_timestamp_from_binary(raw, false),
+            Self::TimestampWithTimeZone => parse_timestamp_from_binary(raw, true),
+            Self::Interval => parse_interval_from_binary(raw),
+            Self::SmallIntArray => parse_array_from_binary(raw, Self::SmallInt),
+            Self::IntegerArray => parse_array_from_binary(raw, Self::Integer),
+            Self::BigIntArray => parse_array_from_binary(raw, Self::BigInt),
+            Self::TextArray => parse_array_from_binary(raw, Self::Text),
         }
     }
 
@@ -142,11 +252,28 @@ impl PostgreSqlType {
         match self {
             Self::Bool => parse_bool_from_text(s),
             Self::Char => parse_char_from_text(s),
-            Self::VarChar => parse_varchar_from_text(s),
+            Self::VarChar
+            | Self::Text
+            | Self::Bytea
+            | Self::Json
+            | Self::Jsonb
+            | Self::SmallIntArray
+            | Self::IntegerArray
+            | Self::BigIntArray
+            | Self::TextArray => parse_varchar_from_text(s),
             Self::SmallInt => parse_smallint_from_text(s),
             Self::Integer => parse_integer_from_text(s),
             Self::BigInt => parse_bigint_from_text(s),
-            other => Err(format!("Unsupported Postgres type: {:?}", other)),
+            // values are read as literals of the type when they are bound
+            Self::Real
+            | Self::DoublePrecision
+            | Self::Decimal
+            | Self::Date
+            | Self::Time
+            | Self::TimeWithTimeZone
+            | Self::Timestamp
+            | Self::TimestampWithTimeZone
+            | Self::Interval => parse_varchar_from_text(s),
         }
     }
 }
@@ -162,6 +289,14 @@ impl Display for PostgreSqlType {
             Self::Real => write!(f, "real"),
             Self::DoublePrecision => write!(f, "double"),
             Self::VarChar => write!(f, "variable character"),
+            Self::Text => write!(f, "text"),
+            Self::Bytea => write!(f, "bytea"),
+            Self::Json => write!(f, "json"),
+            Self::Jsonb => write!(f, "jsonb"),
+            Self::SmallIntArray => write!(f, "smallint[]"),
+            Self::IntegerArray => write!(f, "integer[]"),
+            Self::BigIntArray => write!(f, "bigint[]"),
+            Self::TextArray => write!(f, "text[]"),
             Self::Date => write!(f, "date"),
             Self::Time => write!(f, "time"),
             Self::TimeWithTimeZone => write!(f, "time with timezone"),
@@ -195,6 +330,257 @@ fn parse_bigint_from_text(s: &str) -> Result<PostgreSqlValue, String> {
     Ok(PostgreSqlValue::Int64(v))
 }
 
+fn parse_real_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let v = match buf.read_f32::<BigEndian>() {
+        Ok(v) => v,
+        Err(_) => return Err(format!("Failed to parse Real from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    Ok(PostgreSqlValue::String(render_float(v as f64, v.to_string())))
+}
+
+fn parse_double_precision_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let v = match buf.read_f64::<BigEndian>() {
+        Ok(v) => v,
+        Err(_) => return Err(format!("Failed to parse DoublePrecision from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    Ok(PostgreSqlValue::String(render_float(v, v.to_string())))
+}
+
+/// infinities are named the way PostgreSQL names them
+fn render_float(v: f64, rendered: String) -> String {
+    match v {
+        v if v == f64::INFINITY => "Infinity".to_owned(),
+        v if v == f64::NEG_INFINITY => "-Infinity".to_owned(),
+        _ => rendered,
+    }
+}
+
+/// binary numeric is a sequence of base 10000 digits with the weight of the first one, its sign and display scale
+fn parse_numeric_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let header = (
+        buf.read_u16::<BigEndian>(),
+        buf.read_i16::<BigEndian>(),
+        buf.read_u16::<BigEndian>(),
+        buf.read_u16::<BigEndian>(),
+    );
+    let (ndigits, weight, sign, dscale) = match header {
+        (Ok(ndigits), Ok(weight), Ok(sign), Ok(dscale)) => (ndigits, weight as i64, sign, dscale as usize),
+        _ => return Err(format!("Failed to parse Numeric from: {:?}", buf)),
+    };
+    let mut digits = vec![];
+    for _ in 0..ndigits {
+        match buf.read_i16::<BigEndian>() {
+            Ok(digit) if (0..10_000).contains(&digit) => digits.push(digit),
+            _ => return Err(format!("Failed to parse Numeric from: {:?}", buf)),
+        }
+    }
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    let digit = |index: i64| {
+        if index < 0 {
+            0
+        } else {
+            digits.get(index as usize).copied().unwrap_or(0)
+        }
+    };
+    let mut rendered = match sign {
+        0x0000 => String::new(),
+        0x4000 => "-".to_owned(),
+        0xC000 => return Ok(PostgreSqlValue::String("NaN".to_owned())),
+        _ => return Err(format!("invalid sign in Numeric value: {:#x}", sign)),
+    };
+    if weight < 0 {
+        rendered.push('0');
+    } else {
+        rendered.push_str(&digit(0).to_string());
+        for index in 1..=weight {
+            rendered.push_str(&format!("{:04}", digit(index)));
+        }
+    }
+    if dscale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < dscale {
+            fraction.push_str(&format!("{:04}", digit(index)));
+            index += 1;
+        }
+        fraction.truncate(dscale);
+        rendered.push('.');
+        rendered.push_str(&fraction);
+    }
+    Ok(PostgreSqlValue::String(rendered))
+}
+
+/// binary date is the number of days since 2000-01-01
+fn parse_date_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let v = match buf.read_i32::<BigEndian>() {
+        Ok(v) => v,
+        Err(_) => return Err(format!("Failed to parse Date from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    let rendered = match v {
+        i32::MAX => "infinity".to_owned(),
+        i32::MIN => "-infinity".to_owned(),
+        days => render_date((days as i64 + POSTGRES_EPOCH_DAYS) * MICROS_PER_DAY, 0),
+    };
+    Ok(PostgreSqlValue::String(rendered))
+}
+
+/// binary time is the number of microseconds since midnight
+fn parse_time_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let v = match buf.read_i64::<BigEndian>() {
+        Ok(v) => v,
+        Err(_) => return Err(format!("Failed to parse Time from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    Ok(PostgreSqlValue::String(render_time(v)?))
+}
+
+/// binary time with time zone is followed by the zone offset in seconds west of UTC
+fn parse_time_with_time_zone_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let (micros, zone) = match (buf.read_i64::<BigEndian>(), buf.read_i32::<BigEndian>()) {
+        (Ok(micros), Ok(zone)) => (micros, zone),
+        _ => return Err(format!("Failed to parse TimeWithTimeZone from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    let sign = if zone > 0 { '-' } else { '+' };
+    let offset_minutes = zone.abs() / 60;
+    let mut rendered = format!("{}{}{:02}", render_time(micros)?, sign, offset_minutes / 60);
+    if offset_minutes % 60 != 0 {
+        rendered.push_str(&format!(":{:02}", offset_minutes % 60));
+    }
+    Ok(PostgreSqlValue::String(rendered))
+}
+
+fn render_time(micros: i64) -> Result<String, String> {
+    if !(0..=MICROS_PER_DAY).contains(&micros) {
+        return Err(format!("time out of range: {}", micros));
+    }
+    let seconds = micros / MICROS_PER_SECOND;
+    let mut rendered = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if micros % MICROS_PER_SECOND != 0 {
+        rendered.push_str(format!(".{:06}", micros % MICROS_PER_SECOND).trim_end_matches('0'));
+    }
+    Ok(rendered)
+}
+
+/// binary timestamp is the number of microseconds since 2000-01-01 00:00:00 UTC
+fn parse_timestamp_from_binary(mut buf: &[u8], with_time_zone: bool) -> Result<PostgreSqlValue, String> {
+    let v = match buf.read_i64::<BigEndian>() {
+        Ok(v) => v,
+        Err(_) => return Err(format!("Failed to parse Timestamp from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    let rendered = match v {
+        i64::MAX => "infinity".to_owned(),
+        i64::MIN => "-infinity".to_owned(),
+        micros => match micros.checked_add(POSTGRES_EPOCH_DAYS * MICROS_PER_DAY) {
+            Some(micros) if with_time_zone => render_timestamp_with_time_zone(micros, 0),
+            Some(micros) => render_timestamp_with_time_zone(micros, 0)
+                .trim_end_matches("+00")
+                .to_owned(),
+            None => return Err(format!("timestamp out of range: {}", micros)),
+        },
+    };
+    Ok(PostgreSqlValue::String(rendered))
+}
+
+/// binary interval is the number of microseconds followed by the number of days and months
+fn parse_interval_from_binary(mut buf: &[u8]) -> Result<PostgreSqlValue, String> {
+    let interval = match (
+        buf.read_i64::<BigEndian>(),
+        buf.read_i32::<BigEndian>(),
+        buf.read_i32::<BigEndian>(),
+    ) {
+        (Ok(micros), Ok(days), Ok(months)) => Interval::new(months, days, micros),
+        _ => return Err(format!("Failed to parse Interval from: {:?}", buf)),
+    };
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    Ok(PostgreSqlValue::String(interval.to_string()))
+}
+
+/// only one dimensional arrays are supported, their elements are rendered into the text form of the array
+fn parse_array_from_binary(mut buf: &[u8], element_type: PostgreSqlType) -> Result<PostgreSqlValue, String> {
+    let malformed = || format!("Failed to parse {} array", element_type);
+    let (dimensions, _has_nulls, _element_oid) = match (
+        buf.read_i32::<BigEndian>(),
+        buf.read_i32::<BigEndian>(),
+        buf.read_u32::<BigEndian>(),
+    ) {
+        (Ok(dimensions), Ok(has_nulls), Ok(element_oid)) => (dimensions, has_nulls, element_oid),
+        _ => return Err(malformed()),
+    };
+    let len = match dimensions {
+        0 => 0,
+        1 => match (buf.read_i32::<BigEndian>(), buf.read_i32::<BigEndian>()) {
+            (Ok(len), Ok(_lower_bound)) if len >= 0 => len,
+            _ => return Err(malformed()),
+        },
+        _ => return Err("multidimensional arrays are not supported".to_owned()),
+    };
+    let mut elements = vec![];
+    for _ in 0..len {
+        let element_len = buf.read_i32::<BigEndian>().map_err(|_| malformed())?;
+        if element_len < 0 {
+            elements.push(None);
+            continue;
+        }
+        if buf.len() < element_len as usize {
+            return Err(malformed());
+        }
+        let (element, rest) = buf.split_at(element_len as usize);
+        buf = rest;
+        elements.push(match element_type.decode_binary(element)? {
+            PostgreSqlValue::Null => None,
+            PostgreSqlValue::True => Some("t".to_owned()),
+            PostgreSqlValue::False => Some("f".to_owned()),
+            PostgreSqlValue::Int16(v) => Some(v.to_string()),
+            PostgreSqlValue::Int32(v) => Some(v.to_string()),
+            PostgreSqlValue::Int64(v) => Some(v.to_string()),
+            PostgreSqlValue::String(v) => Some(v),
+        });
+    }
+
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+
+    Ok(PostgreSqlValue::String(array::render(&elements)))
+}
+
 fn parse_bool_from_binary(buf: &[u8]) -> Result<PostgreSqlValue, String> {
     let len = buf.len();
     if len != 1 {
@@ -373,6 +759,134 @@ mod tests {
                 Ok(PostgreSqlValue::Int64(1))
             );
         }
+
+        #[test]
+        fn decode_bytea() {
+            assert_eq!(
+                PostgreSqlType::Bytea.decode(&PostgreSqlFormat::Binary, &[1, 171]),
+                Ok(PostgreSqlValue::String("\\x01ab".into()))
+            );
+        }
+
+        #[test]
+        fn decode_jsonb() {
+            assert_eq!(
+                PostgreSqlType::Jsonb.decode(&PostgreSqlFormat::Binary, b"\x01[1]"),
+                Ok(PostgreSqlValue::String("[1]".into()))
+            );
+        }
+
+        #[test]
+        fn decode_floats() {
+            assert_eq!(
+                PostgreSqlType::Real.decode(&PostgreSqlFormat::Binary, &1.5f32.to_be_bytes()),
+                Ok(PostgreSqlValue::String("1.5".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::DoublePrecision.decode(&PostgreSqlFormat::Binary, &(-0.25f64).to_be_bytes()),
+                Ok(PostgreSqlValue::String("-0.25".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::DoublePrecision.decode(&PostgreSqlFormat::Binary, &f64::INFINITY.to_be_bytes()),
+                Ok(PostgreSqlValue::String("Infinity".into()))
+            );
+        }
+
+        #[test]
+        fn decode_numeric() {
+            assert_eq!(
+                PostgreSqlType::Decimal.decode(
+                    &PostgreSqlFormat::Binary,
+                    &[0, 3, 0, 1, 0, 0, 0, 3, 0, 1, 9, 41, 26, 124]
+                ),
+                Ok(PostgreSqlValue::String("12345.678".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::Decimal.decode(&PostgreSqlFormat::Binary, &[0, 1, 255, 255, 64, 0, 0, 2, 1, 244]),
+                Ok(PostgreSqlValue::String("-0.05".into()))
+            );
+        }
+
+        #[test]
+        fn decode_date() {
+            assert_eq!(
+                PostgreSqlType::Date.decode(&PostgreSqlFormat::Binary, &7i32.to_be_bytes()),
+                Ok(PostgreSqlValue::String("2000-01-08".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::Date.decode(&PostgreSqlFormat::Binary, &(-1i32).to_be_bytes()),
+                Ok(PostgreSqlValue::String("1999-12-31".into()))
+            );
+        }
+
+        #[test]
+        fn decode_time() {
+            assert_eq!(
+                PostgreSqlType::Time.decode(&PostgreSqlFormat::Binary, &3_723_500_000i64.to_be_bytes()),
+                Ok(PostgreSqlValue::String("01:02:03.5".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::TimeWithTimeZone.decode(
+                    &PostgreSqlFormat::Binary,
+                    &[&0i64.to_be_bytes()[..], &(-10_800i32).to_be_bytes()[..]].concat()
+                ),
+                Ok(PostgreSqlValue::String("00:00:00+03".into()))
+            );
+        }
+
+        #[test]
+        fn decode_timestamps() {
+            let micros = 90_000_000_000i64.to_be_bytes();
+            assert_eq!(
+                PostgreSqlType::Timestamp.decode(&PostgreSqlFormat::Binary, &micros),
+                Ok(PostgreSqlValue::String("2000-01-02 01:00:00".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::TimestampWithTimeZone.decode(&PostgreSqlFormat::Binary, &micros),
+                Ok(PostgreSqlValue::String("2000-01-02 01:00:00+00".into()))
+            );
+        }
+
+        #[test]
+        fn decode_interval() {
+            assert_eq!(
+                PostgreSqlType::Interval.decode(
+                    &PostgreSqlFormat::Binary,
+                    &[
+                        &3_600_000_000i64.to_be_bytes()[..],
+                        &2i32.to_be_bytes()[..],
+                        &14i32.to_be_bytes()[..]
+                    ]
+                    .concat()
+                ),
+                Ok(PostgreSqlValue::String("1 year 2 mons 2 days 01:00:00".into()))
+            );
+        }
+
+        #[test]
+        fn decode_arrays() {
+            assert_eq!(
+                PostgreSqlType::IntegerArray.decode(
+                    &PostgreSqlFormat::Binary,
+                    &[
+                        0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 23, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 1, 255, 255,
+                        255, 255
+                    ]
+                ),
+                Ok(PostgreSqlValue::String("{1,NULL}".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::TextArray.decode(
+                    &PostgreSqlFormat::Binary,
+                    &[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 25, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 3, b'a', b' ', b'b']
+                ),
+                Ok(PostgreSqlValue::String("{\"a b\"}".into()))
+            );
+            assert_eq!(
+                PostgreSqlType::BigIntArray.decode(&PostgreSqlFormat::Binary, &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20]),
+                Ok(PostgreSqlValue::String("{}".into()))
+            );
+        }
     }
 
     #[cfg(test)]
@@ -411,6 +925,14 @@ mod tests {
             );
         }
 
+        #[test]
+        fn decode_text() {
+            assert_eq!(
+                PostgreSqlType::Text.decode(&PostgreSqlFormat::Text, b"abc  "),
+                Ok(PostgreSqlValue::String("abc  ".into()))
+            );
+        }
+
         #[test]
         fn decode_smallint() {
             assert_eq!(
@@ -434,5 +956,59 @@ mod tests {
                 Ok(PostgreSqlValue::Int64(123456))
             );
         }
+
+        #[test]
+        fn decode_date() {
+            assert_eq!(
+                PostgreSqlType::Date.decode(&PostgreSqlFormat::Text, b"2020-01-02"),
+                Ok(PostgreSqlValue::String("2020-01-02".into()))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod binary_encoding {
+        use super::*;
+
+        #[test]
+        fn encode_integers() {
+            assert_eq!(
+                PostgreSqlType::SmallInt.encode(&PostgreSqlFormat::Binary, "1"),
+                vec![0, 1]
+            );
+            assert_eq!(
+                PostgreSqlType::Integer.encode(&PostgreSqlFormat::Binary, "-2"),
+                vec![255, 255, 255, 254]
+            );
+            assert_eq!(
+                PostgreSqlType::BigInt.encode(&PostgreSqlFormat::Binary, "3"),
+                vec![0, 0, 0, 0, 0, 0, 0, 3]
+            );
+        }
+
+        #[test]
+        fn encode_bool_and_strings() {
+            assert_eq!(PostgreSqlType::Bool.encode(&PostgreSqlFormat::Binary, "t"), vec![1]);
+            assert_eq!(
+                PostgreSqlType::VarChar.encode(&PostgreSqlFormat::Binary, "abc"),
+                b"abc".to_vec()
+            );
+            assert_eq!(
+                PostgreSqlType::Bytea.encode(&PostgreSqlFormat::Binary, "\\x0aff"),
+                vec![10, 255]
+            );
+        }
+
+        #[test]
+        fn types_without_binary_representation_are_encoded_as_text() {
+            assert_eq!(
+                PostgreSqlType::Interval.encode(&PostgreSqlFormat::Binary, "1 day"),
+                b"1 day".to_vec()
+            );
+            assert_eq!(
+                PostgreSqlType::Integer.encode(&PostgreSqlFormat::Text, "12"),
+                b"12".to_vec()
+            );
+        }
     }
 }
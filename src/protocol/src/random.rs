@@ -0,0 +1,100 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Pseudo-random number generator of a session for `random()` and `setseed()`.
+/// SplitMix64 is implemented here instead of using an external crate so the
+/// same seed produces the same sequence on every platform and build.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Creates generator with explicit seed
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    /// Creates generator seeded from the current system time
+    pub fn from_system_time() -> Random {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_nanos() as u64)
+            .unwrap_or_default();
+        Random::new(seed)
+    }
+
+    /// Restarts sequence from `seed` that `setseed` accepts, from -1.0 to 1.0
+    pub fn set_seed(&mut self, seed: f64) {
+        self.state = (seed * i32::max_value() as f64) as i64 as u64;
+    }
+
+    /// Returns next value from 0.0 inclusive to 1.0 exclusive
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns next value that takes all 32 bits
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+
+        for _ in 0..10 {
+            assert_eq!(first.next_f64(), second.next_f64());
+        }
+    }
+
+    #[test]
+    fn set_seed_restarts_sequence() {
+        let mut random = Random::from_system_time();
+        random.set_seed(0.5);
+        let first = (random.next_f64(), random.next_f64());
+        random.set_seed(0.5);
+        let second = (random.next_f64(), random.next_f64());
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn values_are_in_unit_interval() {
+        let mut random = Random::new(0);
+
+        for _ in 0..1000 {
+            let value = random.next_f64();
+            assert!(value >= 0.0 && value < 1.0);
+        }
+    }
+}
@@ -15,14 +15,18 @@
 use std::fmt::{self, Display, Formatter};
 
 use crate::{
-    messages::{BackendMessage, ColumnMetadata},
-    pgsql_types::PostgreSqlType,
+    messages::{BackendMessage, ColumnMetadata, ErrorDetails, TransactionStatus},
+    pgsql_types::{PostgreSqlFormat, PostgreSqlType},
+    ProcessId,
 };
 
 /// Represents result of SQL query execution
 pub type QueryResult = std::result::Result<QueryEvent, QueryError>;
 /// Represents selected columns from tables
 pub type Description = Vec<(String, PostgreSqlType)>;
+/// Represents table OID and attribute number of selected columns, `None` for
+/// computed columns
+pub type ColumnOrigins = Vec<Option<(u32, i16)>>;
 /// Represents selected data from tables
 pub type Projection = (Description, Vec<Vec<String>>);
 
@@ -33,30 +37,117 @@ pub enum QueryEvent {
     SchemaCreated,
     /// Schema successfully dropped
     SchemaDropped,
+    /// Schema successfully altered
+    SchemaAltered,
+    /// Privileges successfully granted
+    PrivilegesGranted,
+    /// Privileges successfully revoked
+    PrivilegesRevoked,
     /// Table successfully created
     TableCreated,
     /// Table successfully dropped
     TableDropped,
+    /// Sequence successfully created
+    SequenceCreated,
+    /// Sequence successfully dropped
+    SequenceDropped,
+    /// Statistics successfully created
+    StatisticsCreated,
+    /// Statistics successfully dropped
+    StatisticsDropped,
+    /// Type successfully created
+    TypeCreated,
+    /// Function successfully created
+    FunctionCreated,
+    /// Function successfully dropped
+    FunctionDropped,
+    /// Statistics of tables successfully rebuilt
+    TablesAnalyzed,
+    /// Storage of tables successfully compacted
+    TablesVacuumed,
     /// Variable successfully set
     VariableSet,
+    /// Variable successfully reset to its default value
+    VariableReset,
     /// Transaction is started
     TransactionStarted,
+    /// Transaction is committed
+    TransactionCommitted,
+    /// Transaction is rolled back
+    TransactionRolledBack,
+    /// Savepoint is established in the transaction
+    SavepointCreated,
+    /// Savepoint is released
+    SavepointReleased,
+    /// Backup of the database is written
+    BackupCompleted,
     /// Number of records inserted into a table
     RecordsInserted(usize),
-    /// Records selected from database
-    RecordsSelected(Projection),
+    /// Columns of rows that are about to be selected
+    RowDescription(Description),
+    /// Values of a single selected row, rows are sent as they are produced
+    DataRow(Vec<String>),
+    /// Number of records selected from database, all of them are already sent
+    RecordsSelected(usize),
     /// Number of records updated into a table
     RecordsUpdated(usize),
     /// Number of records deleted into a table
     RecordsDeleted(usize),
+    /// Records fetched through a portal, encoded in the portal result formats
+    RecordsFetched(Projection, Vec<PostgreSqlFormat>),
+    /// Records fetched through a portal that has more records to fetch
+    RecordsSuspended(Projection, Vec<PostgreSqlFormat>),
     /// Parameters described needed by a prepared statement
-    PreparedStatementDescribed(Vec<PostgreSqlType>, Description),
+    PreparedStatementDescribed(Vec<PostgreSqlType>, Description, ColumnOrigins),
+    /// Columns of rows returned by a portal
+    PortalDescribed(Description, ColumnOrigins),
     /// Processing of the query is complete
     QueryComplete,
+    /// Processing of the query is complete, the session is in a transaction block
+    QueryCompleteInTransaction,
+    /// Processing of the query is complete, the session is in a failed transaction block
+    QueryCompleteInFailedTransaction,
+    /// Query has no statements to process
+    QueryEmpty,
     /// Parsing the exteneded query is complete
     ParseComplete,
     /// Binding the exteneded query is complete
     BindComplete,
+    /// Closing a prepared statement or a portal is complete
+    CloseComplete,
+    /// Cursor successfully declared
+    CursorDeclared,
+    /// Records fetched from a cursor
+    RecordsFetchedFromCursor(Projection),
+    /// Cursor successfully closed
+    CursorClosed,
+    /// All cursors of the session successfully closed
+    AllCursorsClosed,
+    /// Server is ready to receive data of `COPY FROM STDIN` for the number of columns
+    CopyInStarted(usize),
+    /// Number of records copied into a table
+    RecordsCopied(usize),
+    /// Server starts to send data of `COPY TO STDOUT` for the number of columns
+    CopyOutStarted(usize),
+    /// Rows of `COPY TO STDOUT` encoded in the requested format
+    CopyData(Vec<u8>),
+    /// Number of records copied from a table
+    CopyOutDone(usize),
+    /// Informational message that does not affect processing of the query
+    Notice(String),
+    /// Session started listening to a channel
+    Listening,
+    /// Session stopped listening to one or all channels
+    Unlistening,
+    /// Notification is queued to be delivered to listening sessions
+    Notified,
+    /// Notification from a session with the process id on a channel with a payload
+    Notification(ProcessId, String, String),
+    /// `IF EXISTS` or `IF NOT EXISTS` statement skipped an object for the reason, the client is notified
+    /// about it and the statement completes
+    Skipped(QueryError),
+    /// Statement completes but the client is warned about the reason
+    Warning(QueryError),
 }
 
 impl Into<Vec<BackendMessage>> for QueryEvent {
@@ -64,51 +155,165 @@ impl Into<Vec<BackendMessage>> for QueryEvent {
         match self {
             QueryEvent::SchemaCreated => vec![BackendMessage::CommandComplete("CREATE SCHEMA".to_owned())],
             QueryEvent::SchemaDropped => vec![BackendMessage::CommandComplete("DROP SCHEMA".to_owned())],
+            QueryEvent::SchemaAltered => vec![BackendMessage::CommandComplete("ALTER SCHEMA".to_owned())],
+            QueryEvent::PrivilegesGranted => vec![BackendMessage::CommandComplete("GRANT".to_owned())],
+            QueryEvent::PrivilegesRevoked => vec![BackendMessage::CommandComplete("REVOKE".to_owned())],
             QueryEvent::TableCreated => vec![BackendMessage::CommandComplete("CREATE TABLE".to_owned())],
             QueryEvent::TableDropped => vec![BackendMessage::CommandComplete("DROP TABLE".to_owned())],
+            QueryEvent::SequenceCreated => vec![BackendMessage::CommandComplete("CREATE SEQUENCE".to_owned())],
+            QueryEvent::SequenceDropped => vec![BackendMessage::CommandComplete("DROP SEQUENCE".to_owned())],
+            QueryEvent::StatisticsCreated => vec![BackendMessage::CommandComplete("CREATE STATISTICS".to_owned())],
+            QueryEvent::StatisticsDropped => vec![BackendMessage::CommandComplete("DROP STATISTICS".to_owned())],
+            QueryEvent::TypeCreated => vec![BackendMessage::CommandComplete("CREATE TYPE".to_owned())],
+            QueryEvent::FunctionCreated => vec![BackendMessage::CommandComplete("CREATE FUNCTION".to_owned())],
+            QueryEvent::FunctionDropped => vec![BackendMessage::CommandComplete("DROP FUNCTION".to_owned())],
+            QueryEvent::TablesAnalyzed => vec![BackendMessage::CommandComplete("ANALYZE".to_owned())],
+            QueryEvent::TablesVacuumed => vec![BackendMessage::CommandComplete("VACUUM".to_owned())],
             QueryEvent::VariableSet => vec![BackendMessage::CommandComplete("SET".to_owned())],
+            QueryEvent::VariableReset => vec![BackendMessage::CommandComplete("RESET".to_owned())],
             QueryEvent::TransactionStarted => vec![BackendMessage::CommandComplete("BEGIN".to_owned())],
+            QueryEvent::TransactionCommitted => vec![BackendMessage::CommandComplete("COMMIT".to_owned())],
+            QueryEvent::TransactionRolledBack => vec![BackendMessage::CommandComplete("ROLLBACK".to_owned())],
+            QueryEvent::SavepointCreated => vec![BackendMessage::CommandComplete("SAVEPOINT".to_owned())],
+            QueryEvent::SavepointReleased => vec![BackendMessage::CommandComplete("RELEASE".to_owned())],
+            QueryEvent::BackupCompleted => vec![BackendMessage::CommandComplete("BACKUP".to_owned())],
             QueryEvent::RecordsInserted(records) => {
                 vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records))]
             }
-            QueryEvent::RecordsSelected(projection) => {
-                let definition = projection.0;
-                let description: Vec<ColumnMetadata> = definition
+            QueryEvent::RowDescription(description) => vec![BackendMessage::RowDescription(
+                description
                     .into_iter()
                     .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
-                    .collect();
-                let records = projection.1;
-                let len = records.len();
-                let mut messages = vec![BackendMessage::RowDescription(description)];
-                for record in records {
-                    messages.push(BackendMessage::DataRow(record));
-                }
-                messages.push(BackendMessage::CommandComplete(format!("SELECT {}", len)));
-                messages
+                    .collect(),
+            )],
+            QueryEvent::DataRow(record) => vec![BackendMessage::DataRow(
+                record.into_iter().map(String::into_bytes).collect(),
+            )],
+            QueryEvent::RecordsSelected(records) => {
+                vec![BackendMessage::CommandComplete(format!("SELECT {}", records))]
             }
             QueryEvent::RecordsUpdated(records) => vec![BackendMessage::CommandComplete(format!("UPDATE {}", records))],
             QueryEvent::RecordsDeleted(records) => vec![BackendMessage::CommandComplete(format!("DELETE {}", records))],
-            QueryEvent::PreparedStatementDescribed(param_types, description) => {
-                let desc_message = if description.is_empty() {
-                    BackendMessage::NoData
-                } else {
-                    let columns: Vec<ColumnMetadata> = description
-                        .into_iter()
-                        .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
-                        .collect();
-                    BackendMessage::RowDescription(columns)
-                };
-
+            QueryEvent::RecordsFetched(projection, formats) => {
+                let len = projection.1.len();
+                let mut messages = data_rows(projection, &formats);
+                messages.push(BackendMessage::CommandComplete(format!("SELECT {}", len)));
+                messages
+            }
+            QueryEvent::RecordsSuspended(projection, formats) => {
+                let mut messages = data_rows(projection, &formats);
+                messages.push(BackendMessage::PortalSuspended);
+                messages
+            }
+            QueryEvent::PreparedStatementDescribed(param_types, description, origins) => {
                 let type_ids = param_types.iter().map(PostgreSqlType::pg_oid).collect();
-                vec![BackendMessage::ParameterDescription(type_ids), desc_message]
+                vec![
+                    BackendMessage::ParameterDescription(type_ids),
+                    row_description(description, origins),
+                ]
+            }
+            QueryEvent::PortalDescribed(description, origins) => vec![row_description(description, origins)],
+            QueryEvent::QueryComplete => vec![BackendMessage::ReadyForQuery(TransactionStatus::Idle)],
+            QueryEvent::QueryCompleteInTransaction => {
+                vec![BackendMessage::ReadyForQuery(TransactionStatus::InTransaction)]
             }
-            QueryEvent::QueryComplete => vec![BackendMessage::ReadyForQuery],
+            QueryEvent::QueryCompleteInFailedTransaction => {
+                vec![BackendMessage::ReadyForQuery(TransactionStatus::Failed)]
+            }
+            QueryEvent::QueryEmpty => vec![BackendMessage::EmptyQueryResponse],
             QueryEvent::ParseComplete => vec![BackendMessage::ParseComplete],
             QueryEvent::BindComplete => vec![BackendMessage::BindComplete],
+            QueryEvent::CloseComplete => vec![BackendMessage::CloseComplete],
+            QueryEvent::CursorDeclared => vec![BackendMessage::CommandComplete("DECLARE CURSOR".to_owned())],
+            QueryEvent::RecordsFetchedFromCursor(projection) => {
+                let len = projection.1.len();
+                let mut messages = described_rows(projection);
+                messages.push(BackendMessage::CommandComplete(format!("FETCH {}", len)));
+                messages
+            }
+            QueryEvent::CursorClosed => vec![BackendMessage::CommandComplete("CLOSE CURSOR".to_owned())],
+            QueryEvent::AllCursorsClosed => vec![BackendMessage::CommandComplete("CLOSE CURSOR ALL".to_owned())],
+            QueryEvent::CopyInStarted(columns) => vec![BackendMessage::CopyInResponse(columns as i16)],
+            QueryEvent::RecordsCopied(records) => vec![BackendMessage::CommandComplete(format!("COPY {}", records))],
+            QueryEvent::CopyOutStarted(columns) => vec![BackendMessage::CopyOutResponse(columns as i16)],
+            QueryEvent::CopyData(data) => vec![BackendMessage::CopyData(data)],
+            QueryEvent::CopyOutDone(records) => vec![
+                BackendMessage::CopyDone,
+                BackendMessage::CommandComplete(format!("COPY {}", records)),
+            ],
+            QueryEvent::Notice(message) => vec![BackendMessage::NoticeResponse(
+                Some(Severity::Notice.into()),
+                Some("00000"),
+                Some(message),
+            )],
+            QueryEvent::Listening => vec![BackendMessage::CommandComplete("LISTEN".to_owned())],
+            QueryEvent::Unlistening => vec![BackendMessage::CommandComplete("UNLISTEN".to_owned())],
+            QueryEvent::Notified => vec![BackendMessage::CommandComplete("NOTIFY".to_owned())],
+            QueryEvent::Notification(pid, channel, payload) => {
+                vec![BackendMessage::NotificationResponse(pid, channel, payload)]
+            }
+            QueryEvent::Skipped(reason) => vec![BackendMessage::NoticeResponse(
+                Some(Severity::Notice.into()),
+                reason.code(),
+                reason.message().map(|message| format!("{}, skipping", message)),
+            )],
+            QueryEvent::Warning(reason) => vec![BackendMessage::NoticeResponse(
+                Some(Severity::Warning.into()),
+                reason.code(),
+                reason.message(),
+            )],
         }
     }
 }
 
+fn described_rows((definition, records): Projection) -> Vec<BackendMessage> {
+    let description: Vec<ColumnMetadata> = definition
+        .into_iter()
+        .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
+        .collect();
+    let mut messages = vec![BackendMessage::RowDescription(description)];
+    for record in records {
+        messages.push(BackendMessage::DataRow(
+            record.into_iter().map(String::into_bytes).collect(),
+        ));
+    }
+    messages
+}
+
+fn data_rows((definition, records): Projection, formats: &[PostgreSqlFormat]) -> Vec<BackendMessage> {
+    records
+        .into_iter()
+        .map(|record| {
+            BackendMessage::DataRow(
+                record
+                    .iter()
+                    .zip(definition.iter().zip(formats.iter()))
+                    .map(|(value, ((_name, sql_type), format))| sql_type.encode(format, value))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn row_description(description: Description, origins: ColumnOrigins) -> BackendMessage {
+    if description.is_empty() {
+        BackendMessage::NoData
+    } else {
+        let columns: Vec<ColumnMetadata> = description
+            .into_iter()
+            .zip(origins.into_iter())
+            .map(|((name, sql_type), origin)| {
+                let column = ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len());
+                match origin {
+                    Some((table_id, column_id)) => column.with_origin(table_id, column_id),
+                    None => column,
+                }
+            })
+            .collect();
+        BackendMessage::RowDescription(columns)
+    }
+}
+
 /// Message severities
 /// Reference: defined in https://www.postgresql.org/docs/12/protocol-error-fields.html
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -150,8 +355,22 @@ pub(crate) enum QueryErrorKind {
     InvalidParameterValue(String),
     PreparedStatementDoesNotExist(String),
     PortalDoesNotExist(String),
+    DuplicateCursor(String),
+    InvalidCursorName(String),
+    QueryCanceled(String),
+    BadCopyFileFormat(String),
     ProtocolViolation(String),
     FeatureNotSupported(String),
+    InsufficientPrivilege(String),
+    UniqueViolation(String),
+    NotNullViolation(String),
+    ForeignKeyViolation {
+        table_name: String,
+        constraint_name: String,
+        referencing_table: Option<String>,
+    },
+    InvalidForeignKey(String),
+    MultiplePrimaryKeys(String),
     TooManyInsertExpressions,
     NumericTypeOutOfRange {
         pg_type: PostgreSqlType,
@@ -175,6 +394,34 @@ pub(crate) enum QueryErrorKind {
         left_type: String,
         right_type: String,
     },
+    FunctionDoesNotExist(String),
+    NumericValueOutOfRange(String),
+    InvalidTextRepresentation(String),
+    SubstringError(String),
+    InvalidRegularExpression(String),
+    InvalidArgumentForLogarithm(String),
+    InvalidArgumentForPowerFunction(String),
+    InvalidDatetimeFormat(String),
+    DatetimeValueOutOfRange(String),
+    LargeObjectDoesNotExist(u64),
+    SequenceAlreadyExists(String),
+    SequenceDoesNotExist(String),
+    SequenceLimitExceeded(String),
+    DependentObjectsStillExist(String),
+    StatisticsAlreadyExists(String),
+    StatisticsDoesNotExist(String),
+    TypeAlreadyExists(String),
+    FunctionAlreadyExists(String),
+    InvalidFunctionDefinition(String),
+    StackDepthLimitExceeded,
+    ObjectNotInPrerequisiteState(String),
+    WindowingError(String),
+    GroupingError(String),
+    InvalidObjectDefinition(String),
+    GeneratedAlways(String),
+    DivisionByZero,
+    CheckViolation(String),
+    DatatypeMismatch(String),
     AmbiguousColumnName {
         column: String,
     },
@@ -182,6 +429,16 @@ pub(crate) enum QueryErrorKind {
         column: String,
     },
     SyntaxError(String),
+    ActiveSqlTransaction,
+    NoActiveSqlTransaction,
+    InFailedSqlTransaction,
+    NoTransactionBlock(String),
+    SavepointDoesNotExist(String),
+    DeadlockDetected,
+    TransactionBlockNotAllowed(String),
+    IoError(String),
+    ReplicationSlotAlreadyExists(String),
+    ReplicationSlotDoesNotExist(String),
 }
 
 impl QueryErrorKind {
@@ -196,16 +453,64 @@ impl QueryErrorKind {
             Self::InvalidParameterValue(_) => "22023",
             Self::PreparedStatementDoesNotExist(_) => "26000",
             Self::PortalDoesNotExist(_) => "26000",
+            Self::DuplicateCursor(_) => "42P03",
+            Self::InvalidCursorName(_) => "34000",
+            Self::QueryCanceled(_) => "57014",
+            Self::BadCopyFileFormat(_) => "22P04",
             Self::ProtocolViolation(_) => "08P01",
             Self::FeatureNotSupported(_) => "0A000",
+            Self::InsufficientPrivilege(_) => "42501",
+            Self::UniqueViolation(_) => "23505",
+            Self::NotNullViolation(_) => "23502",
+            Self::ForeignKeyViolation { .. } => "23503",
+            Self::InvalidForeignKey(_) => "42830",
+            Self::MultiplePrimaryKeys(_) => "42P16",
             Self::TooManyInsertExpressions => "42601",
             Self::NumericTypeOutOfRange { .. } => "22003",
             Self::DataTypeMismatch { .. } => "2200G",
             Self::StringTypeLengthMismatch { .. } => "22026",
             Self::UndefinedFunction { .. } => "42883",
+            Self::FunctionDoesNotExist(_) => "42883",
+            Self::NumericValueOutOfRange(_) => "22003",
+            Self::InvalidTextRepresentation(_) => "22P02",
+            Self::SubstringError(_) => "22011",
+            Self::InvalidRegularExpression(_) => "2201B",
+            Self::InvalidArgumentForLogarithm(_) => "2201E",
+            Self::InvalidArgumentForPowerFunction(_) => "2201F",
+            Self::InvalidDatetimeFormat(_) => "22007",
+            Self::DatetimeValueOutOfRange(_) => "22008",
+            Self::LargeObjectDoesNotExist(_) => "42704",
+            Self::SequenceAlreadyExists(_) => "42P07",
+            Self::SequenceDoesNotExist(_) => "42P01",
+            Self::SequenceLimitExceeded(_) => "2200H",
+            Self::DependentObjectsStillExist(_) => "2BP01",
+            Self::StatisticsAlreadyExists(_) => "42710",
+            Self::StatisticsDoesNotExist(_) => "42704",
+            Self::TypeAlreadyExists(_) => "42710",
+            Self::FunctionAlreadyExists(_) => "42723",
+            Self::InvalidFunctionDefinition(_) => "42P13",
+            Self::StackDepthLimitExceeded => "54001",
+            Self::ObjectNotInPrerequisiteState(_) => "55000",
+            Self::WindowingError(_) => "42P20",
+            Self::GroupingError(_) => "42803",
+            Self::InvalidObjectDefinition(_) => "42P17",
+            Self::GeneratedAlways(_) => "428C9",
+            Self::DivisionByZero => "22012",
+            Self::CheckViolation(_) => "23514",
+            Self::DatatypeMismatch(_) => "42804",
             Self::AmbiguousColumnName { .. } => "42702",
             Self::UndefinedColumn { .. } => "42883",
             Self::SyntaxError(_) => "42601",
+            Self::ActiveSqlTransaction => "25001",
+            Self::NoActiveSqlTransaction => "25P01",
+            Self::InFailedSqlTransaction => "25P02",
+            Self::NoTransactionBlock(_) => "25P01",
+            Self::SavepointDoesNotExist(_) => "3B001",
+            Self::DeadlockDetected => "40P01",
+            Self::TransactionBlockNotAllowed(_) => "25001",
+            Self::IoError(_) => "58030",
+            Self::ReplicationSlotAlreadyExists(_) => "42710",
+            Self::ReplicationSlotDoesNotExist(_) => "42704",
         }
     }
 }
@@ -226,10 +531,51 @@ impl Display for QueryErrorKind {
                 write!(f, "prepared statement {} does not exist", statement_name)
             }
             Self::PortalDoesNotExist(portal_name) => write!(f, "portal {} does not exist", portal_name),
+            Self::DuplicateCursor(cursor_name) => write!(f, "cursor \"{}\" already exists", cursor_name),
+            Self::InvalidCursorName(cursor_name) => write!(f, "cursor \"{}\" does not exist", cursor_name),
+            Self::QueryCanceled(message) => write!(f, "{}", message),
+            Self::BadCopyFileFormat(message) => write!(f, "{}", message),
             Self::ProtocolViolation(message) => write!(f, "{}", message),
             Self::FeatureNotSupported(raw_sql_query) => {
                 write!(f, "Currently, Query '{}' can't be executed", raw_sql_query)
             }
+            Self::InsufficientPrivilege(message) => write!(f, "{}", message),
+            Self::UniqueViolation(constraint_name) => write!(
+                f,
+                "duplicate key value violates unique constraint \"{}\"",
+                constraint_name
+            ),
+            Self::NotNullViolation(column_name) => write!(
+                f,
+                "null value in column \"{}\" violates not-null constraint",
+                column_name
+            ),
+            Self::ForeignKeyViolation {
+                table_name,
+                constraint_name,
+                referencing_table: None,
+            } => write!(
+                f,
+                "insert or update on table \"{}\" violates foreign key constraint \"{}\"",
+                table_name, constraint_name
+            ),
+            Self::ForeignKeyViolation {
+                table_name,
+                constraint_name,
+                referencing_table: Some(referencing_table),
+            } => write!(
+                f,
+                "update or delete on table \"{}\" violates foreign key constraint \"{}\" on table \"{}\"",
+                table_name, constraint_name, referencing_table
+            ),
+            Self::InvalidForeignKey(table_name) => write!(
+                f,
+                "there is no unique constraint matching given keys for referenced table \"{}\"",
+                table_name
+            ),
+            Self::MultiplePrimaryKeys(table_name) => {
+                write!(f, "multiple primary keys for table \"{}\" are not allowed", table_name)
+            }
             Self::TooManyInsertExpressions => write!(f, "INSERT has more expressions than target columns"),
             Self::NumericTypeOutOfRange {
                 pg_type,
@@ -269,21 +615,108 @@ impl Display for QueryErrorKind {
                 "operator does not exist: ({} {} {})",
                 left_type, operator, right_type
             ),
+            Self::FunctionDoesNotExist(function) => write!(f, "function {} does not exist", function),
+            Self::NumericValueOutOfRange(message) => write!(f, "{}", message),
+            Self::InvalidTextRepresentation(message) => write!(f, "{}", message),
+            Self::SubstringError(message) => write!(f, "{}", message),
+            Self::InvalidRegularExpression(message) => write!(f, "invalid regular expression: {}", message),
+            Self::InvalidArgumentForLogarithm(message) => write!(f, "{}", message),
+            Self::InvalidArgumentForPowerFunction(message) => write!(f, "{}", message),
+            Self::InvalidDatetimeFormat(message) => write!(f, "{}", message),
+            Self::DatetimeValueOutOfRange(message) => write!(f, "{}", message),
+            Self::LargeObjectDoesNotExist(id) => write!(f, "large object {} does not exist", id),
+            Self::SequenceAlreadyExists(sequence_name) => write!(f, "relation \"{}\" already exists", sequence_name),
+            Self::SequenceDoesNotExist(sequence_name) => write!(f, "relation \"{}\" does not exist", sequence_name),
+            Self::SequenceLimitExceeded(message) => write!(f, "{}", message),
+            Self::DependentObjectsStillExist(object) => {
+                write!(f, "cannot drop {} because other objects depend on it", object)
+            }
+            Self::StatisticsAlreadyExists(statistics_name) => {
+                write!(f, "statistics object \"{}\" already exists", statistics_name)
+            }
+            Self::StatisticsDoesNotExist(statistics_name) => {
+                write!(f, "statistics object \"{}\" does not exist", statistics_name)
+            }
+            Self::TypeAlreadyExists(type_name) => write!(f, "type \"{}\" already exists", type_name),
+            Self::FunctionAlreadyExists(function_name) => write!(f, "function \"{}\" already exists", function_name),
+            Self::InvalidFunctionDefinition(message) => write!(f, "{}", message),
+            Self::StackDepthLimitExceeded => write!(f, "stack depth limit exceeded"),
+            Self::ObjectNotInPrerequisiteState(message) => write!(f, "{}", message),
+            Self::WindowingError(message) => write!(f, "{}", message),
+            Self::GroupingError(message) => write!(f, "{}", message),
+            Self::InvalidObjectDefinition(message) => write!(f, "{}", message),
+            Self::GeneratedAlways(message) => write!(f, "{}", message),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::CheckViolation(message) => write!(f, "{}", message),
+            Self::DatatypeMismatch(message) => write!(f, "{}", message),
             Self::AmbiguousColumnName { column } => write!(f, "use of ambiguous column name in context: '{}'", column),
             Self::UndefinedColumn { column } => write!(f, "use of undefined column: '{}'", column),
             Self::SyntaxError(expression) => write!(f, "syntax error in {}", expression),
+            Self::ActiveSqlTransaction => write!(f, "there is already a transaction in progress"),
+            Self::NoActiveSqlTransaction => write!(f, "there is no transaction in progress"),
+            Self::InFailedSqlTransaction => write!(
+                f,
+                "current transaction is aborted, commands ignored until end of transaction block"
+            ),
+            Self::NoTransactionBlock(statement) => write!(f, "{} can only be used in transaction blocks", statement),
+            Self::SavepointDoesNotExist(savepoint_name) => write!(f, "savepoint \"{}\" does not exist", savepoint_name),
+            Self::DeadlockDetected => write!(f, "deadlock detected"),
+            Self::TransactionBlockNotAllowed(statement) => {
+                write!(f, "{} cannot run inside a transaction block", statement)
+            }
+            Self::IoError(message) => write!(f, "{}", message),
+            Self::ReplicationSlotAlreadyExists(slot_name) => {
+                write!(f, "replication slot \"{}\" already exists", slot_name)
+            }
+            Self::ReplicationSlotDoesNotExist(slot_name) => {
+                write!(f, "replication slot \"{}\" does not exist", slot_name)
+            }
         }
     }
 }
 
+const CASCADE_HINT: &str = "Use DROP ... CASCADE to drop the dependent objects too.";
+
 /// Represents error during query execution
 #[derive(Debug, PartialEq, Clone)]
 pub struct QueryError {
     severity: Severity,
     kind: QueryErrorKind,
+    details: ErrorDetails,
 }
 
 impl QueryError {
+    fn error(kind: QueryErrorKind) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind,
+            details: ErrorDetails::default(),
+        }
+    }
+
+    /// adds a secondary message that carries more details about the error
+    pub fn with_detail<S: ToString>(mut self, detail: S) -> QueryError {
+        self.details.detail = Some(detail.to_string());
+        self
+    }
+
+    /// adds a suggestion what to do about the error
+    pub fn with_hint<S: ToString>(mut self, hint: S) -> QueryError {
+        self.details.hint = Some(hint.to_string());
+        self
+    }
+
+    /// adds 1-based index of the character in the query string where the error is found
+    pub fn with_position(mut self, position: usize) -> QueryError {
+        self.details.position = Some(position);
+        self
+    }
+
+    /// SQLSTATE code of the error
+    pub fn sqlstate(&self) -> &'static str {
+        self.kind.code()
+    }
+
     fn code(&self) -> Option<&'static str> {
         Some(self.kind.code())
     }
@@ -300,157 +733,389 @@ impl QueryError {
 
 impl Into<BackendMessage> for QueryError {
     fn into(self) -> BackendMessage {
-        BackendMessage::ErrorResponse(self.severity(), self.code(), self.message())
+        BackendMessage::ErrorResponse(self.severity(), self.code(), self.message(), self.details)
     }
 }
 
 impl QueryError {
     /// schema already exists error constructor
     pub fn schema_already_exists<S: ToString>(schema_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::SchemaAlreadyExists(schema_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::SchemaAlreadyExists(schema_name.to_string()))
     }
 
     /// schema does not exist error constructor
     pub fn schema_does_not_exist<S: ToString>(schema_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::SchemaDoesNotExist(schema_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::SchemaDoesNotExist(schema_name.to_string()))
     }
 
     /// schema has dependent objects error constructor
     pub fn schema_has_dependent_objects<S: ToString>(schema_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::SchemaHasDependentObjects(schema_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::SchemaHasDependentObjects(schema_name.to_string())).with_hint(CASCADE_HINT)
     }
 
     /// table already exists error constructor
     pub fn table_already_exists<S: ToString>(table_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::TableAlreadyExists(table_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::TableAlreadyExists(table_name.to_string()))
     }
 
     /// table does not exist error constructor
     pub fn table_does_not_exist<S: ToString>(table_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::TableDoesNotExist(table_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::TableDoesNotExist(table_name.to_string()))
     }
 
     /// column does not exists error constructor
     pub fn column_does_not_exist<S: ToString>(non_existing_column: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::ColumnDoesNotExist(non_existing_column.to_string()),
-        }
+        QueryError::error(QueryErrorKind::ColumnDoesNotExist(non_existing_column.to_string()))
     }
 
     /// invalid parameter value error constructor
     pub fn invalid_parameter_value<S: ToString>(message: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::InvalidParameterValue(message.to_string()),
-        }
+        QueryError::error(QueryErrorKind::InvalidParameterValue(message.to_string()))
     }
 
     /// prepared statement does not exist error constructor
     pub fn prepared_statement_does_not_exist<S: ToString>(statement_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::PreparedStatementDoesNotExist(statement_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::PreparedStatementDoesNotExist(
+            statement_name.to_string(),
+        ))
     }
 
     /// portal does not exist error constructor
     pub fn portal_does_not_exist<S: ToString>(portal_name: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::PortalDoesNotExist(portal_name.to_string()),
-        }
+        QueryError::error(QueryErrorKind::PortalDoesNotExist(portal_name.to_string()))
+    }
+
+    /// cursor already exists error constructor
+    pub fn duplicate_cursor<S: ToString>(cursor_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::DuplicateCursor(cursor_name.to_string()))
+    }
+
+    /// cursor does not exist error constructor
+    pub fn invalid_cursor_name<S: ToString>(cursor_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidCursorName(cursor_name.to_string()))
+    }
+
+    /// statement is canceled by a client request error constructor
+    pub fn query_canceled() -> QueryError {
+        QueryError::error(QueryErrorKind::QueryCanceled(
+            "canceling statement due to user request".to_owned(),
+        ))
+    }
+
+    /// data of `COPY` can't be split into rows of the table error constructor
+    pub fn bad_copy_file_format<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::BadCopyFileFormat(message.to_string()))
+    }
+
+    /// client aborted copying of data error constructor
+    pub fn copy_from_stdin_failed<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::QueryCanceled(format!(
+            "COPY from stdin failed: {}",
+            message.to_string()
+        )))
     }
 
     /// protocol violation error constructor
     pub fn protocol_violation<S: ToString>(message: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::ProtocolViolation(message.to_string()),
-        }
+        QueryError::error(QueryErrorKind::ProtocolViolation(message.to_string()))
     }
 
     /// not supported operation error constructor
     pub fn feature_not_supported<S: ToString>(feature_description: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::FeatureNotSupported(feature_description.to_string()),
-        }
+        QueryError::error(QueryErrorKind::FeatureNotSupported(feature_description.to_string()))
+    }
+
+    /// role does not have required privilege on a schema
+    pub fn permission_denied_for_schema<S: ToString>(schema_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InsufficientPrivilege(format!(
+            "permission denied for schema {}",
+            schema_name.to_string()
+        )))
+    }
+
+    /// only schema owner can perform an operation
+    pub fn must_be_owner_of_schema<S: ToString>(schema_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InsufficientPrivilege(format!(
+            "must be owner of schema {}",
+            schema_name.to_string()
+        )))
+    }
+
+    /// inserted or updated key already exists
+    pub fn unique_violation<S: ToString>(constraint_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::UniqueViolation(constraint_name.to_string()))
+    }
+
+    /// NULL is stored into a column declared as NOT NULL
+    pub fn not_null_violation<S: ToString>(column_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::NotNullViolation(column_name.to_string()))
+    }
+
+    /// inserted or updated value is not present in the referenced table
+    pub fn foreign_key_violation<T: ToString, C: ToString>(table_name: T, constraint_name: C) -> QueryError {
+        QueryError::error(QueryErrorKind::ForeignKeyViolation {
+            table_name: table_name.to_string(),
+            constraint_name: constraint_name.to_string(),
+            referencing_table: None,
+        })
+    }
+
+    /// deleted row is still referenced from another table
+    pub fn referenced_row_violation<T: ToString, C: ToString, R: ToString>(
+        table_name: T,
+        constraint_name: C,
+        referencing_table: R,
+    ) -> QueryError {
+        QueryError::error(QueryErrorKind::ForeignKeyViolation {
+            table_name: table_name.to_string(),
+            constraint_name: constraint_name.to_string(),
+            referencing_table: Some(referencing_table.to_string()),
+        })
+    }
+
+    /// referenced columns are not a primary key of the referenced table
+    pub fn invalid_foreign_key<S: ToString>(table_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidForeignKey(table_name.to_string()))
+    }
+
+    /// table definition contains more than one primary key
+    pub fn multiple_primary_keys<S: ToString>(table_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::MultiplePrimaryKeys(table_name.to_string()))
     }
 
     /// too many insert expressions errors constructors
     pub fn too_many_insert_expressions() -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::TooManyInsertExpressions,
-        }
+        QueryError::error(QueryErrorKind::TooManyInsertExpressions)
     }
 
     /// syntax error in the expression as part of query
     pub fn syntax_error<S: ToString>(expression: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::SyntaxError(expression.to_string()),
-        }
+        QueryError::error(QueryErrorKind::SyntaxError(expression.to_string()))
+    }
+
+    /// transaction is started inside of a transaction block
+    pub fn active_sql_transaction() -> QueryError {
+        QueryError::error(QueryErrorKind::ActiveSqlTransaction)
+    }
+
+    /// transaction is ended outside of a transaction block
+    pub fn no_active_sql_transaction() -> QueryError {
+        QueryError::error(QueryErrorKind::NoActiveSqlTransaction)
+    }
+
+    /// statement is run in a transaction block after one of its statements failed
+    pub fn in_failed_sql_transaction() -> QueryError {
+        QueryError::error(QueryErrorKind::InFailedSqlTransaction)
+    }
+
+    /// statement that works with savepoints is run outside of a transaction block
+    pub fn no_transaction_block<S: ToString>(statement: S) -> QueryError {
+        QueryError::error(QueryErrorKind::NoTransactionBlock(statement.to_string()))
+    }
+
+    /// savepoint does not exist error constructor
+    pub fn savepoint_does_not_exist<S: ToString>(savepoint_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::SavepointDoesNotExist(savepoint_name.to_string()))
+    }
+
+    /// transaction waits for a lock held by a transaction that waits for it
+    pub fn deadlock_detected() -> QueryError {
+        QueryError::error(QueryErrorKind::DeadlockDetected)
+    }
+
+    /// statement that can't be undone is run inside of a transaction block
+    pub fn transaction_block_not_allowed<S: ToString>(statement: S) -> QueryError {
+        QueryError::error(QueryErrorKind::TransactionBlockNotAllowed(statement.to_string()))
+    }
+
+    /// file could not be read or written
+    pub fn io_error<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::IoError(message.to_string()))
+    }
+
+    /// replication slot with the name is already created
+    pub fn replication_slot_already_exists<S: ToString>(slot_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::ReplicationSlotAlreadyExists(slot_name.to_string()))
+    }
+
+    /// replication slot with the name is not created or was dropped
+    pub fn replication_slot_does_not_exist<S: ToString>(slot_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::ReplicationSlotDoesNotExist(slot_name.to_string()))
     }
 
     /// operator or function is not found for operands
     pub fn undefined_function<S: ToString>(operator: S, left_type: S, right_type: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::UndefinedFunction {
-                operator: operator.to_string(),
-                left_type: left_type.to_string(),
-                right_type: right_type.to_string(),
-            },
-        }
+        QueryError::error(QueryErrorKind::UndefinedFunction {
+            operator: operator.to_string(),
+            left_type: left_type.to_string(),
+            right_type: right_type.to_string(),
+        })
+        .with_hint("No operator matches the given name and argument types. You might need to add explicit type casts.")
+    }
+
+    /// function argument is out of the range that the function accepts
+    pub fn numeric_value_out_of_range<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::NumericValueOutOfRange(message.to_string()))
+    }
+
+    /// function with the name and arguments is not known
+    pub fn function_does_not_exist<S: ToString>(function: S) -> QueryError {
+        QueryError::error(QueryErrorKind::FunctionDoesNotExist(function.to_string())).with_hint(
+            "No function matches the given name and argument types. You might need to add explicit type casts.",
+        )
+    }
+
+    /// value can't be parsed from its text representation
+    pub fn invalid_text_representation<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidTextRepresentation(message.to_string()))
+    }
+
+    /// arguments of a substring function don't describe a substring
+    pub fn substring_error<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::SubstringError(message.to_string()))
+    }
+
+    /// pattern of a regular expression operator can't be compiled
+    pub fn invalid_regular_expression<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidRegularExpression(message.to_string()))
+    }
+
+    /// logarithm of zero or a negative number
+    pub fn invalid_argument_for_logarithm<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidArgumentForLogarithm(message.to_string()))
+    }
+
+    /// power or square root that does not have a real result
+    pub fn invalid_argument_for_power_function<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidArgumentForPowerFunction(message.to_string()))
+    }
+
+    /// date, time or interval can't be parsed from its text representation
+    pub fn invalid_datetime_format<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidDatetimeFormat(message.to_string()))
+    }
+
+    /// result of date, time or interval arithmetic can't be represented
+    pub fn datetime_value_out_of_range<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::DatetimeValueOutOfRange(message.to_string()))
+    }
+
+    /// large object with the id was not created or was unlinked
+    pub fn large_object_does_not_exist(id: u64) -> QueryError {
+        QueryError::error(QueryErrorKind::LargeObjectDoesNotExist(id))
+    }
+
+    /// sequence with the name already exists in the schema
+    pub fn sequence_already_exists<S: ToString>(sequence_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::SequenceAlreadyExists(sequence_name.to_string()))
+    }
+
+    /// sequence with the name does not exist in the schema
+    pub fn sequence_does_not_exist<S: ToString>(sequence_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::SequenceDoesNotExist(sequence_name.to_string()))
+    }
+
+    /// sequence reached its maximum or minimum value
+    pub fn sequence_limit_exceeded<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::SequenceLimitExceeded(message.to_string()))
+    }
+
+    /// object is used by other objects, e.g. a sequence by defaults of serial columns
+    pub fn dependent_objects_still_exist<S: ToString>(object: S) -> QueryError {
+        QueryError::error(QueryErrorKind::DependentObjectsStillExist(object.to_string())).with_hint(CASCADE_HINT)
+    }
+
+    /// statistics object with the name already exists in the schema
+    pub fn statistics_already_exists<S: ToString>(statistics_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::StatisticsAlreadyExists(statistics_name.to_string()))
+    }
+
+    /// statistics object with the name does not exist in the schema
+    pub fn statistics_does_not_exist<S: ToString>(statistics_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::StatisticsDoesNotExist(statistics_name.to_string()))
+    }
+
+    /// type with the name already exists in the schema
+    pub fn type_already_exists<S: ToString>(type_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::TypeAlreadyExists(type_name.to_string()))
+    }
+
+    /// function with the name already exists in the schema
+    pub fn function_already_exists<S: ToString>(function_name: S) -> QueryError {
+        QueryError::error(QueryErrorKind::FunctionAlreadyExists(function_name.to_string()))
+    }
+
+    /// body of a function is not a single expression of its return type
+    pub fn invalid_function_definition<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidFunctionDefinition(message.to_string()))
+    }
+
+    /// calls of user-defined functions are nested too deep, e.g. a function calls itself
+    pub fn stack_depth_limit_exceeded() -> QueryError {
+        QueryError::error(QueryErrorKind::StackDepthLimitExceeded)
+    }
+
+    /// object can't be used in its current state, e.g. `currval` before `nextval`
+    pub fn object_not_in_prerequisite_state<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::ObjectNotInPrerequisiteState(message.to_string()))
+    }
+
+    /// invalid window frame or window function call
+    pub fn windowing_error<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::WindowingError(message.to_string()))
+    }
+
+    /// column is read outside of aggregates of an aggregated select
+    pub fn grouping_error<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::GroupingError(message.to_string()))
+    }
+
+    /// definition of a table or its column is inconsistent
+    pub fn invalid_object_definition<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::InvalidObjectDefinition(message.to_string()))
+    }
+
+    /// values of generated columns can't be given explicitly
+    pub fn generated_always<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::GeneratedAlways(message.to_string()))
+    }
+
+    /// divisor of a division or modulo operation is zero
+    pub fn division_by_zero() -> QueryError {
+        QueryError::error(QueryErrorKind::DivisionByZero)
+    }
+
+    /// row does not satisfy a constraint of the table, e.g. bounds of its partition
+    pub fn check_violation<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::CheckViolation(message.to_string()))
+    }
+
+    /// values of different types are used where values of one type are expected, e.g. elements of an array
+    pub fn datatype_mismatch<S: ToString>(message: S) -> QueryError {
+        QueryError::error(QueryErrorKind::DatatypeMismatch(message.to_string()))
     }
 
     /// when the name of a column is ambiguous in a multi-table context
     pub fn ambiguous_column<S: ToString>(column: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::AmbiguousColumnName {
-                column: column.to_string(),
-            },
-        }
+        QueryError::error(QueryErrorKind::AmbiguousColumnName {
+            column: column.to_string(),
+        })
     }
 
     /// user of an undefined column
     pub fn undefined_column<S: ToString>(column: S) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::UndefinedColumn {
-                column: column.to_string(),
-            },
-        }
+        QueryError::error(QueryErrorKind::UndefinedColumn {
+            column: column.to_string(),
+        })
     }
 
     /// numeric out of range constructor
     pub fn out_of_range<S: ToString>(pg_type: PostgreSqlType, column_name: S, row_index: usize) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::NumericTypeOutOfRange {
-                pg_type,
-                column_name: column_name.to_string(),
-                row_index,
-            },
-        }
+        QueryError::error(QueryErrorKind::NumericTypeOutOfRange {
+            pg_type,
+            column_name: column_name.to_string(),
+            row_index,
+        })
     }
 
     /// type mismatch constructor
@@ -460,15 +1125,12 @@ impl QueryError {
         column_name: S,
         row_index: usize,
     ) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::DataTypeMismatch {
-                pg_type,
-                value: value.to_string(),
-                column_name: column_name.to_string(),
-                row_index,
-            },
-        }
+        QueryError::error(QueryErrorKind::DataTypeMismatch {
+            pg_type,
+            value: value.to_string(),
+            column_name: column_name.to_string(),
+            row_index,
+        })
     }
 
     /// length of string types do not match constructor
@@ -478,15 +1140,12 @@ impl QueryError {
         column_name: S,
         row_index: usize,
     ) -> QueryError {
-        QueryError {
-            severity: Severity::Error,
-            kind: QueryErrorKind::StringTypeLengthMismatch {
-                pg_type,
-                len,
-                column_name: column_name.to_string(),
-                row_index,
-            },
-        }
+        QueryError::error(QueryErrorKind::StringTypeLengthMismatch {
+            pg_type,
+            len,
+            column_name: column_name.to_string(),
+            row_index,
+        })
     }
 }
 
@@ -507,6 +1166,12 @@ mod tests {
             )
         }
 
+        #[test]
+        fn backup_completed() {
+            let messages: Vec<BackendMessage> = QueryEvent::BackupCompleted.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("BACKUP".to_owned())])
+        }
+
         #[test]
         fn drop_schema() {
             let messages: Vec<BackendMessage> = QueryEvent::SchemaDropped.into();
@@ -516,6 +1181,27 @@ mod tests {
             )
         }
 
+        #[test]
+        fn alter_schema() {
+            let messages: Vec<BackendMessage> = QueryEvent::SchemaAltered.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("ALTER SCHEMA".to_owned())]
+            )
+        }
+
+        #[test]
+        fn grant_privileges() {
+            let messages: Vec<BackendMessage> = QueryEvent::PrivilegesGranted.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("GRANT".to_owned())])
+        }
+
+        #[test]
+        fn revoke_privileges() {
+            let messages: Vec<BackendMessage> = QueryEvent::PrivilegesRevoked.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("REVOKE".to_owned())])
+        }
+
         #[test]
         fn create_table() {
             let messages: Vec<BackendMessage> = QueryEvent::TableCreated.into();
@@ -532,38 +1218,125 @@ mod tests {
         }
 
         #[test]
-        fn insert_record() {
-            let records_number = 3;
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsInserted(records_number).into();
+        fn create_sequence() {
+            let messages: Vec<BackendMessage> = QueryEvent::SequenceCreated.into();
             assert_eq!(
                 messages,
-                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records_number))]
-            )
+                vec![BackendMessage::CommandComplete("CREATE SEQUENCE".to_owned())]
+            );
         }
 
         #[test]
-        fn select_records() {
-            let projection = (
-                vec![
-                    ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
-                    ("column_name_2".to_owned(), PostgreSqlType::SmallInt),
-                ],
-                vec![
-                    vec!["1".to_owned(), "2".to_owned()],
-                    vec!["3".to_owned(), "4".to_owned()],
+        fn drop_sequence() {
+            let messages: Vec<BackendMessage> = QueryEvent::SequenceDropped.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("DROP SEQUENCE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn create_statistics() {
+            let messages: Vec<BackendMessage> = QueryEvent::StatisticsCreated.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CREATE STATISTICS".to_owned())]
+            );
+        }
+
+        #[test]
+        fn analyze() {
+            let messages: Vec<BackendMessage> = QueryEvent::TablesAnalyzed.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("ANALYZE".to_owned())]);
+        }
+
+        #[test]
+        fn vacuum() {
+            let messages: Vec<BackendMessage> = QueryEvent::TablesVacuumed.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("VACUUM".to_owned())]);
+        }
+
+        #[test]
+        fn reset_variable() {
+            let messages: Vec<BackendMessage> = QueryEvent::VariableReset.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("RESET".to_owned())]);
+        }
+
+        #[test]
+        fn insert_record() {
+            let records_number = 3;
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsInserted(records_number).into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records_number))]
+            )
+        }
+
+        #[test]
+        fn row_description() {
+            let messages: Vec<BackendMessage> = QueryEvent::RowDescription(vec![
+                ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
+                ("column_name_2".to_owned(), PostgreSqlType::SmallInt),
+            ])
+            .into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::RowDescription(vec![
+                    ColumnMetadata::new("column_name_1".to_owned(), 21, 2),
+                    ColumnMetadata::new("column_name_2".to_owned(), 21, 2)
+                ])]
+            );
+        }
+
+        #[test]
+        fn data_row() {
+            let messages: Vec<BackendMessage> = QueryEvent::DataRow(vec!["1".to_owned(), "2".to_owned()]).into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::DataRow(vec![b"1".to_vec(), b"2".to_vec()])]
+            );
+        }
+
+        #[test]
+        fn select_records() {
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(2).into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("SELECT 2".to_owned())]);
+        }
+
+        #[test]
+        fn fetch_records_in_binary_format() {
+            let projection = (
+                vec![
+                    ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
+                    ("column_name_2".to_owned(), PostgreSqlType::VarChar),
                 ],
+                vec![vec!["1".to_owned(), "a".to_owned()]],
             );
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(projection).into();
+            let messages: Vec<BackendMessage> =
+                QueryEvent::RecordsFetched(projection, vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text]).into();
             assert_eq!(
                 messages,
                 vec![
-                    BackendMessage::RowDescription(vec![
-                        ColumnMetadata::new("column_name_1".to_owned(), 21, 2),
-                        ColumnMetadata::new("column_name_2".to_owned(), 21, 2)
-                    ]),
-                    BackendMessage::DataRow(vec!["1".to_owned(), "2".to_owned()]),
-                    BackendMessage::DataRow(vec!["3".to_owned(), "4".to_owned()]),
-                    BackendMessage::CommandComplete("SELECT 2".to_owned())
+                    BackendMessage::DataRow(vec![vec![0, 1], b"a".to_vec()]),
+                    BackendMessage::CommandComplete("SELECT 1".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn suspend_fetching_records() {
+            let projection = (
+                vec![("column_name".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["1".to_owned()], vec!["2".to_owned()]],
+            );
+            let messages: Vec<BackendMessage> =
+                QueryEvent::RecordsSuspended(projection, vec![PostgreSqlFormat::Text]).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::DataRow(vec![b"1".to_vec()]),
+                    BackendMessage::DataRow(vec![b"2".to_vec()]),
+                    BackendMessage::PortalSuspended
                 ]
             );
         }
@@ -593,6 +1366,7 @@ mod tests {
             let messages: Vec<BackendMessage> = QueryEvent::PreparedStatementDescribed(
                 vec![PostgreSqlType::SmallInt],
                 vec![("si_column".to_owned(), PostgreSqlType::SmallInt)],
+                vec![None],
             )
             .into();
             assert_eq!(
@@ -603,15 +1377,81 @@ mod tests {
                         name: "si_column".to_owned(),
                         type_id: 21,
                         type_size: 2,
+                        table_id: 0,
+                        column_id: 0,
                     }])
                 ]
             )
         }
 
+        #[test]
+        fn describe_prepared_statement_with_table_columns() {
+            let messages: Vec<BackendMessage> = QueryEvent::PreparedStatementDescribed(
+                vec![],
+                vec![("si_column".to_owned(), PostgreSqlType::SmallInt)],
+                vec![Some((16385, 1))],
+            )
+            .into();
+            assert_eq!(
+                messages,
+                [
+                    BackendMessage::ParameterDescription(vec![]),
+                    BackendMessage::RowDescription(vec![
+                        ColumnMetadata::new("si_column".to_owned(), 21, 2).with_origin(16385, 1)
+                    ])
+                ]
+            )
+        }
+
+        #[test]
+        fn describe_portal() {
+            let messages: Vec<BackendMessage> = QueryEvent::PortalDescribed(
+                vec![("si_column".to_owned(), PostgreSqlType::SmallInt)],
+                vec![Some((16385, 1))],
+            )
+            .into();
+            assert_eq!(
+                messages,
+                [BackendMessage::RowDescription(vec![ColumnMetadata::new(
+                    "si_column".to_owned(),
+                    21,
+                    2
+                )
+                .with_origin(16385, 1)])]
+            )
+        }
+
+        #[test]
+        fn describe_portal_without_rows() {
+            let messages: Vec<BackendMessage> = QueryEvent::PortalDescribed(vec![], vec![]).into();
+            assert_eq!(messages, [BackendMessage::NoData])
+        }
+
         #[test]
         fn complete_query() {
             let messages: Vec<BackendMessage> = QueryEvent::QueryComplete.into();
-            assert_eq!(messages, [BackendMessage::ReadyForQuery])
+            assert_eq!(messages, [BackendMessage::ReadyForQuery(TransactionStatus::Idle)])
+        }
+
+        #[test]
+        fn complete_query_in_transaction() {
+            let messages: Vec<BackendMessage> = QueryEvent::QueryCompleteInTransaction.into();
+            assert_eq!(
+                messages,
+                [BackendMessage::ReadyForQuery(TransactionStatus::InTransaction)]
+            )
+        }
+
+        #[test]
+        fn complete_query_in_failed_transaction() {
+            let messages: Vec<BackendMessage> = QueryEvent::QueryCompleteInFailedTransaction.into();
+            assert_eq!(messages, [BackendMessage::ReadyForQuery(TransactionStatus::Failed)])
+        }
+
+        #[test]
+        fn empty_query() {
+            let messages: Vec<BackendMessage> = QueryEvent::QueryEmpty.into();
+            assert_eq!(messages, [BackendMessage::EmptyQueryResponse])
         }
 
         #[test]
@@ -625,6 +1465,120 @@ mod tests {
             let messages: Vec<BackendMessage> = QueryEvent::BindComplete.into();
             assert_eq!(messages, [BackendMessage::BindComplete])
         }
+
+        #[test]
+        fn fetch_records_from_cursor() {
+            let projection = (
+                vec![("column_name".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["1".to_owned()]],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsFetchedFromCursor(projection).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::RowDescription(vec![ColumnMetadata::new("column_name".to_owned(), 23, 4)]),
+                    BackendMessage::DataRow(vec![b"1".to_vec()]),
+                    BackendMessage::CommandComplete("FETCH 1".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn close_all_cursors() {
+            let messages: Vec<BackendMessage> = QueryEvent::AllCursorsClosed.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CLOSE CURSOR ALL".to_owned())]
+            );
+        }
+
+        #[test]
+        fn complete_close() {
+            let messages: Vec<BackendMessage> = QueryEvent::CloseComplete.into();
+            assert_eq!(messages, [BackendMessage::CloseComplete])
+        }
+
+        #[test]
+        fn copy_in_started() {
+            let messages: Vec<BackendMessage> = QueryEvent::CopyInStarted(3).into();
+            assert_eq!(messages, [BackendMessage::CopyInResponse(3)])
+        }
+
+        #[test]
+        fn records_copied() {
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsCopied(2).into();
+            assert_eq!(messages, [BackendMessage::CommandComplete("COPY 2".to_owned())])
+        }
+
+        #[test]
+        fn copy_out() {
+            let messages: Vec<BackendMessage> = QueryEvent::CopyOutStarted(2).into();
+            assert_eq!(messages, [BackendMessage::CopyOutResponse(2)]);
+            let messages: Vec<BackendMessage> = QueryEvent::CopyData(b"1\ta\n".to_vec()).into();
+            assert_eq!(messages, [BackendMessage::CopyData(b"1\ta\n".to_vec())]);
+            let messages: Vec<BackendMessage> = QueryEvent::CopyOutDone(1).into();
+            assert_eq!(
+                messages,
+                [
+                    BackendMessage::CopyDone,
+                    BackendMessage::CommandComplete("COPY 1".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn notice() {
+            let messages: Vec<BackendMessage> = QueryEvent::Notice("parse: 0.010 ms".to_owned()).into();
+            assert_eq!(
+                messages,
+                [BackendMessage::NoticeResponse(
+                    Some("NOTICE"),
+                    Some("00000"),
+                    Some("parse: 0.010 ms".to_owned())
+                )]
+            )
+        }
+
+        #[test]
+        fn skipped() {
+            let messages: Vec<BackendMessage> =
+                QueryEvent::Skipped(QueryError::table_does_not_exist("schema_name.table_name")).into();
+            assert_eq!(
+                messages,
+                [BackendMessage::NoticeResponse(
+                    Some("NOTICE"),
+                    Some("42P01"),
+                    Some("table \"schema_name.table_name\" does not exist, skipping".to_owned())
+                )]
+            )
+        }
+
+        #[test]
+        fn warning() {
+            let messages: Vec<BackendMessage> = QueryEvent::Warning(QueryError::no_active_sql_transaction()).into();
+            assert_eq!(
+                messages,
+                [BackendMessage::NoticeResponse(
+                    Some("WARNING"),
+                    Some("25P01"),
+                    Some("there is no transaction in progress".to_owned())
+                )]
+            )
+        }
+
+        #[test]
+        fn notification() {
+            let messages: Vec<BackendMessage> =
+                QueryEvent::Notification(1, "channel".to_owned(), "payload".to_owned()).into();
+            assert_eq!(
+                messages,
+                [BackendMessage::NotificationResponse(
+                    1,
+                    "channel".to_owned(),
+                    "payload".to_owned()
+                )]
+            )
+        }
     }
 
     #[cfg(test)]
@@ -641,6 +1595,7 @@ mod tests {
                     Some("ERROR"),
                     Some("42P06"),
                     Some(format!("schema \"{}\" already exists", schema_name)),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -655,6 +1610,7 @@ mod tests {
                     Some("ERROR"),
                     Some("3F000"),
                     Some(format!("schema \"{}\" does not exist", schema_name)),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -669,6 +1625,7 @@ mod tests {
                     Some("ERROR"),
                     Some("42P07"),
                     Some(format!("table \"{}\" already exists", table_name)),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -683,6 +1640,7 @@ mod tests {
                     Some("ERROR"),
                     Some("42P01"),
                     Some(format!("table \"{}\" does not exist", table_name)),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -696,6 +1654,7 @@ mod tests {
                     Some("ERROR"),
                     Some("42703"),
                     Some("column column_not_in_table does not exist".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -705,7 +1664,12 @@ mod tests {
             let messages: BackendMessage = QueryError::invalid_parameter_value("Wrong parameter value").into();
             assert_eq!(
                 messages,
-                BackendMessage::ErrorResponse(Some("ERROR"), Some("22023"), Some("Wrong parameter value".to_owned()))
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22023"),
+                    Some("Wrong parameter value".to_owned()),
+                    ErrorDetails::default()
+                )
             )
         }
 
@@ -718,6 +1682,7 @@ mod tests {
                     Some("ERROR"),
                     Some("26000"),
                     Some("prepared statement statement_name does not exist".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -731,6 +1696,78 @@ mod tests {
                     Some("ERROR"),
                     Some("26000"),
                     Some("portal portal_name does not exist".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn duplicate_cursor() {
+            let message: BackendMessage = QueryError::duplicate_cursor("cursor_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P03"),
+                    Some("cursor \"cursor_name\" already exists".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn query_canceled() {
+            let message: BackendMessage = QueryError::query_canceled().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("57014"),
+                    Some("canceling statement due to user request".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn copy_from_stdin_failed() {
+            let message: BackendMessage = QueryError::copy_from_stdin_failed("aborted by user").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("57014"),
+                    Some("COPY from stdin failed: aborted by user".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn bad_copy_file_format() {
+            let message: BackendMessage =
+                QueryError::bad_copy_file_format("extra data after last expected column").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22P04"),
+                    Some("extra data after last expected column".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_cursor_name() {
+            let message: BackendMessage = QueryError::invalid_cursor_name("cursor_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("34000"),
+                    Some("cursor \"cursor_name\" does not exist".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -740,7 +1777,12 @@ mod tests {
             let messages: BackendMessage = QueryError::protocol_violation("Wrong protocol data").into();
             assert_eq!(
                 messages,
-                BackendMessage::ErrorResponse(Some("ERROR"), Some("08P01"), Some("Wrong protocol data".to_owned()))
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("08P01"),
+                    Some("Wrong protocol data".to_owned()),
+                    ErrorDetails::default()
+                )
             )
         }
 
@@ -754,87 +1796,720 @@ mod tests {
                     Some("ERROR"),
                     Some("0A000"),
                     Some(format!("Currently, Query '{}' can't be executed", raw_sql_query)),
+                    ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn too_many_insert_expressions() {
-            let message: BackendMessage = QueryError::too_many_insert_expressions().into();
+        fn permission_denied_for_schema() {
+            let message: BackendMessage = QueryError::permission_denied_for_schema("schema_name").into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42601"),
-                    Some("INSERT has more expressions than target columns".to_owned()),
+                    Some("42501"),
+                    Some("permission denied for schema schema_name".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn out_of_range_constraint_violation() {
-            let message: BackendMessage =
-                QueryError::out_of_range(PostgreSqlType::SmallInt, "col1".to_string(), 1).into();
+        fn must_be_owner_of_schema() {
+            let message: BackendMessage = QueryError::must_be_owner_of_schema("schema_name").into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("22003"),
-                    Some("smallint is out of range for column 'col1' at row 1".to_owned()),
+                    Some("42501"),
+                    Some("must be owner of schema schema_name".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn type_mismatch_constraint_violation() {
-            let message: BackendMessage = QueryError::type_mismatch("abc", PostgreSqlType::SmallInt, "col1", 1).into();
+        fn unique_violation() {
+            let message: BackendMessage = QueryError::unique_violation("table_name_pkey").into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("2200G"),
-                    Some("invalid input syntax for type smallint for column 'col1' at row 1: \"abc\"".to_owned()),
+                    Some("23505"),
+                    Some("duplicate key value violates unique constraint \"table_name_pkey\"".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn string_length_mismatch_constraint_violation() {
-            let message: BackendMessage =
-                QueryError::string_length_mismatch(PostgreSqlType::Char, 5, "col1".to_string(), 1).into();
+        fn not_null_violation() {
+            let message: BackendMessage = QueryError::not_null_violation("column_name").into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("22026"),
-                    Some("value too long for type character(5) for column 'col1' at row 1".to_owned()),
+                    Some("23502"),
+                    Some("null value in column \"column_name\" violates not-null constraint".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn undefined_function() {
+        fn foreign_key_violation() {
+            let message: BackendMessage = QueryError::foreign_key_violation("orders", "orders_customer_id_fkey").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23503"),
+                    Some(
+                        "insert or update on table \"orders\" violates foreign key constraint \"orders_customer_id_fkey\""
+                            .to_owned()
+                    ), ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn referenced_row_violation() {
             let message: BackendMessage =
-                QueryError::undefined_function("||".to_owned(), "NUMBER".to_owned(), "NUMBER".to_owned()).into();
+                QueryError::referenced_row_violation("customers", "orders_customer_id_fkey", "orders").into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42883"),
-                    Some("operator does not exist: (NUMBER || NUMBER)".to_owned()),
+                    Some("23503"),
+                    Some(
+                        "update or delete on table \"customers\" violates foreign key constraint \"orders_customer_id_fkey\" on table \"orders\""
+                            .to_owned()
+                    ), ErrorDetails::default()
                 )
             )
         }
 
         #[test]
-        fn syntax_error() {
-            let messages: BackendMessage = QueryError::syntax_error("expression".to_owned()).into();
+        fn invalid_foreign_key() {
+            let message: BackendMessage = QueryError::invalid_foreign_key("customers").into();
             assert_eq!(
-                messages,
+                message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42601"),
-                    Some("syntax error in expression".to_owned()),
+                    Some("42830"),
+                    Some(
+                        "there is no unique constraint matching given keys for referenced table \"customers\""
+                            .to_owned()
+                    ),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn multiple_primary_keys() {
+            let message: BackendMessage = QueryError::multiple_primary_keys("table_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P16"),
+                    Some("multiple primary keys for table \"table_name\" are not allowed".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn too_many_insert_expressions() {
+            let message: BackendMessage = QueryError::too_many_insert_expressions().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42601"),
+                    Some("INSERT has more expressions than target columns".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn out_of_range_constraint_violation() {
+            let message: BackendMessage =
+                QueryError::out_of_range(PostgreSqlType::SmallInt, "col1".to_string(), 1).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22003"),
+                    Some("smallint is out of range for column 'col1' at row 1".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn type_mismatch_constraint_violation() {
+            let message: BackendMessage = QueryError::type_mismatch("abc", PostgreSqlType::SmallInt, "col1", 1).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2200G"),
+                    Some("invalid input syntax for type smallint for column 'col1' at row 1: \"abc\"".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn string_length_mismatch_constraint_violation() {
+            let message: BackendMessage =
+                QueryError::string_length_mismatch(PostgreSqlType::Char, 5, "col1".to_string(), 1).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22026"),
+                    Some("value too long for type character(5) for column 'col1' at row 1".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn undefined_function() {
+            let message: BackendMessage =
+                QueryError::undefined_function("||".to_owned(), "NUMBER".to_owned(), "NUMBER".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42883"),
+                    Some("operator does not exist: (NUMBER || NUMBER)".to_owned()),
+                    ErrorDetails {
+                        hint: Some(
+                            "No operator matches the given name and argument types. You might need to add explicit type casts."
+                                .to_owned()
+                        ),
+                        ..ErrorDetails::default()
+                    }
+                )
+            )
+        }
+
+        #[test]
+        fn error_with_details() {
+            let message: BackendMessage = QueryError::syntax_error("select")
+                .with_detail("statement is not complete")
+                .with_position(7)
+                .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42601"),
+                    Some("syntax error in select".to_owned()),
+                    ErrorDetails {
+                        detail: Some("statement is not complete".to_owned()),
+                        hint: None,
+                        position: Some(7),
+                    }
+                )
+            )
+        }
+
+        #[test]
+        fn function_does_not_exist() {
+            let message: BackendMessage = QueryError::function_does_not_exist("foo()").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42883"),
+                    Some("function foo() does not exist".to_owned()),
+                    ErrorDetails {
+                        hint: Some(
+                            "No function matches the given name and argument types. You might need to add explicit type casts."
+                                .to_owned()
+                        ),
+                        ..ErrorDetails::default()
+                    }
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_text_representation() {
+            let message: BackendMessage =
+                QueryError::invalid_text_representation("invalid hexadecimal digit: \"z\"").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22P02"),
+                    Some("invalid hexadecimal digit: \"z\"".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_datetime_format() {
+            let message: BackendMessage =
+                QueryError::invalid_datetime_format("invalid input syntax for type interval: \"soon\"").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22007"),
+                    Some("invalid input syntax for type interval: \"soon\"".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn datetime_value_out_of_range() {
+            let message: BackendMessage = QueryError::datetime_value_out_of_range("interval out of range").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22008"),
+                    Some("interval out of range".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn large_object_does_not_exist() {
+            let message: BackendMessage = QueryError::large_object_does_not_exist(16384).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42704"),
+                    Some("large object 16384 does not exist".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn sequence_already_exists() {
+            let message: BackendMessage = QueryError::sequence_already_exists("schema_name.sequence_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P07"),
+                    Some("relation \"schema_name.sequence_name\" already exists".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn type_already_exists() {
+            let message: BackendMessage = QueryError::type_already_exists("schema_name.mood").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42710"),
+                    Some("type \"schema_name.mood\" already exists".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn function_already_exists() {
+            let message: BackendMessage = QueryError::function_already_exists("schema_name.add").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42723"),
+                    Some("function \"schema_name.add\" already exists".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_function_definition() {
+            let message: BackendMessage =
+                QueryError::invalid_function_definition("return type mismatch in function declared to return integer")
+                    .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P13"),
+                    Some("return type mismatch in function declared to return integer".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn stack_depth_limit_exceeded() {
+            let message: BackendMessage = QueryError::stack_depth_limit_exceeded().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("54001"),
+                    Some("stack depth limit exceeded".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn sequence_does_not_exist() {
+            let message: BackendMessage = QueryError::sequence_does_not_exist("schema_name.sequence_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P01"),
+                    Some("relation \"schema_name.sequence_name\" does not exist".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn sequence_limit_exceeded() {
+            let message: BackendMessage =
+                QueryError::sequence_limit_exceeded("nextval: reached maximum value of sequence \"s\" (3)").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2200H"),
+                    Some("nextval: reached maximum value of sequence \"s\" (3)".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn dependent_objects_still_exist() {
+            let message: BackendMessage =
+                QueryError::dependent_objects_still_exist("sequence table_name_id_seq").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2BP01"),
+                    Some("cannot drop sequence table_name_id_seq because other objects depend on it".to_owned()),
+                    ErrorDetails {
+                        hint: Some(CASCADE_HINT.to_owned()),
+                        ..ErrorDetails::default()
+                    }
+                )
+            )
+        }
+
+        #[test]
+        fn object_not_in_prerequisite_state() {
+            let message: BackendMessage = QueryError::object_not_in_prerequisite_state(
+                "currval of sequence \"s\" is not yet defined in this session",
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("55000"),
+                    Some("currval of sequence \"s\" is not yet defined in this session".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn windowing_error() {
+            let message: BackendMessage =
+                QueryError::windowing_error("frame start cannot be UNBOUNDED FOLLOWING").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P20"),
+                    Some("frame start cannot be UNBOUNDED FOLLOWING".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn grouping_error() {
+            let message: BackendMessage = QueryError::grouping_error(
+                "column \"t.id\" must appear in the GROUP BY clause or be used in an aggregate function",
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42803"),
+                    Some(
+                        "column \"t.id\" must appear in the GROUP BY clause or be used in an aggregate function"
+                            .to_owned()
+                    ),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn generated_always() {
+            let message: BackendMessage =
+                QueryError::generated_always("cannot insert a non-DEFAULT value into column \"total\"").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("428C9"),
+                    Some("cannot insert a non-DEFAULT value into column \"total\"".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn division_by_zero() {
+            let message: BackendMessage = QueryError::division_by_zero().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22012"),
+                    Some("division by zero".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn check_violation() {
+            let message: BackendMessage =
+                QueryError::check_violation("no partition of relation \"t\" found for row").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23514"),
+                    Some("no partition of relation \"t\" found for row".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn datatype_mismatch() {
+            let message: BackendMessage =
+                QueryError::datatype_mismatch("ARRAY types integer and text cannot be matched").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42804"),
+                    Some("ARRAY types integer and text cannot be matched".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn numeric_value_out_of_range() {
+            let message: BackendMessage =
+                QueryError::numeric_value_out_of_range("setseed parameter 2 is out of allowed range [-1,1]").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22003"),
+                    Some("setseed parameter 2 is out of allowed range [-1,1]".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn substring_error() {
+            let message: BackendMessage = QueryError::substring_error("negative substring length not allowed").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22011"),
+                    Some("negative substring length not allowed".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_regular_expression() {
+            let message: BackendMessage = QueryError::invalid_regular_expression("unclosed group").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2201B"),
+                    Some("invalid regular expression: unclosed group".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_argument_for_logarithm() {
+            let message: BackendMessage =
+                QueryError::invalid_argument_for_logarithm("cannot take logarithm of zero").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2201E"),
+                    Some("cannot take logarithm of zero".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_argument_for_power_function() {
+            let message: BackendMessage =
+                QueryError::invalid_argument_for_power_function("zero raised to a negative power is undefined").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2201F"),
+                    Some("zero raised to a negative power is undefined".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn syntax_error() {
+            let messages: BackendMessage = QueryError::syntax_error("expression".to_owned()).into();
+            assert_eq!(
+                messages,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42601"),
+                    Some("syntax error in expression".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn savepoint_does_not_exist() {
+            let message: BackendMessage = QueryError::savepoint_does_not_exist("savepoint_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("3B001"),
+                    Some("savepoint \"savepoint_name\" does not exist".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn in_failed_sql_transaction() {
+            let message: BackendMessage = QueryError::in_failed_sql_transaction().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("25P02"),
+                    Some("current transaction is aborted, commands ignored until end of transaction block".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn transaction_block_not_allowed() {
+            let message: BackendMessage = QueryError::transaction_block_not_allowed("BACKUP").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("25001"),
+                    Some("BACKUP cannot run inside a transaction block".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn io_error() {
+            let message: BackendMessage = QueryError::io_error("No such file or directory").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("58030"),
+                    Some("No such file or directory".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn replication_slot_already_exists() {
+            let message: BackendMessage = QueryError::replication_slot_already_exists("slot_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42710"),
+                    Some("replication slot \"slot_name\" already exists".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn replication_slot_does_not_exist() {
+            let message: BackendMessage = QueryError::replication_slot_does_not_exist("slot_name").into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42704"),
+                    Some("replication slot \"slot_name\" does not exist".to_owned()),
+                    ErrorDetails::default()
+                )
+            )
+        }
+
+        #[test]
+        fn deadlock_detected() {
+            let message: BackendMessage = QueryError::deadlock_detected().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("40P01"),
+                    Some("deadlock detected".to_owned()),
+                    ErrorDetails::default()
                 )
             )
         }
@@ -12,13 +12,88 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
+    clock::{Clock, SystemClock},
     pgsql_types::PostgreSqlFormat,
+    random::Random,
+    results::Projection,
     statement::{Portal, PreparedStatement},
 };
 
+/// Session state that is read and changed by SQL functions,
+/// e.g. `now()` reads the clock and `setseed()` restarts random sequence
+#[derive(Clone, Debug)]
+pub struct FunctionContext {
+    clock: Arc<dyn Clock>,
+    random: Arc<Mutex<Random>>,
+    sequence_values: Arc<Mutex<HashMap<String, i64>>>,
+    time_zone: Option<i32>,
+}
+
+impl Default for FunctionContext {
+    fn default() -> FunctionContext {
+        FunctionContext {
+            clock: Arc::new(SystemClock),
+            random: Arc::new(Mutex::new(Random::from_system_time())),
+            sequence_values: Arc::default(),
+            time_zone: None,
+        }
+    }
+}
+
+impl FunctionContext {
+    /// Source of the current time for the session
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// offset of the session time zone from UTC in seconds, the clock one unless `SET TIME ZONE` changed it
+    pub fn utc_offset(&self) -> i32 {
+        self.time_zone.unwrap_or_else(|| self.clock.utc_offset())
+    }
+
+    /// Next value of the session random sequence
+    pub fn random(&self) -> f64 {
+        self.random.lock().expect("to acquire random lock").next_f64()
+    }
+
+    /// Restarts the session random sequence
+    pub fn set_seed(&self, seed: f64) {
+        self.random.lock().expect("to acquire random lock").set_seed(seed)
+    }
+
+    /// Value most recently returned by `nextval` of the sequence in the session
+    pub fn last_sequence_value(&self, sequence_name: &str) -> Option<i64> {
+        self.sequence_values
+            .lock()
+            .expect("to acquire sequence values lock")
+            .get(sequence_name)
+            .copied()
+    }
+
+    /// Remembers the value returned by `nextval` of the sequence for `currval`
+    pub fn set_last_sequence_value(&self, sequence_name: &str, value: i64) {
+        self.sequence_values
+            .lock()
+            .expect("to acquire sequence values lock")
+            .insert(sequence_name.to_owned(), value);
+    }
+}
+
+/// Limits of SELECT results, rows after a limit is reached are not sent, zero means no limit
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResultLimits {
+    /// Number of rows
+    pub max_rows: usize,
+    /// Total length of values in text format in bytes
+    pub max_size: usize,
+}
+
 /// A `Session` holds SQL state that is attached to a session.
 #[derive(Clone, Debug)]
 pub struct Session<S> {
@@ -26,6 +101,14 @@ pub struct Session<S> {
     prepared_statements: HashMap<String, PreparedStatement<S>>,
     /// A map from statement names to bound statements
     portals: HashMap<String, Portal<S>>,
+    /// A map from cursor names to rows that are left to fetch
+    cursors: HashMap<String, Projection>,
+    /// Session state that SQL functions use
+    function_context: FunctionContext,
+    /// Whether statements report timings of their processing stages
+    trace: bool,
+    /// Limits of SELECT results
+    result_limits: ResultLimits,
 }
 
 impl<S> Default for Session<S> {
@@ -33,6 +116,10 @@ impl<S> Default for Session<S> {
         Session {
             prepared_statements: HashMap::default(),
             portals: HashMap::default(),
+            cursors: HashMap::default(),
+            function_context: FunctionContext::default(),
+            trace: false,
+            result_limits: ResultLimits::default(),
         }
     }
 }
@@ -48,6 +135,13 @@ impl<S> Session<S> {
         self.prepared_statements.insert(name, statement);
     }
 
+    /// remove `PreparedStatement` by its name together with portals bound to it
+    pub fn remove_prepared_statement(&mut self, name: &str) {
+        self.prepared_statements.remove(name);
+        self.portals
+            .retain(|_portal_name, portal| portal.statement_name() != name);
+    }
+
     /// get `Portal` by its name
     pub fn get_portal(&self, name: &str) -> Option<&Portal<S>> {
         self.portals.get(name)
@@ -64,4 +158,88 @@ impl<S> Session<S> {
         let new_portal = Portal::new(statement_name, stmt, result_formats);
         self.portals.insert(portal_name, new_portal);
     }
+
+    /// keep rows that are left to return from the `Portal` after it was executed
+    pub fn suspend_portal(&mut self, name: &str, remaining: Projection) {
+        if let Some(portal) = self.portals.get_mut(name) {
+            portal.suspend(remaining);
+        }
+    }
+
+    /// take rows that are left to return from the executed `Portal`
+    pub fn take_portal_rows(&mut self, name: &str) -> Option<Projection> {
+        self.portals.get_mut(name).and_then(|portal| portal.take_remaining())
+    }
+
+    /// remove `Portal` by its name
+    pub fn remove_portal(&mut self, name: &str) {
+        self.portals.remove(name);
+    }
+
+    /// save rows of a cursor declared with `DECLARE <name> CURSOR FOR <query>`, `false` if the cursor already exists
+    pub fn declare_cursor(&mut self, name: String, rows: Projection) -> bool {
+        if self.cursors.contains_key(&name) {
+            return false;
+        }
+        self.cursors.insert(name, rows);
+        true
+    }
+
+    /// take at most `count` rows of a cursor, all rows that are left when `count` is `None`
+    pub fn fetch_from_cursor(&mut self, name: &str, count: Option<usize>) -> Option<Projection> {
+        self.cursors.get_mut(name).map(|(description, rows)| {
+            let count = count.unwrap_or(rows.len()).min(rows.len());
+            (description.clone(), rows.drain(..count).collect())
+        })
+    }
+
+    /// remove a cursor by its name, `false` if the cursor does not exist
+    pub fn close_cursor(&mut self, name: &str) -> bool {
+        self.cursors.remove(name).is_some()
+    }
+
+    /// remove all cursors of the session
+    pub fn close_all_cursors(&mut self) {
+        self.cursors.clear();
+    }
+
+    /// get session state that SQL functions use
+    pub fn function_context(&self) -> FunctionContext {
+        self.function_context.clone()
+    }
+
+    /// replace session clock, e.g. with a frozen one in tests
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.function_context.clock = clock;
+    }
+
+    /// restart session random sequence from a seed, e.g. to make tests reproducible
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.function_context.random = Arc::new(Mutex::new(Random::new(seed)));
+    }
+
+    /// `None` resets the session time zone to the one of its clock
+    pub fn set_time_zone(&mut self, utc_offset: Option<i32>) {
+        self.function_context.time_zone = utc_offset;
+    }
+
+    /// statements report timings of their processing stages when trace mode is on
+    pub fn trace(&self) -> bool {
+        self.trace
+    }
+
+    /// turn trace mode on or off, e.g. with `SET trace = on`
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    /// limits of SELECT results set with `SET max_result_rows` and `SET max_result_size`
+    pub fn result_limits(&self) -> ResultLimits {
+        self.result_limits
+    }
+
+    /// replace limits of SELECT results
+    pub fn set_result_limits(&mut self, result_limits: ResultLimits) {
+        self.result_limits = result_limits;
+    }
 }
@@ -33,10 +33,12 @@
 //!    statement, there are no longer any free variables permitted.
 //! 4. The client issues an `Execute` message with the name of a portal, causing
 //!    that portal to actually start scanning and returning results.
+//! 5. When `Execute` limits the number of rows the portal is suspended, rows
+//!    that are left are returned by next `Execute` messages of the portal.
 
 use crate::{
     pgsql_types::{PostgreSqlFormat, PostgreSqlType},
-    results::Description,
+    results::{ColumnOrigins, Description, Projection},
 };
 
 /// A prepared statement.
@@ -48,15 +50,23 @@ pub struct PreparedStatement<S> {
     param_types: Vec<PostgreSqlType>,
     /// The type of the rows that will be returned.
     description: Description,
+    /// The table columns that returned columns are read from.
+    origins: ColumnOrigins,
 }
 
 impl<S> PreparedStatement<S> {
     /// Constructs a new `PreparedStatement`.
-    pub fn new(stmt: S, param_types: Vec<PostgreSqlType>, description: Description) -> PreparedStatement<S> {
+    pub fn new(
+        stmt: S,
+        param_types: Vec<PostgreSqlType>,
+        description: Description,
+        origins: ColumnOrigins,
+    ) -> PreparedStatement<S> {
         PreparedStatement {
             stmt,
             param_types,
             description,
+            origins,
         }
     }
 
@@ -74,6 +84,11 @@ impl<S> PreparedStatement<S> {
     pub fn description(&self) -> &[(String, PostgreSqlType)] {
         self.description.as_ref()
     }
+
+    /// Returns the table columns that returned columns are read from.
+    pub fn origins(&self) -> &[Option<(u32, i16)>] {
+        self.origins.as_ref()
+    }
 }
 
 /// A portal represents the execution state of a running or runnable query.
@@ -85,6 +100,8 @@ pub struct Portal<S> {
     stmt: S,
     /// The desired output format for each column in the result set.
     result_formats: Vec<PostgreSqlFormat>,
+    /// Rows that are left to return after the portal was executed.
+    remaining: Option<Projection>,
 }
 
 impl<S> Portal<S> {
@@ -94,11 +111,32 @@ impl<S> Portal<S> {
             statement_name,
             stmt,
             result_formats,
+            remaining: None,
         }
     }
 
+    /// Returns the name of the prepared statement that is bound to this portal.
+    pub fn statement_name(&self) -> &str {
+        self.statement_name.as_str()
+    }
+
     /// Returns the bound SQL statement.
     pub fn stmt(&self) -> &S {
         &self.stmt
     }
+
+    /// Returns the desired output format for each column in the result set.
+    pub fn result_formats(&self) -> &[PostgreSqlFormat] {
+        self.result_formats.as_ref()
+    }
+
+    /// Keeps rows that are left to return after the portal was executed.
+    pub(crate) fn suspend(&mut self, remaining: Projection) {
+        self.remaining = Some(remaining);
+    }
+
+    /// Takes rows that are left to return, `None` if the portal was not executed.
+    pub(crate) fn take_remaining(&mut self) -> Option<Projection> {
+        self.remaining.take()
+    }
 }
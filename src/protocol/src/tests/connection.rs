@@ -35,6 +35,25 @@ mod read_query {
         });
     }
 
+    #[test]
+    fn read_extended_query_commands() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![&[68], &[0, 0, 0, 10], b"Pname\0", &[83], &[0, 0, 0, 4]]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case)));
+            let mut receiver = RequestReceiver::new((VERSION_3, vec![]), channel);
+
+            let describe = receiver.receive().await.expect("no io errors");
+            assert_eq!(
+                describe,
+                Ok(Command::DescribePortal {
+                    name: "name".to_owned()
+                })
+            );
+            let sync = receiver.receive().await.expect("no io errors");
+            assert_eq!(sync, Ok(Command::Sync));
+        });
+    }
+
     #[test]
     fn read_query_successfully() {
         block_on(async {
@@ -22,14 +22,16 @@ use futures_lite::future::block_on;
 
 use crate::{
     hand_shake,
-    messages::{BackendMessage, Encryption},
+    messages::{BackendMessage, Encryption, ErrorDetails, TransactionStatus},
     tests::{
         async_io::{empty_file_named, TestCase},
         certificate_content, pg_frontend,
     },
-    ProtocolConfiguration,
+    ClientRequest, Error, ProcessId, ProtocolConfiguration, SecretKey, SERVER_VERSION,
 };
 
+const KEY_DATA: (ProcessId, SecretKey) = (1, 2);
+
 fn path_to_temp_certificate() -> PathBuf {
     let named_temp_file = empty_file_named();
     let mut file = named_temp_file.reopen().expect("file with content");
@@ -38,6 +40,16 @@ fn path_to_temp_certificate() -> PathBuf {
     named_temp_file.path().to_path_buf()
 }
 
+fn parameter_statuses() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("application_name", "psql"),
+        ("client_encoding", "UTF8"),
+        ("DateStyle", "ISO, MDY"),
+        ("integer_datetimes", "on"),
+        ("server_version", SERVER_VERSION),
+    ]
+}
+
 #[test]
 fn trying_read_from_empty_stream() {
     block_on(async {
@@ -49,6 +61,7 @@ fn trying_read_from_empty_stream() {
             test_case,
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -67,6 +80,7 @@ fn trying_read_only_length_of_ssl_message() {
             test_case,
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -85,6 +99,7 @@ fn sending_reject_notification_for_none_secure() {
             test_case.clone(),
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -108,6 +123,7 @@ fn sending_accept_notification_for_ssl_only_secure() {
             test_case.clone(),
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -143,6 +159,7 @@ fn successful_connection_handshake_for_none_secure() {
             test_case.clone(),
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -153,22 +170,19 @@ fn successful_connection_handshake_for_none_secure() {
         expected_content.extend_from_slice(Encryption::RejectSsl.into());
         expected_content.extend_from_slice(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice());
         expected_content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+        for (name, value) in parameter_statuses() {
+            expected_content.extend_from_slice(
+                BackendMessage::ParameterStatus(name.to_owned(), value.to_owned())
+                    .as_vec()
+                    .as_slice(),
+            );
+        }
+        expected_content.extend_from_slice(BackendMessage::BackendKeyData(1, 2).as_vec().as_slice());
         expected_content.extend_from_slice(
-            BackendMessage::ParameterStatus("client_encoding".to_owned(), "UTF8".to_owned())
-                .as_vec()
-                .as_slice(),
-        );
-        expected_content.extend_from_slice(
-            BackendMessage::ParameterStatus("DateStyle".to_owned(), "ISO".to_owned())
-                .as_vec()
-                .as_slice(),
-        );
-        expected_content.extend_from_slice(
-            BackendMessage::ParameterStatus("integer_datetimes".to_owned(), "off".to_owned())
+            BackendMessage::ReadyForQuery(TransactionStatus::Idle)
                 .as_vec()
                 .as_slice(),
         );
-        expected_content.extend_from_slice(BackendMessage::ReadyForQuery.as_vec().as_slice());
         assert_eq!(actual_content, expected_content);
     });
 }
@@ -196,6 +210,7 @@ fn successful_connection_handshake_for_ssl_only_secure() {
             test_case.clone(),
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
             &config,
+            KEY_DATA,
         )
         .await;
 
@@ -206,16 +221,124 @@ fn successful_connection_handshake_for_ssl_only_secure() {
         expected_content.extend_from_slice(Encryption::AcceptSsl.into());
         expected_content.extend_from_slice(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice());
         expected_content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+        for (name, value) in parameter_statuses() {
+            expected_content.extend_from_slice(
+                BackendMessage::ParameterStatus(name.to_owned(), value.to_owned())
+                    .as_vec()
+                    .as_slice(),
+            );
+        }
+        assert_eq!(actual_content, expected_content);
+    });
+}
+
+#[test]
+fn startup_parameters_with_empty_values() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![
+            pg_frontend::Message::SslRequired.as_vec().as_slice(),
+            pg_frontend::Message::Setup(vec![
+                ("user", "username"),
+                ("options", ""),
+                ("application_name", "psql"),
+                ("client_encoding", "utf-8"),
+            ])
+            .as_vec()
+            .as_slice(),
+            pg_frontend::Message::Password("123").as_vec().as_slice(),
+            &[],
+        ]);
+
+        let config = ProtocolConfiguration::none();
+
+        let result = hand_shake(
+            test_case.clone(),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
+            &config,
+            KEY_DATA,
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+
+        let actual_content = test_case.read_result().await;
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(Encryption::RejectSsl.into());
+        expected_content.extend_from_slice(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice());
+        expected_content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+        for (name, value) in parameter_statuses() {
+            expected_content.extend_from_slice(
+                BackendMessage::ParameterStatus(name.to_owned(), value.to_owned())
+                    .as_vec()
+                    .as_slice(),
+            );
+        }
+        expected_content.extend_from_slice(BackendMessage::BackendKeyData(1, 2).as_vec().as_slice());
         expected_content.extend_from_slice(
-            BackendMessage::ParameterStatus("client_encoding".to_owned(), "UTF8".to_owned())
+            BackendMessage::ReadyForQuery(TransactionStatus::Idle)
                 .as_vec()
                 .as_slice(),
         );
-        expected_content.extend_from_slice(
-            BackendMessage::ParameterStatus("DateStyle".to_owned(), "ISO".to_owned())
+        assert_eq!(actual_content, expected_content);
+    });
+}
+
+#[test]
+fn unsupported_client_encoding() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![
+            pg_frontend::Message::SslRequired.as_vec().as_slice(),
+            pg_frontend::Message::Setup(vec![("user", "username"), ("client_encoding", "LATIN1")])
                 .as_vec()
                 .as_slice(),
+            &[],
+        ]);
+
+        let config = ProtocolConfiguration::none();
+
+        let result = hand_shake(
+            test_case.clone(),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
+            &config,
+            KEY_DATA,
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Err(Error::InvalidInput(_)))));
+
+        let actual_content = test_case.read_result().await;
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(Encryption::RejectSsl.into());
+        expected_content.extend_from_slice(
+            BackendMessage::ErrorResponse(
+                Some("ERROR"),
+                Some("22023"),
+                Some("invalid value for parameter \"client_encoding\": \"LATIN1\"".to_owned()),
+                ErrorDetails::default(),
+            )
+            .as_vec()
+            .as_slice(),
         );
         assert_eq!(actual_content, expected_content);
     });
 }
+
+#[test]
+fn cancel_request() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![&[0, 0, 0, 16], &[4, 210, 22, 46, 0, 0, 0, 7, 0, 0, 1, 0]]);
+
+        let config = ProtocolConfiguration::none();
+
+        let result = hand_shake(
+            test_case.clone(),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)),
+            &config,
+            KEY_DATA,
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Ok(ClientRequest::QueryCancellation(7, 256)))));
+        assert_eq!(test_case.read_result().await, Vec::<u8>::new());
+    });
+}
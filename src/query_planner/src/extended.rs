@@ -0,0 +1,1408 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Statements that are not (yet) supported by `sqlparser` and parsed on top of its tokenizer.
+use crate::plan::CopyFormat;
+use data_manager::{SchemaPrivilege, SequenceOptions, StatisticsKind};
+use sqlparser::{
+    ast::{DataType, Expr, Ident, ObjectName, Query, SetVariableValue, Statement, Value},
+    dialect::{keywords::Keyword, Dialect},
+    parser::{IsOptional, Parser, ParserError},
+    tokenizer::{Token, Tokenizer},
+};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ExtendedStatement {
+    /// ALTER SCHEMA <schema_name> OWNER TO <role>
+    AlterSchemaOwner { schema_name: ObjectName, new_owner: Ident },
+    /// GRANT <privileges> ON SCHEMA <schema_names> TO <roles>
+    GrantOnSchema {
+        privileges: Vec<SchemaPrivilege>,
+        schema_names: Vec<ObjectName>,
+        roles: Vec<Ident>,
+    },
+    /// REVOKE <privileges> ON SCHEMA <schema_names> FROM <roles>
+    RevokeOnSchema {
+        privileges: Vec<SchemaPrivilege>,
+        schema_names: Vec<ObjectName>,
+        roles: Vec<Ident>,
+    },
+    /// CREATE SCHEMA <schema_name> LIKE <template_name> [WITH [NO] DATA]
+    CreateSchemaLike {
+        schema_name: ObjectName,
+        template_name: ObjectName,
+        with_data: bool,
+    },
+    /// SET ROLE <role>
+    SetRole(Ident),
+    /// RESET ROLE
+    ResetRole,
+    /// CREATE SEQUENCE [IF NOT EXISTS] <sequence_name> [INCREMENT [BY] n] [MINVALUE n] [MAXVALUE n] [START [WITH] n]
+    CreateSequence {
+        sequence_name: ObjectName,
+        if_not_exists: bool,
+        options: SequenceOptions,
+    },
+    /// CREATE TYPE <type_name> AS ENUM ([<label>, ...])
+    CreateEnumType { type_name: ObjectName, labels: Vec<String> },
+    /// CREATE [OR REPLACE] FUNCTION <function_name> ([<name> <type>, ...]) RETURNS <type> AS '<body>' LANGUAGE SQL
+    CreateFunction {
+        function_name: ObjectName,
+        or_replace: bool,
+        parameters: Vec<(Ident, DataType)>,
+        return_type: DataType,
+        body: String,
+    },
+    /// DROP FUNCTION [IF EXISTS] <function_names>
+    DropFunction { names: Vec<ObjectName>, if_exists: bool },
+    /// DROP SEQUENCE [IF EXISTS] <sequence_names>
+    DropSequence { names: Vec<ObjectName>, if_exists: bool },
+    /// CREATE TABLE with `GENERATED ALWAYS AS (<expr>) [STORED]` column options or `PARTITION BY RANGE (<column>)`
+    CreateTable {
+        statement: Box<Statement>,
+        generated_columns: Vec<(Ident, Expr)>,
+        partition_by: Option<Ident>,
+    },
+    /// CREATE TABLE <table_name> PARTITION OF <parent_name> FOR VALUES FROM (<n> | MINVALUE) TO (<n> | MAXVALUE)
+    CreatePartition {
+        table_name: ObjectName,
+        parent_name: ObjectName,
+        from: Option<i64>,
+        to: Option<i64>,
+    },
+    /// CREATE STATISTICS [IF NOT EXISTS] <statistics_name> [(ndistinct | dependencies, ...)] ON <columns> FROM <table_name>
+    CreateStatistics {
+        statistics_name: ObjectName,
+        if_not_exists: bool,
+        kinds: Vec<StatisticsKind>,
+        columns: Vec<Ident>,
+        table_name: ObjectName,
+    },
+    /// DROP STATISTICS [IF EXISTS] <statistics_names>
+    DropStatistics { names: Vec<ObjectName>, if_exists: bool },
+    /// ANALYZE [<table_name>]
+    Analyze(Option<ObjectName>),
+    /// VACUUM [<table_name>]
+    Vacuum(Option<ObjectName>),
+    /// SET TIME ZONE <time_zone> | LOCAL | DEFAULT
+    SetTimeZone(SetVariableValue),
+    /// DECLARE <cursor_name> [NO SCROLL] CURSOR [WITH HOLD | WITHOUT HOLD] FOR <query>
+    DeclareCursor { cursor_name: Ident, query: Box<Query> },
+    /// FETCH [NEXT | [FORWARD] <count> | [FORWARD] ALL] [FROM | IN] <cursor_name>, `None` count stands for ALL
+    Fetch { cursor_name: Ident, count: Option<u64> },
+    /// CLOSE <cursor_name> | ALL
+    CloseCursor(Option<Ident>),
+    /// COPY <table_name> [(<columns>)] FROM STDIN
+    CopyFrom {
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+    },
+    /// COPY <table_name> [(<columns>)] TO STDOUT [[WITH] CSV [HEADER] | [WITH] (FORMAT {TEXT | CSV} [, HEADER [<boolean>]])]
+    CopyTo {
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        format: CopyFormat,
+    },
+    /// LISTEN <channel>
+    Listen(Ident),
+    /// UNLISTEN <channel> | *, `None` channel stands for all channels
+    Unlisten(Option<Ident>),
+    /// NOTIFY <channel> [, '<payload>']
+    Notify { channel: Ident, payload: String },
+    /// SAVEPOINT <savepoint_name>
+    Savepoint(Ident),
+    /// RELEASE [SAVEPOINT] <savepoint_name>
+    ReleaseSavepoint(Ident),
+    /// ROLLBACK [WORK | TRANSACTION] TO [SAVEPOINT] <savepoint_name>
+    RollbackToSavepoint(Ident),
+    /// BACKUP TO '<path>'
+    Backup(String),
+}
+
+/// Returns `Ok(None)` if `sql` is not one of extended statements and has to be parsed by `sqlparser`
+pub fn parse(dialect: &dyn Dialect, sql: &str) -> Result<Option<ExtendedStatement>, ParserError> {
+    let tokens = tokenize(dialect, sql)?;
+    let mut parser = Parser::new(tokens.clone());
+    let statement = match parser.next_token() {
+        Token::Word(word) if word.keyword == Keyword::ALTER => {
+            if !parser.parse_keyword(Keyword::SCHEMA) {
+                return Ok(None);
+            }
+            parse_alter_schema(&mut parser)?
+        }
+        Token::Word(word) if word.keyword == Keyword::CREATE => {
+            if parse_word(&mut parser, "SEQUENCE") {
+                parse_create_sequence(&mut parser)?
+            } else if parse_word(&mut parser, "STATISTICS") {
+                parse_create_statistics(&mut parser)?
+            } else if parse_word(&mut parser, "TYPE") {
+                parse_create_enum_type(&mut parser)?
+            } else if parser.parse_keyword(Keyword::FUNCTION) {
+                parse_create_function(&mut parser, false)?
+            } else if parser.parse_keyword(Keyword::OR) {
+                if !parse_word(&mut parser, "REPLACE") || !parser.parse_keyword(Keyword::FUNCTION) {
+                    return Ok(None);
+                }
+                parse_create_function(&mut parser, true)?
+            } else if parser.parse_keyword(Keyword::SCHEMA) {
+                match parser.parse_object_name() {
+                    Ok(schema_name) if parser.parse_keyword(Keyword::LIKE) => {
+                        parse_create_schema_like(&mut parser, schema_name)?
+                    }
+                    _ => return Ok(None),
+                }
+            } else if parser.parse_keyword(Keyword::TABLE) {
+                match parser.parse_object_name() {
+                    Ok(table_name) if parse_word(&mut parser, "PARTITION") => {
+                        parse_create_partition(&mut parser, table_name)?
+                    }
+                    _ if tokens
+                        .iter()
+                        .any(|token| is_word(token, "GENERATED") || is_word(token, "PARTITION")) =>
+                    {
+                        return parse_create_table(tokens).map(Some);
+                    }
+                    _ => return Ok(None),
+                }
+            } else {
+                return Ok(None);
+            }
+        }
+        Token::Word(word) if word.keyword == Keyword::DROP => {
+            if parse_word(&mut parser, "SEQUENCE") {
+                let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                ExtendedStatement::DropSequence {
+                    names: parser.parse_comma_separated(Parser::parse_object_name)?,
+                    if_exists,
+                }
+            } else if parse_word(&mut parser, "STATISTICS") {
+                let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                ExtendedStatement::DropStatistics {
+                    names: parser.parse_comma_separated(Parser::parse_object_name)?,
+                    if_exists,
+                }
+            } else if parser.parse_keyword(Keyword::FUNCTION) {
+                let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                ExtendedStatement::DropFunction {
+                    names: parser.parse_comma_separated(parse_function_name)?,
+                    if_exists,
+                }
+            } else {
+                return Ok(None);
+            }
+        }
+        Token::Word(word) if word.keyword == Keyword::GRANT => {
+            let (privileges, schema_names) = parse_schema_privileges(&mut parser)?;
+            parser.expect_keyword(Keyword::TO)?;
+            ExtendedStatement::GrantOnSchema {
+                privileges,
+                schema_names,
+                roles: parser.parse_comma_separated(Parser::parse_identifier)?,
+            }
+        }
+        Token::Word(word) if word.keyword == Keyword::REVOKE => {
+            let (privileges, schema_names) = parse_schema_privileges(&mut parser)?;
+            parser.expect_keyword(Keyword::FROM)?;
+            ExtendedStatement::RevokeOnSchema {
+                privileges,
+                schema_names,
+                roles: parser.parse_comma_separated(Parser::parse_identifier)?,
+            }
+        }
+        Token::Word(word) if word.keyword == Keyword::SET => {
+            if parser.parse_keywords(&[Keyword::TIME, Keyword::ZONE]) {
+                ExtendedStatement::SetTimeZone(parse_time_zone(&mut parser)?)
+            } else if parse_word(&mut parser, "ROLE") {
+                if !parser.consume_token(&Token::Eq) {
+                    let _ = parser.parse_keyword(Keyword::TO);
+                }
+                match parser.next_token() {
+                    Token::Word(word) => ExtendedStatement::SetRole(word.to_ident()),
+                    Token::SingleQuotedString(role) => ExtendedStatement::SetRole(Ident::new(role)),
+                    unexpected => return expected("role name", unexpected),
+                }
+            } else {
+                return Ok(None);
+            }
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("RESET") => {
+            if !parse_word(&mut parser, "ROLE") {
+                return Ok(None);
+            }
+            ExtendedStatement::ResetRole
+        }
+        Token::Word(word) if word.keyword == Keyword::DECLARE => parse_declare_cursor(&mut parser)?,
+        Token::Word(word) if word.keyword == Keyword::FETCH => parse_fetch(&mut parser)?,
+        Token::Word(word) if word.keyword == Keyword::COPY => parse_copy(&mut parser)?,
+        Token::Word(word) if word.keyword == Keyword::CLOSE => {
+            if parser.parse_keyword(Keyword::ALL) {
+                ExtendedStatement::CloseCursor(None)
+            } else {
+                ExtendedStatement::CloseCursor(Some(parser.parse_identifier()?))
+            }
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("LISTEN") => {
+            ExtendedStatement::Listen(parser.parse_identifier()?)
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("UNLISTEN") => {
+            if parser.consume_token(&Token::Mult) {
+                ExtendedStatement::Unlisten(None)
+            } else {
+                ExtendedStatement::Unlisten(Some(parser.parse_identifier()?))
+            }
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("NOTIFY") => {
+            let channel = parser.parse_identifier()?;
+            let payload = if parser.consume_token(&Token::Comma) {
+                match parser.next_token() {
+                    Token::SingleQuotedString(payload) => payload,
+                    unexpected => return expected("notification payload", unexpected),
+                }
+            } else {
+                String::new()
+            };
+            ExtendedStatement::Notify { channel, payload }
+        }
+        Token::Word(word) if word.keyword == Keyword::SAVEPOINT => {
+            ExtendedStatement::Savepoint(parser.parse_identifier()?)
+        }
+        Token::Word(word) if word.keyword == Keyword::RELEASE => {
+            let _ = parser.parse_keyword(Keyword::SAVEPOINT);
+            ExtendedStatement::ReleaseSavepoint(parser.parse_identifier()?)
+        }
+        Token::Word(word) if word.keyword == Keyword::ROLLBACK => {
+            let _ = parser.parse_keyword(Keyword::WORK) || parser.parse_keyword(Keyword::TRANSACTION);
+            if !parser.parse_keyword(Keyword::TO) {
+                return Ok(None);
+            }
+            let _ = parser.parse_keyword(Keyword::SAVEPOINT);
+            ExtendedStatement::RollbackToSavepoint(parser.parse_identifier()?)
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("BACKUP") => {
+            parser.expect_keyword(Keyword::TO)?;
+            match parser.next_token() {
+                Token::SingleQuotedString(path) => ExtendedStatement::Backup(path),
+                unexpected => return expected("backup file path", unexpected),
+            }
+        }
+        Token::Word(word) if word.value.eq_ignore_ascii_case("ANALYZE") => match parser.peek_token() {
+            Token::EOF | Token::SemiColon => ExtendedStatement::Analyze(None),
+            _ => ExtendedStatement::Analyze(Some(parser.parse_object_name()?)),
+        },
+        Token::Word(word) if word.value.eq_ignore_ascii_case("VACUUM") => match parser.peek_token() {
+            Token::EOF | Token::SemiColon => ExtendedStatement::Vacuum(None),
+            _ => ExtendedStatement::Vacuum(Some(parser.parse_object_name()?)),
+        },
+        _ => return Ok(None),
+    };
+    let _ = parser.consume_token(&Token::SemiColon);
+    match parser.peek_token() {
+        Token::EOF => Ok(Some(statement)),
+        unexpected => expected("end of statement", unexpected),
+    }
+}
+
+/// splits a simple query into statements after `;` outside of string literals, quoted identifiers and comments,
+/// statements keep their `;`, they are trimmed and ones that have nothing but whitespaces and comments are skipped
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut empty = true;
+    let mut quote = None;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        match quote {
+            Some(opening) if c == opening => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                empty = false;
+            }
+            None if c == '-' && matches!(chars.peek(), Some((_, '-'))) => {
+                for (_, c) in &mut chars {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            None if c == '/' && matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                while let Some((_, c)) = chars.next() {
+                    if c == '*' && matches!(chars.peek(), Some((_, '/'))) {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            None if c == ';' => {
+                if !empty {
+                    statements.push(sql[start..=position].trim());
+                }
+                start = position + 1;
+                empty = true;
+            }
+            None if !c.is_whitespace() => empty = false,
+            None => {}
+        }
+    }
+    if !empty {
+        statements.push(sql[start..].trim());
+    }
+    statements
+}
+
+/// parses statements with `sqlparser` once `IS [NOT] DISTINCT FROM` and json operators are rewritten into function calls
+pub fn parse_sql(dialect: &dyn Dialect, sql: &str) -> Result<Vec<Statement>, ParserError> {
+    let mut parser = Parser::new(tokenize(dialect, sql)?);
+    let mut statements = vec![];
+    let mut expecting_statement_delimiter = false;
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        match parser.peek_token() {
+            Token::EOF => break,
+            unexpected if expecting_statement_delimiter => return expected("end of statement", unexpected),
+            _ => {}
+        }
+        statements.push(parser.parse_statement()?);
+        expecting_statement_delimiter = true;
+    }
+    Ok(statements)
+}
+
+/// keywords that end an operand of `IS`, operators between them bind tighter than `IS`
+const OPERAND_BOUNDARIES: &[Keyword] = &[
+    Keyword::AND,
+    Keyword::OR,
+    Keyword::NOT,
+    Keyword::IS,
+    Keyword::BETWEEN,
+    Keyword::SELECT,
+    Keyword::FROM,
+    Keyword::WHERE,
+    Keyword::SET,
+    Keyword::VALUES,
+    Keyword::WHEN,
+    Keyword::THEN,
+    Keyword::ELSE,
+    Keyword::AS,
+    Keyword::ON,
+    Keyword::ORDER,
+    Keyword::GROUP,
+    Keyword::BY,
+    Keyword::HAVING,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+    Keyword::FETCH,
+    Keyword::UNION,
+    Keyword::EXCEPT,
+    Keyword::INTERSECT,
+];
+
+fn is_keyword(token: &Token, keyword: Keyword) -> bool {
+    matches!(token, Token::Word(word) if word.keyword == keyword)
+}
+
+fn is_opening(token: &Token) -> bool {
+    *token == Token::LParen || is_keyword(token, Keyword::CASE)
+}
+
+fn is_closing(token: &Token) -> bool {
+    *token == Token::RParen || is_keyword(token, Keyword::END)
+}
+
+fn is_operand_boundary(token: &Token) -> bool {
+    match token {
+        Token::Comma | Token::SemiColon | Token::EOF => true,
+        Token::Word(word) => OPERAND_BOUNDARIES.contains(&word.keyword),
+        _ => false,
+    }
+}
+
+/// `=` of `SET column = ...` in UPDATE statements
+fn is_assignment(tokens: &[Token], position: usize) -> bool {
+    is_keyword(&tokens[0], Keyword::UPDATE)
+        && tokens[position] == Token::Eq
+        && position >= 2
+        && matches!(tokens[position - 1], Token::Word(_))
+        && (is_keyword(&tokens[position - 2], Keyword::SET) || tokens[position - 2] == Token::Comma)
+}
+
+/// position of the first `IS [NOT] DISTINCT FROM`, whether it is negated and its length in tokens
+fn find_distinct_from(tokens: &[Token]) -> Option<(usize, bool, usize)> {
+    (0..tokens.len()).find_map(|position| match &tokens[position..] {
+        [is, distinct, from, ..]
+            if is_keyword(is, Keyword::IS)
+                && is_keyword(distinct, Keyword::DISTINCT)
+                && is_keyword(from, Keyword::FROM) =>
+        {
+            Some((position, false, 3))
+        }
+        [is, not, distinct, from, ..]
+            if is_keyword(is, Keyword::IS)
+                && is_keyword(not, Keyword::NOT)
+                && is_keyword(distinct, Keyword::DISTINCT)
+                && is_keyword(from, Keyword::FROM) =>
+        {
+            Some((position, true, 4))
+        }
+        _ => None,
+    })
+}
+
+/// `sqlparser` tokenizes `->`, `->>`, `#>` and `#>>` as separate characters, returns the function the operator is
+/// rewritten into and its length in tokens
+fn json_operator(tokens: &[Token], position: usize) -> Option<(&'static str, usize)> {
+    match &tokens[position..] {
+        [Token::Minus, Token::Gt, Token::Gt, ..] => Some(("json_extract_text", 3)),
+        [Token::Minus, Token::Gt, ..] => Some(("json_extract", 2)),
+        [Token::Char('#'), Token::Gt, Token::Gt, ..] => Some(("json_extract_path_text", 3)),
+        [Token::Char('#'), Token::Gt, ..] => Some(("json_extract_path", 2)),
+        _ => None,
+    }
+}
+
+/// `~`, `~*`, `!~`, `!~*` and `[NOT] SIMILAR TO` operators, returns the function the operator is rewritten into,
+/// whether its pattern is a `SIMILAR TO` one and its length in tokens
+fn regex_operator(tokens: &[Token], position: usize) -> Option<(&'static str, bool, usize)> {
+    match &tokens[position..] {
+        [Token::Char('!'), Token::Char('~'), Token::Mult, ..] => Some(("texticregexne", false, 3)),
+        [Token::Char('!'), Token::Char('~'), ..] => Some(("textregexne", false, 2)),
+        [Token::Char('~'), Token::Mult, ..] => Some(("texticregexeq", false, 2)),
+        [Token::Char('~'), ..] => Some(("textregexeq", false, 1)),
+        [similar, to, ..] if is_keyword(similar, Keyword::SIMILAR) && is_keyword(to, Keyword::TO) => {
+            Some(("textregexeq", true, 2))
+        }
+        [not, similar, to, ..]
+            if is_keyword(not, Keyword::NOT)
+                && is_keyword(similar, Keyword::SIMILAR)
+                && is_keyword(to, Keyword::TO) =>
+        {
+            Some(("textregexne", true, 3))
+        }
+        _ => None,
+    }
+}
+
+/// operands of `IS [NOT] DISTINCT FROM` span up to keywords that bind looser than `IS`
+fn is_distinct_from_boundary(tokens: &[Token], position: usize) -> bool {
+    is_operand_boundary(&tokens[position]) || is_assignment(tokens, position)
+}
+
+/// json operators bind tighter than comparisons and string concatenation but looser than arithmetic
+fn is_json_operand_boundary(tokens: &[Token], position: usize) -> bool {
+    match &tokens[position] {
+        Token::Eq | Token::Neq | Token::Lt | Token::Gt | Token::LtEq | Token::GtEq | Token::StringConcat => true,
+        _ => {
+            is_distinct_from_boundary(tokens, position)
+                || json_operator(tokens, position).is_some()
+                || regex_operator(tokens, position).is_some()
+        }
+    }
+}
+
+/// regular expression operators bind tighter than comparisons but looser than arithmetic and string concatenation
+fn is_regex_operand_boundary(tokens: &[Token], position: usize) -> bool {
+    match &tokens[position] {
+        Token::Eq | Token::Neq | Token::Lt | Token::Gt | Token::LtEq | Token::GtEq => true,
+        _ => is_distinct_from_boundary(tokens, position) || regex_operator(tokens, position).is_some(),
+    }
+}
+
+fn left_operand_start(tokens: &[Token], end: usize, is_boundary: fn(&[Token], usize) -> bool) -> usize {
+    let mut depth = 0;
+    for position in (0..end).rev() {
+        let token = &tokens[position];
+        if is_closing(token) {
+            depth += 1;
+        } else if depth > 0 && is_opening(token) {
+            depth -= 1;
+        } else if depth == 0 && (is_opening(token) || is_boundary(tokens, position)) {
+            return position + 1;
+        }
+    }
+    0
+}
+
+fn right_operand_end(tokens: &[Token], start: usize, is_boundary: fn(&[Token], usize) -> bool) -> usize {
+    let mut depth = 0;
+    for (position, token) in tokens.iter().enumerate().skip(start) {
+        if is_opening(token) {
+            depth += 1;
+        } else if depth > 0 && is_closing(token) {
+            depth -= 1;
+        } else if depth == 0 && (is_closing(token) || is_boundary(tokens, position)) {
+            return position;
+        }
+    }
+    tokens.len()
+}
+
+/// `sqlparser` does not support `<left> IS [NOT] DISTINCT FROM <right>`, it is rewritten into
+/// `is_distinct_from(<left>, <right>)` or `is_not_distinct_from(<left>, <right>)`
+/// tokens of `sql` where constructs `sqlparser` can't handle are rewritten into ones it can
+fn tokenize(dialect: &dyn Dialect, sql: &str) -> Result<Vec<Token>, ParserError> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    for position in negated_regex_operators(sql) {
+        tokens.extend(Tokenizer::new(dialect, &sql[start..position]).tokenize()?);
+        tokens.push(Token::Char('!'));
+        start = position + 1;
+    }
+    tokens.extend(Tokenizer::new(dialect, &sql[start..]).tokenize()?);
+    let tokens = tokens
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .collect::<Vec<_>>();
+    let tokens = rewrite_special_functions(rewrite_aggregate_filters(tokens)?)?;
+    let tokens = rewrite_array_constructors(rewrite_array_types(tokens))?;
+    let tokens = rewrite_regex_operators(rewrite_json_operators(rewrite_subscripts(tokens)?)?)?;
+    Ok(rewrite_set_transaction(rewrite_timestamp_with_time_zone(
+        rewrite_distinct_from(tokens)?,
+    )))
+}
+
+/// `sqlparser` expects `=` after `!`, positions of `!~` outside of string literals, quoted identifiers and comments
+/// are found to tokenize the parts of a query around them separately
+fn negated_regex_operators(sql: &str) -> Vec<usize> {
+    let mut positions = vec![];
+    let mut quote = None;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        match quote {
+            Some(opening) if c == opening => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '-' && matches!(chars.peek(), Some((_, '-'))) => {
+                for (_, c) in &mut chars {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            None if c == '!' && matches!(chars.peek(), Some((_, '~'))) => positions.push(position),
+            None => {}
+        }
+    }
+    positions
+}
+
+/// position of `)` that closes `(` at `start`
+fn closing_parenthesis(tokens: &[Token], start: usize) -> Result<usize, ParserError> {
+    let mut depth = 0;
+    for (position, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 1 => return Ok(position),
+            Token::RParen => depth -= 1,
+            _ => {}
+        }
+    }
+    expected(")", Token::EOF)
+}
+
+/// arguments split by the `words` that are not nested into parentheses, each part is labeled with the word
+/// it follows
+fn split_by_words<'t>(tokens: &'t [Token], words: &[&'static str]) -> Vec<(Option<&'static str>, &'t [Token])> {
+    let mut parts = vec![];
+    let mut label = None;
+    let mut start = 0;
+    let mut depth = 0;
+    for (position, token) in tokens.iter().enumerate() {
+        if is_opening(token) {
+            depth += 1;
+        } else if is_closing(token) {
+            depth -= 1;
+        } else if depth == 0 {
+            if let Some(word) = words.iter().find(|word| is_word(token, word)) {
+                parts.push((label, &tokens[start..position]));
+                label = Some(*word);
+                start = position + 1;
+            }
+        }
+    }
+    parts.push((label, &tokens[start..]));
+    parts
+}
+
+fn function_call(name: &str, arguments: &[&[Token]]) -> Vec<Token> {
+    let mut call = vec![Token::make_word(name, None), Token::LParen];
+    for (index, argument) in arguments.iter().enumerate() {
+        if index > 0 {
+            call.push(Token::Comma);
+        }
+        call.extend_from_slice(argument);
+    }
+    call.push(Token::RParen);
+    call
+}
+
+/// `sqlparser` does not support keywords between arguments of string and date/time functions, calls are rewritten
+/// into ones with arguments separated by commas:
+/// `substring(<string> FROM <start> FOR <count>)` into `substring(<string>, <start>, <count>)`,
+/// `position(<substring> IN <string>)` into `strpos(<string>, <substring>)`,
+/// `trim([LEADING | TRAILING | BOTH] [<characters>] FROM <string>)` into `ltrim`, `rtrim` or
+/// `btrim(<string>, <characters>)` and
+/// `extract(<field> FROM <timestamp>)` into `date_part('<field>', <timestamp>)`
+fn rewrite_special_functions(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let first_character = [Token::Number("1".to_owned())];
+    let mut position = 0;
+    while position < tokens.len() {
+        let name = match &tokens[position..] {
+            [Token::Word(word), Token::LParen, ..] if word.quote_style.is_none() => word.value.to_lowercase(),
+            _ => {
+                position += 1;
+                continue;
+            }
+        };
+        let end = closing_parenthesis(&tokens, position + 1)?;
+        let arguments = &tokens[position + 2..end];
+        let call = match name.as_str() {
+            _ if position > 0 && tokens[position - 1] == Token::Period => None,
+            "substring" => match split_by_words(arguments, &["from", "for"]).as_slice() {
+                [(None, string), (Some("from"), start)] => Some(function_call("substring", &[*string, *start])),
+                [(None, string), (Some("from"), start), (Some("for"), count)] => {
+                    Some(function_call("substring", &[*string, *start, *count]))
+                }
+                [(None, string), (Some("for"), count)] => {
+                    Some(function_call("substring", &[*string, &first_character, *count]))
+                }
+                [_] => None,
+                _ => return expected("FROM or FOR", Token::RParen),
+            },
+            "position" => match split_by_words(arguments, &["in"]).as_slice() {
+                [(None, substring), (Some("in"), string)] => Some(function_call("strpos", &[*string, *substring])),
+                [_] => None,
+                _ => return expected("IN", Token::RParen),
+            },
+            "trim" => {
+                let (function, has_side, arguments) = match arguments.first() {
+                    Some(side) if is_word(side, "leading") => ("ltrim", true, &arguments[1..]),
+                    Some(side) if is_word(side, "trailing") => ("rtrim", true, &arguments[1..]),
+                    Some(side) if is_word(side, "both") => ("btrim", true, &arguments[1..]),
+                    _ => ("btrim", false, arguments),
+                };
+                match split_by_words(arguments, &["from"]).as_slice() {
+                    [(None, []), (Some("from"), string)] => Some(function_call(function, &[*string])),
+                    [(None, characters), (Some("from"), string)] => {
+                        Some(function_call(function, &[*string, *characters]))
+                    }
+                    [(None, arguments)] if has_side => Some(function_call(function, &[*arguments])),
+                    [_] => None,
+                    _ => return expected("FROM", Token::RParen),
+                }
+            }
+            "extract" => match split_by_words(arguments, &["from"]).as_slice() {
+                [(None, [Token::Word(field)]), (Some("from"), source)] => Some(function_call(
+                    "date_part",
+                    &[&[Token::SingleQuotedString(field.value.to_lowercase())], *source],
+                )),
+                [(None, [Token::SingleQuotedString(field)]), (Some("from"), source)] => Some(function_call(
+                    "date_part",
+                    &[&[Token::SingleQuotedString(field.to_lowercase())], *source],
+                )),
+                _ => return expected("FROM", Token::RParen),
+            },
+            _ => None,
+        };
+        if let Some(call) = call {
+            tokens.splice(position..=end, call);
+        }
+        position += 1;
+    }
+    Ok(tokens)
+}
+
+/// `sqlparser` does not support `FILTER` of aggregate calls, the condition is passed as the last argument:
+/// `<name>(<arguments>) FILTER (WHERE <condition>)` into `<name>(<arguments>, aggregate_filter(<condition>))`
+fn rewrite_aggregate_filters(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut position = 0;
+    while position < tokens.len() {
+        if let [Token::Word(_), Token::LParen, ..] = &tokens[position..] {
+            let end = closing_parenthesis(&tokens, position + 1)?;
+            let is_filtered = match &tokens[end + 1..] {
+                [filter, Token::LParen, condition, ..] => {
+                    is_word(filter, "FILTER") && is_keyword(condition, Keyword::WHERE)
+                }
+                _ => false,
+            };
+            if is_filtered {
+                let filter_end = closing_parenthesis(&tokens, end + 2)?;
+                let mut filter = function_call("aggregate_filter", &[&tokens[end + 4..filter_end]]);
+                if end > position + 2 {
+                    filter.insert(0, Token::Comma);
+                }
+                filter.push(Token::RParen);
+                tokens.splice(end..=filter_end, filter);
+            }
+        }
+        position += 1;
+    }
+    Ok(tokens)
+}
+
+/// `sqlparser` drops time zone of `TIMESTAMP WITH TIME ZONE` type, so it is replaced with `timestamptz`
+fn rewrite_timestamp_with_time_zone(mut tokens: Vec<Token>) -> Vec<Token> {
+    while let Some(position) = (0..tokens.len()).find(|position| match &tokens[*position..] {
+        [timestamp, with, time, zone, ..] => {
+            is_keyword(timestamp, Keyword::TIMESTAMP)
+                && is_keyword(with, Keyword::WITH)
+                && is_keyword(time, Keyword::TIME)
+                && is_keyword(zone, Keyword::ZONE)
+        }
+        _ => false,
+    }) {
+        tokens.splice(position..position + 4, vec![Token::make_word("timestamptz", None)]);
+    }
+    tokens
+}
+
+/// `sqlparser` recognizes `SET TRANSACTION` only when `TRANSACTION` is written in upper case
+fn rewrite_set_transaction(mut tokens: Vec<Token>) -> Vec<Token> {
+    if let [set, transaction @ Token::Word(_), ..] = tokens.as_mut_slice() {
+        if is_keyword(set, Keyword::SET) && is_keyword(transaction, Keyword::TRANSACTION) {
+            *transaction = Token::make_keyword("TRANSACTION");
+        }
+    }
+    tokens
+}
+
+/// `sqlparser` supports only `TEXT[]` array type, element types followed by `[]` are replaced with names of array types
+fn rewrite_array_types(mut tokens: Vec<Token>) -> Vec<Token> {
+    let array_type = |token: &Token| match token {
+        Token::Word(word) => match word.keyword {
+            Keyword::SMALLINT => Some("_int2"),
+            Keyword::INT | Keyword::INTEGER => Some("_int4"),
+            Keyword::BIGINT => Some("_int8"),
+            Keyword::TEXT | Keyword::VARCHAR => Some("_text"),
+            _ => None,
+        },
+        _ => None,
+    };
+    while let Some((position, name)) = (0..tokens.len()).find_map(|position| match &tokens[position..] {
+        [element_type, Token::LBracket, Token::RBracket, ..] => array_type(element_type).map(|name| (position, name)),
+        _ => None,
+    }) {
+        tokens.splice(position..position + 3, vec![Token::make_word(name, None)]);
+    }
+    tokens
+}
+
+/// position of `]` that closes `[` at `start`
+fn closing_bracket(tokens: &[Token], start: usize) -> Result<usize, ParserError> {
+    let mut depth = 0;
+    for (position, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::LBracket => depth += 1,
+            Token::RBracket if depth == 1 => return Ok(position),
+            Token::RBracket => depth -= 1,
+            _ => {}
+        }
+    }
+    expected("]", Token::EOF)
+}
+
+/// `ARRAY[<elements>]` is rewritten into `array(<elements>)`
+fn rewrite_array_constructors(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some(position) = (0..tokens.len()).find(|position| match &tokens[*position..] {
+        [array, Token::LBracket, ..] => is_keyword(array, Keyword::ARRAY),
+        _ => false,
+    }) {
+        let end = closing_bracket(&tokens, position + 1)?;
+        tokens[end] = Token::RParen;
+        tokens.splice(
+            position..position + 2,
+            vec![Token::make_word("array", None), Token::LParen],
+        );
+    }
+    Ok(tokens)
+}
+
+/// `<array>[<index>]` is rewritten into `array_subscript(<array>, <index>)`, the array is an identifier, a string
+/// literal, a function call or an expression in parentheses
+fn rewrite_subscripts(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some(position) = tokens.iter().position(|token| *token == Token::LBracket) {
+        let end = closing_bracket(&tokens, position)?;
+        let mut start = match tokens[..position].last() {
+            Some(Token::RParen) => {
+                let mut depth = 0;
+                let mut start = position - 1;
+                loop {
+                    match tokens[start] {
+                        Token::RParen => depth += 1,
+                        Token::LParen if depth == 1 => break,
+                        Token::LParen => depth -= 1,
+                        _ => {}
+                    }
+                    if start == 0 {
+                        return expected("(", Token::EOF);
+                    }
+                    start -= 1;
+                }
+                match tokens[..start].last() {
+                    Some(word @ Token::Word(_)) if !is_operand_boundary(word) => start - 1,
+                    _ => start,
+                }
+            }
+            Some(word @ Token::Word(_)) if !is_operand_boundary(word) => position - 1,
+            Some(Token::SingleQuotedString(_)) => position - 1,
+            _ => return expected("expression", Token::LBracket),
+        };
+        while start >= 2 && tokens[start - 1] == Token::Period && matches!(tokens[start - 2], Token::Word(_)) {
+            start -= 2;
+        }
+        if end == position + 1 {
+            return expected("expression", Token::RBracket);
+        }
+        let mut call = vec![Token::make_word("array_subscript", None), Token::LParen];
+        call.extend_from_slice(&tokens[start..position]);
+        call.push(Token::Comma);
+        call.extend_from_slice(&tokens[position + 1..end]);
+        call.push(Token::RParen);
+        tokens.splice(start..=end, call);
+    }
+    Ok(tokens)
+}
+
+/// time zone of `SET TIME ZONE`, negative numbers of hours are parsed here as `sqlparser` values can't have sign
+fn parse_time_zone(parser: &mut Parser) -> Result<SetVariableValue, ParserError> {
+    let negative = parser.consume_token(&Token::Minus);
+    match parser.peek_token() {
+        Token::Word(word) if !negative => {
+            parser.next_token();
+            Ok(SetVariableValue::Ident(word.to_ident()))
+        }
+        Token::SingleQuotedString(zone) if !negative => {
+            parser.next_token();
+            Ok(SetVariableValue::Literal(Value::SingleQuotedString(zone)))
+        }
+        Token::Number(_) => match parser.parse_number_value()? {
+            Value::Number(hours) if negative => Ok(SetVariableValue::Literal(Value::Number(-hours))),
+            hours => Ok(SetVariableValue::Literal(hours)),
+        },
+        unexpected => expected("time zone", unexpected),
+    }
+}
+
+fn rewrite_distinct_from(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some((position, negated, length)) = find_distinct_from(&tokens) {
+        let start = left_operand_start(&tokens, position, is_distinct_from_boundary);
+        let end = right_operand_end(&tokens, position + length, is_distinct_from_boundary);
+        if start == position {
+            return expected("expression", tokens[position].clone());
+        }
+        if end == position + length {
+            return expected("expression", tokens.get(end).cloned().unwrap_or(Token::EOF));
+        }
+        let function = if negated {
+            "is_not_distinct_from"
+        } else {
+            "is_distinct_from"
+        };
+        let mut call = vec![Token::make_word(function, None), Token::LParen];
+        call.extend_from_slice(&tokens[start..position]);
+        call.push(Token::Comma);
+        call.extend_from_slice(&tokens[position + length..end]);
+        call.push(Token::RParen);
+        tokens.splice(start..end, call);
+    }
+    Ok(tokens)
+}
+
+/// `sqlparser` does not support json operators, the leftmost one is rewritten first as they are left associative:
+/// `<left> -> <right>` into `json_extract(<left>, <right>)`, `->>` into `json_extract_text`,
+/// `#>` into `json_extract_path` and `#>>` into `json_extract_path_text`
+fn rewrite_json_operators(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some((position, (function, length))) =
+        (0..tokens.len()).find_map(|position| json_operator(&tokens, position).map(|operator| (position, operator)))
+    {
+        let start = left_operand_start(&tokens, position, is_json_operand_boundary);
+        let end = right_operand_end(&tokens, position + length, is_json_operand_boundary);
+        if start == position {
+            return expected("expression", tokens[position].clone());
+        }
+        if end == position + length {
+            return expected("expression", tokens.get(end).cloned().unwrap_or(Token::EOF));
+        }
+        let mut call = vec![Token::make_word(function, None), Token::LParen];
+        call.extend_from_slice(&tokens[start..position]);
+        call.push(Token::Comma);
+        call.extend_from_slice(&tokens[position + length..end]);
+        call.push(Token::RParen);
+        tokens.splice(start..end, call);
+    }
+    Ok(tokens)
+}
+
+/// `sqlparser` does not support regular expression operators, the leftmost one is rewritten first:
+/// `<left> ~ <right>` into `textregexeq(<left>, <right>)`, `~*` into `texticregexeq`, `!~` into `textregexne`,
+/// `!~*` into `texticregexne` and `<left> [NOT] SIMILAR TO <right>` into
+/// `textregexeq(<left>, similar_to_escape(<right>))` or `textregexne`
+fn rewrite_regex_operators(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some((position, (function, similar, length))) =
+        (0..tokens.len()).find_map(|position| regex_operator(&tokens, position).map(|operator| (position, operator)))
+    {
+        let start = left_operand_start(&tokens, position, is_regex_operand_boundary);
+        let end = right_operand_end(&tokens, position + length, is_regex_operand_boundary);
+        if start == position {
+            return expected("expression", tokens[position].clone());
+        }
+        if end == position + length {
+            return expected("expression", tokens.get(end).cloned().unwrap_or(Token::EOF));
+        }
+        let pattern = &tokens[position + length..end];
+        let call = if similar {
+            let pattern = function_call("similar_to_escape", &[pattern]);
+            function_call(function, &[&tokens[start..position], &pattern])
+        } else {
+            function_call(function, &[&tokens[start..position], pattern])
+        };
+        tokens.splice(start..end, call);
+    }
+    Ok(tokens)
+}
+
+fn parse_alter_schema(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let schema_name = parser.parse_object_name()?;
+    expect_word(parser, "OWNER")?;
+    parser.expect_keyword(Keyword::TO)?;
+    let new_owner = parser.parse_identifier()?;
+    Ok(ExtendedStatement::AlterSchemaOwner { schema_name, new_owner })
+}
+
+fn parse_create_sequence(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let sequence_name = parser.parse_object_name()?;
+    let mut options = SequenceOptions::default();
+    loop {
+        if parse_word(parser, "INCREMENT") {
+            parse_word(parser, "BY");
+            options = options.increment(parse_sequence_value(parser)?);
+        } else if parse_word(parser, "MINVALUE") {
+            options = options.min_value(parse_sequence_value(parser)?);
+        } else if parse_word(parser, "MAXVALUE") {
+            options = options.max_value(parse_sequence_value(parser)?);
+        } else if parser.parse_keyword(Keyword::START) {
+            let _ = parser.parse_keyword(Keyword::WITH);
+            options = options.start(parse_sequence_value(parser)?);
+        } else {
+            break;
+        }
+    }
+    Ok(ExtendedStatement::CreateSequence {
+        sequence_name,
+        if_not_exists,
+        options,
+    })
+}
+
+fn parse_create_schema_like(parser: &mut Parser, schema_name: ObjectName) -> Result<ExtendedStatement, ParserError> {
+    let template_name = parser.parse_object_name()?;
+    let with_data = if parser.parse_keyword(Keyword::WITH) {
+        let with_data = !parser.parse_keyword(Keyword::NO);
+        if !parse_word(parser, "DATA") {
+            return expected("DATA", parser.peek_token());
+        }
+        with_data
+    } else {
+        false
+    };
+    Ok(ExtendedStatement::CreateSchemaLike {
+        schema_name,
+        template_name,
+        with_data,
+    })
+}
+
+fn parse_create_enum_type(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let type_name = parser.parse_object_name()?;
+    parser.expect_keyword(Keyword::AS)?;
+    expect_word(parser, "ENUM")?;
+    parser.expect_token(&Token::LParen)?;
+    let labels = if parser.consume_token(&Token::RParen) {
+        vec![]
+    } else {
+        let labels = parser.parse_comma_separated(parse_enum_label)?;
+        parser.expect_token(&Token::RParen)?;
+        labels
+    };
+    Ok(ExtendedStatement::CreateEnumType { type_name, labels })
+}
+
+/// `AS` and `LANGUAGE` clauses could be given in any order
+fn parse_create_function(parser: &mut Parser, or_replace: bool) -> Result<ExtendedStatement, ParserError> {
+    let function_name = parser.parse_object_name()?;
+    parser.expect_token(&Token::LParen)?;
+    let parameters = if parser.consume_token(&Token::RParen) {
+        vec![]
+    } else {
+        let parameters =
+            parser.parse_comma_separated(|parser| Ok((parser.parse_identifier()?, parser.parse_data_type()?)))?;
+        parser.expect_token(&Token::RParen)?;
+        parameters
+    };
+    parser.expect_keyword(Keyword::RETURNS)?;
+    let return_type = parser.parse_data_type()?;
+    let mut body = None;
+    let mut has_language = false;
+    loop {
+        if body.is_none() && parser.parse_keyword(Keyword::AS) {
+            match parser.next_token() {
+                Token::SingleQuotedString(text) => body = Some(text),
+                unexpected => return expected("function body", unexpected),
+            }
+        } else if !has_language && parser.parse_keyword(Keyword::LANGUAGE) {
+            expect_word(parser, "SQL")?;
+            has_language = true;
+        } else {
+            break;
+        }
+    }
+    match body {
+        Some(body) if has_language => Ok(ExtendedStatement::CreateFunction {
+            function_name,
+            or_replace,
+            parameters,
+            return_type,
+            body,
+        }),
+        Some(_) => expected("LANGUAGE", parser.peek_token()),
+        None => expected("AS", parser.peek_token()),
+    }
+}
+
+/// argument types that follow the name are not used as functions are not overloaded
+fn parse_declare_cursor(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let cursor_name = parser.parse_identifier()?;
+    let _ = parser.parse_keywords(&[Keyword::NO, Keyword::SCROLL]);
+    parser.expect_keyword(Keyword::CURSOR)?;
+    if parser.parse_keyword(Keyword::WITH) || parser.parse_keyword(Keyword::WITHOUT) {
+        parser.expect_keyword(Keyword::HOLD)?;
+    }
+    parser.expect_keyword(Keyword::FOR)?;
+    Ok(ExtendedStatement::DeclareCursor {
+        cursor_name,
+        query: Box::new(parser.parse_query()?),
+    })
+}
+
+/// only forward fetching is supported, fetching without a direction returns the next row
+fn parse_fetch(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let count = if parser.parse_keyword(Keyword::NEXT) {
+        Some(1)
+    } else {
+        parse_word(parser, "FORWARD");
+        if parser.parse_keyword(Keyword::ALL) {
+            None
+        } else if let Token::Number(_) = parser.peek_token() {
+            Some(parser.parse_literal_uint()?)
+        } else {
+            Some(1)
+        }
+    };
+    if !parser.parse_keyword(Keyword::FROM) {
+        let _ = parser.parse_keyword(Keyword::IN);
+    }
+    Ok(ExtendedStatement::Fetch {
+        cursor_name: parser.parse_identifier()?,
+        count,
+    })
+}
+
+/// rows are sent by the client in text format after `FROM STDIN` and by the server after `TO STDOUT`
+fn parse_copy(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let table_name = parser.parse_object_name()?;
+    let columns = parser.parse_parenthesized_column_list(IsOptional::Optional)?;
+    if parser.parse_keyword(Keyword::FROM) {
+        parser.expect_keyword(Keyword::STDIN)?;
+        return Ok(ExtendedStatement::CopyFrom { table_name, columns });
+    }
+    parser.expect_keyword(Keyword::TO)?;
+    expect_word(parser, "STDOUT")?;
+    let _ = parser.parse_keyword(Keyword::WITH);
+    let format = if parse_word(parser, "CSV") {
+        CopyFormat::Csv {
+            header: parse_word(parser, "HEADER"),
+        }
+    } else if parser.consume_token(&Token::LParen) {
+        let format = parse_copy_options(parser)?;
+        parser.expect_token(&Token::RParen)?;
+        format
+    } else {
+        CopyFormat::Text
+    };
+    Ok(ExtendedStatement::CopyTo {
+        table_name,
+        columns,
+        format,
+    })
+}
+
+fn parse_copy_options(parser: &mut Parser) -> Result<CopyFormat, ParserError> {
+    let mut csv = false;
+    let mut header = false;
+    loop {
+        if parse_word(parser, "FORMAT") {
+            if parse_word(parser, "CSV") {
+                csv = true;
+            } else if parse_word(parser, "TEXT") {
+                csv = false;
+            } else {
+                return expected("TEXT or CSV", parser.peek_token());
+            }
+        } else if parse_word(parser, "HEADER") {
+            header = !parse_word(parser, "FALSE");
+            parse_word(parser, "TRUE");
+        } else {
+            return expected("FORMAT or HEADER", parser.peek_token());
+        }
+        if !parser.consume_token(&Token::Comma) {
+            break;
+        }
+    }
+    match (csv, header) {
+        (true, header) => Ok(CopyFormat::Csv { header }),
+        (false, false) => Ok(CopyFormat::Text),
+        (false, true) => Err(ParserError::ParserError(
+            "COPY HEADER available only in CSV mode".to_owned(),
+        )),
+    }
+}
+
+fn parse_function_name(parser: &mut Parser) -> Result<ObjectName, ParserError> {
+    let function_name = parser.parse_object_name()?;
+    if parser.consume_token(&Token::LParen) && !parser.consume_token(&Token::RParen) {
+        parser.parse_comma_separated(Parser::parse_data_type)?;
+        parser.expect_token(&Token::RParen)?;
+    }
+    Ok(function_name)
+}
+
+fn parse_enum_label(parser: &mut Parser) -> Result<String, ParserError> {
+    match parser.next_token() {
+        Token::SingleQuotedString(label) => Ok(label),
+        unexpected => expected("enum label", unexpected),
+    }
+}
+
+/// all kinds of statistics are built when none is listed
+fn parse_create_statistics(parser: &mut Parser) -> Result<ExtendedStatement, ParserError> {
+    let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let statistics_name = parser.parse_object_name()?;
+    let kinds = if parser.consume_token(&Token::LParen) {
+        let kinds = parser.parse_comma_separated(parse_statistics_kind)?;
+        parser.expect_token(&Token::RParen)?;
+        kinds
+    } else {
+        vec![StatisticsKind::NDistinct, StatisticsKind::Dependencies]
+    };
+    parser.expect_keyword(Keyword::ON)?;
+    let columns = parser.parse_comma_separated(Parser::parse_identifier)?;
+    parser.expect_keyword(Keyword::FROM)?;
+    let table_name = parser.parse_object_name()?;
+    Ok(ExtendedStatement::CreateStatistics {
+        statistics_name,
+        if_not_exists,
+        kinds,
+        columns,
+        table_name,
+    })
+}
+
+fn parse_statistics_kind(parser: &mut Parser) -> Result<StatisticsKind, ParserError> {
+    if parse_word(parser, "NDISTINCT") {
+        Ok(StatisticsKind::NDistinct)
+    } else if parse_word(parser, "DEPENDENCIES") {
+        Ok(StatisticsKind::Dependencies)
+    } else {
+        expected("NDISTINCT or DEPENDENCIES", parser.peek_token())
+    }
+}
+
+fn is_word(token: &Token, expected: &str) -> bool {
+    matches!(token, Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(expected))
+}
+
+fn expect_tokens<I: Iterator<Item = Token>>(tokens: &mut I, expected_tokens: &[&str]) -> Result<(), ParserError> {
+    for expected_token in expected_tokens {
+        match tokens.next() {
+            Some(token) if token.to_string().eq_ignore_ascii_case(expected_token) => {}
+            Some(unexpected) => return expected(expected_token, unexpected),
+            None => return expected(expected_token, Token::EOF),
+        }
+    }
+    Ok(())
+}
+
+/// generated column options and `PARTITION BY RANGE (<column>)` are cut out of the statement and the rest
+/// of it is parsed by `sqlparser`, a column option starts after the column name and its type
+fn parse_create_table(tokens: Vec<Token>) -> Result<ExtendedStatement, ParserError> {
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .peekable();
+    let mut table_tokens = vec![];
+    let mut generated_columns = vec![];
+    let mut partition_by = None;
+    let mut depth = 0;
+    let mut column_name = None;
+    let mut has_columns = false;
+    while let Some(token) = tokens.next() {
+        match &token {
+            Token::LParen => {
+                depth += 1;
+                if depth == 1 {
+                    column_name = None;
+                }
+            }
+            Token::RParen => {
+                depth -= 1;
+                has_columns = depth == 0;
+            }
+            Token::Comma if depth == 1 => column_name = None,
+            Token::Word(_) if depth == 0 && has_columns && is_word(&token, "PARTITION") => {
+                expect_tokens(&mut tokens, &["BY", "RANGE", "("])?;
+                partition_by = match tokens.next() {
+                    Some(Token::Word(word)) => Some(word.to_ident()),
+                    Some(unexpected) => return expected("partition key column", unexpected),
+                    None => return expected("partition key column", Token::EOF),
+                };
+                expect_tokens(&mut tokens, &[")"])?;
+                continue;
+            }
+            Token::Word(_) if depth == 1 && column_name.is_some() && is_word(&token, "GENERATED") => {
+                expect_tokens(&mut tokens, &["ALWAYS", "AS", "("])?;
+                let mut expression = vec![];
+                let mut expression_depth = 1;
+                loop {
+                    let token = match tokens.next() {
+                        Some(token) => token,
+                        None => return expected(")", Token::EOF),
+                    };
+                    match token {
+                        Token::LParen => expression_depth += 1,
+                        Token::RParen if expression_depth == 1 => break,
+                        Token::RParen => expression_depth -= 1,
+                        _ => {}
+                    }
+                    expression.push(token);
+                }
+                if let Some(token) = tokens.peek() {
+                    if is_word(token, "STORED") {
+                        tokens.next();
+                    }
+                }
+                generated_columns.push((
+                    column_name.clone().expect("column name"),
+                    Parser::new(expression).parse_expr()?,
+                ));
+                continue;
+            }
+            Token::Word(word) if depth == 1 && column_name.is_none() => column_name = Some(word.to_ident()),
+            _ => {}
+        }
+        table_tokens.push(token);
+    }
+    Ok(ExtendedStatement::CreateTable {
+        statement: Box::new(Parser::new(table_tokens).parse_statement()?),
+        generated_columns,
+        partition_by,
+    })
+}
+
+fn parse_create_partition(parser: &mut Parser, table_name: ObjectName) -> Result<ExtendedStatement, ParserError> {
+    parser.expect_keyword(Keyword::OF)?;
+    let parent_name = parser.parse_object_name()?;
+    parser.expect_keywords(&[Keyword::FOR, Keyword::VALUES, Keyword::FROM])?;
+    let from = parse_range_bound(parser, "MINVALUE")?;
+    parser.expect_keyword(Keyword::TO)?;
+    let to = parse_range_bound(parser, "MAXVALUE")?;
+    Ok(ExtendedStatement::CreatePartition {
+        table_name,
+        parent_name,
+        from,
+        to,
+    })
+}
+
+/// `None` stands for the unbounded side of the range
+fn parse_range_bound(parser: &mut Parser, unbounded: &str) -> Result<Option<i64>, ParserError> {
+    parser.expect_token(&Token::LParen)?;
+    let bound = if parse_word(parser, unbounded) {
+        None
+    } else {
+        Some(parse_sequence_value(parser)?)
+    };
+    parser.expect_token(&Token::RParen)?;
+    Ok(bound)
+}
+
+fn parse_sequence_value(parser: &mut Parser) -> Result<i64, ParserError> {
+    let negative = parser.consume_token(&Token::Minus);
+    match parser.next_token() {
+        Token::Number(number) => match number.parse::<i64>() {
+            Ok(value) if negative => Ok(-value),
+            Ok(value) => Ok(value),
+            Err(_) => expected("bigint value", Token::Number(number)),
+        },
+        unexpected => expected("bigint value", unexpected),
+    }
+}
+
+fn parse_schema_privileges(parser: &mut Parser) -> Result<(Vec<SchemaPrivilege>, Vec<ObjectName>), ParserError> {
+    let privileges = if parser.parse_keyword(Keyword::ALL) {
+        parse_word(parser, "PRIVILEGES");
+        vec![SchemaPrivilege::Usage, SchemaPrivilege::Create]
+    } else {
+        parser.parse_comma_separated(parse_schema_privilege)?
+    };
+    parser.expect_keyword(Keyword::ON)?;
+    parser.expect_keyword(Keyword::SCHEMA)?;
+    let schema_names = parser.parse_comma_separated(Parser::parse_object_name)?;
+    Ok((privileges, schema_names))
+}
+
+fn parse_schema_privilege(parser: &mut Parser) -> Result<SchemaPrivilege, ParserError> {
+    match parser.next_token() {
+        Token::Word(word) if word.keyword == Keyword::CREATE => Ok(SchemaPrivilege::Create),
+        Token::Word(word) if word.value.eq_ignore_ascii_case("USAGE") => Ok(SchemaPrivilege::Usage),
+        unexpected => expected("USAGE or CREATE", unexpected),
+    }
+}
+
+fn parse_word(parser: &mut Parser, expected: &str) -> bool {
+    match parser.peek_token() {
+        Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(expected) => {
+            parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn expect_word(parser: &mut Parser, expected_word: &str) -> Result<(), ParserError> {
+    if parse_word(parser, expected_word) {
+        Ok(())
+    } else {
+        expected(expected_word, parser.peek_token())
+    }
+}
+
+fn expected<T>(expected: &str, found: Token) -> Result<T, ParserError> {
+    Err(ParserError::ParserError(format!(
+        "Expected {}, found: {}",
+        expected, found
+    )))
+}
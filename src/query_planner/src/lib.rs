@@ -20,6 +20,8 @@ use std::fmt::{self, Display, Formatter};
 
 use sql_model::sql_types::SqlType;
 
+pub mod extended;
+pub mod logical;
 ///! Module for representing how a query will be parameters bound, executed and
 ///! values represented during runtime.
 pub mod plan;
@@ -0,0 +1,197 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! logical plan of a select is a tree of relational operators over rows of a table. The planner builds it from the
+//! statement, optimizer passes rewrite it, e.g. conjuncts of a filter are pushed down into the scan, and then it is
+//! lowered into the physical plan that the engine executes. It is shown as a tree of operators, one per line:
+//!
+//! ```text
+//! Project: player, total
+//!   -> Sort: player DESC
+//!     -> Window: sum(score)
+//!       -> Scan on schema_name.leaderboard: player >= 2, score <> 10
+//! ```
+
+use crate::{
+    plan::{ColumnRange, RowLimit, SortKey, WindowFunction},
+    FullTableName, TableId,
+};
+use sqlparser::ast::Expr;
+use std::fmt::{self, Display, Formatter};
+
+/// column of the result, window functions are referred to by their index in the `Window` operator
+#[derive(PartialEq, Debug, Clone)]
+pub enum ProjectedColumn {
+    Column(String),
+    /// name of the result column and index of the window function
+    Window(String, usize),
+    /// name of the result column and expression over columns of a row or call of an aggregate over all rows
+    Expr(String, Expr),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum RelationOp {
+    /// rows of the table, `ranges` and `filters` are conjuncts of predicates that are pushed down into the scan
+    Scan {
+        table_id: TableId,
+        table_name: FullTableName,
+        ranges: Vec<ColumnRange>,
+        filters: Vec<Expr>,
+    },
+    /// rows that satisfy the predicate
+    Filter {
+        input: Box<RelationOp>,
+        predicate: Expr,
+    },
+    /// rows with values of window functions that are computed over all input rows
+    Window {
+        input: Box<RelationOp>,
+        functions: Vec<WindowFunction>,
+    },
+    Sort {
+        input: Box<RelationOp>,
+        order_by: Vec<SortKey>,
+    },
+    Limit {
+        input: Box<RelationOp>,
+        limit: RowLimit,
+    },
+    Project {
+        input: Box<RelationOp>,
+        columns: Vec<ProjectedColumn>,
+    },
+}
+
+impl RelationOp {
+    /// operator that rows are read from, a scan reads them from the table
+    pub fn input(&self) -> Option<&RelationOp> {
+        match self {
+            RelationOp::Scan { .. } => None,
+            RelationOp::Filter { input, .. }
+            | RelationOp::Window { input, .. }
+            | RelationOp::Sort { input, .. }
+            | RelationOp::Limit { input, .. }
+            | RelationOp::Project { input, .. } => Some(input),
+        }
+    }
+
+    /// the same operator over the input that is rewritten by `map`
+    pub fn try_map_input<E, F: FnOnce(RelationOp) -> Result<RelationOp, E>>(self, map: F) -> Result<RelationOp, E> {
+        Ok(match self {
+            RelationOp::Scan { .. } => self,
+            RelationOp::Filter { input, predicate } => RelationOp::Filter {
+                input: Box::new(map(*input)?),
+                predicate,
+            },
+            RelationOp::Window { input, functions } => RelationOp::Window {
+                input: Box::new(map(*input)?),
+                functions,
+            },
+            RelationOp::Sort { input, order_by } => RelationOp::Sort {
+                input: Box::new(map(*input)?),
+                order_by,
+            },
+            RelationOp::Limit { input, limit } => RelationOp::Limit {
+                input: Box::new(map(*input)?),
+                limit,
+            },
+            RelationOp::Project { input, columns } => RelationOp::Project {
+                input: Box::new(map(*input)?),
+                columns,
+            },
+        })
+    }
+
+    fn fmt_operator(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RelationOp::Scan {
+                table_name,
+                ranges,
+                filters,
+                ..
+            } => {
+                write!(f, "Scan on {}", table_name)?;
+                let conditions = ranges
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(filters.iter().map(ToString::to_string))
+                    .collect::<Vec<_>>();
+                if !conditions.is_empty() {
+                    write!(f, ": {}", conditions.join(", "))?;
+                }
+                Ok(())
+            }
+            RelationOp::Filter { predicate, .. } => write!(f, "Filter: {}", predicate),
+            RelationOp::Window { functions, .. } => write!(
+                f,
+                "Window: {}",
+                functions
+                    .iter()
+                    .map(|function| match &function.argument {
+                        Some(argument) => format!("{}({})", function.function, argument),
+                        None if function.function == "row_number" => format!("{}()", function.function),
+                        None => format!("{}(*)", function.function),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            RelationOp::Sort { order_by, .. } => write!(
+                f,
+                "Sort: {}",
+                order_by.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+            ),
+            RelationOp::Limit { limit, .. } => {
+                match limit.count {
+                    Some(count) => write!(f, "Limit: {}", count)?,
+                    None => write!(f, "Limit: ALL")?,
+                }
+                if limit.offset > 0 {
+                    write!(f, " OFFSET {}", limit.offset)?;
+                }
+                if limit.with_ties {
+                    write!(f, " WITH TIES")?;
+                }
+                Ok(())
+            }
+            RelationOp::Project { columns, .. } => write!(
+                f,
+                "Project: {}",
+                columns
+                    .iter()
+                    .map(|column| match column {
+                        ProjectedColumn::Column(name)
+                        | ProjectedColumn::Window(name, _)
+                        | ProjectedColumn::Expr(name, _) => name.as_str(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Display for RelationOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_operator(f)?;
+        let mut depth = 1;
+        let mut operator = self.input();
+        while let Some(input) = operator {
+            write!(f, "\n{}-> ", "  ".repeat(depth))?;
+            input.fmt_operator(f)?;
+            depth += 1;
+            operator = input.input();
+        }
+        Ok(())
+    }
+}
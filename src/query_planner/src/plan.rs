@@ -13,16 +13,32 @@
 // limitations under the License.
 
 ///! represents a plan to be executed by the engine.
-use crate::{SchemaId, TableId};
-use data_manager::ColumnDefinition;
-use sql_model::Id;
-use sqlparser::ast::{Assignment, Expr, Ident, Statement};
+use crate::{logical::RelationOp, SchemaId, TableId};
+use data_manager::{ColumnDefinition, SchemaPrivilege, SequenceOptions, StatisticsKind};
+use protocol::results::QueryEvent;
+use sql_model::{sql_types::SqlType, Id};
+use sqlparser::ast::{Assignment, Expr, Ident, Statement, Value};
+use std::fmt::{self, Display, Formatter};
+
+/// partitioning of a created table
+#[derive(PartialEq, Debug, Clone)]
+pub enum TablePartitioning {
+    /// `PARTITION BY RANGE (column)` of a partitioned table
+    ByRange(String),
+    /// `PARTITION OF parent FOR VALUES FROM (from) TO (to)`, `None` bounds are `MINVALUE` and `MAXVALUE`
+    RangeOf {
+        parent: TableId,
+        from: Option<i64>,
+        to: Option<i64>,
+    },
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct TableCreationInfo {
     pub schema_id: Id,
     pub table_name: String,
     pub columns: Vec<ColumnDefinition>,
+    pub partitioning: Option<TablePartitioning>,
 }
 
 impl TableCreationInfo {
@@ -31,6 +47,14 @@ impl TableCreationInfo {
             schema_id,
             table_name: table_name.to_string(),
             columns,
+            partitioning: None,
+        }
+    }
+
+    pub(crate) fn partitioned(self, partitioning: TablePartitioning) -> TableCreationInfo {
+        TableCreationInfo {
+            partitioning: Some(partitioning),
+            ..self
         }
     }
 
@@ -39,17 +63,84 @@ impl TableCreationInfo {
     }
 }
 
+/// schema whose tables, and their rows `with_data`, are copied into a created schema
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct SchemaTemplate {
+    pub schema_id: Id,
+    pub with_data: bool,
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct SchemaCreationInfo {
     pub schema_name: String,
+    pub template: Option<SchemaTemplate>,
 }
 
 impl SchemaCreationInfo {
     pub(crate) fn new<S: ToString>(schema_name: S) -> SchemaCreationInfo {
         SchemaCreationInfo {
             schema_name: schema_name.to_string(),
+            template: None,
         }
     }
+
+    pub(crate) fn like(self, schema_id: Id, with_data: bool) -> SchemaCreationInfo {
+        SchemaCreationInfo {
+            template: Some(SchemaTemplate { schema_id, with_data }),
+            ..self
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SequenceCreationInfo {
+    pub schema_id: Id,
+    pub sequence_name: String,
+    pub if_not_exists: bool,
+    pub options: SequenceOptions,
+}
+
+/// labels of the enum type in the order of its values
+#[derive(PartialEq, Debug, Clone)]
+pub struct EnumTypeCreationInfo {
+    pub schema_id: Id,
+    pub type_name: String,
+    pub labels: Vec<String>,
+}
+
+/// body of the function is checked when the function is created
+#[derive(PartialEq, Debug, Clone)]
+pub struct FunctionCreationInfo {
+    pub schema_id: Id,
+    pub function_name: String,
+    pub or_replace: bool,
+    pub parameters: Vec<(String, SqlType)>,
+    pub return_type: SqlType,
+    pub body: String,
+}
+
+/// statistics of `columns` of the table are kept in the schema of the statistics name
+#[derive(PartialEq, Debug, Clone)]
+pub struct StatisticsCreationInfo {
+    pub schema_id: Id,
+    pub statistics_name: String,
+    pub if_not_exists: bool,
+    pub table_id: TableId,
+    pub columns: Vec<String>,
+    pub kinds: Vec<StatisticsKind>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SchemaOwnerChange {
+    pub schema_id: SchemaId,
+    pub new_owner: String,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SchemaPrivilegesChange {
+    pub schema_ids: Vec<SchemaId>,
+    pub privileges: Vec<SchemaPrivilege>,
+    pub roles: Vec<String>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -59,21 +150,210 @@ pub struct TableInserts {
     pub input: Vec<Vec<Expr>>,
 }
 
+/// text representation of rows sent by `COPY TO STDOUT`
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CopyFormat {
+    /// tab separated values, `\N` is NULL
+    Text,
+    /// comma separated values, the first line has column names when `header` is set
+    Csv { header: bool },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct TableCopy {
+    pub table_id: TableId,
+    pub columns: Vec<Ident>,
+    pub format: CopyFormat,
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct TableUpdates {
     pub table_id: TableId,
     pub assignments: Vec<Assignment>,
+    /// condition of `WHERE`, only rows that satisfy it are updated
+    pub filter: Option<Expr>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct TableDeletes {
     pub table_id: TableId,
+    /// condition of `WHERE`, only rows that satisfy it are deleted
+    pub filter: Option<Expr>,
+}
+
+/// `ORDER BY` key over a column of the table
+#[derive(PartialEq, Debug, Clone)]
+pub struct SortKey {
+    pub column_name: String,
+    pub ascending: bool,
+    pub nulls_first: bool,
 }
 
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.column_name)?;
+        if !self.ascending {
+            write!(f, " DESC")?;
+        }
+        match (self.ascending, self.nulls_first) {
+            (true, true) => write!(f, " NULLS FIRST"),
+            (false, false) => write!(f, " NULLS LAST"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// rows that are sent after sorting, `count` is given by `LIMIT` or `FETCH FIRST`.
+/// `with_ties` also sends rows that are equal to the last one by sort keys
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct RowLimit {
+    pub offset: u64,
+    pub count: Option<u64>,
+    pub with_ties: bool,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FrameUnits {
+    Rows,
+    Range,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(u64),
+    CurrentRow,
+    Following(u64),
+    UnboundedFollowing,
+}
+
+/// rows of a partition that a window function is computed over,
+/// the default frame is `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct WindowFrame {
+    pub units: FrameUnits,
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+impl Default for WindowFrame {
+    fn default() -> WindowFrame {
+        WindowFrame {
+            units: FrameUnits::Range,
+            start: FrameBound::UnboundedPreceding,
+            end: FrameBound::CurrentRow,
+        }
+    }
+}
+
+/// `function(argument[, parameter]) [FILTER (WHERE filter)] OVER (PARTITION BY ... ORDER BY ... frame)`,
+/// `argument` is `None` for `count(*)` and functions without arguments,
+/// `parameter` is a constant second argument such as the fraction of `approx_percentile` or the delimiter of
+/// `string_agg`, only rows that satisfy `filter` are aggregated
+#[derive(PartialEq, Debug, Clone)]
+pub struct WindowFunction {
+    pub function: String,
+    pub argument: Option<String>,
+    pub parameter: Option<Value>,
+    pub filter: Option<Expr>,
+    pub partition_by: Vec<String>,
+    pub order_by: Vec<SortKey>,
+    pub frame: WindowFrame,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum SelectedColumn {
+    Column(String),
+    /// window function with the name of its result column
+    Window(String, WindowFunction),
+    /// expression over columns of a row or call of an aggregate over all rows, aggregates are resolved by name
+    /// when the query is executed
+    Expr(String, Expr),
+}
+
+/// inclusive bounds of integer column values that rows have to satisfy, `None` bounds are open
+#[derive(PartialEq, Debug, Clone)]
+pub struct ColumnRange {
+    pub column_name: String,
+    pub lower: Option<i64>,
+    pub upper: Option<i64>,
+}
+
+impl Display for ColumnRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) if lower == upper => write!(f, "{} = {}", self.column_name, lower),
+            (Some(lower), Some(upper)) => write!(f, "{} BETWEEN {} AND {}", self.column_name, lower, upper),
+            (Some(lower), None) => write!(f, "{} >= {}", self.column_name, lower),
+            (None, Some(upper)) => write!(f, "{} <= {}", self.column_name, upper),
+            (None, None) => write!(f, "{} IS NOT NULL", self.column_name),
+        }
+    }
+}
+
+/// how rows of scanned tables are read, the one with lower estimated cost is chosen
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ScanMethod {
+    /// every row of the table
+    Sequential,
+    /// rows with keys in the range of the first primary key column
+    PrimaryKeyRange,
+}
+
+/// columns of the table that every operator of a select reads, in the order of table columns. Scanned rows are
+/// unpacked only with `filter` columns, rows that are kept with columns of windows, sorting and the projection
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct RequiredColumns {
+    pub filter: Vec<String>,
+    pub window: Vec<String>,
+    pub sort: Vec<String>,
+    pub project: Vec<String>,
+}
+
+/// `ranges` are given by `WHERE` comparisons of integer columns with literals, `filters` are the other conjuncts of
+/// `WHERE` that are evaluated on scanned rows. `scanned_tables` are the table itself or partitions of a partitioned
+/// table that are left after pruning
 #[derive(PartialEq, Debug, Clone)]
 pub struct SelectInput {
     pub table_id: TableId,
-    pub selected_columns: Vec<String>,
+    pub selected_columns: Vec<SelectedColumn>,
+    pub order_by: Vec<SortKey>,
+    pub limit: RowLimit,
+    pub ranges: Vec<ColumnRange>,
+    pub filters: Vec<Expr>,
+    pub scanned_tables: Vec<TableId>,
+    pub scan: ScanMethod,
+    pub required_columns: RequiredColumns,
+}
+
+/// `SELECT` without `FROM` clause, expressions are evaluated once with their column names
+#[derive(PartialEq, Debug, Clone)]
+pub struct SelectValues {
+    pub values: Vec<(String, Expr)>,
+}
+
+/// `$n` placeholder of a prepared statement, `index` starts from 0. Its type is given by the column its value is
+/// assigned to or compared with
+#[derive(PartialEq, Debug, Clone)]
+pub struct Param {
+    pub index: usize,
+    pub column_name: Option<String>,
+    pub sql_type: SqlType,
+}
+
+/// index of the parameter if the expression is a `$n` placeholder
+pub fn param_index(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Identifier(Ident { value, .. }) => match value.strip_prefix('$') {
+            Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_digit(10)) => digits
+                .parse::<usize>()
+                .ok()
+                .filter(|index| *index > 0)
+                .map(|index| index - 1),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -82,9 +362,49 @@ pub enum Plan {
     CreateSchema(SchemaCreationInfo),
     DropTables(Vec<TableId>),
     DropSchemas(Vec<(SchemaId, bool)>),
+    CreateSequence(SequenceCreationInfo),
+    /// sequences that exist as `(schema id, sequence name)`
+    DropSequences(Vec<(Id, String)>),
+    CreateEnumType(EnumTypeCreationInfo),
+    CreateFunction(FunctionCreationInfo),
+    /// functions that exist as `(schema id, function name)`
+    DropFunctions(Vec<(Id, String)>),
+    CreateStatistics(StatisticsCreationInfo),
+    /// statistics that exist as `(schema id, statistics name)`
+    DropStatistics(Vec<(Id, String)>),
+    /// tables which statistics are rebuilt
+    Analyze(Vec<TableId>),
+    /// tables which storage is compacted
+    Vacuum(Vec<TableId>),
+    /// `SELECT * FROM index_advice` over tables that the role can use
+    IndexAdvice(Vec<TableId>),
+    AlterSchemaOwner(SchemaOwnerChange),
+    GrantSchemaPrivileges(SchemaPrivilegesChange),
+    RevokeSchemaPrivileges(SchemaPrivilegesChange),
     Select(SelectInput),
+    SelectValues(SelectValues),
     Update(TableUpdates),
     Delete(TableDeletes),
     Insert(TableInserts),
+    /// `COPY FROM STDIN` into the table, input is empty until the client sends rows
+    CopyFrom(TableInserts),
+    /// `COPY TO STDOUT` of the table
+    CopyTo(TableCopy),
+    /// `IF EXISTS` or `IF NOT EXISTS` statement that has nothing to do, it completes with the event
+    Skipped(QueryEvent),
     NotProcessed(Box<Statement>),
 }
+
+/// plan of a statement with `$n` placeholders in place of its literals, it is made once for statements that differ
+/// only by literals and values of literals are bound to it every time one of them is executed
+#[derive(PartialEq, Debug, Clone)]
+pub enum GenericPlan {
+    /// logical plan of a select with columns of the table, comparisons with literals bound scanned keys and
+    /// partitions so it is optimized and lowered after literals are bound
+    Select {
+        relation: RelationOp,
+        columns: Vec<ColumnDefinition>,
+    },
+    /// `INSERT`, `UPDATE`, `DELETE` and `SELECT` without `FROM`, literals are bound to expressions of the plan
+    Plan(Plan),
+}
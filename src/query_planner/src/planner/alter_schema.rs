@@ -0,0 +1,76 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{Plan, SchemaOwnerChange},
+    planner::{check_schema_owner, Planner, Result},
+    SchemaId, SchemaName,
+};
+use data_manager::DataManager;
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::{Ident, ObjectName};
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct AlterSchemaOwnerPlanner<'asp> {
+    schema_name: &'asp ObjectName,
+    new_owner: &'asp Ident,
+    role: &'asp str,
+}
+
+impl<'asp> AlterSchemaOwnerPlanner<'asp> {
+    pub(crate) fn new(
+        schema_name: &'asp ObjectName,
+        new_owner: &'asp Ident,
+        role: &'asp str,
+    ) -> AlterSchemaOwnerPlanner<'asp> {
+        AlterSchemaOwnerPlanner {
+            schema_name,
+            new_owner,
+            role,
+        }
+    }
+}
+
+impl Planner for AlterSchemaOwnerPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        match SchemaName::try_from(self.schema_name) {
+            Ok(schema_name) => match data_manager.schema_exists(&schema_name) {
+                None => {
+                    sender
+                        .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                        .expect("To Send Query Result to Client");
+                    Err(())
+                }
+                Some(schema_id) => {
+                    check_schema_owner(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name.as_ref()),
+                    )?;
+                    Ok(Plan::AlterSchemaOwner(SchemaOwnerChange {
+                        schema_id: SchemaId(schema_id),
+                        new_owner: self.new_owner.value.clone(),
+                    }))
+                }
+            },
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::Plan,
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName, TableId,
+};
+use data_manager::{DataManager, Journal, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct AnalyzePlanner<'ap> {
+    table_name: Option<&'ap ObjectName>,
+    role: &'ap str,
+    journal: Option<&'ap Journal>,
+    plan: fn(Vec<TableId>) -> Plan,
+}
+
+impl<'ap> AnalyzePlanner<'ap> {
+    pub(crate) fn new(table_name: Option<&'ap ObjectName>, role: &'ap str) -> AnalyzePlanner<'ap> {
+        AnalyzePlanner {
+            table_name,
+            role,
+            journal: None,
+            plan: Plan::Analyze,
+        }
+    }
+
+    /// tables are resolved as for `ANALYZE` and their storage is compacted
+    pub(crate) fn vacuum(table_name: Option<&'ap ObjectName>, role: &'ap str) -> AnalyzePlanner<'ap> {
+        AnalyzePlanner {
+            table_name,
+            role,
+            journal: None,
+            plan: Plan::Vacuum,
+        }
+    }
+
+    pub(crate) fn with_journal(mut self, journal: Option<&'ap Journal>) -> AnalyzePlanner<'ap> {
+        self.journal = journal;
+        self
+    }
+}
+
+impl Planner for AnalyzePlanner<'_> {
+    /// without a table name every table in schemas that the role can use is analyzed
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let table_name = match self.table_name {
+            Some(table_name) => table_name,
+            None => {
+                let tables = data_manager
+                    .table_ids()
+                    .into_iter()
+                    .filter(|(schema_id, _)| {
+                        data_manager.has_schema_privilege(*schema_id, self.role, SchemaPrivilege::Usage)
+                    })
+                    .map(TableId)
+                    .collect();
+                return Ok((self.plan)(tables));
+            }
+        };
+        let full_table_name = match FullTableName::try_from(table_name) {
+            Ok(full_table_name) => full_table_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, table_name) = full_table_name.as_tuple();
+        match data_manager.table_exists_in(self.journal, &schema_name, &table_name) {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(&full_table_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((schema_id, Some(table_id))) => {
+                check_schema_privilege(
+                    &data_manager,
+                    sender.as_ref(),
+                    self.role,
+                    (schema_id, schema_name),
+                    SchemaPrivilege::Usage,
+                )?;
+                Ok((self.plan)(vec![TableId((schema_id, table_id))]))
+            }
+        }
+    }
+}
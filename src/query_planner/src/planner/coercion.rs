@@ -0,0 +1,186 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! literals compared with integer columns are coerced the way PostgreSQL promotes operands:
+//! a numeric literal promotes the column to numeric, e.g. `id > 1.5` is `id >= 2`,
+//! and a quoted literal of unknown type is read as a value of the column type
+
+use crate::planner::Result;
+use protocol::{results::QueryError, Sender};
+use sql_model::sql_types::SqlType;
+use sqlparser::ast::{BinaryOperator, Expr, UnaryOperator, Value};
+
+/// bounds that no integer satisfies
+const EMPTY: (Option<i64>, Option<i64>) = (Some(1), Some(0));
+
+/// upper limit of values that are exactly representable as `i64` when given as `f64`
+const I64_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+/// literal operand of a comparison with an integer column
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Operand {
+    Integer(i64),
+    Numeric(f64),
+}
+
+impl Operand {
+    fn negated(self) -> Operand {
+        match self {
+            Operand::Integer(value) => match value.checked_neg() {
+                Some(negated) => Operand::Integer(negated),
+                None => Operand::Numeric(-(value as f64)),
+            },
+            Operand::Numeric(value) => Operand::Numeric(-value),
+        }
+    }
+}
+
+/// `None` when the expression isn't a literal, quoted literals that aren't values of the column type are errors
+pub(crate) fn integer_operand(expr: &Expr, sql_type: &SqlType, sender: &dyn Sender) -> Result<Option<Operand>> {
+    match expr {
+        Expr::Value(Value::Number(number)) => {
+            let number = number.to_string();
+            Ok(match number.parse() {
+                Ok(value) => Some(Operand::Integer(value)),
+                Err(_) => number.parse().ok().map(Operand::Numeric),
+            })
+        }
+        Expr::Value(Value::SingleQuotedString(text)) => unknown_literal(text, sql_type, sender).map(Some),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => Ok(integer_operand(expr, sql_type, sender)?.map(Operand::negated)),
+        Expr::UnaryOp {
+            op: UnaryOperator::Plus,
+            expr,
+        } => integer_operand(expr, sql_type, sender),
+        Expr::Nested(expr) => integer_operand(expr, sql_type, sender),
+        _ => Ok(None),
+    }
+}
+
+fn unknown_literal(text: &str, sql_type: &SqlType, sender: &dyn Sender) -> Result<Operand> {
+    let type_name: &'static str = sql_type.into();
+    let (min, max) = match sql_type {
+        SqlType::SmallInt(_) => (i64::from(i16::MIN), i64::from(i16::MAX)),
+        SqlType::Integer(_) => (i64::from(i32::MIN), i64::from(i32::MAX)),
+        _ => (i64::MIN, i64::MAX),
+    };
+    match text.trim().parse::<i128>() {
+        Ok(value) if i128::from(min) <= value && value <= i128::from(max) => Ok(Operand::Integer(value as i64)),
+        Ok(_) => {
+            sender
+                .send(Err(QueryError::numeric_value_out_of_range(format!(
+                    "value \"{}\" is out of range for type {}",
+                    text, type_name
+                ))))
+                .expect("To Send Query Result to Client");
+            Err(())
+        }
+        Err(_) => {
+            sender
+                .send(Err(QueryError::invalid_text_representation(format!(
+                    "invalid input syntax for type {}: \"{}\"",
+                    type_name, text
+                ))))
+                .expect("To Send Query Result to Client");
+            Err(())
+        }
+    }
+}
+
+/// inclusive bounds of integer values that satisfy `column <op> operand`
+pub(crate) fn comparison_bounds(op: &BinaryOperator, operand: Operand) -> Option<(Option<i64>, Option<i64>)> {
+    match operand {
+        Operand::Integer(value) => match op {
+            BinaryOperator::Eq => Some((Some(value), Some(value))),
+            BinaryOperator::Gt => Some(value.checked_add(1).map(|lower| (Some(lower), None)).unwrap_or(EMPTY)),
+            BinaryOperator::GtEq => Some((Some(value), None)),
+            BinaryOperator::Lt => Some(value.checked_sub(1).map(|upper| (None, Some(upper))).unwrap_or(EMPTY)),
+            BinaryOperator::LtEq => Some((None, Some(value))),
+            _ => None,
+        },
+        Operand::Numeric(value) if value.is_nan() => None,
+        Operand::Numeric(value) if value.fract() == 0.0 && -I64_LIMIT <= value && value < I64_LIMIT => {
+            comparison_bounds(op, Operand::Integer(value as i64))
+        }
+        Operand::Numeric(value) => match op {
+            BinaryOperator::Eq => Some(EMPTY),
+            BinaryOperator::Gt | BinaryOperator::GtEq => Some(match value.ceil() {
+                lower if lower >= I64_LIMIT => EMPTY,
+                lower if lower < -I64_LIMIT => (None, None),
+                lower => (Some(lower as i64), None),
+            }),
+            BinaryOperator::Lt | BinaryOperator::LtEq => Some(match value.floor() {
+                upper if upper < -I64_LIMIT => EMPTY,
+                upper if upper >= I64_LIMIT => (None, None),
+                upper => (None, Some(upper as i64)),
+            }),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions_round_to_integer_bounds() {
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Gt, Operand::Numeric(1.5)),
+            Some((Some(2), None))
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::GtEq, Operand::Numeric(-1.5)),
+            Some((Some(-1), None))
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Lt, Operand::Numeric(1.5)),
+            Some((None, Some(1)))
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::LtEq, Operand::Numeric(-1.5)),
+            Some((None, Some(-2)))
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Eq, Operand::Numeric(1.5)),
+            Some(EMPTY)
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Gt, Operand::Numeric(2.0)),
+            Some((Some(3), None))
+        );
+    }
+
+    #[test]
+    fn values_out_of_integer_range() {
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Gt, Operand::Numeric(1e20)),
+            Some(EMPTY)
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Lt, Operand::Numeric(1e20)),
+            Some((None, None))
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Gt, Operand::Integer(i64::MAX)),
+            Some(EMPTY)
+        );
+        assert_eq!(
+            comparison_bounds(&BinaryOperator::Lt, Operand::Integer(i64::MIN)),
+            Some(EMPTY)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{CopyFormat, Plan, TableCopy, TableInserts},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName, TableId,
+};
+use data_manager::{DataManager, Journal, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::{Ident, ObjectName};
+use std::{convert::TryFrom, sync::Arc};
+
+/// `None` format copies rows from the client
+pub(crate) struct CopyPlanner<'cp> {
+    table_name: &'cp ObjectName,
+    columns: &'cp [Ident],
+    format: Option<CopyFormat>,
+    role: &'cp str,
+    journal: Option<&'cp Journal>,
+}
+
+impl<'cp> CopyPlanner<'cp> {
+    pub(crate) fn from_stdin(table_name: &'cp ObjectName, columns: &'cp [Ident], role: &'cp str) -> CopyPlanner<'cp> {
+        CopyPlanner {
+            table_name,
+            columns,
+            format: None,
+            role,
+            journal: None,
+        }
+    }
+
+    pub(crate) fn to_stdout(
+        table_name: &'cp ObjectName,
+        columns: &'cp [Ident],
+        format: CopyFormat,
+        role: &'cp str,
+    ) -> CopyPlanner<'cp> {
+        CopyPlanner {
+            table_name,
+            columns,
+            format: Some(format),
+            role,
+            journal: None,
+        }
+    }
+
+    pub(crate) fn with_journal(mut self, journal: Option<&'cp Journal>) -> CopyPlanner<'cp> {
+        self.journal = journal;
+        self
+    }
+}
+
+impl Planner for CopyPlanner<'_> {
+    /// rows copied from the client are not known while the statement is planned, they are added to the inserts
+    /// when client sent them
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let full_table_name = match FullTableName::try_from(self.table_name) {
+            Ok(full_table_name) => full_table_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, table_name) = full_table_name.as_tuple();
+        match data_manager.table_exists_in(self.journal, &schema_name, &table_name) {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(&full_table_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((schema_id, Some(table_id))) => {
+                check_schema_privilege(
+                    &data_manager,
+                    sender.as_ref(),
+                    self.role,
+                    (schema_id, schema_name),
+                    SchemaPrivilege::Usage,
+                )?;
+                let table_columns = data_manager
+                    .table_columns(&Box::new((schema_id, table_id)))
+                    .unwrap_or_default();
+                for column in self.columns {
+                    if !table_columns
+                        .iter()
+                        .any(|table_column| table_column.has_name(&column.value))
+                    {
+                        sender
+                            .send(Err(QueryError::column_does_not_exist(&column.value)))
+                            .expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                }
+                let table_id = TableId((schema_id, table_id));
+                match self.format {
+                    None => Ok(Plan::CopyFrom(TableInserts {
+                        table_id,
+                        column_indices: self.columns.to_vec(),
+                        input: vec![],
+                    })),
+                    Some(format) => Ok(Plan::CopyTo(TableCopy {
+                        table_id,
+                        columns: self.columns.to_vec(),
+                        format,
+                    })),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! costs of reading rows are estimated in units of reading a row of a table sequentially.
+//! Rows of a table are kept in the order of their keys, so rows of a key range are read as sequentially as rows of
+//! the whole table; the range scan pays for seeking to the start of its ranges and for comparing keys of read rows
+//! with the end of the range instead.
+
+use crate::plan::{ColumnRange, ScanMethod};
+use data_manager::{ColumnDefinition, TableStatistics};
+use sql_model::sql_types::SqlType;
+
+const SEQ_ROW_COST: f64 = 1.0;
+const RANGE_ROW_COST: f64 = 1.1;
+/// a range scan seeks the start of keys written before they were packed in order and the start of its range
+const RANGE_SEEK_COST: f64 = 2.0 * 4.0;
+
+/// number of rows of a table that was never analyzed
+const DEFAULT_ROWS: f64 = 1000.0;
+/// selectivities that PostgreSQL assumes for conditions on a column without statistics
+const DEFAULT_EQUALITY_SELECTIVITY: f64 = 0.005;
+const DEFAULT_RANGE_SELECTIVITY: f64 = 0.005;
+const DEFAULT_INEQUALITY_SELECTIVITY: f64 = 1.0 / 3.0;
+
+fn seq_scan_cost(rows: f64) -> f64 {
+    rows * SEQ_ROW_COST
+}
+
+fn range_scan_cost(rows: f64, selectivity: f64) -> f64 {
+    RANGE_SEEK_COST + rows * selectivity * RANGE_ROW_COST
+}
+
+fn default_selectivity(range: &ColumnRange) -> f64 {
+    match (range.lower, range.upper) {
+        (Some(lower), Some(upper)) if lower == upper => DEFAULT_EQUALITY_SELECTIVITY,
+        (Some(_), Some(_)) => DEFAULT_RANGE_SELECTIVITY,
+        _ => DEFAULT_INEQUALITY_SELECTIVITY,
+    }
+}
+
+/// range of keys is scanned when there is a range of integer first primary key column and its estimated cost is
+/// lower than the cost of reading all rows, tables that were never analyzed are taken to be of `DEFAULT_ROWS`
+pub(crate) fn scan_method(
+    all_columns: &[ColumnDefinition],
+    ranges: &[ColumnRange],
+    statistics: Option<&TableStatistics>,
+) -> ScanMethod {
+    let range = match all_columns.iter().find(|column| column.is_primary_key()) {
+        Some(column)
+            if matches!(
+                column.sql_type(),
+                SqlType::SmallInt(_) | SqlType::Integer(_) | SqlType::BigInt(_)
+            ) =>
+        {
+            ranges.iter().find(|range| column.has_name(&range.column_name))
+        }
+        _ => None,
+    };
+    let range = match range {
+        Some(range) => range,
+        None => return ScanMethod::Sequential,
+    };
+    let rows = statistics
+        .map(|statistics| statistics.rows() as f64)
+        .unwrap_or(DEFAULT_ROWS);
+    let selectivity = statistics
+        .and_then(|statistics| statistics.range_selectivity(&range.column_name, range.lower, range.upper))
+        .unwrap_or_else(|| default_selectivity(range));
+    if range_scan_cost(rows, selectivity) < seq_scan_cost(rows) {
+        ScanMethod::PrimaryKeyRange
+    } else {
+        ScanMethod::Sequential
+    }
+}
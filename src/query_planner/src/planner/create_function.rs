@@ -0,0 +1,128 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{FunctionCreationInfo, Plan},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName,
+};
+use data_manager::{DataManager, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sql_model::sql_types::SqlType;
+use sqlparser::ast::{DataType, Ident, ObjectName};
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct CreateFunctionPlanner<'cfp> {
+    function_name: &'cfp ObjectName,
+    or_replace: bool,
+    parameters: &'cfp [(Ident, DataType)],
+    return_type: &'cfp DataType,
+    body: &'cfp str,
+    role: &'cfp str,
+}
+
+impl<'cfp> CreateFunctionPlanner<'cfp> {
+    pub(crate) fn new(
+        function_name: &'cfp ObjectName,
+        or_replace: bool,
+        parameters: &'cfp [(Ident, DataType)],
+        return_type: &'cfp DataType,
+        body: &'cfp str,
+        role: &'cfp str,
+    ) -> CreateFunctionPlanner<'cfp> {
+        CreateFunctionPlanner {
+            function_name,
+            or_replace,
+            parameters,
+            return_type,
+            body,
+            role,
+        }
+    }
+}
+
+fn sql_type(data_type: &DataType, sender: &dyn Sender) -> Result<SqlType> {
+    match SqlType::try_from(data_type) {
+        Ok(sql_type) => Ok(sql_type),
+        Err(error) => {
+            sender
+                .send(Err(QueryError::feature_not_supported(error)))
+                .expect("To Send Query Result to Client");
+            Err(())
+        }
+    }
+}
+
+impl Planner for CreateFunctionPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let full_function_name = match FullTableName::try_from(self.function_name) {
+            Ok(full_function_name) => full_function_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, function_name) = full_function_name.as_tuple();
+        // calls are resolved by lowercase function names
+        let function_name = function_name.to_lowercase();
+        let schema_id = match data_manager.schema_exists(&schema_name) {
+            Some(schema_id) => schema_id,
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        check_schema_privilege(
+            &data_manager,
+            sender.as_ref(),
+            self.role,
+            (schema_id, schema_name),
+            SchemaPrivilege::Create,
+        )?;
+        if !self.or_replace && data_manager.function(schema_id, &function_name).is_some() {
+            sender
+                .send(Err(QueryError::function_already_exists(&full_function_name)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let mut parameters = Vec::with_capacity(self.parameters.len());
+        for (index, (name, data_type)) in self.parameters.iter().enumerate() {
+            if self.parameters[..index]
+                .iter()
+                .any(|(other, _)| other.value == name.value)
+            {
+                sender
+                    .send(Err(QueryError::invalid_function_definition(format!(
+                        "parameter name \"{}\" used more than once",
+                        name.value
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            parameters.push((name.value.clone(), sql_type(data_type, sender.as_ref())?));
+        }
+        Ok(Plan::CreateFunction(FunctionCreationInfo {
+            schema_id,
+            function_name,
+            or_replace: self.or_replace,
+            parameters,
+            return_type: sql_type(self.return_type, sender.as_ref())?,
+            body: self.body.to_owned(),
+        }))
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{Plan, TableCreationInfo, TablePartitioning},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName, TableId,
+};
+use data_manager::{ColumnDefinition, DataManager, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sql_model::Id;
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct CreatePartitionPlanner<'cpp> {
+    full_table_name: &'cpp ObjectName,
+    parent_name: &'cpp ObjectName,
+    from: Option<i64>,
+    to: Option<i64>,
+    role: &'cpp str,
+}
+
+impl<'cpp> CreatePartitionPlanner<'cpp> {
+    pub(crate) fn new(
+        full_table_name: &'cpp ObjectName,
+        parent_name: &'cpp ObjectName,
+        from: Option<i64>,
+        to: Option<i64>,
+        role: &'cpp str,
+    ) -> CreatePartitionPlanner<'cpp> {
+        CreatePartitionPlanner {
+            full_table_name,
+            parent_name,
+            from,
+            to,
+            role,
+        }
+    }
+
+    fn parent_id(&self, data_manager: &DataManager, sender: &dyn Sender) -> Result<(Id, Id)> {
+        let parent_name = match FullTableName::try_from(self.parent_name) {
+            Ok(parent_name) => parent_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, table_name) = parent_name.as_tuple();
+        let full_table_id = data_manager.table_exists(&schema_name, &table_name);
+        if let Some((schema_id, _)) = full_table_id {
+            check_schema_privilege(
+                data_manager,
+                sender,
+                self.role,
+                (schema_id, schema_name),
+                SchemaPrivilege::Usage,
+            )?;
+        }
+        match full_table_id {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(parent_name)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Some((schema_id, Some(table_id))) => Ok((schema_id, table_id)),
+        }
+    }
+
+    /// bounds have to be a non empty range that does not overlap other partitions
+    fn check_bounds(&self, data_manager: &DataManager, parent_id: (Id, Id), sender: &dyn Sender) -> Result<()> {
+        let partitioning = match data_manager.range_partitioning(&Box::new(parent_id)) {
+            Some(partitioning) => partitioning,
+            None => {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "table \"{}\" is not partitioned",
+                        self.parent_name
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let table_name = self
+            .full_table_name
+            .0
+            .last()
+            .map(|name| name.value.as_str())
+            .unwrap_or_default();
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "empty range bound specified for partition \"{}\"",
+                        table_name
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        }
+        let overlapped = partitioning
+            .partitions()
+            .iter()
+            .find(|partition| partition.overlaps(self.from, self.to));
+        if let Some(partition) = overlapped {
+            let partition_name = data_manager
+                .table_name(&Box::new(partition.table_id()))
+                .map(|(_schema_name, partition_name)| partition_name)
+                .unwrap_or_default();
+            sender
+                .send(Err(QueryError::invalid_object_definition(format!(
+                    "partition \"{}\" would overlap partition \"{}\"",
+                    table_name, partition_name
+                ))))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
+impl Planner for CreatePartitionPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let full_table_name = match FullTableName::try_from(self.full_table_name) {
+            Ok(full_table_name) => full_table_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, table_name) = full_table_name.as_tuple();
+        let full_table_id = data_manager.table_exists(&schema_name, &table_name);
+        if let Some((schema_id, _)) = full_table_id {
+            check_schema_privilege(
+                &data_manager,
+                sender.as_ref(),
+                self.role,
+                (schema_id, schema_name),
+                SchemaPrivilege::Create,
+            )?;
+        }
+        let schema_id = match full_table_id {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            Some((_, Some(_))) => {
+                sender
+                    .send(Err(QueryError::table_already_exists(full_table_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            Some((schema_id, None)) => schema_id,
+        };
+        let parent_id = self.parent_id(&data_manager, sender.as_ref())?;
+        self.check_bounds(&data_manager, parent_id, sender.as_ref())?;
+        let columns = data_manager
+            .table_columns(&Box::new(parent_id))
+            .map_err(|_| ())?
+            .into_iter()
+            .map(ColumnDefinition::without_serial)
+            .collect();
+        Ok(Plan::CreateTable(
+            TableCreationInfo::new(schema_id, table_name, columns).partitioned(TablePartitioning::RangeOf {
+                parent: TableId(parent_id),
+                from: self.from,
+                to: self.to,
+            }),
+        ))
+    }
+}
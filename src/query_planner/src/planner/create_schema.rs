@@ -11,44 +11,91 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-
 use crate::{
     plan::{Plan, SchemaCreationInfo},
-    planner::{Planner, Result},
+    planner::{check_schema_privilege, Planner, Result},
     SchemaName,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, SchemaPrivilege};
 use protocol::{results::QueryError, Sender};
 use sqlparser::ast::ObjectName;
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct CreateSchemaPlanner<'csp> {
     schema_name: &'csp ObjectName,
+    /// template schema, whether its rows are copied and the role that creates the schema
+    template: Option<(&'csp ObjectName, bool, &'csp str)>,
 }
 
-impl CreateSchemaPlanner<'_> {
-    pub(crate) fn new(schema_name: &ObjectName) -> CreateSchemaPlanner<'_> {
-        CreateSchemaPlanner { schema_name }
+impl<'csp> CreateSchemaPlanner<'csp> {
+    pub(crate) fn new(schema_name: &'csp ObjectName) -> CreateSchemaPlanner<'csp> {
+        CreateSchemaPlanner {
+            schema_name,
+            template: None,
+        }
+    }
+
+    pub(crate) fn like(
+        schema_name: &'csp ObjectName,
+        template_name: &'csp ObjectName,
+        with_data: bool,
+        role: &'csp str,
+    ) -> CreateSchemaPlanner<'csp> {
+        CreateSchemaPlanner {
+            schema_name,
+            template: Some((template_name, with_data, role)),
+        }
     }
 }
 
 impl Planner for CreateSchemaPlanner<'_> {
     fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
-        match SchemaName::try_from(self.schema_name) {
-            Ok(schema_name) => match data_manager.schema_exists(&schema_name) {
-                Some(_) => {
-                    sender
-                        .send(Err(QueryError::schema_already_exists(schema_name)))
-                        .expect("To Send Query Result to Client");
-                    Err(())
-                }
-                None => Ok(Plan::CreateSchema(SchemaCreationInfo::new(schema_name))),
-            },
+        let schema_name = match SchemaName::try_from(self.schema_name) {
+            Ok(schema_name) => schema_name,
             Err(error) => {
                 sender
                     .send(Err(QueryError::syntax_error(error)))
                     .expect("To Send Query Result to Client");
-                Err(())
+                return Err(());
+            }
+        };
+        if data_manager.schema_exists(&schema_name).is_some() {
+            sender
+                .send(Err(QueryError::schema_already_exists(schema_name)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let creation_info = SchemaCreationInfo::new(&schema_name);
+        match self.template {
+            None => Ok(Plan::CreateSchema(creation_info)),
+            Some((template_name, with_data, role)) => {
+                let template_name = match SchemaName::try_from(template_name) {
+                    Ok(template_name) => template_name,
+                    Err(error) => {
+                        sender
+                            .send(Err(QueryError::syntax_error(error)))
+                            .expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                };
+                match data_manager.schema_exists(&template_name) {
+                    Some(template_id) => {
+                        check_schema_privilege(
+                            &data_manager,
+                            sender.as_ref(),
+                            role,
+                            (template_id, template_name.as_ref()),
+                            SchemaPrivilege::Usage,
+                        )?;
+                        Ok(Plan::CreateSchema(creation_info.like(template_id, with_data)))
+                    }
+                    None => {
+                        sender
+                            .send(Err(QueryError::schema_does_not_exist(template_name)))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,89 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{Plan, SequenceCreationInfo},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName,
+};
+use data_manager::{DataManager, SchemaPrivilege, SequenceOptions};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct CreateSequencePlanner<'csp> {
+    sequence_name: &'csp ObjectName,
+    if_not_exists: bool,
+    options: SequenceOptions,
+    role: &'csp str,
+}
+
+impl<'csp> CreateSequencePlanner<'csp> {
+    pub(crate) fn new(
+        sequence_name: &'csp ObjectName,
+        if_not_exists: bool,
+        options: SequenceOptions,
+        role: &'csp str,
+    ) -> CreateSequencePlanner<'csp> {
+        CreateSequencePlanner {
+            sequence_name,
+            if_not_exists,
+            options,
+            role,
+        }
+    }
+}
+
+impl Planner for CreateSequencePlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let full_sequence_name = match FullTableName::try_from(self.sequence_name) {
+            Ok(full_sequence_name) => full_sequence_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, sequence_name) = full_sequence_name.as_tuple();
+        let schema_id = match data_manager.schema_exists(&schema_name) {
+            Some(schema_id) => schema_id,
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        check_schema_privilege(
+            &data_manager,
+            sender.as_ref(),
+            self.role,
+            (schema_id, schema_name),
+            SchemaPrivilege::Create,
+        )?;
+        if !self.if_not_exists && data_manager.sequence_exists(schema_id, sequence_name) {
+            sender
+                .send(Err(QueryError::sequence_already_exists(&full_sequence_name)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        Ok(Plan::CreateSequence(SequenceCreationInfo {
+            schema_id,
+            sequence_name: sequence_name.to_owned(),
+            if_not_exists: self.if_not_exists,
+            options: self.options,
+        }))
+    }
+}
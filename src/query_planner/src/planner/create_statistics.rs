@@ -0,0 +1,155 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{Plan, StatisticsCreationInfo},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName, TableId,
+};
+use data_manager::{DataManager, SchemaPrivilege, StatisticsKind};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::{Ident, ObjectName};
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct CreateStatisticsPlanner<'csp> {
+    statistics_name: &'csp ObjectName,
+    if_not_exists: bool,
+    kinds: &'csp [StatisticsKind],
+    columns: &'csp [Ident],
+    table_name: &'csp ObjectName,
+    role: &'csp str,
+}
+
+impl<'csp> CreateStatisticsPlanner<'csp> {
+    pub(crate) fn new(
+        statistics_name: &'csp ObjectName,
+        if_not_exists: bool,
+        kinds: &'csp [StatisticsKind],
+        columns: &'csp [Ident],
+        table_name: &'csp ObjectName,
+        role: &'csp str,
+    ) -> CreateStatisticsPlanner<'csp> {
+        CreateStatisticsPlanner {
+            statistics_name,
+            if_not_exists,
+            kinds,
+            columns,
+            table_name,
+            role,
+        }
+    }
+}
+
+impl Planner for CreateStatisticsPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let (full_statistics_name, full_table_name) = match (
+            FullTableName::try_from(self.statistics_name),
+            FullTableName::try_from(self.table_name),
+        ) {
+            (Ok(full_statistics_name), Ok(full_table_name)) => (full_statistics_name, full_table_name),
+            (Err(error), _) | (_, Err(error)) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, statistics_name) = full_statistics_name.as_tuple();
+        let schema_id = match data_manager.schema_exists(&schema_name) {
+            Some(schema_id) => schema_id,
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        check_schema_privilege(
+            &data_manager,
+            sender.as_ref(),
+            self.role,
+            (schema_id, schema_name),
+            SchemaPrivilege::Create,
+        )?;
+        let (table_schema_name, table_name) = full_table_name.as_tuple();
+        let table_id = match data_manager.table_exists(&table_schema_name, &table_name) {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(table_schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(&full_table_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            Some((table_schema_id, Some(table_id))) => {
+                check_schema_privilege(
+                    &data_manager,
+                    sender.as_ref(),
+                    self.role,
+                    (table_schema_id, table_schema_name),
+                    SchemaPrivilege::Usage,
+                )?;
+                (table_schema_id, table_id)
+            }
+        };
+        if !self.if_not_exists && data_manager.statistics_exists(schema_id, statistics_name) {
+            sender
+                .send(Err(QueryError::statistics_already_exists(statistics_name)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        if self.columns.len() < 2 {
+            sender
+                .send(Err(QueryError::invalid_object_definition(
+                    "extended statistics require at least 2 columns",
+                )))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let table_columns = data_manager.table_columns(&Box::new(table_id)).unwrap_or_default();
+        let mut columns: Vec<String> = vec![];
+        for column in self.columns {
+            if !table_columns
+                .iter()
+                .any(|table_column| table_column.has_name(&column.value))
+            {
+                sender
+                    .send(Err(QueryError::column_does_not_exist(&column.value)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            if columns.contains(&column.value) {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(
+                        "duplicate column name in statistics definition",
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            columns.push(column.value.clone());
+        }
+        Ok(Plan::CreateStatistics(StatisticsCreationInfo {
+            schema_id,
+            statistics_name: statistics_name.to_owned(),
+            if_not_exists: self.if_not_exists,
+            table_id: TableId(table_id),
+            columns,
+            kinds: self.kinds.to_vec(),
+        }))
+    }
+}
@@ -13,26 +13,262 @@
 // limitations under the License.
 
 use crate::{
-    plan::{Plan, TableCreationInfo},
-    planner::{Planner, Result},
+    plan::{Plan, TableCreationInfo, TablePartitioning},
+    planner::{check_schema_privilege, is_integer, Planner, Result},
     FullTableName,
 };
-use data_manager::{ColumnDefinition, DataManager};
-use protocol::{results::QueryError, Sender};
-use sql_model::sql_types::SqlType;
-use sqlparser::ast::{ColumnDef, ObjectName};
+use data_manager::{ColumnDefinition, ColumnReference, DataManager, EnumType, ReferentialAction, SchemaPrivilege};
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sql_model::{sql_types::SqlType, Id};
+use sqlparser::ast::{self, ColumnDef, ColumnOption, DataType, Expr, Ident, ObjectName, TableConstraint};
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct CreateTablePlanner<'ctp> {
     full_table_name: &'ctp ObjectName,
     columns: &'ctp [ColumnDef],
+    constraints: &'ctp [TableConstraint],
+    generated_columns: &'ctp [(Ident, Expr)],
+    partition_by: Option<&'ctp Ident>,
+    if_not_exists: bool,
+    role: &'ctp str,
 }
 
 impl<'ctp> CreateTablePlanner<'ctp> {
-    pub(crate) fn new(full_table_name: &'ctp ObjectName, columns: &'ctp [ColumnDef]) -> CreateTablePlanner<'ctp> {
+    pub(crate) fn new(
+        full_table_name: &'ctp ObjectName,
+        columns: &'ctp [ColumnDef],
+        constraints: &'ctp [TableConstraint],
+        generated_columns: &'ctp [(Ident, Expr)],
+        partition_by: Option<&'ctp Ident>,
+        if_not_exists: bool,
+        role: &'ctp str,
+    ) -> CreateTablePlanner<'ctp> {
         CreateTablePlanner {
             full_table_name,
             columns,
+            constraints,
+            generated_columns,
+            partition_by,
+            if_not_exists,
+            role,
+        }
+    }
+}
+
+fn referential_action(action: &Option<ast::ReferentialAction>, sender: &dyn Sender) -> Result<ReferentialAction> {
+    match action {
+        None | Some(ast::ReferentialAction::NoAction) => Ok(ReferentialAction::NoAction),
+        Some(ast::ReferentialAction::Restrict) => Ok(ReferentialAction::Restrict),
+        Some(ast::ReferentialAction::Cascade) => Ok(ReferentialAction::Cascade),
+        Some(ast::ReferentialAction::SetNull) => Ok(ReferentialAction::SetNull),
+        Some(action) => {
+            sender
+                .send(Err(QueryError::feature_not_supported(format!(
+                    "ON DELETE {} is not supported",
+                    action
+                ))))
+                .expect("To Send Result to Client");
+            Err(())
+        }
+    }
+}
+
+/// `serial` types are integer columns that take their default values from an owned sequence
+fn is_serial(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Custom(name) => matches!(
+            name.to_string().to_lowercase().as_str(),
+            "serial" | "smallserial" | "bigserial"
+        ),
+        _ => false,
+    }
+}
+
+/// enum types are looked up in the schema of the table unless their names are qualified
+fn enum_type(data_manager: &DataManager, schema_id: Id, data_type: &DataType) -> Option<EnumType> {
+    match data_type {
+        DataType::Custom(name) => match name.0.as_slice() {
+            [type_name] => data_manager.enum_type(schema_id, &type_name.value),
+            [schema_name, type_name] => data_manager
+                .schema_exists(&schema_name.value)
+                .and_then(|schema_id| data_manager.enum_type(schema_id, &type_name.value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// names of columns that the expression reads
+fn expression_columns(expr: &Expr, columns: &mut Vec<String>) {
+    match expr {
+        Expr::Identifier(ident) => columns.push(ident.value.clone()),
+        Expr::CompoundIdentifier(idents) => columns.extend(idents.last().map(|ident| ident.value.clone())),
+        Expr::BinaryOp { left, right, .. } => {
+            expression_columns(left, columns);
+            expression_columns(right, columns);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => expression_columns(expr, columns),
+        Expr::Function(function) => {
+            for arg in function.args.iter() {
+                expression_columns(arg, columns);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// generation expressions can read only columns that are not generated
+fn generate_columns(
+    column_defs: &mut [ColumnDefinition],
+    generated_columns: &[(Ident, Expr)],
+    sender: &dyn Sender,
+) -> Result<()> {
+    for (column_name, expr) in generated_columns {
+        let mut dependencies = vec![];
+        expression_columns(expr, &mut dependencies);
+        for dependency in dependencies {
+            let is_generated = generated_columns
+                .iter()
+                .any(|(generated, _)| generated.value == dependency);
+            if is_generated {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "cannot use generated column \"{}\" in column generation expression",
+                        dependency
+                    ))))
+                    .expect("To Send Result to Client");
+                return Err(());
+            }
+            if !column_defs.iter().any(|column_def| column_def.has_name(&dependency)) {
+                sender
+                    .send(Err(QueryError::column_does_not_exist(dependency)))
+                    .expect("To Send Result to Client");
+                return Err(());
+            }
+        }
+        match column_defs
+            .iter()
+            .position(|column_def| column_def.has_name(&column_name.value))
+        {
+            Some(index) if column_defs[index].serial_sequence().is_some() => {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "both default and generation expression specified for column \"{}\"",
+                        column_name
+                    ))))
+                    .expect("To Send Result to Client");
+                return Err(());
+            }
+            Some(index) => column_defs[index] = column_defs[index].clone().generated_always_as(&expr.to_string()),
+            None => {
+                sender
+                    .send(Err(QueryError::column_does_not_exist(column_name)))
+                    .expect("To Send Result to Client");
+                return Err(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// only integer columns can be range partition keys
+fn partition_key(
+    column_defs: &[ColumnDefinition],
+    column_name: &Ident,
+    sender: &dyn Sender,
+) -> Result<TablePartitioning> {
+    match column_defs
+        .iter()
+        .find(|column_def| column_def.has_name(&column_name.value))
+    {
+        Some(column_def) if is_integer(column_def.sql_type()) => Ok(TablePartitioning::ByRange(column_def.name())),
+        Some(_) => {
+            sender
+                .send(Err(QueryError::feature_not_supported(format!(
+                    "range partition key \"{}\" of non integer type",
+                    column_name
+                ))))
+                .expect("To Send Result to Client");
+            Err(())
+        }
+        None => {
+            sender
+                .send(Err(QueryError::column_does_not_exist(column_name)))
+                .expect("To Send Result to Client");
+            Err(())
+        }
+    }
+}
+
+fn column_reference(
+    data_manager: &DataManager,
+    sender: &dyn Sender,
+    foreign_table: &ObjectName,
+    referred_columns: &[Ident],
+    on_delete: ReferentialAction,
+) -> Result<ColumnReference> {
+    let full_table_name = match FullTableName::try_from(foreign_table) {
+        Ok(full_table_name) => full_table_name,
+        Err(error) => {
+            sender
+                .send(Err(QueryError::syntax_error(error)))
+                .expect("To Send Result to Client");
+            return Err(());
+        }
+    };
+    let (schema_name, table_name) = full_table_name.as_tuple();
+    let table_id = match data_manager.table_exists(&schema_name, &table_name) {
+        Some((schema_id, Some(table_id))) => Box::new((schema_id, table_id)),
+        Some((_, None)) => {
+            sender
+                .send(Err(QueryError::table_does_not_exist(full_table_name)))
+                .expect("To Send Result to Client");
+            return Err(());
+        }
+        None => {
+            sender
+                .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                .expect("To Send Result to Client");
+            return Err(());
+        }
+    };
+    let primary_key = data_manager
+        .table_columns(&table_id)
+        .map_err(|_| ())?
+        .into_iter()
+        .filter(ColumnDefinition::is_primary_key)
+        .collect::<Vec<_>>();
+    let referred_column = match referred_columns {
+        [] => primary_key.first().map(ColumnDefinition::name),
+        [column] => Some(column.value.clone()),
+        _ => {
+            sender
+                .send(Err(QueryError::feature_not_supported(
+                    "multi-column foreign keys are not supported",
+                )))
+                .expect("To Send Result to Client");
+            return Err(());
+        }
+    };
+    match (referred_column, primary_key.as_slice()) {
+        (Some(column_name), [key]) if key.has_name(&column_name) => Ok(ColumnReference::new(
+            schema_name,
+            table_name,
+            column_name.as_str(),
+            on_delete,
+        )),
+        _ => {
+            sender
+                .send(Err(QueryError::invalid_foreign_key(full_table_name)))
+                .expect("To Send Result to Client");
+            Err(())
         }
     }
 }
@@ -42,13 +278,31 @@ impl Planner for CreateTablePlanner<'_> {
         match FullTableName::try_from(self.full_table_name) {
             Ok(full_table_name) => {
                 let (schema_name, table_name) = full_table_name.as_tuple();
-                match data_manager.table_exists(&schema_name, &table_name) {
+                let full_table_id = data_manager.table_exists(&schema_name, &table_name);
+                if let Some((schema_id, _)) = full_table_id {
+                    check_schema_privilege(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name),
+                        SchemaPrivilege::Create,
+                    )?;
+                }
+                match full_table_id {
                     None => {
                         sender
                             .send(Err(QueryError::schema_does_not_exist(schema_name)))
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
+                    Some((_, Some(_))) if self.if_not_exists => {
+                        sender
+                            .send(Ok(QueryEvent::Skipped(QueryError::table_already_exists(
+                                full_table_name,
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        Ok(Plan::Skipped(QueryEvent::TableCreated))
+                    }
                     Some((_, Some(_))) => {
                         sender
                             .send(Err(QueryError::table_already_exists(full_table_name)))
@@ -57,24 +311,167 @@ impl Planner for CreateTablePlanner<'_> {
                     }
                     Some((schema_id, None)) => {
                         let mut column_defs = Vec::new();
+                        let mut has_primary_key = false;
                         for column in self.columns {
-                            match SqlType::try_from(&column.data_type) {
-                                Ok(sql_type) => {
-                                    column_defs.push(ColumnDefinition::new(column.name.value.as_str(), sql_type))
+                            let mut column_def = match SqlType::try_from(&column.data_type) {
+                                Ok(sql_type) => ColumnDefinition::new(column.name.value.as_str(), sql_type),
+                                Err(error) => match enum_type(&data_manager, schema_id, &column.data_type) {
+                                    Some(enum_type) => ColumnDefinition::new(column.name.value.as_str(), SqlType::Enum)
+                                        .with_enum_type(enum_type),
+                                    None => {
+                                        sender
+                                            .send(Err(QueryError::feature_not_supported(error)))
+                                            .expect("To Send Result to Client");
+                                        return Err(());
+                                    }
+                                },
+                            };
+                            if is_serial(&column.data_type) {
+                                let sequence_name = format!("{}_{}_seq", table_name, column.name.value);
+                                if data_manager.sequence_exists(schema_id, &sequence_name) {
+                                    sender
+                                        .send(Err(QueryError::sequence_already_exists(format!(
+                                            "{}.{}",
+                                            schema_name, sequence_name
+                                        ))))
+                                        .expect("To Send Result to Client");
+                                    return Err(());
+                                }
+                                column_def = column_def.not_null().serial(&sequence_name);
+                            }
+                            if column
+                                .options
+                                .iter()
+                                .any(|option_def| matches!(option_def.option, ColumnOption::NotNull))
+                            {
+                                column_def = column_def.not_null();
+                            }
+                            for option_def in &column.options {
+                                if let ColumnOption::ForeignKey {
+                                    foreign_table,
+                                    referred_columns,
+                                    on_delete,
+                                    on_update,
+                                } = &option_def.option
+                                {
+                                    if !matches!(
+                                        on_update,
+                                        None | Some(ast::ReferentialAction::NoAction)
+                                            | Some(ast::ReferentialAction::Restrict)
+                                    ) {
+                                        sender
+                                            .send(Err(QueryError::feature_not_supported(
+                                                "ON UPDATE actions are not supported",
+                                            )))
+                                            .expect("To Send Result to Client");
+                                        return Err(());
+                                    }
+                                    let on_delete = referential_action(on_delete, sender.as_ref())?;
+                                    column_def = column_def.references(column_reference(
+                                        &data_manager,
+                                        sender.as_ref(),
+                                        foreign_table,
+                                        referred_columns,
+                                        on_delete,
+                                    )?);
+                                }
+                            }
+                            let is_primary_key = column.options.iter().any(|option_def| {
+                                matches!(option_def.option, ColumnOption::Unique { is_primary: true })
+                            });
+                            if is_primary_key {
+                                if has_primary_key {
+                                    sender
+                                        .send(Err(QueryError::multiple_primary_keys(table_name)))
+                                        .expect("To Send Result to Client");
+                                    return Err(());
                                 }
-                                Err(error) => {
+                                has_primary_key = true;
+                                column_defs.push(column_def.primary_key());
+                            } else {
+                                column_defs.push(column_def);
+                            }
+                        }
+                        for constraint in self.constraints {
+                            if let TableConstraint::Unique {
+                                columns,
+                                is_primary: true,
+                                ..
+                            } = constraint
+                            {
+                                if has_primary_key {
                                     sender
-                                        .send(Err(QueryError::feature_not_supported(error)))
+                                        .send(Err(QueryError::multiple_primary_keys(table_name)))
                                         .expect("To Send Result to Client");
                                     return Err(());
                                 }
+                                has_primary_key = true;
+                                for column_name in columns {
+                                    match column_defs
+                                        .iter()
+                                        .position(|column_def| column_def.has_name(&column_name.value))
+                                    {
+                                        Some(index) => column_defs[index] = column_defs[index].clone().primary_key(),
+                                        None => {
+                                            sender
+                                                .send(Err(QueryError::column_does_not_exist(column_name)))
+                                                .expect("To Send Result to Client");
+                                            return Err(());
+                                        }
+                                    }
+                                }
+                            }
+                            if let TableConstraint::ForeignKey {
+                                columns,
+                                foreign_table,
+                                referred_columns,
+                                ..
+                            } = constraint
+                            {
+                                let column_name = match columns.as_slice() {
+                                    [column_name] => column_name,
+                                    _ => {
+                                        sender
+                                            .send(Err(QueryError::feature_not_supported(
+                                                "multi-column foreign keys are not supported",
+                                            )))
+                                            .expect("To Send Result to Client");
+                                        return Err(());
+                                    }
+                                };
+                                let reference = column_reference(
+                                    &data_manager,
+                                    sender.as_ref(),
+                                    foreign_table,
+                                    referred_columns,
+                                    ReferentialAction::NoAction,
+                                )?;
+                                match column_defs
+                                    .iter()
+                                    .position(|column_def| column_def.has_name(&column_name.value))
+                                {
+                                    Some(index) => {
+                                        column_defs[index] = column_defs[index].clone().references(reference)
+                                    }
+                                    None => {
+                                        sender
+                                            .send(Err(QueryError::column_does_not_exist(column_name)))
+                                            .expect("To Send Result to Client");
+                                        return Err(());
+                                    }
+                                }
                             }
                         }
-                        Ok(Plan::CreateTable(TableCreationInfo::new(
-                            schema_id,
-                            table_name,
-                            column_defs,
-                        )))
+                        generate_columns(&mut column_defs, self.generated_columns, sender.as_ref())?;
+                        let partitioning = match self.partition_by {
+                            Some(column_name) => Some(partition_key(&column_defs, column_name, sender.as_ref())?),
+                            None => None,
+                        };
+                        let creation_info = TableCreationInfo::new(schema_id, table_name, column_defs);
+                        match partitioning {
+                            Some(partitioning) => Ok(Plan::CreateTable(creation_info.partitioned(partitioning))),
+                            None => Ok(Plan::CreateTable(creation_info)),
+                        }
                     }
                 }
             }
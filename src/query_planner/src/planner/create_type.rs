@@ -0,0 +1,96 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{EnumTypeCreationInfo, Plan},
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName,
+};
+use data_manager::{DataManager, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct CreateEnumTypePlanner<'cetp> {
+    type_name: &'cetp ObjectName,
+    labels: &'cetp [String],
+    role: &'cetp str,
+}
+
+impl<'cetp> CreateEnumTypePlanner<'cetp> {
+    pub(crate) fn new(
+        type_name: &'cetp ObjectName,
+        labels: &'cetp [String],
+        role: &'cetp str,
+    ) -> CreateEnumTypePlanner<'cetp> {
+        CreateEnumTypePlanner {
+            type_name,
+            labels,
+            role,
+        }
+    }
+}
+
+impl Planner for CreateEnumTypePlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let full_type_name = match FullTableName::try_from(self.type_name) {
+            Ok(full_type_name) => full_type_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let (schema_name, type_name) = full_type_name.as_tuple();
+        let schema_id = match data_manager.schema_exists(&schema_name) {
+            Some(schema_id) => schema_id,
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        check_schema_privilege(
+            &data_manager,
+            sender.as_ref(),
+            self.role,
+            (schema_id, schema_name),
+            SchemaPrivilege::Create,
+        )?;
+        if data_manager.enum_type(schema_id, type_name).is_some() {
+            sender
+                .send(Err(QueryError::type_already_exists(&full_type_name)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        for (index, label) in self.labels.iter().enumerate() {
+            if self.labels[..index].contains(label) {
+                sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "enum label \"{}\" used more than once",
+                        label
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        }
+        Ok(Plan::CreateEnumType(EnumTypeCreationInfo {
+            schema_id,
+            type_name: type_name.to_owned(),
+            labels: self.labels.to_vec(),
+        }))
+    }
+}
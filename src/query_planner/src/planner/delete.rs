@@ -14,21 +14,34 @@
 
 use crate::{
     plan::{Plan, TableDeletes},
-    planner::{Planner, Result},
+    planner::{check_schema_privilege, Planner, Result},
     FullTableName, TableId,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, SchemaPrivilege};
 use protocol::{results::QueryError, Sender};
-use sqlparser::ast::ObjectName;
+use sqlparser::ast::{Expr, ObjectName};
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct DeletePlanner<'dp> {
     table_name: &'dp ObjectName,
+    selection: Option<&'dp Expr>,
+    role: &'dp str,
+    journal: Option<&'dp Journal>,
 }
 
-impl DeletePlanner<'_> {
-    pub(crate) fn new(table_name: &ObjectName) -> DeletePlanner {
-        DeletePlanner { table_name }
+impl<'dp> DeletePlanner<'dp> {
+    pub(crate) fn new(table_name: &'dp ObjectName, selection: Option<&'dp Expr>, role: &'dp str) -> DeletePlanner<'dp> {
+        DeletePlanner {
+            table_name,
+            selection,
+            role,
+            journal: None,
+        }
+    }
+
+    pub(crate) fn with_journal(mut self, journal: Option<&'dp Journal>) -> DeletePlanner<'dp> {
+        self.journal = journal;
+        self
     }
 }
 
@@ -37,7 +50,17 @@ impl Planner for DeletePlanner<'_> {
         match FullTableName::try_from(self.table_name) {
             Ok(full_table_name) => {
                 let (schema_name, table_name) = full_table_name.as_tuple();
-                match data_manager.table_exists(&schema_name, &table_name) {
+                let full_table_id = data_manager.table_exists_in(self.journal, &schema_name, &table_name);
+                if let Some((schema_id, _)) = full_table_id {
+                    check_schema_privilege(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name),
+                        SchemaPrivilege::Usage,
+                    )?;
+                }
+                match full_table_id {
                     None => {
                         sender
                             .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -53,8 +76,22 @@ impl Planner for DeletePlanner<'_> {
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
+                    Some((schema_id, Some(table_id)))
+                        if data_manager
+                            .range_partitioning(&Box::new((schema_id, table_id)))
+                            .is_some() =>
+                    {
+                        sender
+                            .send(Err(QueryError::feature_not_supported(format!(
+                                "DELETE on partitioned table \"{}\"",
+                                full_table_name
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
                     Some((schema_id, Some(table_id))) => Ok(Plan::Delete(TableDeletes {
                         table_id: TableId((schema_id, table_id)),
+                        filter: self.selection.cloned(),
                     })),
                 }
             }
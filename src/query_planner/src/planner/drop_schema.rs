@@ -18,18 +18,26 @@ use crate::{
     SchemaId, SchemaName,
 };
 use data_manager::DataManager;
-use protocol::{results::QueryError, Sender};
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
 use sqlparser::ast::ObjectName;
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct DropSchemaPlanner<'dsp> {
     names: &'dsp [ObjectName],
+    if_exists: bool,
     cascade: bool,
 }
 
 impl DropSchemaPlanner<'_> {
-    pub(crate) fn new(names: &[ObjectName], cascade: bool) -> DropSchemaPlanner<'_> {
-        DropSchemaPlanner { names, cascade }
+    pub(crate) fn new(names: &[ObjectName], if_exists: bool, cascade: bool) -> DropSchemaPlanner<'_> {
+        DropSchemaPlanner {
+            names,
+            if_exists,
+            cascade,
+        }
     }
 }
 
@@ -39,6 +47,11 @@ impl Planner for DropSchemaPlanner<'_> {
         for name in self.names {
             match SchemaName::try_from(name) {
                 Ok(schema_name) => match data_manager.schema_exists(&schema_name) {
+                    None if self.if_exists => {
+                        sender
+                            .send(Ok(QueryEvent::Skipped(QueryError::schema_does_not_exist(schema_name))))
+                            .expect("To Send Query Result to Client");
+                    }
                     None => {
                         sender
                             .send(Err(QueryError::schema_does_not_exist(schema_name)))
@@ -55,6 +68,10 @@ impl Planner for DropSchemaPlanner<'_> {
                 }
             }
         }
-        Ok(Plan::DropSchemas(schemas))
+        if schemas.is_empty() {
+            Ok(Plan::Skipped(QueryEvent::SchemaDropped))
+        } else {
+            Ok(Plan::DropSchemas(schemas))
+        }
     }
 }
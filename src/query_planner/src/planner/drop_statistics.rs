@@ -0,0 +1,83 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::Plan,
+    planner::{check_schema_privilege, Planner, Result},
+    FullTableName,
+};
+use data_manager::{DataManager, SchemaPrivilege};
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct DropStatisticsPlanner<'dsp> {
+    names: &'dsp [ObjectName],
+    if_exists: bool,
+    role: &'dsp str,
+}
+
+impl<'dsp> DropStatisticsPlanner<'dsp> {
+    pub(crate) fn new(names: &'dsp [ObjectName], if_exists: bool, role: &'dsp str) -> DropStatisticsPlanner<'dsp> {
+        DropStatisticsPlanner { names, if_exists, role }
+    }
+}
+
+impl Planner for DropStatisticsPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let mut statistics = Vec::with_capacity(self.names.len());
+        for name in self.names {
+            let full_statistics_name = match FullTableName::try_from(name) {
+                Ok(full_statistics_name) => full_statistics_name,
+                Err(error) => {
+                    sender
+                        .send(Err(QueryError::syntax_error(error)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            };
+            let (schema_name, statistics_name) = full_statistics_name.as_tuple();
+            match data_manager.schema_exists(&schema_name) {
+                Some(schema_id) if data_manager.statistics_exists(schema_id, statistics_name) => {
+                    check_schema_privilege(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name),
+                        SchemaPrivilege::Usage,
+                    )?;
+                    statistics.push((schema_id, statistics_name.to_owned()));
+                }
+                missing => {
+                    let reason = match missing {
+                        None => QueryError::schema_does_not_exist(schema_name),
+                        Some(_) => QueryError::statistics_does_not_exist(&full_statistics_name),
+                    };
+                    if self.if_exists {
+                        sender
+                            .send(Ok(QueryEvent::Skipped(reason)))
+                            .expect("To Send Query Result to Client");
+                    } else {
+                        sender.send(Err(reason)).expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                }
+            }
+        }
+        Ok(Plan::DropStatistics(statistics))
+    }
+}
@@ -14,21 +14,38 @@
 
 use crate::{
     plan::Plan,
-    planner::{Planner, Result},
+    planner::{check_schema_privilege, Planner, Result},
     FullTableName, TableId,
 };
-use data_manager::DataManager;
-use protocol::{results::QueryError, Sender};
+use data_manager::{DataManager, Journal, SchemaPrivilege};
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
 use sqlparser::ast::ObjectName;
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct DropTablesPlanner<'dtp> {
     names: &'dtp [ObjectName],
+    if_exists: bool,
+    role: &'dtp str,
+    journal: Option<&'dtp Journal>,
 }
 
-impl DropTablesPlanner<'_> {
-    pub(crate) fn new(names: &[ObjectName]) -> DropTablesPlanner {
-        DropTablesPlanner { names }
+impl<'dtp> DropTablesPlanner<'dtp> {
+    pub(crate) fn new(names: &'dtp [ObjectName], if_exists: bool, role: &'dtp str) -> DropTablesPlanner<'dtp> {
+        DropTablesPlanner {
+            names,
+            if_exists,
+            role,
+            journal: None,
+        }
+    }
+
+    /// tables already dropped by the transaction of the journal do not exist for it
+    pub(crate) fn with_journal(mut self, journal: Option<&'dtp Journal>) -> DropTablesPlanner<'dtp> {
+        self.journal = journal;
+        self
     }
 }
 
@@ -39,7 +56,24 @@ impl Planner for DropTablesPlanner<'_> {
             match FullTableName::try_from(name) {
                 Ok(full_table_name) => {
                     let (schema_name, table_name) = full_table_name.as_tuple();
-                    match data_manager.table_exists(&schema_name, &table_name) {
+                    let full_table_id = data_manager.table_exists_in(self.journal, &schema_name, &table_name);
+                    if let Some((schema_id, _)) = full_table_id {
+                        check_schema_privilege(
+                            &data_manager,
+                            sender.as_ref(),
+                            self.role,
+                            (schema_id, schema_name),
+                            SchemaPrivilege::Usage,
+                        )?;
+                    }
+                    match full_table_id {
+                        None | Some((_, None)) if self.if_exists => {
+                            sender
+                                .send(Ok(QueryEvent::Skipped(QueryError::table_does_not_exist(
+                                    full_table_name,
+                                ))))
+                                .expect("To Send Query Result to Client");
+                        }
                         None => {
                             sender
                                 .send(Err(QueryError::schema_does_not_exist(schema_name)))
@@ -63,6 +97,10 @@ impl Planner for DropTablesPlanner<'_> {
                 }
             }
         }
-        Ok(Plan::DropTables(table_names))
+        if table_names.is_empty() {
+            Ok(Plan::Skipped(QueryEvent::TableDropped))
+        } else {
+            Ok(Plan::DropTables(table_names))
+        }
     }
 }
@@ -0,0 +1,263 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! constant subexpressions of `WHERE` are evaluated when a select is planned, so comparisons of columns with them
+//! bound scanned ranges and conjuncts that are always true are not evaluated for every row. Integer arithmetic and
+//! comparisons of integer literals and boolean logic of literals are folded, integers that fit `integer` are
+//! computed as `integer` and the others as `bigint` as the engine types literals. Operations that fail, e.g. on
+//! overflow or division by zero, are left to the engine to report.
+
+use sqlparser::ast::{BinaryOperator, Expr, UnaryOperator, Value};
+use std::cmp::Ordering;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Literal {
+    Integer(i64),
+    Boolean(bool),
+    Null,
+}
+
+fn literal(expr: &Expr) -> Option<Literal> {
+    match expr {
+        Expr::Value(Value::Number(number)) => number.to_string().parse().ok().map(Literal::Integer),
+        Expr::Value(Value::Boolean(value)) => Some(Literal::Boolean(*value)),
+        Expr::Value(Value::Null) => Some(Literal::Null),
+        _ => None,
+    }
+}
+
+fn value(literal: Literal) -> Expr {
+    Expr::Value(match literal {
+        Literal::Integer(value) => Value::Number(value.into()),
+        Literal::Boolean(value) => Value::Boolean(value),
+        Literal::Null => Value::Null,
+    })
+}
+
+fn fits_integer(value: i64) -> bool {
+    i64::from(i32::MIN) <= value && value <= i64::from(i32::MAX)
+}
+
+fn arithmetic(op: &BinaryOperator, left: i64, right: i64) -> Option<i64> {
+    if fits_integer(left) && fits_integer(right) {
+        let (left, right) = (left as i32, right as i32);
+        match op {
+            BinaryOperator::Plus => left.checked_add(right),
+            BinaryOperator::Minus => left.checked_sub(right),
+            BinaryOperator::Multiply => left.checked_mul(right),
+            BinaryOperator::Divide => left.checked_div(right),
+            BinaryOperator::Modulus => left.checked_rem(right),
+            _ => None,
+        }
+        .map(i64::from)
+    } else {
+        match op {
+            BinaryOperator::Plus => left.checked_add(right),
+            BinaryOperator::Minus => left.checked_sub(right),
+            BinaryOperator::Multiply => left.checked_mul(right),
+            BinaryOperator::Divide => left.checked_div(right),
+            BinaryOperator::Modulus => left.checked_rem(right),
+            _ => None,
+        }
+    }
+}
+
+fn comparison(op: &BinaryOperator, ordering: Ordering) -> Option<bool> {
+    match op {
+        BinaryOperator::Eq => Some(ordering == Ordering::Equal),
+        BinaryOperator::NotEq => Some(ordering != Ordering::Equal),
+        BinaryOperator::Lt => Some(ordering == Ordering::Less),
+        BinaryOperator::LtEq => Some(ordering != Ordering::Greater),
+        BinaryOperator::Gt => Some(ordering == Ordering::Greater),
+        BinaryOperator::GtEq => Some(ordering != Ordering::Less),
+        _ => None,
+    }
+}
+
+/// truth value of a boolean literal, NULL is unknown
+fn truth(literal: Literal) -> Option<Option<bool>> {
+    match literal {
+        Literal::Boolean(value) => Some(Some(value)),
+        Literal::Null => Some(None),
+        Literal::Integer(_) => None,
+    }
+}
+
+/// `AND` and `OR` are decided by an operand that is `false` or `true` respectively even when the other is unknown
+fn binary(op: &BinaryOperator, left: Literal, right: Literal) -> Option<Literal> {
+    match op {
+        BinaryOperator::And | BinaryOperator::Or => {
+            let (left, right) = (truth(left)?, truth(right)?);
+            let deciding = *op == BinaryOperator::Or;
+            Some(if left == Some(deciding) || right == Some(deciding) {
+                Literal::Boolean(deciding)
+            } else if left.is_none() || right.is_none() {
+                Literal::Null
+            } else {
+                Literal::Boolean(!deciding)
+            })
+        }
+        _ => match (left, right) {
+            (Literal::Integer(left), Literal::Integer(right)) => arithmetic(op, left, right)
+                .map(Literal::Integer)
+                .or_else(|| comparison(op, left.cmp(&right)).map(Literal::Boolean)),
+            (Literal::Boolean(left), Literal::Boolean(right)) => comparison(op, left.cmp(&right)).map(Literal::Boolean),
+            (Literal::Null, other) | (other, Literal::Null) => {
+                let is_arithmetic = arithmetic(op, 1, 1).is_some() && !matches!(other, Literal::Boolean(_));
+                if is_arithmetic || comparison(op, Ordering::Equal).is_some() {
+                    Some(Literal::Null)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+    }
+}
+
+fn unary(op: &UnaryOperator, operand: Literal) -> Option<Literal> {
+    match (op, operand) {
+        (UnaryOperator::Minus, Literal::Integer(value)) => value.checked_neg().map(Literal::Integer),
+        (UnaryOperator::Plus, Literal::Integer(value)) => Some(Literal::Integer(value)),
+        (UnaryOperator::Not, Literal::Boolean(value)) => Some(Literal::Boolean(!value)),
+        (_, Literal::Null) => Some(Literal::Null),
+        _ => None,
+    }
+}
+
+/// expression with its constant subexpressions replaced by their values
+pub(crate) fn fold_constants(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Nested(inner) => {
+            let inner = fold_constants(inner);
+            match literal(&inner) {
+                Some(_) => inner,
+                None => Expr::Nested(Box::new(inner)),
+            }
+        }
+        Expr::UnaryOp { op, expr } => {
+            let operand = Box::new(fold_constants(expr));
+            match literal(&operand).and_then(|operand| unary(op, operand)) {
+                Some(folded) => value(folded),
+                None => Expr::UnaryOp {
+                    op: op.clone(),
+                    expr: operand,
+                },
+            }
+        }
+        Expr::BinaryOp { left, op, right } => {
+            let (left, right) = (fold_constants(left), fold_constants(right));
+            // `true` operand of `AND` and `false` one of `OR` are dropped, operands that aren't literals are kept
+            // for the engine to type check them
+            let neutral = match op {
+                BinaryOperator::And => Some(Literal::Boolean(true)),
+                BinaryOperator::Or => Some(Literal::Boolean(false)),
+                _ => None,
+            };
+            match (literal(&left), literal(&right)) {
+                (Some(left), Some(right)) => {
+                    if let Some(folded) = binary(op, left, right) {
+                        return value(folded);
+                    }
+                }
+                (Some(literal), None) if Some(literal) == neutral => return right,
+                (None, Some(literal)) if Some(literal) == neutral => return left,
+                _ => {}
+            }
+            Expr::BinaryOp {
+                left: Box::new(left),
+                op: op.clone(),
+                right: Box::new(right),
+            }
+        }
+        Expr::IsNull(operand) => {
+            let operand = Box::new(fold_constants(operand));
+            match literal(&operand) {
+                Some(literal) => value(Literal::Boolean(literal == Literal::Null)),
+                None => Expr::IsNull(operand),
+            }
+        }
+        Expr::IsNotNull(operand) => {
+            let operand = Box::new(fold_constants(operand));
+            match literal(&operand) {
+                Some(literal) => value(Literal::Boolean(literal != Literal::Null)),
+                None => Expr::IsNotNull(operand),
+            }
+        }
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Expr::Between {
+            expr: Box::new(fold_constants(expr)),
+            negated: *negated,
+            low: Box::new(fold_constants(low)),
+            high: Box::new(fold_constants(high)),
+        },
+        Expr::InList { expr, list, negated } => Expr::InList {
+            expr: Box::new(fold_constants(expr)),
+            list: list.iter().map(fold_constants).collect(),
+            negated: *negated,
+        },
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(fold_constants(expr)),
+            data_type: data_type.clone(),
+        },
+        expr => expr.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser, tokenizer::Tokenizer};
+
+    fn folded(sql: &str) -> String {
+        let tokens = Tokenizer::new(&PostgreSqlDialect {}, sql)
+            .tokenize()
+            .expect("expression tokenized");
+        fold_constants(&Parser::new(tokens).parse_expr().expect("expression parsed")).to_string()
+    }
+
+    #[test]
+    fn integer_arithmetic_and_comparisons() {
+        assert_eq!(folded("2 + 3 * (4 - 1)"), "11");
+        assert_eq!(folded("-7 / 2"), "-3");
+        assert_eq!(folded("-7 % 2"), "-1");
+        assert_eq!(folded("1 = 1"), "true");
+        assert_eq!(folded("id > 2 + 3"), "id > 5");
+        assert_eq!(folded("id between 1 + 1 and 2 * 5"), "id BETWEEN 2 AND 10");
+    }
+
+    #[test]
+    fn failing_operations_are_not_folded() {
+        assert_eq!(folded("1 / 0"), "1 / 0");
+        assert_eq!(folded("2147483647 + 1"), "2147483647 + 1");
+        assert_eq!(folded("2147483648 + 1"), "2147483649");
+        assert_eq!(folded("1 + 1.5"), "1 + 1.5");
+    }
+
+    #[test]
+    fn three_valued_boolean_logic() {
+        assert_eq!(folded("not true or 1 < 2"), "true");
+        assert_eq!(folded("null and false"), "false");
+        assert_eq!(folded("null or false"), "NULL");
+        assert_eq!(folded("1 = null"), "NULL");
+        assert_eq!(folded("null is null and 1 is not null"), "true");
+        assert_eq!(folded("name = 'a' and 1 = 1"), "name = 'a'");
+        assert_eq!(folded("(name = 'a' or 1 > 2) and 1 = 0"), "(name = 'a') AND false");
+        assert_eq!(folded("(1 = 1)"), "true");
+    }
+}
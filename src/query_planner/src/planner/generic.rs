@@ -0,0 +1,93 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Expressions of generic plans that values of `$n` placeholders are bound to.
+use crate::{
+    logical::{ProjectedColumn, RelationOp},
+    plan::Plan,
+    planner::Result,
+};
+use protocol::{results::QueryResult, Sender};
+use sqlparser::ast::Expr;
+use std::io;
+
+/// errors of templates that are planned only to be cached are not sent to the client
+pub(crate) struct Discard;
+
+impl Sender for Discard {
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn send(&self, _query_result: QueryResult) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// predicates of filters and of window functions and projected expressions are bound, the scan has no predicates
+/// before optimizer passes
+pub(crate) fn bind_relation(relation: RelationOp, bind: &mut dyn FnMut(&mut Expr)) -> Result<RelationOp> {
+    let relation = match relation {
+        RelationOp::Filter { input, mut predicate } => {
+            bind(&mut predicate);
+            RelationOp::Filter { input, predicate }
+        }
+        RelationOp::Window { input, mut functions } => {
+            for filter in functions.iter_mut().filter_map(|function| function.filter.as_mut()) {
+                bind(filter);
+            }
+            RelationOp::Window { input, functions }
+        }
+        RelationOp::Project { input, mut columns } => {
+            for column in columns.iter_mut() {
+                if let ProjectedColumn::Expr(_, expr) = column {
+                    bind(expr);
+                }
+            }
+            RelationOp::Project { input, columns }
+        }
+        relation => relation,
+    };
+    relation.try_map_input(|input| bind_relation(input, bind))
+}
+
+/// values of inserted rows, assigned values, selected values and conditions of updates and deletes are bound
+pub(crate) fn bind_plan(plan: &mut Plan, bind: &mut dyn FnMut(&mut Expr)) {
+    match plan {
+        Plan::Insert(table_inserts) => {
+            for expr in table_inserts.input.iter_mut().flatten() {
+                bind(expr);
+            }
+        }
+        Plan::Update(table_updates) => {
+            for assignment in table_updates.assignments.iter_mut() {
+                bind(&mut assignment.value);
+            }
+            if let Some(filter) = table_updates.filter.as_mut() {
+                bind(filter);
+            }
+        }
+        Plan::Delete(table_deletes) => {
+            if let Some(filter) = table_deletes.filter.as_mut() {
+                bind(filter);
+            }
+        }
+        Plan::SelectValues(select_values) => {
+            for (_, expr) in select_values.values.iter_mut() {
+                bind(expr);
+            }
+        }
+        _ => {}
+    }
+}
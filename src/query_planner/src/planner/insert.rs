@@ -14,10 +14,10 @@
 
 use crate::{
     plan::{Plan, TableInserts},
-    planner::{Planner, Result},
+    planner::{check_schema_privilege, Planner, Result},
     FullTableName, TableId,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, SchemaPrivilege};
 use protocol::{results::QueryError, Sender};
 use sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
 use std::{convert::TryFrom, sync::Arc};
@@ -26,16 +26,30 @@ pub(crate) struct InsertPlanner<'ip> {
     table_name: &'ip ObjectName,
     columns: &'ip [Ident],
     source: &'ip Query,
+    role: &'ip str,
+    journal: Option<&'ip Journal>,
 }
 
 impl<'ip> InsertPlanner<'ip> {
-    pub(crate) fn new(table_name: &'ip ObjectName, columns: &'ip [Ident], source: &'ip Query) -> InsertPlanner<'ip> {
+    pub(crate) fn new(
+        table_name: &'ip ObjectName,
+        columns: &'ip [Ident],
+        source: &'ip Query,
+        role: &'ip str,
+    ) -> InsertPlanner<'ip> {
         InsertPlanner {
             table_name,
             columns,
             source,
+            role,
+            journal: None,
         }
     }
+
+    pub(crate) fn with_journal(mut self, journal: Option<&'ip Journal>) -> InsertPlanner<'ip> {
+        self.journal = journal;
+        self
+    }
 }
 
 impl Planner for InsertPlanner<'_> {
@@ -43,7 +57,17 @@ impl Planner for InsertPlanner<'_> {
         match FullTableName::try_from(self.table_name) {
             Ok(full_table_name) => {
                 let (schema_name, table_name) = full_table_name.as_tuple();
-                match data_manager.table_exists(&schema_name, &table_name) {
+                let full_table_id = data_manager.table_exists_in(self.journal, &schema_name, &table_name);
+                if let Some((schema_id, _)) = full_table_id {
+                    check_schema_privilege(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name),
+                        SchemaPrivilege::Usage,
+                    )?;
+                }
+                match full_table_id {
                     None => {
                         sender
                             .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -0,0 +1,119 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! physical plan of a select is lowered from its logical plan. The engine runs its operators in a fixed order,
+//! filter, window, sort, limit and project, so they are collected from the tree into fields of the plan. The scan
+//! method and the scanned partitions of the table are chosen here.
+
+use crate::{
+    logical::{ProjectedColumn, RelationOp},
+    plan::{ColumnRange, RowLimit, SelectInput, SelectedColumn},
+    planner::{cost::scan_method, projection::required_columns, Result},
+    TableId,
+};
+use data_manager::DataManager;
+
+pub(crate) fn lower(relation: RelationOp, data_manager: &DataManager) -> Result<SelectInput> {
+    let mut columns = vec![];
+    let mut limit = RowLimit::default();
+    let mut order_by = vec![];
+    let mut functions = vec![];
+    let mut predicates = vec![];
+    let mut operator = relation;
+    let (table_id, ranges, mut filters) = loop {
+        operator = match operator {
+            RelationOp::Project {
+                input,
+                columns: projected,
+            } => {
+                columns = projected;
+                *input
+            }
+            RelationOp::Limit {
+                input,
+                limit: row_limit,
+            } => {
+                limit = row_limit;
+                *input
+            }
+            RelationOp::Sort { input, order_by: keys } => {
+                order_by = keys;
+                *input
+            }
+            RelationOp::Window {
+                input,
+                functions: window_functions,
+            } => {
+                functions = window_functions;
+                *input
+            }
+            RelationOp::Filter { input, predicate } => {
+                predicates.push(predicate);
+                *input
+            }
+            RelationOp::Scan {
+                table_id,
+                ranges,
+                filters,
+                ..
+            } => break (table_id, ranges, filters),
+        }
+    };
+    filters.extend(predicates.into_iter().rev());
+
+    let selected_columns = columns
+        .into_iter()
+        .map(|column| match column {
+            ProjectedColumn::Column(name) => SelectedColumn::Column(name),
+            ProjectedColumn::Window(name, index) => SelectedColumn::Window(name, functions[index].clone()),
+            ProjectedColumn::Expr(name, expr) => SelectedColumn::Expr(name, expr),
+        })
+        .collect::<Vec<SelectedColumn>>();
+    let all_columns = data_manager.table_columns(&table_id).map_err(|_| ())?;
+    let scan = scan_method(&all_columns, &ranges, data_manager.analyzed(&table_id).as_ref());
+    let scanned_tables = scanned_tables(data_manager, &table_id, &ranges);
+    let required_columns = required_columns(&all_columns, &selected_columns, &order_by, &ranges, &filters);
+
+    Ok(SelectInput {
+        table_id,
+        selected_columns,
+        order_by,
+        limit,
+        ranges,
+        filters,
+        scanned_tables,
+        scan,
+        required_columns,
+    })
+}
+
+/// partitions that can't hold values of the partition key range are pruned
+fn scanned_tables(data_manager: &DataManager, table_id: &TableId, ranges: &[ColumnRange]) -> Vec<TableId> {
+    match data_manager.range_partitioning(table_id) {
+        None => vec![table_id.clone()],
+        Some(partitioning) => {
+            let (lower, upper) = ranges
+                .iter()
+                .find(|range| range.column_name == partitioning.column_name())
+                .map(|range| (range.lower, range.upper))
+                .unwrap_or((None, None));
+            partitioning
+                .partitions()
+                .iter()
+                .filter(|partition| partition.intersects(lower, upper))
+                .map(|partition| TableId(partition.table_id()))
+                .collect()
+        }
+    }
+}
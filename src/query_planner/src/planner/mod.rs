@@ -13,26 +13,72 @@
 // limitations under the License.
 
 ///! Module for transforming the input Query AST into representation the engine can process.
+mod alter_schema;
+mod analyze;
+mod coercion;
+mod copy;
+mod cost;
+mod create_function;
+mod create_partition;
 mod create_schema;
+mod create_sequence;
+mod create_statistics;
 mod create_table;
+mod create_type;
 mod delete;
+mod drop_functions;
 mod drop_schema;
+mod drop_sequences;
+mod drop_statistics;
 mod drop_tables;
+mod folding;
+mod generic;
 mod insert;
+mod lowering;
+mod params;
+mod projection;
+mod rewrite;
+mod schema_privileges;
 mod select;
+mod unsupported;
 mod update;
 
 use crate::{
-    plan::Plan,
+    extended::ExtendedStatement,
+    logical::RelationOp,
+    plan::{GenericPlan, Param, Plan},
     planner::{
-        create_schema::CreateSchemaPlanner, create_table::CreateTablePlanner, delete::DeletePlanner,
-        drop_schema::DropSchemaPlanner, drop_tables::DropTablesPlanner, insert::InsertPlanner, select::SelectPlanner,
+        alter_schema::AlterSchemaOwnerPlanner,
+        analyze::AnalyzePlanner,
+        copy::CopyPlanner,
+        create_function::CreateFunctionPlanner,
+        create_partition::CreatePartitionPlanner,
+        create_schema::CreateSchemaPlanner,
+        create_sequence::CreateSequencePlanner,
+        create_statistics::CreateStatisticsPlanner,
+        create_table::CreateTablePlanner,
+        create_type::CreateEnumTypePlanner,
+        delete::DeletePlanner,
+        drop_functions::DropFunctionsPlanner,
+        drop_schema::DropSchemaPlanner,
+        drop_sequences::DropSequencesPlanner,
+        drop_statistics::DropStatisticsPlanner,
+        drop_tables::DropTablesPlanner,
+        generic::{bind_plan, bind_relation, Discard},
+        insert::InsertPlanner,
+        lowering::lower,
+        params::{delete_params, insert_params, select_params, update_params},
+        schema_privileges::SchemaPrivilegesPlanner,
+        select::{optimized, SelectPlanner},
+        unsupported::unsupported_construct,
         update::UpdatePlanner,
     },
+    TableId,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, SchemaPrivilege, SUPERUSER};
 use protocol::{results::QueryError, Sender};
-use sqlparser::ast::{ObjectType, Statement};
+use sql_model::{sql_types::SqlType, Id};
+use sqlparser::ast::{Expr, ObjectType, Query, Statement};
 use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, ()>;
@@ -41,36 +87,314 @@ trait Planner {
     fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan>;
 }
 
+fn check_schema_privilege(
+    data_manager: &DataManager,
+    sender: &dyn Sender,
+    role: &str,
+    (schema_id, schema_name): (Id, &str),
+    privilege: SchemaPrivilege,
+) -> Result<()> {
+    if data_manager.has_schema_privilege(schema_id, role, privilege) {
+        Ok(())
+    } else {
+        sender
+            .send(Err(QueryError::permission_denied_for_schema(schema_name)))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
+
+fn is_integer(sql_type: SqlType) -> bool {
+    matches!(
+        sql_type,
+        SqlType::SmallInt(_) | SqlType::Integer(_) | SqlType::BigInt(_)
+    )
+}
+
+fn check_schema_owner(
+    data_manager: &DataManager,
+    sender: &dyn Sender,
+    role: &str,
+    (schema_id, schema_name): (Id, &str),
+) -> Result<()> {
+    if role == SUPERUSER || data_manager.schema_owner(schema_id).as_deref() == Some(role) {
+        Ok(())
+    } else {
+        sender
+            .send(Err(QueryError::must_be_owner_of_schema(schema_name)))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
+
 pub struct QueryPlanner {
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    role: String,
+    journal: Option<Arc<Journal>>,
 }
 
 impl QueryPlanner {
     pub fn new(data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Self {
-        Self { data_manager, sender }
+        Self {
+            data_manager,
+            sender,
+            role: SUPERUSER.to_owned(),
+            journal: None,
+        }
     }
 
-    pub fn plan(&self, stmt: Statement) -> Result<Plan> {
+    pub fn role(&self) -> &str {
+        self.role.as_str()
+    }
+
+    pub fn set_role<S: ToString>(&mut self, role: S) {
+        self.role = role.to_string();
+    }
+
+    /// tables created and dropped by the transaction of the journal are seen as it sees them
+    pub fn set_journal(&mut self, journal: Option<Arc<Journal>>) {
+        self.journal = journal;
+    }
+
+    pub fn plan_extended(&self, stmt: ExtendedStatement) -> Result<Plan> {
         match &stmt {
-            Statement::CreateTable { name, columns, .. } => {
-                CreateTablePlanner::new(name, columns).plan(self.data_manager.clone(), self.sender.clone())
+            ExtendedStatement::AlterSchemaOwner { schema_name, new_owner } => {
+                AlterSchemaOwnerPlanner::new(schema_name, new_owner, &self.role)
+                    .plan(self.data_manager.clone(), self.sender.clone())
             }
+            ExtendedStatement::GrantOnSchema {
+                privileges,
+                schema_names,
+                roles,
+            } => SchemaPrivilegesPlanner::grant(privileges, schema_names, roles, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::RevokeOnSchema {
+                privileges,
+                schema_names,
+                roles,
+            } => SchemaPrivilegesPlanner::revoke(privileges, schema_names, roles, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::CreateSequence {
+                sequence_name,
+                if_not_exists,
+                options,
+            } => CreateSequencePlanner::new(sequence_name, *if_not_exists, *options, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::CreateEnumType { type_name, labels } => {
+                CreateEnumTypePlanner::new(type_name, labels, &self.role)
+                    .plan(self.data_manager.clone(), self.sender.clone())
+            }
+            ExtendedStatement::CreateFunction {
+                function_name,
+                or_replace,
+                parameters,
+                return_type,
+                body,
+            } => CreateFunctionPlanner::new(function_name, *or_replace, parameters, return_type, body, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::DropFunction { names, if_exists } => {
+                DropFunctionsPlanner::new(names, *if_exists, &self.role)
+                    .plan(self.data_manager.clone(), self.sender.clone())
+            }
+            ExtendedStatement::CreateSchemaLike {
+                schema_name,
+                template_name,
+                with_data,
+            } => CreateSchemaPlanner::like(schema_name, template_name, *with_data, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::DropSequence { names, if_exists } => {
+                DropSequencesPlanner::new(names, *if_exists, &self.role)
+                    .plan(self.data_manager.clone(), self.sender.clone())
+            }
+            ExtendedStatement::CreateTable {
+                statement,
+                generated_columns,
+                partition_by,
+            } => match statement.as_ref() {
+                Statement::CreateTable {
+                    name,
+                    columns,
+                    constraints,
+                    if_not_exists,
+                    ..
+                } => CreateTablePlanner::new(
+                    name,
+                    columns,
+                    constraints,
+                    generated_columns,
+                    partition_by.as_ref(),
+                    *if_not_exists,
+                    &self.role,
+                )
+                .plan(self.data_manager.clone(), self.sender.clone()),
+                _ => {
+                    self.sender
+                        .send(Err(QueryError::feature_not_supported(statement)))
+                        .expect("To Send Result to Client");
+                    Err(())
+                }
+            },
+            ExtendedStatement::CreatePartition {
+                table_name,
+                parent_name,
+                from,
+                to,
+            } => CreatePartitionPlanner::new(table_name, parent_name, *from, *to, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::CreateStatistics {
+                statistics_name,
+                if_not_exists,
+                kinds,
+                columns,
+                table_name,
+            } => CreateStatisticsPlanner::new(statistics_name, *if_not_exists, kinds, columns, table_name, &self.role)
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::DropStatistics { names, if_exists } => {
+                DropStatisticsPlanner::new(names, *if_exists, &self.role)
+                    .plan(self.data_manager.clone(), self.sender.clone())
+            }
+            ExtendedStatement::Analyze(table_name) => AnalyzePlanner::new(table_name.as_ref(), &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::Vacuum(table_name) => AnalyzePlanner::vacuum(table_name.as_ref(), &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::CopyFrom { table_name, columns } => {
+                CopyPlanner::from_stdin(table_name, columns, &self.role)
+                    .with_journal(self.journal.as_deref())
+                    .plan(self.data_manager.clone(), self.sender.clone())
+            }
+            ExtendedStatement::CopyTo {
+                table_name,
+                columns,
+                format,
+            } => CopyPlanner::to_stdout(table_name, columns, *format, &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), self.sender.clone()),
+            ExtendedStatement::SetRole(_)
+            | ExtendedStatement::ResetRole
+            | ExtendedStatement::SetTimeZone(_)
+            | ExtendedStatement::DeclareCursor { .. }
+            | ExtendedStatement::Fetch { .. }
+            | ExtendedStatement::CloseCursor(_)
+            | ExtendedStatement::Listen(_)
+            | ExtendedStatement::Unlisten(_)
+            | ExtendedStatement::Notify { .. }
+            | ExtendedStatement::Savepoint(_)
+            | ExtendedStatement::ReleaseSavepoint(_)
+            | ExtendedStatement::RollbackToSavepoint(_)
+            | ExtendedStatement::Backup(_) => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(format!("{:?}", stmt))))
+                    .expect("To Send Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    /// plans a statement with `$n` placeholders which values are bound when the statement is executed,
+    /// placeholders are returned with types of columns their values are assigned to or compared with
+    pub fn plan_prepared(&self, stmt: &Statement) -> Result<(Plan, Vec<Param>)> {
+        let plan = self.plan(stmt.clone())?;
+        let columns = |table_id: &TableId| self.data_manager.table_columns(table_id).map_err(|_| ());
+        let params = match (&plan, stmt) {
+            (Plan::Insert(inserts), _) => insert_params(inserts, &columns(&inserts.table_id)?),
+            (Plan::Update(updates), _) => update_params(updates, &columns(&updates.table_id)?),
+            (Plan::Delete(deletes), _) => delete_params(deletes, &columns(&deletes.table_id)?),
+            (Plan::Select(input), Statement::Query(query)) => select_params(query, &columns(&input.table_id)?),
+            _ => vec![],
+        };
+        Ok((plan, params))
+    }
+
+    /// optimized logical plan of a select from a table before it is lowered into the physical one
+    pub fn logical_plan(&self, query: Box<Query>) -> Result<Option<RelationOp>> {
+        SelectPlanner::new(query, &self.role)
+            .with_journal(self.journal.as_deref())
+            .logical_plan(&self.data_manager, self.sender.as_ref())
+    }
+
+    pub fn plan(&self, stmt: Statement) -> Result<Plan> {
+        self.plan_with(stmt, self.sender.clone())
+    }
+
+    /// plan of a statement with `$n` placeholders in place of its literals, `None` if binding the literals with `bind`
+    /// doesn't give the plan that the statement with the literals has. Errors of planning the template are not sent
+    pub fn plan_generic<F: FnMut(&mut Expr)>(&self, template: &Statement, bind: F, plan: &Plan) -> Option<GenericPlan> {
+        let discard: Arc<dyn Sender> = Arc::new(Discard);
+        let generic = match template {
+            Statement::Query(query) => match SelectPlanner::new(query.clone(), &self.role)
+                .with_journal(self.journal.as_deref())
+                .unoptimized_plan(&self.data_manager, discard.as_ref())
+                .ok()?
+            {
+                Some((relation, columns)) => GenericPlan::Select { relation, columns },
+                None => GenericPlan::Plan(self.plan_with(template.clone(), discard.clone()).ok()?),
+            },
+            Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. } => {
+                GenericPlan::Plan(self.plan_with(template.clone(), discard.clone()).ok()?)
+            }
+            _ => return None,
+        };
+        match self.bind_with(&generic, bind, discard.as_ref()) {
+            Ok(bound) if &bound == plan => Some(generic),
+            _ => None,
+        }
+    }
+
+    /// plan with `$n` placeholders replaced by `bind`, selects are optimized and lowered with the bound values
+    pub fn bind<F: FnMut(&mut Expr)>(&self, generic: &GenericPlan, bind: F) -> Result<Plan> {
+        self.bind_with(generic, bind, self.sender.as_ref())
+    }
+
+    fn bind_with<F: FnMut(&mut Expr)>(&self, generic: &GenericPlan, mut bind: F, sender: &dyn Sender) -> Result<Plan> {
+        match generic {
+            GenericPlan::Select { relation, columns } => {
+                let relation = bind_relation(relation.clone(), &mut bind)?;
+                lower(optimized(relation, columns, sender)?, &self.data_manager).map(Plan::Select)
+            }
+            GenericPlan::Plan(plan) => {
+                let mut plan = plan.clone();
+                bind_plan(&mut plan, &mut bind);
+                Ok(plan)
+            }
+        }
+    }
+
+    fn plan_with(&self, stmt: Statement, sender: Arc<dyn Sender>) -> Result<Plan> {
+        if let Some(construct) = unsupported_construct(&stmt) {
+            sender
+                .send(Err(QueryError::feature_not_supported(construct)))
+                .expect("To Send Result to Client");
+            return Err(());
+        }
+        match &stmt {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                if_not_exists,
+                ..
+            } => CreateTablePlanner::new(name, columns, constraints, &[], None, *if_not_exists, &self.role)
+                .plan(self.data_manager.clone(), sender.clone()),
             Statement::CreateSchema { schema_name, .. } => {
-                CreateSchemaPlanner::new(schema_name).plan(self.data_manager.clone(), self.sender.clone())
+                CreateSchemaPlanner::new(schema_name).plan(self.data_manager.clone(), sender.clone())
             }
             Statement::Drop {
                 object_type,
+                if_exists,
                 names,
                 cascade,
-                ..
             } => match object_type {
-                ObjectType::Table => DropTablesPlanner::new(names).plan(self.data_manager.clone(), self.sender.clone()),
+                ObjectType::Table => DropTablesPlanner::new(names, *if_exists, &self.role)
+                    .with_journal(self.journal.as_deref())
+                    .plan(self.data_manager.clone(), sender.clone()),
                 ObjectType::Schema => {
-                    DropSchemaPlanner::new(names, *cascade).plan(self.data_manager.clone(), self.sender.clone())
+                    DropSchemaPlanner::new(names, *if_exists, *cascade).plan(self.data_manager.clone(), sender.clone())
                 }
                 _ => {
-                    self.sender
+                    sender
                         .send(Err(QueryError::syntax_error(stmt)))
                         .expect("To Send Result to Client");
                     Err(())
@@ -80,18 +404,24 @@ impl QueryPlanner {
                 table_name,
                 columns,
                 source,
-            } => InsertPlanner::new(table_name, columns, source).plan(self.data_manager.clone(), self.sender.clone()),
+            } => InsertPlanner::new(table_name, columns, source, &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), sender.clone()),
             Statement::Update {
                 table_name,
                 assignments,
-                ..
-            } => UpdatePlanner::new(table_name, assignments).plan(self.data_manager.clone(), self.sender.clone()),
-            Statement::Delete { table_name, .. } => {
-                DeletePlanner::new(table_name).plan(self.data_manager.clone(), self.sender.clone())
-            }
-            Statement::Query(query) => {
-                SelectPlanner::new(query.clone()).plan(self.data_manager.clone(), self.sender.clone())
+                selection,
+            } => UpdatePlanner::new(table_name, assignments, selection.as_ref(), &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), sender.clone()),
+            Statement::Delete { table_name, selection } => {
+                DeletePlanner::new(table_name, selection.as_ref(), &self.role)
+                    .with_journal(self.journal.as_deref())
+                    .plan(self.data_manager.clone(), sender.clone())
             }
+            Statement::Query(query) => SelectPlanner::new(query.clone(), &self.role)
+                .with_journal(self.journal.as_deref())
+                .plan(self.data_manager.clone(), sender.clone()),
             _ => Ok(Plan::NotProcessed(Box::new(stmt))),
         }
     }
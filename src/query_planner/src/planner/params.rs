@@ -0,0 +1,132 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Types of `$n` placeholders inferred from columns their values are assigned to or compared with.
+use crate::plan::{param_index, Param, TableDeletes, TableInserts, TableUpdates};
+use data_manager::ColumnDefinition;
+use sqlparser::ast::{BinaryOperator, Expr, Query, Select, SetExpr, UnaryOperator};
+
+fn column_param(index: usize, column: &ColumnDefinition) -> Param {
+    Param {
+        index,
+        column_name: Some(column.name()),
+        sql_type: column.sql_type(),
+    }
+}
+
+/// values are given in the order of listed columns or of all columns of the table when columns are not listed
+pub(crate) fn insert_params(inserts: &TableInserts, columns: &[ColumnDefinition]) -> Vec<Param> {
+    inserts
+        .input
+        .iter()
+        .flat_map(|row| row.iter().enumerate())
+        .filter_map(|(position, expr)| {
+            let index = param_index(expr)?;
+            let column = match inserts.column_indices.get(position) {
+                Some(name) => columns.iter().find(|column| column.has_name(&name.value)),
+                None => columns.get(position),
+            }?;
+            Some(column_param(index, column))
+        })
+        .collect()
+}
+
+/// placeholders assigned to columns and compared with columns in `WHERE` clause
+pub(crate) fn update_params(updates: &TableUpdates, columns: &[ColumnDefinition]) -> Vec<Param> {
+    let mut params = updates
+        .assignments
+        .iter()
+        .filter_map(|assignment| {
+            let index = param_index(&assignment.value)?;
+            let column = columns.iter().find(|column| column.has_name(&assignment.id.value))?;
+            Some(column_param(index, column))
+        })
+        .collect();
+    if let Some(filter) = &updates.filter {
+        compared_params(filter, columns, &mut params);
+    }
+    params
+}
+
+/// placeholders compared with columns in `WHERE` clause
+pub(crate) fn delete_params(deletes: &TableDeletes, columns: &[ColumnDefinition]) -> Vec<Param> {
+    let mut params = vec![];
+    if let Some(filter) = &deletes.filter {
+        compared_params(filter, columns, &mut params);
+    }
+    params
+}
+
+/// placeholders compared with columns in `WHERE` clause, `sqlparser` accepts only numbers as row counts of `LIMIT`,
+/// `OFFSET` and `FETCH`
+pub(crate) fn select_params(query: &Query, columns: &[ColumnDefinition]) -> Vec<Param> {
+    let mut params = vec![];
+    if let SetExpr::Select(select) = &query.body {
+        let Select { selection, .. } = select.as_ref();
+        if let Some(selection) = selection {
+            compared_params(selection, columns, &mut params);
+        }
+    }
+    params
+}
+
+fn column<'c>(expr: &Expr, columns: &'c [ColumnDefinition]) -> Option<&'c ColumnDefinition> {
+    let column_name = match expr {
+        Expr::Identifier(ident) => ident,
+        Expr::CompoundIdentifier(idents) => idents.last()?,
+        _ => return None,
+    };
+    columns.iter().find(|column| column.has_name(&column_name.value))
+}
+
+fn compared_params(expr: &Expr, columns: &[ColumnDefinition], params: &mut Vec<Param>) {
+    match expr {
+        Expr::Nested(expr)
+        | Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => compared_params(expr, columns, params),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        }
+        | Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Or,
+            right,
+        } => {
+            compared_params(left, columns, params);
+            compared_params(right, columns, params);
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            let compared = match (column(left, columns), param_index(right)) {
+                (Some(column), Some(index)) => Some((index, column)),
+                _ => param_index(left).zip(column(right, columns)),
+            };
+            params.extend(compared.map(|(index, column)| column_param(index, column)));
+        }
+        Expr::Between { expr, low, high, .. } => {
+            if let Some(column) = column(expr, columns) {
+                params.extend(
+                    vec![low, high]
+                        .into_iter()
+                        .filter_map(|bound| param_index(bound))
+                        .map(|index| column_param(index, column)),
+                );
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! every operator of a select reads only some columns of the table, columns that no operator reads are not
+//! unpacked from scanned rows.
+
+use crate::plan::{ColumnRange, RequiredColumns, SelectedColumn, SortKey};
+use data_manager::ColumnDefinition;
+use sqlparser::ast::Expr;
+
+/// marks columns that are referenced by the expression, all of them are marked for expressions that are not
+/// known to reference only named columns
+fn mark_referenced(expr: &Expr, all_columns: &[ColumnDefinition], required: &mut [bool]) {
+    match expr {
+        Expr::Identifier(ident) => mark_named(Some(&ident.value), all_columns, required),
+        Expr::CompoundIdentifier(idents) => mark_named(idents.last().map(|ident| &ident.value), all_columns, required),
+        // `*` is an argument of `count(*)` that reads no column
+        Expr::Value(_) | Expr::Wildcard => {}
+        Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => mark_referenced(expr, all_columns, required),
+        Expr::BinaryOp { left, right, .. } => {
+            mark_referenced(left, all_columns, required);
+            mark_referenced(right, all_columns, required);
+        }
+        Expr::Between { expr, low, high, .. } => {
+            for expr in [expr, low, high].iter() {
+                mark_referenced(expr, all_columns, required);
+            }
+        }
+        Expr::InList { expr, list, .. } => {
+            mark_referenced(expr, all_columns, required);
+            for item in list.iter() {
+                mark_referenced(item, all_columns, required);
+            }
+        }
+        Expr::Function(function) => {
+            for arg in function.args.iter() {
+                mark_referenced(arg, all_columns, required);
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            for expr in operand.iter().chain(else_result.iter()) {
+                mark_referenced(expr, all_columns, required);
+            }
+            for expr in conditions.iter().chain(results.iter()) {
+                mark_referenced(expr, all_columns, required);
+            }
+        }
+        _ => required.iter_mut().for_each(|required| *required = true),
+    }
+}
+
+fn mark_named<'n, I: IntoIterator<Item = &'n String>>(
+    names: I,
+    all_columns: &[ColumnDefinition],
+    required: &mut [bool],
+) {
+    for name in names {
+        if let Some(index) = all_columns.iter().position(|column| column.has_name(name)) {
+            required[index] = true;
+        }
+    }
+}
+
+fn names(all_columns: &[ColumnDefinition], required: &[bool]) -> Vec<String> {
+    all_columns
+        .iter()
+        .zip(required.iter())
+        .filter(|(_, required)| **required)
+        .map(|(column, _)| column.name())
+        .collect()
+}
+
+/// columns read by ranges and filters of scanned rows, by window functions, by sorting and by selected columns
+pub(crate) fn required_columns(
+    all_columns: &[ColumnDefinition],
+    selected_columns: &[SelectedColumn],
+    order_by: &[SortKey],
+    ranges: &[ColumnRange],
+    filters: &[Expr],
+) -> RequiredColumns {
+    let mut filter = vec![false; all_columns.len()];
+    mark_named(ranges.iter().map(|range| &range.column_name), all_columns, &mut filter);
+    for expr in filters {
+        mark_referenced(expr, all_columns, &mut filter);
+    }
+
+    let mut window = vec![false; all_columns.len()];
+    let mut project = vec![false; all_columns.len()];
+    for selected_column in selected_columns {
+        match selected_column {
+            SelectedColumn::Column(name) => mark_named(Some(name), all_columns, &mut project),
+            SelectedColumn::Window(_, function) => {
+                mark_named(function.argument.iter(), all_columns, &mut window);
+                mark_named(function.partition_by.iter(), all_columns, &mut window);
+                mark_named(
+                    function.order_by.iter().map(|key| &key.column_name),
+                    all_columns,
+                    &mut window,
+                );
+                if let Some(expr) = &function.filter {
+                    mark_referenced(expr, all_columns, &mut window);
+                }
+            }
+            SelectedColumn::Expr(_, expr) => mark_referenced(expr, all_columns, &mut project),
+        }
+    }
+
+    let mut sort = vec![false; all_columns.len()];
+    mark_named(order_by.iter().map(|key| &key.column_name), all_columns, &mut sort);
+
+    RequiredColumns {
+        filter: names(all_columns, &filter),
+        window: names(all_columns, &window),
+        sort: names(all_columns, &sort),
+        project: names(all_columns, &project),
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! optimizer passes rewrite the logical plan of a select one rule at a time. A rule rewrites a single operator and
+//! doesn't look deeper than its input, the traversal applies it to every operator of the tree either after their
+//! inputs are rewritten, bottom-up, or before, top-down. Passes run in the given order, each over the whole tree.
+
+use crate::{
+    logical::RelationOp,
+    plan::RowLimit,
+    planner::{folding::fold_constants, Result},
+};
+use sqlparser::ast::{BinaryOperator, Expr, Value};
+
+/// order in which operators of the tree are rewritten
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Traversal {
+    /// inputs of an operator are rewritten before the operator
+    BottomUp,
+    /// an operator is rewritten before its input
+    TopDown,
+}
+
+pub(crate) trait Rule {
+    fn traversal(&self) -> Traversal;
+
+    /// rewritten operator, its input is returned unchanged if the operator is eliminated
+    fn apply(&self, relation: RelationOp) -> Result<RelationOp>;
+}
+
+/// applies the rule to every operator of the tree
+pub(crate) fn rewrite(relation: RelationOp, rule: &dyn Rule) -> Result<RelationOp> {
+    match rule.traversal() {
+        Traversal::BottomUp => rule.apply(relation.try_map_input(|input| rewrite(input, rule))?),
+        Traversal::TopDown => rule.apply(relation)?.try_map_input(|input| rewrite(input, rule)),
+    }
+}
+
+/// runs passes over the tree one after another
+pub(crate) fn optimize(relation: RelationOp, passes: &[&dyn Rule]) -> Result<RelationOp> {
+    passes
+        .iter()
+        .try_fold(relation, |relation, rule| rewrite(relation, *rule))
+}
+
+/// constant subexpressions of filter predicates are folded
+pub(crate) struct FoldConstants;
+
+impl Rule for FoldConstants {
+    fn traversal(&self) -> Traversal {
+        Traversal::TopDown
+    }
+
+    fn apply(&self, relation: RelationOp) -> Result<RelationOp> {
+        Ok(match relation {
+            RelationOp::Filter { input, predicate } => RelationOp::Filter {
+                input,
+                predicate: fold_constants(&predicate),
+            },
+            relation => relation,
+        })
+    }
+}
+
+/// operators that keep their input as it is are removed and adjacent filters are merged into one
+pub(crate) struct EliminateRedundantOperators;
+
+impl Rule for EliminateRedundantOperators {
+    fn traversal(&self) -> Traversal {
+        Traversal::BottomUp
+    }
+
+    fn apply(&self, relation: RelationOp) -> Result<RelationOp> {
+        Ok(match relation {
+            RelationOp::Filter {
+                input,
+                predicate: Expr::Value(Value::Boolean(true)),
+            } => *input,
+            RelationOp::Filter { input, predicate } => match *input {
+                RelationOp::Filter {
+                    input,
+                    predicate: inner,
+                } => RelationOp::Filter {
+                    input,
+                    predicate: Expr::BinaryOp {
+                        left: Box::new(inner),
+                        op: BinaryOperator::And,
+                        right: Box::new(predicate),
+                    },
+                },
+                input => RelationOp::Filter {
+                    input: Box::new(input),
+                    predicate,
+                },
+            },
+            RelationOp::Window { input, functions } if functions.is_empty() => *input,
+            RelationOp::Sort { input, order_by } if order_by.is_empty() => *input,
+            RelationOp::Limit { input, limit } if limit == RowLimit::default() => *input,
+            relation => relation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{logical::ProjectedColumn, plan::SortKey, FullTableName, SchemaName, TableId};
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser, tokenizer::Tokenizer};
+    use std::cell::RefCell;
+
+    fn expr(sql: &str) -> Expr {
+        let tokens = Tokenizer::new(&PostgreSqlDialect {}, sql)
+            .tokenize()
+            .expect("expression tokenized");
+        Parser::new(tokens).parse_expr().expect("expression parsed")
+    }
+
+    fn scan() -> RelationOp {
+        RelationOp::Scan {
+            table_id: TableId((0, 0)),
+            table_name: FullTableName(SchemaName("schema_name".to_owned()), "table_name".to_owned()),
+            ranges: vec![],
+            filters: vec![],
+        }
+    }
+
+    fn filter(input: RelationOp, predicate: &str) -> RelationOp {
+        RelationOp::Filter {
+            input: Box::new(input),
+            predicate: expr(predicate),
+        }
+    }
+
+    /// records names of operators in the order they are rewritten
+    struct Visited(Traversal, RefCell<Vec<String>>);
+
+    impl Rule for Visited {
+        fn traversal(&self) -> Traversal {
+            self.0
+        }
+
+        fn apply(&self, relation: RelationOp) -> Result<RelationOp> {
+            let operator = relation.to_string();
+            self.1
+                .borrow_mut()
+                .push(operator.lines().next().unwrap_or_default().to_owned());
+            Ok(relation)
+        }
+    }
+
+    #[test]
+    fn traversal_order() {
+        let relation = filter(filter(scan(), "a"), "b");
+
+        let bottom_up = Visited(Traversal::BottomUp, RefCell::new(vec![]));
+        assert_eq!(rewrite(relation.clone(), &bottom_up), Ok(relation.clone()));
+        assert_eq!(
+            bottom_up.1.into_inner(),
+            vec!["Scan on schema_name.table_name", "Filter: a", "Filter: b"]
+        );
+
+        let top_down = Visited(Traversal::TopDown, RefCell::new(vec![]));
+        assert_eq!(rewrite(relation.clone(), &top_down), Ok(relation));
+        assert_eq!(
+            top_down.1.into_inner(),
+            vec!["Filter: b", "Filter: a", "Scan on schema_name.table_name"]
+        );
+    }
+
+    #[test]
+    fn predicates_of_filters_are_folded() {
+        assert_eq!(
+            rewrite(filter(filter(scan(), "a > 1 + 1"), "1 = 1"), &FoldConstants),
+            Ok(filter(filter(scan(), "a > 2"), "true"))
+        );
+    }
+
+    #[test]
+    fn redundant_operators_are_eliminated() {
+        let relation = RelationOp::Project {
+            input: Box::new(RelationOp::Limit {
+                input: Box::new(RelationOp::Sort {
+                    input: Box::new(filter(scan(), "true")),
+                    order_by: vec![],
+                }),
+                limit: RowLimit::default(),
+            }),
+            columns: vec![ProjectedColumn::Column("a".to_owned())],
+        };
+
+        assert_eq!(
+            rewrite(relation, &EliminateRedundantOperators),
+            Ok(RelationOp::Project {
+                input: Box::new(scan()),
+                columns: vec![ProjectedColumn::Column("a".to_owned())],
+            })
+        );
+    }
+
+    #[test]
+    fn adjacent_filters_are_merged() {
+        let sort = |input| RelationOp::Sort {
+            input: Box::new(input),
+            order_by: vec![SortKey {
+                column_name: "a".to_owned(),
+                ascending: true,
+                nulls_first: false,
+            }],
+        };
+
+        assert_eq!(
+            rewrite(
+                sort(filter(filter(filter(scan(), "a"), "b"), "c")),
+                &EliminateRedundantOperators
+            ),
+            Ok(sort(filter(scan(), "a AND b AND c")))
+        );
+    }
+
+    #[test]
+    fn passes_run_one_after_another() {
+        assert_eq!(
+            optimize(
+                filter(filter(scan(), "a"), "1 < 2"),
+                &[&FoldConstants, &EliminateRedundantOperators]
+            ),
+            Ok(filter(scan(), "a"))
+        );
+    }
+}
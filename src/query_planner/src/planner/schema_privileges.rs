@@ -0,0 +1,106 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    plan::{Plan, SchemaPrivilegesChange},
+    planner::{check_schema_owner, Planner, Result},
+    SchemaId, SchemaName,
+};
+use data_manager::{DataManager, SchemaPrivilege};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::{Ident, ObjectName};
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct SchemaPrivilegesPlanner<'spp> {
+    grant: bool,
+    privileges: &'spp [SchemaPrivilege],
+    schema_names: &'spp [ObjectName],
+    roles: &'spp [Ident],
+    role: &'spp str,
+}
+
+impl<'spp> SchemaPrivilegesPlanner<'spp> {
+    pub(crate) fn grant(
+        privileges: &'spp [SchemaPrivilege],
+        schema_names: &'spp [ObjectName],
+        roles: &'spp [Ident],
+        role: &'spp str,
+    ) -> SchemaPrivilegesPlanner<'spp> {
+        SchemaPrivilegesPlanner {
+            grant: true,
+            privileges,
+            schema_names,
+            roles,
+            role,
+        }
+    }
+
+    pub(crate) fn revoke(
+        privileges: &'spp [SchemaPrivilege],
+        schema_names: &'spp [ObjectName],
+        roles: &'spp [Ident],
+        role: &'spp str,
+    ) -> SchemaPrivilegesPlanner<'spp> {
+        SchemaPrivilegesPlanner {
+            grant: false,
+            privileges,
+            schema_names,
+            roles,
+            role,
+        }
+    }
+}
+
+impl Planner for SchemaPrivilegesPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let mut schema_ids = Vec::with_capacity(self.schema_names.len());
+        for name in self.schema_names {
+            match SchemaName::try_from(name) {
+                Ok(schema_name) => match data_manager.schema_exists(&schema_name) {
+                    None => {
+                        sender
+                            .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                            .expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                    Some(schema_id) => {
+                        check_schema_owner(
+                            &data_manager,
+                            sender.as_ref(),
+                            self.role,
+                            (schema_id, schema_name.as_ref()),
+                        )?;
+                        schema_ids.push(SchemaId(schema_id))
+                    }
+                },
+                Err(error) => {
+                    sender
+                        .send(Err(QueryError::syntax_error(error)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            }
+        }
+        let change = SchemaPrivilegesChange {
+            schema_ids,
+            privileges: self.privileges.to_vec(),
+            roles: self.roles.iter().map(|role| role.value.clone()).collect(),
+        };
+        if self.grant {
+            Ok(Plan::GrantSchemaPrivileges(change))
+        } else {
+            Ok(Plan::RevokeSchemaPrivileges(change))
+        }
+    }
+}
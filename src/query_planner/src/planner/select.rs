@@ -13,110 +13,769 @@
 // limitations under the License.
 
 use crate::{
-    plan::{Plan, SelectInput},
-    planner::{Planner, Result},
+    logical::{ProjectedColumn, RelationOp},
+    plan::{
+        param_index, ColumnRange, FrameBound, FrameUnits, Plan, RowLimit, SelectValues, SortKey, WindowFrame,
+        WindowFunction,
+    },
+    planner::{
+        check_schema_privilege,
+        coercion::{comparison_bounds, integer_operand},
+        is_integer,
+        lowering::lower,
+        rewrite::{optimize, EliminateRedundantOperators, FoldConstants, Rule, Traversal},
+        Planner, Result,
+    },
     FullTableName, TableId,
 };
-use data_manager::DataManager;
+use data_manager::{ColumnDefinition, DataManager, Journal, SchemaPrivilege};
 use protocol::{results::QueryError, Sender};
-use sqlparser::ast::{Expr, Ident, Query, Select, SelectItem, SetExpr, TableFactor, TableWithJoins};
-use std::{convert::TryFrom, ops::Deref, sync::Arc};
+use sql_model::sql_types::SqlType;
+use sqlparser::ast::{
+    self, BinaryOperator, Expr, Fetch, Function, Ident, Offset, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    TableFactor, Value, WindowFrameUnits, WindowSpec,
+};
+use std::{convert::TryFrom, sync::Arc};
 
-pub(crate) struct SelectPlanner {
+pub(crate) struct SelectPlanner<'sp> {
     query: Box<Query>,
+    role: &'sp str,
+    journal: Option<&'sp Journal>,
 }
 
-impl SelectPlanner {
-    pub(crate) fn new(query: Box<Query>) -> SelectPlanner {
-        SelectPlanner { query }
+impl<'sp> SelectPlanner<'sp> {
+    pub(crate) fn new(query: Box<Query>, role: &'sp str) -> SelectPlanner<'sp> {
+        SelectPlanner {
+            query,
+            role,
+            journal: None,
+        }
     }
-}
 
-impl Planner for SelectPlanner {
-    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
-        let Query { body, .. } = &*self.query;
-        let result = if let SetExpr::Select(select) = body {
-            let Select { projection, from, .. } = select.deref();
-            let TableWithJoins { relation, .. } = &from[0];
-            let name = match relation {
-                TableFactor::Table { name, .. } => name,
+    /// tables are resolved as the transaction of the journal sees them
+    pub(crate) fn with_journal(mut self, journal: Option<&'sp Journal>) -> SelectPlanner<'sp> {
+        self.journal = journal;
+        self
+    }
+
+    /// advice is given for every table in schemas that the role can use
+    fn plan_index_advice(
+        &self,
+        projection: &[SelectItem],
+        data_manager: &DataManager,
+        sender: &dyn Sender,
+    ) -> Result<Plan> {
+        if !matches!(projection, [SelectItem::Wildcard]) {
+            sender
+                .send(Err(QueryError::feature_not_supported(format!(
+                    "only all columns of {} can be selected",
+                    INDEX_ADVICE
+                ))))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        Ok(Plan::IndexAdvice(
+            data_manager
+                .table_ids()
+                .into_iter()
+                .filter(|(schema_id, _)| {
+                    data_manager.has_schema_privilege(*schema_id, self.role, SchemaPrivilege::Usage)
+                })
+                .map(TableId)
+                .collect(),
+        ))
+    }
+
+    fn plan_values(&self, projection: &[SelectItem], sender: &dyn Sender) -> Result<Plan> {
+        let mut values = vec![];
+        for item in projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) => values.push((column_name(expr), expr.clone())),
+                SelectItem::ExprWithAlias { expr, alias } => values.push((alias.value.clone(), expr.clone())),
                 _ => {
                     sender
                         .send(Err(QueryError::feature_not_supported(&*self.query)))
                         .expect("To Send Query Result to Client");
                     return Err(());
                 }
-            };
+            }
+        }
+        Ok(Plan::SelectValues(SelectValues { values }))
+    }
 
-            match FullTableName::try_from(name) {
-                Ok(full_table_name) => {
-                    let (schema_name, table_name) = full_table_name.as_tuple();
-                    match data_manager.table_exists(&schema_name, &table_name) {
-                        None => {
-                            sender
-                                .send(Err(QueryError::schema_does_not_exist(schema_name)))
-                                .expect("To Send Result to Client");
-                            return Err(());
-                        }
-                        Some((_, None)) => {
+    /// `ORDER BY` columns, `OFFSET` and `LIMIT` or `FETCH FIRST` given as literals
+    fn plan_rows(&self, sender: &dyn Sender) -> Result<(Vec<SortKey>, RowLimit)> {
+        let Query {
+            order_by,
+            limit,
+            offset,
+            fetch,
+            ..
+        } = &*self.query;
+        let sort_keys = self.sort_keys(order_by, sender)?;
+        let offset = match offset {
+            Some(Offset { value, .. }) => row_count(value, sender)?.unwrap_or(0),
+            None => 0,
+        };
+        let (count, with_ties) = match (limit, fetch) {
+            (Some(_), Some(_)) => {
+                sender
+                    .send(Err(QueryError::syntax_error("multiple LIMIT clauses not allowed")))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            (Some(limit), None) => (row_count(limit, sender)?, false),
+            (None, Some(Fetch { percent: true, .. })) => {
+                sender
+                    .send(Err(QueryError::feature_not_supported("FETCH FIRST ... PERCENT")))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            (None, Some(Fetch { with_ties: true, .. })) if sort_keys.is_empty() => {
+                sender
+                    .send(Err(QueryError::syntax_error(
+                        "WITH TIES cannot be specified without ORDER BY clause",
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+            (
+                None,
+                Some(Fetch {
+                    quantity, with_ties, ..
+                }),
+            ) => match quantity {
+                Some(quantity) => (row_count(quantity, sender)?, *with_ties),
+                None => (Some(1), *with_ties),
+            },
+            (None, None) => (None, false),
+        };
+        Ok((
+            sort_keys,
+            RowLimit {
+                offset,
+                count,
+                with_ties,
+            },
+        ))
+    }
+
+    /// sort keys can only be columns of the table
+    fn sort_keys(&self, order_by: &[OrderByExpr], sender: &dyn Sender) -> Result<Vec<SortKey>> {
+        let mut sort_keys = vec![];
+        for order_by_expr in order_by {
+            match &order_by_expr.expr {
+                Expr::Identifier(Ident { value, .. }) => {
+                    let ascending = order_by_expr.asc.unwrap_or(true);
+                    sort_keys.push(SortKey {
+                        column_name: value.clone(),
+                        ascending,
+                        nulls_first: order_by_expr.nulls_first.unwrap_or(!ascending),
+                    })
+                }
+                _ => {
+                    sender
+                        .send(Err(QueryError::feature_not_supported(&*self.query)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            }
+        }
+        Ok(sort_keys)
+    }
+
+    fn window_function(&self, function: &Function, window: &WindowSpec, sender: &dyn Sender) -> Result<WindowFunction> {
+        let name = function.name.to_string().to_lowercase();
+        // condition of `FILTER (WHERE ...)` is parsed as the last argument
+        let (arguments, filter) = match function.args.split_last() {
+            Some((Expr::Function(filter), arguments)) if filter.name.to_string() == "aggregate_filter" => {
+                (arguments, filter.args.first().cloned())
+            }
+            _ => (function.args.as_slice(), None),
+        };
+        if name == "row_number" && filter.is_some() {
+            sender
+                .send(Err(QueryError::feature_not_supported(
+                    "FILTER is not implemented for non-aggregate window functions",
+                )))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let (argument, parameter) = match (name.as_str(), arguments) {
+            ("row_number", []) | ("count", [Expr::Wildcard]) => (None, None),
+            // aggregates are resolved by name when the query is executed
+            (_, [Expr::Identifier(column)]) => (Some(column.value.clone()), None),
+            (_, [Expr::Identifier(column), Expr::Value(delimiter @ Value::SingleQuotedString(_))]) => {
+                (Some(column.value.clone()), Some(delimiter.clone()))
+            }
+            (_, [Expr::Identifier(column), Expr::Value(Value::Number(number))]) => {
+                let parameter = number.to_string().parse::<f64>().expect("numeric literal");
+                if name == "approx_percentile" && !(0.0..=1.0).contains(&parameter) {
+                    sender
+                        .send(Err(QueryError::numeric_value_out_of_range(format!(
+                            "percentile value {} is not between 0 and 1",
+                            number
+                        ))))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+                (Some(column.value.clone()), Some(Value::Number(number.clone())))
+            }
+            _ => {
+                sender
+                    .send(Err(QueryError::feature_not_supported(format!(
+                        "window function {}",
+                        function
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let mut partition_by = vec![];
+        for expr in window.partition_by.iter() {
+            match expr {
+                Expr::Identifier(Ident { value, .. }) => partition_by.push(value.clone()),
+                _ => {
+                    sender
+                        .send(Err(QueryError::feature_not_supported(format!("PARTITION BY {}", expr))))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            }
+        }
+        let order_by = self.sort_keys(&window.order_by, sender)?;
+        let frame = match &window.window_frame {
+            None => WindowFrame::default(),
+            Some(window_frame) => {
+                let frame = WindowFrame {
+                    units: match window_frame.units {
+                        WindowFrameUnits::Rows => FrameUnits::Rows,
+                        WindowFrameUnits::Range => FrameUnits::Range,
+                        WindowFrameUnits::Groups => {
                             sender
-                                .send(Err(QueryError::table_does_not_exist(
-                                    schema_name.to_owned() + "." + table_name,
-                                )))
-                                .expect("To Send Result to Client");
+                                .send(Err(QueryError::feature_not_supported("GROUPS frame units")))
+                                .expect("To Send Query Result to Client");
                             return Err(());
                         }
-                        Some((schema_id, Some(table_id))) => {
-                            let selected_columns = {
-                                let projection = projection.clone();
-                                let mut columns: Vec<String> = vec![];
-                                for item in projection {
-                                    match item {
-                                        SelectItem::Wildcard => {
-                                            let all_columns = data_manager
-                                                .table_columns(&Box::new((schema_id, table_id)))
-                                                .map_err(|_| ())?;
-                                            columns.extend(
-                                                all_columns
-                                                    .into_iter()
-                                                    .map(|column_definition| column_definition.name())
-                                                    .collect::<Vec<String>>(),
-                                            )
-                                        }
-                                        SelectItem::UnnamedExpr(Expr::Identifier(Ident { value, .. })) => {
-                                            columns.push(value.clone())
-                                        }
-                                        _ => {
-                                            sender
-                                                .send(Err(QueryError::feature_not_supported(&*self.query)))
-                                                .expect("To Send Query Result to Client");
-                                            return Err(());
-                                        }
-                                    }
-                                }
-                                columns
-                            };
-
-                            Ok(SelectInput {
-                                table_id: TableId((schema_id, table_id)),
-                                selected_columns,
-                            })
-                        }
-                    }
+                    },
+                    start: frame_bound(&window_frame.start_bound),
+                    end: window_frame
+                        .end_bound
+                        .as_ref()
+                        .map(frame_bound)
+                        .unwrap_or(FrameBound::CurrentRow),
+                };
+                if let Err(message) = validate_frame(&frame, order_by.len()) {
+                    sender
+                        .send(Err(QueryError::windowing_error(message)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+                frame
+            }
+        };
+        Ok(WindowFunction {
+            function: name,
+            argument,
+            parameter,
+            filter,
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+
+    /// optimized logical plan of a select from a table, `None` for selects without `FROM` and from `index_advice`
+    pub(crate) fn logical_plan(&self, data_manager: &DataManager, sender: &dyn Sender) -> Result<Option<RelationOp>> {
+        match self.unoptimized_plan(data_manager, sender)? {
+            Some((relation, columns)) => optimized(relation, &columns, sender).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// logical plan as it is built from the statement with columns of the table, `None` for a select without
+    /// `FROM` and from `index_advice`
+    pub(crate) fn unoptimized_plan(
+        &self,
+        data_manager: &DataManager,
+        sender: &dyn Sender,
+    ) -> Result<Option<(RelationOp, Vec<ColumnDefinition>)>> {
+        let select = match &self.query.body {
+            SetExpr::Select(select) if !select.from.is_empty() => select,
+            SetExpr::Select(_) => return Ok(None),
+            _ => {
+                sender
+                    .send(Err(QueryError::feature_not_supported(&*self.query)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let name = match &select.from[0].relation {
+            TableFactor::Table { name, .. } => name,
+            _ => {
+                sender
+                    .send(Err(QueryError::feature_not_supported(&*self.query)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        if name.0.len() == 1 && name.0[0].value.eq_ignore_ascii_case(INDEX_ADVICE) {
+            return Ok(None);
+        }
+        let table_name = match FullTableName::try_from(name) {
+            Ok(table_name) => table_name,
+            Err(error) => {
+                sender
+                    .send(Err(QueryError::syntax_error(error)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let table_id = self.table_id(&table_name, data_manager, sender)?;
+        let all_columns = data_manager.table_columns(&table_id).map_err(|_| ())?;
+        let relation = self.relation(table_id, table_name, select, &all_columns, sender)?;
+        Ok(Some((relation, all_columns)))
+    }
+
+    /// id of an existing table in a schema that the role can use
+    fn table_id(&self, table_name: &FullTableName, data_manager: &DataManager, sender: &dyn Sender) -> Result<TableId> {
+        let (schema_name, table_name) = table_name.as_tuple();
+        let full_table_id = data_manager.table_exists_in(self.journal, &schema_name, &table_name);
+        if let Some((schema_id, _)) = full_table_id {
+            check_schema_privilege(
+                data_manager,
+                sender,
+                self.role,
+                (schema_id, schema_name),
+                SchemaPrivilege::Usage,
+            )?;
+        }
+        match full_table_id {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Result to Client");
+                Err(())
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(
+                        schema_name.to_owned() + "." + table_name,
+                    )))
+                    .expect("To Send Result to Client");
+                Err(())
+            }
+            Some((schema_id, Some(table_id))) => Ok(TableId((schema_id, table_id))),
+        }
+    }
+
+    /// `Project` of `Limit`, `Sort`, `Window` and `Filter` operators over the scan of the table, operators that
+    /// don't change rows are left out
+    fn relation(
+        &self,
+        table_id: TableId,
+        table_name: FullTableName,
+        select: &Select,
+        all_columns: &[ColumnDefinition],
+        sender: &dyn Sender,
+    ) -> Result<RelationOp> {
+        let (order_by, limit) = self.plan_rows(sender)?;
+        let mut columns = vec![];
+        let mut functions = vec![];
+        for item in select.projection.iter() {
+            match item {
+                SelectItem::Wildcard => columns.extend(
+                    all_columns
+                        .iter()
+                        .map(|column_definition| ProjectedColumn::Column(column_definition.name())),
+                ),
+                SelectItem::UnnamedExpr(Expr::Identifier(Ident { value, .. })) => {
+                    columns.push(ProjectedColumn::Column(value.clone()))
                 }
-                Err(error) => {
+                SelectItem::UnnamedExpr(Expr::Function(function)) if function.over.is_some() => {
+                    let window = function.over.as_ref().expect("window is given");
+                    columns.push(ProjectedColumn::Window(
+                        column_name(&Expr::Function(function.clone())),
+                        functions.len(),
+                    ));
+                    functions.push(self.window_function(function, window, sender)?);
+                }
+                SelectItem::ExprWithAlias {
+                    expr: Expr::Function(function),
+                    alias,
+                } if function.over.is_some() => {
+                    let window = function.over.as_ref().expect("window is given");
+                    columns.push(ProjectedColumn::Window(alias.value.clone(), functions.len()));
+                    functions.push(self.window_function(function, window, sender)?);
+                }
+                SelectItem::UnnamedExpr(expr) => columns.push(ProjectedColumn::Expr(column_name(expr), expr.clone())),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    columns.push(ProjectedColumn::Expr(alias.value.clone(), expr.clone()))
+                }
+                SelectItem::QualifiedWildcard(_) => {
                     sender
-                        .send(Err(QueryError::syntax_error(error)))
+                        .send(Err(QueryError::feature_not_supported(&*self.query)))
                         .expect("To Send Query Result to Client");
                     return Err(());
                 }
             }
-        } else {
+        }
+
+        let mut relation = RelationOp::Scan {
+            table_id,
+            table_name,
+            ranges: vec![],
+            filters: vec![],
+        };
+        if let Some(predicate) = &select.selection {
+            relation = RelationOp::Filter {
+                input: Box::new(relation),
+                predicate: predicate.clone(),
+            };
+        }
+        if !functions.is_empty() {
+            relation = RelationOp::Window {
+                input: Box::new(relation),
+                functions,
+            };
+        }
+        if !order_by.is_empty() {
+            relation = RelationOp::Sort {
+                input: Box::new(relation),
+                order_by,
+            };
+        }
+        if limit != RowLimit::default() {
+            relation = RelationOp::Limit {
+                input: Box::new(relation),
+                limit,
+            };
+        }
+        Ok(RelationOp::Project {
+            input: Box::new(relation),
+            columns,
+        })
+    }
+}
+
+fn frame_bound(bound: &ast::WindowFrameBound) -> FrameBound {
+    match bound {
+        ast::WindowFrameBound::CurrentRow => FrameBound::CurrentRow,
+        ast::WindowFrameBound::Preceding(None) => FrameBound::UnboundedPreceding,
+        ast::WindowFrameBound::Preceding(Some(offset)) => FrameBound::Preceding(*offset),
+        ast::WindowFrameBound::Following(None) => FrameBound::UnboundedFollowing,
+        ast::WindowFrameBound::Following(Some(offset)) => FrameBound::Following(*offset),
+    }
+}
+
+/// frame checks and their messages follow PostgreSQL
+fn validate_frame(frame: &WindowFrame, order_by_columns: usize) -> std::result::Result<(), &'static str> {
+    match (frame.start, frame.end) {
+        (FrameBound::UnboundedFollowing, _) => return Err("frame start cannot be UNBOUNDED FOLLOWING"),
+        (_, FrameBound::UnboundedPreceding) => return Err("frame end cannot be UNBOUNDED PRECEDING"),
+        (FrameBound::CurrentRow, FrameBound::Preceding(_)) => {
+            return Err("frame starting from current row cannot have preceding rows")
+        }
+        (FrameBound::Following(_), FrameBound::Preceding(_)) | (FrameBound::Following(_), FrameBound::CurrentRow) => {
+            return Err("frame starting from following row cannot have preceding rows")
+        }
+        _ => {}
+    }
+    let has_offset = [frame.start, frame.end]
+        .iter()
+        .any(|bound| matches!(bound, FrameBound::Preceding(_) | FrameBound::Following(_)));
+    if frame.units == FrameUnits::Range && has_offset && order_by_columns != 1 {
+        return Err("RANGE with offset PRECEDING/FOLLOWING requires exactly one ORDER BY column");
+    }
+    Ok(())
+}
+
+/// row counts can only be non negative integer literals, `$n` placeholders don't limit rows until their values
+/// are bound
+fn row_count(expr: &Expr, sender: &dyn Sender) -> Result<Option<u64>> {
+    if param_index(expr).is_some() {
+        return Ok(None);
+    }
+    match expr.to_string().parse() {
+        Ok(count) => Ok(Some(count)),
+        Err(_) => {
             sender
-                .send(Err(QueryError::feature_not_supported(&*self.query)))
+                .send(Err(QueryError::invalid_parameter_value(format!(
+                    "row count must be a non negative integer, found: {}",
+                    expr
+                ))))
                 .expect("To Send Query Result to Client");
-            return Err(());
+            Err(())
+        }
+    }
+}
+
+/// name and type of an integer column of the table the expression refers to
+fn integer_column(expr: &Expr, columns: &[ColumnDefinition]) -> Option<(String, SqlType)> {
+    let column_name = match expr {
+        Expr::Identifier(ident) => ident,
+        Expr::CompoundIdentifier(idents) => idents.last()?,
+        _ => return None,
+    };
+    columns
+        .iter()
+        .find(|column| column.has_name(&column_name.value) && is_integer(column.sql_type()))
+        .map(|column| (column.name(), column.sql_type()))
+}
+
+/// `value <op> column` is the same as `column <flipped op> value`
+fn flipped(op: &BinaryOperator) -> BinaryOperator {
+    match op {
+        BinaryOperator::Gt => BinaryOperator::Lt,
+        BinaryOperator::GtEq => BinaryOperator::LtEq,
+        BinaryOperator::Lt => BinaryOperator::Gt,
+        BinaryOperator::LtEq => BinaryOperator::GtEq,
+        op => op.clone(),
+    }
+}
+
+fn narrow(ranges: &mut Vec<ColumnRange>, column_name: String, lower: Option<i64>, upper: Option<i64>) {
+    match ranges.iter_mut().find(|range| range.column_name == column_name) {
+        Some(range) => {
+            range.lower = range.lower.max(lower);
+            range.upper = match (range.upper, upper) {
+                (Some(current), Some(upper)) => Some(current.min(upper)),
+                (current, upper) => current.or(upper),
+            };
+        }
+        None => ranges.push(ColumnRange {
+            column_name,
+            lower,
+            upper,
+        }),
+    }
+}
+
+/// bounds of an integer column that a comparison with a literal or `BETWEEN` literals gives
+fn column_range(expr: &Expr, columns: &[ColumnDefinition], sender: &dyn Sender) -> Result<Option<ColumnRange>> {
+    let range = match expr {
+        Expr::BinaryOp { left, op, right } => {
+            let comparison = match (integer_column(left, columns), integer_column(right, columns)) {
+                (Some((column_name, sql_type)), None) => {
+                    integer_operand(right, &sql_type, sender)?.map(|value| (column_name, op.clone(), value))
+                }
+                (None, Some((column_name, sql_type))) => {
+                    integer_operand(left, &sql_type, sender)?.map(|value| (column_name, flipped(op), value))
+                }
+                _ => None,
+            };
+            comparison.and_then(|(column_name, op, value)| {
+                comparison_bounds(&op, value).map(|(lower, upper)| ColumnRange {
+                    column_name,
+                    lower,
+                    upper,
+                })
+            })
+        }
+        Expr::Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        } => match integer_column(expr, columns) {
+            Some((column_name, sql_type)) => {
+                let low = integer_operand(low, &sql_type, sender)?;
+                let high = integer_operand(high, &sql_type, sender)?;
+                let low = low.and_then(|low| comparison_bounds(&BinaryOperator::GtEq, low));
+                let high = high.and_then(|high| comparison_bounds(&BinaryOperator::LtEq, high));
+                match (low, high) {
+                    (Some((lower, _)), Some((_, upper))) => Some(ColumnRange {
+                        column_name,
+                        lower,
+                        upper,
+                    }),
+                    _ => None,
+                }
+            }
+            None => None,
+        },
+        _ => None,
+    };
+    Ok(range)
+}
+
+/// expression of a filter in the form that rows are evaluated with: column names are qualified only by the name of
+/// the scanned table and `BETWEEN` is a pair of comparisons
+fn filter_expr(expr: &Expr) -> Expr {
+    match expr {
+        Expr::CompoundIdentifier(idents) => match idents.last() {
+            Some(ident) => Expr::Identifier(ident.clone()),
+            None => expr.clone(),
+        },
+        Expr::Nested(expr) => Expr::Nested(Box::new(filter_expr(expr))),
+        Expr::UnaryOp { op, expr } => Expr::UnaryOp {
+            op: op.clone(),
+            expr: Box::new(filter_expr(expr)),
+        },
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(filter_expr(expr)),
+            data_type: data_type.clone(),
+        },
+        Expr::BinaryOp { left, op, right } => Expr::BinaryOp {
+            left: Box::new(filter_expr(left)),
+            op: op.clone(),
+            right: Box::new(filter_expr(right)),
+        },
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => {
+            let comparison = |op: BinaryOperator, bound: &Expr| Expr::BinaryOp {
+                left: Box::new(filter_expr(expr)),
+                op,
+                right: Box::new(filter_expr(bound)),
+            };
+            let (low, high, op) = if *negated {
+                (
+                    comparison(BinaryOperator::Lt, low),
+                    comparison(BinaryOperator::Gt, high),
+                    BinaryOperator::Or,
+                )
+            } else {
+                (
+                    comparison(BinaryOperator::GtEq, low),
+                    comparison(BinaryOperator::LtEq, high),
+                    BinaryOperator::And,
+                )
+            };
+            Expr::Nested(Box::new(Expr::BinaryOp {
+                left: Box::new(low),
+                op,
+                right: Box::new(high),
+            }))
+        }
+        Expr::Function(function) => Expr::Function(Function {
+            args: function.args.iter().map(filter_expr).collect(),
+            ..function.clone()
+        }),
+        expr => expr.clone(),
+    }
+}
+
+/// conjuncts of `WHERE` are pushed down into the scan: comparisons of integer columns with literals become ranges
+/// that bound scanned keys and partitions, the rest are filters that every scanned row is checked with before
+/// windows, sorting and projection see it
+fn push_down_predicates(
+    expr: &Expr,
+    columns: &[ColumnDefinition],
+    ranges: &mut Vec<ColumnRange>,
+    filters: &mut Vec<Expr>,
+    sender: &dyn Sender,
+) -> Result<()> {
+    match expr {
+        Expr::Nested(expr) => push_down_predicates(expr, columns, ranges, filters, sender)?,
+        // conjuncts that are folded into `true` don't filter rows
+        Expr::Value(Value::Boolean(true)) => {}
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            push_down_predicates(left, columns, ranges, filters, sender)?;
+            push_down_predicates(right, columns, ranges, filters, sender)?;
+        }
+        expr => match column_range(expr, columns, sender)? {
+            Some(ColumnRange {
+                column_name,
+                lower,
+                upper,
+            }) => narrow(ranges, column_name, lower, upper),
+            None => filters.push(filter_expr(expr)),
+        },
+    }
+    Ok(())
+}
+
+/// conjuncts of a filter over the scan are pushed down into it
+struct PushDownFilters<'p> {
+    columns: &'p [ColumnDefinition],
+    sender: &'p dyn Sender,
+}
+
+impl Rule for PushDownFilters<'_> {
+    fn traversal(&self) -> Traversal {
+        Traversal::BottomUp
+    }
+
+    fn apply(&self, relation: RelationOp) -> Result<RelationOp> {
+        match relation {
+            RelationOp::Filter { input, predicate } => match *input {
+                RelationOp::Scan {
+                    table_id,
+                    table_name,
+                    mut ranges,
+                    mut filters,
+                } => {
+                    push_down_predicates(&predicate, self.columns, &mut ranges, &mut filters, self.sender)?;
+                    Ok(RelationOp::Scan {
+                        table_id,
+                        table_name,
+                        ranges,
+                        filters,
+                    })
+                }
+                input => Ok(RelationOp::Filter {
+                    input: Box::new(input),
+                    predicate,
+                }),
+            },
+            relation => Ok(relation),
+        }
+    }
+}
+
+/// optimizer passes over the logical plan of a select from the table with the columns
+pub(crate) fn optimized(relation: RelationOp, columns: &[ColumnDefinition], sender: &dyn Sender) -> Result<RelationOp> {
+    optimize(
+        relation,
+        &[
+            &FoldConstants,
+            &EliminateRedundantOperators,
+            &PushDownFilters { columns, sender },
+        ],
+    )
+}
+
+/// pseudo table that lists foreign keys and filtered columns without supporting indexes
+const INDEX_ADVICE: &str = "index_advice";
+
+/// name of a column that PostgreSQL gives to an expression without alias
+fn column_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) if ident.quote_style.is_none() => ident.value.to_lowercase(),
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::Function(function) => match function.name.0.last() {
+            Some(name) => name.value.to_lowercase(),
+            None => "?column?".to_owned(),
+        },
+        _ => "?column?".to_owned(),
+    }
+}
+
+impl Planner for SelectPlanner<'_> {
+    fn plan(self, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Result<Plan> {
+        let select = match &self.query.body {
+            SetExpr::Select(select) => select,
+            _ => {
+                sender
+                    .send(Err(QueryError::feature_not_supported(&*self.query)))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
         };
-        Ok(Plan::Select(result?))
+        if select.from.is_empty() {
+            return self.plan_values(&select.projection, sender.as_ref());
+        }
+        match self.logical_plan(&data_manager, sender.as_ref())? {
+            Some(relation) => lower(relation, &data_manager).map(Plan::Select),
+            None => self.plan_index_advice(&select.projection, &data_manager, sender.as_ref()),
+        }
     }
 }
@@ -0,0 +1,154 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Detection of parsed constructs the engine can't execute, done before planning starts so that a statement
+///! is rejected as a whole instead of failing in the middle of its execution.
+use sqlparser::ast::{Expr, Function, Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins};
+
+/// name of the first construct in `statement` the engine can't execute
+pub(crate) fn unsupported_construct(statement: &Statement) -> Option<&'static str> {
+    match statement {
+        Statement::Query(query) => query_construct(query),
+        Statement::Insert { source, .. } => {
+            if !source.ctes.is_empty() {
+                return Some("WITH");
+            }
+            match &source.body {
+                SetExpr::Values(values) => values.0.iter().flatten().find_map(|value| expr_construct(value, false)),
+                _ => Some("INSERT ... SELECT"),
+            }
+        }
+        Statement::Update {
+            assignments, selection, ..
+        } => assignments
+            .iter()
+            .map(|assignment| &assignment.value)
+            .chain(selection)
+            .find_map(|expr| expr_construct(expr, false)),
+        Statement::Delete { selection, .. } => selection
+            .as_ref()
+            .and_then(|selection| expr_construct(selection, false)),
+        _ => None,
+    }
+}
+
+fn query_construct(query: &Query) -> Option<&'static str> {
+    if !query.ctes.is_empty() {
+        return Some("WITH");
+    }
+    match &query.body {
+        SetExpr::Select(select) => select_construct(select),
+        SetExpr::Query(_) => Some("parenthesized query"),
+        SetExpr::SetOperation { .. } => Some("UNION, EXCEPT or INTERSECT"),
+        SetExpr::Values(_) => Some("VALUES"),
+    }
+}
+
+fn select_construct(select: &Select) -> Option<&'static str> {
+    if select.distinct {
+        return Some("SELECT DISTINCT");
+    }
+    if select.top.is_some() {
+        return Some("TOP");
+    }
+    if select.from.len() > 1 {
+        return Some("multiple tables in FROM");
+    }
+    if !select.group_by.is_empty() {
+        return Some("GROUP BY");
+    }
+    if select.having.is_some() {
+        return Some("HAVING");
+    }
+    if let Some(construct) = select.from.iter().find_map(from_construct) {
+        return Some(construct);
+    }
+    if let Some(construct) = select.projection.iter().find_map(projection_construct) {
+        return Some(construct);
+    }
+    select
+        .selection
+        .as_ref()
+        .and_then(|selection| expr_construct(selection, true))
+}
+
+fn from_construct(from: &TableWithJoins) -> Option<&'static str> {
+    if !from.joins.is_empty() {
+        return Some("JOIN");
+    }
+    match &from.relation {
+        TableFactor::Table { args, .. } if !args.is_empty() => Some("table function"),
+        TableFactor::Table { .. } => None,
+        TableFactor::Derived { .. } => Some("subquery in FROM"),
+        TableFactor::NestedJoin(_) => Some("JOIN"),
+    }
+}
+
+fn projection_construct(item: &SelectItem) -> Option<&'static str> {
+    match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => expr_construct(expr, false),
+        SelectItem::QualifiedWildcard(_) => Some("qualified wildcard"),
+        SelectItem::Wildcard => None,
+    }
+}
+
+fn function_construct(function: &Function) -> Option<&'static str> {
+    if function.distinct {
+        return Some("DISTINCT in function arguments");
+    }
+    function.args.iter().find_map(|arg| match arg {
+        Expr::Wildcard => None,
+        arg => expr_construct(arg, false),
+    })
+}
+
+/// qualified column names are only understood by `WHERE` clause of `SELECT` where they limit ranges of scanned rows
+fn expr_construct(expr: &Expr, filter: bool) -> Option<&'static str> {
+    match expr {
+        Expr::Identifier(_) | Expr::Value(_) => None,
+        Expr::CompoundIdentifier(_) if filter => None,
+        Expr::CompoundIdentifier(_) => Some("qualified column name"),
+        Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Extract { expr, .. } => expr_construct(expr, filter),
+        Expr::BinaryOp { left, right, .. } => expr_construct(left, filter).or_else(|| expr_construct(right, filter)),
+        Expr::Between { expr, low, high, .. } => expr_construct(expr, filter)
+            .or_else(|| expr_construct(low, filter))
+            .or_else(|| expr_construct(high, filter)),
+        Expr::InList { expr, list, .. } => {
+            expr_construct(expr, filter).or_else(|| list.iter().find_map(|item| expr_construct(item, filter)))
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .as_deref()
+            .into_iter()
+            .chain(conditions.iter())
+            .chain(results.iter())
+            .chain(else_result.as_deref())
+            .find_map(|expr| expr_construct(expr, filter)),
+        Expr::Function(function) => function_construct(function),
+        Expr::Wildcard | Expr::QualifiedWildcard(_) => Some("wildcard in expression"),
+        Expr::InSubquery { .. } | Expr::Exists(_) | Expr::Subquery(_) => Some("subquery"),
+        Expr::Collate { .. } => Some("COLLATE"),
+        Expr::TypedString { .. } => Some("typed string literal"),
+        Expr::ListAgg(_) => Some("LISTAGG"),
+    }
+}
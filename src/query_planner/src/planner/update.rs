@@ -14,26 +14,42 @@
 
 use crate::{
     plan::{Plan, TableUpdates},
-    planner::{Planner, Result},
+    planner::{check_schema_privilege, Planner, Result},
     FullTableName, TableId,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, SchemaPrivilege};
 use protocol::{results::QueryError, Sender};
-use sqlparser::ast::{Assignment, ObjectName};
+use sqlparser::ast::{Assignment, Expr, ObjectName};
 use std::{convert::TryFrom, sync::Arc};
 
 pub(crate) struct UpdatePlanner<'up> {
     table_name: &'up ObjectName,
     assignments: &'up [Assignment],
+    selection: Option<&'up Expr>,
+    role: &'up str,
+    journal: Option<&'up Journal>,
 }
 
 impl<'up> UpdatePlanner<'up> {
-    pub(crate) fn new(table_name: &'up ObjectName, assignments: &'up [Assignment]) -> UpdatePlanner<'up> {
+    pub(crate) fn new(
+        table_name: &'up ObjectName,
+        assignments: &'up [Assignment],
+        selection: Option<&'up Expr>,
+        role: &'up str,
+    ) -> UpdatePlanner<'up> {
         UpdatePlanner {
             table_name,
             assignments,
+            selection,
+            role,
+            journal: None,
         }
     }
+
+    pub(crate) fn with_journal(mut self, journal: Option<&'up Journal>) -> UpdatePlanner<'up> {
+        self.journal = journal;
+        self
+    }
 }
 
 impl Planner for UpdatePlanner<'_> {
@@ -41,7 +57,17 @@ impl Planner for UpdatePlanner<'_> {
         match FullTableName::try_from(self.table_name) {
             Ok(full_table_name) => {
                 let (schema_name, table_name) = full_table_name.as_tuple();
-                match data_manager.table_exists(&schema_name, &table_name) {
+                let full_table_id = data_manager.table_exists_in(self.journal, &schema_name, &table_name);
+                if let Some((schema_id, _)) = full_table_id {
+                    check_schema_privilege(
+                        &data_manager,
+                        sender.as_ref(),
+                        self.role,
+                        (schema_id, schema_name),
+                        SchemaPrivilege::Usage,
+                    )?;
+                }
+                match full_table_id {
                     None => {
                         sender
                             .send(Err(QueryError::schema_does_not_exist(schema_name)))
@@ -57,9 +83,23 @@ impl Planner for UpdatePlanner<'_> {
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
+                    Some((schema_id, Some(table_id)))
+                        if data_manager
+                            .range_partitioning(&Box::new((schema_id, table_id)))
+                            .is_some() =>
+                    {
+                        sender
+                            .send(Err(QueryError::feature_not_supported(format!(
+                                "UPDATE on partitioned table \"{}\"",
+                                full_table_name
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
                     Some((schema_id, Some(table_id))) => Ok(Plan::Update(TableUpdates {
                         table_id: TableId((schema_id, table_id)),
                         assignments: self.assignments.to_vec(),
+                        filter: self.selection.cloned(),
                     })),
                 }
             }
@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use super::*;
-use crate::plan::{Plan, SchemaCreationInfo};
+use crate::{
+    extended::ExtendedStatement,
+    plan::{Plan, SchemaCreationInfo, SchemaTemplate},
+};
 use protocol::results::QueryError;
 use sqlparser::ast::Statement;
 
@@ -62,3 +65,36 @@ fn create_schema_with_unqualified_name(planner_and_sender: (QueryPlanner, Result
         "only unqualified schema names are supported, 'first_part.second_part.third_part.fourth_part'",
     ))])
 }
+
+#[rstest::rstest]
+fn create_schema_like_existing_one(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert!(matches!(
+        query_planner.plan_extended(ExtendedStatement::CreateSchemaLike {
+            schema_name: ObjectName(vec![ident("tenant")]),
+            template_name: ObjectName(vec![ident(SCHEMA)]),
+            with_data: true,
+        }),
+        Ok(Plan::CreateSchema(SchemaCreationInfo {
+            template: Some(SchemaTemplate { with_data: true, .. }),
+            ..
+        }))
+    ));
+
+    collector.assert_content(vec![]);
+}
+
+#[rstest::rstest]
+fn create_schema_like_nonexistent_one(planner_and_sender: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender;
+    assert_eq!(
+        query_planner.plan_extended(ExtendedStatement::CreateSchemaLike {
+            schema_name: ObjectName(vec![ident("tenant")]),
+            template_name: ObjectName(vec![ident(SCHEMA)]),
+            with_data: false,
+        }),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::schema_does_not_exist(SCHEMA))])
+}
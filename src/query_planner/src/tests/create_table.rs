@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use super::*;
-use crate::plan::{Plan, TableCreationInfo};
+use crate::{
+    extended::ExtendedStatement,
+    plan::{Plan, TableCreationInfo, TablePartitioning},
+};
 use data_manager::ColumnDefinition;
 use protocol::results::QueryError;
-use sqlparser::ast::{ColumnDef, DataType, Statement};
+use sqlparser::ast::{BinaryOperator, ColumnDef, ColumnOption, ColumnOptionDef, DataType, Expr, Statement, Value};
 
 fn column(name: &str, data_type: DataType) -> ColumnDef {
     ColumnDef {
@@ -136,3 +139,259 @@ fn create_table(planner_and_sender_with_schema: (QueryPlanner, ResultCollector))
 
     collector.assert_content(vec![])
 }
+
+#[rstest::rstest]
+fn create_table_without_create_privilege_on_schema(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (mut query_planner, collector) = planner_and_sender_with_schema;
+    query_planner.set_role("some_role");
+
+    assert_eq!(query_planner.plan(table(vec![SCHEMA, TABLE], vec![])), Err(()));
+
+    collector.assert_content(vec![Err(QueryError::permission_denied_for_schema(SCHEMA))])
+}
+
+#[rstest::rstest]
+fn create_table_with_primary_key(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    let mut primary_key_column = column("id", DataType::SmallInt);
+    primary_key_column.options.push(ColumnOptionDef {
+        name: None,
+        option: ColumnOption::Unique { is_primary: true },
+    });
+    assert_eq!(
+        query_planner.plan(table(
+            vec![SCHEMA, TABLE],
+            vec![primary_key_column, column("column_name", DataType::SmallInt)]
+        )),
+        Ok(Plan::CreateTable(TableCreationInfo::new(
+            0,
+            TABLE,
+            vec![
+                ColumnDefinition::new("id", SqlType::SmallInt(i16::min_value())).primary_key(),
+                ColumnDefinition::new("column_name", SqlType::SmallInt(i16::min_value()))
+            ]
+        )))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn create_table_with_not_null_column(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    let mut not_null_column = column("column_name", DataType::SmallInt);
+    not_null_column.options.push(ColumnOptionDef {
+        name: None,
+        option: ColumnOption::NotNull,
+    });
+    assert_eq!(
+        query_planner.plan(table(vec![SCHEMA, TABLE], vec![not_null_column])),
+        Ok(Plan::CreateTable(TableCreationInfo::new(
+            0,
+            TABLE,
+            vec![ColumnDefinition::new("column_name", SqlType::SmallInt(i16::min_value())).not_null()]
+        )))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn create_table_referencing_table_without_primary_key(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    let mut referencing_column = column("column_name", DataType::SmallInt);
+    referencing_column.options.push(ColumnOptionDef {
+        name: None,
+        option: ColumnOption::ForeignKey {
+            foreign_table: ObjectName(vec![ident(SCHEMA), ident(TABLE)]),
+            referred_columns: vec![],
+            on_delete: None,
+            on_update: None,
+        },
+    });
+    assert_eq!(
+        query_planner.plan(table(vec![SCHEMA, "other_table"], vec![referencing_column])),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::invalid_foreign_key(format!(
+        "{}.{}",
+        SCHEMA, TABLE
+    )))])
+}
+
+#[rstest::rstest]
+fn create_table_referencing_nonexistent_table(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    let mut referencing_column = column("column_name", DataType::SmallInt);
+    referencing_column.options.push(ColumnOptionDef {
+        name: None,
+        option: ColumnOption::ForeignKey {
+            foreign_table: ObjectName(vec![ident(SCHEMA), ident("non_existent")]),
+            referred_columns: vec![],
+            on_delete: None,
+            on_update: None,
+        },
+    });
+    assert_eq!(
+        query_planner.plan(table(vec![SCHEMA, TABLE], vec![referencing_column])),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::table_does_not_exist(format!(
+        "{}.non_existent",
+        SCHEMA
+    )))])
+}
+
+#[rstest::rstest]
+fn create_table_with_serial_column(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan(table(
+            vec![SCHEMA, TABLE],
+            vec![column("id", DataType::Custom(ObjectName(vec![ident("serial")])))]
+        )),
+        Ok(Plan::CreateTable(TableCreationInfo::new(
+            0,
+            TABLE,
+            vec![ColumnDefinition::new("id", SqlType::Integer(1))
+                .not_null()
+                .serial("table_name_id_seq")]
+        )))
+    );
+
+    collector.assert_content(vec![])
+}
+
+fn table_with_generated_columns(generated_columns: Vec<(&str, Expr)>) -> ExtendedStatement {
+    ExtendedStatement::CreateTable {
+        statement: Box::new(table(
+            vec![SCHEMA, TABLE],
+            vec![
+                column("price", DataType::Int),
+                column("total", DataType::Int),
+                column("doubled_total", DataType::Int),
+            ],
+        )),
+        generated_columns: generated_columns
+            .into_iter()
+            .map(|(name, expr)| (ident(name), expr))
+            .collect(),
+        partition_by: None,
+    }
+}
+
+fn doubled(column_name: &str) -> Expr {
+    Expr::BinaryOp {
+        left: Box::new(Expr::Identifier(ident(column_name))),
+        op: BinaryOperator::Multiply,
+        right: Box::new(Expr::Value(Value::Number(2i64.into()))),
+    }
+}
+
+#[rstest::rstest]
+fn create_table_with_generated_column(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(table_with_generated_columns(vec![("total", doubled("price"))])),
+        Ok(Plan::CreateTable(TableCreationInfo::new(
+            0,
+            TABLE,
+            vec![
+                ColumnDefinition::new("price", SqlType::Integer(i32::min_value())),
+                ColumnDefinition::new("total", SqlType::Integer(i32::min_value())).generated_always_as("price * 2"),
+                ColumnDefinition::new("doubled_total", SqlType::Integer(i32::min_value())),
+            ]
+        )))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn create_table_with_column_generated_from_generated_column(
+    planner_and_sender_with_schema: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(table_with_generated_columns(vec![
+            ("total", doubled("price")),
+            ("doubled_total", doubled("total"))
+        ])),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::invalid_object_definition(
+        "cannot use generated column \"total\" in column generation expression",
+    ))])
+}
+
+#[rstest::rstest]
+fn create_table_with_column_generated_from_nonexistent_column(
+    planner_and_sender_with_schema: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(table_with_generated_columns(vec![("total", doubled("cost"))])),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::column_does_not_exist("cost"))])
+}
+
+fn table_partitioned_by(column_name: &str) -> ExtendedStatement {
+    ExtendedStatement::CreateTable {
+        statement: Box::new(table(
+            vec![SCHEMA, TABLE],
+            vec![
+                column("id", DataType::BigInt),
+                column("name", DataType::Varchar(Some(10))),
+            ],
+        )),
+        generated_columns: vec![],
+        partition_by: Some(ident(column_name)),
+    }
+}
+
+#[rstest::rstest]
+fn create_table_partitioned_by_range(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(table_partitioned_by("id")),
+        Ok(Plan::CreateTable(
+            TableCreationInfo::new(
+                0,
+                TABLE,
+                vec![
+                    ColumnDefinition::new("id", SqlType::BigInt(i64::min_value())),
+                    ColumnDefinition::new("name", SqlType::VarChar(10)),
+                ]
+            )
+            .partitioned(TablePartitioning::ByRange("id".to_owned()))
+        ))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn create_table_partitioned_by_nonexistent_column(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(table_partitioned_by("non_existent")),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::column_does_not_exist("non_existent"))])
+}
+
+#[rstest::rstest]
+fn create_table_partitioned_by_non_integer_column(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(query_planner.plan_extended(table_partitioned_by("name")), Err(()));
+
+    collector.assert_content(vec![Err(QueryError::feature_not_supported(
+        "range partition key \"name\" of non integer type",
+    ))])
+}
@@ -19,7 +19,7 @@ use crate::{
     tests::{ident, ResultCollector, TABLE},
 };
 use protocol::results::QueryError;
-use sqlparser::ast::{ObjectName, Statement};
+use sqlparser::ast::{Expr, ObjectName, Statement};
 
 #[rstest::rstest]
 fn delete_from_table_that_in_nonexistent_schema(planner_and_sender: (QueryPlanner, ResultCollector)) {
@@ -98,9 +98,44 @@ fn delete_from_table(planner_and_sender_with_table: (QueryPlanner, ResultCollect
             selection: None
         }),
         Ok(Plan::Delete(TableDeletes {
-            table_id: TableId((0, 0))
+            table_id: TableId((0, 0)),
+            filter: None
         }))
     );
 
     collector.assert_content(vec![])
 }
+
+#[rstest::rstest]
+fn delete_from_table_where(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    let selection = Expr::IsNull(Box::new(Expr::Identifier(ident("id"))));
+    assert_eq!(
+        query_planner.plan(Statement::Delete {
+            table_name: ObjectName(vec![ident(SCHEMA), ident(TABLE)]),
+            selection: Some(selection.clone())
+        }),
+        Ok(Plan::Delete(TableDeletes {
+            table_id: TableId((0, 0)),
+            filter: Some(selection)
+        }))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn delete_from_table_without_usage_privilege_on_schema(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (mut query_planner, collector) = planner_and_sender_with_table;
+    query_planner.set_role("some_role");
+
+    assert_eq!(
+        query_planner.plan(Statement::Delete {
+            table_name: ObjectName(vec![ident(SCHEMA), ident(TABLE)]),
+            selection: None
+        }),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::permission_denied_for_schema(SCHEMA))])
+}
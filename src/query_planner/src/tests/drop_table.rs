@@ -14,7 +14,7 @@
 
 use super::*;
 use crate::plan::Plan;
-use protocol::results::QueryError;
+use protocol::results::{QueryError, QueryEvent};
 use sqlparser::ast::{ObjectType, Statement};
 
 #[rstest::rstest]
@@ -108,3 +108,21 @@ fn drop_table(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
 
     collector.assert_content(vec![])
 }
+
+#[rstest::rstest]
+fn drop_nonexistent_table_if_exists(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan(Statement::Drop {
+            object_type: ObjectType::Table,
+            if_exists: true,
+            names: vec![ObjectName(vec![ident(SCHEMA), ident("non_existent_table")])],
+            cascade: false,
+        }),
+        Ok(Plan::Skipped(QueryEvent::TableDropped))
+    );
+
+    collector.assert_content(vec![Ok(QueryEvent::Skipped(QueryError::table_does_not_exist(
+        format!("{}.{}", SCHEMA, "non_existent_table"),
+    )))])
+}
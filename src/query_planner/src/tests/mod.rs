@@ -16,7 +16,10 @@ use super::*;
 use crate::planner::QueryPlanner;
 use data_manager::DataManager;
 use protocol::{results::QueryResult, Sender};
-use sqlparser::ast::Ident;
+use sqlparser::{
+    ast::{Ident, Statement},
+    dialect::PostgreSqlDialect,
+};
 use std::{
     io,
     ops::Deref,
@@ -36,8 +39,16 @@ mod drop_table;
 #[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod params;
+#[cfg(test)]
+mod scan_costs;
+#[cfg(test)]
+mod schema_privileges;
+#[cfg(test)]
 mod select;
 #[cfg(test)]
+mod sequences;
+#[cfg(test)]
 mod update;
 
 struct Collector(Mutex<Vec<QueryResult>>);
@@ -94,6 +105,13 @@ fn planner_and_sender_with_table() -> (QueryPlanner, ResultCollector) {
     (QueryPlanner::new(Arc::new(manager), collector.clone()), collector)
 }
 
+fn statement(sql: &str) -> Statement {
+    crate::extended::parse_sql(&PostgreSqlDialect {}, sql)
+        .expect("statement parsed")
+        .pop()
+        .expect("one statement")
+}
+
 fn ident<S: ToString>(name: S) -> Ident {
     Ident {
         value: name.to_string(),
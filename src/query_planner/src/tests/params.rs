@@ -0,0 +1,213 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    extended,
+    plan::{param_index, ColumnRange, Param, Plan},
+    planner::QueryPlanner,
+    tests::{ResultCollector, SCHEMA, TABLE},
+};
+use data_manager::ColumnDefinition;
+use protocol::results::QueryError;
+use sql_model::sql_types::SqlType;
+use sqlparser::{
+    ast::{Expr, Statement, Value},
+    dialect::Dialect,
+};
+
+#[derive(Debug)]
+struct PlaceholderDialect;
+
+impl Dialect for PlaceholderDialect {
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch.is_ascii_alphabetic() || ch == '$' || ch == '_'
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || ch == '$' || ch == '_'
+    }
+}
+
+fn statement(sql: &str) -> Statement {
+    extended::parse_sql(&PlaceholderDialect, sql)
+        .expect("statement parsed")
+        .pop()
+        .expect("one statement")
+}
+
+#[rstest::fixture]
+fn planner_and_sender_with_columns() -> (QueryPlanner, ResultCollector) {
+    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let manager = DataManager::in_memory().expect("to create data manager");
+    let schema_id = manager.create_schema(SCHEMA).expect("schema created");
+    manager
+        .create_table(
+            schema_id,
+            TABLE,
+            &[
+                ColumnDefinition::new("id", SqlType::Integer(i32::min_value())),
+                ColumnDefinition::new("name", SqlType::VarChar(10)),
+            ],
+        )
+        .expect("table created");
+    (QueryPlanner::new(Arc::new(manager), collector.clone()), collector)
+}
+
+fn column_param(index: usize, column_name: &str, sql_type: SqlType) -> Param {
+    Param {
+        index,
+        column_name: Some(column_name.to_owned()),
+        sql_type,
+    }
+}
+
+#[rstest::rstest]
+fn params_of_insert_take_types_of_listed_columns(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    assert_eq!(
+        query_planner
+            .plan_prepared(&statement(
+                "insert into schema_name.table_name (name, id) values ($2, $1);"
+            ))
+            .map(|(_plan, params)| params),
+        Ok(vec![
+            column_param(1, "name", SqlType::VarChar(10)),
+            column_param(0, "id", SqlType::Integer(i32::min_value())),
+        ])
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn params_of_select_take_types_of_compared_columns(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    assert_eq!(
+        query_planner
+            .plan_prepared(&statement(
+                "select * from schema_name.table_name where $1 < id and (name = $2 or id between 1 and $3);"
+            ))
+            .map(|(_plan, params)| params),
+        Ok(vec![
+            column_param(0, "id", SqlType::Integer(i32::min_value())),
+            column_param(1, "name", SqlType::VarChar(10)),
+            column_param(2, "id", SqlType::Integer(i32::min_value())),
+        ])
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn params_of_update_and_delete_take_types_of_compared_columns(
+    planner_and_sender_with_columns: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    assert_eq!(
+        query_planner
+            .plan_prepared(&statement("update schema_name.table_name set name = $1 where id = $2;"))
+            .map(|(_plan, params)| params),
+        Ok(vec![
+            column_param(0, "name", SqlType::VarChar(10)),
+            column_param(1, "id", SqlType::Integer(i32::min_value())),
+        ])
+    );
+    assert_eq!(
+        query_planner
+            .plan_prepared(&statement("delete from schema_name.table_name where $1 = name;"))
+            .map(|(_plan, params)| params),
+        Ok(vec![column_param(0, "name", SqlType::VarChar(10))])
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn params_of_statement_that_can_not_be_planned(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    assert_eq!(
+        query_planner
+            .plan_prepared(&statement("select * from schema_name.non_existent where id = $1;"))
+            .map(|(_plan, params)| params),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::table_does_not_exist("schema_name.non_existent"))])
+}
+
+/// `$1` compared with a column is bound to the number
+fn bind_number(number: i64) -> impl FnMut(&mut Expr) {
+    move |expr| {
+        if let Expr::BinaryOp { right, .. } = expr {
+            if param_index(right) == Some(0) {
+                **right = Expr::Value(Value::Number(number.to_string().parse().expect("number")));
+            }
+        }
+    }
+}
+
+fn ranges(plan: Result<Plan, ()>) -> Vec<ColumnRange> {
+    match plan {
+        Ok(Plan::Select(select_input)) => select_input.ranges,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    }
+}
+
+#[rstest::rstest]
+fn generic_plan_of_select_is_bound_to_ranges_of_literals(
+    planner_and_sender_with_columns: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let plan = query_planner
+        .plan(statement("select * from schema_name.table_name where id > 10;"))
+        .expect("select is planned");
+    let generic = query_planner
+        .plan_generic(
+            &statement("select * from schema_name.table_name where id > $1;"),
+            bind_number(10),
+            &plan,
+        )
+        .expect("generic plan");
+
+    assert_eq!(
+        ranges(query_planner.bind(&generic, bind_number(20))),
+        vec![ColumnRange {
+            column_name: "id".to_owned(),
+            lower: Some(21),
+            upper: None,
+        }]
+    );
+    assert_eq!(query_planner.bind(&generic, bind_number(10)), Ok(plan));
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn generic_plan_of_statement_that_can_not_be_planned(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let plan = query_planner
+        .plan(statement("select * from schema_name.table_name where id > 10;"))
+        .expect("select is planned");
+    assert_eq!(
+        query_planner.plan_generic(
+            &statement("select * from schema_name.non_existent where id > $1;"),
+            bind_number(10),
+            &plan,
+        ),
+        None
+    );
+
+    collector.assert_content(vec![])
+}
@@ -0,0 +1,124 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    plan::{Plan, ScanMethod},
+    planner::QueryPlanner,
+    tests::{statement, ResultCollector, SCHEMA, TABLE},
+};
+use data_manager::{ColumnDefinition, TableStatistics};
+use representation::Datum;
+use sql_model::{sql_types::SqlType, Id};
+
+type Planner = (QueryPlanner, Arc<DataManager>, (Id, Id), ResultCollector);
+
+#[rstest::fixture]
+fn planner_with_keyed_table() -> Planner {
+    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let manager = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let schema_id = manager.create_schema(SCHEMA).expect("schema created");
+    let table_id = manager
+        .create_table(
+            schema_id,
+            TABLE,
+            &[
+                ColumnDefinition::new("id", SqlType::Integer(i32::min_value())).primary_key(),
+                ColumnDefinition::new("amount", SqlType::Integer(i32::min_value())),
+            ],
+        )
+        .expect("table created");
+    (
+        QueryPlanner::new(manager.clone(), collector.clone()),
+        manager,
+        (schema_id, table_id),
+        collector,
+    )
+}
+
+/// table is analyzed with `rows` rows which `id` and `amount` go from 1 to `rows`
+fn analyze(data_manager: &DataManager, table_id: (Id, Id), rows: i32) {
+    let rows = (1..=rows)
+        .map(|value| vec![Datum::from_i32(value), Datum::from_i32(value)])
+        .collect::<Vec<_>>();
+    data_manager
+        .set_analyzed(
+            &Box::new(table_id),
+            TableStatistics::build(&["id".to_owned(), "amount".to_owned()], &rows),
+        )
+        .expect("no system errors");
+}
+
+fn scan(query_planner: &QueryPlanner, sql: &str) -> ScanMethod {
+    match query_planner.plan(statement(sql)) {
+        Ok(Plan::Select(select_input)) => select_input.scan,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    }
+}
+
+#[rstest::rstest]
+fn range_of_primary_key_in_table_that_was_never_analyzed(planner_with_keyed_table: Planner) {
+    let (query_planner, _data_manager, _table_id, collector) = planner_with_keyed_table;
+
+    assert_eq!(
+        scan(&query_planner, "select * from schema_name.table_name where id > 10;"),
+        ScanMethod::PrimaryKeyRange
+    );
+    assert_eq!(
+        scan(
+            &query_planner,
+            "select * from schema_name.table_name where amount > 10;"
+        ),
+        ScanMethod::Sequential
+    );
+    assert_eq!(
+        scan(&query_planner, "select * from schema_name.table_name;"),
+        ScanMethod::Sequential
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn small_table_is_read_sequentially(planner_with_keyed_table: Planner) {
+    let (query_planner, data_manager, table_id, collector) = planner_with_keyed_table;
+    analyze(&data_manager, table_id, 5);
+
+    assert_eq!(
+        scan(&query_planner, "select * from schema_name.table_name where id = 3;"),
+        ScanMethod::Sequential
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn range_is_scanned_when_it_holds_few_rows_of_table(planner_with_keyed_table: Planner) {
+    let (query_planner, data_manager, table_id, collector) = planner_with_keyed_table;
+    analyze(&data_manager, table_id, 10_000);
+
+    assert_eq!(
+        scan(
+            &query_planner,
+            "select * from schema_name.table_name where id between 100 and 200;"
+        ),
+        ScanMethod::PrimaryKeyRange
+    );
+    assert_eq!(
+        scan(&query_planner, "select * from schema_name.table_name where id > 100;"),
+        ScanMethod::Sequential
+    );
+
+    collector.assert_content(vec![])
+}
@@ -0,0 +1,73 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    extended::ExtendedStatement,
+    plan::{Plan, SchemaOwnerChange, SchemaPrivilegesChange},
+};
+use data_manager::SchemaPrivilege;
+use protocol::results::QueryError;
+
+#[rstest::rstest]
+fn alter_schema_owner(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(ExtendedStatement::AlterSchemaOwner {
+            schema_name: ObjectName(vec![ident(SCHEMA)]),
+            new_owner: ident("some_role"),
+        }),
+        Ok(Plan::AlterSchemaOwner(SchemaOwnerChange {
+            schema_id: SchemaId(0),
+            new_owner: "some_role".to_owned(),
+        }))
+    );
+
+    collector.assert_content(vec![]);
+}
+
+#[rstest::rstest]
+fn grant_privileges_by_not_an_owner(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (mut query_planner, collector) = planner_and_sender_with_schema;
+    query_planner.set_role("some_role");
+    assert_eq!(
+        query_planner.plan_extended(ExtendedStatement::GrantOnSchema {
+            privileges: vec![SchemaPrivilege::Usage],
+            schema_names: vec![ObjectName(vec![ident(SCHEMA)])],
+            roles: vec![ident("some_role")],
+        }),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::must_be_owner_of_schema(SCHEMA))]);
+}
+
+#[rstest::rstest]
+fn revoke_privileges(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(ExtendedStatement::RevokeOnSchema {
+            privileges: vec![SchemaPrivilege::Create],
+            schema_names: vec![ObjectName(vec![ident(SCHEMA)])],
+            roles: vec![ident("some_role")],
+        }),
+        Ok(Plan::RevokeSchemaPrivileges(SchemaPrivilegesChange {
+            schema_ids: vec![SchemaId(0)],
+            privileges: vec![SchemaPrivilege::Create],
+            roles: vec!["some_role".to_owned()],
+        }))
+    );
+
+    collector.assert_content(vec![]);
+}
@@ -14,12 +14,25 @@
 
 use super::*;
 use crate::{
-    plan::{Plan, SelectInput},
+    plan::{
+        ColumnRange, FrameBound, FrameUnits, Plan, RequiredColumns, RowLimit, ScanMethod, SelectInput, SelectValues,
+        SelectedColumn, SortKey, WindowFrame, WindowFunction,
+    },
     planner::QueryPlanner,
-    tests::{ident, ResultCollector, TABLE},
+    tests::{ident, statement, ResultCollector, TABLE},
 };
+use data_manager::ColumnDefinition;
 use protocol::results::QueryError;
-use sqlparser::ast::{ObjectName, Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins};
+use sql_model::sql_types::SqlType;
+use sqlparser::{
+    ast::{
+        Expr, Fetch, Function, ObjectName, OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableFactor,
+        TableWithJoins, Value, WindowFrameBound, WindowFrameUnits, WindowSpec,
+    },
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+    tokenizer::Tokenizer,
+};
 
 #[rstest::rstest]
 fn select_from_table_that_in_nonexistent_schema(planner_and_sender: (QueryPlanner, ResultCollector)) {
@@ -199,9 +212,420 @@ fn select_from_table(planner_and_sender_with_table: (QueryPlanner, ResultCollect
         }))),
         Ok(Plan::Select(SelectInput {
             table_id: TableId((0, 0)),
-            selected_columns: vec![]
+            selected_columns: vec![],
+            order_by: vec![],
+            limit: RowLimit::default(),
+            ranges: vec![],
+            filters: vec![],
+            scanned_tables: vec![TableId((0, 0))],
+            scan: ScanMethod::Sequential,
+            required_columns: RequiredColumns::default(),
+        }))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn select_without_from(planner_and_sender: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender;
+    let now = Expr::Function(Function {
+        name: ObjectName(vec![ident("now")]),
+        args: vec![],
+        over: None,
+        distinct: false,
+    });
+    assert_eq!(
+        query_planner.plan(Statement::Query(Box::new(Query {
+            ctes: vec![],
+            body: SetExpr::Select(Box::new(Select {
+                distinct: false,
+                top: None,
+                projection: vec![
+                    SelectItem::UnnamedExpr(now.clone()),
+                    SelectItem::ExprWithAlias {
+                        expr: now.clone(),
+                        alias: ident("moment")
+                    }
+                ],
+                from: vec![],
+                selection: None,
+                group_by: vec![],
+                having: None,
+            })),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        }))),
+        Ok(Plan::SelectValues(SelectValues {
+            values: vec![("now".to_owned(), now.clone()), ("moment".to_owned(), now)]
         }))
     );
 
     collector.assert_content(vec![])
 }
+
+fn select_with_fetch(order_by: Vec<OrderByExpr>, fetch: Fetch) -> Statement {
+    Statement::Query(Box::new(Query {
+        ctes: vec![],
+        body: SetExpr::Select(Box::new(Select {
+            distinct: false,
+            top: None,
+            projection: vec![SelectItem::Wildcard],
+            from: vec![TableWithJoins {
+                relation: TableFactor::Table {
+                    name: ObjectName(vec![ident(SCHEMA), ident(TABLE)]),
+                    alias: None,
+                    args: vec![],
+                    with_hints: vec![],
+                },
+                joins: vec![],
+            }],
+            selection: None,
+            group_by: vec![],
+            having: None,
+        })),
+        order_by,
+        limit: None,
+        offset: None,
+        fetch: Some(fetch),
+    }))
+}
+
+#[rstest::rstest]
+fn select_fetch_first_with_ties(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    assert_eq!(
+        query_planner.plan(select_with_fetch(
+            vec![OrderByExpr {
+                expr: Expr::Identifier(ident("score")),
+                asc: Some(false),
+                nulls_first: None,
+            }],
+            Fetch {
+                with_ties: true,
+                percent: false,
+                quantity: Some(Expr::Value(Value::Number(3i64.into()))),
+            }
+        )),
+        Ok(Plan::Select(SelectInput {
+            table_id: TableId((0, 0)),
+            selected_columns: vec![],
+            order_by: vec![SortKey {
+                column_name: "score".to_owned(),
+                ascending: false,
+                nulls_first: true,
+            }],
+            limit: RowLimit {
+                offset: 0,
+                count: Some(3),
+                with_ties: true,
+            },
+            ranges: vec![],
+            filters: vec![],
+            scanned_tables: vec![TableId((0, 0))],
+            scan: ScanMethod::Sequential,
+            required_columns: RequiredColumns::default(),
+        }))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn select_fetch_first_with_ties_without_order_by(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    assert_eq!(
+        query_planner.plan(select_with_fetch(
+            vec![],
+            Fetch {
+                with_ties: true,
+                percent: false,
+                quantity: Some(Expr::Value(Value::Number(3i64.into()))),
+            }
+        )),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::syntax_error(
+        "WITH TIES cannot be specified without ORDER BY clause",
+    ))])
+}
+
+fn select_with_window(start_bound: WindowFrameBound, end_bound: Option<WindowFrameBound>) -> Statement {
+    Statement::Query(Box::new(Query {
+        ctes: vec![],
+        body: SetExpr::Select(Box::new(Select {
+            distinct: false,
+            top: None,
+            projection: vec![SelectItem::ExprWithAlias {
+                expr: Expr::Function(Function {
+                    name: ObjectName(vec![ident("sum")]),
+                    args: vec![Expr::Identifier(ident("score"))],
+                    over: Some(WindowSpec {
+                        partition_by: vec![],
+                        order_by: vec![OrderByExpr {
+                            expr: Expr::Identifier(ident("player")),
+                            asc: None,
+                            nulls_first: None,
+                        }],
+                        window_frame: Some(sqlparser::ast::WindowFrame {
+                            units: WindowFrameUnits::Rows,
+                            start_bound,
+                            end_bound,
+                        }),
+                    }),
+                    distinct: false,
+                }),
+                alias: ident("total"),
+            }],
+            from: vec![TableWithJoins {
+                relation: TableFactor::Table {
+                    name: ObjectName(vec![ident(SCHEMA), ident(TABLE)]),
+                    alias: None,
+                    args: vec![],
+                    with_hints: vec![],
+                },
+                joins: vec![],
+            }],
+            selection: None,
+            group_by: vec![],
+            having: None,
+        })),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+    }))
+}
+
+#[rstest::rstest]
+fn select_window_function_with_rows_frame(planner_and_sender_with_table: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    assert_eq!(
+        query_planner.plan(select_with_window(
+            WindowFrameBound::Preceding(Some(1)),
+            Some(WindowFrameBound::CurrentRow)
+        )),
+        Ok(Plan::Select(SelectInput {
+            table_id: TableId((0, 0)),
+            selected_columns: vec![SelectedColumn::Window(
+                "total".to_owned(),
+                WindowFunction {
+                    function: "sum".to_owned(),
+                    argument: Some("score".to_owned()),
+                    parameter: None,
+                    filter: None,
+                    partition_by: vec![],
+                    order_by: vec![SortKey {
+                        column_name: "player".to_owned(),
+                        ascending: true,
+                        nulls_first: false,
+                    }],
+                    frame: WindowFrame {
+                        units: FrameUnits::Rows,
+                        start: FrameBound::Preceding(1),
+                        end: FrameBound::CurrentRow,
+                    },
+                }
+            )],
+            order_by: vec![],
+            limit: RowLimit::default(),
+            ranges: vec![],
+            filters: vec![],
+            scanned_tables: vec![TableId((0, 0))],
+            scan: ScanMethod::Sequential,
+            required_columns: RequiredColumns::default(),
+        }))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn select_window_frame_starting_with_unbounded_following(
+    planner_and_sender_with_table: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_table;
+    assert_eq!(
+        query_planner.plan(select_with_window(WindowFrameBound::Following(None), None)),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::windowing_error(
+        "frame start cannot be UNBOUNDED FOLLOWING",
+    ))])
+}
+
+#[rstest::fixture]
+fn planner_and_sender_with_columns() -> (QueryPlanner, ResultCollector) {
+    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let manager = DataManager::in_memory().expect("to create data manager");
+    let schema_id = manager.create_schema(SCHEMA).expect("schema created");
+    manager
+        .create_table(
+            schema_id,
+            TABLE,
+            &[
+                ColumnDefinition::new("id", SqlType::Integer(i32::min_value())),
+                ColumnDefinition::new("name", SqlType::VarChar(10)),
+                ColumnDefinition::new("score", SqlType::SmallInt(i16::min_value())),
+            ],
+        )
+        .expect("table created");
+    (QueryPlanner::new(Arc::new(manager), collector.clone()), collector)
+}
+
+fn expr(sql: &str) -> Expr {
+    let tokens = Tokenizer::new(&PostgreSqlDialect {}, sql)
+        .tokenize()
+        .expect("expression tokenized");
+    Parser::new(tokens).parse_expr().expect("expression parsed")
+}
+
+#[rstest::rstest]
+fn where_conjuncts_are_pushed_down_into_ranges_and_filters(
+    planner_and_sender_with_columns: (QueryPlanner, ResultCollector),
+) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let select_input = match query_planner.plan(statement(
+        "select * from schema_name.table_name \
+         where id between 1 and 10 and (name = 'a' or id <> 5) and schema_name.table_name.name not between 'a' and 'b';",
+    )) {
+        Ok(Plan::Select(select_input)) => select_input,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    };
+
+    assert_eq!(
+        select_input.ranges,
+        vec![ColumnRange {
+            column_name: "id".to_owned(),
+            lower: Some(1),
+            upper: Some(10),
+        }]
+    );
+    assert_eq!(
+        select_input.filters,
+        vec![expr("name = 'a' or id <> 5"), expr("(name < 'a' or name > 'b')")]
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn columns_required_by_every_operator(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let select_input = match query_planner.plan(statement(
+        "select name, row_number() over (order by score) as position from schema_name.table_name \
+         where id > 3 and name <> 'x' order by id;",
+    )) {
+        Ok(Plan::Select(select_input)) => select_input,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    };
+
+    assert_eq!(
+        select_input.required_columns,
+        RequiredColumns {
+            filter: vec!["id".to_owned(), "name".to_owned()],
+            window: vec!["score".to_owned()],
+            sort: vec!["id".to_owned()],
+            project: vec!["name".to_owned()],
+        }
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn columns_of_function_arguments_are_required(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let select_input = match query_planner.plan(statement(
+        "select id from schema_name.table_name where length(name) > 3;",
+    )) {
+        Ok(Plan::Select(select_input)) => select_input,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    };
+
+    assert_eq!(select_input.required_columns.filter, vec!["name".to_owned()]);
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn constant_subexpressions_of_where_are_folded(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let select_input = match query_planner.plan(statement(
+        "select * from schema_name.table_name where 1 = 1 and id > 2 + 3 and (name = 'a' or 1 > 2);",
+    )) {
+        Ok(Plan::Select(select_input)) => select_input,
+        otherwise => panic!("select is not planned: {:?}", otherwise),
+    };
+
+    assert_eq!(
+        select_input.ranges,
+        vec![ColumnRange {
+            column_name: "id".to_owned(),
+            lower: Some(6),
+            upper: None,
+        }]
+    );
+    assert_eq!(select_input.filters, vec![expr("name = 'a'")]);
+
+    collector.assert_content(vec![])
+}
+
+fn query(sql: &str) -> Box<Query> {
+    match statement(sql) {
+        Statement::Query(query) => query,
+        otherwise => panic!("not a query: {:?}", otherwise),
+    }
+}
+
+#[rstest::rstest]
+fn logical_plan_of_select_from_table(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let relation = query_planner
+        .logical_plan(query("select * from schema_name.table_name;"))
+        .expect("select is planned")
+        .expect("select from table");
+
+    assert_eq!(
+        relation.to_string(),
+        "Project: id, name, score\n  -> Scan on schema_name.table_name"
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn filters_of_logical_plan_are_pushed_down_into_scan(planner_and_sender_with_columns: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_columns;
+    let relation = query_planner
+        .logical_plan(query(
+            "select name, sum(score) over (order by id) as total from schema_name.table_name \
+             where id >= 1 + 1 and score <> 10 order by name desc limit 3 offset 1;",
+        ))
+        .expect("select is planned")
+        .expect("select from table");
+
+    assert_eq!(
+        relation.to_string(),
+        "Project: name, total\n  \
+         -> Limit: 3 OFFSET 1\n    \
+         -> Sort: name DESC\n      \
+         -> Window: sum(score)\n        \
+         -> Scan on schema_name.table_name: id >= 2, score <> 10"
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn select_without_from_has_no_logical_plan(planner_and_sender: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender;
+
+    assert_eq!(query_planner.logical_plan(query("select 1;")), Ok(None));
+
+    collector.assert_content(vec![])
+}
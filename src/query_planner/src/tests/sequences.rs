@@ -0,0 +1,100 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    extended::ExtendedStatement,
+    plan::{Plan, SequenceCreationInfo},
+};
+use data_manager::SequenceOptions;
+use protocol::results::{QueryError, QueryEvent};
+
+fn create_sequence_statement(name: Vec<&str>, if_not_exists: bool) -> ExtendedStatement {
+    ExtendedStatement::CreateSequence {
+        sequence_name: ObjectName(name.into_iter().map(ident).collect()),
+        if_not_exists,
+        options: SequenceOptions::default().start(10),
+    }
+}
+
+fn drop_sequence_statement(name: Vec<&str>, if_exists: bool) -> ExtendedStatement {
+    ExtendedStatement::DropSequence {
+        names: vec![ObjectName(name.into_iter().map(ident).collect())],
+        if_exists,
+    }
+}
+
+#[rstest::rstest]
+fn create_sequence_in_nonexistent_schema(planner_and_sender: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender;
+    assert_eq!(
+        query_planner.plan_extended(create_sequence_statement(vec!["non_existent_schema", "seq"], false)),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::schema_does_not_exist("non_existent_schema"))])
+}
+
+#[rstest::rstest]
+fn create_sequence_with_unqualified_name(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(create_sequence_statement(vec!["seq"], false)),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::syntax_error(
+        "unsupported table name 'seq'. All table names must be qualified",
+    ))])
+}
+
+#[rstest::rstest]
+fn create_sequence(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(create_sequence_statement(vec![SCHEMA, "seq"], false)),
+        Ok(Plan::CreateSequence(SequenceCreationInfo {
+            schema_id: 0,
+            sequence_name: "seq".to_owned(),
+            if_not_exists: false,
+            options: SequenceOptions::default().start(10),
+        }))
+    );
+
+    collector.assert_content(vec![])
+}
+
+#[rstest::rstest]
+fn drop_nonexistent_sequence(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(drop_sequence_statement(vec![SCHEMA, "seq"], false)),
+        Err(())
+    );
+
+    collector.assert_content(vec![Err(QueryError::sequence_does_not_exist("schema_name.seq"))])
+}
+
+#[rstest::rstest]
+fn drop_nonexistent_sequence_if_exists(planner_and_sender_with_schema: (QueryPlanner, ResultCollector)) {
+    let (query_planner, collector) = planner_and_sender_with_schema;
+    assert_eq!(
+        query_planner.plan_extended(drop_sequence_statement(vec![SCHEMA, "seq"], true)),
+        Ok(Plan::DropSequences(vec![]))
+    );
+
+    collector.assert_content(vec![Ok(QueryEvent::Skipped(QueryError::sequence_does_not_exist(
+        "schema_name.seq",
+    )))])
+}
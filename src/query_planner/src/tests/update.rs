@@ -116,7 +116,8 @@ fn update_table(planner_and_sender_with_table: (QueryPlanner, ResultCollector))
             assignments: vec![Assignment {
                 id: ident(""),
                 value: Expr::Value(Value::SingleQuotedString("".to_string()))
-            }]
+            }],
+            filter: None
         }))
     );
 
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::{
-    convert::TryFrom,
+    convert::{TryFrom, TryInto},
     ops::{Add, BitAnd, BitOr, Div, Mul, Rem, Shl, Shr, Sub},
 };
 
@@ -22,6 +22,7 @@ use bigdecimal::ToPrimitive;
 use ordered_float::OrderedFloat;
 use sqlparser::ast::Value;
 
+use protocol::{bytea, clock::render_timestamp_with_time_zone, interval::Interval};
 use sql_model::sql_types::SqlType;
 
 #[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
@@ -34,6 +35,9 @@ pub enum ScalarType {
     Float64,
     Boolean,
     String,
+    Timestamp,
+    Interval,
+    Bytes,
 }
 
 impl ScalarType {
@@ -71,6 +75,9 @@ impl ToString for ScalarType {
             Self::Float64 => "Float64".to_string(),
             Self::Boolean => "Bool".to_string(),
             Self::String => "String".to_string(),
+            Self::Timestamp => "Timestamp".to_string(),
+            Self::Interval => "Interval".to_string(),
+            Self::Bytes => "Bytes".to_string(),
         }
     }
 }
@@ -91,8 +98,13 @@ pub enum Datum<'a> {
     String(&'a str),
     // this should only be used when loading string into a database
     OwnedString(String),
-    // Bytes(&'a [u8]),
+    Bytes(&'a [u8]),
+    // this should only be used when loading bytes into a database
+    OwnedBytes(Vec<u8>),
     SqlType(SqlType),
+    /// microseconds since Unix epoch in UTC
+    Timestamp(i64),
+    Interval(Interval),
     // fill in the rest of the types as they get implemented.
 }
 
@@ -111,6 +123,10 @@ impl<'a> Datum<'a> {
             Self::String(val) => 1 + std::mem::size_of::<usize>() + val.len(),
             Self::OwnedString(val) => 1 + std::mem::size_of::<usize>() + val.len(),
             Self::SqlType(_) => 1 + std::mem::size_of::<SqlType>(),
+            Self::Timestamp(_) => 1 + std::mem::size_of::<i64>(),
+            Self::Interval(_) => 1 + 2 * std::mem::size_of::<i32>() + std::mem::size_of::<i64>(),
+            Self::Bytes(val) => 1 + std::mem::size_of::<usize>() + val.len(),
+            Self::OwnedBytes(val) => 1 + std::mem::size_of::<usize>() + val.len(),
         }
     }
 
@@ -163,6 +179,18 @@ impl<'a> Datum<'a> {
         Datum::SqlType(val)
     }
 
+    pub fn from_timestamp(val: i64) -> Datum<'static> {
+        Datum::Timestamp(val)
+    }
+
+    pub fn from_interval(val: Interval) -> Datum<'static> {
+        Datum::Interval(val)
+    }
+
+    pub fn from_bytes(val: Vec<u8>) -> Datum<'static> {
+        Datum::OwnedBytes(val)
+    }
+
     pub fn scalar_type(&self) -> Option<ScalarType> {
         match self {
             Datum::Null => None,
@@ -174,6 +202,9 @@ impl<'a> Datum<'a> {
             Datum::Float64(_) => Some(ScalarType::Float64),
             Datum::String(_) | Datum::OwnedString(_) => Some(ScalarType::String),
             Datum::UInt64(_) => Some(ScalarType::UInt64),
+            Datum::Timestamp(_) => Some(ScalarType::Timestamp),
+            Datum::Interval(_) => Some(ScalarType::Interval),
+            Datum::Bytes(_) | Datum::OwnedBytes(_) => Some(ScalarType::Bytes),
             _ => None,
         }
     }
@@ -250,6 +281,28 @@ impl<'a> Datum<'a> {
         }
     }
 
+    pub fn as_timestamp(&self) -> i64 {
+        match self {
+            Self::Timestamp(val) => *val,
+            _ => panic!("invalid use of Datum::as_timestamp"),
+        }
+    }
+
+    pub fn as_interval(&self) -> Interval {
+        match self {
+            Self::Interval(val) => *val,
+            _ => panic!("invalid use of Datum::as_interval"),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Bytes(val) => val,
+            Self::OwnedBytes(val) => val,
+            _ => panic!("invalid use of Datum::as_bytes"),
+        }
+    }
+
     pub fn is_integer(&self) -> bool {
         match self {
             Self::Int16(_) | Self::Int32(_) | Self::Int64(_) => true,
@@ -278,6 +331,13 @@ impl<'a> Datum<'a> {
         }
     }
 
+    pub fn is_bytes(&self) -> bool {
+        match self {
+            Self::Bytes(_) | Self::OwnedBytes(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_null(&self) -> bool {
         if let Self::Null = self {
             true
@@ -328,14 +388,21 @@ impl<'a> TryFrom<&Value> for Datum<'a> {
                     Err(EvalError::OutOfRangeNumeric(SqlType::DoublePrecision))
                 }
             }
-            Value::SingleQuotedString(value) => Ok(Datum::from_string(value.trim().to_owned())),
+            Value::SingleQuotedString(value) => Ok(Datum::from_string(value.to_owned())),
             Value::HexStringLiteral(value) => match i64::from_str_radix(value.as_str(), 16) {
                 Ok(val) => Ok(Datum::from_i64(val)),
                 Err(_) => panic!("Failed to parse hex string"),
             },
             Value::Boolean(val) => Ok(Datum::from_bool(*val)),
             Value::Null => Ok(Datum::from_null()),
-            Value::Interval { .. } => Err(EvalError::UnsupportedDatum("Interval".to_string())),
+            Value::Interval {
+                value, leading_field, ..
+            } => match leading_field {
+                Some(field) => Interval::parse_with_field(value, field.to_string().as_str()),
+                None => Interval::parse(value),
+            }
+            .map(Datum::from_interval)
+            .ok_or_else(|| EvalError::UnsupportedDatum("Interval".to_string())),
             Value::NationalStringLiteral(_value) => {
                 Err(EvalError::UnsupportedDatum("NationalStringLiteral".to_string()))
             }
@@ -358,6 +425,10 @@ impl ToString for Datum<'_> {
             Self::String(val) => val.to_string(),
             Self::OwnedString(val) => val.clone(),
             Self::SqlType(val) => val.to_string(),
+            Self::Timestamp(val) => render_timestamp_with_time_zone(*val, 0),
+            Self::Interval(val) => val.to_string(),
+            Self::Bytes(val) => bytea::encode_hex(val),
+            Self::OwnedBytes(val) => bytea::encode_hex(val),
         }
     }
 }
@@ -375,6 +446,9 @@ enum TypeTag {
     F64,
     Str,
     SqlType,
+    Timestamp,
+    Interval,
+    Bytes,
     // fill in the rest of the types.
 }
 
@@ -406,10 +480,386 @@ unsafe fn read_string<'a>(data: &'a [u8], idx: &mut usize) -> &'a str {
     std::str::from_utf8_unchecked(data)
 }
 
+unsafe fn read_bytes<'a>(data: &'a [u8], idx: &mut usize) -> &'a [u8] {
+    let len = read::<usize>(data, idx);
+    let data = &data[*idx..*idx + len];
+    *idx += len;
+    data
+}
+
 fn read_tag(data: &[u8], idx: &mut usize) -> TypeTag {
     unsafe { read::<TypeTag>(data, idx) }
 }
 
+/// Rows of tables are packed with a versioned format. A row starts with a version byte that isn't
+/// a type tag, so rows packed before the format was versioned, which start with the tag of their
+/// first value, still decode:
+///
+/// ```text
+/// [ROW_FORMAT_V1][column count: varint][null bitmap][value of every non NULL column]...
+/// ```
+///
+/// bit `i % 8` of byte `i / 8` of the null bitmap is set when the column `i` is NULL. A value is its
+/// type tag followed by a little endian payload of fixed width, strings and bytes are prefixed by
+/// their length as a varint. Varints are unsigned LEB128, seven bits per byte with the high bit
+/// set on every byte but the last one.
+pub const ROW_FORMAT_V1: u8 = 0x81;
+
+fn push_varint(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+fn read_varint(data: &[u8], idx: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*idx];
+        *idx += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+fn take<'a>(data: &'a [u8], idx: &mut usize, len: usize) -> &'a [u8] {
+    let taken = &data[*idx..*idx + len];
+    *idx += len;
+    taken
+}
+
+macro_rules! read_le {
+    ($data:expr, $idx:expr, $T:ty) => {
+        <$T>::from_le_bytes(
+            take($data, $idx, std::mem::size_of::<$T>())
+                .try_into()
+                .expect("fixed width value"),
+        )
+    };
+}
+
+fn push_value(data: &mut Vec<u8>, datum: &Datum) {
+    match datum {
+        Datum::Null => push_tag(data, TypeTag::Null),
+        Datum::True => push_tag(data, TypeTag::True),
+        Datum::False => push_tag(data, TypeTag::False),
+        Datum::Int16(val) => {
+            push_tag(data, TypeTag::I16);
+            data.extend_from_slice(&val.to_le_bytes());
+        }
+        Datum::Int32(val) => {
+            push_tag(data, TypeTag::I32);
+            data.extend_from_slice(&val.to_le_bytes());
+        }
+        Datum::Int64(val) => {
+            push_tag(data, TypeTag::I64);
+            data.extend_from_slice(&val.to_le_bytes());
+        }
+        Datum::UInt64(val) => {
+            push_tag(data, TypeTag::U64);
+            data.extend_from_slice(&val.to_le_bytes());
+        }
+        Datum::Float32(val) => {
+            push_tag(data, TypeTag::F32);
+            data.extend_from_slice(&val.into_inner().to_le_bytes());
+        }
+        Datum::Float64(val) => {
+            push_tag(data, TypeTag::F64);
+            data.extend_from_slice(&val.into_inner().to_le_bytes());
+        }
+        Datum::String(val) => {
+            push_tag(data, TypeTag::Str);
+            push_varint(data, val.len() as u64);
+            data.extend_from_slice(val.as_bytes());
+        }
+        Datum::OwnedString(val) => {
+            push_tag(data, TypeTag::Str);
+            push_varint(data, val.len() as u64);
+            data.extend_from_slice(val.as_bytes());
+        }
+        // sql types are kept only by catalog records that are packed with the legacy layout
+        Datum::SqlType(sql_type) => {
+            push_tag(data, TypeTag::SqlType);
+            push_copy!(data, *sql_type, SqlType);
+        }
+        Datum::Timestamp(val) => {
+            push_tag(data, TypeTag::Timestamp);
+            data.extend_from_slice(&val.to_le_bytes());
+        }
+        Datum::Interval(val) => {
+            push_tag(data, TypeTag::Interval);
+            data.extend_from_slice(&val.months().to_le_bytes());
+            data.extend_from_slice(&val.days().to_le_bytes());
+            data.extend_from_slice(&val.micros().to_le_bytes());
+        }
+        Datum::Bytes(val) => {
+            push_tag(data, TypeTag::Bytes);
+            push_varint(data, val.len() as u64);
+            data.extend_from_slice(val);
+        }
+        Datum::OwnedBytes(val) => {
+            push_tag(data, TypeTag::Bytes);
+            push_varint(data, val.len() as u64);
+            data.extend_from_slice(val);
+        }
+    }
+}
+
+fn read_value<'a>(data: &'a [u8], idx: &mut usize) -> Datum<'a> {
+    match read_tag(data, idx) {
+        TypeTag::Null => Datum::from_null(),
+        TypeTag::True => Datum::from_bool(true),
+        TypeTag::False => Datum::from_bool(false),
+        TypeTag::I16 => Datum::from_i16(read_le!(data, idx, i16)),
+        TypeTag::I32 => Datum::from_i32(read_le!(data, idx, i32)),
+        TypeTag::I64 => Datum::from_i64(read_le!(data, idx, i64)),
+        TypeTag::U64 => Datum::from_u64(read_le!(data, idx, u64)),
+        TypeTag::F32 => Datum::from_f32(read_le!(data, idx, f32)),
+        TypeTag::F64 => Datum::from_f64(read_le!(data, idx, f64)),
+        TypeTag::Str => {
+            let len = read_varint(data, idx) as usize;
+            Datum::String(std::str::from_utf8(take(data, idx, len)).expect("packed utf-8 string"))
+        }
+        TypeTag::SqlType => Datum::from_sql_type(unsafe { read::<SqlType>(data, idx) }),
+        TypeTag::Timestamp => Datum::from_timestamp(read_le!(data, idx, i64)),
+        TypeTag::Interval => {
+            let months = read_le!(data, idx, i32);
+            let days = read_le!(data, idx, i32);
+            let micros = read_le!(data, idx, i64);
+            Datum::from_interval(Interval::new(months, days, micros))
+        }
+        TypeTag::Bytes => {
+            let len = read_varint(data, idx) as usize;
+            Datum::Bytes(take(data, idx, len))
+        }
+    }
+}
+
+/// moves past a value without decoding it
+fn skip_value(data: &[u8], idx: &mut usize) {
+    let width = match read_tag(data, idx) {
+        TypeTag::Null | TypeTag::True | TypeTag::False => 0,
+        TypeTag::I16 => std::mem::size_of::<i16>(),
+        TypeTag::I32 | TypeTag::F32 => std::mem::size_of::<i32>(),
+        TypeTag::I64 | TypeTag::U64 | TypeTag::F64 | TypeTag::Timestamp => std::mem::size_of::<i64>(),
+        TypeTag::Interval => 2 * std::mem::size_of::<i32>() + std::mem::size_of::<i64>(),
+        TypeTag::SqlType => std::mem::size_of::<SqlType>(),
+        TypeTag::Str | TypeTag::Bytes => read_varint(data, idx) as usize,
+    };
+    *idx += width;
+}
+
+/// Primary keys of table rows are packed with an order preserving format, packed keys compare byte by byte
+/// as their values do, so rows with keys in a range are read by a bounded scan:
+///
+/// ```text
+/// [KEY_FORMAT_V1][tag][value][tag][value]...
+/// ```
+///
+/// Integers and timestamps are big-endian with the sign bit flipped, floats flip the sign bit of positive
+/// values and all bits of negative ones, booleans are `0` or `1` after the tag of `true`. Strings and bytes
+/// escape `0x00` as `0x00 0xff` and end with `0x00 0x00`. Keys packed by `Binary::pack` start with a type
+/// tag and are ordered before every key of the format.
+pub const KEY_FORMAT_V1: u8 = 0x82;
+
+macro_rules! read_be {
+    ($data:expr, $idx:expr, $T:ty) => {
+        <$T>::from_be_bytes(
+            take($data, $idx, std::mem::size_of::<$T>())
+                .try_into()
+                .expect("fixed width value"),
+        )
+    };
+}
+
+fn push_escaped(data: &mut Vec<u8>, bytes: &[u8]) {
+    for byte in bytes {
+        data.push(*byte);
+        if *byte == 0 {
+            data.push(0xff);
+        }
+    }
+    data.extend_from_slice(&[0, 0]);
+}
+
+fn read_escaped(data: &[u8], idx: &mut usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    loop {
+        let byte = data[*idx];
+        *idx += 1;
+        if byte == 0 {
+            let escape = data[*idx];
+            *idx += 1;
+            if escape == 0 {
+                return bytes;
+            }
+        }
+        bytes.push(byte);
+    }
+}
+
+fn ordered_f32(val: f32) -> u32 {
+    let bits = val.to_bits();
+    if bits & (1 << 31) != 0 {
+        !bits
+    } else {
+        bits | (1 << 31)
+    }
+}
+
+fn ordered_f64(val: f64) -> u64 {
+    let bits = val.to_bits();
+    if bits & (1 << 63) != 0 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+fn push_key_value(data: &mut Vec<u8>, datum: &Datum) {
+    match datum {
+        Datum::Null => push_tag(data, TypeTag::Null),
+        Datum::True | Datum::False => {
+            push_tag(data, TypeTag::True);
+            data.push(if datum == &Datum::True { 1 } else { 0 });
+        }
+        Datum::Int16(val) => {
+            push_tag(data, TypeTag::I16);
+            data.extend_from_slice(&(*val as u16 ^ (1 << 15)).to_be_bytes());
+        }
+        Datum::Int32(val) => {
+            push_tag(data, TypeTag::I32);
+            data.extend_from_slice(&(*val as u32 ^ (1 << 31)).to_be_bytes());
+        }
+        Datum::Int64(val) => {
+            push_tag(data, TypeTag::I64);
+            data.extend_from_slice(&(*val as u64 ^ (1 << 63)).to_be_bytes());
+        }
+        Datum::UInt64(val) => {
+            push_tag(data, TypeTag::U64);
+            data.extend_from_slice(&val.to_be_bytes());
+        }
+        Datum::Float32(val) => {
+            push_tag(data, TypeTag::F32);
+            data.extend_from_slice(&ordered_f32(val.into_inner()).to_be_bytes());
+        }
+        Datum::Float64(val) => {
+            push_tag(data, TypeTag::F64);
+            data.extend_from_slice(&ordered_f64(val.into_inner()).to_be_bytes());
+        }
+        Datum::String(val) => {
+            push_tag(data, TypeTag::Str);
+            push_escaped(data, val.as_bytes());
+        }
+        Datum::OwnedString(val) => {
+            push_tag(data, TypeTag::Str);
+            push_escaped(data, val.as_bytes());
+        }
+        // sql types are not values of table columns, they are kept as they are packed by `Binary::pack`
+        Datum::SqlType(sql_type) => {
+            push_tag(data, TypeTag::SqlType);
+            push_copy!(data, *sql_type, SqlType);
+        }
+        Datum::Timestamp(val) => {
+            push_tag(data, TypeTag::Timestamp);
+            data.extend_from_slice(&(*val as u64 ^ (1 << 63)).to_be_bytes());
+        }
+        Datum::Interval(val) => {
+            push_tag(data, TypeTag::Interval);
+            data.extend_from_slice(&(val.months() as u32 ^ (1 << 31)).to_be_bytes());
+            data.extend_from_slice(&(val.days() as u32 ^ (1 << 31)).to_be_bytes());
+            data.extend_from_slice(&(val.micros() as u64 ^ (1 << 63)).to_be_bytes());
+        }
+        Datum::Bytes(val) => {
+            push_tag(data, TypeTag::Bytes);
+            push_escaped(data, val);
+        }
+        Datum::OwnedBytes(val) => {
+            push_tag(data, TypeTag::Bytes);
+            push_escaped(data, val);
+        }
+    }
+}
+
+fn read_key_value(data: &[u8], idx: &mut usize) -> Datum<'static> {
+    match read_tag(data, idx) {
+        TypeTag::Null => Datum::from_null(),
+        TypeTag::True | TypeTag::False => Datum::from_bool(take(data, idx, 1)[0] == 1),
+        TypeTag::I16 => Datum::from_i16((read_be!(data, idx, u16) ^ (1 << 15)) as i16),
+        TypeTag::I32 => Datum::from_i32((read_be!(data, idx, u32) ^ (1 << 31)) as i32),
+        TypeTag::I64 => Datum::from_i64((read_be!(data, idx, u64) ^ (1 << 63)) as i64),
+        TypeTag::U64 => Datum::from_u64(read_be!(data, idx, u64)),
+        TypeTag::F32 => {
+            let bits = read_be!(data, idx, u32);
+            Datum::from_f32(f32::from_bits(if bits & (1 << 31) != 0 {
+                bits & !(1 << 31)
+            } else {
+                !bits
+            }))
+        }
+        TypeTag::F64 => {
+            let bits = read_be!(data, idx, u64);
+            Datum::from_f64(f64::from_bits(if bits & (1 << 63) != 0 {
+                bits & !(1 << 63)
+            } else {
+                !bits
+            }))
+        }
+        TypeTag::Str => Datum::from_string(String::from_utf8(read_escaped(data, idx)).expect("packed utf-8 string")),
+        TypeTag::SqlType => Datum::from_sql_type(unsafe { read::<SqlType>(data, idx) }),
+        TypeTag::Timestamp => Datum::from_timestamp((read_be!(data, idx, u64) ^ (1 << 63)) as i64),
+        TypeTag::Interval => {
+            let months = (read_be!(data, idx, u32) ^ (1 << 31)) as i32;
+            let days = (read_be!(data, idx, u32) ^ (1 << 31)) as i32;
+            let micros = (read_be!(data, idx, u64) ^ (1 << 63)) as i64;
+            Datum::from_interval(Interval::new(months, days, micros))
+        }
+        TypeTag::Bytes => Datum::from_bytes(read_escaped(data, idx)),
+    }
+}
+
+fn unpack_key(data: &[u8]) -> Vec<Datum<'static>> {
+    let mut index = 1;
+    let mut values = vec![];
+    while index < data.len() {
+        values.push(read_key_value(data, &mut index));
+    }
+    values
+}
+
+/// column count and the null bitmap of a row of the current format
+fn null_bitmap<'a>(data: &'a [u8], idx: &mut usize) -> (usize, &'a [u8]) {
+    *idx = 1;
+    let count = read_varint(data, idx) as usize;
+    (count, take(data, idx, (count + 7) / 8))
+}
+
+fn unpack_row(data: &[u8]) -> Vec<Datum> {
+    unpack_row_columns(data, |_| true)
+}
+
+fn unpack_row_columns<F: Fn(usize) -> bool>(data: &[u8], is_required: F) -> Vec<Datum> {
+    let mut index = 0;
+    let (count, bitmap) = null_bitmap(data, &mut index);
+    (0..count)
+        .map(|column| {
+            if bitmap[column / 8] & (1 << (column % 8)) != 0 {
+                Datum::from_null()
+            } else if is_required(column) {
+                read_value(data, &mut index)
+            } else {
+                skip_value(data, &mut index);
+                Datum::from_null()
+            }
+        })
+        .collect()
+}
+
 /// in-memory runtime representation of a table row. It is unable to deserialize
 /// the row without knowing the types of each column, which makes this unsafe
 /// however it is more memory efficient.
@@ -482,18 +932,105 @@ impl Binary {
                     push_tag(&mut data, TypeTag::SqlType);
                     push_copy!(&mut data, *sql_type, SqlType);
                 }
+                Datum::<'a>::Timestamp(val) => {
+                    push_tag(&mut data, TypeTag::Timestamp);
+                    push_copy!(&mut data, *val, i64);
+                }
+                Datum::<'a>::Interval(val) => {
+                    push_tag(&mut data, TypeTag::Interval);
+                    push_copy!(&mut data, val.months(), i32);
+                    push_copy!(&mut data, val.days(), i32);
+                    push_copy!(&mut data, val.micros(), i64);
+                }
+                Datum::<'a>::Bytes(val) => {
+                    push_tag(&mut data, TypeTag::Bytes);
+                    push_copy!(&mut data, val.len(), usize);
+                    data.extend_from_slice(val);
+                }
+                Datum::<'a>::OwnedBytes(val) => {
+                    push_tag(&mut data, TypeTag::Bytes);
+                    push_copy!(&mut data, val.len(), usize);
+                    data.extend_from_slice(val);
+                }
             }
         }
 
         Self(data)
     }
 
+    /// packs values of a table row with the null bitmap, see `ROW_FORMAT_V1`
+    pub fn pack_row(values: &[Datum]) -> Self {
+        let size = values.iter().fold(0usize, |acc, datum| acc + datum.size());
+        let mut data = Vec::with_capacity(2 + values.len() / 8 + size);
+        data.push(ROW_FORMAT_V1);
+        push_varint(&mut data, values.len() as u64);
+        let mut bitmap = vec![0u8; (values.len() + 7) / 8];
+        for (column, datum) in values.iter().enumerate() {
+            if datum.is_null() {
+                bitmap[column / 8] |= 1 << (column % 8);
+            }
+        }
+        data.extend_from_slice(&bitmap);
+        for datum in values.iter().filter(|datum| !datum.is_null()) {
+            push_value(&mut data, datum);
+        }
+        Self(data)
+    }
+
+    /// packs primary key values of a table row, see `KEY_FORMAT_V1`
+    pub fn pack_key(values: &[Datum]) -> Self {
+        let mut data = Vec::with_capacity(1 + values.iter().fold(0usize, |acc, datum| acc + datum.size()));
+        data.push(KEY_FORMAT_V1);
+        for datum in values {
+            push_key_value(&mut data, datum);
+        }
+        Self(data)
+    }
+
     pub fn unpack(&self) -> Vec<Datum> {
         unpack_raw(self.0.as_slice())
     }
+
+    /// values of `required` columns, other columns are NULL. Rows of the current format skip values of the other
+    /// columns without decoding them, so the row keeps its width and columns keep their positions
+    pub fn unpack_columns(&self, required: &[bool]) -> Vec<Datum> {
+        let is_required = |column: usize| required.get(column).copied().unwrap_or(false);
+        match self.0.first() {
+            Some(&ROW_FORMAT_V1) => unpack_row_columns(&self.0, is_required),
+            _ => self
+                .unpack()
+                .into_iter()
+                .enumerate()
+                .map(|(column, datum)| if is_required(column) { datum } else { Datum::from_null() })
+                .collect(),
+        }
+    }
+
+    /// whether the column is NULL or missing, rows of the current format answer it without decoding values
+    pub fn is_null(&self, column: usize) -> bool {
+        match self.0.first() {
+            Some(&ROW_FORMAT_V1) => {
+                let (count, bitmap) = null_bitmap(&self.0, &mut 0);
+                column >= count || bitmap[column / 8] & (1 << (column % 8)) != 0
+            }
+            _ => self.unpack().get(column).map(Datum::is_null).unwrap_or(true),
+        }
+    }
+}
+
+impl AsRef<[u8]> for Binary {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
 }
 
+/// decodes rows packed by `Binary::pack` and `Binary::pack_row` and keys packed by `Binary::pack_key`
 pub fn unpack_raw(data: &[u8]) -> Vec<Datum> {
+    match data.first() {
+        Some(&ROW_FORMAT_V1) => return unpack_row(data),
+        Some(&KEY_FORMAT_V1) => return unpack_key(data),
+        _ => {}
+    }
     let mut index = 0;
     let mut res = Vec::new();
     while index < data.len() {
@@ -534,6 +1071,20 @@ pub fn unpack_raw(data: &[u8]) -> Vec<Datum> {
                 let val = unsafe { read::<SqlType>(data, &mut index) };
                 Datum::from_sql_type(val)
             }
+            TypeTag::Timestamp => {
+                let val = unsafe { read::<i64>(data, &mut index) };
+                Datum::from_timestamp(val)
+            }
+            TypeTag::Interval => {
+                let months = unsafe { read::<i32>(data, &mut index) };
+                let days = unsafe { read::<i32>(data, &mut index) };
+                let micros = unsafe { read::<i64>(data, &mut index) };
+                Datum::from_interval(Interval::new(months, days, micros))
+            }
+            TypeTag::Bytes => {
+                let val = unsafe { read_bytes(data, &mut index) };
+                Datum::Bytes(val)
+            }
         };
         res.push(datum)
     }
@@ -660,5 +1211,250 @@ mod tests {
             let row = Binary::pack(&data);
             assert_eq!(data, row.unpack());
         }
+
+        #[test]
+        fn timestamps() {
+            let data = vec![Datum::from_timestamp(1_596_285_000_000_000)];
+            let row = Binary::pack(&data);
+            assert_eq!(data, row.unpack());
+        }
+
+        #[test]
+        fn bytes() {
+            let data = vec![Datum::from_bytes(vec![0, 1, 255]), Datum::from_bytes(vec![])];
+            let row = Binary::pack(&data);
+            let unpacked = row.unpack();
+            assert_eq!(unpacked[0].as_bytes(), &[0, 1, 255]);
+            assert_eq!(unpacked[1].as_bytes(), &[] as &[u8]);
+            assert_eq!(unpacked[0].to_string(), "\\x0001ff");
+        }
+
+        #[test]
+        fn intervals() {
+            let data = vec![Datum::from_interval(Interval::new(14, -3, 3_600_000_000))];
+            let row = Binary::pack(&data);
+            let unpacked = row.unpack();
+            assert_eq!(unpacked[0].as_interval().months(), 14);
+            assert_eq!(unpacked[0].as_interval().days(), -3);
+            assert_eq!(unpacked[0].as_interval().micros(), 3_600_000_000);
+        }
+    }
+
+    #[cfg(test)]
+    mod row_format {
+        use super::*;
+
+        #[test]
+        fn nulls_are_kept_in_bitmap() {
+            let data = vec![
+                Datum::from_i16(1),
+                Datum::from_null(),
+                Datum::from_str("hello"),
+                Datum::from_null(),
+            ];
+            let row = Binary::pack_row(&data);
+            assert_eq!(
+                row.to_bytes(),
+                &[
+                    ROW_FORMAT_V1,
+                    4,
+                    0b0000_1010,
+                    TypeTag::I16 as u8,
+                    1,
+                    0,
+                    TypeTag::Str as u8,
+                    5,
+                    b'h',
+                    b'e',
+                    b'l',
+                    b'l',
+                    b'o'
+                ]
+            );
+            assert_eq!(data, row.unpack());
+        }
+
+        #[test]
+        fn null_columns() {
+            let row = Binary::pack_row(&[Datum::from_null(), Datum::from_i32(1)]);
+            assert!(row.is_null(0));
+            assert!(!row.is_null(1));
+            assert!(row.is_null(2));
+
+            let legacy = Binary::pack(&[Datum::from_null(), Datum::from_i32(1)]);
+            assert!(legacy.is_null(0));
+            assert!(!legacy.is_null(1));
+        }
+
+        #[test]
+        fn all_types() {
+            let long_string = "long string ".repeat(20);
+            let data = vec![
+                Datum::from_bool(true),
+                Datum::from_bool(false),
+                Datum::from_i16(-100),
+                Datum::from_i32(1_000),
+                Datum::from_i64(-10_000),
+                Datum::from_u64(10_000),
+                Datum::from_f32(1000.123),
+                Datum::from_f64(100.134_219_234_555),
+                Datum::from_str(&long_string),
+                Datum::from_timestamp(1_596_285_000_000_000),
+            ];
+            let row = Binary::pack_row(&data);
+            assert_eq!(data, row.unpack());
+
+            let row = Binary::pack_row(&[
+                Datum::from_interval(Interval::new(14, -3, 3_600_000_000)),
+                Datum::from_bytes(vec![0, 1, 255]),
+            ]);
+            let unpacked = row.unpack();
+            assert_eq!(unpacked[0].as_interval(), Interval::new(14, -3, 3_600_000_000));
+            assert_eq!(unpacked[1].as_bytes(), &[0, 1, 255]);
+        }
+
+        #[test]
+        fn rows_without_format_version_are_decoded() {
+            let data = vec![Datum::from_i32(1), Datum::from_null(), Datum::from_str("hello")];
+            assert_eq!(Binary::pack(&data).unpack(), Binary::pack_row(&data).unpack());
+            assert_eq!(Binary::pack_row(&[]).unpack(), vec![]);
+        }
+
+        #[test]
+        fn only_required_columns_are_unpacked() {
+            let data = vec![
+                Datum::from_str("skipped"),
+                Datum::from_i32(1),
+                Datum::from_null(),
+                Datum::from_interval(Interval::new(1, 2, 3)),
+                Datum::from_bytes(vec![0, 1]),
+                Datum::from_f64(2.5),
+            ];
+            let required = [false, true, true, false, false, true];
+            let expected = vec![
+                Datum::from_null(),
+                Datum::from_i32(1),
+                Datum::from_null(),
+                Datum::from_null(),
+                Datum::from_null(),
+                Datum::from_f64(2.5),
+            ];
+            assert_eq!(Binary::pack_row(&data).unpack_columns(&required), expected);
+            assert_eq!(Binary::pack(&data).unpack_columns(&required), expected);
+            assert_eq!(
+                Binary::pack_row(&data).unpack_columns(&[]),
+                vec![Datum::from_null(); data.len()]
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod key_format {
+        use super::*;
+
+        fn assert_ordered(values: Vec<Datum>) {
+            let keys = values
+                .iter()
+                .map(|value| Binary::pack_key(&[value.clone()]))
+                .collect::<Vec<_>>();
+            for pair in keys.windows(2) {
+                assert!(pair[0] < pair[1], "{:?} is not less than {:?}", pair[0], pair[1]);
+            }
+        }
+
+        #[test]
+        fn integers_are_ordered() {
+            assert_ordered(vec![
+                Datum::from_i16(i16::min_value()),
+                Datum::from_i16(-1),
+                Datum::from_i16(0),
+                Datum::from_i16(256),
+                Datum::from_i16(i16::max_value()),
+            ]);
+            assert_ordered(vec![
+                Datum::from_i32(-70_000),
+                Datum::from_i32(-1),
+                Datum::from_i32(1),
+                Datum::from_i32(70_000),
+            ]);
+            assert_ordered(vec![
+                Datum::from_i64(i64::min_value()),
+                Datum::from_i64(-1),
+                Datum::from_i64(255),
+                Datum::from_i64(256),
+            ]);
+            assert_ordered(vec![
+                Datum::from_u64(1),
+                Datum::from_u64(256),
+                Datum::from_u64(u64::max_value()),
+            ]);
+        }
+
+        #[test]
+        fn floats_are_ordered() {
+            assert_ordered(vec![
+                Datum::from_f32(-10.5),
+                Datum::from_f32(-0.25),
+                Datum::from_f32(0.0),
+                Datum::from_f32(0.25),
+                Datum::from_f32(10.5),
+            ]);
+            assert_ordered(vec![
+                Datum::from_f64(-1e10),
+                Datum::from_f64(-1.0),
+                Datum::from_f64(2.5),
+            ]);
+        }
+
+        #[test]
+        fn strings_are_ordered() {
+            assert_ordered(vec![
+                Datum::from_str(""),
+                Datum::from_str("\0"),
+                Datum::from_str("a"),
+                Datum::from_str("a\0"),
+                Datum::from_str("ab"),
+                Datum::from_str("b"),
+            ]);
+        }
+
+        #[test]
+        fn composite_keys_are_ordered_by_first_value() {
+            assert!(
+                Binary::pack_key(&[Datum::from_str("a"), Datum::from_i32(100)])
+                    < Binary::pack_key(&[Datum::from_str("ab"), Datum::from_i32(-100)])
+            );
+            assert!(
+                Binary::pack_key(&[Datum::from_i32(1)]) < Binary::pack_key(&[Datum::from_i32(1), Datum::from_i32(0)])
+            );
+        }
+
+        #[test]
+        fn keys_are_unpacked() {
+            let data = vec![
+                Datum::from_bool(true),
+                Datum::from_bool(false),
+                Datum::from_null(),
+                Datum::from_i16(-100),
+                Datum::from_i32(1_000),
+                Datum::from_i64(-10_000),
+                Datum::from_u64(10_000),
+                Datum::from_f32(-1000.123),
+                Datum::from_f64(100.134_219_234_555),
+                Datum::from_string("with \0 zero".to_owned()),
+                Datum::from_timestamp(-1_596_285_000_000_000),
+                Datum::from_interval(Interval::new(14, -3, 3_600_000_000)),
+                Datum::from_bytes(vec![0, 1, 255]),
+            ];
+            assert_eq!(Binary::pack_key(&data).unpack(), data);
+        }
+
+        #[test]
+        fn keys_without_format_version_are_ordered_first() {
+            let legacy = Binary::pack(&[Datum::from_str("z")]);
+            assert!(legacy < Binary::pack_key(&[Datum::from_i16(i16::min_value())]));
+            assert!(legacy < Binary::with_data(vec![KEY_FORMAT_V1]));
+            assert_eq!(legacy.unpack(), vec![Datum::from_str("z")]);
+        }
     }
 }
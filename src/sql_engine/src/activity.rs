@@ -0,0 +1,268 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Sessions that share a registry see statements of each other in `pg_stat_activity`.
+///! A running statement reports the operator of its plan that is executing and the number
+///! of rows the operator produced so far, so a stuck query can be diagnosed without
+///! cancelling it.
+///! A client that knows the process id and the secret key of a session can cancel the statement
+///! that the session is running, operators check for cancellation between rows they produce.
+///! Sessions listen to channels and receive notifications that other sessions (or themselves) send on
+///! them, notifications are queued for a session and delivered before it is ready for the next query.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+use protocol::{pgsql_types::PostgreSqlType, random::Random, results::Projection};
+
+use crate::catalog_queries::normalize;
+
+const ACTIVE: &str = "active";
+const IDLE: &str = "idle";
+
+/// normalized query texts answered with activity of registered sessions
+const ACTIVITY_QUERIES: &[&str] = &[
+    "select * from pg_stat_activity",
+    "select * from pg_catalog.pg_stat_activity",
+    "select pid, state, query, plan from pg_stat_activity",
+    "select pid, state, query, plan from pg_catalog.pg_stat_activity",
+];
+
+/// activity of a session at the moment it was looked at, `plan` is empty for idle sessions
+#[derive(Debug, PartialEq, Clone)]
+pub struct Activity {
+    pub pid: u32,
+    pub state: &'static str,
+    pub query: String,
+    pub plan: String,
+}
+
+/// notification sent on a channel by the session with the process id
+#[derive(Debug, PartialEq, Clone)]
+pub struct Notification {
+    pub pid: u32,
+    pub channel: String,
+    pub payload: String,
+}
+
+#[derive(Default)]
+struct Progress {
+    active: bool,
+    query: String,
+    operator: Option<String>,
+    rows: u64,
+}
+
+/// statement a session is executing, idle session keeps the text of its last statement
+pub struct SessionActivity {
+    pid: u32,
+    secret_key: u32,
+    canceled: AtomicBool,
+    progress: Mutex<Progress>,
+    channels: Mutex<BTreeSet<String>>,
+    notifications: Mutex<Vec<Notification>>,
+}
+
+impl SessionActivity {
+    fn new(pid: u32, secret_key: u32) -> SessionActivity {
+        SessionActivity {
+            pid,
+            secret_key,
+            canceled: AtomicBool::new(false),
+            progress: Mutex::new(Progress::default()),
+            channels: Mutex::default(),
+            notifications: Mutex::default(),
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn secret_key(&self) -> u32 {
+        self.secret_key
+    }
+
+    /// cancellation requested before the statement started is not applied to it
+    pub fn start(&self, query: &str) {
+        self.canceled.store(false, Ordering::SeqCst);
+        *self.progress.lock().expect("to acquire activity lock") = Progress {
+            active: true,
+            query: query.to_owned(),
+            operator: None,
+            rows: 0,
+        };
+    }
+
+    /// rows are counted from zero for every operator
+    pub fn enter<S: ToString>(&self, operator: S) {
+        let mut progress = self.progress.lock().expect("to acquire activity lock");
+        progress.operator = Some(operator.to_string());
+        progress.rows = 0;
+    }
+
+    pub fn produced(&self, rows: u64) {
+        self.progress.lock().expect("to acquire activity lock").rows += rows;
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::SeqCst)
+    }
+
+    pub fn finish(&self) {
+        let mut progress = self.progress.lock().expect("to acquire activity lock");
+        progress.active = false;
+        progress.operator = None;
+        progress.rows = 0;
+    }
+
+    pub fn listen(&self, channel: &str) {
+        self.channels
+            .lock()
+            .expect("to acquire channels lock")
+            .insert(channel.to_owned());
+    }
+
+    /// `None` channel stops listening to all channels, notifications that are already queued are kept
+    pub fn unlisten(&self, channel: Option<&str>) {
+        let mut channels = self.channels.lock().expect("to acquire channels lock");
+        match channel {
+            Some(channel) => {
+                channels.remove(channel);
+            }
+            None => channels.clear(),
+        }
+    }
+
+    /// notifications are taken in the order they were sent
+    pub fn take_notifications(&self) -> Vec<Notification> {
+        std::mem::take(&mut *self.notifications.lock().expect("to acquire notifications lock"))
+    }
+
+    pub fn snapshot(&self) -> Activity {
+        let progress = self.progress.lock().expect("to acquire activity lock");
+        Activity {
+            pid: self.pid,
+            state: if progress.active { ACTIVE } else { IDLE },
+            query: progress.query.clone(),
+            plan: progress
+                .operator
+                .as_ref()
+                .map(|operator| format!("{} (rows={})", operator, progress.rows))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// sessions by their process ids, ids are not reused
+pub struct SessionRegistry {
+    next_pid: AtomicU32,
+    secret_keys: Mutex<Random>,
+    sessions: RwLock<BTreeMap<u32, Arc<SessionActivity>>>,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> SessionRegistry {
+        SessionRegistry {
+            next_pid: AtomicU32::new(1),
+            secret_keys: Mutex::new(Random::from_system_time()),
+            sessions: RwLock::default(),
+        }
+    }
+}
+
+impl SessionRegistry {
+    pub fn register(&self) -> Arc<SessionActivity> {
+        let secret_key = self.secret_keys.lock().expect("to acquire secret keys lock").next_u32();
+        let activity = Arc::new(SessionActivity::new(
+            self.next_pid.fetch_add(1, Ordering::SeqCst),
+            secret_key,
+        ));
+        self.sessions
+            .write()
+            .expect("to acquire sessions lock")
+            .insert(activity.pid(), activity.clone());
+        activity
+    }
+
+    pub fn unregister(&self, pid: u32) {
+        self.sessions.write().expect("to acquire sessions lock").remove(&pid);
+    }
+
+    /// statement of the session is canceled only if the secret key matches, returns `false` otherwise
+    pub fn cancel(&self, pid: u32, secret_key: u32) -> bool {
+        match self.sessions.read().expect("to acquire sessions lock").get(&pid) {
+            Some(activity) if activity.secret_key == secret_key => {
+                activity.canceled.store(true, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// notifications are queued for every session that listens to their channels, including the sending one
+    pub fn notify(&self, notifications: Vec<Notification>) {
+        for activity in self.sessions.read().expect("to acquire sessions lock").values() {
+            let channels = activity.channels.lock().expect("to acquire channels lock");
+            let mut queue = activity.notifications.lock().expect("to acquire notifications lock");
+            queue.extend(
+                notifications
+                    .iter()
+                    .filter(|notification| channels.contains(&notification.channel))
+                    .cloned(),
+            );
+        }
+    }
+
+    /// activities are ordered by process ids
+    pub fn activities(&self) -> Vec<Activity> {
+        self.sessions
+            .read()
+            .expect("to acquire sessions lock")
+            .values()
+            .map(|activity| activity.snapshot())
+            .collect()
+    }
+}
+
+/// returns `None` if query is not a query of `pg_stat_activity`
+pub(crate) fn answer(sessions: &SessionRegistry, raw_sql_query: &str) -> Option<Projection> {
+    if !ACTIVITY_QUERIES.contains(&normalize(raw_sql_query).as_str()) {
+        return None;
+    }
+    Some((
+        vec![
+            ("pid".to_owned(), PostgreSqlType::Integer),
+            ("state".to_owned(), PostgreSqlType::VarChar),
+            ("query".to_owned(), PostgreSqlType::VarChar),
+            ("plan".to_owned(), PostgreSqlType::VarChar),
+        ],
+        sessions
+            .activities()
+            .into_iter()
+            .map(|activity| {
+                vec![
+                    activity.pid.to_string(),
+                    activity.state.to_owned(),
+                    activity.query,
+                    activity.plan,
+                ]
+            })
+            .collect(),
+    ))
+}
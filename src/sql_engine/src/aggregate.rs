@@ -0,0 +1,468 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! aggregate functions are split into `init`, `accumulate`, `merge` and `finalize` steps
+//! so that aggregation operators only drive the steps and know nothing about a particular
+//! function, partial states computed over different parts of input could be merged together
+
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+use data_manager::{DistinctSketch, QuantileSketch};
+use protocol::{array, pgsql_types::PostgreSqlType};
+use representation::{Datum, ScalarType};
+use sql_model::sql_types::SqlType;
+
+/// intermediate state of an aggregate, it is created and consumed only by the function itself
+pub type AggregateState = Box<dyn Any>;
+
+pub trait AggregateFunction: Send + Sync {
+    /// type of the result for the type of the argument, `None` for `*` argument
+    fn return_type(&self, argument: Option<&SqlType>) -> PostgreSqlType;
+
+    /// state before any value is accumulated
+    fn init(&self) -> AggregateState;
+
+    /// adds a non NULL value to the state, `*` argument is accumulated as `TRUE` for every row
+    fn accumulate(&self, state: &mut AggregateState, value: &Datum);
+
+    /// NULL values are accumulated as well, e.g. elements of `array_agg`
+    fn accumulates_nulls(&self) -> bool {
+        false
+    }
+
+    /// combines two states computed over disjoint sets of values
+    fn merge(&self, state: &mut AggregateState, other: AggregateState);
+
+    /// result of the aggregate over all accumulated values
+    fn finalize(&self, state: AggregateState) -> Datum<'static>;
+
+    /// aggregate configured by a constant second argument, e.g. the fraction of `approx_percentile(value, 0.9)`,
+    /// `None` when the function takes only a single argument or the argument is of other type
+    fn with_parameter(&self, _parameter: &Datum) -> Option<Arc<dyn AggregateFunction>> {
+        None
+    }
+
+    /// aggregate can't be called without the second argument, e.g. the delimiter of `string_agg`
+    fn requires_parameter(&self) -> bool {
+        false
+    }
+}
+
+/// aggregate functions by their lowercase names
+#[derive(Clone)]
+pub struct AggregateRegistry {
+    functions: HashMap<String, Arc<dyn AggregateFunction>>,
+}
+
+impl AggregateRegistry {
+    pub fn empty() -> AggregateRegistry {
+        AggregateRegistry {
+            functions: HashMap::new(),
+        }
+    }
+
+    /// registered function replaces a function with the same name
+    pub fn register<S: ToString>(&mut self, name: S, function: Arc<dyn AggregateFunction>) {
+        self.functions.insert(name.to_string().to_lowercase(), function);
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<Arc<dyn AggregateFunction>> {
+        self.functions.get(&name.to_lowercase()).cloned()
+    }
+}
+
+impl Default for AggregateRegistry {
+    fn default() -> AggregateRegistry {
+        let mut registry = AggregateRegistry::empty();
+        registry.register("count", Arc::new(Count));
+        registry.register("sum", Arc::new(Sum));
+        registry.register("avg", Arc::new(Avg));
+        registry.register("min", Arc::new(Extreme::Min));
+        registry.register("max", Arc::new(Extreme::Max));
+        registry.register("approx_count_distinct", Arc::new(ApproxCountDistinct));
+        registry.register("approx_percentile", Arc::new(ApproxPercentile { fraction: 0.5 }));
+        registry.register(
+            "string_agg",
+            Arc::new(StringAgg {
+                delimiter: String::new(),
+            }),
+        );
+        registry.register("array_agg", Arc::new(ArrayAgg));
+        registry
+    }
+}
+
+fn state<T: 'static>(state: &mut AggregateState) -> &mut T {
+    state
+        .downcast_mut::<T>()
+        .expect("state is created by the same aggregate")
+}
+
+fn into_state<T: 'static>(state: AggregateState) -> T {
+    *state.downcast::<T>().expect("state is created by the same aggregate")
+}
+
+struct Count;
+
+impl AggregateFunction for Count {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::BigInt
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(0i64)
+    }
+
+    fn accumulate(&self, count: &mut AggregateState, _value: &Datum) {
+        *state::<i64>(count) += 1;
+    }
+
+    fn merge(&self, count: &mut AggregateState, other: AggregateState) {
+        *state::<i64>(count) += into_state::<i64>(other);
+    }
+
+    fn finalize(&self, count: AggregateState) -> Datum<'static> {
+        Datum::from_i64(into_state(count))
+    }
+}
+
+#[derive(Default)]
+struct Total {
+    integer: i64,
+    float: f64,
+    is_float: bool,
+    count: i64,
+}
+
+impl Total {
+    fn add(&mut self, value: &Datum) {
+        match integer(value) {
+            Some(value) => {
+                self.integer += value;
+                self.float += value as f64;
+            }
+            None => {
+                self.float += numeric(value).unwrap_or_default();
+                self.is_float = true;
+            }
+        }
+        self.count += 1;
+    }
+
+    fn merge(&mut self, other: Total) {
+        self.integer += other.integer;
+        self.float += other.float;
+        self.is_float |= other.is_float;
+        self.count += other.count;
+    }
+}
+
+struct Sum;
+
+impl AggregateFunction for Sum {
+    fn return_type(&self, argument: Option<&SqlType>) -> PostgreSqlType {
+        match argument {
+            Some(SqlType::Real) => PostgreSqlType::Real,
+            Some(SqlType::DoublePrecision) => PostgreSqlType::DoublePrecision,
+            _ => PostgreSqlType::BigInt,
+        }
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(Total::default())
+    }
+
+    fn accumulate(&self, total: &mut AggregateState, value: &Datum) {
+        state::<Total>(total).add(value);
+    }
+
+    fn merge(&self, total: &mut AggregateState, other: AggregateState) {
+        state::<Total>(total).merge(into_state(other));
+    }
+
+    fn finalize(&self, total: AggregateState) -> Datum<'static> {
+        let total = into_state::<Total>(total);
+        if total.count == 0 {
+            Datum::from_null()
+        } else if total.is_float {
+            Datum::from_f64(total.float)
+        } else {
+            Datum::from_i64(total.integer)
+        }
+    }
+}
+
+struct Avg;
+
+impl AggregateFunction for Avg {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::DoublePrecision
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(Total::default())
+    }
+
+    fn accumulate(&self, total: &mut AggregateState, value: &Datum) {
+        state::<Total>(total).add(value);
+    }
+
+    fn merge(&self, total: &mut AggregateState, other: AggregateState) {
+        state::<Total>(total).merge(into_state(other));
+    }
+
+    fn finalize(&self, total: AggregateState) -> Datum<'static> {
+        let total = into_state::<Total>(total);
+        if total.count == 0 {
+            Datum::from_null()
+        } else {
+            Datum::from_f64(total.float / total.count as f64)
+        }
+    }
+}
+
+enum Extreme {
+    Min,
+    Max,
+}
+
+impl Extreme {
+    fn pick(&self, current: &mut Option<Datum<'static>>, value: Datum<'static>) {
+        let replace = match (self, current.as_ref()) {
+            (_, None) => true,
+            (Extreme::Min, Some(current)) => value < *current,
+            (Extreme::Max, Some(current)) => value > *current,
+        };
+        if replace {
+            *current = Some(value);
+        }
+    }
+}
+
+impl AggregateFunction for Extreme {
+    fn return_type(&self, argument: Option<&SqlType>) -> PostgreSqlType {
+        argument
+            .map(|sql_type| sql_type.into())
+            .unwrap_or(PostgreSqlType::BigInt)
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(None::<Datum<'static>>)
+    }
+
+    fn accumulate(&self, extreme: &mut AggregateState, value: &Datum) {
+        self.pick(state(extreme), owned(value));
+    }
+
+    fn merge(&self, extreme: &mut AggregateState, other: AggregateState) {
+        if let Some(value) = into_state::<Option<Datum<'static>>>(other) {
+            self.pick(state(extreme), value);
+        }
+    }
+
+    fn finalize(&self, extreme: AggregateState) -> Datum<'static> {
+        into_state::<Option<Datum<'static>>>(extreme).unwrap_or_else(Datum::from_null)
+    }
+}
+
+struct ApproxCountDistinct;
+
+impl AggregateFunction for ApproxCountDistinct {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::BigInt
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(DistinctSketch::new())
+    }
+
+    fn accumulate(&self, sketch: &mut AggregateState, value: &Datum) {
+        state::<DistinctSketch>(sketch).add(std::slice::from_ref(value));
+    }
+
+    fn merge(&self, sketch: &mut AggregateState, other: AggregateState) {
+        state::<DistinctSketch>(sketch).merge(&into_state(other));
+    }
+
+    fn finalize(&self, sketch: AggregateState) -> Datum<'static> {
+        Datum::from_i64(into_state::<DistinctSketch>(sketch).estimate() as i64)
+    }
+}
+
+/// `approx_percentile(value)` estimates the median
+struct ApproxPercentile {
+    fraction: f64,
+}
+
+impl AggregateFunction for ApproxPercentile {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::DoublePrecision
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(QuantileSketch::new())
+    }
+
+    fn accumulate(&self, sketch: &mut AggregateState, value: &Datum) {
+        if let Some(value) = numeric(value) {
+            state::<QuantileSketch>(sketch).add(value);
+        }
+    }
+
+    fn merge(&self, sketch: &mut AggregateState, other: AggregateState) {
+        state::<QuantileSketch>(sketch).merge(&into_state(other));
+    }
+
+    fn finalize(&self, sketch: AggregateState) -> Datum<'static> {
+        into_state::<QuantileSketch>(sketch)
+            .quantile(self.fraction)
+            .map(Datum::from_f64)
+            .unwrap_or_else(Datum::from_null)
+    }
+
+    fn with_parameter(&self, fraction: &Datum) -> Option<Arc<dyn AggregateFunction>> {
+        numeric(fraction).map(|fraction| Arc::new(ApproxPercentile { fraction }) as Arc<dyn AggregateFunction>)
+    }
+}
+
+/// `string_agg(value, delimiter)` concatenates values in the order of the frame
+struct StringAgg {
+    delimiter: String,
+}
+
+impl AggregateFunction for StringAgg {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::Text
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(None::<String>)
+    }
+
+    fn accumulate(&self, concatenated: &mut AggregateState, value: &Datum) {
+        match state::<Option<String>>(concatenated) {
+            Some(concatenated) => {
+                concatenated.push_str(&self.delimiter);
+                concatenated.push_str(&value.to_string());
+            }
+            empty => *empty = Some(value.to_string()),
+        }
+    }
+
+    fn merge(&self, concatenated: &mut AggregateState, other: AggregateState) {
+        if let Some(other) = into_state::<Option<String>>(other) {
+            self.accumulate(concatenated, &Datum::from_string(other));
+        }
+    }
+
+    fn finalize(&self, concatenated: AggregateState) -> Datum<'static> {
+        into_state::<Option<String>>(concatenated)
+            .map(Datum::from_string)
+            .unwrap_or_else(Datum::from_null)
+    }
+
+    fn with_parameter(&self, delimiter: &Datum) -> Option<Arc<dyn AggregateFunction>> {
+        match delimiter.scalar_type() {
+            Some(ScalarType::String) => Some(Arc::new(StringAgg {
+                delimiter: delimiter.to_string(),
+            })),
+            _ => None,
+        }
+    }
+
+    fn requires_parameter(&self) -> bool {
+        true
+    }
+}
+
+/// `array_agg(value)` collects values including NULLs in the order of the frame, values of types without
+/// array types are collected into `text[]`
+struct ArrayAgg;
+
+impl AggregateFunction for ArrayAgg {
+    fn return_type(&self, argument: Option<&SqlType>) -> PostgreSqlType {
+        match argument {
+            Some(SqlType::SmallInt(_)) => PostgreSqlType::SmallIntArray,
+            Some(SqlType::Integer(_)) => PostgreSqlType::IntegerArray,
+            Some(SqlType::BigInt(_)) => PostgreSqlType::BigIntArray,
+            _ => PostgreSqlType::TextArray,
+        }
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(Vec::<Option<String>>::new())
+    }
+
+    fn accumulate(&self, elements: &mut AggregateState, value: &Datum) {
+        let element = if value.is_null() { None } else { Some(value.to_string()) };
+        state::<Vec<Option<String>>>(elements).push(element);
+    }
+
+    fn accumulates_nulls(&self) -> bool {
+        true
+    }
+
+    fn merge(&self, elements: &mut AggregateState, other: AggregateState) {
+        state::<Vec<Option<String>>>(elements).extend(into_state::<Vec<Option<String>>>(other));
+    }
+
+    fn finalize(&self, elements: AggregateState) -> Datum<'static> {
+        let elements = into_state::<Vec<Option<String>>>(elements);
+        if elements.is_empty() {
+            Datum::from_null()
+        } else {
+            Datum::from_string(array::render(&elements))
+        }
+    }
+}
+
+/// states outlive rows they are computed from
+pub(crate) fn owned(datum: &Datum) -> Datum<'static> {
+    match datum {
+        Datum::Null => Datum::Null,
+        Datum::True => Datum::True,
+        Datum::False => Datum::False,
+        Datum::Int16(value) => Datum::Int16(*value),
+        Datum::Int32(value) => Datum::Int32(*value),
+        Datum::Int64(value) => Datum::Int64(*value),
+        Datum::UInt64(value) => Datum::UInt64(*value),
+        Datum::Float32(value) => Datum::Float32(*value),
+        Datum::Float64(value) => Datum::Float64(*value),
+        Datum::String(value) => Datum::OwnedString((*value).to_owned()),
+        Datum::OwnedString(value) => Datum::OwnedString(value.clone()),
+        Datum::SqlType(value) => Datum::SqlType(*value),
+        Datum::Timestamp(value) => Datum::Timestamp(*value),
+        Datum::Interval(value) => Datum::Interval(*value),
+        Datum::Bytes(value) => Datum::OwnedBytes(value.to_vec()),
+        Datum::OwnedBytes(value) => Datum::OwnedBytes(value.clone()),
+    }
+}
+
+pub(crate) fn integer(datum: &Datum) -> Option<i64> {
+    match datum.scalar_type() {
+        Some(ScalarType::Int16) => Some(datum.as_i16() as i64),
+        Some(ScalarType::Int32) => Some(datum.as_i32() as i64),
+        Some(ScalarType::Int64) => Some(datum.as_i64()),
+        Some(ScalarType::UInt64) => Some(datum.as_u64() as i64),
+        _ => None,
+    }
+}
+
+pub(crate) fn numeric(datum: &Datum) -> Option<f64> {
+    match datum.scalar_type() {
+        Some(ScalarType::Float32) => Some(datum.as_f32() as f64),
+        Some(ScalarType::Float64) => Some(datum.as_f64()),
+        _ => integer(datum).map(|value| value as f64),
+    }
+}
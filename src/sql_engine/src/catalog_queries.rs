@@ -0,0 +1,330 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Catalog queries issued by `pg_dump --schema-only` and migration tools
+///! (diesel, sqlx migrate) during their startup and by compile-time query
+///! checks of sqlx and diesel schema inference. The full `pg_catalog` is
+///! not implemented, instead known query texts are answered from the
+///! data manager catalog.
+use data_manager::{ColumnDefinition, DataManager};
+use protocol::{
+    pgsql_types::{Oid, PostgreSqlType},
+    results::Projection,
+};
+use sql_model::Id;
+
+const SERVER_VERSION: &str = "PostgreSQL 12.4 (database)";
+const DATABASE_NAME: &str = "public";
+const SEARCH_PATH_SCHEMA: &str = "public";
+/// OIDs below are reserved by PostgreSQL for system objects
+const FIRST_NORMAL_OID: Oid = 16384;
+const SUPPORTED_TYPES: &[PostgreSqlType] = &[
+    PostgreSqlType::Bool,
+    PostgreSqlType::Char,
+    PostgreSqlType::VarChar,
+    PostgreSqlType::Text,
+    PostgreSqlType::SmallInt,
+    PostgreSqlType::Integer,
+    PostgreSqlType::BigInt,
+];
+
+type Answer = fn(&DataManager) -> Projection;
+type ParameterizedAnswer = fn(&DataManager, &[&str]) -> Option<Projection>;
+
+/// normalized query texts and how they are answered
+const KNOWN_QUERIES: &[(&str, Answer)] = &[
+    ("select version()", version),
+    ("select pg_catalog.version()", version),
+    ("select current_database()", current_database),
+    ("select pg_catalog.current_database()", current_database),
+    ("select current_schema()", current_schema),
+    ("select pg_catalog.current_schema()", current_schema),
+    ("select pg_catalog.set_config('search_path', '', false)", set_config),
+    ("select nspname from pg_catalog.pg_namespace", namespaces),
+    ("select schema_name from information_schema.schemata", schemata),
+    ("select schemaname, tablename from pg_catalog.pg_tables", pg_tables),
+    (
+        "select table_schema, table_name from information_schema.tables",
+        information_schema_tables,
+    ),
+    ("select oid, typname from pg_catalog.pg_type", pg_types),
+    (
+        "select attrelid, attnum, attname, atttypid, attnotnull from pg_catalog.pg_attribute",
+        pg_attributes,
+    ),
+    (
+        "select table_schema, table_name, column_name, udt_name, is_nullable from information_schema.columns",
+        information_schema_columns,
+    ),
+];
+
+/// normalized query texts with `{}` in place of literals and how they are answered
+const KNOWN_PARAMETERIZED_QUERIES: &[(&str, ParameterizedAnswer)] = &[
+    (
+        "select attnotnull from pg_catalog.pg_attribute where attrelid = {} and attnum = {}",
+        column_not_null,
+    ),
+    (
+        "select column_name, udt_name, is_nullable from information_schema.columns \
+         where table_name = '{}' and table_schema = '{}' order by ordinal_position",
+        table_columns,
+    ),
+];
+
+/// OID of a table reported to clients in `RowDescription` and `pg_attribute`
+pub(crate) fn table_oid(table_id: &(Id, Id)) -> Oid {
+    let (schema_id, table_id) = table_id;
+    FIRST_NORMAL_OID + ((*schema_id as Oid) << 16) + *table_id as Oid
+}
+
+/// returns `None` if query is not one of known catalog queries
+pub(crate) fn answer(data_manager: &DataManager, raw_sql_query: &str) -> Option<Projection> {
+    let normalized = normalize(raw_sql_query);
+    match KNOWN_QUERIES.iter().find(|(text, _answer)| *text == normalized) {
+        Some((_text, answer)) => Some(answer(data_manager)),
+        None => KNOWN_PARAMETERIZED_QUERIES.iter().find_map(|(pattern, answer)| {
+            parameters(pattern, normalized.as_str()).and_then(|params| answer(data_manager, &params))
+        }),
+    }
+}
+
+/// extracts literals from the query text in places of `{}` in the pattern
+fn parameters<'q>(pattern: &str, query: &'q str) -> Option<Vec<&'q str>> {
+    let mut parts = pattern.split("{}");
+    let mut rest = query.strip_prefix(parts.next()?)?;
+    let mut params = vec![];
+    for part in parts {
+        let end = if part.is_empty() { rest.len() } else { rest.find(part)? };
+        params.push(&rest[..end]);
+        rest = &rest[end + part.len()..];
+    }
+    if rest.is_empty() {
+        Some(params)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn normalize(raw_sql_query: &str) -> String {
+    raw_sql_query
+        .trim()
+        .trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn single_value(column: &str, value: &str) -> Projection {
+    (
+        vec![(column.to_owned(), PostgreSqlType::VarChar)],
+        vec![vec![value.to_owned()]],
+    )
+}
+
+fn version(_data_manager: &DataManager) -> Projection {
+    single_value("version", SERVER_VERSION)
+}
+
+fn current_database(_data_manager: &DataManager) -> Projection {
+    single_value("current_database", DATABASE_NAME)
+}
+
+fn current_schema(_data_manager: &DataManager) -> Projection {
+    single_value("current_schema", SEARCH_PATH_SCHEMA)
+}
+
+fn set_config(_data_manager: &DataManager) -> Projection {
+    single_value("set_config", "")
+}
+
+fn namespaces(data_manager: &DataManager) -> Projection {
+    (
+        vec![("nspname".to_owned(), PostgreSqlType::VarChar)],
+        data_manager
+            .schema_names()
+            .into_iter()
+            .map(|schema_name| vec![schema_name])
+            .collect(),
+    )
+}
+
+fn schemata(data_manager: &DataManager) -> Projection {
+    (
+        vec![("schema_name".to_owned(), PostgreSqlType::VarChar)],
+        data_manager
+            .schema_names()
+            .into_iter()
+            .map(|schema_name| vec![schema_name])
+            .collect(),
+    )
+}
+
+fn pg_tables(data_manager: &DataManager) -> Projection {
+    (
+        vec![
+            ("schemaname".to_owned(), PostgreSqlType::VarChar),
+            ("tablename".to_owned(), PostgreSqlType::VarChar),
+        ],
+        data_manager
+            .table_names()
+            .into_iter()
+            .map(|(schema_name, table_name)| vec![schema_name, table_name])
+            .collect(),
+    )
+}
+
+fn information_schema_tables(data_manager: &DataManager) -> Projection {
+    (
+        vec![
+            ("table_schema".to_owned(), PostgreSqlType::VarChar),
+            ("table_name".to_owned(), PostgreSqlType::VarChar),
+        ],
+        data_manager
+            .table_names()
+            .into_iter()
+            .map(|(schema_name, table_name)| vec![schema_name, table_name])
+            .collect(),
+    )
+}
+
+fn pg_types(_data_manager: &DataManager) -> Projection {
+    (
+        vec![
+            ("oid".to_owned(), PostgreSqlType::Integer),
+            ("typname".to_owned(), PostgreSqlType::VarChar),
+        ],
+        SUPPORTED_TYPES
+            .iter()
+            .map(|pg_type| vec![pg_type.pg_oid().to_string(), pg_type.pg_type_name().to_owned()])
+            .collect(),
+    )
+}
+
+/// columns of all user tables as (table oid, schema name, table name, attribute number, column definition)
+fn user_columns(data_manager: &DataManager) -> Vec<(Oid, String, String, i16, ColumnDefinition)> {
+    let mut columns = vec![];
+    for table_id in data_manager.table_ids().into_iter().map(Box::new) {
+        if let (Some((schema_name, table_name)), Ok(table_columns)) = (
+            data_manager.table_name(&table_id),
+            data_manager.table_columns(&table_id),
+        ) {
+            for (index, column) in table_columns.into_iter().enumerate() {
+                columns.push((
+                    table_oid(table_id.as_ref()),
+                    schema_name.clone(),
+                    table_name.clone(),
+                    index as i16 + 1,
+                    column,
+                ));
+            }
+        }
+    }
+    columns
+}
+
+fn not_null_flag(column: &ColumnDefinition) -> String {
+    if column.is_nullable() { "f" } else { "t" }.to_owned()
+}
+
+fn is_nullable_flag(column: &ColumnDefinition) -> String {
+    if column.is_nullable() { "YES" } else { "NO" }.to_owned()
+}
+
+fn pg_attributes(data_manager: &DataManager) -> Projection {
+    (
+        vec![
+            ("attrelid".to_owned(), PostgreSqlType::Integer),
+            ("attnum".to_owned(), PostgreSqlType::SmallInt),
+            ("attname".to_owned(), PostgreSqlType::VarChar),
+            ("atttypid".to_owned(), PostgreSqlType::Integer),
+            ("attnotnull".to_owned(), PostgreSqlType::Bool),
+        ],
+        user_columns(data_manager)
+            .into_iter()
+            .map(|(table_oid, _schema_name, _table_name, attnum, column)| {
+                vec![
+                    table_oid.to_string(),
+                    attnum.to_string(),
+                    column.name(),
+                    column.sql_type().to_pg_types().pg_oid().to_string(),
+                    not_null_flag(&column),
+                ]
+            })
+            .collect(),
+    )
+}
+
+fn information_schema_columns(data_manager: &DataManager) -> Projection {
+    (
+        vec![
+            ("table_schema".to_owned(), PostgreSqlType::VarChar),
+            ("table_name".to_owned(), PostgreSqlType::VarChar),
+            ("column_name".to_owned(), PostgreSqlType::VarChar),
+            ("udt_name".to_owned(), PostgreSqlType::VarChar),
+            ("is_nullable".to_owned(), PostgreSqlType::VarChar),
+        ],
+        user_columns(data_manager)
+            .into_iter()
+            .map(|(_table_oid, schema_name, table_name, _attnum, column)| {
+                vec![
+                    schema_name,
+                    table_name,
+                    column.name(),
+                    column.sql_type().to_pg_types().pg_type_name().to_owned(),
+                    is_nullable_flag(&column),
+                ]
+            })
+            .collect(),
+    )
+}
+
+fn column_not_null(data_manager: &DataManager, params: &[&str]) -> Option<Projection> {
+    let attrelid = params[0].parse::<Oid>().ok()?;
+    let attnum = params[1].parse::<i16>().ok()?;
+    Some((
+        vec![("attnotnull".to_owned(), PostgreSqlType::Bool)],
+        user_columns(data_manager)
+            .into_iter()
+            .filter(|(table_oid, _schema_name, _table_name, column_attnum, _column)| {
+                *table_oid == attrelid && *column_attnum == attnum
+            })
+            .map(|(_table_oid, _schema_name, _table_name, _attnum, column)| vec![not_null_flag(&column)])
+            .collect(),
+    ))
+}
+
+fn table_columns(data_manager: &DataManager, params: &[&str]) -> Option<Projection> {
+    let (table_name, schema_name) = (params[0], params[1]);
+    Some((
+        vec![
+            ("column_name".to_owned(), PostgreSqlType::VarChar),
+            ("udt_name".to_owned(), PostgreSqlType::VarChar),
+            ("is_nullable".to_owned(), PostgreSqlType::VarChar),
+        ],
+        user_columns(data_manager)
+            .into_iter()
+            .filter(|(_table_oid, column_schema, column_table, _attnum, _column)| {
+                column_schema == schema_name && column_table == table_name
+            })
+            .map(|(_table_oid, _schema_name, _table_name, _attnum, column)| {
+                vec![
+                    column.name(),
+                    column.sql_type().to_pg_types().pg_type_name().to_owned(),
+                    is_nullable_flag(&column),
+                ]
+            })
+            .collect(),
+    ))
+}
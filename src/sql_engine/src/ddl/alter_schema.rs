@@ -0,0 +1,49 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use query_planner::plan::SchemaOwnerChange;
+
+pub(crate) struct AlterSchemaOwnerCommand {
+    change: SchemaOwnerChange,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AlterSchemaOwnerCommand {
+    pub(crate) fn new(
+        change: SchemaOwnerChange,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> AlterSchemaOwnerCommand {
+        AlterSchemaOwnerCommand {
+            change,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        self.data_manager
+            .alter_schema_owner(*self.change.schema_id.as_ref(), self.change.new_owner.as_str())?;
+        self.sender
+            .send(Ok(QueryEvent::SchemaAltered))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{DataManager, TableStatistics};
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use query_planner::TableId;
+use representation::Binary;
+
+use crate::ddl::create_statistics::{build_statistics, table_records};
+
+pub(crate) struct AnalyzeCommand {
+    tables: Vec<TableId>,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AnalyzeCommand {
+    pub(crate) fn new(tables: Vec<TableId>, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> AnalyzeCommand {
+        AnalyzeCommand {
+            tables,
+            data_manager,
+            sender,
+        }
+    }
+
+    /// number of rows, bounds of integer columns and extended statistics of the tables are rebuilt from their
+    /// current rows, the planner estimates costs of scans with them
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for table_id in &self.tables {
+            let columns = self
+                .data_manager
+                .table_columns(table_id)?
+                .iter()
+                .map(|column| column.name())
+                .collect::<Vec<_>>();
+            let records = table_records(&self.data_manager, *table_id.as_ref())?;
+            let rows = records.iter().map(Binary::unpack).collect::<Vec<_>>();
+            self.data_manager
+                .set_analyzed(table_id, TableStatistics::build(&columns, &rows))?;
+            for ((schema_id, statistics_name), statistics) in self.data_manager.table_statistics(table_id) {
+                let statistics = build_statistics(&self.data_manager, statistics)?;
+                self.data_manager
+                    .update_statistics(schema_id, &statistics_name, statistics)?;
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::TablesAnalyzed))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{DataManager, SqlFunction};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    session::FunctionContext,
+    Sender,
+};
+use query_planner::plan::FunctionCreationInfo;
+
+use crate::{function::FunctionRegistry, operator::OperatorTable, query::expr::ExpressionEvaluation};
+
+pub(crate) struct CreateFunctionCommand {
+    function_info: FunctionCreationInfo,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+}
+
+impl CreateFunctionCommand {
+    pub(crate) fn new(
+        function_info: FunctionCreationInfo,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
+    ) -> CreateFunctionCommand {
+        CreateFunctionCommand {
+            function_info,
+            data_manager,
+            sender,
+            function_context,
+            functions,
+            operators,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let FunctionCreationInfo {
+            schema_id,
+            function_name,
+            or_replace,
+            parameters,
+            return_type,
+            body,
+        } = &self.function_info;
+        let function = SqlFunction::new(function_name, parameters.clone(), *return_type, body);
+        // body is compiled to report errors when the function is created rather than when it is called
+        let evaluation = ExpressionEvaluation::new(
+            self.sender.clone(),
+            vec![],
+            self.function_context.clone(),
+            self.functions.clone(),
+            self.operators.clone(),
+            self.data_manager.clone(),
+        );
+        if evaluation.compile_function(&function).is_err() {
+            return Ok(());
+        }
+        let result = if self.data_manager.create_function(*schema_id, function, *or_replace)? {
+            Ok(QueryEvent::FunctionCreated)
+        } else {
+            Err(QueryError::function_already_exists(function_name))
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
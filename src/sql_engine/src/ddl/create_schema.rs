@@ -14,7 +14,7 @@
 
 use std::sync::Arc;
 
-use data_manager::DataManager;
+use data_manager::{DataManager, SUPERUSER};
 use kernel::SystemResult;
 use protocol::{results::QueryEvent, Sender};
 use query_planner::plan::SchemaCreationInfo;
@@ -23,6 +23,7 @@ pub(crate) struct CreateSchemaCommand {
     schema_info: SchemaCreationInfo,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    owner: String,
 }
 
 impl CreateSchemaCommand {
@@ -35,14 +36,30 @@ impl CreateSchemaCommand {
             schema_info,
             data_manager,
             sender,
+            owner: SUPERUSER.to_owned(),
         }
     }
 
+    /// the schema is owned by the role that creates it
+    pub(crate) fn with_owner(mut self, owner: &str) -> CreateSchemaCommand {
+        self.owner = owner.to_owned();
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let schema_name = &self.schema_info.schema_name;
-        match self.data_manager.create_schema(schema_name) {
+        let created = match self.schema_info.template {
+            Some(template) => self
+                .data_manager
+                .copy_schema(template.schema_id, schema_name, template.with_data),
+            None => self.data_manager.create_schema(schema_name),
+        };
+        match created {
             Err(error) => Err(error),
-            Ok(_schema_id) => {
+            Ok(schema_id) => {
+                if self.owner != SUPERUSER {
+                    self.data_manager.alter_schema_owner(schema_id, self.owner.as_str())?;
+                }
                 self.sender
                     .send(Ok(QueryEvent::SchemaCreated))
                     .expect("To Send Query Result to Client");
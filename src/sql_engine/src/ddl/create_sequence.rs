@@ -0,0 +1,83 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{DataManager, SequenceError};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use query_planner::plan::SequenceCreationInfo;
+
+pub(crate) struct CreateSequenceCommand {
+    sequence_info: SequenceCreationInfo,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreateSequenceCommand {
+    pub(crate) fn new(
+        sequence_info: SequenceCreationInfo,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreateSequenceCommand {
+        CreateSequenceCommand {
+            sequence_info,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let SequenceCreationInfo {
+            schema_id,
+            sequence_name,
+            if_not_exists,
+            options,
+        } = &self.sequence_info;
+        let result = match self.data_manager.create_sequence(*schema_id, sequence_name, *options)? {
+            Ok(()) => Ok(QueryEvent::SequenceCreated),
+            Err(SequenceError::AlreadyExists) if *if_not_exists => {
+                self.sender
+                    .send(Ok(QueryEvent::Skipped(QueryError::sequence_already_exists(
+                        sequence_name,
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Ok(QueryEvent::SequenceCreated)
+            }
+            Err(SequenceError::AlreadyExists) => Err(QueryError::sequence_already_exists(sequence_name)),
+            Err(SequenceError::ZeroIncrement) => Err(QueryError::invalid_parameter_value("INCREMENT must not be zero")),
+            Err(SequenceError::InvalidBounds { min_value, max_value }) => Err(QueryError::invalid_parameter_value(
+                format!("MINVALUE ({}) must be less than MAXVALUE ({})", min_value, max_value),
+            )),
+            Err(SequenceError::StartOutOfBounds { start, min_value, .. }) if start < min_value => {
+                Err(QueryError::invalid_parameter_value(format!(
+                    "START value ({}) cannot be less than MINVALUE ({})",
+                    start, min_value
+                )))
+            }
+            Err(SequenceError::StartOutOfBounds { start, max_value, .. }) => {
+                Err(QueryError::invalid_parameter_value(format!(
+                    "START value ({}) cannot be greater than MAXVALUE ({})",
+                    start, max_value
+                )))
+            }
+            Err(error) => unreachable!("sequence creation can't fail with {:?}", error),
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{DataManager, ExtendedStatistics, RangePartition};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use query_planner::plan::StatisticsCreationInfo;
+use representation::{Binary, Datum};
+use sql_model::Id;
+
+/// rows of a partitioned table are rows of all its partitions
+pub(crate) fn table_records(data_manager: &DataManager, table_id: (Id, Id)) -> SystemResult<Vec<Binary>> {
+    let scanned_tables = match data_manager.range_partitioning(&Box::new(table_id)) {
+        Some(partitioning) => partitioning
+            .partitions()
+            .iter()
+            .map(RangePartition::table_id)
+            .collect::<Vec<_>>(),
+        None => vec![table_id],
+    };
+    let mut records = vec![];
+    for scanned_table in scanned_tables {
+        records.extend(
+            data_manager
+                .full_scan(&Box::new(scanned_table))?
+                .map(Result::unwrap)
+                .map(Result::unwrap)
+                .map(|(_key, values)| values),
+        );
+    }
+    Ok(records)
+}
+
+/// statistics of a partitioned table are built from rows of all its partitions
+pub(crate) fn build_statistics(
+    data_manager: &DataManager,
+    statistics: ExtendedStatistics,
+) -> SystemResult<ExtendedStatistics> {
+    let table_columns = data_manager.table_columns(&Box::new(statistics.table_id()))?;
+    let indices = statistics
+        .columns()
+        .iter()
+        .filter_map(|name| table_columns.iter().position(|column| column.has_name(name)))
+        .collect::<Vec<_>>();
+    let records = table_records(data_manager, statistics.table_id())?;
+    let rows = records
+        .iter()
+        .map(Binary::unpack)
+        .map(|row| indices.iter().map(|index| row[*index].clone()).collect())
+        .collect::<Vec<Vec<Datum>>>();
+    Ok(statistics.build(&rows))
+}
+
+pub(crate) struct CreateStatisticsCommand {
+    statistics_info: StatisticsCreationInfo,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreateStatisticsCommand {
+    pub(crate) fn new(
+        statistics_info: StatisticsCreationInfo,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreateStatisticsCommand {
+        CreateStatisticsCommand {
+            statistics_info,
+            data_manager,
+            sender,
+        }
+    }
+
+    /// statistics are built right away instead of waiting for the next `ANALYZE`
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let StatisticsCreationInfo {
+            schema_id,
+            statistics_name,
+            if_not_exists,
+            table_id,
+            columns,
+            kinds,
+        } = &self.statistics_info;
+        let statistics = build_statistics(
+            &self.data_manager,
+            ExtendedStatistics::new(*table_id.as_ref(), columns.clone(), kinds.clone()),
+        )?;
+        let result = if self
+            .data_manager
+            .create_statistics(*schema_id, statistics_name, statistics)?
+        {
+            Ok(QueryEvent::StatisticsCreated)
+        } else if *if_not_exists {
+            self.sender
+                .send(Ok(QueryEvent::Skipped(QueryError::statistics_already_exists(
+                    statistics_name,
+                ))))
+                .expect("To Send Query Result to Client");
+            Ok(QueryEvent::StatisticsCreated)
+        } else {
+            Err(QueryError::statistics_already_exists(statistics_name))
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -14,15 +14,16 @@
 
 use std::sync::Arc;
 
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, RangePartition};
 use kernel::SystemResult;
 use protocol::{results::QueryEvent, Sender};
-use query_planner::plan::TableCreationInfo;
+use query_planner::plan::{TableCreationInfo, TablePartitioning};
 
 pub(crate) struct CreateTableCommand {
     table_info: TableCreationInfo,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl CreateTableCommand {
@@ -35,14 +36,34 @@ impl CreateTableCommand {
             table_info,
             data_manager,
             sender,
+            journal: None,
         }
     }
 
+    /// the table is seen by other sessions when the transaction is committed and dropped when it is rolled back
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> CreateTableCommand {
+        self.journal = journal;
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let (schema_id, table_name, columns) = self.table_info.as_tuple();
         match self.data_manager.create_table(schema_id, table_name, columns) {
             Err(error) => Err(error),
-            Ok(_table_id) => {
+            Ok(table_id) => {
+                if let Some(journal) = self.journal.as_ref() {
+                    self.data_manager
+                        .journal_created_table(journal, &Box::new((schema_id, table_id)));
+                }
+                match &self.table_info.partitioning {
+                    Some(TablePartitioning::ByRange(column_name)) => self
+                        .data_manager
+                        .partition_by_range(&Box::new((schema_id, table_id)), column_name)?,
+                    Some(TablePartitioning::RangeOf { parent, from, to }) => self
+                        .data_manager
+                        .attach_range_partition(parent, RangePartition::new((schema_id, table_id), *from, *to))?,
+                    None => {}
+                }
                 self.sender
                     .send(Ok(QueryEvent::TableCreated))
                     .expect("To Send Query Result to Client");
@@ -0,0 +1,61 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{DataManager, EnumType};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use query_planner::plan::EnumTypeCreationInfo;
+
+pub(crate) struct CreateEnumTypeCommand {
+    type_info: EnumTypeCreationInfo,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreateEnumTypeCommand {
+    pub(crate) fn new(
+        type_info: EnumTypeCreationInfo,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreateEnumTypeCommand {
+        CreateEnumTypeCommand {
+            type_info,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let EnumTypeCreationInfo {
+            schema_id,
+            type_name,
+            labels,
+        } = &self.type_info;
+        let result = if self
+            .data_manager
+            .create_enum_type(*schema_id, EnumType::new(type_name, labels.clone()))?
+        {
+            Ok(QueryEvent::TypeCreated)
+        } else {
+            Err(QueryError::type_already_exists(type_name))
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
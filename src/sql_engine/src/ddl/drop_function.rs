@@ -0,0 +1,58 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sql_model::Id;
+
+pub(crate) struct DropFunctionsCommand {
+    functions: Vec<(Id, String)>,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl DropFunctionsCommand {
+    pub(crate) fn new(
+        functions: Vec<(Id, String)>,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DropFunctionsCommand {
+        DropFunctionsCommand {
+            functions,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for (schema_id, function_name) in &self.functions {
+            if !self.data_manager.drop_function(*schema_id, function_name)? {
+                self.sender
+                    .send(Err(QueryError::function_does_not_exist(function_name)))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::FunctionDropped))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
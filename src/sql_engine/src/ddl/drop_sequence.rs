@@ -0,0 +1,74 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sql_model::Id;
+
+pub(crate) struct DropSequencesCommand {
+    sequences: Vec<(Id, String)>,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl DropSequencesCommand {
+    pub(crate) fn new(
+        sequences: Vec<(Id, String)>,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DropSequencesCommand {
+        DropSequencesCommand {
+            sequences,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for (schema_id, sequence_name) in &self.sequences {
+            // defaults of serial columns take values of their sequence until the table is dropped
+            if !self
+                .data_manager
+                .sequence_dependents(*schema_id, sequence_name)
+                .is_empty()
+            {
+                self.sender
+                    .send(Err(QueryError::dependent_objects_still_exist(format!(
+                        "sequence {}",
+                        sequence_name
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        for (schema_id, sequence_name) in &self.sequences {
+            if !self.data_manager.drop_sequence(*schema_id, sequence_name)? {
+                self.sender
+                    .send(Err(QueryError::sequence_does_not_exist(sequence_name)))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::SequenceDropped))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
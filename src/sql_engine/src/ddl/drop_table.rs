@@ -14,7 +14,7 @@
 
 use std::sync::Arc;
 
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal};
 use kernel::SystemResult;
 use protocol::{results::QueryEvent, Sender};
 use query_planner::TableId;
@@ -23,6 +23,7 @@ pub(crate) struct DropTableCommand {
     table_id: TableId,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl DropTableCommand {
@@ -31,11 +32,25 @@ impl DropTableCommand {
             table_id,
             data_manager,
             sender,
+            journal: None,
         }
     }
 
+    /// the table is dropped when the transaction is committed
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> DropTableCommand {
+        self.journal = journal;
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
-        match self.data_manager.drop_table(&self.table_id) {
+        let dropped = match self.journal.as_ref() {
+            Some(journal) => {
+                self.data_manager.journal_dropped_table(journal, &self.table_id);
+                Ok(())
+            }
+            None => self.data_manager.drop_table(&self.table_id),
+        };
+        match dropped {
             Err(error) => Err(error),
             Ok(()) => {
                 self.sender
@@ -12,7 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod alter_schema;
+pub(crate) mod analyze;
+pub(crate) mod create_function;
 pub(crate) mod create_schema;
+pub(crate) mod create_sequence;
+pub(crate) mod create_statistics;
 pub(crate) mod create_table;
+pub(crate) mod create_type;
+pub(crate) mod drop_function;
 pub(crate) mod drop_schema;
+pub(crate) mod drop_sequence;
+pub(crate) mod drop_statistics;
 pub(crate) mod drop_table;
+pub(crate) mod schema_privileges;
+pub(crate) mod vacuum;
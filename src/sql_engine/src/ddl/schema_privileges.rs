@@ -0,0 +1,86 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use query_planner::plan::SchemaPrivilegesChange;
+
+pub(crate) struct GrantSchemaPrivilegesCommand {
+    change: SchemaPrivilegesChange,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl GrantSchemaPrivilegesCommand {
+    pub(crate) fn new(
+        change: SchemaPrivilegesChange,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> GrantSchemaPrivilegesCommand {
+        GrantSchemaPrivilegesCommand {
+            change,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for schema_id in self.change.schema_ids.iter() {
+            for role in self.change.roles.iter() {
+                self.data_manager
+                    .grant_schema_privileges(*schema_id.as_ref(), role, &self.change.privileges)?;
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::PrivilegesGranted))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
+
+pub(crate) struct RevokeSchemaPrivilegesCommand {
+    change: SchemaPrivilegesChange,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl RevokeSchemaPrivilegesCommand {
+    pub(crate) fn new(
+        change: SchemaPrivilegesChange,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> RevokeSchemaPrivilegesCommand {
+        RevokeSchemaPrivilegesCommand {
+            change,
+            data_manager,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for schema_id in self.change.schema_ids.iter() {
+            for role in self.change.roles.iter() {
+                self.data_manager
+                    .revoke_schema_privileges(*schema_id.as_ref(), role, &self.change.privileges)?;
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::PrivilegesRevoked))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use query_planner::TableId;
+
+pub(crate) struct VacuumCommand {
+    tables: Vec<TableId>,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl VacuumCommand {
+    pub(crate) fn new(tables: Vec<TableId>, data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> VacuumCommand {
+        VacuumCommand {
+            tables,
+            data_manager,
+            sender,
+        }
+    }
+
+    /// space of deleted and updated rows is given back to the storage, tables are not versioned
+    /// so there are no dead row versions to prune
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        for table_id in &self.tables {
+            self.data_manager.vacuum(table_id)?;
+        }
+        self.sender
+            .send(Ok(QueryEvent::TablesVacuumed))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
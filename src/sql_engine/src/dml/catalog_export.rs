@@ -0,0 +1,115 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::{catalog_migration, CatalogExport, CatalogImportError, DataManager};
+use kernel::SystemResult;
+use protocol::{results::QueryError, Sender};
+use representation::{Datum, ScalarType};
+
+/// `export_catalog()` describes user schemas and tables as JSON text
+/// that `import_catalog(text)` recreates on an instance without user schemas,
+/// `catalog_migration([from text,] to text)` lists statements that migrate
+/// one exported catalog, or the current one, to another
+pub(crate) struct CatalogFunctions<'cf> {
+    data_manager: &'cf DataManager,
+    sender: &'cf dyn Sender,
+}
+
+impl<'cf> CatalogFunctions<'cf> {
+    pub(crate) fn new(data_manager: &'cf DataManager, sender: &'cf dyn Sender) -> CatalogFunctions<'cf> {
+        CatalogFunctions { data_manager, sender }
+    }
+
+    pub(crate) fn is_catalog_function(name: &str) -> bool {
+        matches!(name, "export_catalog" | "import_catalog" | "catalog_migration")
+    }
+
+    pub(crate) fn call(&self, name: &str, args: &[Datum]) -> SystemResult<Result<Datum<'static>, ()>> {
+        match (name, args) {
+            ("export_catalog", []) => Ok(Ok(Datum::from_string(self.data_manager.export_catalog()?))),
+            ("import_catalog", [catalog]) if catalog.is_string() => {
+                match self.data_manager.import_catalog(catalog.to_string().as_str())? {
+                    Ok(()) => Ok(Ok(Datum::from_bool(true))),
+                    Err(CatalogImportError::Malformed(message)) => {
+                        self.sender
+                            .send(Err(QueryError::invalid_text_representation(format!(
+                                "invalid catalog: {}",
+                                message
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        Ok(Err(()))
+                    }
+                    Err(CatalogImportError::NotEmpty) => {
+                        self.sender
+                            .send(Err(QueryError::object_not_in_prerequisite_state(
+                                "catalog can be imported only into an instance without schemas",
+                            )))
+                            .expect("To Send Query Result to Client");
+                        Ok(Err(()))
+                    }
+                }
+            }
+            ("catalog_migration", [to]) if to.is_string() => {
+                let from = self.data_manager.catalog()?;
+                Ok(self.migration(Ok(from), to.to_string().as_str()))
+            }
+            ("catalog_migration", [from, to]) if from.is_string() && to.is_string() => Ok(self.migration(
+                CatalogExport::from_json(from.to_string().as_str()),
+                to.to_string().as_str(),
+            )),
+            _ => Ok(self.function_does_not_exist(name, args)),
+        }
+    }
+
+    /// statements are separated by new lines
+    fn migration(&self, from: Result<CatalogExport, String>, to: &str) -> Result<Datum<'static>, ()> {
+        match (from, CatalogExport::from_json(to)) {
+            (Ok(from), Ok(to)) => Ok(Datum::from_string(catalog_migration(&from, &to).join("\n"))),
+            (Err(message), _) | (_, Err(message)) => {
+                self.sender
+                    .send(Err(QueryError::invalid_text_representation(format!(
+                        "invalid catalog: {}",
+                        message
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    fn function_does_not_exist(&self, name: &str, args: &[Datum]) -> Result<Datum<'static>, ()> {
+        let arg_types = args
+            .iter()
+            .map(|arg| match arg.scalar_type() {
+                Some(ScalarType::String) => "text",
+                Some(ScalarType::Int16) | Some(ScalarType::Int32) => "integer",
+                Some(ScalarType::Int64) | Some(ScalarType::UInt64) => "bigint",
+                Some(ScalarType::Float32) | Some(ScalarType::Float64) => "double precision",
+                Some(ScalarType::Boolean) => "boolean",
+                Some(ScalarType::Timestamp) => "timestamp with time zone",
+                Some(ScalarType::Interval) => "interval",
+                Some(ScalarType::Bytes) => "bytea",
+                None => "unknown",
+            })
+            .collect::<Vec<_>>();
+        self.sender
+            .send(Err(QueryError::function_does_not_exist(format!(
+                "{}({})",
+                name,
+                arg_types.join(", ")
+            ))))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
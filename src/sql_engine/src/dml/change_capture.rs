@@ -0,0 +1,162 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::{CommittedChanges, DataManager, RowChange, TableChanges};
+use kernel::SystemResult;
+use protocol::{json::JsonValue, results::QueryError, session::FunctionContext, Sender};
+use representation::{Binary, Datum, ScalarType};
+
+use crate::dml::select::render;
+
+/// `create_change_slot(text)` starts capturing committed row changes into the slot,
+/// `take_slot_changes(text)` returns changes captured since they were taken the last time
+/// as a JSON array and `drop_change_slot(text)` stops capturing them
+pub(crate) struct ChangeSlotFunctions<'csf> {
+    data_manager: &'csf DataManager,
+    sender: &'csf dyn Sender,
+    function_context: &'csf FunctionContext,
+}
+
+impl<'csf> ChangeSlotFunctions<'csf> {
+    pub(crate) fn new(
+        data_manager: &'csf DataManager,
+        sender: &'csf dyn Sender,
+        function_context: &'csf FunctionContext,
+    ) -> ChangeSlotFunctions<'csf> {
+        ChangeSlotFunctions {
+            data_manager,
+            sender,
+            function_context,
+        }
+    }
+
+    pub(crate) fn is_change_slot_function(name: &str) -> bool {
+        matches!(name, "create_change_slot" | "drop_change_slot" | "take_slot_changes")
+    }
+
+    pub(crate) fn call(&self, name: &str, args: &[Datum]) -> SystemResult<Result<Datum<'static>, ()>> {
+        match (name, args) {
+            ("create_change_slot", [slot_name]) if slot_name.is_string() => {
+                let slot_name = slot_name.to_string();
+                if self.data_manager.create_change_slot(slot_name.as_str()) {
+                    Ok(Ok(Datum::from_bool(true)))
+                } else {
+                    self.sender
+                        .send(Err(QueryError::replication_slot_already_exists(slot_name.as_str())))
+                        .expect("To Send Query Result to Client");
+                    Ok(Err(()))
+                }
+            }
+            ("drop_change_slot", [slot_name]) if slot_name.is_string() => {
+                let slot_name = slot_name.to_string();
+                if self.data_manager.drop_change_slot(slot_name.as_str()) {
+                    Ok(Ok(Datum::from_bool(true)))
+                } else {
+                    Ok(self.slot_does_not_exist(slot_name.as_str()))
+                }
+            }
+            ("take_slot_changes", [slot_name]) if slot_name.is_string() => {
+                let slot_name = slot_name.to_string();
+                match self.data_manager.take_slot_changes(slot_name.as_str()) {
+                    Some(committed) => {
+                        let changes = committed.iter().flat_map(|committed| self.changes(committed)).collect();
+                        Ok(Ok(Datum::from_string(JsonValue::Array(changes).to_string())))
+                    }
+                    None => Ok(self.slot_does_not_exist(slot_name.as_str())),
+                }
+            }
+            _ => Ok(self.function_does_not_exist(name, args)),
+        }
+    }
+
+    /// every changed row is an object with the transaction that committed it, its table, the kind of the change
+    /// and its images, images are objects of column names and their values as they are selected
+    fn changes(&self, committed: &CommittedChanges) -> Vec<JsonValue> {
+        let mut changes = vec![];
+        for table in committed.tables.iter() {
+            for row in table.rows.iter() {
+                let kind = match row {
+                    RowChange { before: None, .. } => "insert",
+                    RowChange { after: None, .. } => "delete",
+                    _ => "update",
+                };
+                changes.push(JsonValue::Object(vec![
+                    (
+                        "transaction".to_owned(),
+                        JsonValue::Number(committed.transaction.to_string()),
+                    ),
+                    ("schema".to_owned(), JsonValue::String(table.schema_name.clone())),
+                    ("table".to_owned(), JsonValue::String(table.table_name.clone())),
+                    ("kind".to_owned(), JsonValue::String(kind.to_owned())),
+                    ("before".to_owned(), self.image(table, row.before.as_ref())),
+                    ("after".to_owned(), self.image(table, row.after.as_ref())),
+                ]));
+            }
+        }
+        changes
+    }
+
+    fn image(&self, table: &TableChanges, values: Option<&Binary>) -> JsonValue {
+        match values {
+            Some(values) => JsonValue::Object(
+                table
+                    .columns
+                    .iter()
+                    .zip(values.unpack().iter())
+                    .map(|(column, datum)| {
+                        let value = if datum.is_null() {
+                            JsonValue::Null
+                        } else {
+                            JsonValue::String(render(datum, column, self.function_context))
+                        };
+                        (column.name(), value)
+                    })
+                    .collect(),
+            ),
+            None => JsonValue::Null,
+        }
+    }
+
+    fn slot_does_not_exist(&self, slot_name: &str) -> Result<Datum<'static>, ()> {
+        self.sender
+            .send(Err(QueryError::replication_slot_does_not_exist(slot_name)))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+
+    fn function_does_not_exist(&self, name: &str, args: &[Datum]) -> Result<Datum<'static>, ()> {
+        let arg_types = args
+            .iter()
+            .map(|arg| match arg.scalar_type() {
+                Some(ScalarType::String) => "text",
+                Some(ScalarType::Int16) | Some(ScalarType::Int32) => "integer",
+                Some(ScalarType::Int64) | Some(ScalarType::UInt64) => "bigint",
+                Some(ScalarType::Float32) | Some(ScalarType::Float64) => "double precision",
+                Some(ScalarType::Boolean) => "boolean",
+                Some(ScalarType::Timestamp) => "timestamp with time zone",
+                Some(ScalarType::Interval) => "interval",
+                Some(ScalarType::Bytes) => "bytea",
+                None => "unknown",
+            })
+            .collect::<Vec<_>>();
+        self.sender
+            .send(Err(QueryError::function_does_not_exist(format!(
+                "{}({})",
+                name,
+                arg_types.join(", ")
+            ))))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
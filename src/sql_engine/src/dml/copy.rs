@@ -0,0 +1,255 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{io, sync::Arc};
+
+use data_manager::{DataManager, Journal, RangePartition};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent, QueryResult},
+    session::FunctionContext,
+    Sender,
+};
+use query_planner::plan::{CopyFormat, TableCopy, TableInserts};
+use sqlparser::ast::{Expr, Value};
+
+use crate::dml::{scan_table, select::render};
+
+/// `COPY ... FROM STDIN` that waits for the client to send all of its rows
+pub(crate) struct CopyIn {
+    query: String,
+    table_inserts: TableInserts,
+    data: Vec<u8>,
+}
+
+impl CopyIn {
+    pub(crate) fn new(query: &str, table_inserts: TableInserts) -> CopyIn {
+        CopyIn {
+            query: query.to_owned(),
+            table_inserts,
+            data: vec![],
+        }
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(crate) fn table_inserts(&self) -> &TableInserts {
+        &self.table_inserts
+    }
+
+    /// rows can be split between `CopyData` messages in any place
+    pub(crate) fn append(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    /// rows of text format are parsed into values of the inserts, `columns` is the number of fields in every row
+    pub(crate) fn into_inserts(self, columns: usize) -> Result<TableInserts, QueryError> {
+        let CopyIn {
+            mut table_inserts,
+            data,
+            ..
+        } = self;
+        let data = String::from_utf8(data)
+            .map_err(|_| QueryError::bad_copy_file_format("invalid byte sequence for encoding \"UTF8\""))?;
+        for line in data.lines() {
+            if line == "\\." {
+                break;
+            }
+            let row = line.split('\t').map(field).collect::<Vec<Expr>>();
+            if row.len() < columns {
+                return Err(QueryError::bad_copy_file_format("missing data for column"));
+            }
+            if row.len() > columns {
+                return Err(QueryError::bad_copy_file_format(
+                    "extra data after last expected column",
+                ));
+            }
+            table_inserts.input.push(row);
+        }
+        Ok(table_inserts)
+    }
+}
+
+/// `\N` is NULL, backslash escapes are replaced with characters they stand for
+fn field(text: &str) -> Expr {
+    if text == "\\N" {
+        return Expr::Value(Value::Null);
+    }
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('b') => value.push('\x08'),
+            Some('f') => value.push('\x0c'),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('v') => value.push('\x0b'),
+            Some(other) => value.push(other),
+            None => value.push('\\'),
+        }
+    }
+    Expr::Value(Value::SingleQuotedString(value))
+}
+
+/// reports inserted records as copied ones
+pub(crate) struct CopySender(pub(crate) Arc<dyn Sender>);
+
+impl Sender for CopySender {
+    fn flush(&self) -> io::Result<()> {
+        self.0.flush()
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        match query_result {
+            Ok(QueryEvent::RecordsInserted(records)) => self.0.send(Ok(QueryEvent::RecordsCopied(records))),
+            other => self.0.send(other),
+        }
+    }
+}
+
+/// `COPY ... TO STDOUT` that sends every row of the table in its own `CopyData` message
+pub(crate) struct CopyOutCommand {
+    table_copy: TableCopy,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    journal: Option<Arc<Journal>>,
+}
+
+impl CopyOutCommand {
+    pub(crate) fn new(
+        table_copy: TableCopy,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+    ) -> CopyOutCommand {
+        CopyOutCommand {
+            table_copy,
+            data_manager,
+            sender,
+            function_context,
+            journal: None,
+        }
+    }
+
+    /// rows written by the transaction of the journal are copied instead of committed ones
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> CopyOutCommand {
+        self.journal = journal;
+        self
+    }
+
+    /// rows of a partitioned table are copied from all of its partitions
+    pub(crate) fn execute(&self) -> SystemResult<()> {
+        let all_columns = self.data_manager.table_columns(&self.table_copy.table_id)?;
+        // listed columns are checked by the planner
+        let columns = if self.table_copy.columns.is_empty() {
+            (0..all_columns.len()).collect::<Vec<usize>>()
+        } else {
+            self.table_copy
+                .columns
+                .iter()
+                .filter_map(|column| all_columns.iter().position(|def| def.has_name(&column.value)))
+                .collect()
+        };
+        let stored_tables = match self.data_manager.range_partitioning(&self.table_copy.table_id) {
+            Some(partitioning) => partitioning
+                .partitions()
+                .iter()
+                .map(RangePartition::table_id)
+                .collect::<Vec<_>>(),
+            None => vec![*self.table_copy.table_id.as_ref()],
+        };
+        self.sender
+            .send(Ok(QueryEvent::CopyOutStarted(columns.len())))
+            .expect("To Send Query Result to Client");
+        let format = self.table_copy.format;
+        if let CopyFormat::Csv { header: true } = format {
+            let names = columns
+                .iter()
+                .map(|index| Some(all_columns[*index].name()))
+                .collect::<Vec<_>>();
+            self.send_line(format, names);
+        }
+        let mut records = 0;
+        for table_id in stored_tables {
+            for (_key, values) in scan_table(&self.data_manager, &self.journal, table_id)?
+                .map(Result::unwrap)
+                .map(Result::unwrap)
+            {
+                let row = values.unpack();
+                let fields = columns
+                    .iter()
+                    .map(|index| {
+                        if row[*index].is_null() {
+                            None
+                        } else {
+                            Some(render(&row[*index], &all_columns[*index], &self.function_context))
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                self.send_line(format, fields);
+                records += 1;
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::CopyOutDone(records)))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    /// `None` fields are NULLs
+    fn send_line(&self, format: CopyFormat, fields: Vec<Option<String>>) {
+        let mut line = match format {
+            CopyFormat::Text => fields
+                .iter()
+                .map(|field| field.as_deref().map(text_field).unwrap_or_else(|| "\\N".to_owned()))
+                .collect::<Vec<String>>()
+                .join("\t"),
+            CopyFormat::Csv { .. } => fields
+                .iter()
+                .map(|field| field.as_deref().map(csv_field).unwrap_or_default())
+                .collect::<Vec<String>>()
+                .join(","),
+        };
+        line.push('\n');
+        self.sender
+            .send(Ok(QueryEvent::CopyData(line.into_bytes())))
+            .expect("To Send Query Result to Client");
+    }
+}
+
+/// backslash and characters that separate fields and rows are escaped
+fn text_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// values that could be read as NULL or split into several fields are quoted
+fn csv_field(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
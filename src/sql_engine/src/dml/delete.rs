@@ -12,17 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
-use data_manager::DataManager;
+use data_manager::{DataManager, Journal, ReferentialAction};
 use kernel::SystemResult;
-use protocol::{results::QueryEvent, Sender};
+use protocol::{
+    results::{QueryError, QueryEvent},
+    session::FunctionContext,
+    Sender,
+};
 use query_planner::plan::TableDeletes;
+use representation::{unpack_raw, Binary, Datum};
+use sql_model::Id;
+
+use crate::{
+    dml::{delete_rows, referenced_value, referencing_columns, scan_table, write_rows},
+    function::FunctionRegistry,
+    operator::OperatorTable,
+    query::expr::{EvalScalarOp, ExpressionEvaluation},
+};
 
 pub(crate) struct DeleteCommand {
     table_deletes: TableDeletes,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl DeleteCommand {
@@ -30,25 +50,92 @@ impl DeleteCommand {
         table_deletes: TableDeletes,
         data_manager: Arc<DataManager>,
         sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
     ) -> DeleteCommand {
         DeleteCommand {
             table_deletes,
             data_manager,
             sender,
+            function_context,
+            functions,
+            operators,
+            journal: None,
         }
     }
 
+    /// rows deleted or set to NULL are kept in the journal until its transaction is committed
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> DeleteCommand {
+        self.journal = journal;
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
-        match self.data_manager.full_scan(&self.table_deletes.table_id) {
+        let all_columns = self.data_manager.table_columns(&self.table_deletes.table_id)?;
+        let filter = match &self.table_deletes.filter {
+            Some(filter) => {
+                let evaluation = ExpressionEvaluation::new(
+                    self.sender.clone(),
+                    all_columns.clone(),
+                    self.function_context.clone(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                    self.data_manager.clone(),
+                );
+                match evaluation.eval_condition(filter, "WHERE") {
+                    Ok(filter) => Some(filter),
+                    Err(()) => return Ok(()),
+                }
+            }
+            None => None,
+        };
+        match scan_table(&self.data_manager, &self.journal, *self.table_deletes.table_id.as_ref()) {
             Err(e) => return Err(e),
             Ok(reads) => {
-                let keys = reads
-                    .map(Result::unwrap)
-                    .map(Result::unwrap)
-                    .map(|(key, _)| key)
-                    .collect();
+                let expr_eval =
+                    EvalScalarOp::new(self.sender.as_ref(), all_columns, self.function_context.utc_offset());
+                let mut keys: Vec<Binary> = vec![];
+                for (key, values) in reads.map(Result::unwrap).map(Result::unwrap) {
+                    let is_deleted = match &filter {
+                        Some(filter) => match expr_eval.eval(&unpack_raw(values.to_bytes()), filter) {
+                            Ok(satisfied) => satisfied == Datum::from_bool(true),
+                            Err(()) => return Ok(()),
+                        },
+                        None => true,
+                    };
+                    if is_deleted {
+                        keys.push(key);
+                    }
+                }
+
+                let table_id = *self.table_deletes.table_id.as_ref();
+                let (cascaded_deletes, nullified_rows) = match self.referential_actions(table_id, &keys)? {
+                    Some(actions) => actions,
+                    None => return Ok(()),
+                };
+                for (referencing_table_id, rows) in nullified_rows {
+                    let rows = rows
+                        .into_iter()
+                        .filter(|(key, _values)| {
+                            cascaded_deletes
+                                .get(&referencing_table_id)
+                                .map(|deleted| !deleted.contains(key))
+                                .unwrap_or(true)
+                        })
+                        .collect();
+                    write_rows(&self.data_manager, &self.journal, referencing_table_id, rows)?;
+                }
+                for (referencing_table_id, deleted) in cascaded_deletes {
+                    delete_rows(
+                        &self.data_manager,
+                        &self.journal,
+                        referencing_table_id,
+                        deleted.into_iter().collect(),
+                    )?;
+                }
 
-                match self.data_manager.delete_from(&self.table_deletes.table_id, keys) {
+                match delete_rows(&self.data_manager, &self.journal, table_id, keys) {
                     Err(e) => return Err(e),
                     Ok(records_number) => self
                         .sender
@@ -59,4 +146,96 @@ impl DeleteCommand {
         }
         Ok(())
     }
+
+    /// collects rows of referencing tables that have to be deleted or set to NULL
+    /// when keys are deleted from the table; `None` if any foreign key forbids it
+    #[allow(clippy::type_complexity)]
+    fn referential_actions(
+        &self,
+        table_id: (Id, Id),
+        keys: &[Binary],
+    ) -> SystemResult<
+        Option<(
+            BTreeMap<(Id, Id), BTreeSet<Binary>>,
+            BTreeMap<(Id, Id), BTreeMap<Binary, Binary>>,
+        )>,
+    > {
+        let mut cascaded_deletes: BTreeMap<(Id, Id), BTreeSet<Binary>> = BTreeMap::new();
+        let mut nullified_rows: BTreeMap<(Id, Id), BTreeMap<Binary, Binary>> = BTreeMap::new();
+        // keys of tables that no table refers to can be generated row ids that are not packed values
+        if referencing_columns(&self.data_manager, &table_id)?.is_empty() {
+            return Ok(Some((cascaded_deletes, nullified_rows)));
+        }
+        let mut to_check = vec![(table_id, keys.iter().map(referenced_value).collect::<BTreeSet<_>>())];
+        while let Some((referenced_table_id, deleted_values)) = to_check.pop() {
+            for referencing in referencing_columns(&self.data_manager, &referenced_table_id)? {
+                let mut cascaded_values = BTreeSet::new();
+                for (key, values) in scan_table(&self.data_manager, &self.journal, referencing.table_id)?
+                    .map(Result::unwrap)
+                    .map(Result::unwrap)
+                {
+                    let already_deleted = cascaded_deletes
+                        .get(&referencing.table_id)
+                        .map(|deleted| deleted.contains(&key))
+                        .unwrap_or(false);
+                    if already_deleted || values.is_null(referencing.index) {
+                        continue;
+                    }
+                    let mut datums = values.unpack();
+                    if !deleted_values.contains(&datums[referencing.index].to_string()) {
+                        continue;
+                    }
+                    let on_delete = referencing
+                        .column
+                        .referenced_column()
+                        .map(|reference| reference.on_delete())
+                        .unwrap_or(ReferentialAction::NoAction);
+                    match on_delete {
+                        ReferentialAction::NoAction | ReferentialAction::Restrict => {
+                            self.sender
+                                .send(Err(QueryError::referenced_row_violation(
+                                    self.data_manager
+                                        .table_name(&Box::new(referenced_table_id))
+                                        .map(|(_schema_name, table_name)| table_name)
+                                        .unwrap_or_default(),
+                                    referencing.constraint_name(),
+                                    referencing.table_name.as_str(),
+                                )))
+                                .expect("To Send Query Result to Client");
+                            return Ok(None);
+                        }
+                        ReferentialAction::Cascade => {
+                            cascaded_values.insert(referenced_value(&key));
+                            cascaded_deletes.entry(referencing.table_id).or_default().insert(key);
+                        }
+                        ReferentialAction::SetNull => {
+                            if !referencing.column.is_nullable() {
+                                self.sender
+                                    .send(Err(QueryError::not_null_violation(referencing.column.name())))
+                                    .expect("To Send Query Result to Client");
+                                return Ok(None);
+                            }
+                            let nullified = nullified_rows.entry(referencing.table_id).or_default();
+                            let values = match nullified.get(&key) {
+                                Some(already_nullified) => {
+                                    let mut datums = already_nullified.unpack();
+                                    datums[referencing.index] = Datum::from_null();
+                                    Binary::pack_row(&datums)
+                                }
+                                None => {
+                                    datums[referencing.index] = Datum::from_null();
+                                    Binary::pack_row(&datums)
+                                }
+                            };
+                            nullified.insert(key, values);
+                        }
+                    }
+                }
+                if !cascaded_values.is_empty() {
+                    to_check.push((referencing.table_id, cascaded_values));
+                }
+            }
+        }
+        Ok(Some((cascaded_deletes, nullified_rows)))
+    }
 }
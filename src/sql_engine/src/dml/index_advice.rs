@@ -0,0 +1,111 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::{ColumnDefinition, DataManager};
+use kernel::SystemResult;
+use protocol::{pgsql_types::PostgreSqlType, Sender};
+use query_planner::TableId;
+
+use crate::dml::send_projection;
+
+const FOREIGN_KEY: &str = "foreign key without index";
+const FILTERED: &str = "filtered without index";
+
+pub(crate) struct IndexAdviceCommand {
+    tables: Vec<TableId>,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl IndexAdviceCommand {
+    pub(crate) fn new(
+        tables: Vec<TableId>,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> IndexAdviceCommand {
+        IndexAdviceCommand {
+            tables,
+            data_manager,
+            sender,
+        }
+    }
+
+    /// foreign keys are listed before columns filtered by `WHERE` that are ordered by number of filtering statements
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let mut foreign_keys = vec![];
+        let mut filtered = vec![];
+        for table_id in self.tables.iter() {
+            let (schema_name, table_name) = match self.data_manager.table_name(table_id) {
+                Some(full_name) => full_name,
+                None => continue,
+            };
+            let columns = self.data_manager.table_columns(table_id)?;
+            let filters = self.data_manager.column_filters(table_id);
+            for column in columns.iter().filter(|column| !is_indexed(&columns, column)) {
+                let filter_count = filters
+                    .iter()
+                    .find(|(name, _)| column.has_name(name))
+                    .map(|(_, count)| *count)
+                    .unwrap_or_default();
+                if column.referenced_column().is_some() {
+                    foreign_keys.push(vec![
+                        schema_name.clone(),
+                        table_name.clone(),
+                        column.name(),
+                        FOREIGN_KEY.to_owned(),
+                        filter_count.to_string(),
+                    ]);
+                } else if filter_count > 0 {
+                    filtered.push((
+                        filter_count,
+                        vec![
+                            schema_name.clone(),
+                            table_name.clone(),
+                            column.name(),
+                            FILTERED.to_owned(),
+                            filter_count.to_string(),
+                        ],
+                    ));
+                }
+            }
+        }
+        filtered.sort_by(|(left, _), (right, _)| right.cmp(left));
+        foreign_keys.extend(filtered.into_iter().map(|(_, row)| row));
+        send_projection(
+            self.sender.as_ref(),
+            (
+                vec![
+                    ("schema_name".to_owned(), PostgreSqlType::VarChar),
+                    ("table_name".to_owned(), PostgreSqlType::VarChar),
+                    ("column_name".to_owned(), PostgreSqlType::VarChar),
+                    ("reason".to_owned(), PostgreSqlType::VarChar),
+                    ("filters".to_owned(), PostgreSqlType::BigInt),
+                ],
+                foreign_keys,
+            ),
+        );
+        Ok(())
+    }
+}
+
+/// primary key is the only index of a table, it supports lookups by its leading column
+fn is_indexed(columns: &[ColumnDefinition], column: &ColumnDefinition) -> bool {
+    columns
+        .iter()
+        .find(|column| column.is_primary_key())
+        .map(|leading| leading.has_name(&column.name()))
+        .unwrap_or(false)
+}
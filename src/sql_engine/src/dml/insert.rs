@@ -12,24 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
-use data_manager::{DataManager, Row};
+use data_manager::{ColumnDefinition, DataManager, Journal, RangePartition, RangePartitioning, Row};
 use kernel::SystemResult;
 use protocol::{
     results::{QueryError, QueryEvent},
+    session::FunctionContext,
     Sender,
 };
 use representation::{Binary, Datum};
-use sql_model::sql_types::ConstraintError;
+use sql_model::{sql_types::SqlType, Id};
 
-use crate::query::expr::{ExprMetadata, ExpressionEvaluation};
+use crate::{
+    aggregate::integer,
+    dml::{
+        column_value, foreign_key_violation, generated_columns, null_in_not_null_column, primary_key_columns,
+        primary_key_constraint_name, sequence::SequenceFunctions, write_rows,
+    },
+    function::FunctionRegistry,
+    operator::OperatorTable,
+    query::{
+        expr::{EvalScalarOp, ExprMetadata, ExpressionEvaluation},
+        scalar::ScalarOp,
+    },
+};
 use query_planner::plan::TableInserts;
 
 pub(crate) struct InsertCommand {
     table_inserts: TableInserts,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl InsertCommand {
@@ -37,87 +57,110 @@ impl InsertCommand {
         table_inserts: TableInserts,
         data_manager: Arc<DataManager>,
         sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
     ) -> InsertCommand {
         InsertCommand {
             table_inserts,
             data_manager,
             sender,
+            function_context,
+            functions,
+            operators,
+            journal: None,
         }
     }
 
-    pub(crate) fn execute(&mut self) -> SystemResult<()> {
-        let table_definition = self.data_manager.table_columns(&self.table_inserts.table_id)?;
-        let all_columns = table_definition.clone();
+    /// rows are kept in the journal until its transaction is committed
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> InsertCommand {
+        self.journal = journal;
+        self
+    }
 
-        let evaluation = ExpressionEvaluation::new(self.sender.clone(), table_definition);
-        let mut rows = vec![];
-        let mut has_error = false;
-        for line in self.table_inserts.input.iter() {
-            let mut row = vec![];
-            for (idx, col) in line.iter().enumerate() {
-                let meta = ExprMetadata::new(&all_columns[idx], idx);
-                match evaluation.eval(col, Some(meta)) {
-                    Ok(v) => {
-                        if v.is_literal() {
-                            let datum = v.as_datum().unwrap();
-                            match all_columns[idx]
-                                .sql_type()
-                                .constraint()
-                                .validate(datum.to_string().as_str())
-                            {
-                                Ok(()) => row.push(v),
-                                Err(ConstraintError::OutOfRange) => {
-                                    self.sender
-                                        .send(Err(QueryError::out_of_range(
-                                            (&meta.column().sql_type()).into(),
-                                            meta.column().name(),
-                                            idx + 1,
-                                        )))
-                                        .expect("To Send Query Result to client");
-                                    has_error = true;
-                                }
-                                Err(ConstraintError::TypeMismatch(value)) => {
-                                    self.sender
-                                        .send(Err(QueryError::type_mismatch(
-                                            &value,
-                                            (&meta.column().sql_type()).into(),
-                                            &meta.column().name(),
-                                            idx + 1,
-                                        )))
-                                        .expect("To Send Query Result to client");
-                                    has_error = true;
-                                }
-                                Err(ConstraintError::ValueTooLong(len)) => {
-                                    self.sender
-                                        .send(Err(QueryError::string_length_mismatch(
-                                            (&meta.column().sql_type()).into(),
-                                            len,
-                                            meta.column().name(),
-                                            idx + 1,
-                                        )))
-                                        .expect("To Send Query Result to client");
-                                    has_error = true;
-                                }
-                            }
-                        } else {
-                            self.sender
-                                .send(Err(QueryError::feature_not_supported(
-                                    "Only expressions resulting in a literal are supported",
-                                )))
-                                .expect("To Send Query Result to Client");
-                            return Ok(());
-                        }
+    /// the key is looked up in every table that stores rows of the inserted one, errors of the storage are sent
+    /// to the client
+    fn key_exists(&self, stored_tables: &[(Id, Id)], key: &Binary) -> SystemResult<Result<bool, ()>> {
+        for table_id in stored_tables {
+            let error = match self
+                .data_manager
+                .read_row(self.journal.as_deref(), &Box::new(*table_id), key.clone())?
+            {
+                None => continue,
+                Some(Ok(Ok(_row))) => return Ok(Ok(true)),
+                Some(Ok(Err(error))) => QueryError::io_error(format!("{:?}", error)),
+                Some(Err(error)) => QueryError::io_error(error),
+            };
+            self.sender.send(Err(error)).expect("To Send Result to Client");
+            return Ok(Err(()));
+        }
+        Ok(Ok(false))
+    }
+
+    /// table that stores the record, rows of a partitioned table are routed to the partition that holds their key
+    fn route(
+        &self,
+        partitioning: &Option<RangePartitioning>,
+        bounds: &Option<(String, RangePartition)>,
+        all_columns: &[ColumnDefinition],
+        record: &[Datum],
+    ) -> Option<(Id, Id)> {
+        let key_value = |column_name: &str| {
+            all_columns
+                .iter()
+                .position(|column| column.has_name(column_name))
+                .and_then(|index| integer(&record[index]))
+        };
+        let table_name = || {
+            self.data_manager
+                .table_name(&self.table_inserts.table_id)
+                .map(|(_schema_name, table_name)| table_name)
+                .unwrap_or_default()
+        };
+        match (partitioning, bounds) {
+            (Some(partitioning), _) => {
+                match key_value(partitioning.column_name()).and_then(|value| partitioning.partition_for(value)) {
+                    Some(partition) => Some(partition.table_id()),
+                    None => {
+                        self.sender
+                            .send(Err(QueryError::check_violation(format!(
+                                "no partition of relation \"{}\" found for row",
+                                table_name()
+                            ))))
+                            .expect("To Send Result to Client");
+                        None
                     }
-                    Err(_) => return Ok(()),
                 }
             }
-            rows.push(row);
+            (None, Some((column_name, partition)))
+                if !key_value(column_name.as_str())
+                    .map(|value| partition.contains(value))
+                    .unwrap_or(false) =>
+            {
+                self.sender
+                    .send(Err(QueryError::check_violation(format!(
+                        "new row for relation \"{}\" violates partition constraint",
+                        table_name()
+                    ))))
+                    .expect("To Send Result to Client");
+                None
+            }
+            _ => Some(*self.table_inserts.table_id.as_ref()),
         }
+    }
 
-        if has_error {
-            return Ok(());
-        }
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let table_definition = self.data_manager.table_columns(&self.table_inserts.table_id)?;
+        let all_columns = table_definition.clone();
 
+        let evaluation = ExpressionEvaluation::new(
+            self.sender.clone(),
+            table_definition,
+            self.function_context.clone(),
+            self.functions.clone(),
+            self.operators.clone(),
+            self.data_manager.clone(),
+        );
         let index_columns = if self.table_inserts.column_indices.is_empty() {
             let mut index_cols = vec![];
             for (index, column_definition) in all_columns.iter().cloned().enumerate() {
@@ -159,37 +202,192 @@ impl InsertCommand {
             index_cols
         };
 
-        let mut to_write: Vec<Row> = vec![];
-        for row in rows.iter() {
-            if row.len() > all_columns.len() {
-                self.sender
-                    .send(Err(QueryError::too_many_insert_expressions()))
-                    .expect("To Send Result to Client");
-                return Ok(());
+        // values are coerced to the types of the columns they are inserted into
+        let mut rows = vec![];
+        let mut has_error = false;
+        for (row_index, line) in self.table_inserts.input.iter().enumerate() {
+            let mut row = vec![];
+            for (idx, col) in line.iter().enumerate() {
+                let column = match index_columns.get(idx) {
+                    Some((_, column)) => column,
+                    None => {
+                        self.sender
+                            .send(Err(QueryError::too_many_insert_expressions()))
+                            .expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                };
+                let meta = ExprMetadata::new(column, row_index);
+                match evaluation.eval(col, Some(meta)) {
+                    Ok(v) => {
+                        if v.is_literal() {
+                            let datum = v.as_datum().unwrap();
+                            match column_value(datum, meta.column(), row_index, self.function_context.utc_offset()) {
+                                Ok(datum) => row.push(ScalarOp::Literal(datum)),
+                                Err(error) => {
+                                    self.sender.send(Err(error)).expect("To Send Query Result to client");
+                                    has_error = true;
+                                }
+                            }
+                        } else {
+                            self.sender
+                                .send(Err(QueryError::feature_not_supported(
+                                    "Only expressions resulting in a literal are supported",
+                                )))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => return Ok(()),
+                }
             }
+            rows.push(row);
+        }
 
-            let key = self
-                .data_manager
-                .next_key_id(&self.table_inserts.table_id)
-                .to_be_bytes()
-                .to_vec();
+        if has_error {
+            return Ok(());
+        }
+
+        let partitioning = self.data_manager.range_partitioning(&self.table_inserts.table_id);
+        let partition_bounds = self.data_manager.partition_bounds(&self.table_inserts.table_id);
+        let stored_tables = match &partitioning {
+            Some(partitioning) => partitioning
+                .partitions()
+                .iter()
+                .map(RangePartition::table_id)
+                .collect::<Vec<_>>(),
+            None => vec![*self.table_inserts.table_id.as_ref()],
+        };
+        let primary_key = primary_key_columns(&all_columns);
+        let mut inserted_keys = BTreeSet::new();
 
+        let generated_columns = match generated_columns(&all_columns, &evaluation) {
+            Ok(generated_columns) => generated_columns,
+            Err(()) => return Ok(()),
+        };
+        let expr_eval = EvalScalarOp::new(
+            self.sender.as_ref(),
+            all_columns.clone(),
+            self.function_context.utc_offset(),
+        );
+        let (schema_id, _) = *self.table_inserts.table_id.as_ref();
+        let sequences = SequenceFunctions::new(&self.data_manager, self.sender.as_ref(), &self.function_context);
+        let mut to_write: Vec<Row> = vec![];
+        let mut targets = vec![];
+        for (row_index, row) in rows.iter().enumerate() {
             // TODO: The default value or NULL should be initialized for SQL types of all columns.
             let mut record = vec![Datum::from_null(); all_columns.len()];
             for (item, (index, _column_definition)) in row.iter().zip(index_columns.iter()) {
                 let datum = item.as_datum().unwrap();
                 record[*index] = datum;
             }
-            to_write.push((Binary::with_data(key), Binary::pack(&record)));
+
+            // serial columns that are not given take the next value of their sequence
+            let given = index_columns
+                .iter()
+                .take(row.len())
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>();
+            if let Some(index) = given
+                .iter()
+                .find(|index| all_columns[**index].generation_expression().is_some())
+            {
+                self.sender
+                    .send(Err(QueryError::generated_always(format!(
+                        "cannot insert a non-DEFAULT value into column \"{}\"",
+                        all_columns[*index].name()
+                    ))))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            for (index, column) in all_columns.iter().enumerate() {
+                if let (Some(sequence_name), false) = (column.serial_sequence(), given.contains(&index)) {
+                    let value = match sequences.next_value(schema_id, sequence_name)? {
+                        Ok(value) => value,
+                        Err(()) => return Ok(()),
+                    };
+                    record[index] = match column.sql_type() {
+                        SqlType::SmallInt(_) => Datum::from_i16(value as i16),
+                        SqlType::Integer(_) => Datum::from_i32(value as i32),
+                        _ => Datum::from_i64(value),
+                    };
+                }
+            }
+
+            for generated_column in generated_columns.iter() {
+                if expr_eval.eval_on_row(&mut record, generated_column, row_index).is_err() {
+                    return Ok(());
+                }
+            }
+
+            if let Some(column_name) = null_in_not_null_column(&all_columns, &record) {
+                self.sender
+                    .send(Err(QueryError::not_null_violation(column_name)))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+
+            let target = match self.route(&partitioning, &partition_bounds, &all_columns, &record) {
+                Some(target) => target,
+                None => return Ok(()),
+            };
+            let key = if primary_key.is_empty() {
+                Binary::with_data(self.data_manager.next_key_id(&Box::new(target)).to_be_bytes().to_vec())
+            } else {
+                let key = Binary::pack_key(
+                    &primary_key
+                        .iter()
+                        .map(|index| record[*index].clone())
+                        .collect::<Vec<Datum>>(),
+                );
+                let exists = !inserted_keys.insert(key.clone())
+                    || match self.key_exists(&stored_tables, &key)? {
+                        Ok(exists) => exists,
+                        Err(()) => return Ok(()),
+                    };
+                if exists {
+                    self.sender
+                        .send(Err(QueryError::unique_violation(primary_key_constraint_name(
+                            &self.data_manager,
+                            &self.table_inserts.table_id,
+                        ))))
+                        .expect("To Send Result to Client");
+                    return Ok(());
+                }
+                key
+            };
+            to_write.push((key, Binary::pack_row(&record)));
+            targets.push(target);
+        }
+
+        let records = to_write
+            .iter()
+            .map(|(_key, values)| values.unpack())
+            .collect::<Vec<_>>();
+        if let Some((table_name, constraint_name)) = foreign_key_violation(
+            &self.data_manager,
+            &self.journal,
+            &self.table_inserts.table_id,
+            &all_columns,
+            &records,
+        )? {
+            self.sender
+                .send(Err(QueryError::foreign_key_violation(table_name, constraint_name)))
+                .expect("To Send Result to Client");
+            return Ok(());
         }
 
-        match self.data_manager.write_into(&self.table_inserts.table_id, to_write) {
-            Err(error) => return Err(error),
-            Ok(size) => self
-                .sender
-                .send(Ok(QueryEvent::RecordsInserted(size)))
-                .expect("To Send Result to Client"),
+        let mut writes: BTreeMap<(Id, Id), Vec<Row>> = BTreeMap::new();
+        for (target, row) in targets.into_iter().zip(to_write) {
+            writes.entry(target).or_default().push(row);
+        }
+        let mut size = 0;
+        for (target, rows) in writes {
+            size += write_rows(&self.data_manager, &self.journal, target, rows)?;
         }
+        self.sender
+            .send(Ok(QueryEvent::RecordsInserted(size)))
+            .expect("To Send Result to Client");
 
         Ok(())
     }
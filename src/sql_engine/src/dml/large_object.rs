@@ -0,0 +1,183 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{bytea, results::QueryError, Sender};
+use representation::{Datum, ScalarType};
+use sql_model::Id;
+
+/// Postgres names the unique index on large object ids in its duplicate key error
+const LARGE_OBJECT_ID_INDEX: &str = "pg_largeobject_metadata_oid_index";
+
+/// server side large object functions. Objects are written and read in
+/// chunks with `lo_put` and `lo_get`, so neither the client nor the server
+/// has to hold a whole object in memory
+pub(crate) struct LargeObjectFunctions<'lo> {
+    data_manager: &'lo DataManager,
+    sender: &'lo dyn Sender,
+}
+
+impl<'lo> LargeObjectFunctions<'lo> {
+    pub(crate) fn new(data_manager: &'lo DataManager, sender: &'lo dyn Sender) -> LargeObjectFunctions<'lo> {
+        LargeObjectFunctions { data_manager, sender }
+    }
+
+    pub(crate) fn is_large_object_function(name: &str) -> bool {
+        matches!(
+            name.trim_start_matches("pg_catalog."),
+            "lo_create" | "lo_from_bytea" | "lo_put" | "lo_get" | "lo_unlink"
+        )
+    }
+
+    pub(crate) fn call(&self, name: &str, args: &[Datum]) -> SystemResult<Result<Datum<'static>, ()>> {
+        let function = name.trim_start_matches("pg_catalog.");
+        match (function, args) {
+            ("lo_create", [id]) => match Self::as_unsigned(id) {
+                Some(id) => Ok(self.create(id)?.map(Datum::from_u64)),
+                None => Ok(self.function_does_not_exist(name, args)),
+            },
+            ("lo_from_bytea", [id, data]) => match (Self::as_unsigned(id), data.is_string() || data.is_bytes()) {
+                (Some(id), true) => {
+                    let data = match self.decode_bytea(&data.to_string()) {
+                        Ok(data) => data,
+                        Err(()) => return Ok(Err(())),
+                    };
+                    let id = match self.create(id)? {
+                        Ok(id) => id,
+                        Err(()) => return Ok(Err(())),
+                    };
+                    self.data_manager.write_large_object(id, 0, &data)?;
+                    Ok(Ok(Datum::from_u64(id)))
+                }
+                _ => Ok(self.function_does_not_exist(name, args)),
+            },
+            ("lo_put", [id, offset, data]) => {
+                match (
+                    Self::as_unsigned(id),
+                    Self::as_unsigned(offset),
+                    data.is_string() || data.is_bytes(),
+                ) {
+                    (Some(id), Some(offset), true) => {
+                        let data = match self.decode_bytea(&data.to_string()) {
+                            Ok(data) => data,
+                            Err(()) => return Ok(Err(())),
+                        };
+                        match self.data_manager.write_large_object(id, offset, &data)? {
+                            Some(_written) => Ok(Ok(Datum::from_string(String::new()))),
+                            None => Ok(self.does_not_exist(id)),
+                        }
+                    }
+                    _ => Ok(self.function_does_not_exist(name, args)),
+                }
+            }
+            ("lo_get", [id]) => match Self::as_unsigned(id) {
+                Some(id) => self.get(id, 0, None),
+                None => Ok(self.function_does_not_exist(name, args)),
+            },
+            ("lo_get", [id, offset, length]) => {
+                match (
+                    Self::as_unsigned(id),
+                    Self::as_unsigned(offset),
+                    Self::as_unsigned(length),
+                ) {
+                    (Some(id), Some(offset), Some(length)) => self.get(id, offset, Some(length)),
+                    _ => Ok(self.function_does_not_exist(name, args)),
+                }
+            }
+            ("lo_unlink", [id]) => match Self::as_unsigned(id) {
+                Some(id) => {
+                    if self.data_manager.unlink_large_object(id)? {
+                        Ok(Ok(Datum::from_i32(1)))
+                    } else {
+                        Ok(self.does_not_exist(id))
+                    }
+                }
+                None => Ok(self.function_does_not_exist(name, args)),
+            },
+            _ => Ok(self.function_does_not_exist(name, args)),
+        }
+    }
+
+    /// `0` asks for the next free id
+    fn create(&self, id: Id) -> SystemResult<Result<Id, ()>> {
+        let requested = if id == 0 { None } else { Some(id) };
+        match self.data_manager.create_large_object(requested)? {
+            Some(id) => Ok(Ok(id)),
+            None => {
+                self.sender
+                    .send(Err(QueryError::unique_violation(LARGE_OBJECT_ID_INDEX)))
+                    .expect("To Send Query Result to Client");
+                Ok(Err(()))
+            }
+        }
+    }
+
+    fn get(&self, id: Id, offset: u64, length: Option<u64>) -> SystemResult<Result<Datum<'static>, ()>> {
+        match self.data_manager.read_large_object(id, offset, length)? {
+            Some(data) => Ok(Ok(Datum::from_string(bytea::encode_hex(&data)))),
+            None => Ok(self.does_not_exist(id)),
+        }
+    }
+
+    fn as_unsigned(datum: &Datum) -> Option<u64> {
+        match datum.scalar_type() {
+            Some(ScalarType::Int16) if datum.as_i16() >= 0 => Some(datum.as_i16() as u64),
+            Some(ScalarType::Int32) if datum.as_i32() >= 0 => Some(datum.as_i32() as u64),
+            Some(ScalarType::Int64) if datum.as_i64() >= 0 => Some(datum.as_i64() as u64),
+            Some(ScalarType::UInt64) => Some(datum.as_u64()),
+            _ => None,
+        }
+    }
+
+    /// bytea text input: `\x` followed by pairs of hex digits, otherwise the bytes of the string
+    fn decode_bytea(&self, text: &str) -> Result<Vec<u8>, ()> {
+        bytea::decode(text).map_err(|message| {
+            self.sender
+                .send(Err(QueryError::invalid_text_representation(message)))
+                .expect("To Send Query Result to Client");
+        })
+    }
+
+    fn does_not_exist(&self, id: Id) -> Result<Datum<'static>, ()> {
+        self.sender
+            .send(Err(QueryError::large_object_does_not_exist(id)))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+
+    fn function_does_not_exist(&self, name: &str, args: &[Datum]) -> Result<Datum<'static>, ()> {
+        let arg_types = args
+            .iter()
+            .map(|arg| match arg.scalar_type() {
+                Some(ScalarType::String) | Some(ScalarType::Bytes) => "bytea",
+                Some(ScalarType::Int16) | Some(ScalarType::Int32) => "integer",
+                Some(ScalarType::Int64) | Some(ScalarType::UInt64) => "bigint",
+                Some(ScalarType::Float32) | Some(ScalarType::Float64) => "double precision",
+                Some(ScalarType::Boolean) => "boolean",
+                Some(ScalarType::Timestamp) => "timestamp with time zone",
+                Some(ScalarType::Interval) => "interval",
+                None => "unknown",
+            })
+            .collect::<Vec<_>>();
+        self.sender
+            .send(Err(QueryError::function_does_not_exist(format!(
+                "{}({})",
+                name,
+                arg_types.join(", ")
+            ))))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
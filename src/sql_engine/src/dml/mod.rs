@@ -12,7 +12,406 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{cmp::Ordering, collections::BTreeSet, convert::TryFrom, sync::Arc};
+
+use data_manager::{ColumnDefinition, DataManager, Journal, Key, ReadCursor, Row};
+use kernel::SystemResult;
+use protocol::{
+    array, bytea,
+    clock::parse_timestamp_with_time_zone,
+    interval::Interval,
+    json,
+    results::{Projection, QueryError, QueryEvent},
+    Sender,
+};
+use query_planner::{plan::SortKey, TableId};
+use representation::{Binary, Datum, ScalarType};
+use sql_model::{
+    sql_types::{ConstraintError, SqlType},
+    Id,
+};
+use sqlparser::{
+    ast::{Assignment, Ident},
+    parser::Parser,
+    tokenizer::Tokenizer,
+};
+
+use crate::{
+    aggregate::integer,
+    operator::cast,
+    query::{expr::ExpressionEvaluation, scalar::ScalarOp},
+    PreparedStatementDialect,
+};
+
+pub(crate) mod catalog_export;
+pub(crate) mod change_capture;
+pub(crate) mod copy;
 pub(crate) mod delete;
+pub(crate) mod index_advice;
 pub(crate) mod insert;
+pub(crate) mod large_object;
 pub(crate) mod select;
+pub(crate) mod select_values;
+pub(crate) mod sequence;
 pub(crate) mod update;
+pub(crate) mod window;
+
+/// rows of the table as the statement sees them, rows written by its transaction replace committed ones
+pub(crate) fn scan_table(
+    data_manager: &DataManager,
+    journal: &Option<Arc<Journal>>,
+    table_id: (Id, Id),
+) -> SystemResult<ReadCursor> {
+    match journal {
+        Some(journal) => data_manager.journaled_scan(journal, &Box::new(table_id)),
+        None => data_manager.full_scan(&Box::new(table_id)),
+    }
+}
+
+/// rows are kept in the journal of the transaction until it is committed, they are written right away without it
+pub(crate) fn write_rows(
+    data_manager: &DataManager,
+    journal: &Option<Arc<Journal>>,
+    table_id: (Id, Id),
+    rows: Vec<Row>,
+) -> SystemResult<usize> {
+    match journal {
+        Some(journal) => data_manager.journal_write(journal, &Box::new(table_id), rows),
+        None => data_manager.write_into(&Box::new(table_id), rows),
+    }
+}
+
+/// rows are kept deleted in the journal of the transaction until it is committed, they are deleted right away
+/// without it
+pub(crate) fn delete_rows(
+    data_manager: &DataManager,
+    journal: &Option<Arc<Journal>>,
+    table_id: (Id, Id),
+    keys: Vec<Key>,
+) -> SystemResult<usize> {
+    match journal {
+        Some(journal) => data_manager.journal_delete(journal, &Box::new(table_id), keys),
+        None => data_manager.delete_from(&Box::new(table_id), keys),
+    }
+}
+
+/// rows that are already selected are sent one by one after their description
+pub(crate) fn send_projection(sender: &dyn Sender, (description, records): Projection) {
+    let selected = records.len();
+    sender
+        .send(Ok(QueryEvent::RowDescription(description)))
+        .expect("To Send Query Result to Client");
+    for record in records {
+        sender
+            .send(Ok(QueryEvent::DataRow(record)))
+            .expect("To Send Query Result to Client");
+    }
+    sender
+        .send(Ok(QueryEvent::RecordsSelected(selected)))
+        .expect("To Send Query Result to Client");
+}
+
+/// assignments of generation expressions to generated columns, they are evaluated after other values of a row are known
+pub(crate) fn generated_columns(
+    all_columns: &[ColumnDefinition],
+    evaluation: &ExpressionEvaluation,
+) -> Result<Vec<ScalarOp>, ()> {
+    let mut assignments = vec![];
+    for column in all_columns {
+        if let Some(expression) = column.generation_expression() {
+            let tokens = Tokenizer::new(&PreparedStatementDialect {}, expression)
+                .tokenize()
+                .expect("generation expression is parsed when table is created");
+            let value = Parser::new(tokens)
+                .parse_expr()
+                .expect("generation expression is parsed when table is created");
+            assignments.push(evaluation.eval_assignment(&Assignment {
+                id: Ident::new(column.name()),
+                value,
+            })?);
+        }
+    }
+    Ok(assignments)
+}
+
+/// NULLs are ordered by `nulls_first` regardless of direction of the sort key
+pub(crate) fn compare_rows(left: &[Datum], right: &[Datum], sort_keys: &[(usize, &SortKey)]) -> Ordering {
+    for (index, sort_key) in sort_keys {
+        let ordering = match (left[*index].is_null(), right[*index].is_null()) {
+            (true, true) => Ordering::Equal,
+            (true, false) if sort_key.nulls_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if sort_key.nulls_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) if sort_key.ascending => left[*index].cmp(&right[*index]),
+            (false, false) => right[*index].cmp(&left[*index]),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// enum columns accept only labels of their type
+pub(crate) fn invalid_enum_label(column: &ColumnDefinition, value: &str) -> Option<QueryError> {
+    match column.enum_type() {
+        Some(enum_type) if enum_type.position(value).is_none() => Some(QueryError::invalid_text_representation(
+            format!("invalid input value for enum {}: \"{}\"", enum_type.name(), value),
+        )),
+        _ => None,
+    }
+}
+
+/// datum of the column type that rows store for an evaluated value, integers are range checked as numbers and keep
+/// the width of the column, other values are validated by their text, errors report the `row_index`
+pub(crate) fn column_value<'d>(
+    datum: Datum<'d>,
+    column: &ColumnDefinition,
+    row_index: usize,
+    utc_offset: i32,
+) -> Result<Datum<'d>, QueryError> {
+    let sql_type = column.sql_type();
+    let out_of_range = || QueryError::out_of_range((&sql_type).into(), column.name(), row_index + 1);
+    if datum.is_null() {
+        return Ok(datum);
+    }
+    match (sql_type, datum.scalar_type()) {
+        (SqlType::SmallInt(_), _) | (SqlType::Integer(_), _) | (SqlType::BigInt(_), _) if integer(&datum).is_some() => {
+            return integer_value(integer(&datum), sql_type).ok_or_else(out_of_range)
+        }
+        (SqlType::Bool, Some(ScalarType::Boolean))
+        | (SqlType::Interval, Some(ScalarType::Interval))
+        | (SqlType::Bytea, Some(ScalarType::Bytes)) => return Ok(datum),
+        _ => {}
+    }
+    let text = datum.to_string();
+    match sql_type.constraint().validate(text.as_str()) {
+        Ok(()) => match sql_type {
+            SqlType::SmallInt(_) | SqlType::Integer(_) | SqlType::BigInt(_) => {
+                integer_value(text.parse().ok(), sql_type).ok_or_else(out_of_range)
+            }
+            SqlType::Bool => Ok(cast(datum, ScalarType::Boolean)),
+            SqlType::TimestampWithTimeZone => Ok(Datum::from_timestamp(
+                parse_timestamp_with_time_zone(text.as_str(), utc_offset).expect("validated timestamp with time zone"),
+            )),
+            SqlType::Interval => Ok(Datum::from_interval(
+                Interval::parse(text.as_str()).expect("validated interval"),
+            )),
+            SqlType::Bytea => Ok(Datum::from_bytes(
+                bytea::decode(text.as_str()).expect("validated bytea"),
+            )),
+            SqlType::Jsonb => Ok(Datum::from_string(
+                json::parse(text.as_str())
+                    .expect("validated json")
+                    .normalized()
+                    .to_string(),
+            )),
+            sql_type if sql_type.element_type().is_some() => Ok(Datum::from_string(array::render(
+                &array::parse(text.as_str()).expect("validated array"),
+            ))),
+            // trailing spaces are not kept as the serializers of the types trim them
+            SqlType::Char(_) | SqlType::VarChar(_) => Ok(Datum::from_string(text.trim_end().to_owned())),
+            _ => match invalid_enum_label(column, text.as_str()) {
+                Some(error) => Err(error),
+                None if datum.scalar_type() == Some(ScalarType::String) => Ok(datum),
+                None => Ok(Datum::from_string(text)),
+            },
+        },
+        Err(ConstraintError::OutOfRange) => Err(out_of_range()),
+        Err(ConstraintError::TypeMismatch(value)) => Err(QueryError::type_mismatch(
+            &value,
+            (&sql_type).into(),
+            &column.name(),
+            row_index + 1,
+        )),
+        Err(ConstraintError::ValueTooLong(len)) => Err(QueryError::string_length_mismatch(
+            (&sql_type).into(),
+            len,
+            column.name(),
+            row_index + 1,
+        )),
+    }
+}
+
+/// integer of the width of an integer column that is within the column bounds
+fn integer_value(value: Option<i64>, sql_type: SqlType) -> Option<Datum<'static>> {
+    let value = value?;
+    match sql_type {
+        SqlType::SmallInt(min) => i16::try_from(value)
+            .ok()
+            .filter(|value| min <= *value)
+            .map(Datum::from_i16),
+        SqlType::Integer(min) => i32::try_from(value)
+            .ok()
+            .filter(|value| min <= *value)
+            .map(Datum::from_i32),
+        SqlType::BigInt(min) => Some(value).filter(|value| min <= *value).map(Datum::from_i64),
+        _ => None,
+    }
+}
+
+/// values of enum columns are replaced by positions of their labels, rows are ordered by them as the type orders its values
+pub(crate) fn enum_positions<'r>(row: &[Datum<'r>], columns: &[ColumnDefinition]) -> Vec<Datum<'r>> {
+    row.iter()
+        .enumerate()
+        .map(|(index, datum)| {
+            match (
+                columns.get(index).and_then(ColumnDefinition::enum_type),
+                datum.is_null(),
+            ) {
+                (Some(enum_type), false) => enum_type
+                    .position(datum.to_string().as_str())
+                    .map(|position| Datum::from_i32(position as i32))
+                    .unwrap_or_else(|| datum.clone()),
+                _ => datum.clone(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn primary_key_columns(columns: &[ColumnDefinition]) -> Vec<usize> {
+    columns
+        .iter()
+        .enumerate()
+        .filter(|(_index, column)| column.is_primary_key())
+        .map(|(index, _column)| index)
+        .collect()
+}
+
+/// name of the first NOT NULL column that would store NULL from the record
+pub(crate) fn null_in_not_null_column(columns: &[ColumnDefinition], record: &[Datum]) -> Option<String> {
+    columns
+        .iter()
+        .zip(record.iter())
+        .find(|(column, datum)| !column.is_nullable() && datum.is_null())
+        .map(|(column, _datum)| column.name())
+}
+
+pub(crate) fn primary_key_constraint_name(data_manager: &DataManager, table_id: &TableId) -> String {
+    match data_manager.table_name(table_id) {
+        Some((_schema_name, table_name)) => format!("{}_pkey", table_name),
+        None => "pkey".to_owned(),
+    }
+}
+
+pub(crate) fn foreign_key_constraint_name(table_name: &str, column_name: &str) -> String {
+    format!("{}_{}_fkey", table_name, column_name)
+}
+
+/// column of another table that references primary key of a table
+pub(crate) struct ReferencingColumn {
+    pub(crate) table_id: (Id, Id),
+    pub(crate) table_name: String,
+    pub(crate) index: usize,
+    pub(crate) column: ColumnDefinition,
+}
+
+impl ReferencingColumn {
+    pub(crate) fn constraint_name(&self) -> String {
+        foreign_key_constraint_name(&self.table_name, &self.column.name())
+    }
+}
+
+pub(crate) fn referencing_columns(
+    data_manager: &DataManager,
+    table_id: &(Id, Id),
+) -> SystemResult<Vec<ReferencingColumn>> {
+    let (schema_name, table_name) = match data_manager.table_name(&Box::new(*table_id)) {
+        Some(full_name) => full_name,
+        None => return Ok(vec![]),
+    };
+    let mut referencing = vec![];
+    for other_table_id in data_manager.table_ids() {
+        let other_table_name = match data_manager.table_name(&Box::new(other_table_id)) {
+            Some((_schema_name, other_table_name)) => other_table_name,
+            None => continue,
+        };
+        for (index, column) in data_manager
+            .table_columns(&Box::new(other_table_id))?
+            .into_iter()
+            .enumerate()
+        {
+            let references_table = column
+                .referenced_column()
+                .map(|reference| reference.schema_name() == schema_name && reference.table_name() == table_name)
+                .unwrap_or(false);
+            if references_table {
+                referencing.push(ReferencingColumn {
+                    table_id: other_table_id,
+                    table_name: other_table_name.clone(),
+                    index,
+                    column,
+                });
+            }
+        }
+    }
+    Ok(referencing)
+}
+
+/// primary key value of a row that other tables refer to
+pub(crate) fn referenced_value(key: &Binary) -> String {
+    key.unpack().first().map(Datum::to_string).unwrap_or_default()
+}
+
+/// table and constraint names of the first foreign key constraint that the records violate
+pub(crate) fn foreign_key_violation(
+    data_manager: &DataManager,
+    journal: &Option<Arc<Journal>>,
+    table_id: &TableId,
+    columns: &[ColumnDefinition],
+    records: &[Vec<Datum>],
+) -> SystemResult<Option<(String, String)>> {
+    let table_name = match data_manager.table_name(table_id) {
+        Some((_schema_name, table_name)) => table_name,
+        None => return Ok(None),
+    };
+    for (index, column) in columns.iter().enumerate() {
+        let reference = match column.referenced_column() {
+            Some(reference) => reference,
+            None => continue,
+        };
+        let existing_values =
+            match data_manager.table_exists_in(journal.as_deref(), &reference.schema_name(), &reference.table_name()) {
+                Some((schema_id, Some(referenced_table_id))) => {
+                    scan_table(data_manager, journal, (schema_id, referenced_table_id))?
+                        .map(Result::unwrap)
+                        .map(Result::unwrap)
+                        .map(|(key, _values)| referenced_value(&key))
+                        .collect::<BTreeSet<String>>()
+                }
+                _ => BTreeSet::new(),
+            };
+        let violated = records
+            .iter()
+            .map(|record| &record[index])
+            .any(|datum| !datum.is_null() && !existing_values.contains(&datum.to_string()));
+        if violated {
+            let constraint_name = foreign_key_constraint_name(&table_name, &column.name());
+            return Ok(Some((table_name, constraint_name)));
+        }
+    }
+    Ok(None)
+}
+
+/// first column of another table that still holds any of the referenced values
+pub(crate) fn referencing_row(
+    data_manager: &DataManager,
+    journal: &Option<Arc<Journal>>,
+    table_id: &(Id, Id),
+    values: &BTreeSet<String>,
+) -> SystemResult<Option<ReferencingColumn>> {
+    for referencing in referencing_columns(data_manager, table_id)? {
+        let found = scan_table(data_manager, journal, referencing.table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .any(|(_key, values_of_row)| {
+                !values_of_row.is_null(referencing.index)
+                    && values.contains(&values_of_row.unpack()[referencing.index].to_string())
+            });
+        if found {
+            return Ok(Some(referencing));
+        }
+    }
+    Ok(None)
+}
@@ -12,20 +12,152 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{cmp::Ordering, ops::Bound, sync::Arc};
 
-use data_manager::DataManager;
+use data_manager::{ColumnDefinition, DataManager, Journal, KeyRange, SCAN_BATCH_SIZE};
 use kernel::{SystemError, SystemResult};
 use protocol::{
-    results::{Description, QueryError, QueryEvent},
+    clock::render_timestamp_with_time_zone,
+    pgsql_types::PostgreSqlType,
+    results::{ColumnOrigins, Description, QueryError, QueryEvent},
+    session::{FunctionContext, ResultLimits},
     Sender,
 };
-use query_planner::plan::SelectInput;
+use query_planner::{
+    plan::{ColumnRange, RowLimit, ScanMethod, SelectInput, SelectedColumn, SortKey},
+    TableId,
+};
+use representation::{Binary, Datum, ScalarType, KEY_FORMAT_V1};
+use sql_model::sql_types::SqlType;
+use sqlparser::ast::{Expr, Function, Value};
+
+use crate::{
+    activity::SessionActivity,
+    aggregate::{integer, AggregateFunction, AggregateRegistry},
+    catalog_queries::table_oid,
+    dml::{compare_rows, enum_positions, select_values::pg_type, window::Window},
+    function::FunctionRegistry,
+    operator::OperatorTable,
+    query::{
+        expr::{EvalScalarOp, ExpressionEvaluation},
+        scalar::ScalarOp,
+    },
+    MAX_RESULT_ROWS, MAX_RESULT_SIZE,
+};
+
+/// selected column resolved to the index of a table column, of a window function or of an aggregate, or to an
+/// expression over table columns and its type
+enum Output {
+    Column(usize),
+    Window(usize),
+    Aggregate(usize),
+    Expr(ScalarOp, Option<ScalarType>),
+}
+
+/// aggregate called without `OVER`, all selected rows are aggregated into a single row
+struct Aggregate {
+    function: Arc<dyn AggregateFunction>,
+    /// `None` for `*`
+    argument: Option<usize>,
+    /// condition of `FILTER (WHERE ...)` compiled over table columns
+    filter: Option<ScalarOp>,
+}
+
+impl Aggregate {
+    fn result_type(&self, columns: &[ColumnDefinition]) -> PostgreSqlType {
+        self.function
+            .return_type(self.argument.map(|index| columns[index].sql_type()).as_ref())
+    }
+
+    /// NULLs are skipped unless the aggregate accumulates them, `*` argument counts every row
+    fn evaluate(&self, rows: &[Vec<Datum>]) -> Result<Datum<'static>, QueryError> {
+        let mut state = self.function.init();
+        for row in rows {
+            if let Some(filter) = &self.filter {
+                if EvalScalarOp::evaluate(row, filter)? != Datum::from_bool(true) {
+                    continue;
+                }
+            }
+            match self.argument {
+                Some(column) if row[column].is_null() && !self.function.accumulates_nulls() => {}
+                Some(column) => self.function.accumulate(&mut state, &row[column]),
+                None => self.function.accumulate(&mut state, &Datum::from_bool(true)),
+            }
+        }
+        Ok(self.function.finalize(state))
+    }
+}
+
+/// selected columns with window functions and aggregates that they are computed by
+type Resolved<'s> = (Vec<(String, Output)>, Vec<Window<'s>>, Vec<Aggregate>);
+
+/// rows are sent to the client as they are produced, the row description is sent right before the first of
+/// them so that errors of the first rows are not preceded by it
+struct ResultRows<'s> {
+    sender: &'s dyn Sender,
+    description: Option<Description>,
+    max_rows: usize,
+    max_size: usize,
+    size: usize,
+    rows_sent: usize,
+    truncated_by: Option<String>,
+}
+
+impl<'s> ResultRows<'s> {
+    fn describe(&mut self) {
+        if let Some(description) = self.description.take() {
+            self.sender
+                .send(Ok(QueryEvent::RowDescription(description)))
+                .expect("To Send Query Result to Client");
+        }
+    }
+
+    /// `false` when the result is truncated by limits of the session and no more rows are sent
+    fn send(&mut self, record: Vec<String>) -> bool {
+        self.describe();
+        if self.max_rows > 0 && self.rows_sent == self.max_rows {
+            self.truncated_by = Some(format!("{} of {}", MAX_RESULT_ROWS, self.max_rows));
+            return false;
+        }
+        self.size += record.iter().map(String::len).sum::<usize>();
+        if self.max_size > 0 && self.size > self.max_size {
+            self.truncated_by = Some(format!("{} of {} bytes", MAX_RESULT_SIZE, self.max_size));
+            return false;
+        }
+        self.sender
+            .send(Ok(QueryEvent::DataRow(record)))
+            .expect("To Send Query Result to Client");
+        self.rows_sent += 1;
+        true
+    }
+
+    fn finish(mut self) {
+        self.describe();
+        self.sender
+            .send(Ok(QueryEvent::RecordsSelected(self.rows_sent)))
+            .expect("To Send Query Result to Client");
+        if let Some(limit) = self.truncated_by {
+            self.sender
+                .send(Ok(QueryEvent::Notice(format!(
+                    "result is truncated to {} rows by {}",
+                    self.rows_sent, limit
+                ))))
+                .expect("To Send Query Result to Client");
+        }
+    }
+}
 
 pub(crate) struct SelectCommand {
     select_input: SelectInput,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    aggregates: Arc<AggregateRegistry>,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    function_context: FunctionContext,
+    activity: Arc<SessionActivity>,
+    result_limits: ResultLimits,
+    journal: Option<Arc<Journal>>,
 }
 
 impl SelectCommand {
@@ -33,111 +165,797 @@ impl SelectCommand {
         select_input: SelectInput,
         data_manager: Arc<DataManager>,
         sender: Arc<dyn Sender>,
+        aggregates: Arc<AggregateRegistry>,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
+        function_context: FunctionContext,
+        activity: Arc<SessionActivity>,
     ) -> SelectCommand {
         SelectCommand {
             select_input,
             data_manager,
             sender,
+            aggregates,
+            functions,
+            operators,
+            function_context,
+            activity,
+            result_limits: ResultLimits::default(),
+            journal: None,
         }
     }
 
-    pub(crate) fn describe(&mut self) -> SystemResult<Description> {
-        let all_columns = self.data_manager.table_columns(&self.select_input.table_id)?;
-        let mut column_definitions = vec![];
+    /// rows written by the transaction of the journal are selected instead of committed ones
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> SelectCommand {
+        self.journal = journal;
+        self
+    }
+
+    /// rows after the limits are not rendered and sent, the client is notified that the result is truncated
+    pub(crate) fn with_result_limits(mut self, result_limits: ResultLimits) -> SelectCommand {
+        self.result_limits = result_limits;
+        self
+    }
+
+    fn column_index(&self, all_columns: &[ColumnDefinition], column_name: &str) -> Option<usize> {
+        let index = all_columns
+            .iter()
+            .position(|column_definition| column_definition.has_name(column_name));
+        if index.is_none() {
+            self.sender
+                .send(Err(QueryError::column_does_not_exist(column_name)))
+                .expect("To Send Result to Client");
+        }
+        index
+    }
+
+    fn sort_keys<'s>(
+        &self,
+        all_columns: &[ColumnDefinition],
+        order_by: &'s [SortKey],
+    ) -> Option<Vec<(usize, &'s SortKey)>> {
+        let mut sort_keys = vec![];
+        let mut has_error = false;
+        for sort_key in order_by {
+            match self.column_index(all_columns, &sort_key.column_name) {
+                Some(index) => sort_keys.push((index, sort_key)),
+                None => has_error = true,
+            }
+        }
+        if has_error {
+            None
+        } else {
+            Some(sort_keys)
+        }
+    }
+
+    /// condition of `WHERE` or of `FILTER (WHERE ...)` over table columns, it has to be boolean
+    fn filter(&self, all_columns: &[ColumnDefinition], filter: &Expr, clause: &str) -> Option<ScalarOp> {
+        let evaluation = ExpressionEvaluation::new(
+            self.sender.clone(),
+            all_columns.to_vec(),
+            self.function_context.clone(),
+            self.functions.clone(),
+            self.operators.clone(),
+            self.data_manager.clone(),
+        );
+        evaluation.eval_condition(filter, clause).ok()
+    }
+
+    /// sends errors for every column that does not exist
+    fn resolve(&self, all_columns: &[ColumnDefinition]) -> Option<Resolved<'_>> {
+        let mut outputs = vec![];
+        let mut windows = vec![];
+        let mut aggregates = vec![];
         let mut has_error = false;
-        for column_name in &self.select_input.selected_columns {
-            let mut found = None;
-            for column_definition in &all_columns {
-                if column_definition.has_name(&column_name) {
-                    found = Some(column_definition);
-                    break;
+        for selected_column in self.select_input.selected_columns.iter() {
+            match selected_column {
+                SelectedColumn::Column(column_name) => match self.column_index(all_columns, column_name) {
+                    Some(index) => outputs.push((all_columns[index].name(), Output::Column(index))),
+                    None => has_error = true,
+                },
+                SelectedColumn::Window(name, function) => {
+                    let argument = match &function.argument {
+                        Some(column_name) => match self.column_index(all_columns, column_name) {
+                            Some(index) => Some(index),
+                            None => {
+                                has_error = true;
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    let mut partition_by = vec![];
+                    for column_name in function.partition_by.iter() {
+                        match self.column_index(all_columns, column_name) {
+                            Some(index) => partition_by.push(index),
+                            None => has_error = true,
+                        }
+                    }
+                    let order_by = match self.sort_keys(all_columns, &function.order_by) {
+                        Some(order_by) => order_by,
+                        None => {
+                            has_error = true;
+                            continue;
+                        }
+                    };
+                    let aggregate = if function.function == "row_number" {
+                        None
+                    } else {
+                        match self.with_parameter(
+                            &function.function,
+                            self.aggregates.lookup(&function.function),
+                            all_columns,
+                            argument,
+                            function.parameter.as_ref(),
+                        ) {
+                            Some(aggregate) => Some(aggregate),
+                            None => {
+                                has_error = true;
+                                continue;
+                            }
+                        }
+                    };
+                    let filter = match &function.filter {
+                        Some(filter) => match self.filter(all_columns, filter, "FILTER") {
+                            Some(filter) => Some(filter),
+                            None => {
+                                has_error = true;
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    outputs.push((name.clone(), Output::Window(windows.len())));
+                    windows.push(Window::new(
+                        function,
+                        aggregate,
+                        argument,
+                        filter,
+                        partition_by,
+                        order_by,
+                    ));
+                }
+                SelectedColumn::Expr(name, Expr::Function(function))
+                    if function.over.is_none() && self.aggregates.lookup(&function.name.to_string()).is_some() =>
+                {
+                    match self.aggregate(all_columns, function) {
+                        Some(aggregate) => {
+                            outputs.push((name.clone(), Output::Aggregate(aggregates.len())));
+                            aggregates.push(aggregate);
+                        }
+                        None => has_error = true,
+                    }
+                }
+                SelectedColumn::Expr(_name, expr) if self.calls_aggregate(expr) => {
+                    self.sender
+                        .send(Err(QueryError::feature_not_supported(
+                            "aggregates inside of expressions",
+                        )))
+                        .expect("To Send Result to Client");
+                    has_error = true;
+                }
+                SelectedColumn::Expr(name, expr) => {
+                    let evaluation = ExpressionEvaluation::new(
+                        self.sender.clone(),
+                        all_columns.to_vec(),
+                        self.function_context.clone(),
+                        self.functions.clone(),
+                        self.operators.clone(),
+                        self.data_manager.clone(),
+                    );
+                    match evaluation.eval_for_rows(expr) {
+                        Ok(value) => {
+                            let ty = match value.as_datum() {
+                                Some(datum) => datum.scalar_type(),
+                                None => Some(value.scalar_type()),
+                            };
+                            outputs.push((name.clone(), Output::Expr(value, ty)))
+                        }
+                        Err(()) => has_error = true,
+                    }
                 }
             }
+        }
+        if has_error || (!aggregates.is_empty() && !self.is_aggregated(all_columns, &outputs)) {
+            None
+        } else {
+            Some((outputs, windows, aggregates))
+        }
+    }
 
-            if let Some(column_definition) = found {
-                column_definitions.push(column_definition);
-            } else {
+    /// aggregate with its second argument given as a constant, sends an error when there is no such aggregate
+    fn with_parameter(
+        &self,
+        name: &str,
+        aggregate: Option<Arc<dyn AggregateFunction>>,
+        all_columns: &[ColumnDefinition],
+        argument: Option<usize>,
+        parameter: Option<&Value>,
+    ) -> Option<Arc<dyn AggregateFunction>> {
+        let aggregate = match parameter {
+            Some(parameter) => aggregate.and_then(|aggregate| aggregate.with_parameter(&parameter_datum(parameter))),
+            None => aggregate.filter(|aggregate| !aggregate.requires_parameter()),
+        };
+        if aggregate.is_none() {
+            let argument_type = argument
+                .map(|index| output_type(&Output::Column(index), all_columns, &[], &[]).to_string())
+                .unwrap_or_else(|| "*".to_owned());
+            let parameter_type = match parameter {
+                Some(Value::Number(_)) => ", numeric",
+                Some(_) => ", unknown",
+                None => "",
+            };
+            self.sender
+                .send(Err(QueryError::function_does_not_exist(format!(
+                    "{}({}{})",
+                    name, argument_type, parameter_type
+                ))))
+                .expect("To Send Result to Client");
+        }
+        aggregate
+    }
+
+    /// aggregates are only computed when they are selected on their own, not as operands of other expressions
+    fn calls_aggregate(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Function(function) => {
+                (function.over.is_none() && self.aggregates.lookup(&function.name.to_string()).is_some())
+                    || function.args.iter().any(|arg| self.calls_aggregate(arg))
+            }
+            Expr::BinaryOp { left, right, .. } => self.calls_aggregate(left) || self.calls_aggregate(right),
+            Expr::UnaryOp { expr, .. } | Expr::Nested(expr) | Expr::Cast { expr, .. } => self.calls_aggregate(expr),
+            _ => false,
+        }
+    }
+
+    /// arguments of an aggregate called without `OVER` are the ones that window aggregates take: `*` or a column
+    /// followed by a constant parameter and the condition of `FILTER (WHERE ...)`
+    fn aggregate(&self, all_columns: &[ColumnDefinition], function: &Function) -> Option<Aggregate> {
+        let name = function.name.to_string().to_lowercase();
+        let (arguments, filter) = match function.args.split_last() {
+            Some((Expr::Function(filter), arguments)) if filter.name.to_string() == "aggregate_filter" => {
+                (arguments, filter.args.first())
+            }
+            _ => (function.args.as_slice(), None),
+        };
+        let (argument, parameter) = match arguments {
+            [Expr::Wildcard] if name == "count" => (None, None),
+            [Expr::Identifier(column)] => (Some(self.column_index(all_columns, &column.value)?), None),
+            [Expr::Identifier(column), Expr::Value(parameter)] => {
+                (Some(self.column_index(all_columns, &column.value)?), Some(parameter))
+            }
+            _ => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(format!(
+                        "aggregate {}",
+                        function
+                    ))))
+                    .expect("To Send Result to Client");
+                return None;
+            }
+        };
+        if let (Some(Value::Number(number)), "approx_percentile") = (parameter, name.as_str()) {
+            if !(0.0..=1.0).contains(&number.to_string().parse::<f64>().expect("numeric literal")) {
                 self.sender
-                    .send(Err(QueryError::column_does_not_exist(column_name)))
+                    .send(Err(QueryError::numeric_value_out_of_range(format!(
+                        "percentile value {} is not between 0 and 1",
+                        number
+                    ))))
                     .expect("To Send Result to Client");
-                has_error = true;
+                return None;
             }
         }
+        let function = self.with_parameter(&name, self.aggregates.lookup(&name), all_columns, argument, parameter)?;
+        let filter = match filter {
+            Some(filter) => Some(self.filter(all_columns, filter, "FILTER")?),
+            None => None,
+        };
+        Some(Aggregate {
+            function,
+            argument,
+            filter,
+        })
+    }
 
-        if has_error {
-            return Err(SystemError::runtime_check_failure(&"Column Does Not Exist"));
+    /// rows are aggregated into one, so other selected columns and sort keys can't read columns of the table
+    fn is_aggregated(&self, all_columns: &[ColumnDefinition], outputs: &[(String, Output)]) -> bool {
+        let read_column = outputs
+            .iter()
+            .find_map(|(_name, output)| match output {
+                Output::Column(index) => Some(all_columns[*index].name()),
+                Output::Expr(value, _) => value.column().map(|index| all_columns[index].name()),
+                Output::Window(_) | Output::Aggregate(_) => None,
+            })
+            .or_else(|| {
+                self.select_input
+                    .order_by
+                    .first()
+                    .map(|sort_key| sort_key.column_name.clone())
+            });
+        if outputs
+            .iter()
+            .any(|(_name, output)| matches!(output, Output::Window(_)))
+        {
+            self.sender
+                .send(Err(QueryError::feature_not_supported(
+                    "window functions along with aggregates",
+                )))
+                .expect("To Send Result to Client");
+            return false;
+        }
+        match read_column {
+            Some(column_name) => {
+                let table_name = self
+                    .data_manager
+                    .table_name(&self.select_input.table_id)
+                    .map(|(_schema_name, table_name)| table_name)
+                    .unwrap_or_default();
+                self.sender
+                    .send(Err(QueryError::grouping_error(format!(
+                        "column \"{}.{}\" must appear in the GROUP BY clause or be used in an aggregate function",
+                        table_name, column_name
+                    ))))
+                    .expect("To Send Result to Client");
+                false
+            }
+            None => true,
         }
+    }
 
-        let description = column_definitions
-            .into_iter()
-            .map(|column_definition| (column_definition.name(), (&column_definition.sql_type()).into()))
+    pub(crate) fn describe(&mut self) -> SystemResult<(Description, ColumnOrigins)> {
+        let all_columns = self.data_manager.table_columns(&self.select_input.table_id)?;
+        let (outputs, windows, aggregates) = match self.resolve(&all_columns) {
+            Some(resolved) => resolved,
+            None => return Err(SystemError::runtime_check_failure(&"Column Does Not Exist")),
+        };
+
+        let description = outputs
+            .iter()
+            .map(|(name, output)| (name.clone(), output_type(output, &all_columns, &windows, &aggregates)))
+            .collect();
+        let table_oid = table_oid(self.select_input.table_id.as_ref());
+        let origins = outputs
+            .iter()
+            .map(|(_name, output)| match output {
+                Output::Column(index) => Some((table_oid, *index as i16 + 1)),
+                Output::Window(_) | Output::Aggregate(_) | Output::Expr(_, _) => None,
+            })
             .collect();
 
-        Ok(description)
+        Ok((description, origins))
     }
 
+    /// rows after the ones that are sent are not read when they are streamed rather than buffered for windows,
+    /// aggregates and sorting, a row over the row limit is read to tell that the result is truncated
+    fn rows_needed(&self, max_rows: usize, streamed: bool) -> usize {
+        if !streamed {
+            return usize::MAX;
+        }
+        let RowLimit {
+            offset,
+            count,
+            with_ties,
+        } = self.select_input.limit;
+        let by_limit = match count {
+            Some(count) if !with_ties => (offset as usize).saturating_add(count as usize),
+            _ => usize::MAX,
+        };
+        let by_max_rows = if max_rows > 0 {
+            (offset as usize).saturating_add(max_rows + 1)
+        } else {
+            usize::MAX
+        };
+        by_limit.min(by_max_rows)
+    }
+
+    /// sends `query canceled` error when a client canceled the statement
+    fn canceled(&self) -> bool {
+        let canceled = self.activity.is_canceled();
+        if canceled {
+            self.sender
+                .send(Err(QueryError::query_canceled()))
+                .expect("To Send Query Result to Client");
+        }
+        canceled
+    }
+
+    /// every stage of the pipeline is reported to the session activity as an operator of the plan
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
-        match self.data_manager.full_scan(&self.select_input.table_id) {
-            Err(error) => Err(error),
-            Ok(records) => {
-                let all_columns = self.data_manager.table_columns(&self.select_input.table_id)?;
-                let mut description = vec![];
-                let mut column_indexes = vec![];
-                let mut has_error = false;
-                for column_name in self.select_input.selected_columns.iter() {
-                    let mut found = None;
-                    for (index, column_definition) in all_columns.iter().enumerate() {
-                        if column_definition.has_name(column_name) {
-                            found = Some((index, column_definition.clone()));
-                            break;
+        let all_columns = self.data_manager.table_columns(&self.select_input.table_id)?;
+        let resolved = self.resolve(&all_columns);
+        let sort_keys = self.sort_keys(&all_columns, &self.select_input.order_by);
+        let ((outputs, windows, aggregates), sort_keys) = match (resolved, sort_keys) {
+            (Some(resolved), Some(sort_keys)) => (resolved, sort_keys),
+            _ => return Ok(()),
+        };
+
+        let ranges = self
+            .select_input
+            .ranges
+            .iter()
+            .filter_map(|range| {
+                all_columns
+                    .iter()
+                    .position(|column| column.has_name(&range.column_name))
+                    .map(|index| (index, range))
+            })
+            .collect::<Vec<_>>();
+        for (_, range) in ranges.iter() {
+            self.data_manager
+                .record_column_filter(&self.select_input.table_id, &range.column_name);
+        }
+        let filters = match self
+            .select_input
+            .filters
+            .iter()
+            .map(|filter| self.filter(&all_columns, filter, "WHERE"))
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(filters) => filters,
+            None => return Ok(()),
+        };
+        // filters that are constant are decided once, no row satisfies a filter that is `false` or NULL
+        let (constants, filters): (Vec<ScalarOp>, Vec<ScalarOp>) = filters.into_iter().partition(ScalarOp::is_literal);
+        let scanned_tables: &[TableId] = if constants
+            .iter()
+            .all(|constant| constant.as_datum() == Some(Datum::from_bool(true)))
+        {
+            self.select_input.scanned_tables.as_slice()
+        } else {
+            &[]
+        };
+        // columns that no operator reads are left NULL in unpacked rows
+        let required = &self.select_input.required_columns;
+        let filter_columns = required_columns(&all_columns, &[&required.filter]);
+        let kept_columns = required_columns(&all_columns, &[&required.window, &required.sort, &required.project]);
+        let ResultLimits { max_rows, max_size } = self.result_limits;
+        // rows are buffered only when windows, aggregates or sorting have to see all of them
+        let streamed = windows.is_empty() && aggregates.is_empty() && sort_keys.is_empty();
+        let rows_needed = self.rows_needed(max_rows, streamed);
+
+        // the planner reads the range of keys when it is estimated to be cheaper than reading all rows
+        let key_range = match self.select_input.scan {
+            ScanMethod::PrimaryKeyRange => primary_key_range(&all_columns, &self.select_input.ranges),
+            ScanMethod::Sequential => None,
+        };
+        let key_ranges = match key_range.clone() {
+            // keys written before primary keys were packed in order are all before the keys of the format
+            Some(key_range) => vec![
+                (
+                    Bound::Unbounded,
+                    Bound::Excluded(Binary::with_data(vec![KEY_FORMAT_V1])),
+                ),
+                key_range,
+            ],
+            None => vec![(Bound::Unbounded, Bound::Unbounded)],
+        };
+        // window values follow columns of the table in rows, aggregate values follow window values
+        let render_row = |row: &[Datum]| {
+            outputs
+                .iter()
+                .map(|(_name, output)| match output {
+                    Output::Column(index) => Ok(render(&row[*index], &all_columns[*index], &self.function_context)),
+                    Output::Window(index) => Ok(row[all_columns.len() + *index].to_string()),
+                    Output::Aggregate(index) => Ok(row[all_columns.len() + windows.len() + *index].to_string()),
+                    Output::Expr(value, ty) => EvalScalarOp::evaluate(row, value)
+                        .map(|datum| render_value(&datum, *ty, &self.function_context)),
+                })
+                .collect::<Result<Vec<String>, QueryError>>()
+        };
+        let mut result = ResultRows {
+            sender: self.sender.as_ref(),
+            description: Some(
+                outputs
+                    .iter()
+                    .map(|(name, output)| (name.clone(), output_type(output, &all_columns, &windows, &aggregates)))
+                    .collect(),
+            ),
+            max_rows,
+            max_size,
+            size: 0,
+            rows_sent: 0,
+            truncated_by: None,
+        };
+        let limit = &self.select_input.limit;
+        let is_limited = limit.count.is_some() || limit.offset > 0;
+        let streamed_rows = match limit.count {
+            Some(count) if !limit.with_ties => {
+                (limit.offset as usize)..(limit.offset as usize).saturating_add(count as usize)
+            }
+            _ => (limit.offset as usize)..usize::MAX,
+        };
+        let mut records = vec![];
+        let mut kept_rows = 0;
+        // partitions of a partitioned table that can't hold selected rows are pruned by the planner
+        'scan: for table_id in scanned_tables.iter() {
+            match (self.data_manager.table_name(table_id), &key_range) {
+                (Some((schema_name, table_name)), Some(_)) => self.activity.enter(format!(
+                    "Index Scan using {}_pkey on {}.{}",
+                    table_name, schema_name, table_name
+                )),
+                (Some((schema_name, table_name)), None) => self
+                    .activity
+                    .enter(format!("Seq Scan on {}.{}", schema_name, table_name)),
+                (None, _) => self.activity.enter("Seq Scan"),
+            }
+            // rows are read by batches until there are enough of them for the limits
+            for keys in key_ranges.iter() {
+                for batch in self
+                    .data_manager
+                    .scan(table_id, keys.clone(), SCAN_BATCH_SIZE)
+                    .with_journal(self.journal.as_deref())
+                {
+                    let rows = match batch? {
+                        Ok(rows) => rows,
+                        Err(error) => {
+                            self.sender
+                                .send(Err(QueryError::io_error(format!("{:?}", error))))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
+                        }
+                    };
+                    for (_key, values) in rows {
+                        if self.canceled() {
+                            return Ok(());
+                        }
+                        self.activity.produced(1);
+                        // rows are filtered as they are scanned, before windows and sorting see them
+                        let kept = if ranges.is_empty() && filters.is_empty() {
+                            Ok(true)
+                        } else {
+                            let row = values.unpack_columns(&filter_columns);
+                            if in_ranges(&row, &ranges) {
+                                satisfies(&row, &filters)
+                            } else {
+                                Ok(false)
+                            }
+                        };
+                        match kept {
+                            Ok(true) => kept_rows += 1,
+                            Ok(false) => continue,
+                            Err(error) => {
+                                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                return Ok(());
+                            }
+                        }
+                        if !streamed {
+                            records.push(values);
+                        } else if streamed_rows.contains(&(kept_rows - 1)) {
+                            match render_row(&values.unpack_columns(&kept_columns)) {
+                                Ok(record) => {
+                                    if !result.send(record) {
+                                        break 'scan;
+                                    }
+                                }
+                                Err(error) => {
+                                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        if kept_rows >= rows_needed {
+                            break 'scan;
                         }
                     }
-
-                    if let Some((index, column_definition)) = found {
-                        column_indexes.push(index);
-                        description.push(column_definition);
-                    } else {
-                        self.sender
-                            .send(Err(QueryError::column_does_not_exist(column_name)))
-                            .expect("To Send Result to Client");
-                        has_error = true;
+                }
+            }
+        }
+        if !ranges.is_empty() || !filters.is_empty() || !constants.is_empty() {
+            self.activity.enter("Filter");
+            self.activity.produced(kept_rows as u64);
+        }
+        if streamed {
+            if is_limited {
+                self.activity.enter("Limit");
+                self.activity
+                    .produced(kept_rows.min(streamed_rows.end).saturating_sub(streamed_rows.start) as u64);
+            }
+            result.finish();
+            return Ok(());
+        }
+        let mut rows = records
+            .iter()
+            .map(|record| record.unpack_columns(&kept_columns))
+            .collect::<Vec<Vec<Datum>>>();
+        // window functions are computed before rows are sorted and limited
+        if !windows.is_empty() {
+            self.activity.enter("WindowAgg");
+        }
+        let window_values = match windows
+            .iter()
+            .map(|window| window.evaluate(&rows))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(window_values) => window_values,
+            Err(error) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        for (index, row) in rows.iter_mut().enumerate() {
+            row.extend(window_values.iter().map(|values| values[index].clone()));
+        }
+        if !windows.is_empty() {
+            self.activity.produced(rows.len() as u64);
+        }
+        // rows are aggregated into one, constant expressions are the only other selected columns
+        if !aggregates.is_empty() {
+            self.activity.enter("Aggregate");
+            let mut aggregated = vec![Datum::from_null(); all_columns.len()];
+            for aggregate in aggregates.iter() {
+                match aggregate.evaluate(&rows) {
+                    Ok(value) => aggregated.push(value),
+                    Err(error) => {
+                        self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                        return Ok(());
                     }
                 }
-
-                if has_error {
+            }
+            rows = vec![aggregated];
+            self.activity.produced(1);
+        }
+        if self.canceled() {
+            return Ok(());
+        }
+        if !sort_keys.is_empty() {
+            self.activity.enter("Sort");
+            if all_columns.iter().any(|column| column.enum_type().is_some()) {
+                let mut keyed_rows = rows
+                    .into_iter()
+                    .map(|row| (enum_positions(&row, &all_columns), row))
+                    .collect::<Vec<_>>();
+                keyed_rows.sort_by(|(left, _), (right, _)| compare_rows(left, right, &sort_keys));
+                rows = keyed_rows.into_iter().map(|(_, row)| row).collect();
+            } else {
+                rows.sort_by(|left, right| compare_rows(left, right, &sort_keys));
+            }
+            self.activity.produced(rows.len() as u64);
+        }
+        if self.canceled() {
+            return Ok(());
+        }
+        if is_limited {
+            self.activity.enter("Limit");
+        }
+        let rows = limit_rows(rows, limit, &sort_keys);
+        if is_limited {
+            self.activity.produced(rows.len() as u64);
+        }
+        for row in rows {
+            match render_row(&row) {
+                Ok(record) => {
+                    if !result.send(record) {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
                     return Ok(());
                 }
+            }
+        }
+        result.finish();
+        Ok(())
+    }
+}
 
-                let values: Vec<Vec<String>> = records
-                    .map(Result::unwrap)
-                    .map(Result::unwrap)
-                    .map(|(_key, values)| {
-                        let row: Vec<String> = values.unpack().into_iter().map(|datum| datum.to_string()).collect();
-
-                        let mut values = vec![];
-                        for origin in column_indexes.iter() {
-                            for (index, value) in row.iter().enumerate() {
-                                if index == *origin {
-                                    values.push(value.clone())
-                                }
-                            }
-                        }
-                        values
-                    })
-                    .collect();
-
-                let projection = (
-                    description
-                        .into_iter()
-                        .map(|column| (column.name(), (&column.sql_type()).into()))
-                        .collect(),
-                    values,
-                );
-                self.sender
-                    .send(Ok(QueryEvent::RecordsSelected(projection)))
-                    .expect("To Send Query Result to Client");
-                Ok(())
+/// timestamptz values are stored as UTC micros and shown in the session time zone
+pub(crate) fn render(datum: &Datum, column: &ColumnDefinition, function_context: &FunctionContext) -> String {
+    if column.sql_type() == SqlType::TimestampWithTimeZone && !datum.is_null() {
+        render_timestamp_with_time_zone(datum.as_timestamp(), function_context.utc_offset())
+    } else {
+        datum.to_string()
+    }
+}
+
+/// timestamps computed by expressions are shown in the session time zone as timestamptz columns are
+fn render_value(datum: &Datum, ty: Option<ScalarType>, function_context: &FunctionContext) -> String {
+    if ty == Some(ScalarType::Timestamp) && !datum.is_null() {
+        render_timestamp_with_time_zone(datum.as_timestamp(), function_context.utc_offset())
+    } else {
+        datum.to_string()
+    }
+}
+
+/// constant second argument of an aggregate
+fn parameter_datum(parameter: &Value) -> Datum<'static> {
+    match parameter {
+        Value::Number(number) => Datum::from_f64(number.to_string().parse().expect("numeric literal")),
+        Value::SingleQuotedString(value) => Datum::from_string(value.clone()),
+        _ => Datum::from_null(),
+    }
+}
+
+/// NULLs are out of any range
+/// keys of rows that can be in the range of the first primary key column, rows are still filtered by the range.
+/// Keys that start with a value are ordered after the key of the value and before the key of the next value
+fn primary_key_range(all_columns: &[ColumnDefinition], ranges: &[ColumnRange]) -> Option<KeyRange> {
+    let column = all_columns.iter().find(|column| column.is_primary_key())?;
+    let range = ranges.iter().find(|range| column.has_name(&range.column_name))?;
+    let (min, max) = match column.sql_type() {
+        SqlType::SmallInt(_) => (i16::min_value() as i64, i16::max_value() as i64),
+        SqlType::Integer(_) => (i32::min_value() as i64, i32::max_value() as i64),
+        SqlType::BigInt(_) => (i64::min_value(), i64::max_value()),
+        _ => return None,
+    };
+    let key = |value: i64| {
+        let value = value.max(min).min(max);
+        Binary::pack_key(&[match column.sql_type() {
+            SqlType::SmallInt(_) => Datum::from_i16(value as i16),
+            SqlType::Integer(_) => Datum::from_i32(value as i32),
+            _ => Datum::from_i64(value),
+        }])
+    };
+    let start = match range.lower {
+        Some(lower) => Bound::Included(key(lower)),
+        None => Bound::Unbounded,
+    };
+    let end = match range.upper {
+        Some(upper) if upper < max => Bound::Excluded(key(upper + 1)),
+        _ => Bound::Unbounded,
+    };
+    Some((start, end))
+}
+
+/// whether every column of the table is read by one of the operators
+fn required_columns(all_columns: &[ColumnDefinition], operators: &[&Vec<String>]) -> Vec<bool> {
+    all_columns
+        .iter()
+        .map(|column| {
+            operators
+                .iter()
+                .any(|names| names.iter().any(|name| column.has_name(name)))
+        })
+        .collect()
+}
+
+fn in_ranges(row: &[Datum], ranges: &[(usize, &ColumnRange)]) -> bool {
+    ranges.iter().all(|(index, range)| match integer(&row[*index]) {
+        Some(value) => {
+            range.lower.map(|lower| lower <= value).unwrap_or(true)
+                && range.upper.map(|upper| value <= upper).unwrap_or(true)
+        }
+        None => false,
+    })
+}
+
+/// NULL doesn't satisfy a filter as `false` doesn't
+fn satisfies(row: &[Datum], filters: &[ScalarOp]) -> Result<bool, QueryError> {
+    for filter in filters {
+        if EvalScalarOp::evaluate(row, filter)? != Datum::from_bool(true) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn output_type(
+    output: &Output,
+    all_columns: &[ColumnDefinition],
+    windows: &[Window],
+    aggregates: &[Aggregate],
+) -> PostgreSqlType {
+    match output {
+        Output::Column(index) => (&all_columns[*index].sql_type()).into(),
+        Output::Window(index) => windows[*index].result_type(all_columns),
+        Output::Aggregate(index) => aggregates[*index].result_type(all_columns),
+        Output::Expr(_, ty) => pg_type(*ty),
+    }
+}
+
+/// skips `offset` sorted rows and takes `count` of the rest, `WITH TIES`
+/// keeps taking rows while they are equal to the last taken one
+fn limit_rows<'r>(rows: Vec<Vec<Datum<'r>>>, limit: &RowLimit, sort_keys: &[(usize, &SortKey)]) -> Vec<Vec<Datum<'r>>> {
+    let mut rows = rows.into_iter().skip(limit.offset as usize).collect::<Vec<_>>();
+    if let Some(count) = limit.count {
+        let mut end = (count as usize).min(rows.len());
+        if limit.with_ties && end > 0 {
+            while end < rows.len() && compare_rows(&rows[end - 1], &rows[end], sort_keys) == Ordering::Equal {
+                end += 1;
             }
         }
+        rows.truncate(end);
     }
+    rows
 }
@@ -0,0 +1,161 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{pgsql_types::PostgreSqlType, results::QueryError, session::FunctionContext, Sender};
+use query_planner::plan::SelectValues;
+use representation::ScalarType;
+use sqlparser::ast::Expr;
+
+use crate::{
+    dml::{
+        catalog_export::CatalogFunctions, change_capture::ChangeSlotFunctions, large_object::LargeObjectFunctions,
+        send_projection, sequence::SequenceFunctions,
+    },
+    function::FunctionRegistry,
+    operator::OperatorTable,
+    query::expr::ExpressionEvaluation,
+};
+
+pub(crate) struct SelectValuesCommand {
+    select_values: SelectValues,
+    data_manager: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+}
+
+impl SelectValuesCommand {
+    pub(crate) fn new(
+        select_values: SelectValues,
+        data_manager: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
+    ) -> SelectValuesCommand {
+        SelectValuesCommand {
+            select_values,
+            data_manager,
+            sender,
+            function_context,
+            functions,
+            operators,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let evaluation = ExpressionEvaluation::new(
+            self.sender.clone(),
+            vec![],
+            self.function_context.clone(),
+            self.functions.clone(),
+            self.operators.clone(),
+            self.data_manager.clone(),
+        );
+        let mut description = vec![];
+        let mut record = vec![];
+        let large_objects = LargeObjectFunctions::new(&self.data_manager, self.sender.as_ref());
+        let sequences = SequenceFunctions::new(&self.data_manager, self.sender.as_ref(), &self.function_context);
+        let catalog = CatalogFunctions::new(&self.data_manager, self.sender.as_ref());
+        let change_slots = ChangeSlotFunctions::new(&self.data_manager, self.sender.as_ref(), &self.function_context);
+        for (name, expr) in self.select_values.values.iter() {
+            if let Expr::Function(function) = expr {
+                let function_name = function.name.to_string().to_lowercase();
+                let is_large_object_function = LargeObjectFunctions::is_large_object_function(&function_name);
+                let is_catalog_function = CatalogFunctions::is_catalog_function(&function_name);
+                let is_change_slot_function = ChangeSlotFunctions::is_change_slot_function(&function_name);
+                if is_large_object_function
+                    || is_catalog_function
+                    || is_change_slot_function
+                    || SequenceFunctions::is_sequence_function(&function_name)
+                {
+                    let mut args = vec![];
+                    for arg in function.args.iter() {
+                        match evaluation.eval(arg, None) {
+                            Ok(value) => match value.as_datum() {
+                                Some(datum) => args.push(datum),
+                                None => {
+                                    self.sender
+                                        .send(Err(QueryError::feature_not_supported(
+                                            "Only expressions resulting in a literal are supported",
+                                        )))
+                                        .expect("To Send Query Result to Client");
+                                    return Ok(());
+                                }
+                            },
+                            Err(()) => return Ok(()),
+                        }
+                    }
+                    let result = if is_large_object_function {
+                        large_objects.call(&function_name, &args)?
+                    } else if is_catalog_function {
+                        catalog.call(&function_name, &args)?
+                    } else if is_change_slot_function {
+                        change_slots.call(&function_name, &args)?
+                    } else {
+                        sequences.call(&function_name, &args)?
+                    };
+                    match result {
+                        Ok(datum) => {
+                            description.push((name.clone(), pg_type(datum.scalar_type())));
+                            record.push(datum.to_string());
+                        }
+                        Err(()) => return Ok(()),
+                    }
+                    continue;
+                }
+            }
+            let (datum, scalar_type) = match evaluation.eval_typed(expr, None) {
+                Ok((value, scalar_type)) => match value.as_datum() {
+                    Some(datum) => (datum, scalar_type),
+                    None => {
+                        self.sender
+                            .send(Err(QueryError::feature_not_supported(
+                                "Only expressions resulting in a literal are supported",
+                            )))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+                },
+                Err(()) => return Ok(()),
+            };
+            description.push((name.clone(), pg_type(scalar_type)));
+            record.push(datum.to_string());
+        }
+
+        send_projection(self.sender.as_ref(), (description, vec![record]));
+        Ok(())
+    }
+}
+
+/// NULL does not have a type and is sent as text
+pub(crate) fn pg_type(scalar_type: Option<ScalarType>) -> PostgreSqlType {
+    match scalar_type {
+        Some(ScalarType::Int16) => PostgreSqlType::SmallInt,
+        Some(ScalarType::Int32) => PostgreSqlType::Integer,
+        Some(ScalarType::Int64) | Some(ScalarType::UInt64) => PostgreSqlType::BigInt,
+        Some(ScalarType::Float32) => PostgreSqlType::Real,
+        Some(ScalarType::Float64) => PostgreSqlType::DoublePrecision,
+        Some(ScalarType::Boolean) => PostgreSqlType::Bool,
+        Some(ScalarType::Timestamp) => PostgreSqlType::TimestampWithTimeZone,
+        Some(ScalarType::Interval) => PostgreSqlType::Interval,
+        Some(ScalarType::Bytes) => PostgreSqlType::Bytea,
+        Some(ScalarType::String) | None => PostgreSqlType::VarChar,
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::{DataManager, SequenceError};
+use kernel::SystemResult;
+use protocol::{results::QueryError, session::FunctionContext, Sender};
+use representation::{Datum, ScalarType};
+use sql_model::Id;
+
+/// sequence manipulation functions. Sequences are named by a qualified
+/// `'schema.sequence'` string, `nextval` values are remembered by the
+/// session for `currval`
+pub(crate) struct SequenceFunctions<'sf> {
+    data_manager: &'sf DataManager,
+    sender: &'sf dyn Sender,
+    function_context: &'sf FunctionContext,
+}
+
+impl<'sf> SequenceFunctions<'sf> {
+    pub(crate) fn new(
+        data_manager: &'sf DataManager,
+        sender: &'sf dyn Sender,
+        function_context: &'sf FunctionContext,
+    ) -> SequenceFunctions<'sf> {
+        SequenceFunctions {
+            data_manager,
+            sender,
+            function_context,
+        }
+    }
+
+    pub(crate) fn is_sequence_function(name: &str) -> bool {
+        matches!(name.trim_start_matches("pg_catalog."), "nextval" | "currval" | "setval")
+    }
+
+    pub(crate) fn call(&self, name: &str, all_args: &[Datum]) -> SystemResult<Result<Datum<'static>, ()>> {
+        let function = name.trim_start_matches("pg_catalog.");
+        let (sequence_name, args) = match all_args.split_first() {
+            Some((Datum::String(sequence_name), args)) => (*sequence_name, args),
+            Some((Datum::OwnedString(sequence_name), args)) => (sequence_name.as_str(), args),
+            _ => return Ok(self.function_does_not_exist(name, all_args)),
+        };
+        let (schema_id, sequence_name) = match self.resolve(sequence_name) {
+            Ok(resolved) => resolved,
+            Err(()) => return Ok(Err(())),
+        };
+        match (function, args) {
+            ("nextval", []) => Ok(self.next_value(schema_id, sequence_name)?.map(Datum::from_i64)),
+            ("currval", []) => match self
+                .function_context
+                .last_sequence_value(&session_key(schema_id, sequence_name))
+            {
+                Some(value) => Ok(Ok(Datum::from_i64(value))),
+                None => {
+                    self.sender
+                        .send(Err(QueryError::object_not_in_prerequisite_state(format!(
+                            "currval of sequence \"{}\" is not yet defined in this session",
+                            sequence_name
+                        ))))
+                        .expect("To Send Query Result to Client");
+                    Ok(Err(()))
+                }
+            },
+            ("setval", [value]) => match as_signed(value) {
+                Some(value) => self.set_value(schema_id, sequence_name, value, true),
+                None => Ok(self.function_does_not_exist(name, all_args)),
+            },
+            ("setval", [value, is_called]) => match (as_signed(value), is_called.scalar_type()) {
+                (Some(value), Some(ScalarType::Boolean)) => {
+                    self.set_value(schema_id, sequence_name, value, matches!(is_called, Datum::True))
+                }
+                _ => Ok(self.function_does_not_exist(name, all_args)),
+            },
+            _ => Ok(self.function_does_not_exist(name, all_args)),
+        }
+    }
+
+    /// advances the sequence and remembers the value for `currval`
+    pub(crate) fn next_value(&self, schema_id: Id, sequence_name: &str) -> SystemResult<Result<i64, ()>> {
+        match self.data_manager.next_sequence_value(schema_id, sequence_name)? {
+            Ok(value) => {
+                self.function_context
+                    .set_last_sequence_value(&session_key(schema_id, sequence_name), value);
+                Ok(Ok(value))
+            }
+            Err(SequenceError::MaxValueReached(max_value)) => {
+                self.sender
+                    .send(Err(QueryError::sequence_limit_exceeded(format!(
+                        "nextval: reached maximum value of sequence \"{}\" ({})",
+                        sequence_name, max_value
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Ok(Err(()))
+            }
+            Err(SequenceError::MinValueReached(min_value)) => {
+                self.sender
+                    .send(Err(QueryError::sequence_limit_exceeded(format!(
+                        "nextval: reached minimum value of sequence \"{}\" ({})",
+                        sequence_name, min_value
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Ok(Err(()))
+            }
+            Err(_) => Ok(self.does_not_exist(sequence_name)),
+        }
+    }
+
+    fn set_value(
+        &self,
+        schema_id: Id,
+        sequence_name: &str,
+        value: i64,
+        is_called: bool,
+    ) -> SystemResult<Result<Datum<'static>, ()>> {
+        match self
+            .data_manager
+            .set_sequence_value(schema_id, sequence_name, value, is_called)?
+        {
+            Ok(value) => Ok(Ok(Datum::from_i64(value))),
+            Err(SequenceError::ValueOutOfBounds {
+                value,
+                min_value,
+                max_value,
+            }) => {
+                self.sender
+                    .send(Err(QueryError::numeric_value_out_of_range(format!(
+                        "setval: value {} is out of bounds for sequence \"{}\" ({}..{})",
+                        value, sequence_name, min_value, max_value
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Ok(Err(()))
+            }
+            Err(_) => Ok(self.does_not_exist(sequence_name)),
+        }
+    }
+
+    fn resolve<'n>(&self, name: &'n str) -> Result<(Id, &'n str), ()> {
+        let mut parts = name.split('.');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(schema_name), Some(sequence_name), None) => match self.data_manager.schema_exists(&schema_name) {
+                Some(schema_id) if self.data_manager.sequence_exists(schema_id, sequence_name) => {
+                    Ok((schema_id, sequence_name))
+                }
+                Some(_) => self.does_not_exist(name),
+                None => {
+                    self.sender
+                        .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                        .expect("To Send Query Result to Client");
+                    Err(())
+                }
+            },
+            _ => self.does_not_exist(name),
+        }
+    }
+
+    fn does_not_exist<T>(&self, sequence_name: &str) -> Result<T, ()> {
+        self.sender
+            .send(Err(QueryError::sequence_does_not_exist(sequence_name)))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+
+    fn function_does_not_exist(&self, name: &str, args: &[Datum]) -> Result<Datum<'static>, ()> {
+        let arg_types = args
+            .iter()
+            .map(|arg| match arg.scalar_type() {
+                Some(ScalarType::String) => "text",
+                Some(ScalarType::Int16) | Some(ScalarType::Int32) => "integer",
+                Some(ScalarType::Int64) | Some(ScalarType::UInt64) => "bigint",
+                Some(ScalarType::Float32) | Some(ScalarType::Float64) => "double precision",
+                Some(ScalarType::Boolean) => "boolean",
+                Some(ScalarType::Timestamp) => "timestamp with time zone",
+                Some(ScalarType::Interval) => "interval",
+                Some(ScalarType::Bytes) => "bytea",
+                None => "unknown",
+            })
+            .collect::<Vec<_>>();
+        self.sender
+            .send(Err(QueryError::function_does_not_exist(format!(
+                "{}({})",
+                name,
+                arg_types.join(", ")
+            ))))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
+
+/// key of the value remembered by the session for `currval`
+pub(crate) fn session_key(schema_id: Id, sequence_name: &str) -> String {
+    format!("{}.{}", schema_id, sequence_name)
+}
+
+fn as_signed(datum: &Datum) -> Option<i64> {
+    match datum.scalar_type() {
+        Some(ScalarType::Int16) => Some(datum.as_i16() as i64),
+        Some(ScalarType::Int32) => Some(datum.as_i32() as i64),
+        Some(ScalarType::Int64) => Some(datum.as_i64()),
+        _ => None,
+    }
+}
@@ -12,21 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
-use data_manager::{DataManager, Row};
+use data_manager::{DataManager, Journal, Row};
 use kernel::SystemResult;
-use protocol::Sender;
-use representation::{unpack_raw, Binary};
+use protocol::{session::FunctionContext, Sender};
+use representation::{unpack_raw, Binary, Datum};
 
-use crate::query::expr::{EvalScalarOp, ExpressionEvaluation};
-use protocol::results::QueryEvent;
+use crate::{
+    dml::{
+        delete_rows, foreign_key_violation, generated_columns, null_in_not_null_column, primary_key_columns,
+        primary_key_constraint_name, referenced_value, referencing_row, scan_table, write_rows,
+    },
+    function::FunctionRegistry,
+    operator::OperatorTable,
+    query::expr::{EvalScalarOp, ExpressionEvaluation},
+};
+use protocol::results::{QueryError, QueryEvent};
 use query_planner::plan::TableUpdates;
 
 pub(crate) struct UpdateCommand {
     table_update: TableUpdates,
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl UpdateCommand {
@@ -34,41 +46,120 @@ impl UpdateCommand {
         table_update: TableUpdates,
         data_manager: Arc<DataManager>,
         sender: Arc<dyn Sender>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
     ) -> UpdateCommand {
         UpdateCommand {
             table_update,
             data_manager,
             sender,
+            function_context,
+            functions,
+            operators,
+            journal: None,
         }
     }
 
+    /// updated rows are kept in the journal until its transaction is committed
+    pub(crate) fn with_journal(mut self, journal: Option<Arc<Journal>>) -> UpdateCommand {
+        self.journal = journal;
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let table_definition = self.data_manager.table_columns(&self.table_update.table_id)?;
         let all_columns = table_definition.clone();
 
-        let evaluation = ExpressionEvaluation::new(self.sender.clone(), table_definition);
+        let evaluation = ExpressionEvaluation::new(
+            self.sender.clone(),
+            table_definition,
+            self.function_context.clone(),
+            self.functions.clone(),
+            self.operators.clone(),
+            self.data_manager.clone(),
+        );
 
         let mut to_update = vec![];
         let mut has_error = false;
         for item in self.table_update.assignments.iter() {
+            let is_generated = all_columns
+                .iter()
+                .any(|column| column.has_name(&item.id.value) && column.generation_expression().is_some());
+            if is_generated {
+                self.sender
+                    .send(Err(QueryError::generated_always(format!(
+                        "column \"{}\" can only be updated to DEFAULT",
+                        item.id.value
+                    ))))
+                    .expect("To Send Query Result to Client");
+                has_error = true;
+                continue;
+            }
             match evaluation.eval_assignment(item) {
                 Ok(assign) => to_update.push(assign),
                 Err(()) => has_error = true,
             }
         }
 
+        let filter = match &self.table_update.filter {
+            Some(filter) => match evaluation.eval_condition(filter, "WHERE") {
+                Ok(filter) => Some(filter),
+                Err(()) => return Ok(()),
+            },
+            None => None,
+        };
+
         if has_error {
             return Ok(());
         }
 
-        let to_update: Vec<Row> = match self.data_manager.full_scan(&self.table_update.table_id) {
+        // generated columns are computed from updated values
+        match generated_columns(&all_columns, &evaluation) {
+            Ok(generated_columns) => to_update.extend(generated_columns),
+            Err(()) => return Ok(()),
+        }
+
+        let primary_key = primary_key_columns(&all_columns);
+        let mut moved_keys = vec![];
+        let table_id = *self.table_update.table_id.as_ref();
+        let to_update: Vec<Row> = match scan_table(&self.data_manager, &self.journal, table_id) {
             Err(error) => return Err(error),
             Ok(reads) => {
-                let expr_eval = EvalScalarOp::new(self.sender.as_ref(), all_columns.to_vec());
+                let expr_eval = EvalScalarOp::new(
+                    self.sender.as_ref(),
+                    all_columns.to_vec(),
+                    self.function_context.utc_offset(),
+                );
+                let unique_violation = || {
+                    self.sender
+                        .send(Err(QueryError::unique_violation(primary_key_constraint_name(
+                            &self.data_manager,
+                            &self.table_update.table_id,
+                        ))))
+                        .expect("To Send Query Result to Client")
+                };
                 let mut res = Vec::new();
+                let mut updated_keys = BTreeSet::new();
                 for (row_idx, (key, values)) in reads.map(Result::unwrap).map(Result::unwrap).enumerate() {
                     let mut datums = unpack_raw(values.to_bytes());
 
+                    let is_updated = match &filter {
+                        Some(filter) => match expr_eval.eval(&datums, filter) {
+                            Ok(satisfied) => satisfied == Datum::from_bool(true),
+                            Err(()) => return Ok(()),
+                        },
+                        None => true,
+                    };
+                    // rows that are not updated keep their keys, updated rows can't be moved onto them
+                    if !is_updated {
+                        if !updated_keys.insert(key) {
+                            unique_violation();
+                            return Ok(());
+                        }
+                        continue;
+                    }
+
                     let mut has_err = false;
                     for update in to_update.as_slice() {
                         has_err = expr_eval
@@ -81,13 +172,79 @@ impl UpdateCommand {
                         return Ok(());
                     }
 
-                    res.push((key, Binary::pack(&datums)));
+                    if let Some(column_name) = null_in_not_null_column(&all_columns, &datums) {
+                        self.sender
+                            .send(Err(QueryError::not_null_violation(column_name)))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+
+                    let new_key = if primary_key.is_empty() {
+                        key.clone()
+                    } else {
+                        // rows with keys written before primary keys were packed in order are moved to the new keys
+                        Binary::pack_key(
+                            &primary_key
+                                .iter()
+                                .map(|index| datums[*index].clone())
+                                .collect::<Vec<Datum>>(),
+                        )
+                    };
+                    if !updated_keys.insert(new_key.clone()) {
+                        unique_violation();
+                        return Ok(());
+                    }
+                    if new_key != key {
+                        moved_keys.push(key);
+                    }
+
+                    res.push((new_key, Binary::pack_row(&datums)));
                 }
                 res
             }
         };
 
-        match self.data_manager.write_into(&self.table_update.table_id, to_update) {
+        let records = to_update
+            .iter()
+            .map(|(_key, values)| values.unpack())
+            .collect::<Vec<_>>();
+        if let Some((table_name, constraint_name)) = foreign_key_violation(
+            &self.data_manager,
+            &self.journal,
+            &self.table_update.table_id,
+            &all_columns,
+            &records,
+        )? {
+            self.sender
+                .send(Err(QueryError::foreign_key_violation(table_name, constraint_name)))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+
+        if !moved_keys.is_empty() {
+            let new_keys = to_update.iter().map(|(key, _values)| key).collect::<BTreeSet<_>>();
+            let stale_keys = moved_keys
+                .into_iter()
+                .filter(|key| !new_keys.contains(key))
+                .collect::<Vec<_>>();
+            let stale_values = stale_keys.iter().map(referenced_value).collect::<BTreeSet<_>>();
+            if let Some(referencing) = referencing_row(&self.data_manager, &self.journal, &table_id, &stale_values)? {
+                self.sender
+                    .send(Err(QueryError::referenced_row_violation(
+                        self.data_manager
+                            .table_name(&self.table_update.table_id)
+                            .map(|(_schema_name, table_name)| table_name)
+                            .unwrap_or_default(),
+                        referencing.constraint_name(),
+                        referencing.table_name,
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+            delete_rows(&self.data_manager, &self.journal, table_id, stale_keys)?;
+        }
+
+        match write_rows(&self.data_manager, &self.journal, table_id, to_update) {
             Err(error) => return Err(error),
             Ok(records_number) => {
                 self.sender
@@ -0,0 +1,211 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
+
+use data_manager::ColumnDefinition;
+use protocol::{pgsql_types::PostgreSqlType, results::QueryError};
+use query_planner::plan::{FrameBound, FrameUnits, SortKey, WindowFunction};
+use representation::Datum;
+
+use crate::{
+    aggregate::{numeric, AggregateFunction},
+    dml::compare_rows,
+    query::{expr::EvalScalarOp, scalar::ScalarOp},
+};
+
+/// window function with its columns resolved to indexes of table columns
+pub(crate) struct Window<'w> {
+    function: &'w WindowFunction,
+    /// `None` for `row_number()`
+    aggregate: Option<Arc<dyn AggregateFunction>>,
+    argument: Option<usize>,
+    /// condition of `FILTER (WHERE ...)` compiled over table columns
+    filter: Option<ScalarOp>,
+    partition_by: Vec<usize>,
+    order_by: Vec<(usize, &'w SortKey)>,
+}
+
+impl<'w> Window<'w> {
+    pub(crate) fn new(
+        function: &'w WindowFunction,
+        aggregate: Option<Arc<dyn AggregateFunction>>,
+        argument: Option<usize>,
+        filter: Option<ScalarOp>,
+        partition_by: Vec<usize>,
+        order_by: Vec<(usize, &'w SortKey)>,
+    ) -> Window<'w> {
+        Window {
+            function,
+            aggregate,
+            argument,
+            filter,
+            partition_by,
+            order_by,
+        }
+    }
+
+    pub(crate) fn result_type(&self, columns: &[ColumnDefinition]) -> PostgreSqlType {
+        match &self.aggregate {
+            Some(aggregate) => aggregate.return_type(self.argument.map(|index| columns[index].sql_type()).as_ref()),
+            None => PostgreSqlType::BigInt,
+        }
+    }
+
+    /// computes the function for each of the rows, rows are split into partitions
+    /// and ordered within them, a row is computed over its frame in the partition
+    pub(crate) fn evaluate<'r>(&self, rows: &[Vec<Datum<'r>>]) -> Result<Vec<Datum<'r>>, QueryError> {
+        // rows that don't satisfy the filter are in frames but are not aggregated
+        let mut is_aggregated = vec![true; rows.len()];
+        if let Some(filter) = &self.filter {
+            for (index, row) in rows.iter().enumerate() {
+                is_aggregated[index] = EvalScalarOp::evaluate(row, filter)? == Datum::from_bool(true);
+            }
+        }
+        let mut partitions: BTreeMap<Vec<Datum>, Vec<usize>> = BTreeMap::new();
+        for (index, row) in rows.iter().enumerate() {
+            partitions
+                .entry(self.partition_by.iter().map(|column| row[*column].clone()).collect())
+                .or_default()
+                .push(index);
+        }
+        let mut results = vec![Datum::from_null(); rows.len()];
+        for (_key, mut partition) in partitions {
+            partition.sort_by(|left, right| compare_rows(&rows[*left], &rows[*right], &self.order_by));
+            for position in 0..partition.len() {
+                results[partition[position]] = match &self.aggregate {
+                    None => Datum::from_i64(position as i64 + 1),
+                    Some(aggregate) => {
+                        let (start, end) = self.frame(rows, &partition, position);
+                        let frame = partition[start..end]
+                            .iter()
+                            .copied()
+                            .filter(|index| is_aggregated[*index])
+                            .collect::<Vec<_>>();
+                        self.aggregate(aggregate.as_ref(), rows, &frame)
+                    }
+                };
+            }
+        }
+        Ok(results)
+    }
+
+    /// frame of the row at `position` as a range of positions in the partition
+    fn frame(&self, rows: &[Vec<Datum>], partition: &[usize], position: usize) -> (usize, usize) {
+        let frame = &self.function.frame;
+        let len = partition.len();
+        let (start, end) = match frame.units {
+            FrameUnits::Rows => {
+                let start = match frame.start {
+                    FrameBound::UnboundedPreceding => 0,
+                    FrameBound::Preceding(offset) => position.saturating_sub(offset as usize),
+                    FrameBound::CurrentRow => position,
+                    FrameBound::Following(offset) => position.saturating_add(offset as usize),
+                    FrameBound::UnboundedFollowing => len,
+                };
+                let end = match frame.end {
+                    FrameBound::UnboundedPreceding => 0,
+                    FrameBound::Preceding(offset) => (position + 1).saturating_sub(offset as usize),
+                    FrameBound::CurrentRow => position + 1,
+                    FrameBound::Following(offset) => position.saturating_add(offset as usize).saturating_add(1),
+                    FrameBound::UnboundedFollowing => len,
+                };
+                (start, end)
+            }
+            FrameUnits::Range => {
+                let is_peer = |other: usize| {
+                    compare_rows(&rows[partition[other]], &rows[partition[position]], &self.order_by) == Ordering::Equal
+                };
+                let first_peer = (0..=position).find(|other| is_peer(*other)).unwrap_or(position);
+                let after_last_peer = (position..len).find(|other| !is_peer(*other)).unwrap_or(len);
+                // rows with NULL sort value are framed by their peers
+                let has_value = self.offset(rows, partition, position, position).is_some();
+                let start = match frame.start {
+                    FrameBound::UnboundedPreceding => 0,
+                    FrameBound::Preceding(offset) if has_value => {
+                        self.range_start(rows, partition, position, -(offset as f64))
+                    }
+                    FrameBound::Following(offset) if has_value => {
+                        self.range_start(rows, partition, position, offset as f64)
+                    }
+                    FrameBound::Preceding(_) | FrameBound::Following(_) | FrameBound::CurrentRow => first_peer,
+                    FrameBound::UnboundedFollowing => len,
+                };
+                let end = match frame.end {
+                    FrameBound::UnboundedPreceding => 0,
+                    FrameBound::Preceding(offset) if has_value => {
+                        self.range_end(rows, partition, position, -(offset as f64))
+                    }
+                    FrameBound::Following(offset) if has_value => {
+                        self.range_end(rows, partition, position, offset as f64)
+                    }
+                    FrameBound::Preceding(_) | FrameBound::Following(_) | FrameBound::CurrentRow => after_last_peer,
+                    FrameBound::UnboundedFollowing => len,
+                };
+                (start, end)
+            }
+        };
+        let end = end.min(len);
+        (start.min(end), end)
+    }
+
+    /// how far the sort value of the `other` row is from the current one in the direction of sorting
+    fn offset(&self, rows: &[Vec<Datum>], partition: &[usize], position: usize, other: usize) -> Option<f64> {
+        let (column, sort_key) = self.order_by.first()?;
+        let current = numeric(&rows[partition[position]][*column])?;
+        let other = numeric(&rows[partition[other]][*column])?;
+        if sort_key.ascending {
+            Some(other - current)
+        } else {
+            Some(current - other)
+        }
+    }
+
+    /// first row that is at least `bound` away from the current one
+    fn range_start(&self, rows: &[Vec<Datum>], partition: &[usize], position: usize, bound: f64) -> usize {
+        (0..partition.len())
+            .find(|other| {
+                self.offset(rows, partition, position, *other)
+                    .map(|offset| offset >= bound)
+                    .unwrap_or(false)
+            })
+            .unwrap_or_else(|| partition.len())
+    }
+
+    /// after the last row that is at most `bound` away from the current one
+    fn range_end(&self, rows: &[Vec<Datum>], partition: &[usize], position: usize, bound: f64) -> usize {
+        (0..partition.len())
+            .rev()
+            .find(|other| {
+                self.offset(rows, partition, position, *other)
+                    .map(|offset| offset <= bound)
+                    .unwrap_or(false)
+            })
+            .map(|last| last + 1)
+            .unwrap_or(0)
+    }
+
+    /// aggregates skip NULLs unless they accumulate them, `*` argument counts every row of the frame
+    fn aggregate(&self, aggregate: &dyn AggregateFunction, rows: &[Vec<Datum>], frame: &[usize]) -> Datum<'static> {
+        let mut state = aggregate.init();
+        for index in frame {
+            match self.argument {
+                Some(column) if rows[*index][column].is_null() && !aggregate.accumulates_nulls() => {}
+                Some(column) => aggregate.accumulate(&mut state, &rows[*index][column]),
+                None => aggregate.accumulate(&mut state, &Datum::from_bool(true)),
+            }
+        }
+        aggregate.finalize(state)
+    }
+}
@@ -0,0 +1,1234 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! scalar functions describe their signature, how they treat NULL arguments and whether
+//! their result could change between calls, calls of non volatile functions with constant
+//! arguments are evaluated once per statement, volatile functions are evaluated for each row
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex},
+};
+
+use regex::{Regex, RegexBuilder};
+use sqlparser::ast::BinaryOperator;
+
+use protocol::{array, clock, interval::Interval, json, results::QueryError, session::FunctionContext};
+use representation::{Datum, ScalarType};
+
+use crate::{
+    aggregate::{integer, numeric, owned},
+    operator::{cast, implicit_cast_cost, parse_bool, type_name},
+    query::{expr::EvalScalarOp, scalar::ScalarOp},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Volatility {
+    /// result depends only on arguments
+    Immutable,
+    /// result does not change within a statement
+    Stable,
+    /// result could change on every call
+    Volatile,
+}
+
+pub trait ScalarFunction: Send + Sync {
+    /// arguments are implicitly casted to the declared types before the call
+    fn argument_types(&self) -> &[ScalarType];
+
+    fn return_type(&self) -> ScalarType;
+
+    /// strict function returns NULL without being called if any of its arguments is NULL
+    fn is_strict(&self) -> bool;
+
+    fn volatility(&self) -> Volatility;
+
+    fn call(&self, context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError>;
+}
+
+/// builds a function for the types of arguments of a call, functions that take any number of arguments of any
+/// types or keep state for a single call are registered as builders
+pub trait FunctionBuilder: Send + Sync {
+    /// argument types come with whether the argument is a NULL literal
+    fn build(&self, args: &[(ScalarType, bool)]) -> Result<Arc<dyn ScalarFunction>, QueryError>;
+}
+
+/// scalar functions by their lowercase names, a name could have overloads with different argument types
+#[derive(Clone)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Vec<Arc<dyn ScalarFunction>>>,
+    builders: HashMap<String, Arc<dyn FunctionBuilder>>,
+}
+
+impl FunctionRegistry {
+    pub fn empty() -> FunctionRegistry {
+        FunctionRegistry {
+            functions: HashMap::new(),
+            builders: HashMap::new(),
+        }
+    }
+
+    /// builder is used for all calls of the name instead of the registered overloads
+    pub fn register_builder<S: ToString>(&mut self, name: S, builder: Arc<dyn FunctionBuilder>) {
+        self.builders.insert(name.to_string().to_lowercase(), builder);
+    }
+
+    pub fn builder(&self, name: &str) -> Option<Arc<dyn FunctionBuilder>> {
+        let name = name.to_lowercase();
+        self.builders.get(name.trim_start_matches("pg_catalog.")).cloned()
+    }
+
+    /// registered function replaces a function with the same name and argument types
+    pub fn register<S: ToString>(&mut self, name: S, function: Arc<dyn ScalarFunction>) {
+        let overloads = self.functions.entry(name.to_string().to_lowercase()).or_default();
+        overloads.retain(|overload| overload.argument_types() != function.argument_types());
+        overloads.push(function);
+    }
+
+    /// names could be qualified with `pg_catalog` schema
+    pub fn lookup(&self, name: &str) -> Option<Arc<dyn ScalarFunction>> {
+        self.overloads(name).first().cloned()
+    }
+
+    /// overload that accepts the arguments with the cheapest implicit casts, or any overload to report that
+    /// arguments don't match
+    pub fn resolve(&self, name: &str, args: &[(ScalarType, bool)]) -> Option<Arc<dyn ScalarFunction>> {
+        let overloads = self.overloads(name);
+        overloads
+            .iter()
+            .filter_map(|overload| Some((casts_cost(overload.argument_types(), args)?, overload)))
+            .min_by_key(|(cost, _)| *cost)
+            .map(|(_, overload)| overload)
+            .or_else(|| overloads.first())
+            .cloned()
+    }
+
+    fn overloads(&self, name: &str) -> &[Arc<dyn ScalarFunction>] {
+        let name = name.to_lowercase();
+        self.functions
+            .get(name.trim_start_matches("pg_catalog."))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for FunctionRegistry {
+    fn default() -> FunctionRegistry {
+        let mut registry = FunctionRegistry::empty();
+        registry.register("now", Arc::new(Now));
+        registry.register("random", Arc::new(Random));
+        registry.register("setseed", Arc::new(SetSeed));
+        registry.register_builder("concat", Arc::new(ConcatBuilder));
+        registry.register_builder("array", Arc::new(ArrayBuilder));
+        for (name, case_insensitive, negated) in &[
+            ("textregexeq", false, false),
+            ("texticregexeq", true, false),
+            ("textregexne", false, true),
+            ("texticregexne", true, true),
+        ] {
+            registry.register_builder(name, Arc::new(RegexMatchBuilder(*case_insensitive, *negated)));
+        }
+        for (name, operation) in &[
+            ("current_date", DateTimeOperation::CurrentDate),
+            ("date_part", DateTimeOperation::DatePart),
+            ("date_trunc", DateTimeOperation::DateTrunc),
+            ("age", DateTimeOperation::Age),
+        ] {
+            for function in DateTimeFunction::overloads(*operation) {
+                registry.register(name, Arc::new(function));
+            }
+        }
+        for (name, operation) in &[
+            ("length", StringOperation::Length),
+            ("char_length", StringOperation::Length),
+            ("upper", StringOperation::Upper),
+            ("lower", StringOperation::Lower),
+            ("substring", StringOperation::Substring),
+            ("substr", StringOperation::Substring),
+            ("trim", StringOperation::Trim(TrimSide::Both)),
+            ("btrim", StringOperation::Trim(TrimSide::Both)),
+            ("ltrim", StringOperation::Trim(TrimSide::Leading)),
+            ("rtrim", StringOperation::Trim(TrimSide::Trailing)),
+            ("replace", StringOperation::Replace),
+            ("strpos", StringOperation::Position),
+            ("similar_to_escape", StringOperation::SimilarToEscape),
+        ] {
+            for function in StringFunction::overloads(*operation) {
+                registry.register(name, Arc::new(function));
+            }
+        }
+        for (name, operation) in &[
+            ("abs", MathOperation::Abs),
+            ("round", MathOperation::Round),
+            ("ceil", MathOperation::Ceil),
+            ("ceiling", MathOperation::Ceil),
+            ("floor", MathOperation::Floor),
+            ("mod", MathOperation::Mod),
+            ("power", MathOperation::Power),
+            ("pow", MathOperation::Power),
+            ("sqrt", MathOperation::Sqrt),
+            ("exp", MathOperation::Exp),
+            ("ln", MathOperation::Ln),
+        ] {
+            for function in MathFunction::overloads(*operation) {
+                registry.register(name, Arc::new(function));
+            }
+        }
+        for prefix in &["json", "jsonb"] {
+            for (name, step, as_text) in &[
+                ("object_field", JsonStep::Field, false),
+                ("object_field_text", JsonStep::Field, true),
+                ("array_element", JsonStep::Element, false),
+                ("array_element_text", JsonStep::Element, true),
+                ("extract_path", JsonStep::Path, false),
+                ("extract_path_text", JsonStep::Path, true),
+            ] {
+                registry.register(
+                    format!("{}_{}", prefix, name),
+                    Arc::new(JsonExtract::new(*step, *as_text)),
+                );
+            }
+        }
+        registry
+    }
+}
+
+/// total cost of implicit casts of arguments to the declared types, NULLs are casted to any type
+fn casts_cost(argument_types: &[ScalarType], args: &[(ScalarType, bool)]) -> Option<usize> {
+    if argument_types.len() != args.len() {
+        return None;
+    }
+    argument_types
+        .iter()
+        .zip(args.iter())
+        .map(|(expected, (actual, is_null))| {
+            if *is_null {
+                Some(0)
+            } else {
+                implicit_cast_cost(*actual, *expected)
+            }
+        })
+        .sum()
+}
+
+/// function resolved for a call with the session it is called in
+#[derive(Clone)]
+pub struct FunctionCall {
+    name: String,
+    function: Arc<dyn ScalarFunction>,
+    context: FunctionContext,
+}
+
+impl FunctionCall {
+    pub(crate) fn new(name: String, function: Arc<dyn ScalarFunction>, context: FunctionContext) -> FunctionCall {
+        FunctionCall {
+            name,
+            function,
+            context,
+        }
+    }
+
+    /// NULLs and arguments implicitly casted to the declared types are accepted
+    pub(crate) fn accepts(&self, args: &[(ScalarType, bool)]) -> bool {
+        casts_cost(self.function.argument_types(), args).is_some()
+    }
+
+    pub(crate) fn return_type(&self) -> ScalarType {
+        self.function.return_type()
+    }
+
+    pub(crate) fn volatility(&self) -> Volatility {
+        self.function.volatility()
+    }
+
+    pub(crate) fn call(&self, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        if self.function.is_strict() && args.iter().any(Datum::is_null) {
+            Ok(Datum::from_null())
+        } else {
+            let args = args
+                .iter()
+                .zip(self.function.argument_types().iter())
+                .map(|(arg, ty)| cast(arg.clone(), *ty))
+                .collect::<Vec<_>>();
+            self.function.call(&self.context, &args)
+        }
+    }
+}
+
+impl Debug for FunctionCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "FunctionCall({})", self.name)
+    }
+}
+
+impl PartialEq for FunctionCall {
+    fn eq(&self, other: &FunctionCall) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for FunctionCall {}
+
+/// `now()` and `current_timestamp` are read from the session clock
+struct Now;
+
+impl ScalarFunction for Now {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::String
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Stable
+    }
+
+    fn call(&self, context: &FunctionContext, _args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        Ok(Datum::from_string(clock::render_timestamp_with_time_zone(
+            clock::unix_micros(context.clock().now()),
+            context.utc_offset(),
+        )))
+    }
+}
+
+struct Random;
+
+impl ScalarFunction for Random {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Float64
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Volatile
+    }
+
+    fn call(&self, context: &FunctionContext, _args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        Ok(Datum::from_f64(context.random()))
+    }
+}
+
+struct SetSeed;
+
+impl ScalarFunction for SetSeed {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[ScalarType::Float64]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::String
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Volatile
+    }
+
+    fn call(&self, context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let seed = args[0].as_f64();
+        if seed >= -1.0 && seed <= 1.0 {
+            context.set_seed(seed);
+            Ok(Datum::from_string(String::new()))
+        } else {
+            Err(QueryError::numeric_value_out_of_range(format!(
+                "setseed parameter {} is out of allowed range [-1,1]",
+                seed
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TrimSide {
+    Leading,
+    Trailing,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StringOperation {
+    Length,
+    Upper,
+    Lower,
+    /// characters from a position, the first one is 1, up to an optional count
+    Substring,
+    /// characters of the second argument, spaces by default, are removed from the side of the string
+    Trim(TrimSide),
+    Replace,
+    /// position of the first occurrence of a substring, 0 if there is none
+    Position,
+    /// regular expression that `SIMILAR TO` pattern is converted into
+    SimilarToEscape,
+}
+
+/// string functions work with characters rather than bytes
+struct StringFunction {
+    operation: StringOperation,
+    argument_types: Vec<ScalarType>,
+}
+
+impl StringFunction {
+    fn overloads(operation: StringOperation) -> Vec<StringFunction> {
+        let signatures = match operation {
+            StringOperation::Length
+            | StringOperation::Upper
+            | StringOperation::Lower
+            | StringOperation::SimilarToEscape => vec![vec![ScalarType::String]],
+            StringOperation::Substring => vec![
+                vec![ScalarType::String, ScalarType::Int64],
+                vec![ScalarType::String, ScalarType::Int64, ScalarType::Int64],
+            ],
+            StringOperation::Trim(_) => vec![vec![ScalarType::String], vec![ScalarType::String, ScalarType::String]],
+            StringOperation::Replace => vec![vec![ScalarType::String, ScalarType::String, ScalarType::String]],
+            StringOperation::Position => vec![vec![ScalarType::String, ScalarType::String]],
+        };
+        signatures
+            .into_iter()
+            .map(|argument_types| StringFunction {
+                operation,
+                argument_types,
+            })
+            .collect()
+    }
+}
+
+impl ScalarFunction for StringFunction {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        match self.operation {
+            StringOperation::Length | StringOperation::Position => ScalarType::Int32,
+            _ => ScalarType::String,
+        }
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let string = args[0].to_string();
+        match self.operation {
+            StringOperation::Length => Ok(Datum::from_i32(string.chars().count() as i32)),
+            StringOperation::Upper => Ok(Datum::from_string(string.to_uppercase())),
+            StringOperation::Lower => Ok(Datum::from_string(string.to_lowercase())),
+            StringOperation::Substring => {
+                let start = args[1].as_i64();
+                let end = match args.get(2).map(Datum::as_i64) {
+                    Some(count) if count < 0 => {
+                        return Err(QueryError::substring_error("negative substring length not allowed"))
+                    }
+                    Some(count) => start.saturating_add(count),
+                    None => i64::MAX,
+                };
+                let skip = start.max(1) - 1;
+                let take = (end.max(1) - 1).saturating_sub(skip);
+                Ok(Datum::from_string(
+                    string.chars().skip(skip as usize).take(take as usize).collect(),
+                ))
+            }
+            StringOperation::Trim(side) => {
+                let characters = args.get(1).map(Datum::to_string).unwrap_or_else(|| " ".to_owned());
+                let is_trimmed = |c: char| characters.contains(c);
+                let trimmed = match side {
+                    TrimSide::Leading => string.trim_start_matches(is_trimmed),
+                    TrimSide::Trailing => string.trim_end_matches(is_trimmed),
+                    TrimSide::Both => string.trim_matches(is_trimmed),
+                };
+                Ok(Datum::from_string(trimmed.to_owned()))
+            }
+            StringOperation::Replace => {
+                let from = args[1].to_string();
+                if from.is_empty() {
+                    Ok(Datum::from_string(string))
+                } else {
+                    Ok(Datum::from_string(
+                        string.replace(from.as_str(), args[2].to_string().as_str()),
+                    ))
+                }
+            }
+            StringOperation::Position => {
+                let position = string
+                    .find(args[1].to_string().as_str())
+                    .map(|index| string[..index].chars().count() + 1)
+                    .unwrap_or(0);
+                Ok(Datum::from_i32(position as i32))
+            }
+            StringOperation::SimilarToEscape => Ok(Datum::from_string(similar_to_regex(string.as_str()))),
+        }
+    }
+}
+
+/// `SIMILAR TO` pattern matches a whole string, `%` and `_` are its wildcards, `\` escapes the next character and
+/// `.`, `^` and `$` are not special
+fn similar_to_regex(pattern: &str) -> String {
+    let mut regex = "^(?:".to_owned();
+    let mut in_brackets = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => regex.push_str(&regex::escape(&chars.next().unwrap_or('\\').to_string())),
+            ']' if in_brackets => {
+                in_brackets = false;
+                regex.push(c);
+            }
+            _ if in_brackets => regex.push(c),
+            '[' => {
+                in_brackets = true;
+                regex.push(c);
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '.' | '^' | '$' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    regex.push_str(")$");
+    regex
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MathOperation {
+    Abs,
+    /// half away from zero, to an optional number of decimal places
+    Round,
+    Ceil,
+    Floor,
+    Mod,
+    Power,
+    Sqrt,
+    Exp,
+    Ln,
+}
+
+/// `abs` and `mod` keep integer types of their arguments, other math functions compute with double precision
+struct MathFunction {
+    operation: MathOperation,
+    argument_types: Vec<ScalarType>,
+}
+
+impl MathFunction {
+    fn overloads(operation: MathOperation) -> Vec<MathFunction> {
+        let float = ScalarType::Float64;
+        let signatures = match operation {
+            MathOperation::Abs => vec![
+                vec![ScalarType::Int16],
+                vec![ScalarType::Int32],
+                vec![ScalarType::Int64],
+                vec![ScalarType::Float32],
+                vec![float],
+            ],
+            MathOperation::Mod => vec![
+                vec![ScalarType::Int16, ScalarType::Int16],
+                vec![ScalarType::Int32, ScalarType::Int32],
+                vec![ScalarType::Int64, ScalarType::Int64],
+                vec![float, float],
+            ],
+            MathOperation::Round => vec![vec![float], vec![float, ScalarType::Int64]],
+            MathOperation::Power => vec![vec![float, float]],
+            MathOperation::Ceil
+            | MathOperation::Floor
+            | MathOperation::Sqrt
+            | MathOperation::Exp
+            | MathOperation::Ln => {
+                vec![vec![float]]
+            }
+        };
+        signatures
+            .into_iter()
+            .map(|argument_types| MathFunction {
+                operation,
+                argument_types,
+            })
+            .collect()
+    }
+
+    /// integer result of the argument type
+    fn integer(&self, value: Option<i64>) -> Result<Datum<'static>, QueryError> {
+        let (datum, message) = match self.argument_types[0] {
+            ScalarType::Int16 => (
+                value.and_then(|value| i16::try_from(value).ok()).map(Datum::from_i16),
+                "smallint out of range",
+            ),
+            ScalarType::Int32 => (
+                value.and_then(|value| i32::try_from(value).ok()).map(Datum::from_i32),
+                "integer out of range",
+            ),
+            _ => (value.map(Datum::from_i64), "bigint out of range"),
+        };
+        datum.ok_or_else(|| QueryError::numeric_value_out_of_range(message))
+    }
+
+    /// result of the argument type, infinite results of finite arguments overflow and zero results underflow
+    /// unless the arguments could give zero
+    fn float(&self, result: f64, args: &[f64], zero_is_valid: bool) -> Result<Datum<'static>, QueryError> {
+        if result.is_infinite() && args.iter().all(|arg| arg.is_finite()) {
+            Err(QueryError::numeric_value_out_of_range("value out of range: overflow"))
+        } else if result == 0.0 && !zero_is_valid {
+            Err(QueryError::numeric_value_out_of_range("value out of range: underflow"))
+        } else if self.argument_types[0] == ScalarType::Float32 {
+            Ok(Datum::from_f32(result as f32))
+        } else {
+            Ok(Datum::from_f64(result))
+        }
+    }
+}
+
+impl ScalarFunction for MathFunction {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        self.argument_types[0]
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let is_integer = self.argument_types[0].is_integer();
+        let integer_arg = |index: usize| integer(&args[index]).unwrap_or_default();
+        let numeric_arg = |index: usize| numeric(&args[index]).unwrap_or_default();
+        let value = numeric_arg(0);
+        match self.operation {
+            MathOperation::Abs if is_integer => self.integer(integer_arg(0).checked_abs()),
+            MathOperation::Abs => self.float(value.abs(), &[value], true),
+            MathOperation::Mod if is_integer => match integer_arg(1) {
+                0 => Err(QueryError::division_by_zero()),
+                // the only overflowing case is `MIN % -1` which is zero
+                divisor => self.integer(Some(integer_arg(0).checked_rem(divisor).unwrap_or(0))),
+            },
+            MathOperation::Mod => match numeric_arg(1) {
+                divisor if divisor == 0.0 => Err(QueryError::division_by_zero()),
+                divisor => self.float(value % divisor, &[value, divisor], true),
+            },
+            MathOperation::Round => {
+                let places = args.get(1).and_then(integer).unwrap_or(0).max(-308).min(308) as i32;
+                let scale = 10f64.powi(places);
+                let rounded = if places == 0 {
+                    value.round()
+                } else {
+                    (value * scale).round() / scale
+                };
+                self.float(if rounded.is_finite() { rounded } else { value }, &[value], true)
+            }
+            MathOperation::Ceil => self.float(value.ceil(), &[value], true),
+            MathOperation::Floor => self.float(value.floor(), &[value], true),
+            MathOperation::Power => {
+                let exponent = numeric_arg(1);
+                if value == 0.0 && exponent < 0.0 {
+                    Err(QueryError::invalid_argument_for_power_function(
+                        "zero raised to a negative power is undefined",
+                    ))
+                } else if value < 0.0 && exponent.fract() != 0.0 {
+                    Err(QueryError::invalid_argument_for_power_function(
+                        "a negative number raised to a non-integer power yields a complex result",
+                    ))
+                } else {
+                    self.float(value.powf(exponent), &[value, exponent], value == 0.0)
+                }
+            }
+            MathOperation::Sqrt if value < 0.0 => Err(QueryError::invalid_argument_for_power_function(
+                "cannot take square root of a negative number",
+            )),
+            MathOperation::Sqrt => self.float(value.sqrt(), &[value], value == 0.0),
+            MathOperation::Exp => self.float(value.exp(), &[value], value.is_infinite()),
+            MathOperation::Ln if value == 0.0 => Err(QueryError::invalid_argument_for_logarithm(
+                "cannot take logarithm of zero",
+            )),
+            MathOperation::Ln if value < 0.0 => Err(QueryError::invalid_argument_for_logarithm(
+                "cannot take logarithm of a negative number",
+            )),
+            MathOperation::Ln => self.float(value.ln(), &[value], value == 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DateTimeOperation {
+    CurrentDate,
+    DatePart,
+    DateTrunc,
+    Age,
+}
+
+/// timestamps are taken as `timestamp with time zone` values or as text, e.g. results of `now()`, their fields are
+/// computed in the session time zone, `date_trunc` keeps the type of its argument
+struct DateTimeFunction {
+    operation: DateTimeOperation,
+    argument_types: Vec<ScalarType>,
+}
+
+impl DateTimeFunction {
+    fn overloads(operation: DateTimeOperation) -> Vec<DateTimeFunction> {
+        let timestamps = [ScalarType::Timestamp, ScalarType::String];
+        let signatures = match operation {
+            DateTimeOperation::CurrentDate => vec![vec![]],
+            DateTimeOperation::DatePart | DateTimeOperation::DateTrunc => timestamps
+                .iter()
+                .map(|timestamp| vec![ScalarType::String, *timestamp])
+                .collect(),
+            DateTimeOperation::Age => timestamps
+                .iter()
+                .map(|timestamp| vec![*timestamp])
+                .chain(
+                    timestamps
+                        .iter()
+                        .flat_map(|left| timestamps.iter().map(move |right| vec![*left, *right])),
+                )
+                .collect(),
+        };
+        signatures
+            .into_iter()
+            .map(|argument_types| DateTimeFunction {
+                operation,
+                argument_types,
+            })
+            .collect()
+    }
+
+    fn timestamp(datum: &Datum, utc_offset: i32) -> Result<i64, QueryError> {
+        match datum {
+            Datum::Timestamp(micros) => Ok(*micros),
+            text => {
+                let text = text.to_string();
+                clock::parse_timestamp_with_time_zone(&text, utc_offset).ok_or_else(|| {
+                    QueryError::invalid_datetime_format(format!(
+                        "invalid input syntax for type timestamp with time zone: \"{}\"",
+                        text
+                    ))
+                })
+            }
+        }
+    }
+
+    fn units_not_recognized(field: &str) -> QueryError {
+        QueryError::invalid_parameter_value(format!("timestamp with time zone units \"{}\" not recognized", field))
+    }
+}
+
+impl ScalarFunction for DateTimeFunction {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        match self.operation {
+            DateTimeOperation::CurrentDate => ScalarType::String,
+            DateTimeOperation::DatePart => ScalarType::Float64,
+            DateTimeOperation::DateTrunc => self.argument_types[1],
+            DateTimeOperation::Age => ScalarType::Interval,
+        }
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Stable
+    }
+
+    fn call(&self, context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let utc_offset = context.utc_offset();
+        let now = clock::unix_micros(context.clock().now());
+        match self.operation {
+            DateTimeOperation::CurrentDate => Ok(Datum::from_string(clock::render_date(now, utc_offset))),
+            DateTimeOperation::DatePart => {
+                let field = args[0].to_string();
+                clock::date_part(&field, Self::timestamp(&args[1], utc_offset)?, utc_offset)
+                    .map(Datum::from_f64)
+                    .ok_or_else(|| Self::units_not_recognized(&field))
+            }
+            DateTimeOperation::DateTrunc => {
+                let field = args[0].to_string();
+                let micros = clock::date_trunc(&field, Self::timestamp(&args[1], utc_offset)?, utc_offset)
+                    .ok_or_else(|| Self::units_not_recognized(&field))?;
+                if self.argument_types[1] == ScalarType::Timestamp {
+                    Ok(Datum::from_timestamp(micros))
+                } else {
+                    Ok(Datum::from_string(clock::render_timestamp_with_time_zone(
+                        micros, utc_offset,
+                    )))
+                }
+            }
+            DateTimeOperation::Age => {
+                // a single argument is subtracted from the midnight of the current date
+                let (micros, since) = match args {
+                    [since] => (clock::date_trunc("day", now, utc_offset).unwrap_or(now), since),
+                    [micros, since] => (Self::timestamp(micros, utc_offset)?, since),
+                    _ => unreachable!("age takes one or two arguments"),
+                };
+                Interval::age(micros, Self::timestamp(since, utc_offset)?, utc_offset)
+                    .map(Datum::from_interval)
+                    .ok_or_else(|| QueryError::datetime_value_out_of_range("interval out of range"))
+            }
+        }
+    }
+}
+
+/// `concat(<values>)` joins text representations of its arguments, NULLs are ignored
+struct Concat {
+    argument_types: Vec<ScalarType>,
+}
+
+/// `concat` takes any number of arguments of any types
+struct ConcatBuilder;
+
+impl FunctionBuilder for ConcatBuilder {
+    fn build(&self, args: &[(ScalarType, bool)]) -> Result<Arc<dyn ScalarFunction>, QueryError> {
+        Ok(Arc::new(Concat {
+            argument_types: args.iter().map(|(ty, _is_null)| *ty).collect(),
+        }))
+    }
+}
+
+impl ScalarFunction for Concat {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::String
+    }
+
+    fn is_strict(&self) -> bool {
+        false
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        Ok(Datum::from_string(
+            args.iter()
+                .filter(|arg| !arg.is_null())
+                .map(Datum::to_string)
+                .collect::<String>(),
+        ))
+    }
+}
+
+/// `~`, `~*`, `!~` and `!~*` operators, patterns are compiled once for all rows of a statement
+struct RegexMatch {
+    case_insensitive: bool,
+    negated: bool,
+    compiled: Mutex<HashMap<String, Regex>>,
+}
+
+/// every call of a regular expression operator has its own cache of compiled patterns
+struct RegexMatchBuilder(bool, bool);
+
+impl FunctionBuilder for RegexMatchBuilder {
+    fn build(&self, _args: &[(ScalarType, bool)]) -> Result<Arc<dyn ScalarFunction>, QueryError> {
+        let RegexMatchBuilder(case_insensitive, negated) = *self;
+        Ok(Arc::new(RegexMatch {
+            case_insensitive,
+            negated,
+            compiled: Mutex::new(HashMap::new()),
+        }))
+    }
+}
+
+impl ScalarFunction for RegexMatch {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[ScalarType::String, ScalarType::String]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Boolean
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let pattern = args[1].to_string();
+        let mut compiled = self.compiled.lock().expect("to acquire compiled regex lock");
+        if !compiled.contains_key(&pattern) {
+            let regex = RegexBuilder::new(pattern.as_str())
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .map_err(|error| {
+                    // the last line of regex errors tells what is wrong, the ones before it point at the place
+                    let message = error.to_string();
+                    QueryError::invalid_regular_expression(
+                        message.lines().last().unwrap_or_default().trim_start_matches("error: "),
+                    )
+                })?;
+            compiled.insert(pattern.clone(), regex);
+        }
+        let is_match = compiled[&pattern].is_match(args[0].to_string().as_str());
+        Ok(Datum::from_bool(is_match != self.negated))
+    }
+}
+
+/// how a part of a json value is found by `->`, `->>`, `#>` and `#>>` operators
+#[derive(Debug, Clone, Copy)]
+enum JsonStep {
+    /// field of an object by its key
+    Field,
+    /// element of an array by its index
+    Element,
+    /// nested value by a text array of keys and indexes
+    Path,
+}
+
+/// json functions return NULL when the part does not exist
+struct JsonExtract {
+    step: JsonStep,
+    as_text: bool,
+    argument_types: [ScalarType; 2],
+}
+
+impl JsonExtract {
+    fn new(step: JsonStep, as_text: bool) -> JsonExtract {
+        let key_type = match step {
+            JsonStep::Element => ScalarType::Int64,
+            JsonStep::Field | JsonStep::Path => ScalarType::String,
+        };
+        JsonExtract {
+            step,
+            as_text,
+            argument_types: [ScalarType::String, key_type],
+        }
+    }
+}
+
+impl ScalarFunction for JsonExtract {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::String
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let value = json::parse(args[0].to_string().as_str()).map_err(|message| {
+            QueryError::invalid_text_representation(format!("invalid input syntax for type json: {}", message))
+        })?;
+        let part = match self.step {
+            JsonStep::Field => value.field(args[1].to_string().as_str()),
+            JsonStep::Element => value.element(args[1].as_i64()),
+            JsonStep::Path => {
+                let path =
+                    json::parse_path(args[1].to_string().as_str()).map_err(QueryError::invalid_text_representation)?;
+                value.path(&path)
+            }
+        };
+        let text = if self.as_text {
+            part.and_then(json::JsonValue::to_text)
+        } else {
+            part.map(ToString::to_string)
+        };
+        Ok(text.map(Datum::from_string).unwrap_or_else(Datum::from_null))
+    }
+}
+
+/// `ARRAY[<elements>]`, elements are casted to their common type
+struct ArrayConstructor {
+    argument_types: Vec<ScalarType>,
+}
+
+/// `ARRAY[...]` is parsed into a call of `array`, integers and floats are widened to the type that holds them all
+struct ArrayBuilder;
+
+impl FunctionBuilder for ArrayBuilder {
+    fn build(&self, args: &[(ScalarType, bool)]) -> Result<Arc<dyn ScalarFunction>, QueryError> {
+        let mut element_type: Option<ScalarType> = None;
+        for (ty, _is_null) in args.iter().filter(|(_ty, is_null)| !is_null) {
+            let ty = *ty;
+            element_type = match element_type {
+                None => Some(ty),
+                Some(current) if current == ty => Some(current),
+                Some(current) if current.is_integer() && ty.is_integer() => Some(ScalarType::Int64),
+                Some(current) if (current.is_integer() || current.is_float()) && (ty.is_integer() || ty.is_float()) => {
+                    Some(ScalarType::Float64)
+                }
+                Some(current) => {
+                    return Err(QueryError::datatype_mismatch(format!(
+                        "ARRAY types {} and {} cannot be matched",
+                        type_name(current),
+                        type_name(ty)
+                    )))
+                }
+            };
+        }
+        Ok(Arc::new(ArrayConstructor {
+            argument_types: vec![element_type.unwrap_or(ScalarType::String); args.len()],
+        }))
+    }
+}
+
+impl ScalarFunction for ArrayConstructor {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::String
+    }
+
+    fn is_strict(&self) -> bool {
+        false
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let elements = args
+            .iter()
+            .map(|arg| if arg.is_null() { None } else { Some(arg.to_string()) })
+            .collect::<Vec<_>>();
+        Ok(Datum::from_string(array::render(&elements)))
+    }
+}
+
+/// `<array>[<index>]`, indexes start from 1 and NULL is returned for indexes out of bounds
+pub(crate) struct ArraySubscript {
+    element_type: ScalarType,
+}
+
+impl ArraySubscript {
+    pub(crate) fn new(element_type: ScalarType) -> ArraySubscript {
+        ArraySubscript { element_type }
+    }
+}
+
+impl ScalarFunction for ArraySubscript {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[ScalarType::String, ScalarType::Int64]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        self.element_type
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let elements = array::parse(args[0].to_string().as_str()).map_err(QueryError::invalid_text_representation)?;
+        let index = args[1].as_i64();
+        let element = if index < 1 {
+            None
+        } else {
+            elements.into_iter().nth(index as usize - 1).flatten()
+        };
+        match element {
+            None => Ok(Datum::from_null()),
+            Some(element) => match self.element_type {
+                ScalarType::Int16 | ScalarType::Int32 | ScalarType::Int64 => match element.parse::<i64>() {
+                    Ok(value) => Ok(cast(Datum::from_i64(value), self.element_type)),
+                    Err(_) => Err(QueryError::invalid_text_representation(format!(
+                        "invalid input syntax for type integer: \"{}\"",
+                        element
+                    ))),
+                },
+                _ => Ok(Datum::from_string(element)),
+            },
+        }
+    }
+}
+
+/// `<value> <op> ANY(<array>)` is true when the comparison is true for any element, otherwise it is NULL
+/// if any element is NULL
+pub(crate) struct ArrayAny {
+    op: BinaryOperator,
+    argument_types: [ScalarType; 2],
+}
+
+impl ArrayAny {
+    pub(crate) fn new(op: BinaryOperator, value_type: ScalarType) -> ArrayAny {
+        ArrayAny {
+            op,
+            argument_types: [value_type, ScalarType::String],
+        }
+    }
+
+    /// elements are read as values of the type of the compared value
+    fn compare(&self, value: &Datum, element: &str) -> Result<Ordering, QueryError> {
+        let invalid = |type_name: &str| {
+            QueryError::invalid_text_representation(format!(
+                "invalid input syntax for type {}: \"{}\"",
+                type_name, element
+            ))
+        };
+        match self.argument_types[0] {
+            ScalarType::Int16 | ScalarType::Int32 | ScalarType::Int64 => match element.trim().parse::<i64>() {
+                Ok(element) => Ok(cast(value.clone(), ScalarType::Int64).as_i64().cmp(&element)),
+                Err(_) => Err(invalid("integer")),
+            },
+            ScalarType::Float32 | ScalarType::Float64 => match element.trim().parse::<f64>() {
+                Ok(element) => Ok(cast(value.clone(), ScalarType::Float64)
+                    .as_f64()
+                    .partial_cmp(&element)
+                    .unwrap_or(Ordering::Equal)),
+                Err(_) => Err(invalid("double precision")),
+            },
+            ScalarType::Boolean => match parse_bool(element) {
+                Some(element) => Ok(value.as_bool().cmp(&element)),
+                None => Err(invalid("boolean")),
+            },
+            _ => Ok(value.to_string().as_str().cmp(element)),
+        }
+    }
+}
+
+impl ScalarFunction for ArrayAny {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Boolean
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let elements = array::parse(args[1].to_string().as_str()).map_err(QueryError::invalid_text_representation)?;
+        let mut has_null = false;
+        for element in elements {
+            let element = match element {
+                Some(element) => element,
+                None => {
+                    has_null = true;
+                    continue;
+                }
+            };
+            let ordering = self.compare(&args[0], &element)?;
+            let satisfied = match self.op {
+                BinaryOperator::Eq => ordering == Ordering::Equal,
+                BinaryOperator::NotEq => ordering != Ordering::Equal,
+                BinaryOperator::Lt => ordering == Ordering::Less,
+                BinaryOperator::LtEq => ordering != Ordering::Greater,
+                BinaryOperator::Gt => ordering == Ordering::Greater,
+                BinaryOperator::GtEq => ordering != Ordering::Less,
+                _ => false,
+            };
+            if satisfied {
+                return Ok(Datum::from_bool(true));
+            }
+        }
+        if has_null {
+            Ok(Datum::from_null())
+        } else {
+            Ok(Datum::from_bool(false))
+        }
+    }
+}
+
+/// function created by `CREATE FUNCTION ... LANGUAGE SQL`, its body reads arguments as columns.
+/// Like in PostgreSQL it is volatile and called on NULL arguments
+pub(crate) struct SqlBodyFunction {
+    argument_types: Vec<ScalarType>,
+    return_type: ScalarType,
+    body: ScalarOp,
+}
+
+impl SqlBodyFunction {
+    pub(crate) fn new(argument_types: Vec<ScalarType>, return_type: ScalarType, body: ScalarOp) -> SqlBodyFunction {
+        SqlBodyFunction {
+            argument_types,
+            return_type,
+            body,
+        }
+    }
+}
+
+impl ScalarFunction for SqlBodyFunction {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        self.return_type
+    }
+
+    fn is_strict(&self) -> bool {
+        false
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Volatile
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        let value = EvalScalarOp::evaluate(args, &self.body)?;
+        Ok(owned(&cast(value, self.return_type)))
+    }
+}
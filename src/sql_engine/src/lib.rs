@@ -15,61 +15,354 @@
 extern crate bigdecimal;
 extern crate log;
 
-use std::{iter, sync::Arc};
+use std::{iter, path::Path, sync::Arc};
 
 use itertools::izip;
-use sqlparser::{ast::Statement, dialect::Dialect, parser::Parser};
+use sqlparser::{
+    ast::{
+        BinaryOperator, Expr, Query, SetVariableValue, Statement, TransactionAccessMode, TransactionIsolationLevel,
+        TransactionMode, UnaryOperator, Value,
+    },
+    dialect::Dialect,
+};
 
-use data_manager::DataManager;
+use data_manager::{BackupError, DataManager, Journal, TableLock};
 use kernel::SystemResult;
 use protocol::{
+    clock::{self, Clock},
     pgsql_types::{PostgreSqlFormat, PostgreSqlType, PostgreSqlValue},
-    results::{QueryError, QueryEvent},
+    results::{Projection, QueryError, QueryEvent},
     session::Session,
     statement::PreparedStatement,
     Sender,
 };
+use representation::ScalarType;
 
 use crate::{
+    activity::{Notification, SessionActivity, SessionRegistry},
+    aggregate::{AggregateFunction, AggregateRegistry},
     ddl::{
-        create_schema::CreateSchemaCommand, create_table::CreateTableCommand, drop_schema::DropSchemaCommand,
+        alter_schema::AlterSchemaOwnerCommand,
+        analyze::AnalyzeCommand,
+        create_function::CreateFunctionCommand,
+        create_schema::CreateSchemaCommand,
+        create_sequence::CreateSequenceCommand,
+        create_statistics::CreateStatisticsCommand,
+        create_table::CreateTableCommand,
+        create_type::CreateEnumTypeCommand,
+        drop_function::DropFunctionsCommand,
+        drop_schema::DropSchemaCommand,
+        drop_sequence::DropSequencesCommand,
+        drop_statistics::DropStatisticsCommand,
         drop_table::DropTableCommand,
+        schema_privileges::{GrantSchemaPrivilegesCommand, RevokeSchemaPrivilegesCommand},
+        vacuum::VacuumCommand,
     },
-    dml::{delete::DeleteCommand, insert::InsertCommand, select::SelectCommand, update::UpdateCommand},
-    query::bind::ParamBinder,
+    dml::{
+        copy::{CopyIn, CopyOutCommand, CopySender},
+        delete::DeleteCommand,
+        index_advice::IndexAdviceCommand,
+        insert::InsertCommand,
+        select::SelectCommand,
+        select_values::SelectValuesCommand,
+        send_projection,
+        update::UpdateCommand,
+    },
+    function::{FunctionBuilder, FunctionRegistry, ScalarFunction},
+    operator::{BinaryFn, OperatorTable, UnaryFn},
+    plan_cache::{Cached, Key, PlanCache, PLAN_CACHE_CAPACITY},
+    query::bind::{self, ParamBinder},
+    trace::{StageTimings, TimedSender, TRACE},
+};
+use data_manager::SUPERUSER;
+use query_planner::{
+    extended::{self, ExtendedStatement},
+    plan::{Param, Plan, TableInserts},
+    planner::QueryPlanner,
+    TableId,
 };
-use query_planner::{plan::Plan, planner::QueryPlanner};
 
+pub mod activity;
+pub mod aggregate;
+mod catalog_queries;
 mod ddl;
 mod dml;
+pub mod function;
+pub mod operator;
+mod plan_cache;
 mod query;
+mod trace;
+
+/// session setting changed by `SET TIME ZONE` and `SET timezone`
+const TIME_ZONE: &str = "timezone";
+/// session setting of the number of rows a SELECT sends at most
+pub(crate) const MAX_RESULT_ROWS: &str = "max_result_rows";
+/// session setting of the number of bytes of values a SELECT sends at most
+pub(crate) const MAX_RESULT_SIZE: &str = "max_result_size";
 
 pub struct QueryExecutor {
     data_manager: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    timed_sender: Arc<TimedSender>,
     session: Session<Statement>,
     query_planner: QueryPlanner,
+    plan_cache: PlanCache,
     param_binder: ParamBinder,
+    aggregates: Arc<AggregateRegistry>,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    sessions: Arc<SessionRegistry>,
+    activity: Arc<SessionActivity>,
+    discard_until_sync: bool,
+    copy_in: Option<CopyIn>,
+    notifications: Vec<Notification>,
+    transaction: Option<Arc<Journal>>,
+    transaction_failed: bool,
+    lock_owner: u64,
+    /// role that the client connected as, `RESET ROLE` returns to it
+    session_user: String,
 }
 
 impl QueryExecutor {
     pub fn new(data_manager: Arc<DataManager>, sender: Arc<dyn Sender>) -> Self {
+        let timed_sender = Arc::new(TimedSender::new(sender));
+        let sender: Arc<dyn Sender> = timed_sender.clone();
+        let sessions = Arc::new(SessionRegistry::default());
+        let activity = sessions.register();
+        let lock_owner = data_manager.lock_owner();
         Self {
             data_manager: data_manager.clone(),
             sender: sender.clone(),
+            timed_sender,
             session: Session::default(),
             query_planner: QueryPlanner::new(data_manager, sender.clone()),
+            plan_cache: PlanCache::new(PLAN_CACHE_CAPACITY),
             param_binder: ParamBinder::new(sender),
+            aggregates: Arc::new(AggregateRegistry::default()),
+            functions: Arc::new(FunctionRegistry::default()),
+            operators: Arc::new(OperatorTable::default()),
+            sessions,
+            activity,
+            discard_until_sync: false,
+            copy_in: None,
+            notifications: vec![],
+            transaction: None,
+            transaction_failed: false,
+            lock_owner,
+            session_user: SUPERUSER.to_owned(),
         }
     }
 
+    /// statements of the session are run as the role that the client connected as
+    pub fn set_session_user(&mut self, user: &str) {
+        self.session_user = user.to_owned();
+        self.query_planner.set_role(user);
+    }
+
+    /// the session leaves its current registry and is listed in `pg_stat_activity` of the given one
+    pub fn set_session_registry(&mut self, sessions: Arc<SessionRegistry>) {
+        let activity = sessions.register();
+        self.set_session(sessions, activity);
+    }
+
+    /// the session takes the activity that is already registered in the given registry, e.g. when
+    /// its process id and secret key were sent to the client before the session was created
+    pub fn set_session(&mut self, sessions: Arc<SessionRegistry>, activity: Arc<SessionActivity>) {
+        self.sessions.unregister(self.activity.pid());
+        self.activity = activity;
+        self.sessions = sessions;
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.activity.pid()
+    }
+
+    pub fn secret_key(&self) -> u32 {
+        self.activity.secret_key()
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.session.set_clock(clock);
+    }
+
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.session.set_random_seed(seed);
+    }
+
+    /// makes the aggregate available for queries executed after the call
+    pub fn register_aggregate<S: ToString>(&mut self, name: S, function: Arc<dyn AggregateFunction>) {
+        Arc::make_mut(&mut self.aggregates).register(name, function);
+    }
+
+    /// makes the scalar function available for queries executed after the call
+    pub fn register_function<S: ToString>(&mut self, name: S, function: Arc<dyn ScalarFunction>) {
+        Arc::make_mut(&mut self.functions).register(name, function);
+    }
+
+    /// makes the function built for argument types of every call available for queries executed after the call
+    pub fn register_function_builder<S: ToString>(&mut self, name: S, builder: Arc<dyn FunctionBuilder>) {
+        Arc::make_mut(&mut self.functions).register_builder(name, builder);
+    }
+
+    /// makes the operator implementation available for queries executed after the call
+    pub fn register_binary_operator(
+        &mut self,
+        operator: BinaryOperator,
+        left: ScalarType,
+        right: ScalarType,
+        result: ScalarType,
+        function: BinaryFn,
+    ) {
+        Arc::make_mut(&mut self.operators).register_binary(operator, left, right, result, function);
+    }
+
+    /// makes the operator implementation available for queries executed after the call
+    pub fn register_unary_operator(
+        &mut self,
+        operator: UnaryOperator,
+        operand: ScalarType,
+        result: ScalarType,
+        function: UnaryFn,
+    ) {
+        Arc::make_mut(&mut self.operators).register_unary(operator, operand, result, function);
+    }
+
+    /// statements of the query are run one by one and the query completes once after all of them,
+    /// statements after a failed one or after `COPY ... FROM STDIN` are not run
     pub fn execute(&mut self, raw_sql_query: &str) -> SystemResult<()> {
-        match Parser::parse_sql(&PreparedStatementDialect {}, raw_sql_query) {
-            Ok(mut statements) => {
-                log::info!("stmts: {:#?}", statements);
-                let statement = statements.pop().unwrap();
-                self.process_statement(raw_sql_query, statement)?;
+        let statements = extended::split_statements(raw_sql_query);
+        if statements.is_empty() {
+            self.sender
+                .send(Ok(QueryEvent::QueryEmpty))
+                .expect("To Send Query Result to Client");
+        }
+        for statement in statements {
+            let errors = self.timed_sender.errors();
+            self.activity.start(statement);
+            let result = self.execute_query(statement);
+            self.activity.finish();
+            result?;
+            if self.timed_sender.errors() != errors {
+                self.fail_transaction();
+                break;
+            }
+            if self.copy_in.is_some() {
+                break;
+            }
+        }
+        self.commit_notifications();
+
+        if self.copy_in.is_none() {
+            self.deliver_notifications();
+            self.sender
+                .send(Ok(self.ready_for_query()))
+                .expect("To Send Query Complete Event to Client");
+        }
+
+        Ok(())
+    }
+
+    /// runs already parsed statements skipping the parsing of raw queries, results of each statement
+    /// end with `QueryComplete`, statements after the first failed one are not run.
+    /// Returns the number of statements that succeeded
+    pub fn execute_batch(&mut self, statements: &[Statement]) -> SystemResult<usize> {
+        let mut succeeded = 0;
+        for statement in statements {
+            let raw_sql_query = format!("{}", statement);
+            let errors = self.timed_sender.errors();
+            let mut timings = StageTimings::default();
+            self.activity.start(&raw_sql_query);
+            let result = if self.rejected_by_failed_transaction(&raw_sql_query) {
+                Ok(())
+            } else {
+                self.process_statement(&raw_sql_query, statement.clone(), &mut timings)
+            };
+            self.activity.finish();
+            result?;
+            self.report_timings(&timings);
+            let failed = self.timed_sender.errors() != errors;
+            if failed {
+                self.fail_transaction();
+            }
+            self.sender
+                .send(Ok(self.ready_for_query()))
+                .expect("To Send Query Complete Event to Client");
+            if failed {
+                break;
             }
+            succeeded += 1;
+        }
+        Ok(succeeded)
+    }
+
+    fn execute_query(&mut self, raw_sql_query: &str) -> SystemResult<()> {
+        if self.rejected_by_failed_transaction(raw_sql_query) {
+            return Ok(());
+        }
+        let projection = activity::answer(&self.sessions, raw_sql_query)
+            .or_else(|| catalog_queries::answer(&self.data_manager, raw_sql_query));
+        if let Some(projection) = projection {
+            send_projection(self.sender.as_ref(), projection);
+            return Ok(());
+        }
+
+        let mut timings = StageTimings::default();
+        match timings.parse(|| extended::parse(&PreparedStatementDialect {}, raw_sql_query)) {
+            Ok(Some(statement)) => {
+                log::info!("extended stmt: {:#?}", statement);
+                self.process_extended_statement(raw_sql_query, statement, &mut timings)?;
+            }
+            Ok(None) => self.process_query(raw_sql_query, &mut timings)?,
+            Err(e) => {
+                log::error!("{:?} can't be parsed. Error: {:?}", raw_sql_query, e);
+                self.sender
+                    .send(Err(QueryError::syntax_error(format!(
+                        "{:?} can't be parsed",
+                        raw_sql_query
+                    ))))
+                    .expect("To Send Query Result to Client");
+            }
+        };
+        self.report_timings(&timings);
+        Ok(())
+    }
+
+    /// plans of single `SELECT`, `INSERT`, `UPDATE` and `DELETE` statements are taken from the plan cache, statements
+    /// that differ from a cached one only by literals are not parsed again and their literals are bound to its plan
+    fn process_query(&mut self, raw_sql_query: &str, timings: &mut StageTimings) -> SystemResult<()> {
+        // tables created or dropped by the transaction are seen differently by other sessions
+        let defines_tables = self
+            .transaction
+            .as_ref()
+            .map_or(false, |journal| journal.defines_tables());
+        let normalized = if defines_tables {
+            None
+        } else {
+            timings
+                .parse(|| plan_cache::normalize(raw_sql_query))
+                .map(|(text, literals)| ((text, self.query_planner.role().to_owned()), literals))
+        };
+        let cached = match &normalized {
+            Some((key, _)) => self.plan_cache.lookup(key, &self.data_manager),
+            None => Cached::NotCacheable,
+        };
+        let missing = matches!(cached, Cached::Missing);
+        match (cached, &normalized) {
+            (Cached::Plan(generic), Some((_, literals))) => {
+                let plan = timings.plan(|| {
+                    self.query_planner
+                        .bind(&generic, |expr| bind::bind_expr(expr, literals))
+                });
+                return timings.execute(&self.timed_sender.clone(), || self.execute_plan(raw_sql_query, plan));
+            }
+            (Cached::Template(template), Some((key, literals))) => {
+                return self.process_cacheable(raw_sql_query, key, literals, &template, timings);
+            }
+            _ => {}
+        }
+
+        let statements = match timings.parse(|| extended::parse_sql(&PreparedStatementDialect {}, raw_sql_query)) {
+            Ok(statements) => statements,
             Err(e) => {
                 log::error!("{:?} can't be parsed. Error: {:?}", raw_sql_query, e);
                 self.sender
@@ -78,23 +371,143 @@ impl QueryExecutor {
                         raw_sql_query
                     ))))
                     .expect("To Send Query Result to Client");
+                return Ok(());
             }
         };
+        log::info!("stmts: {:#?}", statements);
+        if let (true, Some((key, literals)), [statement]) = (missing, &normalized, statements.as_slice()) {
+            let template = plan_cache::template(&key.0, literals, statement);
+            self.plan_cache.insert_template(key.clone(), template.clone());
+            if let Some(template) = template {
+                return self.process_cacheable(raw_sql_query, key, literals, &template, timings);
+            }
+        }
+        for statement in statements {
+            self.process_statement(raw_sql_query, statement, timings)?;
+        }
+        Ok(())
+    }
+
+    /// generic plan of the template is kept in the plan cache when binding the literals to it gives the plan of the
+    /// statement
+    fn process_cacheable(
+        &mut self,
+        raw_sql_query: &str,
+        key: &Key,
+        literals: &[Value],
+        template: &Statement,
+        timings: &mut StageTimings,
+    ) -> SystemResult<()> {
+        let mut statement = template.clone();
+        bind::bind_literals(&mut statement, literals);
+        log::debug!("STATEMENT = {:?}", statement);
+        let plan = timings.plan(|| self.query_planner.plan(statement));
+        if let Ok(plan) = &plan {
+            let bind = |expr: &mut Expr| bind::bind_expr(expr, literals);
+            if let Some(generic) = self.query_planner.plan_generic(template, bind, plan) {
+                self.plan_cache.insert_plan(key, generic, plan, &self.data_manager);
+            }
+        }
+        timings.execute(&self.timed_sender.clone(), || self.execute_plan(raw_sql_query, plan))
+    }
+
+    /// rows of `COPY ... FROM STDIN` are collected until the client ends copying, data sent when the
+    /// session does not copy anything is ignored
+    pub fn copy_data(&mut self, data: &[u8]) {
+        if let Some(copy_in) = self.copy_in.as_mut() {
+            copy_in.append(data);
+        }
+    }
 
+    /// rows sent by the client since `COPY ... FROM STDIN` are inserted into the table
+    pub fn copy_done(&mut self) -> SystemResult<()> {
+        let copy_in = match self.copy_in.take() {
+            Some(copy_in) => copy_in,
+            None => return Ok(()),
+        };
+        let errors = self.timed_sender.errors();
+        self.activity.start(copy_in.query());
+        let result = self.copy_rows(copy_in);
+        self.activity.finish();
+        if self.timed_sender.errors() != errors {
+            self.fail_transaction();
+        }
         self.sender
-            .send(Ok(QueryEvent::QueryComplete))
+            .send(Ok(self.ready_for_query()))
             .expect("To Send Query Complete Event to Client");
+        result
+    }
 
-        Ok(())
+    /// rows sent by the client are discarded, none of them is inserted
+    pub fn copy_fail(&mut self, message: &str) {
+        if self.copy_in.take().is_some() {
+            self.sender
+                .send(Err(QueryError::copy_from_stdin_failed(message)))
+                .expect("To Send Query Result to Client");
+            self.fail_transaction();
+            self.sender
+                .send(Ok(self.ready_for_query()))
+                .expect("To Send Query Complete Event to Client");
+        }
+    }
+
+    fn copy_rows(&self, copy_in: CopyIn) -> SystemResult<()> {
+        let columns = self.copied_columns(copy_in.table_inserts())?;
+        match copy_in.into_inserts(columns) {
+            Ok(table_inserts) => {
+                self.enter_modification("Copy", &table_inserts.table_id);
+                if !self.lock_table(&table_inserts.table_id) {
+                    return Ok(());
+                }
+                let journal = self.write_journal();
+                let copied = InsertCommand::new(
+                    table_inserts,
+                    self.data_manager.clone(),
+                    Arc::new(CopySender(self.sender.clone())),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .with_journal(journal.clone())
+                .execute();
+                self.unlock_tables(journal)?;
+                copied
+            }
+            Err(error) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                Ok(())
+            }
+        }
+    }
+
+    /// all columns of the table are copied when the statement does not list them
+    fn copied_columns(&self, table_inserts: &TableInserts) -> SystemResult<usize> {
+        if table_inserts.column_indices.is_empty() {
+            Ok(self.data_manager.table_columns(&table_inserts.table_id)?.len())
+        } else {
+            Ok(table_inserts.column_indices.len())
+        }
     }
 
     pub fn parse_prepared_statement(
         &mut self,
         statement_name: &str,
         raw_sql_query: &str,
-        param_types: &[PostgreSqlType],
+        param_types: &[Option<PostgreSqlType>],
     ) -> SystemResult<()> {
-        let statement = match Parser::parse_sql(&PreparedStatementDialect {}, raw_sql_query) {
+        self.extended_message(|executor| executor.parse(statement_name, raw_sql_query, param_types))
+    }
+
+    fn parse(
+        &mut self,
+        statement_name: &str,
+        raw_sql_query: &str,
+        param_types: &[Option<PostgreSqlType>],
+    ) -> SystemResult<()> {
+        if self.rejected_by_failed_transaction(raw_sql_query) {
+            return Ok(());
+        }
+        let statement = match extended::parse_sql(&PreparedStatementDialect {}, raw_sql_query) {
             Ok(mut statements) => {
                 log::info!("stmts: {:#?}", statements);
                 statements.pop().unwrap()
@@ -111,14 +524,43 @@ impl QueryExecutor {
             }
         };
 
-        let description = match self.query_planner.plan(statement.clone()) {
-            Ok(Plan::Select(select_input)) => {
-                SelectCommand::new(select_input, self.data_manager.clone(), self.sender.clone()).describe()?
-            }
-            _ => vec![],
+        let (plan, params) = match self.plan_prepared(&statement) {
+            Some(planned) => planned,
+            None => return Ok(()),
+        };
+        let (description, origins) = match plan {
+            Some(Plan::Select(select_input)) => SelectCommand::new(
+                select_input,
+                self.data_manager.clone(),
+                self.sender.clone(),
+                self.aggregates.clone(),
+                self.functions.clone(),
+                self.operators.clone(),
+                self.session.function_context(),
+                self.activity.clone(),
+            )
+            .describe()?,
+            _ => (vec![], vec![]),
         };
 
-        let prepared_statement = PreparedStatement::new(statement, param_types.to_vec(), description);
+        // parameters without specified types take types of columns they are assigned to or compared with,
+        // the rest are sent as text
+        let param_types = (0..param_types.len().max(bind::referenced_params(&statement)))
+            .map(|index| {
+                param_types
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .or_else(|| {
+                        params
+                            .iter()
+                            .find(|param| param.index == index)
+                            .map(|param| (&param.sql_type).into())
+                    })
+                    .unwrap_or(PostgreSqlType::VarChar)
+            })
+            .collect::<Vec<PostgreSqlType>>();
+        let prepared_statement = PreparedStatement::new(statement, param_types, description, origins);
         self.session
             .set_prepared_statement(statement_name.to_owned(), prepared_statement);
 
@@ -130,12 +572,17 @@ impl QueryExecutor {
     }
 
     pub fn describe_prepared_statement(&mut self, name: &str) -> SystemResult<()> {
+        self.extended_message(|executor| executor.describe_statement(name))
+    }
+
+    fn describe_statement(&mut self, name: &str) -> SystemResult<()> {
         match self.session.get_prepared_statement(name) {
             Some(stmt) => {
                 self.sender
                     .send(Ok(QueryEvent::PreparedStatementDescribed(
                         stmt.param_types().to_vec(),
                         stmt.description().to_vec(),
+                        stmt.origins().to_vec(),
                     )))
                     .expect("To Send ParametersDescribed Event");
             }
@@ -156,6 +603,19 @@ impl QueryExecutor {
         param_formats: &[PostgreSqlFormat],
         raw_params: &[Option<Vec<u8>>],
         result_formats: &[PostgreSqlFormat],
+    ) -> SystemResult<()> {
+        self.extended_message(|executor| {
+            executor.bind(portal_name, statement_name, param_formats, raw_params, result_formats)
+        })
+    }
+
+    fn bind(
+        &mut self,
+        portal_name: &str,
+        statement_name: &str,
+        param_formats: &[PostgreSqlFormat],
+        raw_params: &[Option<Vec<u8>>],
+        result_formats: &[PostgreSqlFormat],
     ) -> SystemResult<()> {
         let prepared_statement = match self.session.get_prepared_statement(statement_name) {
             Some(prepared_statement) => prepared_statement,
@@ -166,15 +626,21 @@ impl QueryExecutor {
                 return Ok(());
             }
         };
+        if self.rejected_by_failed_transaction(&format!("{}", prepared_statement.stmt())) {
+            return Ok(());
+        }
 
-        let param_types = prepared_statement.param_types();
-        if param_types.len() != raw_params.len() {
+        let required_params = prepared_statement
+            .param_types()
+            .len()
+            .max(bind::referenced_params(prepared_statement.stmt()));
+        if required_params != raw_params.len() {
             let message = format!(
                 "Bind message supplies {actual} parameters, \
                  but prepared statement \"{name}\" requires {expected}",
                 name = statement_name,
                 actual = raw_params.len(),
-                expected = param_types.len()
+                expected = required_params
             );
             self.sender
                 .send(Err(QueryError::protocol_violation(message)))
@@ -192,22 +658,48 @@ impl QueryExecutor {
             }
         };
 
+        // parameters without specified types are sent as text
+        let param_types = prepared_statement
+            .param_types()
+            .iter()
+            .copied()
+            .chain(iter::repeat(PostgreSqlType::VarChar))
+            .take(required_params)
+            .collect::<Vec<PostgreSqlType>>();
         let mut params: Vec<PostgreSqlValue> = vec![];
-        for (raw_param, typ, format) in izip!(raw_params, param_types, param_formats) {
+        for (raw_param, typ, format) in izip!(raw_params, &param_types, param_formats) {
             match raw_param {
                 None => params.push(PostgreSqlValue::Null),
                 Some(bytes) => match typ.decode(&format, &bytes) {
                     Ok(param) => params.push(param),
                     Err(msg) => {
-                        self.sender
-                            .send(Err(QueryError::invalid_parameter_value(msg)))
-                            .expect("To Send Error to Client");
+                        let error = match format {
+                            PostgreSqlFormat::Text => QueryError::invalid_text_representation(format!(
+                                "invalid input syntax for type {}: \"{}\"",
+                                typ,
+                                String::from_utf8_lossy(bytes)
+                            )),
+                            PostgreSqlFormat::Binary => QueryError::invalid_parameter_value(msg),
+                        };
+                        self.sender.send(Err(error)).expect("To Send Error to Client");
                         return Ok(());
                     }
                 },
             }
         }
 
+        let targets = match self.plan_prepared(prepared_statement.stmt()) {
+            Some((_plan, params)) => params,
+            None => return Ok(()),
+        };
+        if self
+            .param_binder
+            .check_coercion(&targets, &param_types, &params)
+            .is_err()
+        {
+            return Ok(());
+        }
+
         let mut new_stmt = prepared_statement.stmt().clone();
         if self.param_binder.bind(&mut new_stmt, &params).is_err() {
             return Ok(());
@@ -237,8 +729,13 @@ impl QueryExecutor {
         Ok(())
     }
 
-    // TODO: Parameter `max_rows` should be handled.
-    pub fn execute_portal(&mut self, portal_name: &str, _max_rows: i32) -> SystemResult<()> {
+    pub fn execute_portal(&mut self, portal_name: &str, max_rows: i32) -> SystemResult<()> {
+        self.extended_message(|executor| executor.execute_bound(portal_name, max_rows))
+    }
+
+    /// rows selected by the portal statement are sent without their description in the portal result formats,
+    /// the portal is executed once and next executions return rows that are left
+    fn execute_bound(&mut self, portal_name: &str, max_rows: i32) -> SystemResult<()> {
         let portal = match self.session.get_portal(portal_name) {
             Some(portal) => portal,
             None => {
@@ -249,9 +746,308 @@ impl QueryExecutor {
             }
         };
 
-        let statement = portal.stmt();
+        let statement = portal.stmt().clone();
+        if self.rejected_by_failed_transaction(&format!("{}", statement)) {
+            return Ok(());
+        }
+        let result_formats = portal.result_formats().to_vec();
+        if let Some(remaining) = self.session.take_portal_rows(portal_name) {
+            self.fetch(portal_name, remaining, result_formats, max_rows);
+            return Ok(());
+        }
+
+        self.timed_sender.keep_selected();
         let raw_sql_query = format!("{}", statement);
-        self.process_statement(&raw_sql_query, statement.clone())
+        let mut timings = StageTimings::default();
+        self.activity.start(&raw_sql_query);
+        let result = self.process_statement(&raw_sql_query, statement, &mut timings);
+        self.activity.finish();
+        let selected = self.timed_sender.take_selected();
+        result?;
+        if let Some(selected) = selected {
+            self.fetch(portal_name, selected, result_formats, max_rows);
+        }
+        self.report_timings(&timings);
+        Ok(())
+    }
+
+    /// sends at most `max_rows` rows, all of them when it is not positive, and suspends the portal when
+    /// there are rows left
+    fn fetch(
+        &mut self,
+        portal_name: &str,
+        (description, mut records): Projection,
+        result_formats: Vec<PostgreSqlFormat>,
+        max_rows: i32,
+    ) {
+        let remaining = if max_rows > 0 && records.len() > max_rows as usize {
+            records.split_off(max_rows as usize)
+        } else {
+            vec![]
+        };
+        let event = if remaining.is_empty() {
+            QueryEvent::RecordsFetched((description.clone(), records), result_formats)
+        } else {
+            QueryEvent::RecordsSuspended((description.clone(), records), result_formats)
+        };
+        self.session.suspend_portal(portal_name, (description, remaining));
+        self.sender.send(Ok(event)).expect("To Send Fetched Records");
+    }
+
+    pub fn describe_portal(&mut self, name: &str) -> SystemResult<()> {
+        self.extended_message(|executor| executor.describe_bound(name))
+    }
+
+    fn describe_bound(&mut self, name: &str) -> SystemResult<()> {
+        let statement = self
+            .session
+            .get_portal(name)
+            .and_then(|portal| self.session.get_prepared_statement(portal.statement_name()));
+        match statement {
+            Some(stmt) => {
+                self.sender
+                    .send(Ok(QueryEvent::PortalDescribed(
+                        stmt.description().to_vec(),
+                        stmt.origins().to_vec(),
+                    )))
+                    .expect("To Send PortalDescribed Event");
+            }
+            None => {
+                self.sender
+                    .send(Err(QueryError::portal_does_not_exist(name)))
+                    .expect("To Send Error to Client");
+            }
+        };
+
+        Ok(())
+    }
+
+    /// closing a statement closes portals bound to it, closing nonexistent statement is not an error
+    pub fn close_prepared_statement(&mut self, name: &str) -> SystemResult<()> {
+        self.extended_message(|executor| {
+            executor.session.remove_prepared_statement(name);
+            executor
+                .sender
+                .send(Ok(QueryEvent::CloseComplete))
+                .expect("To Send CloseComplete Event");
+            Ok(())
+        })
+    }
+
+    /// closing nonexistent portal is not an error
+    pub fn close_portal(&mut self, name: &str) -> SystemResult<()> {
+        self.extended_message(|executor| {
+            executor.session.remove_portal(name);
+            executor
+                .sender
+                .send(Ok(QueryEvent::CloseComplete))
+                .expect("To Send CloseComplete Event");
+            Ok(())
+        })
+    }
+
+    /// ends the extended query, the unnamed portal is closed and messages are processed again after an error
+    pub fn sync(&mut self) {
+        self.discard_until_sync = false;
+        self.session.remove_portal("");
+        self.deliver_notifications();
+        self.sender
+            .send(Ok(self.ready_for_query()))
+            .expect("To Send Query Complete Event to Client");
+        self.flush();
+    }
+
+    /// after an error the extended query messages are discarded until `Sync`
+    fn extended_message<F: FnOnce(&mut Self) -> SystemResult<()>>(&mut self, message: F) -> SystemResult<()> {
+        if self.discard_until_sync {
+            return Ok(());
+        }
+        let errors = self.timed_sender.errors();
+        let result = message(self);
+        if self.timed_sender.errors() != errors {
+            self.discard_until_sync = true;
+            self.fail_transaction();
+        }
+        result
+    }
+
+    /// statements outside of a transaction block commit on their own, notifications they queued
+    /// are sent to listening sessions
+    fn commit_notifications(&mut self) {
+        if self.transaction.is_none() && !self.notifications.is_empty() {
+            self.sessions.notify(std::mem::take(&mut self.notifications));
+        }
+    }
+
+    /// the client is told whether the session is in a transaction block and whether it has failed
+    fn ready_for_query(&self) -> QueryEvent {
+        match (&self.transaction, self.transaction_failed) {
+            (None, _) => QueryEvent::QueryComplete,
+            (Some(_), false) => QueryEvent::QueryCompleteInTransaction,
+            (Some(_), true) => QueryEvent::QueryCompleteInFailedTransaction,
+        }
+    }
+
+    /// writes into tables are journaled until the transaction block is committed or rolled back
+    fn begin_transaction(&mut self, raw_sql_query: &str, modes: &[TransactionMode]) {
+        if self.check_transaction_modes(raw_sql_query, modes).is_err() {
+            return;
+        }
+        if self.transaction.is_some() {
+            self.sender
+                .send(Ok(QueryEvent::Warning(QueryError::active_sql_transaction())))
+                .expect("To Send Query Result to Client");
+        } else {
+            self.transaction = Some(Arc::default());
+            self.transaction_failed = false;
+            self.query_planner.set_journal(self.transaction.clone());
+        }
+        self.sender
+            .send(Ok(QueryEvent::TransactionStarted))
+            .expect("To Send Query Result to Client");
+    }
+
+    /// the client is warned when it is used outside of a transaction block
+    fn set_transaction(&mut self, raw_sql_query: &str, modes: &[TransactionMode]) {
+        if self.check_transaction_modes(raw_sql_query, modes).is_err() {
+            return;
+        }
+        if self.transaction.is_none() {
+            self.sender
+                .send(Ok(QueryEvent::Warning(QueryError::no_transaction_block(
+                    "SET TRANSACTION",
+                ))))
+                .expect("To Send Query Result to Client");
+        }
+        self.sender
+            .send(Ok(QueryEvent::VariableSet))
+            .expect("To Send Query Result to Client");
+    }
+
+    /// every transaction block is `READ COMMITTED`, `READ UNCOMMITTED` behaves as it. Tables are not versioned,
+    /// so `REPEATABLE READ`, `SERIALIZABLE` and `READ ONLY` transactions are not supported
+    fn check_transaction_modes(&self, raw_sql_query: &str, modes: &[TransactionMode]) -> Result<(), ()> {
+        for mode in modes {
+            match mode {
+                TransactionMode::IsolationLevel(TransactionIsolationLevel::ReadUncommitted)
+                | TransactionMode::IsolationLevel(TransactionIsolationLevel::ReadCommitted)
+                | TransactionMode::AccessMode(TransactionAccessMode::ReadWrite) => {}
+                TransactionMode::IsolationLevel(TransactionIsolationLevel::RepeatableRead)
+                | TransactionMode::IsolationLevel(TransactionIsolationLevel::Serializable)
+                | TransactionMode::AccessMode(TransactionAccessMode::ReadOnly) => {
+                    self.sender
+                        .send(Err(QueryError::feature_not_supported(raw_sql_query)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// a failed transaction block is rolled back instead of being committed
+    fn commit_transaction(&mut self, chain: bool) -> SystemResult<()> {
+        match self.transaction.take() {
+            None => {
+                self.sender
+                    .send(Ok(QueryEvent::Warning(QueryError::no_active_sql_transaction())))
+                    .expect("To Send Query Result to Client");
+                self.sender
+                    .send(Ok(QueryEvent::TransactionCommitted))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+            Some(journal) if self.transaction_failed => {
+                self.data_manager.roll_back(&journal)?;
+                self.notifications.clear();
+                self.sender
+                    .send(Ok(QueryEvent::TransactionRolledBack))
+                    .expect("To Send Query Result to Client");
+            }
+            Some(journal) => {
+                self.data_manager.commit(&journal)?;
+                self.commit_notifications();
+                self.sender
+                    .send(Ok(QueryEvent::TransactionCommitted))
+                    .expect("To Send Query Result to Client");
+            }
+        }
+        self.chain_transaction(chain);
+        Ok(())
+    }
+
+    /// rows of tables are restored as they were before the transaction block and its notifications are dropped
+    fn rollback_transaction(&mut self, chain: bool) -> SystemResult<()> {
+        match self.transaction.take() {
+            None => {
+                self.sender
+                    .send(Ok(QueryEvent::Warning(QueryError::no_active_sql_transaction())))
+                    .expect("To Send Query Result to Client");
+            }
+            Some(journal) => {
+                self.data_manager.roll_back(&journal)?;
+                self.notifications.clear();
+                self.chain_transaction(chain);
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::TransactionRolledBack))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    /// writes after the savepoint are undone and a failed transaction block can be used again
+    fn rollback_to_savepoint(&mut self, savepoint_name: &str) -> SystemResult<()> {
+        let result = match self.transaction.clone() {
+            None => Err(QueryError::no_transaction_block("ROLLBACK TO SAVEPOINT")),
+            Some(journal) => {
+                if self.data_manager.roll_back_to_savepoint(&journal, savepoint_name)? {
+                    self.transaction_failed = false;
+                    Ok(QueryEvent::TransactionRolledBack)
+                } else {
+                    Err(QueryError::savepoint_does_not_exist(savepoint_name))
+                }
+            }
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    /// tables of the ended transaction block are unlocked, `AND CHAIN` starts a new block right after it
+    fn chain_transaction(&mut self, chain: bool) {
+        self.data_manager.unlock_tables(self.lock_owner);
+        self.transaction_failed = false;
+        if chain {
+            self.transaction = Some(Arc::default());
+        }
+        self.query_planner.set_journal(self.transaction.clone());
+    }
+
+    /// an error in a transaction block fails it, the block can only be ended after that
+    fn fail_transaction(&mut self) {
+        if self.transaction.is_some() {
+            self.transaction_failed = true;
+        }
+    }
+
+    /// statements other than `COMMIT`, `ROLLBACK` and `ROLLBACK TO SAVEPOINT` are rejected in a failed transaction block
+    fn rejected_by_failed_transaction(&self, raw_sql_query: &str) -> bool {
+        if self.transaction_failed && !ends_transaction(raw_sql_query) {
+            self.sender
+                .send(Err(QueryError::in_failed_sql_transaction()))
+                .expect("To Send Query Result to Client");
+            return true;
+        }
+        false
+    }
+
+    /// notifications received by the session are sent right before it is ready for the next query
+    fn deliver_notifications(&self) {
+        for Notification { pid, channel, payload } in self.activity.take_notifications() {
+            self.sender
+                .send(Ok(QueryEvent::Notification(pid, channel, payload)))
+                .expect("To Send Notification to Client");
+        }
     }
 
     pub fn flush(&self) {
@@ -263,14 +1059,410 @@ impl QueryExecutor {
         };
     }
 
-    fn process_statement(&self, raw_sql_query: &str, statement: Statement) -> SystemResult<()> {
+    fn process_extended_statement(
+        &mut self,
+        raw_sql_query: &str,
+        statement: ExtendedStatement,
+        timings: &mut StageTimings,
+    ) -> SystemResult<()> {
+        log::debug!("EXTENDED STATEMENT = {:?}", statement);
+        match statement {
+            ExtendedStatement::SetRole(role) => {
+                self.query_planner.set_role(role.value);
+                self.sender
+                    .send(Ok(QueryEvent::VariableSet))
+                    .expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::ResetRole => {
+                self.query_planner.set_role(self.session_user.as_str());
+                self.sender
+                    .send(Ok(QueryEvent::VariableReset))
+                    .expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::SetTimeZone(value) => {
+                self.set_time_zone(&value);
+                Ok(())
+            }
+            ExtendedStatement::DeclareCursor { cursor_name, query } => {
+                self.declare_cursor(cursor_name.value, query, timings)
+            }
+            ExtendedStatement::Fetch { cursor_name, count } => {
+                let result = match self
+                    .session
+                    .fetch_from_cursor(&cursor_name.value, count.map(|count| count as usize))
+                {
+                    Some(rows) => Ok(QueryEvent::RecordsFetchedFromCursor(rows)),
+                    None => Err(QueryError::invalid_cursor_name(cursor_name.value)),
+                };
+                self.sender.send(result).expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::CloseCursor(cursor_name) => {
+                let result = match cursor_name {
+                    Some(cursor_name) if !self.session.close_cursor(&cursor_name.value) => {
+                        Err(QueryError::invalid_cursor_name(cursor_name.value))
+                    }
+                    Some(_) => Ok(QueryEvent::CursorClosed),
+                    None => {
+                        self.session.close_all_cursors();
+                        Ok(QueryEvent::AllCursorsClosed)
+                    }
+                };
+                self.sender.send(result).expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::Listen(channel) => {
+                self.activity.listen(&channel.value);
+                self.sender
+                    .send(Ok(QueryEvent::Listening))
+                    .expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::Unlisten(channel) => {
+                self.activity
+                    .unlisten(channel.as_ref().map(|channel| channel.value.as_str()));
+                self.sender
+                    .send(Ok(QueryEvent::Unlistening))
+                    .expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::Notify { channel, payload } => {
+                // identical notifications of the same transaction are delivered once
+                let notification = Notification {
+                    pid: self.activity.pid(),
+                    channel: channel.value,
+                    payload,
+                };
+                if !self.notifications.contains(&notification) {
+                    self.notifications.push(notification);
+                }
+                self.sender
+                    .send(Ok(QueryEvent::Notified))
+                    .expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::Savepoint(savepoint_name) => {
+                let result = match &self.transaction {
+                    Some(journal) => {
+                        journal.savepoint(&savepoint_name.value);
+                        Ok(QueryEvent::SavepointCreated)
+                    }
+                    None => Err(QueryError::no_transaction_block("SAVEPOINT")),
+                };
+                self.sender.send(result).expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::ReleaseSavepoint(savepoint_name) => {
+                let result = match &self.transaction {
+                    Some(journal) if journal.release(&savepoint_name.value) => Ok(QueryEvent::SavepointReleased),
+                    Some(_) => Err(QueryError::savepoint_does_not_exist(savepoint_name.value)),
+                    None => Err(QueryError::no_transaction_block("RELEASE SAVEPOINT")),
+                };
+                self.sender.send(result).expect("To Send Query Result to Client");
+                Ok(())
+            }
+            ExtendedStatement::RollbackToSavepoint(savepoint_name) => self.rollback_to_savepoint(&savepoint_name.value),
+            ExtendedStatement::Backup(path) => {
+                // tables locked by the transaction of the session would never be released for the backup
+                let result = if self.transaction.is_some() {
+                    Err(QueryError::transaction_block_not_allowed("BACKUP"))
+                } else {
+                    match self.data_manager.backup(Path::new(&path))? {
+                        Ok(()) => Ok(QueryEvent::BackupCompleted),
+                        Err(BackupError::Deadlock) => Err(QueryError::deadlock_detected()),
+                        Err(BackupError::Io(message)) => Err(QueryError::io_error(format!(
+                            "could not write backup file \"{}\": {}",
+                            path, message
+                        ))),
+                        Err(error) => unreachable!("{:?} is returned only when a backup is restored", error),
+                    }
+                };
+                self.sender.send(result).expect("To Send Query Result to Client");
+                Ok(())
+            }
+            statement @ ExtendedStatement::CopyFrom { .. } => {
+                if let Ok(Plan::CopyFrom(table_inserts)) = timings.plan(|| self.query_planner.plan_extended(statement))
+                {
+                    let columns = self.copied_columns(&table_inserts)?;
+                    self.copy_in = Some(CopyIn::new(raw_sql_query, table_inserts));
+                    self.sender
+                        .send(Ok(QueryEvent::CopyInStarted(columns)))
+                        .expect("To Send Query Result to Client");
+                }
+                Ok(())
+            }
+            statement => {
+                let plan = timings.plan(|| self.query_planner.plan_extended(statement));
+                timings.execute(&self.timed_sender.clone(), || self.execute_plan(raw_sql_query, plan))
+            }
+        }
+    }
+
+    /// rows of the cursor query are selected when the cursor is declared and fetched from the session later
+    fn declare_cursor(
+        &mut self,
+        cursor_name: String,
+        query: Box<Query>,
+        timings: &mut StageTimings,
+    ) -> SystemResult<()> {
+        let raw_sql_query = format!("{}", query);
+        self.timed_sender.keep_selected();
+        let result = self.process_statement(&raw_sql_query, Statement::Query(query), timings);
+        let selected = self.timed_sender.take_selected();
+        result?;
+        if let Some(rows) = selected {
+            let result = if self.session.declare_cursor(cursor_name.clone(), rows) {
+                Ok(QueryEvent::CursorDeclared)
+            } else {
+                Err(QueryError::duplicate_cursor(cursor_name))
+            };
+            self.sender.send(result).expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
+
+    /// plan of a statement that can have parameters together with parameters which types are inferred,
+    /// `None` if the statement can't be planned. Other statements are planned when they are executed
+    fn plan_prepared(&self, stmt: &Statement) -> Option<(Option<Plan>, Vec<Param>)> {
+        match stmt {
+            Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. } => {
+                self.query_planner
+                    .plan_prepared(stmt)
+                    .ok()
+                    .map(|(plan, params)| (Some(plan), params))
+            }
+            _ => Some((None, vec![])),
+        }
+    }
+
+    fn process_statement(
+        &mut self,
+        raw_sql_query: &str,
+        statement: Statement,
+        timings: &mut StageTimings,
+    ) -> SystemResult<()> {
         log::debug!("STATEMENT = {:?}", statement);
-        match self.query_planner.plan(statement) {
+        if let Statement::SetVariable { variable, value, .. } = &statement {
+            if variable.value.eq_ignore_ascii_case(TRACE) {
+                self.set_trace(value);
+                return Ok(());
+            }
+            if variable.value.eq_ignore_ascii_case(TIME_ZONE) {
+                self.set_time_zone(value);
+                return Ok(());
+            }
+            if variable.value.eq_ignore_ascii_case(MAX_RESULT_ROWS)
+                || variable.value.eq_ignore_ascii_case(MAX_RESULT_SIZE)
+            {
+                self.set_result_limit(&variable.value.to_lowercase(), value);
+                return Ok(());
+            }
+        }
+        let plan = timings.plan(|| self.query_planner.plan(statement));
+        timings.execute(&self.timed_sender.clone(), || self.execute_plan(raw_sql_query, plan))
+    }
+
+    fn set_trace(&mut self, value: &SetVariableValue) {
+        let trace = match value {
+            SetVariableValue::Ident(ident) => operator::parse_bool(&ident.value),
+            SetVariableValue::Literal(Value::SingleQuotedString(value)) => operator::parse_bool(value),
+            SetVariableValue::Literal(Value::Boolean(value)) => Some(*value),
+            SetVariableValue::Literal(Value::Number(value)) => operator::parse_bool(&value.to_string()),
+            SetVariableValue::Literal(_) => None,
+        };
+        match trace {
+            Some(trace) => {
+                self.session.set_trace(trace);
+                self.sender
+                    .send(Ok(QueryEvent::VariableSet))
+                    .expect("To Send Query Result to Client");
+            }
+            None => {
+                self.sender
+                    .send(Err(QueryError::invalid_parameter_value(format!(
+                        "parameter \"{}\" requires a Boolean value",
+                        TRACE
+                    ))))
+                    .expect("To Send Query Result to Client");
+            }
+        }
+    }
+
+    /// `LOCAL` and `DEFAULT` go back to the offset of the clock, numbers are hours east of UTC
+    fn set_time_zone(&mut self, value: &SetVariableValue) {
+        let time_zone = match value {
+            SetVariableValue::Ident(ident)
+                if ident.value.eq_ignore_ascii_case("local") || ident.value.eq_ignore_ascii_case("default") =>
+            {
+                Some(None)
+            }
+            SetVariableValue::Ident(ident) => clock::parse_utc_offset(&ident.value).map(Some),
+            SetVariableValue::Literal(Value::SingleQuotedString(zone)) => clock::parse_utc_offset(zone).map(Some),
+            SetVariableValue::Literal(Value::Number(hours)) => hours
+                .to_string()
+                .parse::<f64>()
+                .ok()
+                .map(|hours| (hours * 3600.0).round())
+                .filter(|seconds| seconds.abs() <= f64::from(clock::MAX_UTC_OFFSET_HOURS * 3600))
+                .map(|seconds| Some(seconds as i32)),
+            SetVariableValue::Literal(_) => None,
+        };
+        match time_zone {
+            Some(time_zone) => {
+                self.session.set_time_zone(time_zone);
+                self.sender
+                    .send(Ok(QueryEvent::VariableSet))
+                    .expect("To Send Query Result to Client");
+            }
+            None => {
+                self.sender
+                    .send(Err(QueryError::invalid_parameter_value(format!(
+                        "invalid value for parameter \"{}\": \"{}\"",
+                        TIME_ZONE,
+                        value.to_string().trim_matches('\'')
+                    ))))
+                    .expect("To Send Query Result to Client");
+            }
+        }
+    }
+
+    /// limits are numbers of rows or bytes, sizes could have `kB`, `MB` or `GB` units, zero and `DEFAULT` turn
+    /// the limit off
+    fn set_result_limit(&mut self, name: &str, value: &SetVariableValue) {
+        let text = match value {
+            SetVariableValue::Ident(ident) if ident.value.eq_ignore_ascii_case("default") => "0".to_owned(),
+            SetVariableValue::Ident(ident) => ident.value.clone(),
+            SetVariableValue::Literal(Value::SingleQuotedString(value)) => value.clone(),
+            SetVariableValue::Literal(Value::Number(value)) => value.to_string(),
+            SetVariableValue::Literal(_) => String::new(),
+        };
+        let text = text.trim();
+        let (digits, multiplier) = match text.find(|c: char| !c.is_ascii_digit()) {
+            Some(position) if name == MAX_RESULT_SIZE => match text[position..].trim() {
+                "kB" => (&text[..position], Some(1 << 10)),
+                "MB" => (&text[..position], Some(1 << 20)),
+                "GB" => (&text[..position], Some(1 << 30)),
+                _ => (text, None),
+            },
+            Some(_) => (text, None),
+            None => (text, Some(1)),
+        };
+        let limit = multiplier.and_then(|multiplier| {
+            digits
+                .parse::<usize>()
+                .ok()
+                .and_then(|limit| limit.checked_mul(multiplier))
+        });
+        match limit {
+            Some(limit) => {
+                let mut result_limits = self.session.result_limits();
+                if name == MAX_RESULT_ROWS {
+                    result_limits.max_rows = limit;
+                } else {
+                    result_limits.max_size = limit;
+                }
+                self.session.set_result_limits(result_limits);
+                self.sender
+                    .send(Ok(QueryEvent::VariableSet))
+                    .expect("To Send Query Result to Client");
+            }
+            None => {
+                self.sender
+                    .send(Err(QueryError::invalid_parameter_value(format!(
+                        "invalid value for parameter \"{}\": \"{}\"",
+                        name,
+                        value.to_string().trim_matches('\'')
+                    ))))
+                    .expect("To Send Query Result to Client");
+            }
+        }
+    }
+
+    fn report_timings(&self, timings: &StageTimings) {
+        if self.session.trace() {
+            self.sender
+                .send(Ok(QueryEvent::Notice(timings.to_string())))
+                .expect("To Send Query Result to Client");
+        }
+    }
+
+    fn enter_modification(&self, operator: &str, table_id: &TableId) {
+        match self.data_manager.table_name(table_id) {
+            Some((schema_name, table_name)) => self
+                .activity
+                .enter(format!("{} on {}.{}", operator, schema_name, table_name)),
+            None => self.activity.enter(operator),
+        }
+    }
+
+    /// rows of the table are read and written by one session at a time until the statement or the transaction
+    /// block is finished. Returns `false` if the statement is canceled while it waits for the table, or if the
+    /// session would wait for itself, its transaction block is rolled back then
+    fn lock_table(&self, table_id: &TableId) -> bool {
+        match self
+            .data_manager
+            .lock_table(table_id, self.lock_owner, &|| self.activity.is_canceled())
+        {
+            TableLock::Acquired => return true,
+            TableLock::Canceled => {
+                if self.transaction.is_none() {
+                    self.data_manager.unlock_tables(self.lock_owner);
+                }
+                self.sender
+                    .send(Err(QueryError::query_canceled()))
+                    .expect("To Send Query Result to Client");
+                return false;
+            }
+            TableLock::Deadlock => {}
+        }
+        if let Some(journal) = self.transaction.as_ref() {
+            if let Err(error) = self.data_manager.roll_back(journal) {
+                log::error!("Transaction can't be rolled back. Error: {:?}", error);
+            }
+        }
+        self.data_manager.unlock_tables(self.lock_owner);
+        self.sender
+            .send(Err(QueryError::deadlock_detected()))
+            .expect("To Send Query Result to Client");
+        false
+    }
+
+    /// writes of a statement outside of a transaction block are journaled only while changes are captured
+    fn write_journal(&self) -> Option<Arc<Journal>> {
+        match self.transaction.as_ref() {
+            Some(journal) => Some(journal.clone()),
+            None if self.data_manager.captures_changes() => Some(Arc::default()),
+            None => None,
+        }
+    }
+
+    /// tables written in a transaction block stay locked until the block is ended,
+    /// a statement outside of it commits its writes before its tables are unlocked
+    fn unlock_tables(&self, journal: Option<Arc<Journal>>) -> SystemResult<()> {
+        if self.transaction.is_some() {
+            return Ok(());
+        }
+        let committed = match journal {
+            Some(journal) => self.data_manager.commit(&journal),
+            None => Ok(()),
+        };
+        self.data_manager.unlock_tables(self.lock_owner);
+        committed
+    }
+
+    fn execute_plan(&mut self, raw_sql_query: &str, plan: Result<Plan, ()>) -> SystemResult<()> {
+        match plan {
             Ok(Plan::CreateSchema(creation_info)) => {
-                CreateSchemaCommand::new(creation_info, self.data_manager.clone(), self.sender.clone()).execute()?;
+                CreateSchemaCommand::new(creation_info, self.data_manager.clone(), self.sender.clone())
+                    .with_owner(self.query_planner.role())
+                    .execute()?;
             }
             Ok(Plan::CreateTable(creation_info)) => {
-                CreateTableCommand::new(creation_info, self.data_manager.clone(), self.sender.clone()).execute()?;
+                CreateTableCommand::new(creation_info, self.data_manager.clone(), self.sender.clone())
+                    .with_journal(self.transaction.clone())
+                    .execute()?;
             }
             Ok(Plan::DropSchemas(schemas)) => {
                 for (schema, cascade) in schemas {
@@ -278,29 +1470,179 @@ impl QueryExecutor {
                         .execute()?;
                 }
             }
+            Ok(Plan::CreateSequence(creation_info)) => {
+                CreateSequenceCommand::new(creation_info, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::DropSequences(sequences)) => {
+                DropSequencesCommand::new(sequences, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::CreateEnumType(creation_info)) => {
+                CreateEnumTypeCommand::new(creation_info, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::CreateFunction(function_info)) => {
+                CreateFunctionCommand::new(
+                    function_info,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .execute()?;
+            }
+            Ok(Plan::DropFunctions(functions)) => {
+                DropFunctionsCommand::new(functions, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::CreateStatistics(creation_info)) => {
+                CreateStatisticsCommand::new(creation_info, self.data_manager.clone(), self.sender.clone())
+                    .execute()?;
+            }
+            Ok(Plan::DropStatistics(statistics)) => {
+                DropStatisticsCommand::new(statistics, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::Analyze(tables)) => {
+                AnalyzeCommand::new(tables, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::Vacuum(tables)) => {
+                // compaction can't be rolled back, as in PostgreSQL it runs outside of transaction blocks
+                if self.transaction.is_some() {
+                    self.sender
+                        .send(Err(QueryError::transaction_block_not_allowed("VACUUM")))
+                        .expect("To Send Query Result to Client");
+                } else {
+                    VacuumCommand::new(tables, self.data_manager.clone(), self.sender.clone()).execute()?;
+                }
+            }
+            Ok(Plan::IndexAdvice(tables)) => {
+                IndexAdviceCommand::new(tables, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::AlterSchemaOwner(change)) => {
+                AlterSchemaOwnerCommand::new(change, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::GrantSchemaPrivileges(change)) => {
+                GrantSchemaPrivilegesCommand::new(change, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
+            Ok(Plan::RevokeSchemaPrivileges(change)) => {
+                RevokeSchemaPrivilegesCommand::new(change, self.data_manager.clone(), self.sender.clone()).execute()?;
+            }
             Ok(Plan::DropTables(tables)) => {
                 for table in tables {
-                    DropTableCommand::new(table, self.data_manager.clone(), self.sender.clone()).execute()?;
+                    // rows of the table can't be written by other sessions until the drop is committed
+                    if self.transaction.is_some() && !self.lock_table(&table) {
+                        return Ok(());
+                    }
+                    DropTableCommand::new(table, self.data_manager.clone(), self.sender.clone())
+                        .with_journal(self.transaction.clone())
+                        .execute()?;
                 }
             }
             Ok(Plan::Insert(table_insert)) => {
-                InsertCommand::new(table_insert, self.data_manager.clone(), self.sender.clone()).execute()?;
+                self.enter_modification("Insert", &table_insert.table_id);
+                if !self.lock_table(&table_insert.table_id) {
+                    return Ok(());
+                }
+                let journal = self.write_journal();
+                let inserted = InsertCommand::new(
+                    table_insert,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .with_journal(journal.clone())
+                .execute();
+                self.unlock_tables(journal)?;
+                inserted?;
+            }
+            Ok(Plan::CopyTo(table_copy)) => {
+                self.enter_modification("Copy", &table_copy.table_id);
+                CopyOutCommand::new(
+                    table_copy,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                )
+                .with_journal(self.transaction.clone())
+                .execute()?;
+            }
+            Ok(Plan::Skipped(event)) => {
+                self.sender.send(Ok(event)).expect("To Send Query Result to Client");
+            }
+            Ok(Plan::CopyFrom(_)) => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(raw_sql_query)))
+                    .expect("To Send Query Result to Client");
             }
             Ok(Plan::Update(table_update)) => {
-                UpdateCommand::new(table_update, self.data_manager.clone(), self.sender.clone()).execute()?;
+                self.enter_modification("Update", &table_update.table_id);
+                if !self.lock_table(&table_update.table_id) {
+                    return Ok(());
+                }
+                let journal = self.write_journal();
+                let updated = UpdateCommand::new(
+                    table_update,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .with_journal(journal.clone())
+                .execute();
+                self.unlock_tables(journal)?;
+                updated?;
             }
             Ok(Plan::Delete(table_delete)) => {
-                DeleteCommand::new(table_delete, self.data_manager.clone(), self.sender.clone()).execute()?;
+                self.enter_modification("Delete", &table_delete.table_id);
+                if !self.lock_table(&table_delete.table_id) {
+                    return Ok(());
+                }
+                let journal = self.write_journal();
+                let deleted = DeleteCommand::new(
+                    table_delete,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .with_journal(journal.clone())
+                .execute();
+                self.unlock_tables(journal)?;
+                deleted?;
             }
             Ok(Plan::Select(select_input)) => {
-                SelectCommand::new(select_input, self.data_manager.clone(), self.sender.clone()).execute()?;
+                SelectCommand::new(
+                    select_input,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.aggregates.clone(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                    self.session.function_context(),
+                    self.activity.clone(),
+                )
+                .with_result_limits(self.session.result_limits())
+                .with_journal(self.transaction.clone())
+                .execute()?;
+            }
+            Ok(Plan::SelectValues(select_values)) => {
+                SelectValuesCommand::new(
+                    select_values,
+                    self.data_manager.clone(),
+                    self.sender.clone(),
+                    self.session.function_context(),
+                    self.functions.clone(),
+                    self.operators.clone(),
+                )
+                .execute()?;
             }
             Ok(Plan::NotProcessed(statement)) => match *statement {
-                Statement::StartTransaction { .. } => {
-                    self.sender
-                        .send(Ok(QueryEvent::TransactionStarted))
-                        .expect("To Send Query Result to Client");
-                }
+                Statement::StartTransaction { modes } => self.begin_transaction(raw_sql_query, &modes),
+                Statement::SetTransaction { modes } => self.set_transaction(raw_sql_query, &modes),
+                Statement::Commit { chain } => self.commit_transaction(chain)?,
+                Statement::Rollback { chain } => self.rollback_transaction(chain)?,
                 Statement::SetVariable { .. } => {
                     self.sender
                         .send(Ok(QueryEvent::VariableSet))
@@ -324,8 +1666,20 @@ impl QueryExecutor {
     }
 }
 
+impl Drop for QueryExecutor {
+    fn drop(&mut self) {
+        if let Some(journal) = self.transaction.take() {
+            if let Err(error) = self.data_manager.roll_back(&journal) {
+                log::error!("Transaction can't be rolled back. Error: {:?}", error);
+            }
+        }
+        self.data_manager.unlock_tables(self.lock_owner);
+        self.sessions.unregister(self.activity.pid());
+    }
+}
+
 #[derive(Debug)]
-struct PreparedStatementDialect {}
+pub(crate) struct PreparedStatementDialect {}
 
 impl Dialect for PreparedStatementDialect {
     fn is_identifier_start(&self, ch: char) -> bool {
@@ -337,6 +1691,15 @@ impl Dialect for PreparedStatementDialect {
     }
 }
 
+/// `COMMIT` and `ROLLBACK` end a transaction block, `ROLLBACK TO SAVEPOINT` ends its failed part
+fn ends_transaction(raw_sql_query: &str) -> bool {
+    raw_sql_query
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .next()
+        .map(|word| word.eq_ignore_ascii_case("commit") || word.eq_ignore_ascii_case("rollback"))
+        .unwrap_or(false)
+}
+
 fn pad_formats(formats: &[PostgreSqlFormat], param_len: usize) -> Result<Vec<PostgreSqlFormat>, String> {
     match (formats.len(), param_len) {
         (0, n) => Ok(vec![PostgreSqlFormat::Text; n]),
@@ -0,0 +1,628 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! operators are resolved once per statement by the types of their operands, when none of
+//! implementations matches the operand types exactly the one reachable with the cheapest
+//! implicit casts is chosen, e.g. `smallint * integer` is evaluated as `integer * integer`
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+};
+
+use sqlparser::ast::{BinaryOperator, UnaryOperator};
+
+use protocol::{
+    bytea,
+    clock::{parse_timestamp_in_time_zone, render_timestamp_with_time_zone},
+    interval::Interval,
+    results::QueryError,
+};
+use representation::{Datum, ScalarType};
+
+use crate::aggregate::{integer, numeric};
+
+pub type BinaryFn = for<'a> fn(Datum<'a>, Datum<'a>) -> Result<Datum<'a>, QueryError>;
+
+pub type UnaryFn = for<'a> fn(Datum<'a>) -> Result<Datum<'a>, QueryError>;
+
+/// cost of casting a non string operand to a string, it is taken only when the other operand is a string
+const STRING_CAST_COST: usize = 8;
+
+/// cost of reading a string as bytea input, it is preferred over casting bytea to a string
+const BYTEA_INPUT_COST: usize = 4;
+
+/// numeric types widen from smallint up to double precision
+fn numeric_rank(ty: ScalarType) -> Option<usize> {
+    match ty {
+        ScalarType::Int16 => Some(0),
+        ScalarType::Int32 => Some(1),
+        ScalarType::Int64 => Some(2),
+        ScalarType::Float32 => Some(3),
+        ScalarType::Float64 => Some(4),
+        _ => None,
+    }
+}
+
+/// number of widening steps from one type to another, `None` if there is no implicit cast
+pub(crate) fn implicit_cast_cost(from: ScalarType, to: ScalarType) -> Option<usize> {
+    if from == to {
+        return Some(0);
+    }
+    match (numeric_rank(from), numeric_rank(to)) {
+        (Some(from), Some(to)) if from < to => Some(to - from),
+        _ => None,
+    }
+}
+
+fn operand_cost(declared: ScalarType, actual: ScalarType, other: ScalarType) -> Option<usize> {
+    if declared == actual {
+        Some(0)
+    } else if declared.is_string() && other.is_string() {
+        Some(STRING_CAST_COST)
+    } else if declared == ScalarType::Bytes && actual.is_string() {
+        Some(BYTEA_INPUT_COST)
+    } else {
+        implicit_cast_cost(actual, declared)
+    }
+}
+
+/// narrowing casts happen only to values stored in a column with a wider type than the column has
+pub(crate) fn cast(value: Datum, to: ScalarType) -> Datum {
+    if value.is_null() || value.scalar_type() == Some(to) {
+        return value;
+    }
+    let casted: Option<Datum> = match to {
+        ScalarType::Int16 => integer(&value).map(|v| Datum::from_i16(v as i16)),
+        ScalarType::Int32 => integer(&value).map(|v| Datum::from_i32(v as i32)),
+        ScalarType::Int64 => integer(&value).map(Datum::from_i64),
+        ScalarType::Float32 => numeric(&value).map(|v| Datum::from_f32(v as f32)),
+        ScalarType::Float64 => numeric(&value).map(Datum::from_f64),
+        ScalarType::String => Some(Datum::from_string(value.to_string())),
+        ScalarType::Boolean => parse_bool(value.to_string().as_str()).map(Datum::from_bool),
+        ScalarType::Bytes => {
+            let text = value.to_string();
+            Some(Datum::from_bytes(
+                bytea::decode(text.as_str()).unwrap_or_else(|_| text.into_bytes()),
+            ))
+        }
+        ScalarType::UInt64 | ScalarType::Timestamp | ScalarType::Interval => None,
+    };
+    casted.unwrap_or(value)
+}
+
+/// integers and floats are reported as numbers in errors
+pub(crate) fn type_name(ty: ScalarType) -> String {
+    if ty.is_integer() || ty.is_float() {
+        "NUMBER".to_owned()
+    } else {
+        ty.to_string()
+    }
+}
+
+/// boolean input spellings accepted by PostgreSQL
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "on" | "1" => Some(true),
+        "false" | "f" | "no" | "n" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// values are casted to the same type, strings are ordered by their text and `false < true`
+pub(crate) fn compare(left: &Datum, right: &Datum) -> Ordering {
+    match left.scalar_type() {
+        Some(ScalarType::String) => left.to_string().cmp(&right.to_string()),
+        Some(ScalarType::Boolean) => left.as_bool().cmp(&right.as_bool()),
+        Some(ScalarType::Bytes) => left.as_bytes().cmp(right.as_bytes()),
+        _ => left.cmp(right),
+    }
+}
+
+/// type both operands are implicitly casted to before they are compared
+pub(crate) fn comparison_type(left: ScalarType, right: ScalarType) -> Option<ScalarType> {
+    if implicit_cast_cost(left, right).is_some() {
+        Some(right)
+    } else if implicit_cast_cost(right, left).is_some() {
+        Some(left)
+    } else {
+        None
+    }
+}
+
+/// NULLs are not distinct from each other and are distinct from any other value
+pub(crate) fn is_distinct(left: Datum, right: Datum, ty: ScalarType) -> bool {
+    match (left.is_null(), right.is_null()) {
+        (true, true) => false,
+        (true, false) | (false, true) => true,
+        (false, false) => {
+            let (left, right) = (cast(left, ty), cast(right, ty));
+            if ty.is_string() {
+                left.to_string() != right.to_string()
+            } else if ty == ScalarType::Bytes {
+                left.as_bytes() != right.as_bytes()
+            } else {
+                left != right
+            }
+        }
+    }
+}
+
+/// implementation of a binary operator for a pair of operand types
+#[derive(Clone)]
+pub struct BinaryOperation {
+    operator: BinaryOperator,
+    left: ScalarType,
+    right: ScalarType,
+    result: ScalarType,
+    function: BinaryFn,
+    strict: bool,
+}
+
+impl BinaryOperation {
+    pub fn result_type(&self) -> ScalarType {
+        self.result
+    }
+
+    /// operands are casted to the declared types, NULL operand makes the result of a strict operation NULL
+    pub fn apply<'a>(&self, left: Datum<'a>, right: Datum<'a>) -> Result<Datum<'a>, QueryError> {
+        if self.strict && (left.is_null() || right.is_null()) {
+            Ok(Datum::from_null())
+        } else {
+            (self.function)(cast(left, self.left), cast(right, self.right))
+        }
+    }
+}
+
+impl Debug for BinaryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BinaryOperation({:?} {} {:?} -> {:?})",
+            self.left, self.operator, self.right, self.result
+        )
+    }
+}
+
+impl PartialEq for BinaryOperation {
+    fn eq(&self, other: &BinaryOperation) -> bool {
+        self.operator == other.operator
+            && self.left == other.left
+            && self.right == other.right
+            && self.result == other.result
+    }
+}
+
+impl Eq for BinaryOperation {}
+
+/// implementation of a prefix operator for an operand type
+#[derive(Clone)]
+pub struct UnaryOperation {
+    operator: UnaryOperator,
+    operand: ScalarType,
+    result: ScalarType,
+    function: UnaryFn,
+}
+
+impl UnaryOperation {
+    pub fn result_type(&self) -> ScalarType {
+        self.result
+    }
+
+    /// operand is casted to the declared type, NULL operand makes the result NULL
+    pub fn apply<'a>(&self, operand: Datum<'a>) -> Result<Datum<'a>, QueryError> {
+        if operand.is_null() {
+            Ok(Datum::from_null())
+        } else {
+            (self.function)(cast(operand, self.operand))
+        }
+    }
+}
+
+impl Debug for UnaryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "UnaryOperation({} {:?} -> {:?})",
+            self.operator, self.operand, self.result
+        )
+    }
+}
+
+impl PartialEq for UnaryOperation {
+    fn eq(&self, other: &UnaryOperation) -> bool {
+        self.operator == other.operator && self.operand == other.operand && self.result == other.result
+    }
+}
+
+impl Eq for UnaryOperation {}
+
+/// operator implementations by operator and operand types
+#[derive(Clone)]
+pub struct OperatorTable {
+    binary: HashMap<BinaryOperator, Vec<BinaryOperation>>,
+    unary: HashMap<UnaryOperator, Vec<UnaryOperation>>,
+}
+
+impl OperatorTable {
+    pub fn empty() -> OperatorTable {
+        OperatorTable {
+            binary: HashMap::new(),
+            unary: HashMap::new(),
+        }
+    }
+
+    /// registered implementation replaces the one with the same operand types
+    pub fn register_binary(
+        &mut self,
+        operator: BinaryOperator,
+        left: ScalarType,
+        right: ScalarType,
+        result: ScalarType,
+        function: BinaryFn,
+    ) {
+        self.insert_binary(BinaryOperation {
+            operator,
+            left,
+            right,
+            result,
+            function,
+            strict: true,
+        });
+    }
+
+    /// same as `register_binary` but the function also handles NULL operands, e.g. `NULL OR true` is true
+    pub fn register_non_strict_binary(
+        &mut self,
+        operator: BinaryOperator,
+        left: ScalarType,
+        right: ScalarType,
+        result: ScalarType,
+        function: BinaryFn,
+    ) {
+        self.insert_binary(BinaryOperation {
+            operator,
+            left,
+            right,
+            result,
+            function,
+            strict: false,
+        });
+    }
+
+    fn insert_binary(&mut self, operation: BinaryOperation) {
+        let operations = self.binary.entry(operation.operator.clone()).or_default();
+        operations.retain(|registered| registered.left != operation.left || registered.right != operation.right);
+        operations.push(operation);
+    }
+
+    /// registered implementation replaces the one with the same operand type
+    pub fn register_unary(
+        &mut self,
+        operator: UnaryOperator,
+        operand: ScalarType,
+        result: ScalarType,
+        function: UnaryFn,
+    ) {
+        let operations = self.unary.entry(operator.clone()).or_default();
+        operations.retain(|operation| operation.operand != operand);
+        operations.push(UnaryOperation {
+            operator,
+            operand,
+            result,
+            function,
+        });
+    }
+
+    pub fn resolve_binary(
+        &self,
+        operator: &BinaryOperator,
+        left: ScalarType,
+        right: ScalarType,
+    ) -> Option<BinaryOperation> {
+        self.binary
+            .get(operator)?
+            .iter()
+            .filter_map(|operation| {
+                let left_cost = operand_cost(operation.left, left, right)?;
+                let right_cost = operand_cost(operation.right, right, left)?;
+                Some((left_cost + right_cost, operation))
+            })
+            .min_by_key(|(cost, _)| *cost)
+            .map(|(_, operation)| operation.clone())
+    }
+
+    pub fn resolve_unary(&self, operator: &UnaryOperator, operand: ScalarType) -> Option<UnaryOperation> {
+        self.unary
+            .get(operator)?
+            .iter()
+            .filter_map(|operation| Some((implicit_cast_cost(operand, operation.operand)?, operation)))
+            .min_by_key(|(cost, _)| *cost)
+            .map(|(_, operation)| operation.clone())
+    }
+}
+
+macro_rules! checked {
+    ($ty:ident, $method:ident, $message:expr) => {
+        |left, right| match (left, right) {
+            (Datum::$ty(left), Datum::$ty(right)) => left
+                .$method(right)
+                .map(Datum::$ty)
+                .ok_or_else(|| QueryError::numeric_value_out_of_range($message)),
+            (left, right) => unreachable!("{:?} and {:?} are casted to {}", left, right, stringify!($ty)),
+        }
+    };
+}
+
+/// the argument fixes the type of zero, `==` alone leaves it ambiguous
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+macro_rules! non_zero_divisor {
+    ($ty:ident, |$left:ident, $right:ident| $body:expr) => {
+        |left, right| match (left, right) {
+            (Datum::$ty(_), Datum::$ty(right)) if is_zero(&right) => Err(QueryError::division_by_zero()),
+            (Datum::$ty($left), Datum::$ty($right)) => $body,
+            (left, right) => unreachable!("{:?} and {:?} are casted to {}", left, right, stringify!($ty)),
+        }
+    };
+}
+
+macro_rules! infallible {
+    ($ty:ident, $op:tt) => {
+        |left, right| match (left, right) {
+            (Datum::$ty(left), Datum::$ty(right)) => Ok(Datum::$ty(left $op right)),
+            (left, right) => unreachable!("{:?} and {:?} are casted to {}", left, right, stringify!($ty)),
+        }
+    };
+}
+
+macro_rules! register_integer_operators {
+    ($table:expr, $ty:ident, $message:expr) => {{
+        let ty = ScalarType::$ty;
+        $table.register_binary(BinaryOperator::Plus, ty, ty, ty, checked!($ty, checked_add, $message));
+        $table.register_binary(BinaryOperator::Minus, ty, ty, ty, checked!($ty, checked_sub, $message));
+        $table.register_binary(BinaryOperator::Multiply, ty, ty, ty, checked!($ty, checked_mul, $message));
+        $table.register_binary(
+            BinaryOperator::Divide,
+            ty,
+            ty,
+            ty,
+            non_zero_divisor!($ty, |left, right| left
+                .checked_div(right)
+                .map(Datum::$ty)
+                .ok_or_else(|| QueryError::numeric_value_out_of_range($message))),
+        );
+        $table.register_binary(
+            BinaryOperator::Modulus,
+            ty,
+            ty,
+            ty,
+            // the only overflowing case is `MIN % -1` which is zero
+            non_zero_divisor!($ty, |left, right| Ok(Datum::$ty(left.checked_rem(right).unwrap_or(0)))),
+        );
+        $table.register_binary(BinaryOperator::BitwiseAnd, ty, ty, ty, infallible!($ty, &));
+        $table.register_binary(BinaryOperator::BitwiseOr, ty, ty, ty, infallible!($ty, |));
+        $table.register_unary(UnaryOperator::Plus, ty, ty, |operand| Ok(operand));
+        $table.register_unary(UnaryOperator::Minus, ty, ty, |operand| match operand {
+            Datum::$ty(operand) => operand
+                .checked_neg()
+                .map(Datum::$ty)
+                .ok_or_else(|| QueryError::numeric_value_out_of_range($message)),
+            operand => unreachable!("{:?} is casted to {}", operand, stringify!($ty)),
+        });
+    }};
+}
+
+macro_rules! register_float_operators {
+    ($table:expr, $ty:ident) => {{
+        let ty = ScalarType::$ty;
+        $table.register_binary(BinaryOperator::Plus, ty, ty, ty, infallible!($ty, +));
+        $table.register_binary(BinaryOperator::Minus, ty, ty, ty, infallible!($ty, -));
+        $table.register_binary(BinaryOperator::Multiply, ty, ty, ty, infallible!($ty, *));
+        $table.register_binary(
+            BinaryOperator::Divide,
+            ty,
+            ty,
+            ty,
+            non_zero_divisor!($ty, |left, right| Ok(Datum::$ty(left / right))),
+        );
+        $table.register_binary(
+            BinaryOperator::Modulus,
+            ty,
+            ty,
+            ty,
+            non_zero_divisor!($ty, |left, right| Ok(Datum::$ty((*left % *right).into()))),
+        );
+        $table.register_unary(UnaryOperator::Plus, ty, ty, |operand| Ok(operand));
+        $table.register_unary(UnaryOperator::Minus, ty, ty, |operand| match operand {
+            Datum::$ty(operand) => Ok(Datum::$ty(-operand)),
+            operand => unreachable!("{:?} is casted to {}", operand, stringify!($ty)),
+        });
+    }};
+}
+
+macro_rules! comparison {
+    ($($ordering:ident)|+) => {
+        |left, right| Ok(Datum::from_bool(matches!(compare(&left, &right), $(Ordering::$ordering)|+)))
+    };
+}
+
+fn register_comparison_operators(table: &mut OperatorTable, ty: ScalarType) {
+    let result = ScalarType::Boolean;
+    table.register_binary(BinaryOperator::Eq, ty, ty, result, comparison!(Equal));
+    table.register_binary(BinaryOperator::NotEq, ty, ty, result, comparison!(Less | Greater));
+    table.register_binary(BinaryOperator::Lt, ty, ty, result, comparison!(Less));
+    table.register_binary(BinaryOperator::LtEq, ty, ty, result, comparison!(Less | Equal));
+    table.register_binary(BinaryOperator::Gt, ty, ty, result, comparison!(Greater));
+    table.register_binary(BinaryOperator::GtEq, ty, ty, result, comparison!(Greater | Equal));
+}
+
+fn interval_out_of_range() -> QueryError {
+    QueryError::datetime_value_out_of_range("interval out of range")
+}
+
+fn timestamp_out_of_range() -> QueryError {
+    QueryError::datetime_value_out_of_range("timestamp out of range")
+}
+
+/// text timestamps, e.g. results of `now()`, keep their time zone, months are added in it
+fn add_interval<'a>(timestamp: Datum<'a>, interval: Interval) -> Result<Datum<'a>, QueryError> {
+    match timestamp {
+        Datum::Timestamp(micros) => interval
+            .add_to_timestamp(micros, 0)
+            .map(Datum::from_timestamp)
+            .ok_or_else(timestamp_out_of_range),
+        text => {
+            let text = text.to_string();
+            let (micros, utc_offset) = parse_timestamp_in_time_zone(&text, 0).ok_or_else(|| {
+                QueryError::invalid_datetime_format(format!(
+                    "invalid input syntax for type timestamp with time zone: \"{}\"",
+                    text
+                ))
+            })?;
+            interval
+                .add_to_timestamp(micros, utc_offset)
+                .map(|micros| Datum::from_string(render_timestamp_with_time_zone(micros, utc_offset)))
+                .ok_or_else(timestamp_out_of_range)
+        }
+    }
+}
+
+fn subtract_interval<'a>(timestamp: Datum<'a>, interval: Interval) -> Result<Datum<'a>, QueryError> {
+    add_interval(timestamp, interval.checked_neg().ok_or_else(interval_out_of_range)?)
+}
+
+fn register_interval_operators(table: &mut OperatorTable) {
+    let interval = ScalarType::Interval;
+    let factor = ScalarType::Float64;
+    table.register_binary(BinaryOperator::Plus, interval, interval, interval, |left, right| {
+        left.as_interval()
+            .checked_add(right.as_interval())
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    table.register_binary(BinaryOperator::Minus, interval, interval, interval, |left, right| {
+        left.as_interval()
+            .checked_sub(right.as_interval())
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    table.register_binary(BinaryOperator::Multiply, interval, factor, interval, |left, right| {
+        left.as_interval()
+            .checked_mul(right.as_f64())
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    table.register_binary(BinaryOperator::Multiply, factor, interval, interval, |left, right| {
+        right
+            .as_interval()
+            .checked_mul(left.as_f64())
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    table.register_binary(BinaryOperator::Divide, interval, factor, interval, |left, right| {
+        if right.as_f64() == 0.0 {
+            return Err(QueryError::division_by_zero());
+        }
+        left.as_interval()
+            .checked_mul(1.0 / right.as_f64())
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    table.register_unary(UnaryOperator::Plus, interval, interval, |operand| Ok(operand));
+    table.register_unary(UnaryOperator::Minus, interval, interval, |operand| {
+        operand
+            .as_interval()
+            .checked_neg()
+            .map(Datum::from_interval)
+            .ok_or_else(interval_out_of_range)
+    });
+    for timestamp in vec![ScalarType::Timestamp, ScalarType::String] {
+        table.register_binary(BinaryOperator::Plus, timestamp, interval, timestamp, |left, right| {
+            add_interval(left, right.as_interval())
+        });
+        table.register_binary(BinaryOperator::Plus, interval, timestamp, timestamp, |left, right| {
+            add_interval(right, left.as_interval())
+        });
+        table.register_binary(BinaryOperator::Minus, timestamp, interval, timestamp, |left, right| {
+            subtract_interval(left, right.as_interval())
+        });
+    }
+}
+
+impl Default for OperatorTable {
+    fn default() -> OperatorTable {
+        let mut table = OperatorTable::empty();
+        register_integer_operators!(table, Int16, "smallint out of range");
+        register_integer_operators!(table, Int32, "integer out of range");
+        register_integer_operators!(table, Int64, "bigint out of range");
+        register_float_operators!(table, Float32);
+        register_float_operators!(table, Float64);
+        table.register_binary(
+            BinaryOperator::StringConcat,
+            ScalarType::String,
+            ScalarType::String,
+            ScalarType::String,
+            |left, right| Ok(Datum::from_string(left.to_string() + right.to_string().as_str())),
+        );
+        table.register_unary(
+            UnaryOperator::Not,
+            ScalarType::Boolean,
+            ScalarType::Boolean,
+            |operand| Ok(Datum::from_bool(!operand.as_bool())),
+        );
+        // three-valued logic, NULL is unknown and is decided by the other operand when it can be
+        table.register_non_strict_binary(
+            BinaryOperator::And,
+            ScalarType::Boolean,
+            ScalarType::Boolean,
+            ScalarType::Boolean,
+            |left, right| match (left, right) {
+                (Datum::False, _) | (_, Datum::False) => Ok(Datum::from_bool(false)),
+                (Datum::True, Datum::True) => Ok(Datum::from_bool(true)),
+                _ => Ok(Datum::from_null()),
+            },
+        );
+        table.register_non_strict_binary(
+            BinaryOperator::Or,
+            ScalarType::Boolean,
+            ScalarType::Boolean,
+            ScalarType::Boolean,
+            |left, right| match (left, right) {
+                (Datum::True, _) | (_, Datum::True) => Ok(Datum::from_bool(true)),
+                (Datum::False, Datum::False) => Ok(Datum::from_bool(false)),
+                _ => Ok(Datum::from_null()),
+            },
+        );
+        for ty in vec![
+            ScalarType::Boolean,
+            ScalarType::Int16,
+            ScalarType::Int32,
+            ScalarType::Int64,
+            ScalarType::Float32,
+            ScalarType::Float64,
+            ScalarType::String,
+            ScalarType::Timestamp,
+            ScalarType::Interval,
+            ScalarType::Bytes,
+        ] {
+            register_comparison_operators(&mut table, ty);
+        }
+        register_interval_operators(&mut table);
+        table
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Plans of statements that are executed again and again are cached by the session. Statements are keyed by
+///! their text with literals replaced by `$n` placeholders, so statements that differ only by literals share the
+///! parsed template and its generic plan. Literals of a statement are bound to the generic plan, selects are
+///! optimized and lowered with them as comparisons of a primary key with a literal bound the scanned range. The
+///! generic plan is reused until one of the tables it reads or writes changes
+use std::collections::HashMap;
+
+use sqlparser::{
+    ast::{Statement, Value},
+    tokenizer::{Token, Tokenizer},
+};
+
+use data_manager::DataManager;
+use query_planner::{
+    extended,
+    plan::{GenericPlan, Plan},
+    TableId,
+};
+
+use crate::{query::bind, PreparedStatementDialect};
+
+/// number of statements that a session keeps plans of, the least recently used one is evicted
+pub(crate) const PLAN_CACHE_CAPACITY: usize = 256;
+
+/// normalized text of a statement and the role it is planned for
+pub(crate) type Key = (String, String);
+
+/// text of the statement with literals replaced by `$n` placeholders and values of literals in their order,
+/// `None` if the text can't be tokenized or has placeholders of its own
+pub(crate) fn normalize(raw_sql_query: &str) -> Option<(String, Vec<Value>)> {
+    let tokens = Tokenizer::new(&PreparedStatementDialect {}, raw_sql_query)
+        .tokenize()
+        .ok()?;
+    let mut text = String::new();
+    let mut literals = vec![];
+    for token in tokens {
+        let literal = match token {
+            Token::Whitespace(_) => {
+                if !text.is_empty() && !text.ends_with(' ') {
+                    text.push(' ');
+                }
+                continue;
+            }
+            Token::SemiColon => continue,
+            Token::Word(word) if word.value.starts_with('$') => return None,
+            Token::Number(number) => Value::Number(number.parse().ok()?),
+            Token::SingleQuotedString(string) => Value::SingleQuotedString(string),
+            token => {
+                text.push_str(&token.to_string());
+                continue;
+            }
+        };
+        literals.push(literal);
+        text.push_str(&format!("${}", literals.len()));
+    }
+    Some((text.trim_end().to_owned(), literals))
+}
+
+/// parsed normalized text of a single `SELECT`, `INSERT`, `UPDATE` or `DELETE`, `None` if binding the literals
+/// to it doesn't give the statement back
+pub(crate) fn template(text: &str, literals: &[Value], statement: &Statement) -> Option<Statement> {
+    match statement {
+        Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. } => {}
+        _ => return None,
+    }
+    let template = extended::parse_sql(&PreparedStatementDialect {}, text).ok()?.pop()?;
+    let mut bound = template.clone();
+    bind::bind_literals(&mut bound, literals);
+    if &bound == statement {
+        Some(template)
+    } else {
+        None
+    }
+}
+
+/// what the cache has for a statement
+pub(crate) enum Cached {
+    /// generic plan that is not stale, literals of the statement are bound to it
+    Plan(GenericPlan),
+    /// parsed statement with placeholders to bind the literals to
+    Template(Statement),
+    /// binding literals to the parsed normalized text doesn't give the statement, e.g. for literals of
+    /// `varchar(10)` or `date '2020-10-10'`
+    NotCacheable,
+    Missing,
+}
+
+struct Planned {
+    plan: GenericPlan,
+    /// versions of tables that the plan was made with
+    tables: Vec<(TableId, u64)>,
+}
+
+struct Entry {
+    template: Option<Statement>,
+    planned: Option<Planned>,
+    last_used: u64,
+}
+
+pub(crate) struct PlanCache {
+    capacity: usize,
+    uses: u64,
+    hits: u64,
+    entries: HashMap<Key, Entry>,
+}
+
+impl PlanCache {
+    pub(crate) fn new(capacity: usize) -> PlanCache {
+        PlanCache {
+            capacity,
+            uses: 0,
+            hits: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// number of lookups that found a generic plan
+    #[cfg(test)]
+    pub(crate) fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub(crate) fn lookup(&mut self, key: &Key, data_manager: &DataManager) -> Cached {
+        self.uses += 1;
+        let entry = match self.entries.get_mut(key) {
+            Some(entry) => entry,
+            None => return Cached::Missing,
+        };
+        entry.last_used = self.uses;
+        let template = match &entry.template {
+            Some(template) => template,
+            None => return Cached::NotCacheable,
+        };
+        match &entry.planned {
+            Some(planned)
+                if planned
+                    .tables
+                    .iter()
+                    .all(|(table_id, version)| data_manager.table_version(table_id) == *version) =>
+            {
+                self.hits += 1;
+                Cached::Plan(planned.plan.clone())
+            }
+            _ => Cached::Template(template.clone()),
+        }
+    }
+
+    /// template of the normalized text, `None` if the statement is not cacheable
+    pub(crate) fn insert_template(&mut self, key: Key, template: Option<Statement>) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                template,
+                planned: None,
+                last_used: self.uses,
+            },
+        );
+    }
+
+    /// generic plan that gives the plan when literals are bound to it, it is kept while tables that the plan reads
+    /// or writes don't change. Plans of statements other than DML are not kept
+    pub(crate) fn insert_plan(&mut self, key: &Key, generic: GenericPlan, plan: &Plan, data_manager: &DataManager) {
+        let tables = match plan_tables(plan) {
+            Some(tables) => tables,
+            None => return,
+        };
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.planned = Some(Planned {
+                plan: generic,
+                tables: tables
+                    .into_iter()
+                    .map(|table_id| {
+                        let version = data_manager.table_version(&table_id);
+                        (table_id, version)
+                    })
+                    .collect(),
+            });
+        }
+    }
+
+    fn evict(&mut self) {
+        let least_recently_used = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = least_recently_used {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+/// tables that the plan reads or writes, `None` for plans that are not cached
+fn plan_tables(plan: &Plan) -> Option<Vec<TableId>> {
+    match plan {
+        Plan::Select(select_input) => {
+            let mut tables = vec![select_input.table_id.clone()];
+            tables.extend(select_input.scanned_tables.iter().cloned());
+            Some(tables)
+        }
+        Plan::SelectValues(_) => Some(vec![]),
+        Plan::Insert(table_inserts) => Some(vec![table_inserts.table_id.clone()]),
+        Plan::Update(table_updates) => Some(vec![table_updates.table_id.clone()]),
+        Plan::Delete(table_deletes) => Some(vec![table_deletes.table_id.clone()]),
+        _ => None,
+    }
+}
@@ -15,9 +15,17 @@
 use std::sync::Arc;
 
 use bigdecimal::BigDecimal;
-use sqlparser::ast::{Assignment, Expr, Ident, Query, SetExpr, Statement, Value};
+use sqlparser::ast::{Expr, Query, SelectItem, SetExpr, Statement, Value};
 
-use protocol::{pgsql_types::PostgreSqlValue, results::QueryError, Sender};
+use protocol::{
+    pgsql_types::{PostgreSqlType, PostgreSqlValue},
+    results::QueryError,
+    Sender,
+};
+use query_planner::plan::{param_index, Param};
+use sql_model::sql_types::SqlType;
+
+use crate::operator::parse_bool;
 
 type Result = std::result::Result<(), ()>;
 
@@ -30,18 +38,15 @@ impl ParamBinder {
         Self { sender }
     }
 
-    /// Replaces the parameters of prepared statement with values.
-    ///
-    /// TODO:
-    /// Only two SQL formats has been supported to bind parameters as below.
-    ///     `insert into schema_name.table_name values ($1, 1), ($2, 2)`
-    ///     `update schema_name.table_name set col1 = $1, col2 = $2`
-    /// Needs to support other statements (as `select` and `delete`) and other
-    /// expressions in SQL (as `BinaryOp` and `UnaryOp` in `where` statement).
+    /// Replaces the parameters of prepared statement with values in every expression of `select`, `insert`,
+    /// `update` and `delete` statements.
     pub fn bind(&self, stmt: &mut Statement, params: &[PostgreSqlValue]) -> Result {
         match stmt {
-            Statement::Insert { .. } => bind_insert(stmt, params),
-            Statement::Update { .. } => bind_update(stmt, params),
+            Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. } => {
+                visit_exprs(stmt, &mut |expr| replace_expr_with_params(expr, params));
+                log::debug!("Bound SQL: {}", stmt);
+                Ok(())
+            }
             _ => {
                 self.sender
                     .send(Err(QueryError::feature_not_supported(format!(
@@ -53,58 +58,206 @@ impl ParamBinder {
             }
         }
     }
-}
 
-fn bind_insert(stmt: &mut Statement, params: &[PostgreSqlValue]) -> Result {
-    let mut body = match stmt {
-        Statement::Insert { source, .. } => {
-            let source: &mut Query = source;
-            let Query { body, .. } = source;
-            body
-        }
-        _ => return Err(()),
-    };
-
-    if let SetExpr::Values(values) = &mut body {
-        let values = &mut values.0;
-        for line in values {
-            for col in line {
-                replace_expr_with_params(col, params);
-            }
+    /// Checks that values of parameters can be assigned to or compared with the columns they are bound to
+    pub fn check_coercion(
+        &self,
+        targets: &[Param],
+        param_types: &[PostgreSqlType],
+        params: &[PostgreSqlValue],
+    ) -> Result {
+        for Param {
+            index,
+            column_name,
+            sql_type,
+        } in targets
+        {
+            let column_name = match column_name {
+                Some(column_name) => column_name,
+                None => continue,
+            };
+            let column_type: PostgreSqlType = sql_type.into();
+            let error = match (params.get(*index), param_types.get(*index)) {
+                (Some(PostgreSqlValue::String(value)), _) if !text_coercible(value, *sql_type) => {
+                    QueryError::invalid_text_representation(format!(
+                        "invalid input syntax for type {}: \"{}\"",
+                        column_type, value
+                    ))
+                }
+                (Some(value), Some(param_type)) if !coercible(value, *sql_type) => {
+                    QueryError::invalid_parameter_value(format!(
+                        "column \"{}\" is of type {} but parameter ${} is of type {}",
+                        column_name,
+                        column_type,
+                        index + 1,
+                        param_type
+                    ))
+                }
+                _ => continue,
+            };
+            self.sender.send(Err(error)).expect("To Send Bind Error");
+            return Err(());
         }
+        Ok(())
     }
+}
+
+/// Returns the highest index of parameters in expressions of the statement.
+pub(crate) fn referenced_params(stmt: &Statement) -> usize {
+    let mut referenced = 0;
+    visit_exprs(&mut stmt.clone(), &mut |expr| {
+        if let Some(index) = param_index(expr) {
+            referenced = referenced.max(index + 1);
+        }
+    });
+    referenced
+}
+
+/// Replaces `$n` placeholders in expressions of the statement with literals, `$1` with the first of them.
+pub(crate) fn bind_literals(stmt: &mut Statement, literals: &[Value]) {
+    visit_exprs(stmt, &mut |expr| bind_literal(expr, literals));
+}
 
-    log::debug!("Bound Insert SQL: {}", stmt);
-    Ok(())
+/// Replaces `$n` placeholders in the expression and in its subexpressions with literals.
+pub(crate) fn bind_expr(expr: &mut Expr, literals: &[Value]) {
+    visit_expr(expr, &mut |expr| bind_literal(expr, literals));
 }
 
-fn bind_update(stmt: &mut Statement, params: &[PostgreSqlValue]) -> Result {
-    let assignments = match stmt {
-        Statement::Update { assignments, .. } => assignments,
-        _ => return Err(()),
-    };
+fn bind_literal(expr: &mut Expr, literals: &[Value]) {
+    if let Some(literal) = param_index(expr).and_then(|index| literals.get(index)) {
+        *expr = Expr::Value(literal.clone());
+    }
+}
 
-    for assignment in assignments {
-        let Assignment { value, .. } = assignment;
-        replace_expr_with_params(value, params);
+/// calls `visit` on every expression of the statement and on their subexpressions
+fn visit_exprs<F: FnMut(&mut Expr)>(stmt: &mut Statement, visit: &mut F) {
+    match stmt {
+        Statement::Query(query) => visit_query(query, visit),
+        Statement::Insert { source, .. } => visit_query(source, visit),
+        Statement::Update {
+            assignments, selection, ..
+        } => {
+            for assignment in assignments.iter_mut() {
+                visit_expr(&mut assignment.value, visit);
+            }
+            if let Some(selection) = selection {
+                visit_expr(selection, visit);
+            }
+        }
+        Statement::Delete {
+            selection: Some(selection),
+            ..
+        } => visit_expr(selection, visit),
+        _ => {}
     }
+}
 
-    log::debug!("Bound Insert SQL: {}", stmt);
-    Ok(())
+fn visit_query<F: FnMut(&mut Expr)>(query: &mut Query, visit: &mut F) {
+    match &mut query.body {
+        SetExpr::Select(select) => {
+            for item in select.projection.iter_mut() {
+                if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item {
+                    visit_expr(expr, visit);
+                }
+            }
+            if let Some(selection) = &mut select.selection {
+                visit_expr(selection, visit);
+            }
+        }
+        SetExpr::Values(values) => {
+            for expr in values.0.iter_mut().flatten() {
+                visit_expr(expr, visit);
+            }
+        }
+        _ => {}
+    }
+    if let Some(limit) = &mut query.limit {
+        visit_expr(limit, visit);
+    }
+    if let Some(offset) = &mut query.offset {
+        visit_expr(&mut offset.value, visit);
+    }
+    if let Some(quantity) = query.fetch.as_mut().and_then(|fetch| fetch.quantity.as_mut()) {
+        visit_expr(quantity, visit);
+    }
 }
 
-fn parse_param_index(value: &str) -> Option<usize> {
-    let mut chars = value.chars();
-    if chars.next() != Some('$') || !chars.all(|c| c.is_digit(10)) {
-        return None;
+fn visit_expr<F: FnMut(&mut Expr)>(expr: &mut Expr, visit: &mut F) {
+    visit(expr);
+    match expr {
+        Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => visit_expr(expr, visit),
+        Expr::BinaryOp { left, right, .. } => {
+            visit_expr(left, visit);
+            visit_expr(right, visit);
+        }
+        Expr::Between { expr, low, high, .. } => {
+            visit_expr(expr, visit);
+            visit_expr(low, visit);
+            visit_expr(high, visit);
+        }
+        Expr::InList { expr, list, .. } => {
+            visit_expr(expr, visit);
+            for item in list.iter_mut() {
+                visit_expr(item, visit);
+            }
+        }
+        Expr::Function(function) => {
+            for arg in function.args.iter_mut() {
+                visit_expr(arg, visit);
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            for expr in operand.iter_mut().chain(else_result.iter_mut()) {
+                visit_expr(expr, visit);
+            }
+            for expr in conditions.iter_mut().chain(results.iter_mut()) {
+                visit_expr(expr, visit);
+            }
+        }
+        _ => {}
     }
+}
 
-    let index: usize = (&value[1..]).parse().unwrap();
-    if index == 0 {
-        return None;
+fn coercible(value: &PostgreSqlValue, sql_type: SqlType) -> bool {
+    match value {
+        PostgreSqlValue::Null | PostgreSqlValue::String(_) => true,
+        PostgreSqlValue::True | PostgreSqlValue::False => {
+            matches!(
+                sql_type,
+                SqlType::Bool | SqlType::Char(_) | SqlType::VarChar(_) | SqlType::Text
+            )
+        }
+        PostgreSqlValue::Int16(_) | PostgreSqlValue::Int32(_) | PostgreSqlValue::Int64(_) => matches!(
+            sql_type,
+            SqlType::SmallInt(_)
+                | SqlType::Integer(_)
+                | SqlType::BigInt(_)
+                | SqlType::Real
+                | SqlType::DoublePrecision
+                | SqlType::Decimal
+                | SqlType::Char(_)
+                | SqlType::VarChar(_)
+                | SqlType::Text
+        ),
     }
+}
 
-    Some(index - 1)
+fn text_coercible(value: &str, sql_type: SqlType) -> bool {
+    match sql_type {
+        SqlType::SmallInt(_) | SqlType::Integer(_) | SqlType::BigInt(_) => value.trim().parse::<i64>().is_ok(),
+        SqlType::Real | SqlType::DoublePrecision | SqlType::Decimal => value.trim().parse::<f64>().is_ok(),
+        SqlType::Bool => parse_bool(value).is_some(),
+        _ => true,
+    }
 }
 
 fn pg_value_to_expr(value: &PostgreSqlValue) -> Expr {
@@ -120,17 +273,7 @@ fn pg_value_to_expr(value: &PostgreSqlValue) -> Expr {
 }
 
 fn replace_expr_with_params(expr: &mut Expr, params: &[PostgreSqlValue]) {
-    let value = match expr {
-        Expr::Identifier(Ident { value, .. }) => value,
-        _ => return,
-    };
-
-    let index = match parse_param_index(value) {
-        Some(index) => index,
-        _ => return,
-    };
-
-    if index < params.len() {
-        *expr = pg_value_to_expr(&params[index]);
+    if let Some(param) = param_index(expr).and_then(|index| params.get(index)) {
+        *expr = pg_value_to_expr(param);
     }
 }
@@ -12,20 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{convert::TryFrom, ops::Deref, str::FromStr, sync::Arc};
+use std::{cmp::Ordering, convert::TryFrom, ops::Deref, sync::Arc};
 
-use sqlparser::ast::{Assignment, BinaryOperator, DataType, Expr, UnaryOperator, Value};
+use sqlparser::ast::{
+    Assignment, BinaryOperator, DataType, Expr, SelectItem, SetExpr, Statement, UnaryOperator, Value,
+};
 
-use data_manager::ColumnDefinition;
-use protocol::{results::QueryError, Sender};
+use data_manager::{ColumnDefinition, DataManager, SqlFunction};
+use protocol::{results::QueryError, session::FunctionContext, Sender};
+use query_planner::extended;
 use representation::{Datum, EvalError, ScalarType};
-use sql_model::sql_types::{ConstraintError, SqlType};
+use sql_model::sql_types::SqlType;
 
-use crate::query::scalar::ScalarOp;
+use crate::{
+    dml::column_value,
+    function::{ArrayAny, ArraySubscript, FunctionCall, FunctionRegistry, SqlBodyFunction, Volatility},
+    operator::{cast, compare, comparison_type, implicit_cast_cost, is_distinct, parse_bool, type_name, OperatorTable},
+    query::scalar::{Conditional, ScalarOp},
+    PreparedStatementDialect,
+};
+
+/// calls of user-defined functions nested deeper than this are stopped, e.g. a function that calls itself
+const MAX_FUNCTION_DEPTH: usize = 32;
 
 pub(crate) struct ExpressionEvaluation {
     session: Arc<dyn Sender>,
     table_info: Vec<ColumnDefinition>,
+    function_context: FunctionContext,
+    functions: Arc<FunctionRegistry>,
+    operators: Arc<OperatorTable>,
+    data_manager: Arc<DataManager>,
+    depth: usize,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,20 +69,203 @@ impl<'a> ExprMetadata<'a> {
 }
 
 impl ExpressionEvaluation {
-    pub(crate) fn new(session: Arc<dyn Sender>, table_info: Vec<ColumnDefinition>) -> ExpressionEvaluation {
-        ExpressionEvaluation { session, table_info }
+    pub(crate) fn new(
+        session: Arc<dyn Sender>,
+        table_info: Vec<ColumnDefinition>,
+        function_context: FunctionContext,
+        functions: Arc<FunctionRegistry>,
+        operators: Arc<OperatorTable>,
+        data_manager: Arc<DataManager>,
+    ) -> ExpressionEvaluation {
+        ExpressionEvaluation {
+            session,
+            table_info,
+            function_context,
+            functions,
+            operators,
+            data_manager,
+            depth: 0,
+        }
+    }
+
+    /// body of a `LANGUAGE SQL` function is compiled with its parameters as columns, functions that it calls are
+    /// compiled with it
+    pub(crate) fn compile_function(&self, function: &SqlFunction) -> Result<SqlBodyFunction, ()> {
+        if self.depth == MAX_FUNCTION_DEPTH {
+            self.session
+                .send(Err(QueryError::stack_depth_limit_exceeded()))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let expr = match Self::function_body(function.body()) {
+            Ok(expr) => expr,
+            Err(error) => {
+                self.session.send(Err(error)).expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        let parameters = function
+            .parameters()
+            .iter()
+            .map(|(name, sql_type)| ColumnDefinition::new(name, *sql_type))
+            .collect::<Vec<_>>();
+        let argument_types = parameters
+            .iter()
+            .map(|parameter| Self::convert_sql_type(parameter.sql_type()))
+            .collect();
+        let body_evaluation = ExpressionEvaluation {
+            session: self.session.clone(),
+            table_info: parameters,
+            function_context: self.function_context.clone(),
+            functions: self.functions.clone(),
+            operators: self.operators.clone(),
+            data_manager: self.data_manager.clone(),
+            depth: self.depth + 1,
+        };
+        let body = body_evaluation.inner_eval(&expr, None)?;
+        let return_type = Self::convert_sql_type(function.return_type());
+        if let Some(body_type) = Self::operand_type(&body) {
+            if implicit_cast_cost(body_type, return_type).is_none() {
+                self.session
+                    .send(Err(QueryError::invalid_function_definition(format!(
+                        "return type mismatch in function declared to return {}",
+                        function.return_type().to_string()
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        }
+        Ok(SqlBodyFunction::new(argument_types, return_type, body))
     }
 
+    /// the only statement of a function body is `SELECT <expression>` without any other clause
+    fn function_body(body: &str) -> Result<Expr, QueryError> {
+        let statements = extended::parse_sql(&PreparedStatementDialect {}, body).map_err(QueryError::syntax_error)?;
+        if let [Statement::Query(query)] = statements.as_slice() {
+            if let SetExpr::Select(select) = &query.body {
+                let is_plain = query.ctes.is_empty()
+                    && query.order_by.is_empty()
+                    && query.limit.is_none()
+                    && query.offset.is_none()
+                    && query.fetch.is_none()
+                    && !select.distinct
+                    && select.top.is_none()
+                    && select.from.is_empty()
+                    && select.selection.is_none()
+                    && select.group_by.is_empty()
+                    && select.having.is_none();
+                match select.projection.as_slice() {
+                    [SelectItem::UnnamedExpr(expr)] | [SelectItem::ExprWithAlias { expr, .. }] if is_plain => {
+                        return Ok(expr.clone())
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Err(QueryError::invalid_function_definition(
+            "function body has to be a single SELECT of one expression",
+        ))
+    }
+
+    /// functions created with `CREATE FUNCTION` are called by their schema qualified names
+    fn user_function(&self, expr: &Expr) -> Option<SqlFunction> {
+        match expr {
+            Expr::Function(function) => match function.name.0.as_slice() {
+                [schema_name, function_name] => {
+                    self.data_manager
+                        .schema_exists(&schema_name.value)
+                        .and_then(|schema_id| {
+                            self.data_manager
+                                .function(schema_id, &function_name.value.to_lowercase())
+                        })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// expressions without columns are evaluated right away including volatile function calls
     pub(crate) fn eval<'a>(&self, expr: &Expr, expr_metadata: Option<ExprMetadata<'a>>) -> Result<ScalarOp, ()> {
-        self.inner_eval(expr, expr_metadata)
+        self.eval_typed(expr, expr_metadata).map(|(value, _)| value)
+    }
+
+    /// evaluates as `eval` does along with the type of the expression, which NULL result of a constant expression
+    /// keeps; bare NULL literal has no type
+    pub(crate) fn eval_typed<'a>(
+        &self,
+        expr: &Expr,
+        expr_metadata: Option<ExprMetadata<'a>>,
+    ) -> Result<(ScalarOp, Option<ScalarType>), ()> {
+        let value = self.inner_eval(expr, expr_metadata)?;
+        let ty = match value.as_datum() {
+            Some(datum) => datum.scalar_type(),
+            None => Some(value.scalar_type()),
+        };
+        if !value.is_literal() && value.is_constant() {
+            EvalScalarOp::new(self.session.as_ref(), vec![], self.function_context.utc_offset())
+                .eval(&[], &value)
+                .map(|datum| (ScalarOp::Literal(datum), ty))
+        } else {
+            Ok((value, ty))
+        }
+    }
+
+    /// condition of a clause as `WHERE` has to be boolean or NULL
+    pub(crate) fn eval_condition(&self, expr: &Expr, clause: &str) -> Result<ScalarOp, ()> {
+        let condition = self.eval(expr, None)?;
+        self.check_condition(&condition, clause)?;
+        Ok(condition)
+    }
+
+    fn check_condition(&self, condition: &ScalarOp, clause: &str) -> Result<(), ()> {
+        match Self::operand_type(condition) {
+            Some(ScalarType::Boolean) | None => Ok(()),
+            Some(ty) => {
+                self.session
+                    .send(Err(QueryError::datatype_mismatch(format!(
+                        "argument of {} must be type boolean, not type {}",
+                        clause,
+                        type_name(ty)
+                    ))))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    /// volatile function calls are kept to be called for every row even when they read no column
+    pub(crate) fn eval_for_rows(&self, expr: &Expr) -> Result<ScalarOp, ()> {
+        self.inner_eval(expr, None)
+    }
+
+    /// operations of literals are evaluated into literals except ones resulting in NULL,
+    /// which are kept to not lose their type
+    fn fold(&self, operation: ScalarOp) -> Result<ScalarOp, ()> {
+        let datum = EvalScalarOp::new(self.session.as_ref(), vec![], self.function_context.utc_offset())
+            .eval(&[], &operation)?;
+        if datum.is_null() {
+            Ok(operation)
+        } else {
+            Ok(ScalarOp::Literal(datum))
+        }
     }
 
     fn inner_eval<'a>(&self, expr: &Expr, expr_metadata: Option<ExprMetadata<'a>>) -> Result<ScalarOp, ()> {
         match expr {
             Expr::Cast { expr, data_type } => match (&**expr, data_type) {
-                (Expr::Value(Value::SingleQuotedString(v)), DataType::Boolean) => {
-                    Ok(ScalarOp::Literal(Datum::from_bool(bool::from_str(v).unwrap())))
-                }
+                (Expr::Value(Value::SingleQuotedString(v)), DataType::Boolean) => match parse_bool(v) {
+                    Some(value) => Ok(ScalarOp::Literal(Datum::from_bool(value))),
+                    None => {
+                        self.session
+                            .send(Err(QueryError::invalid_text_representation(format!(
+                                "invalid input syntax for type boolean: \"{}\"",
+                                v
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
+                },
                 (Expr::Value(Value::Boolean(val)), DataType::Boolean) => Ok(ScalarOp::Literal(Datum::from_bool(*val))),
                 _ => {
                     self.session
@@ -90,7 +290,7 @@ impl ExpressionEvaluation {
                                     EvalError::OutOfRangeNumeric(_) => QueryError::out_of_range(
                                         meta_data.column().sql_type().to_pg_types(),
                                         meta_data.column().name(),
-                                        meta_data.index(),
+                                        meta_data.index() + 1,
                                     ),
                                     EvalError::UnsupportedOperation => {
                                         QueryError::feature_not_supported("Use of unsupported expression feature")
@@ -114,36 +314,85 @@ impl ExpressionEvaluation {
                         }
                     }
                 }
-                (op, _operand) => {
-                    self.session
-                        .send(Err(QueryError::syntax_error(
-                            op.to_string() + expr.to_string().as_str(),
-                        )))
-                        .expect("To Send Query Result to Client");
-                    Err(())
+                (op, operand) => {
+                    let operand = self.inner_eval(operand, expr_metadata)?;
+                    let operand_type = match operand.as_datum() {
+                        Some(datum) if datum.is_null() => return Ok(operand),
+                        _ => operand.scalar_type(),
+                    };
+                    match self.operators.resolve_unary(op, operand_type) {
+                        Some(operation) => {
+                            let is_literal = operand.is_literal();
+                            let unary = ScalarOp::Unary(operation, Box::new(operand));
+                            if is_literal {
+                                self.fold(unary)
+                            } else {
+                                Ok(unary)
+                            }
+                        }
+                        None => {
+                            self.session
+                                .send(Err(QueryError::syntax_error(
+                                    op.to_string() + expr.to_string().as_str(),
+                                )))
+                                .expect("To Send Query Result to Client");
+                            Err(())
+                        }
+                    }
                 }
             },
+            Expr::BinaryOp { op, left, right } if Self::any_argument(right).is_some() => {
+                let value = self.inner_eval(left.deref(), expr_metadata)?;
+                let array = self.inner_eval(Self::any_argument(right).expect("ANY argument"), None)?;
+                self.any(op, value, array, expr)
+            }
             Expr::BinaryOp { op, left, right } => {
                 let lhs = self.inner_eval(left.deref(), expr_metadata)?;
                 let rhs = self.inner_eval(right.deref(), expr_metadata)?;
-                if let Some(ty) = self.compatible_types_for_op(op.clone(), lhs.scalar_type(), rhs.scalar_type()) {
-                    match (lhs, rhs) {
-                        (ScalarOp::Literal(left), ScalarOp::Literal(right)) => {
-                            EvalScalarOp::eval_binary_literal_expr(self.session.as_ref(), op.clone(), left, right)
-                                .map(ScalarOp::Literal)
+                // NULL literal takes the type of the other operand
+                let (left_type, right_type) = match (Self::operand_type(&lhs), Self::operand_type(&rhs)) {
+                    (None, None) => return Ok(ScalarOp::Literal(Datum::from_null())),
+                    (Some(left_type), None) => (left_type, left_type),
+                    (None, Some(right_type)) => (right_type, right_type),
+                    (Some(left_type), Some(right_type)) => (left_type, right_type),
+                };
+                match self.operators.resolve_binary(op, left_type, right_type) {
+                    Some(operation) => {
+                        if let Some(simplified) = Self::simplify_logic(op, &lhs, &rhs) {
+                            return Ok(simplified);
+                        }
+                        let is_literal = lhs.is_literal() && rhs.is_literal();
+                        let binary = ScalarOp::Binary(operation, Box::new(lhs), Box::new(rhs));
+                        if is_literal {
+                            self.fold(binary)
+                        } else {
+                            Ok(binary)
                         }
-                        (left, right) => Ok(ScalarOp::Binary(op.clone(), Box::new(left), Box::new(right), ty)),
                     }
-                } else {
-                    let kind = QueryError::undefined_function(
-                        op.to_string(),
-                        lhs.scalar_type().to_string(),
-                        rhs.scalar_type().to_string(),
-                    );
-                    self.session.send(Err(kind)).expect("To Send Query Result to Client");
-                    Err(())
+                    None => {
+                        let kind =
+                            QueryError::undefined_function(op.to_string(), type_name(left_type), type_name(right_type));
+                        self.session.send(Err(kind)).expect("To Send Query Result to Client");
+                        Err(())
+                    }
                 }
             }
+            Expr::Value(interval @ Value::Interval { .. }) => match Datum::try_from(interval) {
+                Ok(datum) => Ok(ScalarOp::Literal(datum)),
+                Err(_) => {
+                    let text = match interval {
+                        Value::Interval { value, .. } => value,
+                        _ => unreachable!(),
+                    };
+                    self.session
+                        .send(Err(QueryError::invalid_datetime_format(format!(
+                            "invalid input syntax for type interval: \"{}\"",
+                            text
+                        ))))
+                        .expect("To Send Query Result to Client");
+                    Err(())
+                }
+            },
             Expr::Value(value) => match Datum::try_from(value) {
                 Ok(datum) => Ok(ScalarOp::Literal(datum)),
                 Err(e) => {
@@ -155,7 +404,7 @@ impl ExpressionEvaluation {
                             EvalError::OutOfRangeNumeric(_) => QueryError::out_of_range(
                                 meta_data.column().sql_type().to_pg_types(),
                                 meta_data.column().name(),
-                                meta_data.index(),
+                                meta_data.index() + 1,
                             ),
                             EvalError::UnsupportedOperation => {
                                 QueryError::feature_not_supported("Use of unsupported expression feature")
@@ -179,6 +428,91 @@ impl ExpressionEvaluation {
                     Err(())
                 }
             },
+            Expr::Identifier(ident)
+                if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("current_timestamp") =>
+            {
+                self.call_function("now", vec![], expr)
+            }
+            Expr::Identifier(ident)
+                if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("current_date") =>
+            {
+                self.call_function("current_date", vec![], expr)
+            }
+            Expr::Nested(expr) => self.inner_eval(expr, expr_metadata),
+            Expr::IsNull(operand) => self.is_null(operand, false, expr_metadata),
+            Expr::IsNotNull(operand) => self.is_null(operand, true, expr_metadata),
+            Expr::InList { expr, list, negated } if !list.is_empty() => {
+                // `x IN (a, b)` is `x = a OR x = b`, so NULLs in the list are handled as `=` handles them
+                let comparisons = list.iter().map(|item| Expr::BinaryOp {
+                    left: expr.clone(),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(item.clone()),
+                });
+                let any = comparisons
+                    .reduce(|left, right| Expr::BinaryOp {
+                        left: Box::new(left),
+                        op: BinaryOperator::Or,
+                        right: Box::new(right),
+                    })
+                    .expect("IN list is not empty");
+                self.inner_eval(&Self::negate(any, *negated), expr_metadata)
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let (low_op, high_op, op) = if *negated {
+                    (BinaryOperator::Lt, BinaryOperator::Gt, BinaryOperator::Or)
+                } else {
+                    (BinaryOperator::GtEq, BinaryOperator::LtEq, BinaryOperator::And)
+                };
+                let range = Expr::BinaryOp {
+                    left: Box::new(Expr::BinaryOp {
+                        left: expr.clone(),
+                        op: low_op,
+                        right: low.clone(),
+                    }),
+                    op,
+                    right: Box::new(Expr::BinaryOp {
+                        left: expr.clone(),
+                        op: high_op,
+                        right: high.clone(),
+                    }),
+                };
+                self.inner_eval(&range, expr_metadata)
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => self.case(
+                operand.as_deref(),
+                conditions,
+                results,
+                else_result.as_deref(),
+                expr_metadata,
+            ),
+            Expr::Function(function) => {
+                let mut args = vec![];
+                for arg in function.args.iter() {
+                    args.push(self.inner_eval(arg, None)?);
+                }
+                match function.name.to_string().to_lowercase().as_str() {
+                    "is_distinct_from" => self.distinct_from(args, false, expr),
+                    "is_not_distinct_from" => self.distinct_from(args, true, expr),
+                    "json_extract" => self.json_extract(args, false, expr),
+                    "json_extract_text" => self.json_extract(args, true, expr),
+                    "coalesce" => self.conditional(Conditional::Coalesce, args, expr),
+                    "nullif" => self.conditional(Conditional::NullIf, args, expr),
+                    "greatest" => self.conditional(Conditional::Greatest, args, expr),
+                    "least" => self.conditional(Conditional::Least, args, expr),
+                    "array_subscript" => self.array_subscript(function.args.first(), args, expr),
+                    name => self.call_function(name, args, expr),
+                }
+            }
             Expr::Identifier(ident) => {
                 if let Some((idx, column_def)) = self.find_column_by_name(ident.value.as_str())? {
                     let scalar_type = column_def.sql_type();
@@ -205,9 +539,353 @@ impl ExpressionEvaluation {
         }
     }
 
+    /// `AND` with a `false` operand and `OR` with a `true` one are decided by it, a `true` operand of `AND` and a
+    /// `false` one of `OR` are dropped. Operands are simplified after both are type checked, so type errors of
+    /// dropped operands are still reported
+    fn simplify_logic(op: &BinaryOperator, lhs: &ScalarOp, rhs: &ScalarOp) -> Option<ScalarOp> {
+        let (deciding, neutral) = match op {
+            BinaryOperator::And => (Datum::from_bool(false), Datum::from_bool(true)),
+            BinaryOperator::Or => (Datum::from_bool(true), Datum::from_bool(false)),
+            _ => return None,
+        };
+        match (lhs.as_datum(), rhs.as_datum()) {
+            (Some(left), _) if left == deciding => Some(ScalarOp::Literal(deciding)),
+            (_, Some(right)) if right == deciding => Some(ScalarOp::Literal(deciding)),
+            // dropping the neutral operand would leave an untyped NULL literal
+            (Some(left), Some(right)) if left.is_null() || right.is_null() => None,
+            (Some(left), _) if left == neutral => Some(rhs.clone()),
+            (_, Some(right)) if right == neutral => Some(lhs.clone()),
+            _ => None,
+        }
+    }
+
+    fn negate(expr: Expr, negated: bool) -> Expr {
+        if negated {
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: Box::new(Expr::Nested(Box::new(expr))),
+            }
+        } else {
+            expr
+        }
+    }
+
+    fn is_null<'a>(
+        &self,
+        operand: &Expr,
+        negated: bool,
+        expr_metadata: Option<ExprMetadata<'a>>,
+    ) -> Result<ScalarOp, ()> {
+        let operand = self.inner_eval(operand, expr_metadata)?;
+        let is_literal = operand.is_literal();
+        let is_null = ScalarOp::IsNull {
+            operand: Box::new(operand),
+            negated,
+        };
+        if is_literal {
+            self.fold(is_null)
+        } else {
+            Ok(is_null)
+        }
+    }
+
+    /// `CASE <operand> WHEN <value>` compares the operand with every value as `=` does, conditions have to be
+    /// boolean and results are casted to their common type
+    fn case<'a>(
+        &self,
+        operand: Option<&Expr>,
+        conditions: &[Expr],
+        results: &[Expr],
+        else_result: Option<&Expr>,
+        expr_metadata: Option<ExprMetadata<'a>>,
+    ) -> Result<ScalarOp, ()> {
+        let mut branches = vec![];
+        for (condition, result) in conditions.iter().zip(results.iter()) {
+            let condition = match operand {
+                Some(operand) => self.inner_eval(
+                    &Expr::BinaryOp {
+                        left: Box::new(operand.clone()),
+                        op: BinaryOperator::Eq,
+                        right: Box::new(condition.clone()),
+                    },
+                    expr_metadata,
+                )?,
+                None => self.inner_eval(condition, expr_metadata)?,
+            };
+            self.check_condition(&condition, "CASE/WHEN")?;
+            branches.push((condition, self.inner_eval(result, expr_metadata)?));
+        }
+        let else_result = match else_result {
+            Some(else_result) => Some(Box::new(self.inner_eval(else_result, expr_metadata)?)),
+            None => None,
+        };
+        let mut common_type: Option<ScalarType> = None;
+        let result_types = branches
+            .iter()
+            .map(|(_, result)| result)
+            .chain(else_result.as_deref())
+            .filter_map(Self::operand_type);
+        for ty in result_types {
+            common_type = match common_type {
+                None => Some(ty),
+                Some(current) => match comparison_type(current, ty) {
+                    Some(common) => Some(common),
+                    None => {
+                        self.session
+                            .send(Err(QueryError::datatype_mismatch(format!(
+                                "CASE types {} and {} cannot be matched",
+                                type_name(current),
+                                type_name(ty)
+                            ))))
+                            .expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                },
+            };
+        }
+        let is_literal = branches
+            .iter()
+            .all(|(condition, result)| condition.is_literal() && result.is_literal())
+            && else_result.iter().all(|result| result.is_literal());
+        let case = ScalarOp::Case {
+            branches,
+            else_result,
+            ty: common_type.unwrap_or(ScalarType::String),
+        };
+        if is_literal {
+            self.fold(case)
+        } else {
+            Ok(case)
+        }
+    }
+
+    /// functions of names registered with a builder are built for the types of arguments
+    fn call_function(&self, name: &str, args: Vec<ScalarOp>, expr: &Expr) -> Result<ScalarOp, ()> {
+        let argument_types = Self::argument_types(&args);
+        let function = match self.functions.builder(name) {
+            Some(builder) => match builder.build(&argument_types) {
+                Ok(function) => Some(function),
+                Err(error) => {
+                    self.session.send(Err(error)).expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            },
+            None => match (self.functions.resolve(name, &argument_types), self.user_function(expr)) {
+                (None, Some(user_function)) => Some(Arc::new(self.compile_function(&user_function)?) as Arc<_>),
+                (function, _) => function,
+            },
+        };
+        match function {
+            Some(function) => self.apply(
+                FunctionCall::new(name.to_lowercase(), function, self.function_context.clone()),
+                args,
+                expr,
+            ),
+            None => {
+                self.session
+                    .send(Err(QueryError::function_does_not_exist(expr)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    /// types of arguments and whether they are NULL literals
+    fn argument_types(args: &[ScalarOp]) -> Vec<(ScalarType, bool)> {
+        args.iter()
+            .map(|arg| match arg.as_datum() {
+                Some(datum) if datum.is_null() => (ScalarType::String, true),
+                _ => (arg.scalar_type(), false),
+            })
+            .collect()
+    }
+
+    /// calls of non volatile functions with constant arguments are folded into literals
+    fn apply(&self, call: FunctionCall, args: Vec<ScalarOp>, expr: &Expr) -> Result<ScalarOp, ()> {
+        if !call.accepts(&Self::argument_types(&args)) {
+            self.session
+                .send(Err(QueryError::function_does_not_exist(expr)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let ty = call.return_type();
+        let is_foldable = call.volatility() != Volatility::Volatile && args.iter().all(ScalarOp::is_literal);
+        let function = ScalarOp::Function(call, args, ty);
+        if is_foldable {
+            self.fold(function)
+        } else {
+            Ok(function)
+        }
+    }
+
+    /// `IS [NOT] DISTINCT FROM` is parsed into a call of `is_distinct_from` or `is_not_distinct_from`
+    fn distinct_from(&self, mut args: Vec<ScalarOp>, negated: bool, expr: &Expr) -> Result<ScalarOp, ()> {
+        if args.len() != 2 {
+            self.session
+                .send(Err(QueryError::function_does_not_exist(expr)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let right = args.pop().unwrap();
+        let left = args.pop().unwrap();
+        let ty = match (Self::operand_type(&left), Self::operand_type(&right)) {
+            (Some(left_type), Some(right_type)) => match comparison_type(left_type, right_type) {
+                Some(ty) => ty,
+                None => {
+                    let operator = if negated {
+                        "IS NOT DISTINCT FROM"
+                    } else {
+                        "IS DISTINCT FROM"
+                    };
+                    let kind = QueryError::undefined_function(
+                        operator.to_owned(),
+                        type_name(left_type),
+                        type_name(right_type),
+                    );
+                    self.session.send(Err(kind)).expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            },
+            (Some(ty), None) | (None, Some(ty)) => ty,
+            (None, None) => ScalarType::String,
+        };
+        let is_literal = left.is_literal() && right.is_literal();
+        let distinct_from = ScalarOp::DistinctFrom {
+            left: Box::new(left),
+            right: Box::new(right),
+            ty,
+            negated,
+        };
+        if is_literal {
+            EvalScalarOp::new(self.session.as_ref(), vec![], self.function_context.utc_offset())
+                .eval(&[], &distinct_from)
+                .map(ScalarOp::Literal)
+        } else {
+            Ok(distinct_from)
+        }
+    }
+
+    /// arguments of `COALESCE`, `GREATEST` and `LEAST` are casted to their common type,
+    /// `NULLIF` compares its arguments as `=` does and has the type of the first one
+    fn conditional(&self, kind: Conditional, args: Vec<ScalarOp>, expr: &Expr) -> Result<ScalarOp, ()> {
+        if args.is_empty() || (kind == Conditional::NullIf && args.len() != 2) {
+            self.session
+                .send(Err(QueryError::function_does_not_exist(expr)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        let mut common_type: Option<ScalarType> = None;
+        for ty in args.iter().filter_map(Self::operand_type) {
+            common_type = match common_type {
+                None => Some(ty),
+                Some(current) => match comparison_type(current, ty) {
+                    Some(common) => Some(common),
+                    None => {
+                        let error = if kind == Conditional::NullIf {
+                            QueryError::undefined_function("=".to_owned(), type_name(current), type_name(ty))
+                        } else {
+                            QueryError::datatype_mismatch(format!(
+                                "{} types {} and {} cannot be matched",
+                                kind.name(),
+                                type_name(current),
+                                type_name(ty)
+                            ))
+                        };
+                        self.session.send(Err(error)).expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                },
+            };
+        }
+        let ty = match kind {
+            Conditional::NullIf => Self::operand_type(&args[0]),
+            _ => common_type,
+        }
+        .unwrap_or(ScalarType::String);
+        let is_literal = args.iter().all(ScalarOp::is_literal);
+        let conditional = ScalarOp::Conditional(kind, args, ty);
+        if is_literal {
+            self.fold(conditional)
+        } else {
+            Ok(conditional)
+        }
+    }
+
+    /// `->` and `->>` are parsed into calls of `json_extract` and `json_extract_text`, integer keys are indexes of arrays
+    fn json_extract(&self, args: Vec<ScalarOp>, as_text: bool, expr: &Expr) -> Result<ScalarOp, ()> {
+        let step = match args.get(1).and_then(Self::operand_type) {
+            Some(ty) if ty.is_integer() => "array_element",
+            _ => "object_field",
+        };
+        let name = if as_text {
+            format!("json_{}_text", step)
+        } else {
+            format!("json_{}", step)
+        };
+        self.call_function(name.as_str(), args, expr)
+    }
+
+    /// `<array>[<index>]` is parsed into a call of `array_subscript`, elements of array columns have the column element type
+    fn array_subscript(&self, array: Option<&Expr>, args: Vec<ScalarOp>, expr: &Expr) -> Result<ScalarOp, ()> {
+        let element_type = match array {
+            Some(Expr::Identifier(ident)) => self
+                .find_column_by_name(ident.value.as_str())?
+                .and_then(|(_, column)| column.sql_type().element_type())
+                .map(Self::convert_sql_type),
+            _ => None,
+        };
+        let function = ArraySubscript::new(element_type.unwrap_or(ScalarType::String));
+        self.apply(
+            FunctionCall::new(
+                "array_subscript".to_owned(),
+                Arc::new(function),
+                self.function_context.clone(),
+            ),
+            args,
+            expr,
+        )
+    }
+
+    /// the argument of `ANY` on the right side of a comparison
+    fn any_argument(expr: &Expr) -> Option<&Expr> {
+        match expr {
+            Expr::Function(function) if function.name.to_string().eq_ignore_ascii_case("any") => {
+                match function.args.as_slice() {
+                    [argument] => Some(argument),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn any(&self, op: &BinaryOperator, value: ScalarOp, array: ScalarOp, expr: &Expr) -> Result<ScalarOp, ()> {
+        let value_type = Self::operand_type(&value).unwrap_or(ScalarType::String);
+        match op {
+            BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq => {
+                let function = ArrayAny::new(op.clone(), value_type);
+                self.apply(
+                    FunctionCall::new("any".to_owned(), Arc::new(function), self.function_context.clone()),
+                    vec![value, array],
+                    expr,
+                )
+            }
+            _ => {
+                let kind = QueryError::undefined_function(op.to_string(), type_name(value_type), "ARRAY".to_owned());
+                self.session.send(Err(kind)).expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
     pub fn eval_assignment(&self, assignment: &Assignment) -> Result<ScalarOp, ()> {
         let Assignment { id, value } = assignment;
-        let (destination, _column_def) = if let Some((idx, def)) = self.find_column_by_name(id.value.as_str())? {
+        let (destination, column_def) = if let Some((idx, def)) = self.find_column_by_name(id.value.as_str())? {
             (idx, def)
         } else {
             let kind = QueryError::column_does_not_exist(id.value.as_str());
@@ -215,8 +893,13 @@ impl ExpressionEvaluation {
             return Err(());
         };
 
-        let value = self.eval(value, None)?;
-        let ty = value.scalar_type();
+        // volatile functions are called for every updated row
+        let value = self.inner_eval(value, None)?;
+        // NULL literal does not have a type on its own, it takes the type of the column
+        let ty = match value.as_datum() {
+            Some(datum) if datum.is_null() => Self::convert_sql_type(column_def.sql_type()),
+            _ => value.scalar_type(),
+        };
 
         Ok(ScalarOp::Assignment {
             destination,
@@ -243,67 +926,37 @@ impl ExpressionEvaluation {
         }
     }
 
-    pub fn compatible_types_for_op(
-        &self,
-        op: BinaryOperator,
-        lhs_type: ScalarType,
-        rhs_type: ScalarType,
-    ) -> Option<ScalarType> {
-        if lhs_type == rhs_type {
-            if lhs_type.is_integer() {
-                match op {
-                    BinaryOperator::Plus
-                    | BinaryOperator::Minus
-                    | BinaryOperator::Multiply
-                    | BinaryOperator::Divide
-                    | BinaryOperator::Modulus
-                    | BinaryOperator::BitwiseAnd
-                    | BinaryOperator::BitwiseOr => Some(lhs_type),
-                    BinaryOperator::StringConcat => Some(ScalarType::String),
-                    _ => None,
-                }
-            } else if lhs_type.is_float() {
-                match op {
-                    BinaryOperator::Plus
-                    | BinaryOperator::Minus
-                    | BinaryOperator::Multiply
-                    | BinaryOperator::Divide => Some(lhs_type),
-                    _ => None,
-                }
-            } else if lhs_type.is_string() {
-                match op {
-                    BinaryOperator::StringConcat => Some(ScalarType::String),
-                    _ => None,
-                }
-            } else {
-                None
-            }
-        } else if (lhs_type.is_string() && rhs_type.is_integer()) || (lhs_type.is_integer() && rhs_type.is_string()) {
-            match op {
-                BinaryOperator::StringConcat => Some(ScalarType::String),
-                _ => None,
-            }
-        } else {
-            None
+    fn operand_type(operand: &ScalarOp) -> Option<ScalarType> {
+        match operand.as_datum() {
+            Some(datum) if datum.is_null() => None,
+            _ => Some(operand.scalar_type()),
         }
     }
 
     fn convert_sql_type(sql_type: SqlType) -> ScalarType {
         match sql_type {
             SqlType::Bool => ScalarType::Boolean,
-            SqlType::Char(_) | SqlType::VarChar(_) => ScalarType::String,
+            SqlType::Char(_)
+            | SqlType::VarChar(_)
+            | SqlType::Text
+            | SqlType::Json
+            | SqlType::Jsonb
+            | SqlType::SmallIntArray
+            | SqlType::IntegerArray
+            | SqlType::BigIntArray
+            | SqlType::TextArray
+            | SqlType::Enum => ScalarType::String,
             SqlType::SmallInt(_) => ScalarType::Int16,
             SqlType::Integer(_) => ScalarType::Int32,
             SqlType::BigInt(_) => ScalarType::Int64,
             SqlType::Real => ScalarType::Float32,
             SqlType::DoublePrecision => ScalarType::Float64,
-            SqlType::Time
-            | SqlType::TimeWithTimeZone
-            | SqlType::Timestamp
-            | SqlType::TimestampWithTimeZone
-            | SqlType::Date
-            | SqlType::Interval
-            | SqlType::Decimal => panic!(),
+            SqlType::TimestampWithTimeZone => ScalarType::Timestamp,
+            SqlType::Interval => ScalarType::Interval,
+            SqlType::Bytea => ScalarType::Bytes,
+            SqlType::Time | SqlType::TimeWithTimeZone | SqlType::Timestamp | SqlType::Date | SqlType::Decimal => {
+                panic!()
+            }
         }
     }
 }
@@ -311,21 +964,72 @@ impl ExpressionEvaluation {
 pub struct EvalScalarOp<'a> {
     session: &'a dyn Sender,
     columns: Vec<ColumnDefinition>,
+    utc_offset: i32,
 }
 
 impl<'a> EvalScalarOp<'a> {
-    pub fn new(session: &'a dyn Sender, columns: Vec<ColumnDefinition>) -> Self {
-        Self { session, columns }
+    pub fn new(session: &'a dyn Sender, columns: Vec<ColumnDefinition>, utc_offset: i32) -> Self {
+        Self {
+            session,
+            columns,
+            utc_offset,
+        }
     }
 
     pub fn eval<'b>(&self, row: &[Datum<'b>], eval: &ScalarOp) -> Result<Datum<'b>, ()> {
+        self.report(Self::evaluate(row, eval))
+    }
+
+    /// errors are returned instead of being sent, function bodies are evaluated this way
+    pub(crate) fn evaluate<'b>(row: &[Datum<'b>], eval: &ScalarOp) -> Result<Datum<'b>, QueryError> {
         match eval {
             ScalarOp::Column(idx, _) => Ok(row[*idx].clone()),
             ScalarOp::Literal(datum) => Ok(datum.clone()),
-            ScalarOp::Binary(op, lhs, rhs, _) => {
-                let left = self.eval(row, lhs.as_ref())?;
-                let right = self.eval(row, rhs.as_ref())?;
-                Self::eval_binary_literal_expr(self.session, op.clone(), left, right)
+            ScalarOp::Binary(operation, lhs, rhs) => {
+                let left = Self::evaluate(row, lhs.as_ref())?;
+                let right = Self::evaluate(row, rhs.as_ref())?;
+                operation.apply(left, right)
+            }
+            ScalarOp::Unary(operation, operand) => {
+                let operand = Self::evaluate(row, operand.as_ref())?;
+                operation.apply(operand)
+            }
+            ScalarOp::DistinctFrom {
+                left,
+                right,
+                ty,
+                negated,
+            } => {
+                let left = Self::evaluate(row, left.as_ref())?;
+                let right = Self::evaluate(row, right.as_ref())?;
+                Ok(Datum::from_bool(is_distinct(left, right, *ty) != *negated))
+            }
+            ScalarOp::Conditional(kind, args, ty) => Self::evaluate_conditional(row, *kind, args, *ty),
+            ScalarOp::IsNull { operand, negated } => {
+                let operand = Self::evaluate(row, operand.as_ref())?;
+                Ok(Datum::from_bool(operand.is_null() != *negated))
+            }
+            ScalarOp::Case {
+                branches,
+                else_result,
+                ty,
+            } => {
+                for (condition, result) in branches.iter() {
+                    if Self::evaluate(row, condition)? == Datum::from_bool(true) {
+                        return Ok(cast(Self::evaluate(row, result)?, *ty));
+                    }
+                }
+                match else_result {
+                    Some(result) => Ok(cast(Self::evaluate(row, result)?, *ty)),
+                    None => Ok(Datum::from_null()),
+                }
+            }
+            ScalarOp::Function(call, args, _) => {
+                let mut values = vec![];
+                for arg in args.iter() {
+                    values.push(Self::evaluate(row, arg)?);
+                }
+                call.call(&values)
             }
             ScalarOp::Assignment { .. } => {
                 panic!("EvalScalarOp:eval should not be evaluated on a ScalarOp::Assignment")
@@ -333,6 +1037,54 @@ impl<'a> EvalScalarOp<'a> {
         }
     }
 
+    /// `COALESCE` stops at the first non NULL argument, `GREATEST` and `LEAST` ignore NULLs
+    fn evaluate_conditional<'b>(
+        row: &[Datum<'b>],
+        kind: Conditional,
+        args: &[ScalarOp],
+        ty: ScalarType,
+    ) -> Result<Datum<'b>, QueryError> {
+        match kind {
+            Conditional::Coalesce => {
+                for arg in args.iter() {
+                    let value = Self::evaluate(row, arg)?;
+                    if !value.is_null() {
+                        return Ok(cast(value, ty));
+                    }
+                }
+                Ok(Datum::from_null())
+            }
+            Conditional::NullIf => {
+                let left = Self::evaluate(row, &args[0])?;
+                let right = Self::evaluate(row, &args[1])?;
+                let comparison = match (left.scalar_type(), right.scalar_type()) {
+                    (Some(left_type), Some(right_type)) => comparison_type(left_type, right_type).unwrap_or(ty),
+                    _ => ty,
+                };
+                if left.is_null() || right.is_null() || is_distinct(left.clone(), right, comparison) {
+                    Ok(left)
+                } else {
+                    Ok(Datum::from_null())
+                }
+            }
+            Conditional::Greatest | Conditional::Least => {
+                let wanted = if kind == Conditional::Greatest {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                };
+                let mut result = Datum::from_null();
+                for arg in args.iter() {
+                    let value = cast(Self::evaluate(row, arg)?, ty);
+                    if !value.is_null() && (result.is_null() || compare(&value, &result) == wanted) {
+                        result = value;
+                    }
+                }
+                Ok(result)
+            }
+        }
+    }
+
     pub fn eval_on_row(&self, row: &mut [Datum], eval: &ScalarOp, row_idx: usize) -> Result<(), ()> {
         match eval {
             ScalarOp::Assignment {
@@ -341,39 +1093,10 @@ impl<'a> EvalScalarOp<'a> {
                 ty: _,
             } => {
                 let value = self.eval(row, value.as_ref())?;
-                let column = &self.columns[*destination];
-                match column.sql_type().constraint().validate(value.to_string().as_str()) {
-                    Ok(()) => row[*destination] = value,
-                    Err(ConstraintError::OutOfRange) => {
-                        self.session
-                            .send(Err(QueryError::out_of_range(
-                                (&column.sql_type()).into(),
-                                column.name(),
-                                row_idx + 1,
-                            )))
-                            .expect("To Send Query Result to client");
-                        return Err(());
-                    }
-                    Err(ConstraintError::TypeMismatch(value)) => {
-                        self.session
-                            .send(Err(QueryError::type_mismatch(
-                                &value,
-                                (&column.sql_type()).into(),
-                                &column.name(),
-                                row_idx + 1,
-                            )))
-                            .expect("To Send Query Result to client");
-                        return Err(());
-                    }
-                    Err(ConstraintError::ValueTooLong(len)) => {
-                        self.session
-                            .send(Err(QueryError::string_length_mismatch(
-                                (&column.sql_type()).into(),
-                                len,
-                                column.name(),
-                                row_idx + 1,
-                            )))
-                            .expect("To Send Query Result to client");
+                match column_value(value, &self.columns[*destination], row_idx, self.utc_offset) {
+                    Ok(value) => row[*destination] = value,
+                    Err(error) => {
+                        self.session.send(Err(error)).expect("To Send Query Result to client");
                         return Err(());
                     }
                 }
@@ -385,63 +1108,13 @@ impl<'a> EvalScalarOp<'a> {
         Ok(())
     }
 
-    pub fn eval_binary_literal_expr<'b>(
-        session: &dyn Sender,
-        op: BinaryOperator,
-        left: Datum<'b>,
-        right: Datum<'b>,
-    ) -> Result<Datum<'b>, ()> {
-        if left.is_integer() && right.is_integer() {
-            match op {
-                BinaryOperator::Plus => Ok(left + right),
-                BinaryOperator::Minus => Ok(left - right),
-                BinaryOperator::Multiply => Ok(left * right),
-                BinaryOperator::Divide => Ok(left / right),
-                BinaryOperator::Modulus => Ok(left % right),
-                BinaryOperator::BitwiseAnd => Ok(left & right),
-                BinaryOperator::BitwiseOr => Ok(left | right),
-                BinaryOperator::StringConcat => {
-                    let kind = QueryError::undefined_function(op.to_string(), "NUMBER".to_owned(), "NUMBER".to_owned());
-                    session.send(Err(kind)).expect("To Send Query Result to Client");
-                    Err(())
-                }
-                _ => panic!(),
-            }
-        } else if left.is_float() && right.is_float() {
-            match op {
-                BinaryOperator::Plus => Ok(left + right),
-                BinaryOperator::Minus => Ok(left - right),
-                BinaryOperator::Multiply => Ok(left * right),
-                BinaryOperator::Divide => Ok(left / right),
-                BinaryOperator::StringConcat => {
-                    let kind = QueryError::undefined_function(op.to_string(), "NUMBER".to_owned(), "NUMBER".to_owned());
-                    session.send(Err(kind)).expect("To Send Query Result to Client");
-                    Err(())
-                }
-                _ => panic!(),
-            }
-        } else if left.is_string() || right.is_string() {
-            match op {
-                BinaryOperator::StringConcat => {
-                    let value = format!("{}{}", left.to_string(), right.to_string());
-                    Ok(Datum::OwnedString(value))
-                }
-                _ => {
-                    let kind = QueryError::undefined_function(op.to_string(), "STRING".to_owned(), "STRING".to_owned());
-                    session.send(Err(kind)).expect("To Send Query Result to Client");
-                    Err(())
-                }
+    fn report<'b>(&self, result: Result<Datum<'b>, QueryError>) -> Result<Datum<'b>, ()> {
+        match result {
+            Ok(datum) => Ok(datum),
+            Err(error) => {
+                self.session.send(Err(error)).expect("To Send Query Result to Client");
+                Err(())
             }
-        } else {
-            session
-                .send(Err(QueryError::syntax_error(format!(
-                    "{} {} {}",
-                    left.to_string(),
-                    op.to_string(),
-                    right.to_string()
-                ))))
-                .expect("To Send Query Result to Client");
-            Err(())
         }
     }
 }
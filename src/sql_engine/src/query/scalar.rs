@@ -12,14 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sqlparser::ast::BinaryOperator;
-
 use representation::{Datum, ScalarType};
 
+use crate::{
+    function::FunctionCall,
+    operator::{BinaryOperation, UnaryOperation},
+};
+
 ///! Module for representing scalar level operations. Implementation of
 ///! theses operators will be defined in a sperate module.
 // use crate::query::relation::RelationType;
 
+/// conditional expressions evaluate only as many arguments as they need
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conditional {
+    /// first non NULL argument
+    Coalesce,
+    /// NULL if both arguments are equal, the first argument otherwise
+    NullIf,
+    /// largest non NULL argument
+    Greatest,
+    /// smallest non NULL argument
+    Least,
+}
+
+impl Conditional {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Conditional::Coalesce => "COALESCE",
+            Conditional::NullIf => "NULLIF",
+            Conditional::Greatest => "GREATEST",
+            Conditional::Least => "LEAST",
+        }
+    }
+}
+
 /// Operation performed on the table
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScalarOp {
@@ -27,8 +54,29 @@ pub enum ScalarOp {
     Column(usize, ScalarType),
     /// literal value (owned) and expected type.
     Literal(Datum<'static>),
-    /// binary operator
-    Binary(BinaryOperator, Box<ScalarOp>, Box<ScalarOp>, ScalarType),
+    /// binary operator resolved for the types of its operands
+    Binary(BinaryOperation, Box<ScalarOp>, Box<ScalarOp>),
+    /// prefix operator resolved for the type of its operand
+    Unary(UnaryOperation, Box<ScalarOp>),
+    /// `IS [NOT] DISTINCT FROM` of operands casted to the type
+    DistinctFrom {
+        left: Box<ScalarOp>,
+        right: Box<ScalarOp>,
+        ty: ScalarType,
+        negated: bool,
+    },
+    /// function call and its return type
+    Function(FunctionCall, Vec<ScalarOp>, ScalarType),
+    /// conditional expression of arguments casted to the type
+    Conditional(Conditional, Vec<ScalarOp>, ScalarType),
+    /// `IS [NOT] NULL`
+    IsNull { operand: Box<ScalarOp>, negated: bool },
+    /// `CASE` of conditions and their results casted to the type, result of the first `true` condition is evaluated
+    Case {
+        branches: Vec<(ScalarOp, ScalarOp)>,
+        else_result: Option<Box<ScalarOp>>,
+        ty: ScalarType,
+    },
     Assignment {
         destination: usize,
         value: Box<ScalarOp>,
@@ -44,6 +92,33 @@ impl ScalarOp {
         }
     }
 
+    /// operation does not read any column
+    pub fn is_constant(&self) -> bool {
+        self.column().is_none()
+    }
+
+    /// index of the first column that the operation reads
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            ScalarOp::Column(index, _) => Some(*index),
+            ScalarOp::Literal(_) => None,
+            ScalarOp::Binary(_, left, right) | ScalarOp::DistinctFrom { left, right, .. } => {
+                left.column().or_else(|| right.column())
+            }
+            ScalarOp::Unary(_, operand) | ScalarOp::IsNull { operand, .. } => operand.column(),
+            ScalarOp::Function(_, args, _) | ScalarOp::Conditional(_, args, _) => {
+                args.iter().find_map(ScalarOp::column)
+            }
+            ScalarOp::Case {
+                branches, else_result, ..
+            } => branches
+                .iter()
+                .find_map(|(condition, result)| condition.column().or_else(|| result.column()))
+                .or_else(|| else_result.as_ref().and_then(|result| result.column())),
+            ScalarOp::Assignment { value, .. } => value.column(),
+        }
+    }
+
     pub fn as_datum(&self) -> Option<Datum<'static>> {
         match self {
             ScalarOp::Literal(datum) => Some(datum.clone()),
@@ -55,7 +130,13 @@ impl ScalarOp {
         match self {
             ScalarOp::Column(_, ty) => *ty,
             ScalarOp::Literal(datum) => datum.scalar_type().unwrap(),
-            ScalarOp::Binary(_, _, _, ty) => *ty,
+            ScalarOp::Binary(operation, _, _) => operation.result_type(),
+            ScalarOp::Unary(operation, _) => operation.result_type(),
+            ScalarOp::DistinctFrom { .. } => ScalarType::Boolean,
+            ScalarOp::Function(_, _, ty) => *ty,
+            ScalarOp::Conditional(_, _, ty) => *ty,
+            ScalarOp::IsNull { .. } => ScalarType::Boolean,
+            ScalarOp::Case { ty, .. } => *ty,
             ScalarOp::Assignment { ty, .. } => *ty,
         }
     }
@@ -0,0 +1,237 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::DataManager;
+use protocol::pgsql_types::PostgreSqlType;
+use representation::Datum;
+use sql_model::sql_types::SqlType;
+
+use crate::{
+    activity::{Activity, SessionRegistry},
+    aggregate::{AggregateFunction, AggregateState},
+};
+
+use super::*;
+
+/// remembers activities of all sessions while the statement that calls it is running
+struct Snapshot {
+    sessions: Arc<SessionRegistry>,
+    activities: Mutex<Vec<Activity>>,
+}
+
+impl AggregateFunction for Snapshot {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::Bool
+    }
+
+    fn init(&self) -> AggregateState {
+        *self.activities.lock().expect("locked") = self.sessions.activities();
+        Box::new(())
+    }
+
+    fn accumulate(&self, _state: &mut AggregateState, _value: &Datum) {}
+
+    fn merge(&self, _state: &mut AggregateState, _other: AggregateState) {}
+
+    fn finalize(&self, _state: AggregateState) -> Datum<'static> {
+        Datum::from_bool(true)
+    }
+}
+
+/// cancels statement of the session that calls it
+struct Cancel {
+    sessions: Arc<SessionRegistry>,
+    pid: u32,
+    secret_key: u32,
+}
+
+impl AggregateFunction for Cancel {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::Bool
+    }
+
+    fn init(&self) -> AggregateState {
+        self.sessions.cancel(self.pid, self.secret_key);
+        Box::new(())
+    }
+
+    fn accumulate(&self, _state: &mut AggregateState, _value: &Datum) {}
+
+    fn merge(&self, _state: &mut AggregateState, _other: AggregateState) {}
+
+    fn finalize(&self, _state: AggregateState) -> Datum<'static> {
+        Datum::from_bool(true)
+    }
+}
+
+pub(super) fn session(sessions: &Arc<SessionRegistry>) -> (QueryExecutor, ResultCollector) {
+    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let mut engine = QueryExecutor::new(
+        Arc::new(DataManager::in_memory().expect("to create data manager")),
+        collector.clone(),
+    );
+    engine.set_session_registry(sessions.clone());
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn sessions_are_listed_in_pg_stat_activity() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut first, _first_collector) = session(&sessions);
+    let (mut second, collector) = session(&sessions);
+
+    first.execute("create schema schema_name;").expect("no system errors");
+    second
+        .execute("select pid, state, query, plan from pg_stat_activity;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("pid".to_owned(), PostgreSqlType::Integer),
+            ("state".to_owned(), PostgreSqlType::VarChar),
+            ("query".to_owned(), PostgreSqlType::VarChar),
+            ("plan".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            first.pid().to_string(),
+            "idle".to_owned(),
+            "create schema schema_name;".to_owned(),
+            "".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            second.pid().to_string(),
+            "active".to_owned(),
+            "select pid, state, query, plan from pg_stat_activity;".to_owned(),
+            "".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn closed_sessions_are_not_listed() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (first, _first_collector) = session(&sessions);
+    let (second, _second_collector) = session(&sessions);
+    let pid = second.pid();
+
+    drop(first);
+
+    assert_eq!(
+        sessions
+            .activities()
+            .into_iter()
+            .map(|activity| activity.pid)
+            .collect::<Vec<_>>(),
+        vec![pid]
+    );
+}
+
+#[rstest::rstest]
+fn operator_of_running_statement() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut engine, _collector) = session(&sessions);
+    let snapshot = Arc::new(Snapshot {
+        sessions: sessions.clone(),
+        activities: Mutex::new(vec![]),
+    });
+    engine.register_aggregate("snapshot", snapshot.clone());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.numbers (value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (1), (2), (3);")
+        .expect("no system errors");
+
+    engine
+        .execute("select snapshot(value) over () from schema_name.numbers;")
+        .expect("no system errors");
+
+    assert_eq!(
+        snapshot.activities.lock().expect("locked").clone(),
+        vec![Activity {
+            pid: engine.pid(),
+            state: "active",
+            query: "select snapshot(value) over () from schema_name.numbers;".to_owned(),
+            plan: "WindowAgg (rows=0)".to_owned(),
+        }]
+    );
+    assert_eq!(
+        sessions.activities(),
+        vec![Activity {
+            pid: engine.pid(),
+            state: "idle",
+            query: "select snapshot(value) over () from schema_name.numbers;".to_owned(),
+            plan: "".to_owned(),
+        }]
+    );
+}
+
+#[rstest::rstest]
+fn canceled_statement() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut engine, collector) = session(&sessions);
+    let cancel = Arc::new(Cancel {
+        sessions: sessions.clone(),
+        pid: engine.pid(),
+        secret_key: engine.secret_key(),
+    });
+    engine.register_aggregate("cancel", cancel);
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.numbers (value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (1), (2), (3);")
+        .expect("no system errors");
+
+    engine
+        .execute("select cancel(value) over () from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select value from schema_name.numbers;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::query_canceled()),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "value".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn cancel_with_wrong_secret_key() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (engine, _collector) = session(&sessions);
+
+    assert!(!sessions.cancel(engine.pid(), engine.secret_key().wrapping_add(1)));
+    assert!(!sessions.cancel(engine.pid() + 1, engine.secret_key()));
+    assert!(sessions.cancel(engine.pid(), engine.secret_key()));
+}
@@ -0,0 +1,453 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+use representation::Datum;
+use sql_model::sql_types::SqlType;
+
+use crate::aggregate::{AggregateFunction, AggregateRegistry, AggregateState};
+
+use super::*;
+
+struct Product;
+
+impl AggregateFunction for Product {
+    fn return_type(&self, _argument: Option<&SqlType>) -> PostgreSqlType {
+        PostgreSqlType::BigInt
+    }
+
+    fn init(&self) -> AggregateState {
+        Box::new(1i64)
+    }
+
+    fn accumulate(&self, state: &mut AggregateState, value: &Datum) {
+        *state.downcast_mut::<i64>().expect("product state") *= value.as_i16() as i64;
+    }
+
+    fn merge(&self, state: &mut AggregateState, other: AggregateState) {
+        *state.downcast_mut::<i64>().expect("product state") *= *other.downcast::<i64>().expect("product state");
+    }
+
+    fn finalize(&self, state: AggregateState) -> Datum<'static> {
+        Datum::from_i64(*state.downcast::<i64>().expect("product state"))
+    }
+}
+
+#[rstest::fixture]
+fn numbers(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.numbers (position smallint, value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (1, 2), (2, 3), (3, null), (4, 5);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[test]
+fn merge_partial_states() {
+    let registry = AggregateRegistry::default();
+    let sum = registry.lookup("SUM").expect("sum is built in");
+
+    let mut left = sum.init();
+    sum.accumulate(&mut left, &Datum::from_i16(10));
+    sum.accumulate(&mut left, &Datum::from_i16(20));
+    let mut right = sum.init();
+    sum.accumulate(&mut right, &Datum::from_i32(30));
+    sum.merge(&mut left, right);
+
+    assert_eq!(sum.finalize(left), Datum::from_i64(60));
+}
+
+#[test]
+fn merge_with_empty_state() {
+    let registry = AggregateRegistry::default();
+    let max = registry.lookup("max").expect("max is built in");
+
+    let mut state = max.init();
+    max.merge(&mut state, max.init());
+    assert_eq!(max.finalize(state), Datum::from_null());
+
+    let mut state = max.init();
+    max.accumulate(&mut state, &Datum::from_str("b"));
+    let mut other = max.init();
+    max.accumulate(&mut other, &Datum::from_str("a"));
+    max.merge(&mut state, other);
+    assert_eq!(max.finalize(state), Datum::from_string("b".to_owned()));
+}
+
+#[rstest::rstest]
+fn registered_aggregate_over_window(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine.register_aggregate("product", Arc::new(Product));
+    engine
+        .execute(
+            "select position, product(value) over (order by position rows between 1 preceding and current row) as total \
+             from schema_name.numbers order by position;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("total".to_owned(), PostgreSqlType::BigInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned(), "2".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned(), "6".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned(), "3".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["4".to_owned(), "5".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn unknown_aggregate(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute("select median(value) over (order by position) from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::function_does_not_exist("median(smallint)")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn approximate_aggregates(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute(
+            "select position, \
+             approx_count_distinct(value) over (order by position) as distinct_values, \
+             approx_percentile(value, 0.5) over (order by position) as median \
+             from schema_name.numbers order by position;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("distinct_values".to_owned(), PostgreSqlType::BigInt),
+            ("median".to_owned(), PostgreSqlType::DoublePrecision),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1".to_owned(),
+            "1".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2".to_owned(),
+            "2".to_owned(),
+            "2.5".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "3".to_owned(),
+            "2".to_owned(),
+            "2.5".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "4".to_owned(),
+            "3".to_owned(),
+            "3".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn aggregates_without_window_aggregate_all_rows(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute(
+            "select count(*) as rows, count(value), sum(value), min(value), max(value), \
+             approx_count_distinct(value) as distinct_values, \
+             approx_percentile(value, 0.5) as median, \
+             count(*) filter (where value > 2) as large \
+             from schema_name.numbers;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select count(*), sum(value) from schema_name.numbers where position > 10;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            records_selected(
+                vec![
+                    ("rows".to_owned(), PostgreSqlType::BigInt),
+                    ("count".to_owned(), PostgreSqlType::BigInt),
+                    ("sum".to_owned(), PostgreSqlType::BigInt),
+                    ("min".to_owned(), PostgreSqlType::SmallInt),
+                    ("max".to_owned(), PostgreSqlType::SmallInt),
+                    ("distinct_values".to_owned(), PostgreSqlType::BigInt),
+                    ("median".to_owned(), PostgreSqlType::DoublePrecision),
+                    ("large".to_owned(), PostgreSqlType::BigInt),
+                ],
+                vec![vec![
+                    "4".to_owned(),
+                    "3".to_owned(),
+                    "10".to_owned(),
+                    "2".to_owned(),
+                    "5".to_owned(),
+                    "3".to_owned(),
+                    "3".to_owned(),
+                    "2".to_owned(),
+                ]],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+            records_selected(
+                vec![
+                    ("count".to_owned(), PostgreSqlType::BigInt),
+                    ("sum".to_owned(), PostgreSqlType::BigInt),
+                ],
+                vec![vec!["0".to_owned(), "NULL".to_owned()]],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn columns_of_aggregated_select(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute("select position, count(*) from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) as total, 'rows' as label from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) + 1 as next from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Err(QueryError::grouping_error(
+                    "column \"numbers.position\" must appear in the GROUP BY clause or be used in an aggregate function",
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            records_selected(
+                vec![
+                    ("total".to_owned(), PostgreSqlType::BigInt),
+                    ("label".to_owned(), PostgreSqlType::VarChar),
+                ],
+                vec![vec!["4".to_owned(), "rows".to_owned()]],
+            ),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::feature_not_supported("aggregates inside of expressions")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_aggregate_parameters(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute("select approx_percentile(value, 1.5) over (order by position) from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select count(value, 2) over (order by position) from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::numeric_value_out_of_range(
+            "percentile value 1.5 is not between 0 and 1",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("count(smallint, numeric)")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn filtered_aggregates(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute(
+            "select position, \
+             count(*) filter (where value > 2) over (order by position) as big, \
+             sum(value) filter (where position <> 2) over (order by position) as total \
+             from schema_name.numbers order by position;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("big".to_owned(), PostgreSqlType::BigInt),
+            ("total".to_owned(), PostgreSqlType::BigInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1".to_owned(),
+            "0".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2".to_owned(),
+            "1".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "3".to_owned(),
+            "1".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "4".to_owned(),
+            "2".to_owned(),
+            "7".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn string_and_array_aggregates(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute("create table schema_name.words (position smallint, word varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.words values (1, 'a'), (2, null), (3, 'c');")
+        .expect("no system errors");
+    engine
+        .execute(
+            "select position, \
+             string_agg(word, ', ') over (order by position) as words, \
+             array_agg(word) over (order by position) as elements \
+             from schema_name.words order by position;",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "select position, array_agg(value) filter (where position > 1) over () as tail \
+             from schema_name.numbers order by position;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("words".to_owned(), PostgreSqlType::Text),
+            ("elements".to_owned(), PostgreSqlType::TextArray),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1".to_owned(),
+            "a".to_owned(),
+            "{a}".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2".to_owned(),
+            "a".to_owned(),
+            "{a,NULL}".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "3".to_owned(),
+            "a, c".to_owned(),
+            "{a,NULL,c}".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("tail".to_owned(), PostgreSqlType::SmallIntArray),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned(), "{3,NULL,5}".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned(), "{3,NULL,5}".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned(), "{3,NULL,5}".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["4".to_owned(), "{3,NULL,5}".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_filters_and_parameters(numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = numbers;
+    engine
+        .execute("select count(*) filter (where value) over () from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select row_number() filter (where value > 2) over () from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select string_agg(value) over () from schema_name.numbers;")
+        .expect("no system errors");
+    engine
+        .execute("select approx_percentile(value, 'half') over () from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::datatype_mismatch(
+            "argument of FILTER must be type boolean, not type NUMBER",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported(
+            "FILTER is not implemented for non-aggregate window functions",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("string_agg(smallint)")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist(
+            "approx_percentile(smallint, unknown)",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
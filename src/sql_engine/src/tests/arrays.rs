@@ -0,0 +1,189 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn array_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name(numbers int[], tags text[], first integer, has_two boolean);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn array_literals_and_constructors(array_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = array_table;
+    engine
+        .execute(
+            "insert into schema_name.table_name values \
+            ('{1, 2,3}', '{a,\"b c\",NULL}', null, null), \
+            (ARRAY[4, 5], ARRAY['x', null], null, null);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select numbers, tags from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("numbers".to_owned(), PostgreSqlType::IntegerArray),
+            ("tags".to_owned(), PostgreSqlType::TextArray),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{1,2,3}".to_owned(),
+            "{a,\"b c\",NULL}".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["{4,5}".to_owned(), "{x,NULL}".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_elements(array_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = array_table;
+    engine
+        .execute("insert into schema_name.table_name values ('{1,a}', null, null, null);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('{1,3000000000}', null, null, null);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (null, 'a,b', null, null);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::type_mismatch(
+            "{1,a}",
+            PostgreSqlType::IntegerArray,
+            "numbers",
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::out_of_range(PostgreSqlType::IntegerArray, "numbers", 1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch("a,b", PostgreSqlType::TextArray, "tags", 1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn element_access_and_any(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select ARRAY[10, 20, 30][2] as second, \
+                '{a,b}'[3] as missing, \
+                2 = any(ARRAY[1, 2]) as found, \
+                5 > any('{7,8}') as not_found, \
+                1 = any('{2,NULL}') as unknown;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("second".to_owned(), PostgreSqlType::VarChar),
+            ("missing".to_owned(), PostgreSqlType::VarChar),
+            ("found".to_owned(), PostgreSqlType::Bool),
+            ("not_found".to_owned(), PostgreSqlType::Bool),
+            ("unknown".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "20".to_owned(),
+            "NULL".to_owned(),
+            "t".to_owned(),
+            "f".to_owned(),
+            "NULL".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn elements_of_array_columns(array_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = array_table;
+    engine
+        .execute("insert into schema_name.table_name values ('{7,2}', '{}', null, null), ('{3}', '{}', null, null);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set first = numbers[1] + 1, has_two = 2 = any(numbers);")
+        .expect("no system errors");
+    engine
+        .execute("select numbers, first, has_two from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("numbers".to_owned(), PostgreSqlType::IntegerArray),
+            ("first".to_owned(), PostgreSqlType::Integer),
+            ("has_two".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{7,2}".to_owned(),
+            "8".to_owned(),
+            "t".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{3}".to_owned(),
+            "4".to_owned(),
+            "f".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn elements_of_different_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("select ARRAY[1, 'a'];").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::datatype_mismatch(
+            "ARRAY types NUMBER and String cannot be matched",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
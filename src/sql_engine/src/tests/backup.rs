@@ -0,0 +1,64 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::rstest]
+fn backup_is_written_to_file(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    let directory = tempfile::tempdir().expect("to create temporary directory");
+    let path = directory.path().join("node.backup");
+    engine
+        .execute("create table schema_name.table_name (id integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute(format!("backup to '{}';", path.display()).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::BackupCompleted),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    assert!(path.exists());
+}
+
+#[rstest::rstest]
+fn backup_in_transaction_block(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    let directory = tempfile::tempdir().expect("to create temporary directory");
+    let path = directory.path().join("node.backup");
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute(format!("backup to '{}';", path.display()).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Err(QueryError::transaction_block_not_allowed("BACKUP")),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+    ]);
+    assert!(!path.exists());
+}
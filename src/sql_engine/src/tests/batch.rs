@@ -0,0 +1,88 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+use query_planner::extended;
+use sqlparser::ast::Statement;
+
+use super::*;
+
+fn statements(sql: &str) -> Vec<Statement> {
+    extended::parse_sql(&PreparedStatementDialect {}, sql).expect("statements parsed")
+}
+
+#[rstest::rstest]
+fn results_of_each_statement(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    assert_eq!(
+        engine
+            .execute_batch(&statements(
+                "create table schema_name.table_name (col smallint); \
+                insert into schema_name.table_name values (1), (2); \
+                select * from schema_name.table_name;"
+            ))
+            .expect("no system errors"),
+        3
+    );
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn statements_after_failed_one_are_not_run(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    assert_eq!(
+        engine
+            .execute_batch(&statements(
+                "create table schema_name.table_name (col smallint); \
+                insert into schema_name.non_existent values (1); \
+                insert into schema_name.table_name values (1);"
+            ))
+            .expect("no system errors"),
+        1
+    );
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::RecordsSelected(0)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use sqlparser::parser::Parser;
+
 use super::*;
 
 #[rstest::rstest]
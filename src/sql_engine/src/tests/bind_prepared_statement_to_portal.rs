@@ -26,7 +26,7 @@ fn bind_insert_statement_to_portal(sql_engine_with_schema: (QueryExecutor, Resul
         .parse_prepared_statement(
             "statement_name",
             "insert into schema_name.table_name values ($1, $2);",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -62,7 +62,7 @@ fn bind_update_statement_to_portal(sql_engine_with_schema: (QueryExecutor, Resul
         .parse_prepared_statement(
             "statement_name",
             "update schema_name.table_name set column_1 = $1, column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -86,3 +86,139 @@ fn bind_update_statement_to_portal(sql_engine_with_schema: (QueryExecutor, Resul
         Ok(QueryEvent::BindComplete),
     ]);
 }
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn bind_fewer_params_than_statement_refers_to(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .parse_prepared_statement(
+            "statement_name",
+            "insert into schema_name.table_name values ($1, $2);",
+            &[],
+        )
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "portal_name",
+            "statement_name",
+            &[PostgreSqlFormat::Text],
+            &[Some(b"1".to_vec())],
+            &[],
+        )
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Err(QueryError::protocol_violation(
+            "Bind message supplies 1 parameters, but prepared statement \"statement_name\" requires 2",
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn bind_params_without_types_as_text(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .parse_prepared_statement(
+            "statement_name",
+            "insert into schema_name.table_name values ($1, $2);",
+            &[],
+        )
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "portal_name",
+            "statement_name",
+            &[PostgreSqlFormat::Text],
+            &[Some(b"1".to_vec()), Some(b"2".to_vec())],
+            &[],
+        )
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::BindComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn bind_text_param_that_is_not_coercible_to_column_type(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .parse_prepared_statement(
+            "statement_name",
+            "insert into schema_name.table_name (column_2, column_1) values ($1, $2);",
+            &[],
+        )
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "portal_name",
+            "statement_name",
+            &[PostgreSqlFormat::Text],
+            &[Some(b"1".to_vec()), Some(b"abc".to_vec())],
+            &[],
+        )
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Err(QueryError::invalid_text_representation(
+            "invalid input syntax for type smallint: \"abc\"",
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn bind_param_of_type_that_is_not_coercible_to_column_type(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .parse_prepared_statement(
+            "statement_name",
+            "update schema_name.table_name set column_2 = $1;",
+            &[Some(PostgreSqlType::Bool)],
+        )
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "portal_name",
+            "statement_name",
+            &[PostgreSqlFormat::Text],
+            &[Some(b"true".to_vec())],
+            &[],
+        )
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Err(QueryError::invalid_parameter_value(
+            "column \"column_2\" is of type smallint but parameter $1 is of type bool",
+        )),
+    ]);
+}
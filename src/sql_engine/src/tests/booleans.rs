@@ -0,0 +1,188 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_flags(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.flags (id smallint, flag boolean);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.flags values (1, true), (2, 'no'), (3, null), (4, 'on');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn flags(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("flag".to_owned(), PostgreSqlType::Bool),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn three_valued_logic(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select true and null as and_null, \
+                false and null as and_false, \
+                true or null as or_true, \
+                false or null as or_null, \
+                not false as negated;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("and_null".to_owned(), PostgreSqlType::Bool),
+            ("and_false".to_owned(), PostgreSqlType::Bool),
+            ("or_true".to_owned(), PostgreSqlType::Bool),
+            ("or_null".to_owned(), PostgreSqlType::Bool),
+            ("negated".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "NULL".to_owned(),
+            "f".to_owned(),
+            "t".to_owned(),
+            "NULL".to_owned(),
+            "t".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn comparisons_result_in_booleans(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select 1 < 2 as less, \
+                2.5 >= 3 as greater_or_equal, \
+                'abc' = 'abc' as equal, \
+                false < true as ordered, \
+                1 <> null as unknown;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("less".to_owned(), PostgreSqlType::Bool),
+            ("greater_or_equal".to_owned(), PostgreSqlType::Bool),
+            ("equal".to_owned(), PostgreSqlType::Bool),
+            ("ordered".to_owned(), PostgreSqlType::Bool),
+            ("unknown".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "t".to_owned(),
+            "f".to_owned(),
+            "t".to_owned(),
+            "t".to_owned(),
+            "NULL".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn text_input_is_stored_as_boolean(with_flags: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_flags;
+    engine
+        .execute("select * from schema_name.flags order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            flags(vec![vec!["1", "t"], vec!["2", "f"], vec!["3", "NULL"], vec!["4", "t"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn column_is_used_in_expressions_without_cast(with_flags: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_flags;
+    engine
+        .execute("update schema_name.flags set flag = not flag;")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.flags set flag = flag or id = 3;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.flags order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::RecordsUpdated(4)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsUpdated(4)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            flags(vec![vec!["1", "f"], vec!["2", "t"], vec!["3", "t"], vec!["4", "f"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_boolean_text(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select 'maybe'::boolean as flag;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_text_representation(
+            "invalid input syntax for type boolean: \"maybe\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,114 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn bytea_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name(col bytea);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn hex_input_and_output(bytea_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = bytea_table;
+    engine
+        .execute("insert into schema_name.table_name values ('\\x0102FF'), ('abc');")
+        .expect("no system errors");
+    engine
+        .execute("select col from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select col from schema_name.table_name where col = '\\x616263';")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::Bytea,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["\\x0102ff".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["\\x616263".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::Bytea,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["\\x616263".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_bytes(bytea_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = bytea_table;
+    engine
+        .execute("insert into schema_name.table_name values ('\\x00');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set col = '\\xdeadbeef';")
+        .expect("no system errors");
+    engine
+        .execute("select col from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::Bytea,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["\\xdeadbeef".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn invalid_hex(bytea_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = bytea_table;
+    engine
+        .execute("insert into schema_name.table_name values ('\\x0g');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch("\\x0g", PostgreSqlType::Bytea, "col", 1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
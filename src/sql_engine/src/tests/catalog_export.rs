@@ -0,0 +1,141 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::DataManager;
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn exported_catalog_is_imported_into_empty_instance(sender: ResultCollector) {
+    let data_manager = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let mut source = QueryExecutor::new(data_manager.clone(), sender.clone());
+    source.execute("create schema schema_name;").expect("no system errors");
+    source
+        .execute("create table schema_name.table_name (id integer primary key, name varchar(10) not null);")
+        .expect("no system errors");
+    source.execute("select export_catalog();").expect("no system errors");
+    let exported = data_manager.export_catalog().expect("no system errors");
+
+    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let mut target = QueryExecutor::new(
+        Arc::new(DataManager::in_memory().expect("to create data manager")),
+        collector.clone(),
+    );
+    target
+        .execute(format!("select import_catalog('{}');", exported).as_str())
+        .expect("no system errors");
+    target
+        .execute("insert into schema_name.table_name values (1, 'abc');")
+        .expect("no system errors");
+    target.execute("select export_catalog();").expect("no system errors");
+
+    sender.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "export_catalog".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec![exported.clone()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "import_catalog".to_owned(),
+            PostgreSqlType::Bool,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["t".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "export_catalog".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec![exported])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn catalog_is_not_imported_into_instance_with_schemas(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select import_catalog('{\"version\": 1, \"schemas\": []}');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::object_not_in_prerequisite_state(
+            "catalog can be imported only into an instance without schemas",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn malformed_catalog(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select import_catalog('{\"version\": 2, \"schemas\": []}');")
+        .expect("no system errors");
+    engine.execute("select import_catalog(1);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_text_representation(
+            "invalid catalog: unsupported catalog version 2",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("import_catalog(integer)")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn migration_from_current_catalog(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id integer);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "select catalog_migration('{\"version\": 1, \"schemas\": [\
+             {\"name\": \"schema_name\", \"owner\": \"postgres\", \"tables\": []}, \
+             {\"name\": \"other_schema\", \"owner\": \"postgres\", \"tables\": []}]}');",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "catalog_migration".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec![
+            "DROP TABLE schema_name.table_name;\nCREATE SCHEMA other_schema;".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,235 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn server_version(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("SELECT version();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "version".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["PostgreSQL 12.4 (database)".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn current_database_issued_by_sqlx(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("SELECT current_database()").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "current_database".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["public".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn search_path_reset_issued_by_pg_dump(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("SELECT pg_catalog.set_config('search_path', '', false);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "set_config".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn list_namespaces(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("SELECT nspname\n    FROM pg_catalog.pg_namespace;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "nspname".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["schema_name".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn list_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("SELECT table_schema, table_name FROM information_schema.tables;")
+        .expect("no system errors");
+    engine
+        .execute("SELECT schemaname, tablename FROM pg_catalog.pg_tables;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("table_schema".to_owned(), PostgreSqlType::VarChar),
+            ("table_name".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "schema_name".to_owned(),
+            "table_name".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("schemaname".to_owned(), PostgreSqlType::VarChar),
+            ("tablename".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "schema_name".to_owned(),
+            "table_name".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn column_nullability_issued_by_sqlx(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint not null, column_2 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("SELECT attnotnull FROM pg_catalog.pg_attribute WHERE attrelid = 16384 AND attnum = 1;")
+        .expect("no system errors");
+    engine
+        .execute("SELECT attnotnull FROM pg_catalog.pg_attribute WHERE attrelid = 16384 AND attnum = 2;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "attnotnull".to_owned(),
+            PostgreSqlType::Bool,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["t".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "attnotnull".to_owned(),
+            PostgreSqlType::Bool,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["f".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn table_columns_issued_by_diesel(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key, column_1 varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute(
+            "SELECT column_name, udt_name, is_nullable FROM information_schema.columns \
+             WHERE table_name = 'table_name' AND table_schema = 'schema_name' ORDER BY ordinal_position;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_name".to_owned(), PostgreSqlType::VarChar),
+            ("udt_name".to_owned(), PostgreSqlType::VarChar),
+            ("is_nullable".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "id".to_owned(),
+            "int2".to_owned(),
+            "NO".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "column_1".to_owned(),
+            "varchar".to_owned(),
+            "YES".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn all_table_attributes(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 integer not null);")
+        .expect("no system errors");
+    engine
+        .execute("SELECT attrelid, attnum, attname, atttypid, attnotnull FROM pg_catalog.pg_attribute;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("attrelid".to_owned(), PostgreSqlType::Integer),
+            ("attnum".to_owned(), PostgreSqlType::SmallInt),
+            ("attname".to_owned(), PostgreSqlType::VarChar),
+            ("atttypid".to_owned(), PostgreSqlType::Integer),
+            ("attnotnull".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "16384".to_owned(),
+            "1".to_owned(),
+            "column_1".to_owned(),
+            "23".to_owned(),
+            "t".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
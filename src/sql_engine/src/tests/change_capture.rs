@@ -0,0 +1,142 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+fn change(transaction: u64, kind: &str, before: Option<&str>, after: Option<&str>) -> String {
+    let image = |value: Option<&str>| match value {
+        Some(value) => format!("{{\"col\": \"{}\"}}", value),
+        None => "null".to_owned(),
+    };
+    format!(
+        "{{\"transaction\": {}, \"schema\": \"schema_name\", \"table\": \"table_name\", \"kind\": \"{}\", \
+         \"before\": {}, \"after\": {}}}",
+        transaction,
+        kind,
+        image(before),
+        image(after)
+    )
+}
+
+fn function_result(name: &str, pg_type: PostgreSqlType, value: String) -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::RowDescription(vec![(name.to_owned(), pg_type)])),
+        Ok(QueryEvent::DataRow(vec![value])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn committed_changes_are_taken_from_slot(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select create_change_slot('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+    engine
+        .execute("begin; delete from schema_name.table_name; rollback;")
+        .expect("no system errors");
+    engine
+        .execute("begin; update schema_name.table_name set col = 10; commit;")
+        .expect("no system errors");
+    engine
+        .execute("select take_slot_changes('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("select take_slot_changes('slot_name');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            function_result("create_change_slot", PostgreSqlType::Bool, "t".to_owned()),
+            vec![
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::RecordsDeleted(2)),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::RecordsUpdated(2)),
+                Ok(QueryEvent::TransactionCommitted),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            function_result(
+                "take_slot_changes",
+                PostgreSqlType::VarChar,
+                format!(
+                    "[{}, {}, {}, {}]",
+                    change(1, "insert", None, Some("1")),
+                    change(1, "insert", None, Some("2")),
+                    change(2, "update", Some("1"), Some("10")),
+                    change(2, "update", Some("2"), Some("10"))
+                ),
+            ),
+            function_result("take_slot_changes", PostgreSqlType::VarChar, "[]".to_owned()),
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn slots_are_created_and_dropped_once(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select create_change_slot('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("select create_change_slot('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("select drop_change_slot('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("select drop_change_slot('slot_name');")
+        .expect("no system errors");
+    engine
+        .execute("select take_slot_changes('slot_name');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            function_result("create_change_slot", PostgreSqlType::Bool, "t".to_owned()),
+            vec![
+                Err(QueryError::replication_slot_already_exists("slot_name")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            function_result("drop_change_slot", PostgreSqlType::Bool, "t".to_owned()),
+            vec![
+                Err(QueryError::replication_slot_does_not_exist("slot_name")),
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::replication_slot_does_not_exist("slot_name")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
@@ -0,0 +1,328 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{sync::mpsc, thread, time::Duration};
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+const SESSIONS: usize = 8;
+const STATEMENTS: usize = 25;
+
+/// every session runs the statement a number of times in its own thread
+fn run_concurrently(data_manager: Arc<DataManager>, sql: &'static str) {
+    let sessions = (0..SESSIONS)
+        .map(|_| {
+            let data_manager = data_manager.clone();
+            thread::spawn(move || {
+                let mut engine = QueryExecutor::new(data_manager, sender());
+                for _ in 0..STATEMENTS {
+                    engine.execute(sql).expect("no system errors");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for session in sessions {
+        session.join().expect("session is finished");
+    }
+}
+
+#[rstest::fixture]
+fn storage() -> Arc<DataManager> {
+    let data_manager = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let mut engine = QueryExecutor::new(data_manager.clone(), sender());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (col integer);")
+        .expect("no system errors");
+    data_manager
+}
+
+#[rstest::rstest]
+fn concurrent_inserts_into_the_same_table(storage: Arc<DataManager>) {
+    run_concurrently(storage.clone(), "insert into schema_name.table_name values (1);");
+
+    let collector = sender();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            records_selected(
+                vec![("col".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["1".to_owned()]; SESSIONS * STATEMENTS],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn concurrent_updates_of_the_same_rows_are_not_lost(storage: Arc<DataManager>) {
+    let mut engine = QueryExecutor::new(storage.clone(), sender());
+    engine
+        .execute("insert into schema_name.table_name values (0);")
+        .expect("no system errors");
+
+    run_concurrently(storage.clone(), "update schema_name.table_name set col = col + 1;");
+
+    let collector = sender();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            records_selected(
+                vec![("col".to_owned(), PostgreSqlType::Integer)],
+                vec![vec![(SESSIONS * STATEMENTS).to_string()]],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn concurrent_inserts_into_different_tables(storage: Arc<DataManager>) {
+    let mut engine = QueryExecutor::new(storage.clone(), sender());
+    engine
+        .execute("create table schema_name.other_table (col integer);")
+        .expect("no system errors");
+
+    let other = {
+        let storage = storage.clone();
+        thread::spawn(move || run_concurrently(storage, "insert into schema_name.other_table values (2);"))
+    };
+    run_concurrently(storage.clone(), "insert into schema_name.table_name values (1);");
+    other.join().expect("sessions are finished");
+
+    let collector = sender();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select * from schema_name.other_table;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            records_selected(
+                vec![("col".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["2".to_owned()]; SESSIONS * STATEMENTS],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn deadlocked_transaction_is_rolled_back(storage: Arc<DataManager>) {
+    let collector = sender();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine
+        .execute("create table schema_name.other_table (col integer);")
+        .expect("no system errors");
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    let (started, waiting) = mpsc::channel();
+    let other = {
+        let storage = storage.clone();
+        thread::spawn(move || {
+            let collector = sender();
+            let mut engine = QueryExecutor::new(storage, collector.clone());
+            engine.execute("begin;").expect("no system errors");
+            engine
+                .execute("insert into schema_name.other_table values (2);")
+                .expect("no system errors");
+            started.send(()).expect("to notify that transaction is started");
+            engine
+                .execute("insert into schema_name.table_name values (2);")
+                .expect("no system errors");
+            engine.execute("commit;").expect("no system errors");
+            collector
+        })
+    };
+    waiting.recv().expect("other transaction is started");
+    // the other transaction waits for the table locked by this one meanwhile
+    thread::sleep(Duration::from_millis(100));
+    engine
+        .execute("insert into schema_name.other_table values (1);")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+    let other_collector = other.join().expect("other transaction is finished");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            vec![
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Err(QueryError::deadlock_detected()),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            records_selected(
+                vec![("col".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["2".to_owned()]],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    other_collector.assert_content(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TransactionCommitted),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn statement_waiting_for_locked_table_is_canceled(storage: Arc<DataManager>) {
+    let sessions = Arc::new(SessionRegistry::default());
+    let collector = sender();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    let (session, waiting) = mpsc::channel();
+    let other = {
+        let sessions = sessions.clone();
+        thread::spawn(move || {
+            let collector = sender();
+            let mut engine = QueryExecutor::new(storage, collector.clone());
+            engine.set_session_registry(sessions);
+            session
+                .send((engine.pid(), engine.secret_key()))
+                .expect("to send session keys");
+            engine
+                .execute("insert into schema_name.table_name values (2);")
+                .expect("no system errors");
+            collector
+        })
+    };
+    let (pid, secret_key) = waiting.recv().expect("other session is started");
+    // cancellation requested before the statement started would not be applied to it
+    while !sessions
+        .activities()
+        .iter()
+        .any(|activity| activity.pid == pid && activity.state == "active")
+    {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(sessions.cancel(pid, secret_key));
+    let other_collector = other.join().expect("other session is finished");
+    engine.execute("commit;").expect("no system errors");
+
+    other_collector.assert_content(vec![Err(QueryError::query_canceled()), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest]
+fn uncommitted_writes_are_not_seen_by_other_sessions(storage: Arc<DataManager>) {
+    let mut writer = QueryExecutor::new(storage.clone(), sender());
+    writer.execute("begin;").expect("no system errors");
+    writer
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    let collector = sender();
+    let mut reader = QueryExecutor::new(storage, collector.clone());
+    reader
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    writer.execute("commit;").expect("no system errors");
+    reader
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            records_selected(vec![("col".to_owned(), PostgreSqlType::Integer)], vec![]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            records_selected(
+                vec![("col".to_owned(), PostgreSqlType::Integer)],
+                vec![vec!["1".to_owned()]],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn tables_defined_in_transaction_are_seen_by_other_sessions_when_committed(storage: Arc<DataManager>) {
+    let mut writer = QueryExecutor::new(storage.clone(), sender());
+    writer.execute("begin;").expect("no system errors");
+    writer
+        .execute("create table schema_name.created_table (col integer);")
+        .expect("no system errors");
+    writer
+        .execute("drop table schema_name.table_name;")
+        .expect("no system errors");
+
+    let collector = sender();
+    let mut reader = QueryExecutor::new(storage, collector.clone());
+    reader
+        .execute("select * from schema_name.created_table;")
+        .expect("no system errors");
+    reader
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    writer.execute("commit;").expect("no system errors");
+    reader
+        .execute("select * from schema_name.created_table;")
+        .expect("no system errors");
+    reader
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(
+        [
+            vec![
+                Err(QueryError::table_does_not_exist("schema_name.created_table")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            records_selected(vec![("col".to_owned(), PostgreSqlType::Integer)], vec![]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            records_selected(vec![("col".to_owned(), PostgreSqlType::Integer)], vec![]),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::table_does_not_exist("schema_name.table_name")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
@@ -0,0 +1,272 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_readings(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.readings \
+            (id smallint, primary_value integer, fallback smallint, largest integer, smallest integer);",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "insert into schema_name.readings (id, primary_value, fallback) \
+            values (1, 1, 0), (2, null, 2), (3, null, null), (4, 5, 5);",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn readings(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("primary_value".to_owned(), PostgreSqlType::Integer),
+            ("fallback".to_owned(), PostgreSqlType::SmallInt),
+            ("largest".to_owned(), PostgreSqlType::Integer),
+            ("smallest".to_owned(), PostgreSqlType::Integer),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn conditionals_of_constants(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select coalesce(null, 2, 3) as coalesce, \
+                nullif(1, 2) as nullif, \
+                greatest(1, 5, null, 3) as greatest, \
+                least('pear', 'apple') as least;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("coalesce".to_owned(), PostgreSqlType::Integer),
+            ("nullif".to_owned(), PostgreSqlType::Integer),
+            ("greatest".to_owned(), PostgreSqlType::Integer),
+            ("least".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2".to_owned(),
+            "1".to_owned(),
+            "5".to_owned(),
+            "apple".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn coalesce_stops_at_first_non_null_argument(with_readings: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_readings;
+    engine
+        .execute("update schema_name.readings set largest = coalesce(primary_value, 10 / fallback);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.readings order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            readings(vec![
+                vec!["1", "1", "0", "1", "NULL"],
+                vec!["2", "NULL", "2", "5", "NULL"],
+                vec!["3", "NULL", "NULL", "NULL", "NULL"],
+                vec!["4", "5", "5", "5", "NULL"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn nullif_of_columns(with_readings: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_readings;
+    engine
+        .execute("update schema_name.readings set largest = nullif(primary_value, fallback);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.readings order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            readings(vec![
+                vec!["1", "1", "0", "1", "NULL"],
+                vec!["2", "NULL", "2", "NULL", "NULL"],
+                vec!["3", "NULL", "NULL", "NULL", "NULL"],
+                vec!["4", "5", "5", "NULL", "NULL"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn greatest_and_least_ignore_nulls(with_readings: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_readings;
+    engine
+        .execute(
+            "update schema_name.readings set \
+            largest = greatest(primary_value, fallback), \
+            smallest = least(primary_value, fallback);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.readings order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            readings(vec![
+                vec!["1", "1", "0", "1", "0"],
+                vec!["2", "NULL", "2", "2", "2"],
+                vec!["3", "NULL", "NULL", "NULL", "NULL"],
+                vec!["4", "5", "5", "5", "5"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn select_conditionals_of_columns(with_readings: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_readings;
+    engine
+        .execute(
+            "select id, \
+                coalesce(primary_value, fallback) as coalesce, \
+                nullif(primary_value, fallback) as nullif, \
+                greatest(primary_value, fallback) as greatest, \
+                least(primary_value, fallback) as least, \
+                case when primary_value is null then 'missing' else 'present' end as state \
+            from schema_name.readings where id in (1, 2, 3) order by id;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            records_selected(
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt),
+                    ("coalesce".to_owned(), PostgreSqlType::Integer),
+                    ("nullif".to_owned(), PostgreSqlType::Integer),
+                    ("greatest".to_owned(), PostgreSqlType::Integer),
+                    ("least".to_owned(), PostgreSqlType::Integer),
+                    ("state".to_owned(), PostgreSqlType::VarChar),
+                ],
+                [
+                    ["1", "1", "1", "1", "0", "present"],
+                    ["2", "2", "NULL", "2", "2", "missing"],
+                    ["3", "NULL", "NULL", "NULL", "NULL", "missing"],
+                ]
+                .iter()
+                .map(|row| row.iter().map(|value| (*value).to_owned()).collect())
+                .collect(),
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn case_of_incompatible_types(with_readings: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_readings;
+    engine
+        .execute("select case when id = 1 then 'one' else id end as label from schema_name.readings;")
+        .expect("no system errors");
+    engine
+        .execute("select case when id then 'one' end as label from schema_name.readings;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::datatype_mismatch(
+            "CASE types String and NUMBER cannot be matched",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::datatype_mismatch(
+            "argument of CASE/WHEN must be type boolean, not type NUMBER",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn arguments_of_incompatible_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select coalesce(1, 'one') as mixed;")
+        .expect("no system errors");
+    engine
+        .execute("select nullif(1, true) as mixed;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::datatype_mismatch(
+            "COALESCE types NUMBER and String cannot be matched",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::undefined_function(
+            "=".to_owned(),
+            "NUMBER".to_owned(),
+            "Bool".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
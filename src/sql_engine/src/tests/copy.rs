@@ -0,0 +1,252 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, name varchar(20));")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn rows(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("name".to_owned(), PostgreSqlType::VarChar),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn copy_rows_split_between_messages(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\tfirst\n2\t\\N\n3\ttab");
+    engine.copy_data(b"\\there\n\\.\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::CopyInStarted(2)),
+                Ok(QueryEvent::RecordsCopied(3)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            rows(vec![vec!["1", "first"], vec!["2", "NULL"], vec!["3", "tab\there"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_listed_columns(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name (id) from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\r\n2\r\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::CopyInStarted(1)),
+                Ok(QueryEvent::RecordsCopied(2)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            rows(vec![vec!["1", "NULL"], vec!["2", "NULL"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_into_nonexistent_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name (id, non_existent) from stdin;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::column_does_not_exist("non_existent")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn missing_data_for_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\tfirst\n2\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::CopyInStarted(2)),
+                Err(QueryError::bad_copy_file_format("missing data for column")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            rows(vec![]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn client_fails_copy(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\tfirst\n");
+    engine.copy_fail("canceled by user");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::CopyInStarted(2)),
+                Err(QueryError::copy_from_stdin_failed("canceled by user")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            rows(vec![]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+fn copied(lines: Vec<&str>) -> Vec<QueryResult> {
+    lines
+        .into_iter()
+        .map(|line| Ok(QueryEvent::CopyData(line.as_bytes().to_vec())))
+        .collect()
+}
+
+#[rstest::fixture]
+fn with_rows(with_table: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values (1, 'tab\tand, \"quote\"'), (2, null), (3, '');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn copy_to_stdout_in_text_format(with_rows: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_rows;
+    engine
+        .execute("copy schema_name.table_name to stdout;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::CopyOutStarted(2)),
+    ]);
+    expected.extend(copied(vec!["1\ttab\\tand, \"quote\"\n", "2\t\\N\n", "3\t\n"]));
+    expected.extend(vec![Ok(QueryEvent::CopyOutDone(3)), Ok(QueryEvent::QueryComplete)]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_to_stdout_in_csv_format(with_rows: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_rows;
+    engine
+        .execute("copy schema_name.table_name (name, id) to stdout with (format csv, header);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::CopyOutStarted(2)),
+    ]);
+    expected.extend(copied(vec![
+        "name,id\n",
+        "\"tab\tand, \"\"quote\"\"\",1\n",
+        ",2\n",
+        "\"\",3\n",
+    ]));
+    expected.extend(vec![Ok(QueryEvent::CopyOutDone(3)), Ok(QueryEvent::QueryComplete)]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_header_in_text_format(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name to stdout (format text, header);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::syntax_error(
+            "\"copy schema_name.table_name to stdout (format text, header);\" can't be parsed",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,145 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_cursor(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 'a'), (2, 'b'), (3, 'c');")
+        .expect("no system errors");
+    engine
+        .execute("declare names no scroll cursor for select name from schema_name.table_name;")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::CursorDeclared),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn names(names: Vec<&str>) -> QueryResult {
+    Ok(QueryEvent::RecordsFetchedFromCursor((
+        vec![("name".to_owned(), PostgreSqlType::VarChar)],
+        names.into_iter().map(|name| vec![name.to_owned()]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn fetch_rows_incrementally(with_cursor: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_cursor;
+    engine.execute("fetch names;").expect("no system errors");
+    engine.execute("fetch forward 5 from names;").expect("no system errors");
+    engine.execute("fetch next in names;").expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        names(vec!["a"]),
+        Ok(QueryEvent::QueryComplete),
+        names(vec!["b", "c"]),
+        Ok(QueryEvent::QueryComplete),
+        names(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn rows_are_selected_when_cursor_is_declared(with_cursor: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_cursor;
+    engine
+        .execute("insert into schema_name.table_name values (4, 'd');")
+        .expect("no system errors");
+    engine.execute("fetch all from names;").expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        names(vec!["a", "b", "c"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn declare_existing_cursor(with_cursor: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_cursor;
+    engine
+        .execute("declare names cursor with hold for select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::duplicate_cursor("names")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn close_cursor(with_cursor: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_cursor;
+    engine.execute("close names;").expect("no system errors");
+    engine.execute("fetch names;").expect("no system errors");
+    engine.execute("close names;").expect("no system errors");
+    engine.execute("close all;").expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorClosed),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_cursor_name("names")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_cursor_name("names")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::AllCursorsClosed),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn declare_cursor_over_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("declare missing cursor for select * from schema_name.non_existent;")
+        .expect("no system errors");
+    engine.execute("fetch missing;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_cursor_name("missing")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
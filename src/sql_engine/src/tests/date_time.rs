@@ -0,0 +1,189 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{clock::FrozenClock, pgsql_types::PostgreSqlType};
+
+use super::*;
+
+#[rstest::rstest]
+fn extract_fields_of_timestamp(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(
+            "select extract(year from '2020-08-13 12:34:56.5+00') as year_part, \
+                extract(DOW from '2020-08-13 12:34:56.5+00') as day_of_week, \
+                extract(second from '2020-08-13 12:34:56.5+00') as seconds, \
+                date_part('hour', '2020-08-13 12:34:56.5+03') as hours;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("year_part".to_owned(), PostgreSqlType::DoublePrecision),
+            ("day_of_week".to_owned(), PostgreSqlType::DoublePrecision),
+            ("seconds".to_owned(), PostgreSqlType::DoublePrecision),
+            ("hours".to_owned(), PostgreSqlType::DoublePrecision),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2020".to_owned(),
+            "4".to_owned(),
+            "56.5".to_owned(),
+            "9".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn truncate_current_timestamp(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(
+        FrozenClock::at_unix_seconds(1_597_322_096).with_utc_offset(2 * 60 * 60),
+    ));
+    engine
+        .execute("select date_trunc('month', now()) as truncated, current_date as today;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("truncated".to_owned(), PostgreSqlType::VarChar),
+            ("today".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2020-08-01 00:00:00+02".to_owned(),
+            "2020-08-13".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn age_of_timestamps(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(FrozenClock::at_unix_seconds(1_597_322_096)));
+    engine
+        .execute(
+            "select age('2020-03-01 00:00:00+00', '2020-01-31 06:00:00+00') as between_moments, \
+                age('2019-08-01 12:00:00+00') as since_moment;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![
+            ("between_moments".to_owned(), PostgreSqlType::Interval),
+            ("since_moment".to_owned(), PostgreSqlType::Interval),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1 mon 18:00:00".to_owned(),
+            "1 year 11 days 12:00:00".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn truncate_timestamp_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.events (happened_at timestamp with time zone, week_of timestamp with time zone);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.events (happened_at) values ('2020-08-13 12:34:56+00'), (null);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.events set week_of = date_trunc('week', happened_at);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.events;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("happened_at".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+            ("week_of".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2020-08-13 12:34:56+00".to_owned(),
+            "2020-08-10 00:00:00+00".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["NULL".to_owned(), "NULL".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_date_time_functions_of_columns(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.events (happened_at timestamp with time zone);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.events values ('2020-08-13 12:34:56+00'), (null);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "select extract(hour from happened_at) as hour, date_trunc('day', happened_at) as day \
+            from schema_name.events;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("hour".to_owned(), PostgreSqlType::DoublePrecision),
+            ("day".to_owned(), PostgreSqlType::TimestampWithTimeZone),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "12".to_owned(),
+            "2020-08-13 00:00:00+00".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["NULL".to_owned(), "NULL".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn unknown_units(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select date_trunc('fortnight', '2020-08-13 12:34:56+00') as truncated;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_parameter_value(
+            "timestamp with time zone units \"fortnight\" not recognized",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
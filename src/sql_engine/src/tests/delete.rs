@@ -64,17 +64,61 @@ fn delete_all_records(sql_engine_with_schema: (QueryExecutor, ResultCollector))
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()], vec!["456".to_owned()]],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![(
+            "column_test".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["123".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["456".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsDeleted(2)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![(
+            "column_test".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::RecordsSelected(0)),
         Ok(QueryEvent::QueryComplete),
     ])
 }
+
+#[rstest::rstest]
+fn delete_records_that_satisfy_where(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10), (2, null), (3, 30), (4, 40);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where id = 1 or value is null;")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where value not between 20 and 35;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("value".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned(), "30".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -26,7 +26,7 @@ fn describe_select_statement(sql_engine_with_schema: (QueryExecutor, ResultColle
         .parse_prepared_statement(
             "statement_name",
             "select * from schema_name.table_name where column = $1 and column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -44,6 +44,7 @@ fn describe_select_statement(sql_engine_with_schema: (QueryExecutor, ResultColle
                 ("column_1".to_owned(), PostgreSqlType::SmallInt),
                 ("column_2".to_owned(), PostgreSqlType::SmallInt),
             ],
+            vec![Some((16384, 1)), Some((16384, 2))],
         )),
     ]);
 }
@@ -57,8 +58,8 @@ fn describe_update_statement(sql_engine_with_schema: (QueryExecutor, ResultColle
     engine
         .parse_prepared_statement(
             "statement_name",
-            "update schema_name.table_name set column_1 = $1 where column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            "update schema_name.table_name set column_1 = $1, column_2 = $2;",
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -74,6 +75,54 @@ fn describe_update_statement(sql_engine_with_schema: (QueryExecutor, ResultColle
         Ok(QueryEvent::PreparedStatementDescribed(
             vec![PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
             vec![],
+            vec![],
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn describe_statement_with_inferred_param_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint, column_2 varchar(10));")
+        .expect("no system errors");
+    engine
+        .parse_prepared_statement(
+            "select_statement",
+            "select column_2 from schema_name.table_name where column_1 = $1;",
+            &[],
+        )
+        .expect("no system errors");
+    engine
+        .parse_prepared_statement(
+            "insert_statement",
+            "insert into schema_name.table_name values ($1, $2);",
+            &[None, Some(PostgreSqlType::Integer)],
+        )
+        .expect("no system errors");
+    engine
+        .describe_prepared_statement("select_statement")
+        .expect("no system errors");
+    engine
+        .describe_prepared_statement("insert_statement")
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::PreparedStatementDescribed(
+            vec![PostgreSqlType::SmallInt],
+            vec![("column_2".to_owned(), PostgreSqlType::VarChar)],
+            vec![Some((16384, 2))],
+        )),
+        Ok(QueryEvent::PreparedStatementDescribed(
+            vec![PostgreSqlType::SmallInt, PostgreSqlType::Integer],
+            vec![],
+            vec![],
         )),
     ]);
 }
@@ -0,0 +1,165 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_pairs(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.pairs (id smallint, left_value integer, right_value smallint, same boolean);",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "insert into schema_name.pairs (id, left_value, right_value) \
+            values (1, 1, 1), (2, 1, 2), (3, 1, null), (4, null, null);",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn pairs(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("left_value".to_owned(), PostgreSqlType::Integer),
+            ("right_value".to_owned(), PostgreSqlType::SmallInt),
+            ("same".to_owned(), PostgreSqlType::Bool),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn null_safe_comparison_of_constants(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select null is distinct from null as both_null, \
+                1 is distinct from null as one_null, \
+                1 is distinct from 1 + 0 as equal, \
+                2 is not distinct from 1 as not_equal;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("both_null".to_owned(), PostgreSqlType::Bool),
+            ("one_null".to_owned(), PostgreSqlType::Bool),
+            ("equal".to_owned(), PostgreSqlType::Bool),
+            ("not_equal".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "f".to_owned(),
+            "t".to_owned(),
+            "f".to_owned(),
+            "f".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn null_safe_comparison_of_columns(with_pairs: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_pairs;
+    engine
+        .execute("update schema_name.pairs set same = left_value is not distinct from right_value;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.pairs order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            pairs(vec![
+                vec!["1", "1", "1", "t"],
+                vec!["2", "1", "2", "f"],
+                vec!["3", "1", "NULL", "f"],
+                vec!["4", "NULL", "NULL", "t"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn distinct_from_binds_looser_than_arithmetic(with_pairs: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_pairs;
+    engine
+        .execute("update schema_name.pairs set same = left_value + 1 is distinct from right_value * 2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.pairs order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            pairs(vec![
+                vec!["1", "1", "1", "f"],
+                vec!["2", "1", "2", "t"],
+                vec!["3", "1", "NULL", "t"],
+                vec!["4", "NULL", "NULL", "f"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn incomparable_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select 1 is distinct from 'one' as different;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::undefined_function(
+            "IS DISTINCT FROM".to_owned(),
+            "NUMBER".to_owned(),
+            "String".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
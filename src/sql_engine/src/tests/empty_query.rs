@@ -0,0 +1,35 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::rstest]
+fn empty_query(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("").expect("no system errors");
+    engine.execute("  \n\t").expect("no system errors");
+    engine.execute(";;").expect("no system errors");
+    engine.execute("-- nothing to run").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::QueryEmpty),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryEmpty),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryEmpty),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryEmpty),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,133 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_mood(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create type schema_name.mood as enum ('sad', 'ok', 'happy');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.people (name varchar(10), mood mood, last_mood schema_name.mood);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TypeCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn order_by_label_position(with_mood: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_mood;
+    engine
+        .execute(
+            "insert into schema_name.people values \
+            ('ann', 'ok', null), ('bob', 'happy', 'sad'), ('cid', 'sad', 'happy'), ('dan', null, 'ok');",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select name, mood from schema_name.people order by mood desc;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("name".to_owned(), PostgreSqlType::VarChar),
+            ("mood".to_owned(), PostgreSqlType::Text),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["dan".to_owned(), "NULL".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["bob".to_owned(), "happy".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["ann".to_owned(), "ok".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["cid".to_owned(), "sad".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn values_out_of_labels(with_mood: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_mood;
+    engine
+        .execute("insert into schema_name.people values ('ann', 'angry', null);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.people values ('bob', 'ok', 'sad');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.people set last_mood = 'Happy';")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::invalid_text_representation(
+            "invalid input value for enum mood: \"angry\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_text_representation(
+            "invalid input value for enum mood: \"Happy\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn type_already_exists(with_mood: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_mood;
+    engine
+        .execute("create type schema_name.mood as enum ('up', 'down');")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::type_already_exists("schema_name.mood")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn repeated_labels(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create type schema_name.answer as enum ('yes', 'no', 'yes');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_object_definition(
+            "enum label \"yes\" used more than once",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
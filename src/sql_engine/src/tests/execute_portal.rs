@@ -26,7 +26,7 @@ fn execute_insert_portal(sql_engine_with_schema: (QueryExecutor, ResultCollector
         .parse_prepared_statement(
             "statement_name",
             "insert into schema_name.table_name values ($1, $2);",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -64,7 +64,7 @@ fn execute_update_portal(sql_engine_with_schema: (QueryExecutor, ResultCollector
         .parse_prepared_statement(
             "statement_name",
             "update schema_name.table_name set column_1 = $1, column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
     engine
@@ -90,3 +90,194 @@ fn execute_update_portal(sql_engine_with_schema: (QueryExecutor, ResultCollector
         Ok(QueryEvent::RecordsUpdated(1)),
     ]);
 }
+
+#[rstest::fixture]
+fn with_select_portal(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint, column_2 varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 'a');")
+        .expect("no system errors");
+    engine
+        .parse_prepared_statement("statement_name", "select * from schema_name.table_name;", &[])
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "portal_name",
+            "statement_name",
+            &[],
+            &[],
+            &[PostgreSqlFormat::Binary, PostgreSqlFormat::Text],
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn select_portal_prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::BindComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn describe_and_execute_select_portal(with_select_portal: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_select_portal;
+    engine.describe_portal("portal_name").expect("no system errors");
+    engine.execute_portal("portal_name", 0).expect("no system errors");
+    engine.sync();
+
+    let mut expected = select_portal_prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::PortalDescribed(
+            vec![
+                ("column_1".to_owned(), PostgreSqlType::SmallInt),
+                ("column_2".to_owned(), PostgreSqlType::VarChar),
+            ],
+            vec![Some((16384, 1)), Some((16384, 2))],
+        )),
+        Ok(QueryEvent::RecordsFetched(
+            (
+                vec![
+                    ("column_1".to_owned(), PostgreSqlType::SmallInt),
+                    ("column_2".to_owned(), PostgreSqlType::VarChar),
+                ],
+                vec![vec!["1".to_owned(), "a".to_owned()]],
+            ),
+            vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text],
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn execute_select_portal_with_params(with_select_portal: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_select_portal;
+    engine
+        .execute("insert into schema_name.table_name values (2, 'b'), (3, 'c');")
+        .expect("no system errors");
+    engine
+        .parse_prepared_statement(
+            "filtered",
+            "select column_2 from schema_name.table_name where column_1 > $1;",
+            &[],
+        )
+        .expect("no system errors");
+    engine
+        .bind_prepared_statement_to_portal(
+            "filtered_portal",
+            "filtered",
+            &[PostgreSqlFormat::Text],
+            &[Some(b"2".to_vec())],
+            &[],
+        )
+        .expect("no system errors");
+    engine.execute_portal("filtered_portal", 0).expect("no system errors");
+    engine.sync();
+
+    let mut expected = select_portal_prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::BindComplete),
+        Ok(QueryEvent::RecordsFetched(
+            (
+                vec![("column_2".to_owned(), PostgreSqlType::VarChar)],
+                vec![vec!["c".to_owned()]],
+            ),
+            vec![PostgreSqlFormat::Text],
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn execute_select_portal_with_row_limit(with_select_portal: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_select_portal;
+    engine
+        .execute("insert into schema_name.table_name values (2, 'b'), (3, 'c');")
+        .expect("no system errors");
+    engine.execute_portal("portal_name", 2).expect("no system errors");
+    engine.execute_portal("portal_name", 2).expect("no system errors");
+    engine.execute_portal("portal_name", 2).expect("no system errors");
+    engine.sync();
+
+    let description = vec![
+        ("column_1".to_owned(), PostgreSqlType::SmallInt),
+        ("column_2".to_owned(), PostgreSqlType::VarChar),
+    ];
+    let rows = |rows: Vec<Vec<&str>>| -> Vec<Vec<String>> {
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect()
+    };
+    let formats = vec![PostgreSqlFormat::Binary, PostgreSqlFormat::Text];
+    let mut expected = select_portal_prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSuspended(
+            (description.clone(), rows(vec![vec!["1", "a"], vec!["2", "b"]])),
+            formats.clone(),
+        )),
+        Ok(QueryEvent::RecordsFetched(
+            (description.clone(), rows(vec![vec!["3", "c"]])),
+            formats.clone(),
+        )),
+        Ok(QueryEvent::RecordsFetched((description, vec![]), formats)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn closed_statement_closes_its_portals(with_select_portal: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_select_portal;
+    engine
+        .close_prepared_statement("statement_name")
+        .expect("no system errors");
+    engine.close_portal("non_existent").expect("no system errors");
+    engine.describe_portal("portal_name").expect("no system errors");
+    engine.sync();
+
+    let mut expected = select_portal_prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::CloseComplete),
+        Ok(QueryEvent::CloseComplete),
+        Err(QueryError::portal_does_not_exist("portal_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn messages_after_error_are_discarded_until_sync(with_select_portal: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_select_portal;
+    engine.execute_portal("non_existent", 0).expect("no system errors");
+    engine.describe_portal("portal_name").expect("no system errors");
+    engine.execute_portal("portal_name", 0).expect("no system errors");
+    engine.sync();
+    engine.close_portal("portal_name").expect("no system errors");
+    engine.sync();
+
+    let mut expected = select_portal_prelude();
+    expected.extend(vec![
+        Err(QueryError::portal_does_not_exist("non_existent")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::CloseComplete),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
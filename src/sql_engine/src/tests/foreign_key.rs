@@ -0,0 +1,232 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+fn create_tables(engine: &mut QueryExecutor, on_delete: &str) {
+    engine
+        .execute("create table schema_name.customers (id smallint primary key, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute(
+            format!(
+                "create table schema_name.orders (id smallint primary key, customer_id smallint references schema_name.customers {});",
+                on_delete
+            )
+            .as_str(),
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.customers values (1, 'alice'), (2, 'bob');")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values (10, 1), (20, 2);")
+        .expect("no system errors");
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn orders(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("customer_id".to_owned(), PostgreSqlType::SmallInt),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn reference_to_nonexistent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.orders (customer_id smallint references schema_name.customers);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.customers")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_value_missing_in_referenced_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    create_tables(&mut engine, "");
+    engine
+        .execute("insert into schema_name.orders values (30, 3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values (30, null);")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::foreign_key_violation("orders", "orders_customer_id_fkey")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_to_value_missing_in_referenced_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    create_tables(&mut engine, "");
+    engine
+        .execute("update schema_name.orders set customer_id = 3;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::foreign_key_violation("orders", "orders_customer_id_fkey")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_referenced_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.customers (id smallint primary key);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders (customer_id smallint references schema_name.customers (id));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.customers values (1);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values (1);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.customers set id = 2;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::referenced_row_violation(
+            "customers",
+            "orders_customer_id_fkey",
+            "orders",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn delete_restricted_referenced_rows(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    create_tables(&mut engine, "on delete restrict");
+    engine
+        .execute("delete from schema_name.customers;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.orders;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(
+        [
+            vec![
+                Err(QueryError::referenced_row_violation(
+                    "customers",
+                    "orders_customer_id_fkey",
+                    "orders",
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            orders(vec![vec!["10", "1"], vec!["20", "2"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn delete_referenced_rows_with_cascade(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    create_tables(&mut engine, "on delete cascade");
+    engine
+        .execute("delete from schema_name.customers;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.orders;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsDeleted(2)), Ok(QueryEvent::QueryComplete)],
+            orders(vec![]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn delete_referenced_rows_with_set_null(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    create_tables(&mut engine, "on delete set null");
+    engine
+        .execute("delete from schema_name.customers;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.orders;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsDeleted(2)), Ok(QueryEvent::QueryComplete)],
+            orders(vec![vec!["10", "NULL"], vec!["20", "NULL"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,333 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use protocol::{pgsql_types::PostgreSqlType, session::FunctionContext};
+use representation::{Datum, ScalarType};
+
+use crate::function::{FunctionBuilder, FunctionRegistry, ScalarFunction, Volatility};
+
+use super::*;
+
+/// counts its calls to show how many times it is evaluated
+struct Tick {
+    volatility: Volatility,
+    calls: AtomicI64,
+}
+
+impl Tick {
+    fn new(volatility: Volatility) -> Tick {
+        Tick {
+            volatility,
+            calls: AtomicI64::new(0),
+        }
+    }
+}
+
+impl ScalarFunction for Tick {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Int64
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        self.volatility
+    }
+
+    fn call(&self, _context: &FunctionContext, _args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        Ok(Datum::from_i64(self.calls.fetch_add(1, Ordering::SeqCst) + 1))
+    }
+}
+
+struct Double;
+
+impl ScalarFunction for Double {
+    fn argument_types(&self) -> &[ScalarType] {
+        &[ScalarType::Int64]
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Int64
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        match args[0].scalar_type() {
+            Some(ScalarType::Int64) => Ok(Datum::from_i64(args[0].as_i64() * 2)),
+            _ => panic!("strict function is not called with NULL"),
+        }
+    }
+}
+
+/// adds up any number of integers
+struct Total {
+    argument_types: Vec<ScalarType>,
+}
+
+impl ScalarFunction for Total {
+    fn argument_types(&self) -> &[ScalarType] {
+        &self.argument_types
+    }
+
+    fn return_type(&self) -> ScalarType {
+        ScalarType::Int64
+    }
+
+    fn is_strict(&self) -> bool {
+        true
+    }
+
+    fn volatility(&self) -> Volatility {
+        Volatility::Immutable
+    }
+
+    fn call(&self, _context: &FunctionContext, args: &[Datum]) -> Result<Datum<'static>, QueryError> {
+        Ok(Datum::from_i64(args.iter().map(Datum::as_i64).sum()))
+    }
+}
+
+struct TotalBuilder;
+
+impl FunctionBuilder for TotalBuilder {
+    fn build(&self, args: &[(ScalarType, bool)]) -> Result<Arc<dyn ScalarFunction>, QueryError> {
+        if args.is_empty() {
+            Err(QueryError::syntax_error("total requires at least one argument"))
+        } else {
+            Ok(Arc::new(Total {
+                argument_types: vec![ScalarType::Int64; args.len()],
+            }))
+        }
+    }
+}
+
+#[rstest::fixture]
+fn counters(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.register_function("tick", Arc::new(Tick::new(Volatility::Volatile)));
+    engine.register_function("stable_tick", Arc::new(Tick::new(Volatility::Stable)));
+    engine.register_function("double", Arc::new(Double));
+    engine.register_function_builder("total", Arc::new(TotalBuilder));
+    engine
+        .execute("create table schema_name.counters (position smallint, value bigint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.counters values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn counters_selected(values: Vec<&str>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("position".to_owned(), PostgreSqlType::SmallInt),
+            ("value".to_owned(), PostgreSqlType::BigInt),
+        ],
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| vec![(index + 1).to_string(), value.to_owned()])
+            .collect(),
+    )
+}
+
+#[test]
+fn built_in_functions_metadata() {
+    let registry = FunctionRegistry::default();
+
+    let now = registry.lookup("pg_catalog.now").expect("now is built in");
+    assert_eq!(now.volatility(), Volatility::Stable);
+    assert_eq!(now.return_type(), ScalarType::String);
+
+    let random = registry.lookup("RANDOM").expect("random is built in");
+    assert_eq!(random.volatility(), Volatility::Volatile);
+
+    let setseed = registry.lookup("setseed").expect("setseed is built in");
+    assert_eq!(setseed.argument_types(), &[ScalarType::Float64]);
+    assert!(setseed.is_strict());
+
+    let concat = registry
+        .builder("pg_catalog.CONCAT")
+        .expect("concat is built in")
+        .build(&[(ScalarType::Int32, false), (ScalarType::String, true)])
+        .expect("concat takes any arguments");
+    assert_eq!(concat.argument_types(), &[ScalarType::Int32, ScalarType::String]);
+    assert_eq!(concat.return_type(), ScalarType::String);
+}
+
+#[rstest::rstest]
+fn volatile_function_is_called_for_every_row(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = tick();")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.counters order by position;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(3)), Ok(QueryEvent::QueryComplete)],
+            counters_selected(vec!["1", "2", "3"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn stable_function_is_called_once_per_statement(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = stable_tick();")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.counters order by position;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(3)), Ok(QueryEvent::QueryComplete)],
+            counters_selected(vec!["1", "1", "1"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_of_column(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = double(value);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.counters order by position;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(3)), Ok(QueryEvent::QueryComplete)],
+            counters_selected(vec!["20", "40", "60"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn strict_function_with_null_argument(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = double(null);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.counters order by position;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(3)), Ok(QueryEvent::QueryComplete)],
+            counters_selected(vec!["NULL", "NULL", "NULL"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_with_wrong_arguments(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = double(1, 2);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::function_does_not_exist("double(1, 2)")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_built_for_arguments(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = total(value, position, 1);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.counters order by position;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(3)), Ok(QueryEvent::QueryComplete)],
+            counters_selected(vec!["12", "23", "34"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_builder_rejects_arguments(counters: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = counters;
+    engine
+        .execute("update schema_name.counters set value = total();")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::syntax_error("total requires at least one argument")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
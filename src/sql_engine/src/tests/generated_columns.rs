@@ -0,0 +1,157 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_items(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.items (\
+                price integer, \
+                quantity integer, \
+                total integer generated always as (price * quantity) stored\
+            );",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (10, 2), (5, 3);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn items(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("price".to_owned(), PostgreSqlType::Integer),
+            ("quantity".to_owned(), PostgreSqlType::Integer),
+            ("total".to_owned(), PostgreSqlType::Integer),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn generated_on_insert(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("select * from schema_name.items order by price;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            items(vec![vec!["5", "3", "15"], vec!["10", "2", "20"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn generated_on_update(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("update schema_name.items set quantity = 4;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items order by price;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(2)), Ok(QueryEvent::QueryComplete)],
+            items(vec![vec!["5", "4", "20"], vec!["10", "4", "40"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn insert_into_generated_column(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("insert into schema_name.items values (1, 1, 1);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::generated_always(
+            "cannot insert a non-DEFAULT value into column \"total\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_generated_column(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("update schema_name.items set total = 1;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::generated_always(
+            "column \"total\" can only be updated to DEFAULT",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn generated_from_generated_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.items (\
+                price integer, \
+                total integer generated always as (price * 2) stored, \
+                doubled integer generated always as (total * 2) stored\
+            );",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_object_definition(
+            "cannot use generated column \"total\" in column generation expression",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
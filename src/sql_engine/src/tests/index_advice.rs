@@ -0,0 +1,127 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_orders(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.customers (id smallint primary key, age smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.orders (id smallint primary key, amount integer, \
+            customer_id smallint references schema_name.customers (id));",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn advice(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("schema_name".to_owned(), PostgreSqlType::VarChar),
+            ("table_name".to_owned(), PostgreSqlType::VarChar),
+            ("column_name".to_owned(), PostgreSqlType::VarChar),
+            ("reason".to_owned(), PostgreSqlType::VarChar),
+            ("filters".to_owned(), PostgreSqlType::BigInt),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+fn no_rows(columns: Vec<(&str, PostgreSqlType)>) -> Vec<QueryResult> {
+    records_selected(
+        columns
+            .into_iter()
+            .map(|(name, pg_type)| (name.to_owned(), pg_type))
+            .collect(),
+        vec![],
+    )
+}
+
+#[rstest::rstest]
+fn foreign_keys_and_filtered_columns_without_index(with_orders: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_orders;
+    engine
+        .execute("select * from schema_name.customers where age > 18;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.orders where amount >= 100;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.orders where amount < 10 and id = 1;")
+        .expect("no system errors");
+    engine.execute("select * from index_advice;").expect("no system errors");
+
+    let customers = vec![("id", PostgreSqlType::SmallInt), ("age", PostgreSqlType::SmallInt)];
+    let orders = vec![
+        ("id", PostgreSqlType::SmallInt),
+        ("amount", PostgreSqlType::Integer),
+        ("customer_id", PostgreSqlType::SmallInt),
+    ];
+    let mut expected = prelude();
+    expected.extend(
+        [
+            no_rows(customers),
+            vec![Ok(QueryEvent::QueryComplete)],
+            no_rows(orders.clone()),
+            vec![Ok(QueryEvent::QueryComplete)],
+            no_rows(orders),
+            vec![Ok(QueryEvent::QueryComplete)],
+            advice(vec![
+                vec!["schema_name", "orders", "customer_id", "foreign key without index", "0"],
+                vec!["schema_name", "orders", "amount", "filtered without index", "2"],
+                vec!["schema_name", "customers", "age", "filtered without index", "1"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn only_all_columns_are_selected(with_orders: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_orders;
+    engine
+        .execute("select reason from index_advice;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::feature_not_supported(
+            "only all columns of index_advice can be selected",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,196 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{clock::FrozenClock, pgsql_types::PostgreSqlType};
+
+use super::*;
+
+fn selected(name: &str, pg_type: PostgreSqlType, value: &str) -> Vec<QueryResult> {
+    records_selected(vec![(name.to_owned(), pg_type)], vec![vec![value.to_owned()]])
+}
+
+#[rstest::rstest]
+fn interval_literals(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select interval '1 year 14 months 2 hours' as span;")
+        .expect("no system errors");
+    engine
+        .execute("select interval '7' day as week;")
+        .expect("no system errors");
+    engine
+        .execute("select interval '-1 day 1.5 seconds' as span;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("span", PostgreSqlType::Interval, "2 years 2 mons 02:00:00"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("week", PostgreSqlType::Interval, "7 days"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("span", PostgreSqlType::Interval, "-1 days +00:00:01.5"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn interval_arithmetic(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select interval '1 day' + interval '3 hours' as total;")
+        .expect("no system errors");
+    engine
+        .execute("select interval '1 day' - interval '1 month' as total;")
+        .expect("no system errors");
+    engine
+        .execute("select -interval '2 mons' as total;")
+        .expect("no system errors");
+    engine
+        .execute("select interval '1 hour' * 2.5 as total;")
+        .expect("no system errors");
+    engine
+        .execute("select interval '1 day' > interval '23 hours' as longer;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("total", PostgreSqlType::Interval, "1 day 03:00:00"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("total", PostgreSqlType::Interval, "-1 mons +1 day"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("total", PostgreSqlType::Interval, "-2 mons"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("total", PostgreSqlType::Interval, "02:30:00"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("longer", PostgreSqlType::Bool, "t"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn timestamp_plus_interval(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(FrozenClock::at_unix_seconds(946_684_800)));
+    engine
+        .execute("select now() + interval '1 month 1 day' as later;")
+        .expect("no system errors");
+    engine
+        .execute("select now() - interval '1 hour' as earlier;")
+        .expect("no system errors");
+    engine
+        .execute("select '2020-01-31 10:00:00+03' + interval '1 month' as later;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("later", PostgreSqlType::VarChar, "2000-02-02 00:00:00+00"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("earlier", PostgreSqlType::VarChar, "1999-12-31 23:00:00+00"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("later", PostgreSqlType::VarChar, "2020-02-29 10:00:00+03"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn shift_timestamp_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (moment timestamptz);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('2020-08-01 12:30:00+03');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set moment = moment + interval '1 day 30 minutes';")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsUpdated(1)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(
+                "moment",
+                PostgreSqlType::TimestampWithTimeZone,
+                "2020-08-02 10:00:00+00",
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn interval_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (span interval);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('36 hours'), (interval '90' minute), (null);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "span".to_owned(),
+            PostgreSqlType::Interval,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["36:00:00".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["01:30:00".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["NULL".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn malformed_interval(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select interval 'soon' as span;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_datetime_format(
+            "invalid input syntax for type interval: \"soon\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,176 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn json_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name(doc json, bin jsonb, name varchar(10));")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn json_is_stored_as_written_and_jsonb_normalized(json_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = json_table;
+    engine
+        .execute(
+            "insert into schema_name.table_name (doc, bin) \
+            values ('{\"b\": 1,  \"a\": [1, 2], \"b\": 2}', '{\"b\": 1,  \"a\": [1, 2], \"b\": 2}');",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select doc, bin from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("doc".to_owned(), PostgreSqlType::Json),
+            ("bin".to_owned(), PostgreSqlType::Jsonb),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{\"b\": 1,  \"a\": [1, 2], \"b\": 2}".to_owned(),
+            "{\"a\": [1, 2], \"b\": 2}".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_json_is_rejected(json_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = json_table;
+    engine
+        .execute("insert into schema_name.table_name (doc) values ('{a: 1}');")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (bin) values ('[1, 2');")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::type_mismatch("{a: 1}", PostgreSqlType::Json, "doc", 1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch("[1, 2", PostgreSqlType::Jsonb, "bin", 1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn extraction_operators(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select '{\"a\": {\"b\": [10, \"x\"]}}' -> 'a' as object, \
+                '{\"a\": {\"b\": [10, \"x\"]}}' -> 'a' -> 'b' ->> 0 as chained, \
+                '{\"a\": {\"b\": [10, \"x\"]}}' #> '{a,b,1}' as path, \
+                '{\"a\": {\"b\": [10, \"x\"]}}' #>> '{a,b,1}' as path_text, \
+                '[1, 2, 3]' -> -1 as last, \
+                '{\"a\": null}' ->> 'a' as json_null, \
+                '{\"a\": 1}' -> 'b' as missing;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("object".to_owned(), PostgreSqlType::VarChar),
+            ("chained".to_owned(), PostgreSqlType::VarChar),
+            ("path".to_owned(), PostgreSqlType::VarChar),
+            ("path_text".to_owned(), PostgreSqlType::VarChar),
+            ("last".to_owned(), PostgreSqlType::VarChar),
+            ("json_null".to_owned(), PostgreSqlType::VarChar),
+            ("missing".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{\"b\": [10, \"x\"]}".to_owned(),
+            "10".to_owned(),
+            "\"x\"".to_owned(),
+            "x".to_owned(),
+            "3".to_owned(),
+            "NULL".to_owned(),
+            "NULL".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn extraction_from_columns(json_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = json_table;
+    engine
+        .execute("insert into schema_name.table_name (bin) values ('{\"user\": {\"name\": \"alice\"}}');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set name = bin -> 'user' ->> 'name', doc = bin #> '{user}';")
+        .expect("no system errors");
+    engine
+        .execute("select doc, name from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("doc".to_owned(), PostgreSqlType::Json),
+            ("name".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "{\"name\": \"alice\"}".to_owned(),
+            "alice".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn extraction_from_invalid_json(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("select '{a' -> 'b';").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_text_representation(
+            "invalid input syntax for type json: Token \"a\" is invalid.",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
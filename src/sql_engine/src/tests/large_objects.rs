@@ -0,0 +1,144 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+fn selected(name: &str, pg_type: PostgreSqlType, value: &str) -> Vec<QueryResult> {
+    records_selected(vec![(name.to_owned(), pg_type)], vec![vec![value.to_owned()]])
+}
+
+#[rstest::rstest]
+fn write_and_read_large_object_in_chunks(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select lo_create(16500);").expect("no system errors");
+    engine
+        .execute("select lo_put(16500, 0, '\\x01020304');")
+        .expect("no system errors");
+    engine
+        .execute("select lo_put(16500, 4, '\\x0506');")
+        .expect("no system errors");
+    engine.execute("select lo_get(16500, 2, 3);").expect("no system errors");
+    engine.execute("select lo_get(16500);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("lo_create", PostgreSqlType::BigInt, "16500"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_put", PostgreSqlType::VarChar, ""),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_put", PostgreSqlType::VarChar, ""),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_get", PostgreSqlType::VarChar, "\\x030405"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_get", PostgreSqlType::VarChar, "\\x010203040506"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn large_object_from_bytea(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select lo_from_bytea(16500, 'abc');")
+        .expect("no system errors");
+    engine.execute("select lo_get(16500);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("lo_from_bytea", PostgreSqlType::BigInt, "16500"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_get", PostgreSqlType::VarChar, "\\x616263"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn create_large_object_with_taken_id(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select lo_create(16500);").expect("no system errors");
+    engine.execute("select lo_create(16500);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("lo_create", PostgreSqlType::BigInt, "16500"),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::unique_violation("pg_largeobject_metadata_oid_index")),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn unlinked_large_object_does_not_exist(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select lo_create(16500);").expect("no system errors");
+    engine.execute("select lo_unlink(16500);").expect("no system errors");
+    engine.execute("select lo_get(16500);").expect("no system errors");
+    engine.execute("select lo_unlink(16500);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("lo_create", PostgreSqlType::BigInt, "16500"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("lo_unlink", PostgreSqlType::Integer, "1"),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::large_object_does_not_exist(16500)),
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::large_object_does_not_exist(16500)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn put_invalid_hex_data(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select lo_create(16500);").expect("no system errors");
+    engine
+        .execute("select lo_put(16500, 0, '\\x0z');")
+        .expect("no system errors");
+    engine
+        .execute("select lo_put(16500, 0, '\\x012');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            selected("lo_create", PostgreSqlType::BigInt, "16500"),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::invalid_text_representation(
+                    "invalid hexadecimal digit: \"z\"",
+                )),
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::invalid_text_representation(
+                    "invalid hexadecimal data: odd number of digits",
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
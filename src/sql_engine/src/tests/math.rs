@@ -0,0 +1,185 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn math_functions_of_constants(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select abs(-5) as abs, \
+                abs(-2.5) as abs_float, \
+                round(2.5) as round, \
+                round(3.14159, 2) as round_places, \
+                ceil(-1.5) as ceil, \
+                floor(-1.5) as floor, \
+                mod(-10, 3) as mod, \
+                5.5 % 2 as modulo, \
+                power(2, 10) as power, \
+                sqrt(16) as sqrt, \
+                exp(0) as exp, \
+                ln(1) as ln;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("abs".to_owned(), PostgreSqlType::Integer),
+            ("abs_float".to_owned(), PostgreSqlType::Real),
+            ("round".to_owned(), PostgreSqlType::DoublePrecision),
+            ("round_places".to_owned(), PostgreSqlType::DoublePrecision),
+            ("ceil".to_owned(), PostgreSqlType::DoublePrecision),
+            ("floor".to_owned(), PostgreSqlType::DoublePrecision),
+            ("mod".to_owned(), PostgreSqlType::Integer),
+            ("modulo".to_owned(), PostgreSqlType::Real),
+            ("power".to_owned(), PostgreSqlType::DoublePrecision),
+            ("sqrt".to_owned(), PostgreSqlType::DoublePrecision),
+            ("exp".to_owned(), PostgreSqlType::DoublePrecision),
+            ("ln".to_owned(), PostgreSqlType::DoublePrecision),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "5".to_owned(),
+            "2.5".to_owned(),
+            "3".to_owned(),
+            "3.14".to_owned(),
+            "-1".to_owned(),
+            "-2".to_owned(),
+            "-1".to_owned(),
+            "1.5".to_owned(),
+            "1024".to_owned(),
+            "4".to_owned(),
+            "1".to_owned(),
+            "0".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn math_functions_keep_integer_column_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.numbers (id smallint, value smallint, result smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers (id, value) values (1, -7), (2, null), (3, -32768);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.numbers set result = mod(value, 4);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.numbers set result = abs(value);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(3)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::numeric_value_out_of_range("smallint out of range")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_math_of_columns(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.numbers (id smallint, value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (1, -7), (2, null);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "select id, abs(value) as abs, mod(value, 4) as mod, value * 2 as doubled, -value as negated \
+            from schema_name.numbers order by id;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("abs".to_owned(), PostgreSqlType::SmallInt),
+            ("mod".to_owned(), PostgreSqlType::Integer),
+            ("doubled".to_owned(), PostgreSqlType::Integer),
+            ("negated".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1".to_owned(),
+            "7".to_owned(),
+            "-3".to_owned(),
+            "-14".to_owned(),
+            "7".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "2".to_owned(),
+            "NULL".to_owned(),
+            "NULL".to_owned(),
+            "NULL".to_owned(),
+            "NULL".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn math_errors(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("select mod(1, 0) as mod;").expect("no system errors");
+    engine.execute("select exp(1000) as exp;").expect("no system errors");
+    engine.execute("select ln(0) as ln;").expect("no system errors");
+    engine.execute("select sqrt(-1) as sqrt;").expect("no system errors");
+    engine
+        .execute("select power(0, -1) as power;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::division_by_zero()),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::numeric_value_out_of_range("value out of range: overflow")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_argument_for_logarithm(
+            "cannot take logarithm of zero",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_argument_for_power_function(
+            "cannot take square root of a negative number",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_argument_for_power_function(
+            "zero raised to a negative power is undefined",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
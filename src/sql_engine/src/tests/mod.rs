@@ -18,38 +18,134 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use protocol::results::{QueryError, QueryResult};
+use protocol::results::{Description, QueryError, QueryResult};
 
 use crate::QueryExecutor;
 
 use super::*;
 
+#[cfg(test)]
+mod activity;
+#[cfg(test)]
+mod aggregates;
+#[cfg(test)]
+mod arrays;
+#[cfg(test)]
+mod backup;
+#[cfg(test)]
+mod batch;
 #[cfg(test)]
 mod bind;
 #[cfg(test)]
 mod bind_prepared_statement_to_portal;
 #[cfg(test)]
+mod booleans;
+#[cfg(test)]
+mod bytea;
+#[cfg(test)]
+mod catalog_export;
+#[cfg(test)]
+mod catalog_queries;
+#[cfg(test)]
+mod change_capture;
+#[cfg(test)]
+mod concurrency;
+#[cfg(test)]
+mod conditionals;
+#[cfg(test)]
+mod copy;
+#[cfg(test)]
+mod cursors;
+#[cfg(test)]
+mod date_time;
+#[cfg(test)]
 mod delete;
 #[cfg(test)]
 mod describe_prepared_statement;
 #[cfg(test)]
+mod distinct_from;
+#[cfg(test)]
+mod empty_query;
+#[cfg(test)]
+mod enums;
+#[cfg(test)]
 mod error_responses;
 #[cfg(test)]
 mod execute_portal;
 #[cfg(test)]
+mod foreign_key;
+#[cfg(test)]
+mod functions;
+#[cfg(test)]
+mod generated_columns;
+#[cfg(test)]
+mod index_advice;
+#[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod interval;
+#[cfg(test)]
+mod json;
+#[cfg(test)]
+mod large_objects;
+#[cfg(test)]
+mod math;
+#[cfg(test)]
+mod multiple_statements;
+#[cfg(test)]
+mod not_null;
+#[cfg(test)]
+mod notifications;
+#[cfg(test)]
+mod operators;
+#[cfg(test)]
 mod parse_prepared_statement;
 #[cfg(test)]
+mod partitions;
+#[cfg(test)]
+mod plan_cache;
+#[cfg(test)]
+mod primary_key;
+#[cfg(test)]
+mod random;
+#[cfg(test)]
+mod regex;
+#[cfg(test)]
+mod result_limits;
+#[cfg(test)]
 mod schema;
 #[cfg(test)]
+mod schema_privileges;
+#[cfg(test)]
+mod schema_templates;
+#[cfg(test)]
 mod select;
 #[cfg(test)]
+mod sequences;
+#[cfg(test)]
+mod session_clock;
+#[cfg(test)]
+mod sql_functions;
+#[cfg(test)]
+mod statistics;
+#[cfg(test)]
+mod strings;
+#[cfg(test)]
 mod table;
 #[cfg(test)]
+mod time_zone;
+#[cfg(test)]
+mod trace;
+#[cfg(test)]
+mod transactions;
+#[cfg(test)]
 mod type_constraints;
 #[cfg(test)]
+mod unsupported;
+#[cfg(test)]
 mod update;
+#[cfg(test)]
+mod vacuum;
 
 struct Collector(Mutex<Vec<QueryResult>>);
 
@@ -78,6 +174,15 @@ impl Collector {
 
 type ResultCollector = Arc<Collector>;
 
+/// events of selected rows as they are streamed to the client
+fn records_selected(description: Description, rows: Vec<Vec<String>>) -> Vec<QueryResult> {
+    let selected = rows.len();
+    let mut events = vec![Ok(QueryEvent::RowDescription(description))];
+    events.extend(rows.into_iter().map(|row| Ok(QueryEvent::DataRow(row))));
+    events.push(Ok(QueryEvent::RecordsSelected(selected)));
+    events
+}
+
 #[rstest::fixture]
 fn sender() -> ResultCollector {
     Arc::new(Collector(Mutex::new(vec![])))
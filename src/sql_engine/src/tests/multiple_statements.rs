@@ -0,0 +1,96 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn statements_complete_once(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.table_name (col smallint); \
+            insert into schema_name.table_name values (1), (2); \
+            select * from schema_name.table_name;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn statements_after_failed_one_are_not_run(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "insert into schema_name.table_name values (1); \
+            create table schema_name.table_name (col smallint);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.table_name")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn semicolons_in_literals_and_comments_do_not_split_statements(
+    sql_engine_with_schema: (QueryExecutor, ResultCollector),
+) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.table_name (col varchar(10));; \
+            -- a comment; with a semicolon\n\
+            insert into schema_name.table_name values ('a;b') /* c;d */; \
+            select * from schema_name.table_name",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::RowDescription(vec![(
+            "col".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["a;b".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
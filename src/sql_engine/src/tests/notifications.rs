@@ -0,0 +1,76 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::activity::SessionRegistry;
+
+use super::{activity::session, *};
+
+#[rstest::rstest]
+fn notification_is_delivered_to_listening_sessions() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut listener, listener_collector) = session(&sessions);
+    let (mut notifier, notifier_collector) = session(&sessions);
+
+    listener.execute("listen events;").expect("no system errors");
+    notifier
+        .execute("notify events, 'table created';")
+        .expect("no system errors");
+    listener.execute("unlisten events;").expect("no system errors");
+    notifier.execute("notify events;").expect("no system errors");
+    listener.execute("unlisten *;").expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Listening),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Unlistening),
+        Ok(QueryEvent::Notification(
+            notifier.pid(),
+            "events".to_owned(),
+            "table created".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Unlistening),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    notifier_collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Notified),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Notified),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn session_receives_own_notifications() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut engine, collector) = session(&sessions);
+
+    engine.execute("listen events;").expect("no system errors");
+    engine.execute("notify events, 'payload';").expect("no system errors");
+    engine.execute("notify other;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Listening),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Notified),
+        Ok(QueryEvent::Notification(
+            engine.pid(),
+            "events".to_owned(),
+            "payload".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Notified),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
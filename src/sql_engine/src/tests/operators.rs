@@ -0,0 +1,153 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+use representation::{Datum, ScalarType};
+use sqlparser::ast::BinaryOperator;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_numbers(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.numbers (small smallint, regular integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (3, 50000);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn numbers(small: &str, regular: &str) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("small".to_owned(), PostgreSqlType::SmallInt),
+            ("regular".to_owned(), PostgreSqlType::Integer),
+        ],
+        vec![vec![small.to_owned(), regular.to_owned()]],
+    )
+}
+
+#[rstest::rstest]
+fn smallint_is_widened_to_integer(with_numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_numbers;
+    engine
+        .execute("update schema_name.numbers set regular = small * regular;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(1)), Ok(QueryEvent::QueryComplete)],
+            numbers("3", "150000"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn negation_of_column(with_numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_numbers;
+    engine
+        .execute("update schema_name.numbers set small = -small;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(1)), Ok(QueryEvent::QueryComplete)],
+            numbers("-3", "50000"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn integer_overflow(with_numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_numbers;
+    engine
+        .execute("update schema_name.numbers set regular = regular * regular;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::numeric_value_out_of_range("integer out of range")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn division_by_zero(with_numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_numbers;
+    engine
+        .execute("insert into schema_name.numbers values (1 / 0, 1);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![Err(QueryError::division_by_zero()), Ok(QueryEvent::QueryComplete)]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn registered_operator(with_numbers: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_numbers;
+    engine.register_binary_operator(
+        BinaryOperator::BitwiseXor,
+        ScalarType::Int32,
+        ScalarType::Int32,
+        ScalarType::Int32,
+        |left, right| Ok(Datum::from_i32(left.as_i32() ^ right.as_i32())),
+    );
+    engine
+        .execute("update schema_name.numbers set regular = small ^ 5;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.numbers;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(1)), Ok(QueryEvent::QueryComplete)],
+            numbers("3", "6"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
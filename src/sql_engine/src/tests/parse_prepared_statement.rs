@@ -27,7 +27,7 @@ fn parse_select_statement(sql_engine_with_schema: (QueryExecutor, ResultCollecto
         .parse_prepared_statement(
             "statement_name",
             "select * from schema_name.table_name where column = $1 and column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
 
@@ -64,7 +64,6 @@ fn parse_select_statement_with_not_existed_table(sql_engine_with_schema: (QueryE
         Ok(QueryEvent::SchemaCreated),
         Ok(QueryEvent::QueryComplete),
         Err(QueryError::table_does_not_exist("schema_name.non_existent")),
-        Ok(QueryEvent::ParseComplete), // TODO should be removed
     ]);
 }
 
@@ -101,8 +100,8 @@ fn parse_update_statement(sql_engine_with_schema: (QueryExecutor, ResultCollecto
     engine
         .parse_prepared_statement(
             "statement_name",
-            "update schema_name.table_name set column_1 = $1 where column_2 = $2;",
-            &[PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
+            "update schema_name.table_name set column_1 = $1, column_2 = $2;",
+            &[Some(PostgreSqlType::SmallInt), Some(PostgreSqlType::SmallInt)],
         )
         .expect("no system errors");
 
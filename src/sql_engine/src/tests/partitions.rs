@@ -0,0 +1,224 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_measurements(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.measurements (id integer, value integer) partition by range (id);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.low partition of schema_name.measurements for values from (minvalue) to (10);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.high partition of schema_name.measurements for values from (10) to (20);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values (1, 100), (15, 200), (5, 300);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn measurements(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::Integer),
+            ("value".to_owned(), PostgreSqlType::Integer),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn rows_are_routed_to_partitions(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("select * from schema_name.low order by id;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.high order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            measurements(vec![vec!["1", "100"], vec!["5", "300"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            measurements(vec![vec!["15", "200"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn select_from_partitioned_table(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("select * from schema_name.measurements order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            measurements(vec![vec!["1", "100"], vec!["5", "300"], vec!["15", "200"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn select_with_range_predicates(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("select * from schema_name.measurements where id >= 10;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.measurements where 2 < id and id between 0 and 15 order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            measurements(vec![vec!["15", "200"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            measurements(vec![vec!["5", "300"], vec!["15", "200"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn select_with_coerced_range_predicates(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("select * from schema_name.measurements where id > 4.5 order by id;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.measurements where id <= '5' and id between 0.5 and 20 order by id;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.measurements where id = 5.5;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.measurements where id = 'five';")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            measurements(vec![vec!["5", "300"], vec!["15", "200"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            measurements(vec![vec!["1", "100"], vec!["5", "300"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            measurements(vec![]),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::invalid_text_representation(
+                    "invalid input syntax for type integer: \"five\"",
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn insert_row_without_partition(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("insert into schema_name.measurements values (25, 400);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::check_violation(
+            "no partition of relation \"measurements\" found for row",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn insert_into_partition_out_of_its_bounds(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("insert into schema_name.high values (1, 400);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::check_violation(
+            "new row for relation \"high\" violates partition constraint",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn create_overlapping_partition(with_measurements: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_measurements;
+    engine
+        .execute("create table schema_name.middle partition of schema_name.measurements for values from (5) to (15);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.empty partition of schema_name.measurements for values from (30) to (30);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::invalid_object_definition(
+            "partition \"middle\" would overlap partition \"low\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_object_definition(
+            "empty range bound specified for partition \"empty\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,253 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_items(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.items (id smallint, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'one'), (2, 'two'), (3, 'three');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn items(rows: Vec<(&str, &str)>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("name".to_owned(), PostgreSqlType::VarChar),
+        ],
+        rows.into_iter()
+            .map(|(id, name)| vec![id.to_owned(), name.to_owned()])
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn statements_that_differ_by_literals(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    for query in &[
+        "select * from schema_name.items where id = 1;",
+        "select * from schema_name.items where id = 2;",
+        "SELECT *   FROM schema_name.items WHERE id = 1",
+        "select * from schema_name.items where name = 'three';",
+    ] {
+        engine.execute(query).expect("no system errors");
+    }
+
+    let mut expected = prelude();
+    for rows in [
+        vec![("1", "one")],
+        vec![("2", "two")],
+        vec![("1", "one")],
+        vec![("3", "three")],
+    ] {
+        expected.extend(items(rows));
+        expected.push(Ok(QueryEvent::QueryComplete));
+    }
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn statements_that_differ_by_literals_share_plan(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("select * from schema_name.items where id = 1;")
+        .expect("no system errors");
+    let hits = engine.plan_cache.hits();
+    engine
+        .execute("select * from schema_name.items where id = 2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 3;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 100000;")
+        .expect("no system errors");
+    assert_eq!(engine.plan_cache.hits(), hits + 3);
+
+    engine
+        .execute("insert into schema_name.items values (4, 'four');")
+        .expect("no system errors");
+    let hits = engine.plan_cache.hits();
+    engine
+        .execute("insert into schema_name.items values (5, 'five');")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id >= 4;")
+        .expect("no system errors");
+    assert_eq!(engine.plan_cache.hits(), hits + 1);
+
+    let mut expected = prelude();
+    for rows in [vec![("1", "one")], vec![("2", "two")], vec![("3", "three")], vec![]] {
+        expected.extend(items(rows));
+        expected.push(Ok(QueryEvent::QueryComplete));
+    }
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(items(vec![("4", "four"), ("5", "five")]));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn statements_are_planned_again_when_table_is_recreated(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("select * from schema_name.items where id = 1;")
+        .expect("no system errors");
+    engine
+        .execute("drop table schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.items (id smallint, name varchar(10), price integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'one', 10);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 1;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(items(vec![("1", "one")]));
+    expected.extend(vec![
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableDropped),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("name".to_owned(), PostgreSqlType::VarChar),
+            ("price".to_owned(), PostgreSqlType::Integer),
+        ],
+        vec![vec!["1".to_owned(), "one".to_owned(), "10".to_owned()]],
+    ));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn statements_are_planned_again_when_table_is_analyzed(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("select * from schema_name.items where id = 1;")
+        .expect("no system errors");
+    let hits = engine.plan_cache.hits();
+    engine.execute("analyze schema_name.items;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 2;")
+        .expect("no system errors");
+    assert_eq!(engine.plan_cache.hits(), hits);
+
+    engine
+        .execute("create statistics schema_name.id_name on id, name from schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 3;")
+        .expect("no system errors");
+    assert_eq!(engine.plan_cache.hits(), hits);
+
+    engine
+        .execute("select * from schema_name.items where id = 1;")
+        .expect("no system errors");
+    assert_eq!(engine.plan_cache.hits(), hits + 1);
+
+    let mut expected = prelude();
+    expected.extend(items(vec![("1", "one")]));
+    expected.extend(vec![
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TablesAnalyzed),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(items(vec![("2", "two")]));
+    expected.extend(vec![
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::StatisticsCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(items(vec![("3", "three")]));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    expected.extend(items(vec![("1", "one")]));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn privileges_are_checked_again_when_they_are_revoked(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine
+        .execute("grant usage on schema schema_name to some_role;")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 2;")
+        .expect("no system errors");
+    engine.execute("reset role;").expect("no system errors");
+    engine
+        .execute("revoke usage on schema schema_name from some_role;")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where id = 2;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::PrivilegesGranted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(items(vec![("2", "two")]));
+    expected.extend(vec![
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableReset),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::PrivilegesRevoked),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
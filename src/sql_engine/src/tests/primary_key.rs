@@ -0,0 +1,269 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn insert_duplicate_primary_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key, value smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 20);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("value".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned(), "10".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_duplicate_keys_in_the_same_statement(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (1);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn composite_primary_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (first smallint, second smallint, primary key (first, second));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 1);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 2);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 2);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_primary_key_to_duplicate(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set id = 3;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_primary_key_to_key_of_row_that_is_not_updated(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set id = 2 where id = 1;")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set id = 3 where id = 2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "id".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_primary_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint primary key);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set id = 2;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (2);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::unique_violation("table_name_pkey")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn multiple_primary_keys(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (first smallint primary key, second smallint primary key);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::multiple_primary_keys("table_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_range_of_primary_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (first smallint, second integer, primary key (first, second));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 5), (-2, 1), (2, 6), (0, 3), (1, 4), (-1, 2);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name where first between -1 and 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name where first > 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name where first < -5;")
+        .expect("no system errors");
+
+    let description = vec![
+        ("first".to_owned(), PostgreSqlType::SmallInt),
+        ("second".to_owned(), PostgreSqlType::Integer),
+    ];
+    let mut expected = vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(6)),
+        Ok(QueryEvent::QueryComplete),
+    ];
+    expected.extend(records_selected(
+        description.clone(),
+        vec![
+            vec!["-1".to_owned(), "2".to_owned()],
+            vec!["0".to_owned(), "3".to_owned()],
+            vec!["1".to_owned(), "4".to_owned()],
+            vec!["1".to_owned(), "5".to_owned()],
+        ],
+    ));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    expected.extend(records_selected(
+        description.clone(),
+        vec![vec!["2".to_owned(), "6".to_owned()]],
+    ));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    expected.extend(records_selected(description, vec![]));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    collector.assert_content_for_single_queries(expected);
+}
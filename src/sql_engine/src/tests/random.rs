@@ -0,0 +1,95 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn random_after_setseed(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select setseed(0.5);").expect("no system errors");
+    engine.execute("select random();").expect("no system errors");
+    engine.execute("select random();").expect("no system errors");
+    engine.execute("select setseed(0.5);").expect("no system errors");
+    engine.execute("select random();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "setseed".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "random".to_owned(),
+            PostgreSqlType::DoublePrecision,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["0.5309024233166101".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "random".to_owned(),
+            PostgreSqlType::DoublePrecision,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["0.9359355535857959".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "setseed".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "random".to_owned(),
+            PostgreSqlType::DoublePrecision,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["0.5309024233166101".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn random_with_session_seed(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_random_seed(42);
+    engine.execute("select random();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "random".to_owned(),
+            PostgreSqlType::DoublePrecision,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["0.7415648787718233".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn setseed_out_of_range(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select setseed(2);").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::numeric_value_out_of_range(
+            "setseed parameter 2 is out of allowed range [-1,1]",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
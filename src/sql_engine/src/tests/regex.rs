@@ -0,0 +1,170 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_words(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.words (id smallint, word varchar(20), pattern varchar(20), matched boolean);",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "insert into schema_name.words (id, word, pattern) \
+            values (1, 'apple', '^a'), (2, 'Banana', 'b(an)+a$'), (3, 'cherry', null), (4, null, 'x');",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn words(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("word".to_owned(), PostgreSqlType::VarChar),
+            ("pattern".to_owned(), PostgreSqlType::VarChar),
+            ("matched".to_owned(), PostgreSqlType::Bool),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn regex_operators_of_constants(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select 'foobar' ~ 'o+b' as matches, \
+                'FOO' ~* 'foo' as insensitive, \
+                'foo' !~ 'bar' as not_matches, \
+                'FOO' !~* 'foo' as not_insensitive, \
+                'abc' similar to 'a%' as similar, \
+                'abc' not similar to '(b|c)%' as not_similar, \
+                'abc' similar to 'a.c' as dot;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("matches".to_owned(), PostgreSqlType::Bool),
+            ("insensitive".to_owned(), PostgreSqlType::Bool),
+            ("not_matches".to_owned(), PostgreSqlType::Bool),
+            ("not_insensitive".to_owned(), PostgreSqlType::Bool),
+            ("similar".to_owned(), PostgreSqlType::Bool),
+            ("not_similar".to_owned(), PostgreSqlType::Bool),
+            ("dot".to_owned(), PostgreSqlType::Bool),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "t".to_owned(),
+            "t".to_owned(),
+            "t".to_owned(),
+            "f".to_owned(),
+            "t".to_owned(),
+            "t".to_owned(),
+            "f".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn patterns_of_each_row(with_words: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_words;
+    engine
+        .execute("update schema_name.words set matched = word ~* pattern;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.words order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            words(vec![
+                vec!["1", "apple", "^a", "t"],
+                vec!["2", "Banana", "b(an)+a$", "t"],
+                vec!["3", "cherry", "NULL", "NULL"],
+                vec!["4", "NULL", "x", "NULL"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn similar_to_pattern_matches_whole_string(with_words: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_words;
+    engine
+        .execute("update schema_name.words set matched = word similar to '%(an|pl)_';")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.words order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(4)), Ok(QueryEvent::QueryComplete)],
+            words(vec![
+                vec!["1", "apple", "^a", "t"],
+                vec!["2", "Banana", "b(an)+a$", "t"],
+                vec!["3", "cherry", "NULL", "f"],
+                vec!["4", "NULL", "x", "NULL"],
+            ]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_pattern(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select 'abc' ~ '(' as invalid;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_regular_expression("unclosed group")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
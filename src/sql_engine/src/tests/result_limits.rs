@@ -0,0 +1,149 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_items(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.items (id smallint, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'one'), (2, 'two'), (3, 'three');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn items(rows: Vec<(&str, &str)>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("name".to_owned(), PostgreSqlType::VarChar),
+        ],
+        rows.into_iter()
+            .map(|(id, name)| vec![id.to_owned(), name.to_owned()])
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn rows_after_row_limit_are_not_sent(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine.execute("set max_result_rows = 2;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.items order by id;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items order by id limit 2;")
+        .expect("no system errors");
+    engine
+        .execute("set max_result_rows to default;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::VariableSet), Ok(QueryEvent::QueryComplete)],
+            items(vec![("1", "one"), ("2", "two")]),
+            vec![
+                Ok(QueryEvent::Notice(
+                    "result is truncated to 2 rows by max_result_rows of 2".to_owned(),
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            items(vec![("1", "one"), ("2", "two")]),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::VariableSet),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            items(vec![("1", "one"), ("2", "two"), ("3", "three")]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn rows_after_size_limit_are_not_sent(with_items: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_items;
+    engine.execute("set max_result_size = 8;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.items order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::VariableSet), Ok(QueryEvent::QueryComplete)],
+            items(vec![("1", "one"), ("2", "two")]),
+            vec![
+                Ok(QueryEvent::Notice(
+                    "result is truncated to 2 rows by max_result_size of 8 bytes".to_owned(),
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_result_limits(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("set max_result_rows = 'many';")
+        .expect("no system errors");
+    engine
+        .execute("set max_result_rows = '1MB';")
+        .expect("no system errors");
+    engine
+        .execute("set max_result_size = '1MB';")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "invalid value for parameter \"max_result_rows\": \"many\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "invalid value for parameter \"max_result_rows\": \"1MB\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -62,6 +62,21 @@ fn drop_non_existent_schema(sql_engine: (QueryExecutor, ResultCollector)) {
     ]);
 }
 
+#[rstest::rstest]
+fn drop_non_existent_schema_if_exists(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+
+    engine
+        .execute("drop schema if exists non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Skipped(QueryError::schema_does_not_exist("non_existent"))),
+        Ok(QueryEvent::SchemaDropped),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
 #[rstest::rstest]
 fn select_from_nonexistent_schema(sql_engine: (QueryExecutor, ResultCollector)) {
     let (mut engine, collector) = sql_engine;
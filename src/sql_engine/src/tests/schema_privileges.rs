@@ -0,0 +1,231 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::rstest]
+fn create_table_without_create_privilege(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn create_table_with_granted_create_privilege(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("grant usage, create on schema schema_name to some_role;")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::PrivilegesGranted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_from_table_without_usage_privilege(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn revoked_usage_privilege(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("grant usage on schema schema_name to some_role;")
+        .expect("no system errors");
+    engine
+        .execute("revoke usage on schema schema_name from some_role;")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::PrivilegesGranted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::PrivilegesRevoked),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn schema_owner_has_all_privileges(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("alter schema schema_name owner to some_role;")
+        .expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SchemaAltered),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "column_test".to_owned(),
+            PostgreSqlType::SmallInt,
+        )])),
+        Ok(QueryEvent::RecordsSelected(0)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn only_owner_can_alter_schema(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("alter schema schema_name owner to some_role;")
+        .expect("no system errors");
+    engine
+        .execute("grant usage on schema schema_name to some_role;")
+        .expect("no system errors");
+    engine.execute("reset role;").expect("no system errors");
+    engine
+        .execute("alter schema schema_name owner to some_role;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::must_be_owner_of_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::must_be_owner_of_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableReset),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SchemaAltered),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn session_user_owns_created_schema_and_role_is_reset_to_it(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("create schema other_schema;").expect("no system errors");
+    engine.set_session_user("session_user");
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine.execute("set role some_role;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine.execute("reset role;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("create table other_schema.table_name (column_test smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("schema_name")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VariableReset),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied_for_schema("other_schema")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn alter_owner_of_non_existent_schema(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("alter schema non_existent owner to some_role;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::schema_does_not_exist("non_existent")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
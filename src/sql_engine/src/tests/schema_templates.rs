@@ -0,0 +1,130 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_template(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.accounts (id serial, balance smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.accounts (balance) values (10), (20);")
+        .expect("no system errors");
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn accounts(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::Integer),
+            ("balance".to_owned(), PostgreSqlType::SmallInt),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn tables_are_copied_without_data(with_template: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_template;
+    engine
+        .execute("create schema tenant like schema_name;")
+        .expect("no system errors");
+    engine
+        .execute("insert into tenant.accounts (balance) values (30);")
+        .expect("no system errors");
+    engine
+        .execute("select * from tenant.accounts;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            accounts(vec![vec!["1", "30"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn tables_are_copied_with_data(with_template: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_template;
+    engine
+        .execute("create schema tenant like schema_name with data;")
+        .expect("no system errors");
+    engine
+        .execute("insert into tenant.accounts (balance) values (30);")
+        .expect("no system errors");
+    engine
+        .execute("select * from tenant.accounts;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.accounts;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            accounts(vec![vec!["1", "10"], vec!["2", "20"], vec!["3", "30"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+            accounts(vec![vec!["1", "10"], vec!["2", "20"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn template_does_not_exist(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("create schema tenant like template;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::schema_does_not_exist("template")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -66,14 +66,17 @@ fn select_all_from_table_with_multiple_columns(sql_engine_with_schema: (QueryExe
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-            ],
-            vec![vec!["123".to_owned(), "456".to_owned(), "789".to_owned()]],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_1".to_owned(), PostgreSqlType::SmallInt),
+            ("column_2".to_owned(), PostgreSqlType::SmallInt),
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "123".to_owned(),
+            "456".to_owned(),
+            "789".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -98,17 +101,14 @@ fn select_not_all_columns(sql_engine_with_schema: (QueryExecutor, ResultCollecto
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(3)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-            ],
-            vec![
-                vec!["7".to_owned(), "4".to_owned()],
-                vec!["8".to_owned(), "5".to_owned()],
-                vec!["9".to_owned(), "6".to_owned()],
-            ],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+            ("column_2".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["7".to_owned(), "4".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["8".to_owned(), "5".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["9".to_owned(), "6".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -167,17 +167,14 @@ fn select_first_and_last_columns_from_table_with_multiple_columns(
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-            ],
-            vec![
-                vec!["3".to_owned(), "1".to_owned()],
-                vec!["6".to_owned(), "4".to_owned()],
-                vec!["9".to_owned(), "7".to_owned()],
-            ],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+            ("column_1".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned(), "1".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["6".to_owned(), "4".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["9".to_owned(), "7".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -216,18 +213,27 @@ fn select_all_columns_reordered_from_table_with_multiple_columns(
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-            ],
-            vec![
-                vec!["3".to_owned(), "1".to_owned(), "2".to_owned()],
-                vec!["6".to_owned(), "4".to_owned(), "5".to_owned()],
-                vec!["9".to_owned(), "7".to_owned(), "8".to_owned()],
-            ],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+            ("column_1".to_owned(), PostgreSqlType::SmallInt),
+            ("column_2".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "3".to_owned(),
+            "1".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "6".to_owned(),
+            "4".to_owned(),
+            "5".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "9".to_owned(),
+            "7".to_owned(),
+            "8".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(3)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -263,38 +269,35 @@ fn select_with_column_name_duplication(sql_engine_with_schema: (QueryExecutor, R
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-            ],
-            vec![
-                vec![
-                    "3".to_owned(),
-                    "2".to_owned(),
-                    "1".to_owned(),
-                    "3".to_owned(),
-                    "2".to_owned(),
-                ],
-                vec![
-                    "6".to_owned(),
-                    "5".to_owned(),
-                    "4".to_owned(),
-                    "6".to_owned(),
-                    "5".to_owned(),
-                ],
-                vec![
-                    "9".to_owned(),
-                    "8".to_owned(),
-                    "7".to_owned(),
-                    "9".to_owned(),
-                    "8".to_owned(),
-                ],
-            ],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+            ("column_2".to_owned(), PostgreSqlType::SmallInt),
+            ("column_1".to_owned(), PostgreSqlType::SmallInt),
+            ("column_3".to_owned(), PostgreSqlType::SmallInt),
+            ("column_2".to_owned(), PostgreSqlType::SmallInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "3".to_owned(),
+            "2".to_owned(),
+            "1".to_owned(),
+            "3".to_owned(),
+            "2".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "6".to_owned(),
+            "5".to_owned(),
+            "4".to_owned(),
+            "6".to_owned(),
+            "5".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "9".to_owned(),
+            "8".to_owned(),
+            "7".to_owned(),
+            "9".to_owned(),
+            "8".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(3)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -332,18 +335,27 @@ fn select_different_integer_types(sql_engine_with_schema: (QueryExecutor, Result
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
-            vec![
-                ("column_si".to_owned(), PostgreSqlType::SmallInt),
-                ("column_i".to_owned(), PostgreSqlType::Integer),
-                ("column_bi".to_owned(), PostgreSqlType::BigInt),
-            ],
-            vec![
-                vec!["1000".to_owned(), "2000000".to_owned(), "3000000000".to_owned()],
-                vec!["4000".to_owned(), "5000000".to_owned(), "6000000000".to_owned()],
-                vec!["7000".to_owned(), "8000000".to_owned(), "9000000000".to_owned()],
-            ],
-        ))),
+        Ok(QueryEvent::RowDescription(vec![
+            ("column_si".to_owned(), PostgreSqlType::SmallInt),
+            ("column_i".to_owned(), PostgreSqlType::Integer),
+            ("column_bi".to_owned(), PostgreSqlType::BigInt),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1000".to_owned(),
+            "2000000".to_owned(),
+            "3000000000".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "4000".to_owned(),
+            "5000000".to_owned(),
+            "6000000000".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "7000".to_owned(),
+            "8000000".to_owned(),
+            "9000000000".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(3)),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -381,17 +393,381 @@ fn select_different_character_strings_types(sql_engine_with_schema: (QueryExecut
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
-        Ok(QueryEvent::RecordsSelected((
+        Ok(QueryEvent::RowDescription(vec![
+            ("char_10".to_owned(), PostgreSqlType::Char),
+            ("var_char_20".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "1234567890".to_owned(),
+            "12345678901234567890".to_owned(),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["12345".to_owned(), "1234567890".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["12345".to_owned(), "1234567890".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod order_and_limit {
+    use super::*;
+
+    fn scores(rows: Vec<(&str, &str)>) -> Vec<QueryResult> {
+        records_selected(
             vec![
-                ("char_10".to_owned(), PostgreSqlType::Char),
-                ("var_char_20".to_owned(), PostgreSqlType::VarChar),
+                ("player".to_owned(), PostgreSqlType::SmallInt),
+                ("score".to_owned(), PostgreSqlType::SmallInt),
             ],
+            rows.into_iter()
+                .map(|(player, score)| vec![player.to_owned(), score.to_owned()])
+                .collect(),
+        )
+    }
+
+    #[rstest::fixture]
+    pub(super) fn leaderboard(
+        sql_engine_with_schema: (QueryExecutor, ResultCollector),
+    ) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.leaderboard (player smallint, score smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.leaderboard values (1, 50), (2, 90), (3, 70), (4, 90), (5, 70), (6, 10);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    pub(super) fn prelude() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(6)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    #[rstest::rstest]
+    fn order_by_with_limit_and_offset(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard order by score, player desc limit 3 offset 1;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("1", "50"), ("5", "70"), ("3", "70")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn limit_and_offset_of_filtered_rows(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard where score >= 50 limit 2 offset 1;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("2", "90"), ("3", "70")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn limit_zero_of_rows_that_are_not_sorted(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard limit 0;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend([scores(vec![]), vec![Ok(QueryEvent::QueryComplete)]].concat());
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn rows_that_are_not_sorted_are_sent_before_failed_row(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard where 100 / (score - 10) > 0;")
+            .expect("no system errors");
+
+        let mut sent = scores(vec![("1", "50"), ("2", "90"), ("3", "70"), ("4", "90"), ("5", "70")]);
+        sent.pop();
+        let mut expected = prelude();
+        expected.extend(
+            [
+                sent,
+                vec![Err(QueryError::division_by_zero()), Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn disjunctions_filter_scanned_rows(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute(
+                "select * from schema_name.leaderboard where player <> 2 and (score > 80 or player between 5 and 6);",
+            )
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("4", "90"), ("5", "70"), ("6", "10")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn filtered_and_sorted_by_columns_that_are_not_selected(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select score from schema_name.leaderboard where player > 2 order by player desc;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                records_selected(
+                    vec![("score".to_owned(), PostgreSqlType::SmallInt)],
+                    vec![
+                        vec!["10".to_owned()],
+                        vec!["70".to_owned()],
+                        vec!["90".to_owned()],
+                        vec!["70".to_owned()],
+                    ],
+                ),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn constant_false_where_clause(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard where 1 + 1 = 3;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend([scores(vec![]), vec![Ok(QueryEvent::QueryComplete)]].concat());
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn boolean_logic_with_constant_operands(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute(
+                "select * from schema_name.leaderboard where player <> 1 and (score > 60 or player = 6 and false);",
+            )
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("2", "90"), ("3", "70"), ("4", "90"), ("5", "70")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn non_boolean_where_clause(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard where score + 1;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(vec![
+            Err(QueryError::datatype_mismatch(
+                "argument of WHERE must be type boolean, not type NUMBER",
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn fetch_first_rows_only(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard order by score desc fetch first 3 rows only;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("2", "90"), ("4", "90"), ("3", "70")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn fetch_first_rows_with_ties(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard order by score desc fetch first 3 rows with ties;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                scores(vec![("2", "90"), ("4", "90"), ("3", "70"), ("5", "70")]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn with_ties_without_order_by(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard fetch first 3 rows with ties;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(vec![
+            Err(QueryError::syntax_error(
+                "WITH TIES cannot be specified without ORDER BY clause",
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn order_by_nonexistent_column(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select * from schema_name.leaderboard order by rank;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(vec![
+            Err(QueryError::column_does_not_exist("rank")),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+mod window_frames {
+    use super::{
+        order_and_limit::{leaderboard, prelude},
+        *,
+    };
+
+    fn totals(rows: Vec<(&str, &str)>) -> Vec<QueryResult> {
+        records_selected(
             vec![
-                vec!["1234567890".to_owned(), "12345678901234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
+                ("player".to_owned(), PostgreSqlType::SmallInt),
+                ("total".to_owned(), PostgreSqlType::BigInt),
             ],
-        ))),
-        Ok(QueryEvent::QueryComplete),
-    ]);
+            rows.into_iter()
+                .map(|(player, total)| vec![player.to_owned(), total.to_owned()])
+                .collect(),
+        )
+    }
+
+    #[rstest::rstest]
+    fn rows_between_preceding_and_current_row(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute(
+                "select player, sum(score) over (order by player rows between 1 preceding and current row) as total \
+                 from schema_name.leaderboard order by player;",
+            )
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                totals(vec![
+                    ("1", "50"),
+                    ("2", "140"),
+                    ("3", "160"),
+                    ("4", "160"),
+                    ("5", "160"),
+                    ("6", "80"),
+                ]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn default_range_frame_includes_peers(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute("select player, sum(score) over (order by score) as total from schema_name.leaderboard order by player;")
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(
+            [
+                totals(vec![
+                    ("1", "60"),
+                    ("2", "380"),
+                    ("3", "200"),
+                    ("4", "380"),
+                    ("5", "200"),
+                    ("6", "10"),
+                ]),
+                vec![Ok(QueryEvent::QueryComplete)],
+            ]
+            .concat(),
+        );
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn frame_start_unbounded_following(leaderboard: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = leaderboard;
+        engine
+            .execute(
+                "select player, sum(score) over (order by player rows between unbounded following and current row) \
+                 from schema_name.leaderboard;",
+            )
+            .expect("no system errors");
+
+        let mut expected = prelude();
+        expected.extend(vec![
+            Err(QueryError::windowing_error("frame start cannot be UNBOUNDED FOLLOWING")),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
 }
@@ -0,0 +1,279 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+fn selected(name: &str, value: &str) -> Vec<QueryResult> {
+    records_selected(
+        vec![(name.to_owned(), PostgreSqlType::BigInt)],
+        vec![vec![value.to_owned()]],
+    )
+}
+
+#[rstest::rstest]
+fn next_values_of_sequence(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create sequence schema_name.seq increment by 10 start with 5;")
+        .expect("no system errors");
+    engine
+        .execute("select nextval('schema_name.seq');")
+        .expect("no system errors");
+    engine
+        .execute("select nextval('schema_name.seq');")
+        .expect("no system errors");
+    engine
+        .execute("select currval('schema_name.seq');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::SequenceCreated),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected("nextval", "5"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("nextval", "15"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("currval", "15"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn currval_before_nextval(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create sequence schema_name.seq;")
+        .expect("no system errors");
+    engine
+        .execute("select currval('schema_name.seq');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SequenceCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::object_not_in_prerequisite_state(
+            "currval of sequence \"seq\" is not yet defined in this session",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn setval_and_reach_max_value(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create sequence schema_name.seq maxvalue 10;")
+        .expect("no system errors");
+    engine
+        .execute("select setval('schema_name.seq', 9);")
+        .expect("no system errors");
+    engine
+        .execute("select nextval('schema_name.seq');")
+        .expect("no system errors");
+    engine
+        .execute("select nextval('schema_name.seq');")
+        .expect("no system errors");
+    engine
+        .execute("select setval('schema_name.seq', 11);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::SequenceCreated),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected("setval", "9"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("nextval", "10"),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::sequence_limit_exceeded(
+                    "nextval: reached maximum value of sequence \"seq\" (10)",
+                )),
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::numeric_value_out_of_range(
+                    "setval: value 11 is out of bounds for sequence \"seq\" (1..10)",
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn create_sequence_with_invalid_options(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create sequence schema_name.seq increment 0;")
+        .expect("no system errors");
+    engine
+        .execute("create sequence schema_name.seq minvalue 10 maxvalue 1;")
+        .expect("no system errors");
+    engine
+        .execute("create sequence schema_name.seq start -1;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value("INCREMENT must not be zero")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "MINVALUE (10) must be less than MAXVALUE (1)",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "START value (-1) cannot be less than MINVALUE (1)",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn drop_sequence(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create sequence schema_name.seq;")
+        .expect("no system errors");
+    engine
+        .execute("create sequence if not exists schema_name.seq;")
+        .expect("no system errors");
+    engine
+        .execute("drop sequence schema_name.seq;")
+        .expect("no system errors");
+    engine
+        .execute("drop sequence if exists schema_name.seq;")
+        .expect("no system errors");
+    engine
+        .execute("select nextval('schema_name.seq');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SequenceCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::sequence_already_exists("seq"))),
+        Ok(QueryEvent::SequenceCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SequenceDropped),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::sequence_does_not_exist(
+            "schema_name.seq",
+        ))),
+        Ok(QueryEvent::SequenceDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::sequence_does_not_exist("schema_name.seq")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_into_serial_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id serial, name smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (name) values (10), (20);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (100, 30);")
+        .expect("no system errors");
+    engine
+        .execute("select currval('schema_name.table_name_id_seq');")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected("currval", "2"),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RowDescription(vec![
+                    ("id".to_owned(), PostgreSqlType::Integer),
+                    ("name".to_owned(), PostgreSqlType::SmallInt),
+                ])),
+                Ok(QueryEvent::DataRow(vec!["1".to_owned(), "10".to_owned()])),
+                Ok(QueryEvent::DataRow(vec!["2".to_owned(), "20".to_owned()])),
+                Ok(QueryEvent::DataRow(vec!["100".to_owned(), "30".to_owned()])),
+                Ok(QueryEvent::RecordsSelected(3)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn drop_sequence_of_serial_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id serial, name smallint);")
+        .expect("no system errors");
+    engine
+        .execute("drop sequence schema_name.table_name_id_seq;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (name) values (10);")
+        .expect("no system errors");
+    engine
+        .execute("drop table schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("drop sequence schema_name.table_name_id_seq;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::dependent_objects_still_exist("sequence table_name_id_seq")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::sequence_does_not_exist("schema_name.table_name_id_seq")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
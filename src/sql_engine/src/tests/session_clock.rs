@@ -0,0 +1,105 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{clock::FrozenClock, pgsql_types::PostgreSqlType};
+
+use super::*;
+
+#[rstest::rstest]
+fn now_is_read_from_session_clock(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(FrozenClock::at_unix_seconds(946_684_800)));
+    engine.execute("select now();").expect("no system errors");
+    engine
+        .execute("select current_timestamp as moment;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "now".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["2000-01-01 00:00:00+00".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "moment".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["2000-01-01 00:00:00+00".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn session_clock_in_time_zone(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(
+        FrozenClock::at_unix_seconds(946_684_800).with_utc_offset(2 * 60 * 60),
+    ));
+    engine.execute("select now();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RowDescription(vec![(
+            "now".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["2000-01-01 02:00:00+02".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_now(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.set_clock(Arc::new(FrozenClock::at_unix_seconds(0)));
+    engine
+        .execute("create table schema_name.table_name (created_at varchar(30));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (now());")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "created_at".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["1970-01-01 00:00:00+00".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn unknown_function(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select no_such_function();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::function_does_not_exist("no_such_function()")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,263 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_add(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create function schema_name.add(a int, b int) returns int as 'select a + b' language sql;")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn selected(name: &str, pg_type: PostgreSqlType, value: &str) -> Vec<QueryResult> {
+    records_selected(vec![(name.to_owned(), pg_type)], vec![vec![value.to_owned()]])
+}
+
+#[rstest::rstest]
+fn call_of_constants(with_add: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_add;
+    engine
+        .execute("select schema_name.add(1, 2) as total;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            selected("total", PostgreSqlType::Integer, "3"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn call_of_columns(with_add: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_add;
+    engine
+        .execute("create table schema_name.numbers (id smallint, value integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.numbers values (1, 10), (2, 20), (3, null);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.numbers set value = schema_name.add(value, id);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.numbers order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("value".to_owned(), PostgreSqlType::Integer),
+        ])),
+        Ok(QueryEvent::DataRow(vec!["1".to_owned(), "11".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["2".to_owned(), "22".to_owned()])),
+        Ok(QueryEvent::DataRow(vec!["3".to_owned(), "NULL".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_calls_function(with_add: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_add;
+    engine
+        .execute(
+            "create function schema_name.greet(name text) returns text \
+            language sql as 'select ''hello '' || name';",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.twice(a int) returns bigint \
+            as 'select schema_name.add(a, a)' language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.greet('world') as greeting;")
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.twice(21) as answer;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Ok(QueryEvent::FunctionCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::FunctionCreated),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected("greeting", PostgreSqlType::VarChar, "hello world"),
+            vec![Ok(QueryEvent::QueryComplete)],
+            selected("answer", PostgreSqlType::BigInt, "42"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn replace_function(with_add: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_add;
+    engine
+        .execute("create function schema_name.add(a int, b int) returns int as 'select a * b' language sql;")
+        .expect("no system errors");
+    engine
+        .execute("create or replace function schema_name.add(a int, b int) returns int as 'select a * b' language sql;")
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.add(3, 4) as product;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![
+                Err(QueryError::function_already_exists("schema_name.add")),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::FunctionCreated),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected("product", PostgreSqlType::Integer, "12"),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn drop_function(with_add: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_add;
+    engine
+        .execute("drop function schema_name.add(int, int);")
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.add(1, 2) as total;")
+        .expect("no system errors");
+    engine
+        .execute("drop function schema_name.add;")
+        .expect("no system errors");
+    engine
+        .execute("drop function if exists schema_name.add;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::FunctionDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("schema_name.add(1, 2)")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("schema_name.add")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::function_does_not_exist(
+            "schema_name.add",
+        ))),
+        Ok(QueryEvent::FunctionDropped),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_function_bodies(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create function schema_name.flag() returns boolean as 'select 1' language sql;")
+        .expect("no system errors");
+    engine
+        .execute("create function schema_name.first() returns int as 'select 1, 2' language sql;")
+        .expect("no system errors");
+    engine
+        .execute("create function schema_name.wrong(a int) returns int as 'select b' language sql;")
+        .expect("no system errors");
+    engine
+        .execute("create function schema_name.twice(a int, a int) returns int as 'select a' language sql;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_function_definition(
+            "return type mismatch in function declared to return bool",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_function_definition(
+            "function body has to be a single SELECT of one expression",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::undefined_column("b")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_function_definition(
+            "parameter name \"a\" used more than once",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn recursive_function(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create function schema_name.forever() returns int as 'select 1' language sql;")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create or replace function schema_name.forever() returns int \
+            as 'select schema_name.forever()' language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.forever() as never;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::stack_depth_limit_exceeded()),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,127 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::fixture]
+fn with_addresses(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.addresses (city varchar(20), zip integer, street varchar(20));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.addresses values ('Kyiv', 1, 'a'), ('Lviv', 2, 'b');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn create_analyze_and_drop_statistics(with_addresses: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_addresses;
+    engine
+        .execute(
+            "create statistics schema_name.city_zip (dependencies, ndistinct) on city, zip from schema_name.addresses;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("create statistics schema_name.city_zip on city, zip from schema_name.addresses;")
+        .expect("no system errors");
+    engine
+        .execute("create statistics if not exists schema_name.city_zip on city, zip from schema_name.addresses;")
+        .expect("no system errors");
+    engine
+        .execute("analyze schema_name.addresses;")
+        .expect("no system errors");
+    engine.execute("analyze;").expect("no system errors");
+    engine
+        .execute("drop statistics schema_name.city_zip;")
+        .expect("no system errors");
+    engine
+        .execute("drop statistics schema_name.city_zip;")
+        .expect("no system errors");
+    engine
+        .execute("drop statistics if exists schema_name.city_zip;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::StatisticsCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::statistics_already_exists("city_zip")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::statistics_already_exists("city_zip"))),
+        Ok(QueryEvent::StatisticsCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TablesAnalyzed),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TablesAnalyzed),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::StatisticsDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::statistics_does_not_exist("schema_name.city_zip")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::statistics_does_not_exist(
+            "schema_name.city_zip",
+        ))),
+        Ok(QueryEvent::StatisticsDropped),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn statistics_on_invalid_columns(with_addresses: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_addresses;
+    engine
+        .execute("create statistics schema_name.single on city from schema_name.addresses;")
+        .expect("no system errors");
+    engine
+        .execute("create statistics schema_name.twice on city, city from schema_name.addresses;")
+        .expect("no system errors");
+    engine
+        .execute("create statistics schema_name.missing on city, country from schema_name.addresses;")
+        .expect("no system errors");
+    engine
+        .execute("create statistics schema_name.missing on city, zip from schema_name.non_existent;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::invalid_object_definition(
+            "extended statistics require at least 2 columns",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_object_definition(
+            "duplicate column name in statistics definition",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::column_does_not_exist("country")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
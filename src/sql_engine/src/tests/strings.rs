@@ -0,0 +1,228 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_words(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.words (id smallint, word varchar(20), result varchar(20));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.words (id, word) values (1, '  Hello  '), (2, null);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn words(rows: Vec<Vec<&str>>) -> Vec<QueryResult> {
+    records_selected(
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt),
+            ("word".to_owned(), PostgreSqlType::VarChar),
+            ("result".to_owned(), PostgreSqlType::VarChar),
+        ],
+        rows.into_iter()
+            .map(|row| row.into_iter().map(ToOwned::to_owned).collect())
+            .collect(),
+    )
+}
+
+#[rstest::rstest]
+fn string_functions_of_constants(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select length('héllo') as length, \
+                upper('abc') as upper, \
+                lower('ABC') as lower, \
+                substring('hello' from 2 for 3) as substring, \
+                substring('hello', 0, 3) as from_zero, \
+                trim(both 'x' from 'xxhixx') as trim, \
+                ltrim('  a ') as ltrim, \
+                rtrim(' a  ') as rtrim, \
+                replace('abcabc', 'b', 'X') as replace, \
+                position('l' in 'hello') as position, \
+                concat('a', null, 1, true) as concat, \
+                'a' || 'b' as concatenated;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("length".to_owned(), PostgreSqlType::Integer),
+            ("upper".to_owned(), PostgreSqlType::VarChar),
+            ("lower".to_owned(), PostgreSqlType::VarChar),
+            ("substring".to_owned(), PostgreSqlType::VarChar),
+            ("from_zero".to_owned(), PostgreSqlType::VarChar),
+            ("trim".to_owned(), PostgreSqlType::VarChar),
+            ("ltrim".to_owned(), PostgreSqlType::VarChar),
+            ("rtrim".to_owned(), PostgreSqlType::VarChar),
+            ("replace".to_owned(), PostgreSqlType::VarChar),
+            ("position".to_owned(), PostgreSqlType::Integer),
+            ("concat".to_owned(), PostgreSqlType::VarChar),
+            ("concatenated".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "5".to_owned(),
+            "ABC".to_owned(),
+            "abc".to_owned(),
+            "ell".to_owned(),
+            "he".to_owned(),
+            "hi".to_owned(),
+            "a ".to_owned(),
+            " a".to_owned(),
+            "aXcaXc".to_owned(),
+            "3".to_owned(),
+            "a1t".to_owned(),
+            "ab".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn null_arguments(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "select length(null) as length, \
+                substring('hello', null) as substring, \
+                concat(null, null) as concat, \
+                'a' || null as concatenated;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("length".to_owned(), PostgreSqlType::Integer),
+            ("substring".to_owned(), PostgreSqlType::VarChar),
+            ("concat".to_owned(), PostgreSqlType::VarChar),
+            ("concatenated".to_owned(), PostgreSqlType::VarChar),
+        ])),
+        Ok(QueryEvent::DataRow(vec![
+            "NULL".to_owned(),
+            "NULL".to_owned(),
+            "".to_owned(),
+            "NULL".to_owned(),
+        ])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn string_functions_of_columns(with_words: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_words;
+    engine
+        .execute("update schema_name.words set result = upper(trim(word)) || '!';")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.words order by id;")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.words set result = concat(id, ':', lower(word));")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.words order by id;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            vec![Ok(QueryEvent::RecordsUpdated(2)), Ok(QueryEvent::QueryComplete)],
+            words(vec![vec!["1", "  Hello", "HELLO!"], vec!["2", "NULL", "NULL"]]),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsUpdated(2)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            words(vec![vec!["1", "  Hello", "1:  hello"], vec!["2", "NULL", "2:"]]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn select_string_functions_of_columns(with_words: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_words;
+    engine
+        .execute(
+            "select id, upper(word) as upper, length(trim(word)) as length, word || '!' as exclaimed \
+            from schema_name.words order by id;",
+        )
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(
+        [
+            records_selected(
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt),
+                    ("upper".to_owned(), PostgreSqlType::VarChar),
+                    ("length".to_owned(), PostgreSqlType::Integer),
+                    ("exclaimed".to_owned(), PostgreSqlType::VarChar),
+                ],
+                vec![
+                    vec![
+                        "1".to_owned(),
+                        "  HELLO".to_owned(),
+                        "5".to_owned(),
+                        "  Hello!".to_owned(),
+                    ],
+                    vec!["2".to_owned(), "NULL".to_owned(), "NULL".to_owned(), "NULL".to_owned()],
+                ],
+            ),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn negative_substring_length(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("select substring('hello' from 1 for -1) as substring;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::substring_error("negative substring length not allowed")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -82,6 +82,29 @@ fn create_same_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
     ]);
 }
 
+#[rstest::rstest]
+fn create_same_table_if_not_exists(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_name smallint);")
+        .expect("no system errors");
+    engine
+        .execute("create table if not exists schema_name.table_name (column_name smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::table_already_exists(
+            "schema_name.table_name",
+        ))),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
 #[rstest::rstest]
 fn drop_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
     let (mut engine, collector) = sql_engine_with_schema;
@@ -122,6 +145,24 @@ fn drop_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollect
     ]);
 }
 
+#[rstest::rstest]
+fn drop_non_existent_table_if_exists(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("drop table if exists schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Skipped(QueryError::table_does_not_exist(
+            "schema_name.table_name",
+        ))),
+        Ok(QueryEvent::TableDropped),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
 #[cfg(test)]
 mod different_types {
     use super::*;
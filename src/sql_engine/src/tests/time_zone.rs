@@ -0,0 +1,200 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{clock::FrozenClock, pgsql_types::PostgreSqlType};
+
+use super::*;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (moment timestamp with time zone);")
+        .expect("no system errors");
+    (engine, collector)
+}
+
+fn selected(moments: Vec<&str>) -> Vec<QueryResult> {
+    records_selected(
+        vec![("moment".to_owned(), PostgreSqlType::TimestampWithTimeZone)],
+        moments.into_iter().map(|moment| vec![moment.to_owned()]).collect(),
+    )
+}
+
+#[rstest::rstest]
+fn timestamps_are_rendered_in_utc_by_default(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values ('2020-08-01 12:30:00+03'), (null);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(vec!["2020-08-01 09:30:00+00", "NULL"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn timestamps_are_rendered_in_session_time_zone(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values ('2020-08-01 12:30:00+03');")
+        .expect("no system errors");
+    engine.execute("set time zone -5;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("set timezone = 'UTC';").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::VariableSet),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(vec!["2020-08-01 04:30:00-05"]),
+            vec![
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::VariableSet),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(vec!["2020-08-01 09:30:00+00"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn timestamps_without_offset_are_in_session_time_zone(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("set time zone 3;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('2020-08-01 12:30:00');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set moment = '2020-08-01 13:30:00';")
+        .expect("no system errors");
+    engine.execute("set time zone local;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::VariableSet),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsUpdated(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::VariableSet),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(vec!["2020-08-01 10:30:00+00"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn now_is_rendered_in_session_time_zone(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.set_clock(Arc::new(FrozenClock::at_unix_seconds(946_684_800)));
+    engine.execute("set time zone 5.5;").expect("no system errors");
+    engine.execute("select now();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![(
+            "now".to_owned(),
+            PostgreSqlType::VarChar,
+        )])),
+        Ok(QueryEvent::DataRow(vec!["2000-01-01 05:30:00+05:30".to_owned()])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn invalid_time_zone(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("set time zone 'Mars/Olympus';")
+        .expect("no system errors");
+    engine.execute("set time zone 20;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_parameter_value(
+            "invalid value for parameter \"timezone\": \"Mars/Olympus\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "invalid value for parameter \"timezone\": \"20\"",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn malformed_timestamp(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values ('2020-02-30 00:00:00');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            "2020-02-30 00:00:00",
+            PostgreSqlType::TimestampWithTimeZone,
+            "moment",
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
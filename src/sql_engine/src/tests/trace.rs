@@ -0,0 +1,105 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+fn notices(collector: &Collector) -> Vec<String> {
+    collector
+        .0
+        .lock()
+        .expect("locked")
+        .iter()
+        .filter_map(|result| match result {
+            Ok(QueryEvent::Notice(message)) => Some(message.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[rstest::rstest]
+fn no_timings_are_reported_by_default(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint);")
+        .expect("no system errors");
+
+    assert_eq!(notices(&collector), Vec::<String>::new());
+}
+
+#[rstest::rstest]
+fn statements_report_stage_timings_in_trace_mode(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set trace = on;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let notices = notices(&collector);
+    assert_eq!(notices.len(), 3);
+    for notice in notices {
+        let stages = notice
+            .split(", ")
+            .map(|stage| stage.split(':').next().expect("stage name"))
+            .collect::<Vec<&str>>();
+        assert_eq!(stages, vec!["parse", "plan", "execute", "serialize"]);
+    }
+}
+
+#[rstest::rstest]
+fn notice_is_sent_before_query_completes(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set trace = on;").expect("no system errors");
+
+    let results = collector.0.lock().expect("locked");
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::Notice(_)),
+            Ok(QueryEvent::QueryComplete)
+        ]
+    ));
+}
+
+#[rstest::rstest]
+fn trace_mode_is_turned_off(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set trace = on;").expect("no system errors");
+    engine.execute("set trace to off;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint);")
+        .expect("no system errors");
+
+    assert_eq!(notices(&collector).len(), 1);
+}
+
+#[rstest::rstest]
+fn trace_requires_boolean_value(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("set trace = 'sometimes';").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "parameter \"trace\" requires a Boolean value",
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
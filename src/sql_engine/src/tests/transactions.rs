@@ -0,0 +1,600 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use crate::activity::SessionRegistry;
+
+use super::{activity::session, *};
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn selected(values: &[&str]) -> Vec<QueryResult> {
+    records_selected(
+        vec![("col".to_owned(), PostgreSqlType::SmallInt)],
+        values.iter().map(|value| vec![value.to_string()]).collect(),
+    )
+}
+
+#[rstest::rstest]
+fn committed_writes_are_kept(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set col = 10;")
+        .expect("no system errors");
+    engine.execute("commit;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsUpdated(3)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TransactionCommitted),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["10", "10", "10"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn rolled_back_writes_are_discarded(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(
+            "start transaction; \
+            insert into schema_name.table_name values (3); \
+            update schema_name.table_name set col = 10; \
+            delete from schema_name.table_name;",
+        )
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::RecordsUpdated(3)),
+                Ok(QueryEvent::RecordsDeleted(3)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn failed_transaction_is_rolled_back_on_commit(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.non_existent values (4);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("commit;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Err(QueryError::in_failed_sql_transaction()),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn transaction_block_is_not_nested(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("begin;").expect("no system errors");
+    engine.execute("begin;").expect("no system errors");
+    engine.execute("commit;").expect("no system errors");
+    engine.execute("commit;").expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::Warning(QueryError::active_sql_transaction())),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TransactionCommitted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Warning(QueryError::no_active_sql_transaction())),
+        Ok(QueryEvent::TransactionCommitted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Warning(QueryError::no_active_sql_transaction())),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn notifications_are_sent_on_commit() {
+    let sessions = Arc::new(SessionRegistry::default());
+    let (mut listener, listener_collector) = session(&sessions);
+    let (mut notifier, _notifier_collector) = session(&sessions);
+
+    listener.execute("listen events;").expect("no system errors");
+    notifier
+        .execute("begin; notify events, 'rolled back'; rollback;")
+        .expect("no system errors");
+    notifier
+        .execute("begin; notify events, 'committed';")
+        .expect("no system errors");
+    listener.execute("listen events;").expect("no system errors");
+    notifier.execute("commit;").expect("no system errors");
+    listener.execute("unlisten *;").expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Listening),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Listening),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::Unlistening),
+        Ok(QueryEvent::Notification(
+            notifier.pid(),
+            "events".to_owned(),
+            "committed".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn writes_after_savepoint_are_rolled_back(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(
+            "begin; \
+            insert into schema_name.table_name values (3); \
+            savepoint first; \
+            insert into schema_name.table_name values (4); \
+            savepoint second; \
+            update schema_name.table_name set col = 10; \
+            rollback to savepoint first; \
+            insert into schema_name.table_name values (5); \
+            commit;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::SavepointCreated),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::SavepointCreated),
+                Ok(QueryEvent::RecordsUpdated(4)),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::TransactionCommitted),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2", "3", "5"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn rollback_to_savepoint_recovers_failed_transaction(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("begin; savepoint before_insert;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.non_existent values (3);")
+        .expect("no system errors");
+    engine
+        .execute("release savepoint before_insert;")
+        .expect("no system errors");
+    engine.execute("rollback to before_insert;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3); release before_insert; commit;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::SavepointCreated),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Err(QueryError::in_failed_sql_transaction()),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::SavepointReleased),
+                Ok(QueryEvent::TransactionCommitted),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2", "3"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn savepoints_outside_of_transaction_block(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("savepoint savepoint_name;").expect("no system errors");
+    engine
+        .execute("rollback to savepoint savepoint_name;")
+        .expect("no system errors");
+    engine
+        .execute("release savepoint savepoint_name;")
+        .expect("no system errors");
+    engine
+        .execute("begin; release savepoint savepoint_name;")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::no_transaction_block("SAVEPOINT")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::no_transaction_block("ROLLBACK TO SAVEPOINT")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::no_transaction_block("RELEASE SAVEPOINT")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Err(QueryError::savepoint_does_not_exist("savepoint_name")),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn isolation_level_of_transaction_block(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("begin isolation level read committed;")
+        .expect("no system errors");
+    engine
+        .execute("set transaction isolation level read committed;")
+        .expect("no system errors");
+    engine.execute("commit;").expect("no system errors");
+    engine
+        .execute("start transaction isolation level read uncommitted, read write;")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TransactionCommitted),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn unsupported_transaction_modes(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("set transaction isolation level read committed;")
+        .expect("no system errors");
+    engine
+        .execute("begin isolation level repeatable read;")
+        .expect("no system errors");
+    engine
+        .execute("begin isolation level serializable;")
+        .expect("no system errors");
+    engine.execute("begin read only;").expect("no system errors");
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("set transaction isolation level repeatable read;")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Warning(QueryError::no_transaction_block("SET TRANSACTION"))),
+        Ok(QueryEvent::VariableSet),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported(
+            "begin isolation level repeatable read;",
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported("begin isolation level serializable;")),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported("begin read only;")),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Err(QueryError::feature_not_supported(
+            "set transaction isolation level repeatable read;",
+        )),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn extended_query_in_failed_transaction(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .parse_prepared_statement("statement_name", "select * from schema_name.table_name;", &[])
+        .expect("no system errors");
+    engine.sync();
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .parse_prepared_statement("failed", "select * from schema_name.non_existent;", &[])
+        .expect("no system errors");
+    engine.sync();
+    engine
+        .parse_prepared_statement("rejected", "select * from schema_name.table_name;", &[])
+        .expect("no system errors");
+    engine.sync();
+    engine
+        .bind_prepared_statement_to_portal("portal_name", "statement_name", &[], &[], &[])
+        .expect("no system errors");
+    engine.sync();
+    engine.execute("rollback;").expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::ParseComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+        Err(QueryError::in_failed_sql_transaction()),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+        Err(QueryError::in_failed_sql_transaction()),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn created_table_is_dropped_on_rollback(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Ok(QueryEvent::TransactionRolledBack),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.table_name")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn dropped_table_is_kept_on_rollback(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    engine
+        .execute("drop table schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (4);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TableDropped),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Err(QueryError::table_does_not_exist("schema_name.table_name")),
+                Ok(QueryEvent::QueryCompleteInFailedTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2", "4"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
+
+#[rstest::rstest]
+fn table_dropped_after_savepoint_is_kept(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("begin;").expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    engine.execute("savepoint before_drop;").expect("no system errors");
+    engine
+        .execute("drop table schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("rollback to savepoint before_drop;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("rollback;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(
+        [
+            vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TransactionStarted),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::SavepointCreated),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TableDropped),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryCompleteInTransaction),
+            ],
+            selected(&["1", "2", "3"]),
+            vec![
+                Ok(QueryEvent::QueryCompleteInTransaction),
+                Ok(QueryEvent::TransactionRolledBack),
+                Ok(QueryEvent::QueryComplete),
+            ],
+            selected(&["1", "2"]),
+            vec![Ok(QueryEvent::QueryComplete)],
+        ]
+        .concat(),
+    );
+}
@@ -266,3 +266,34 @@ mod update {
         ]);
     }
 }
+
+#[rstest::rstest]
+fn unbounded_strings(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    let long_string = "a".repeat(1_000);
+    engine
+        .execute("create table schema_name.table_name(col_text text, col_varchar varchar);")
+        .expect("no system errors");
+    engine
+        .execute(format!("insert into schema_name.table_name values ('{0}', '{0}');", long_string).as_str())
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RowDescription(vec![
+            ("col_text".to_owned(), PostgreSqlType::Text),
+            ("col_varchar".to_owned(), PostgreSqlType::Text),
+        ])),
+        Ok(QueryEvent::DataRow(vec![long_string.clone(), long_string])),
+        Ok(QueryEvent::RecordsSelected(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
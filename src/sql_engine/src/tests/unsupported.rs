@@ -0,0 +1,94 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint, column_2 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 2), (3, 4);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest(
+    sql,
+    construct,
+    case::join(
+        "select * from schema_name.table_name as t1 join schema_name.table_name as t2 on t1.column_1 = t2.column_1;",
+        "JOIN"
+    ),
+    case::multiple_tables(
+        "select * from schema_name.table_name, schema_name.table_name;",
+        "multiple tables in FROM"
+    ),
+    case::group_by("select column_1 from schema_name.table_name group by column_1;", "GROUP BY"),
+    case::union(
+        "select * from schema_name.table_name union select * from schema_name.table_name;",
+        "UNION, EXCEPT or INTERSECT"
+    ),
+    case::having("select column_1 from schema_name.table_name having column_1 > 1;", "HAVING"),
+    case::distinct("select distinct column_1 from schema_name.table_name;", "SELECT DISTINCT"),
+    case::subquery_in_where(
+        "delete from schema_name.table_name where column_1 in (select column_2 from schema_name.table_name);",
+        "subquery"
+    ),
+    case::insert_select(
+        "insert into schema_name.table_name select * from schema_name.table_name;",
+        "INSERT ... SELECT"
+    ),
+)]
+fn statement_is_rejected_before_execution(
+    with_table: (QueryExecutor, ResultCollector),
+    sql: &str,
+    construct: &str,
+) {
+    let (mut engine, collector) = with_table;
+    engine.execute(sql).expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::feature_not_supported(construct)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn nested_expressions_are_evaluated(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values ((1 + 2) * 2, 5);")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![Ok(QueryEvent::RecordsInserted(1)), Ok(QueryEvent::QueryComplete)]);
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,108 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::pgsql_types::PostgreSqlType;
+
+use super::*;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2), (3);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (3);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn prelude() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn vacuum_table_keeps_its_rows(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("vacuum schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("vacuum;").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::TablesVacuumed),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TablesVacuumed),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected.extend(records_selected(
+        vec![("col".to_owned(), PostgreSqlType::SmallInt)],
+        vec![vec!["1".to_owned()], vec!["3".to_owned()]],
+    ));
+    expected.push(Ok(QueryEvent::QueryComplete));
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn vacuum_table_that_does_not_exist(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("vacuum schema_name.non_existent;")
+        .expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Err(QueryError::table_does_not_exist("schema_name.non_existent")),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn vacuum_in_transaction_block(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("begin;").expect("no system errors");
+    engine.execute("vacuum;").expect("no system errors");
+
+    let mut expected = prelude();
+    expected.extend(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::QueryCompleteInTransaction),
+        Err(QueryError::transaction_block_not_allowed("VACUUM")),
+        Ok(QueryEvent::QueryCompleteInFailedTransaction),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,157 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+///! Timings of statement processing stages reported to clients in trace mode
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use protocol::{
+    results::{Projection, QueryEvent, QueryResult},
+    Sender,
+};
+
+/// session variable that turns trace mode on and off
+pub(crate) const TRACE: &str = "trace";
+
+/// sender that accounts time spent in serializing and sending results to client
+pub(crate) struct TimedSender {
+    sender: Arc<dyn Sender>,
+    spent: Mutex<Duration>,
+    errors: AtomicUsize,
+    selected: Mutex<Option<Option<Projection>>>,
+}
+
+impl TimedSender {
+    pub(crate) fn new(sender: Arc<dyn Sender>) -> TimedSender {
+        TimedSender {
+            sender,
+            spent: Mutex::new(Duration::default()),
+            errors: AtomicUsize::new(0),
+            selected: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn spent(&self) -> Duration {
+        *self.spent.lock().expect("to acquire spent time lock")
+    }
+
+    /// number of errors sent to client
+    pub(crate) fn errors(&self) -> usize {
+        self.errors.load(Ordering::SeqCst)
+    }
+
+    /// selected records are collected to be fetched through a portal or a cursor instead of being sent
+    pub(crate) fn keep_selected(&self) {
+        *self.selected.lock().expect("to acquire selected records lock") = Some(None);
+    }
+
+    /// records kept since `keep_selected`, records are sent again after that
+    pub(crate) fn take_selected(&self) -> Option<Projection> {
+        self.selected
+            .lock()
+            .expect("to acquire selected records lock")
+            .take()
+            .flatten()
+    }
+}
+
+impl Sender for TimedSender {
+    fn flush(&self) -> io::Result<()> {
+        self.sender.flush()
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        if query_result.is_err() {
+            self.errors.fetch_add(1, Ordering::SeqCst);
+        }
+        let query_result = match (
+            self.selected.lock().expect("to acquire selected records lock").as_mut(),
+            query_result,
+        ) {
+            (Some(kept), Ok(QueryEvent::RowDescription(description))) => {
+                *kept = Some((description, vec![]));
+                return Ok(());
+            }
+            (Some(Some((_description, records))), Ok(QueryEvent::DataRow(record))) => {
+                records.push(record);
+                return Ok(());
+            }
+            (Some(_), Ok(QueryEvent::RecordsSelected(_))) => return Ok(()),
+            (_, query_result) => query_result,
+        };
+        let started = Instant::now();
+        let result = self.sender.send(query_result);
+        *self.spent.lock().expect("to acquire spent time lock") += started.elapsed();
+        result
+    }
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct StageTimings {
+    parse: Duration,
+    plan: Duration,
+    execute: Duration,
+    serialize: Duration,
+}
+
+impl StageTimings {
+    pub(crate) fn parse<T, F: FnOnce() -> T>(&mut self, stage: F) -> T {
+        let started = Instant::now();
+        let result = stage();
+        self.parse += started.elapsed();
+        result
+    }
+
+    pub(crate) fn plan<T, F: FnOnce() -> T>(&mut self, stage: F) -> T {
+        let started = Instant::now();
+        let result = stage();
+        self.plan += started.elapsed();
+        result
+    }
+
+    /// time spent in `sender` while executing is accounted as serialization
+    pub(crate) fn execute<T, F: FnOnce() -> T>(&mut self, sender: &TimedSender, stage: F) -> T {
+        let started = Instant::now();
+        let sent = sender.spent();
+        let result = stage();
+        let serialize = sender.spent() - sent;
+        self.execute += started.elapsed().checked_sub(serialize).unwrap_or_default();
+        self.serialize += serialize;
+        result
+    }
+}
+
+impl Display for StageTimings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parse: {:.3} ms, plan: {:.3} ms, execute: {:.3} ms, serialize: {:.3} ms",
+            millis(self.parse),
+            millis(self.plan),
+            millis(self.execute),
+            millis(self.serialize)
+        )
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -16,7 +16,13 @@ use std::convert::{TryFrom, TryInto};
 
 use serde::{Deserialize, Serialize};
 
-use protocol::pgsql_types::PostgreSqlType;
+use protocol::{
+    array, bytea,
+    clock::{parse_timestamp_with_time_zone, render_timestamp_with_time_zone},
+    interval::Interval,
+    json,
+    pgsql_types::PostgreSqlType,
+};
 use sqlparser::ast::DataType;
 use std::fmt::{self, Display, Formatter};
 
@@ -37,6 +43,16 @@ pub enum SqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    Text,
+    Bytea,
+    Json,
+    Jsonb,
+    SmallIntArray,
+    IntegerArray,
+    BigIntArray,
+    TextArray,
+    /// labels of user-defined enum types are kept by columns of the type
+    Enum,
 }
 
 impl TryFrom<&DataType> for SqlType {
@@ -48,14 +64,31 @@ impl TryFrom<&DataType> for SqlType {
             DataType::Int => Ok(SqlType::Integer(i32::min_value())),
             DataType::BigInt => Ok(SqlType::BigInt(i64::min_value())),
             DataType::Char(len) => Ok(SqlType::Char(len.unwrap_or(255))),
-            DataType::Varchar(len) => Ok(SqlType::VarChar(len.unwrap_or(255))),
+            DataType::Varchar(Some(len)) => Ok(SqlType::VarChar(*len)),
+            DataType::Varchar(None) | DataType::Text => Ok(SqlType::Text),
             DataType::Boolean => Ok(SqlType::Bool),
+            DataType::Interval => Ok(SqlType::Interval),
+            DataType::Bytea => Ok(SqlType::Bytea),
+            DataType::Array(element_type) => match **element_type {
+                DataType::SmallInt => Ok(SqlType::SmallIntArray),
+                DataType::Int => Ok(SqlType::IntegerArray),
+                DataType::BigInt => Ok(SqlType::BigIntArray),
+                DataType::Text | DataType::Varchar(_) => Ok(SqlType::TextArray),
+                _ => Err(NotSupportedType(data_type.clone())),
+            },
             DataType::Custom(name) => {
-                let name = name.to_string();
+                let name = name.to_string().to_lowercase();
                 match name.as_str() {
                     "serial" => Ok(SqlType::Integer(1)),
                     "smallserial" => Ok(SqlType::SmallInt(1)),
                     "bigserial" => Ok(SqlType::BigInt(1)),
+                    "timestamptz" => Ok(SqlType::TimestampWithTimeZone),
+                    "json" => Ok(SqlType::Json),
+                    "jsonb" => Ok(SqlType::Jsonb),
+                    "_int2" => Ok(SqlType::SmallIntArray),
+                    "_int4" => Ok(SqlType::IntegerArray),
+                    "_int8" => Ok(SqlType::BigIntArray),
+                    "_text" => Ok(SqlType::TextArray),
                     _other_type => Err(NotSupportedType(data_type.clone())),
                 }
             }
@@ -97,6 +130,15 @@ impl Into<&'static str> for &SqlType {
             SqlType::TimestampWithTimeZone => "timestamp with time zone",
             SqlType::Date => "date",
             SqlType::Interval => "interval",
+            SqlType::Text => "text",
+            SqlType::Bytea => "bytea",
+            SqlType::Json => "json",
+            SqlType::Jsonb => "jsonb",
+            SqlType::SmallIntArray => "smallint[]",
+            SqlType::IntegerArray => "integer[]",
+            SqlType::BigIntArray => "bigint[]",
+            SqlType::TextArray => "text[]",
+            SqlType::Enum => "anyenum",
         }
     }
 }
@@ -114,6 +156,16 @@ impl SqlType {
             Self::Integer(min) => Box::new(IntegerSqlTypeConstraint { min }),
             Self::BigInt(min) => Box::new(BigIntTypeConstraint { min }),
             Self::Bool => Box::new(BoolSqlTypeConstraint),
+            Self::TimestampWithTimeZone => Box::new(TimestampWithTimeZoneConstraint),
+            Self::Interval => Box::new(IntervalConstraint),
+            Self::Text | Self::Enum => Box::new(TextSqlTypeConstraint),
+            Self::Bytea => Box::new(ByteaConstraint),
+            Self::Json | Self::Jsonb => Box::new(JsonConstraint),
+            Self::SmallIntArray | Self::IntegerArray | Self::BigIntArray | Self::TextArray => {
+                Box::new(ArrayConstraint {
+                    element: self.element_type().expect("array type"),
+                })
+            }
             sql_type => unimplemented!("Type constraint for {:?} is not currently implemented", sql_type),
         }
     }
@@ -126,6 +178,13 @@ impl SqlType {
             Self::Integer(_min) => Box::new(IntegerSqlTypeSerializer),
             Self::BigInt(_min) => Box::new(BigIntTypeSerializer),
             Self::Bool => Box::new(BoolSqlTypeSerializer),
+            Self::TimestampWithTimeZone => Box::new(TimestampWithTimeZoneSerializer),
+            Self::Interval => Box::new(IntervalSerializer),
+            Self::Text | Self::Enum => Box::new(TextSqlTypeSerializer),
+            Self::Bytea => Box::new(ByteaSerializer),
+            Self::Json => Box::new(JsonSerializer),
+            Self::Jsonb => Box::new(JsonbSerializer),
+            Self::SmallIntArray | Self::IntegerArray | Self::BigIntArray | Self::TextArray => Box::new(ArraySerializer),
             sql_type => unimplemented!("Type Serializer for {:?} is not currently implemented", sql_type),
         }
     }
@@ -147,6 +206,26 @@ impl SqlType {
             Self::TimestampWithTimeZone => PostgreSqlType::TimestampWithTimeZone,
             Self::Date => PostgreSqlType::Date,
             Self::Interval => PostgreSqlType::Interval,
+            Self::Text => PostgreSqlType::Text,
+            Self::Bytea => PostgreSqlType::Bytea,
+            Self::Json => PostgreSqlType::Json,
+            Self::Jsonb => PostgreSqlType::Jsonb,
+            Self::SmallIntArray => PostgreSqlType::SmallIntArray,
+            Self::IntegerArray => PostgreSqlType::IntegerArray,
+            Self::BigIntArray => PostgreSqlType::BigIntArray,
+            Self::TextArray => PostgreSqlType::TextArray,
+            Self::Enum => PostgreSqlType::Text,
+        }
+    }
+
+    /// type of elements of array types
+    pub fn element_type(&self) -> Option<SqlType> {
+        match *self {
+            Self::SmallIntArray => Some(SqlType::SmallInt(i16::min_value())),
+            Self::IntegerArray => Some(SqlType::Integer(i32::min_value())),
+            Self::BigIntArray => Some(SqlType::BigInt(i64::min_value())),
+            Self::TextArray => Some(SqlType::Text),
+            _ => None,
         }
     }
 }
@@ -169,6 +248,15 @@ impl Into<PostgreSqlType> for &SqlType {
             SqlType::TimestampWithTimeZone => PostgreSqlType::TimestampWithTimeZone,
             SqlType::Date => PostgreSqlType::Date,
             SqlType::Interval => PostgreSqlType::Interval,
+            SqlType::Text => PostgreSqlType::Text,
+            SqlType::Bytea => PostgreSqlType::Bytea,
+            SqlType::Json => PostgreSqlType::Json,
+            SqlType::Jsonb => PostgreSqlType::Jsonb,
+            SqlType::SmallIntArray => PostgreSqlType::SmallIntArray,
+            SqlType::IntegerArray => PostgreSqlType::IntegerArray,
+            SqlType::BigIntArray => PostgreSqlType::BigIntArray,
+            SqlType::TextArray => PostgreSqlType::TextArray,
+            SqlType::Enum => PostgreSqlType::Text,
         }
     }
 }
@@ -358,6 +446,28 @@ impl Serializer for VarCharSqlTypeSerializer {
     }
 }
 
+/// text values have no length limit
+struct TextSqlTypeConstraint;
+
+impl Constraint for TextSqlTypeConstraint {
+    fn validate(&self, _in_value: &str) -> Result<(), ConstraintError> {
+        Ok(())
+    }
+}
+
+/// unlike char and varchar, trailing spaces of text values are significant
+struct TextSqlTypeSerializer;
+
+impl Serializer for TextSqlTypeSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        in_value.as_bytes().to_vec()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
 struct BoolSqlTypeConstraint;
 
 impl Constraint for BoolSqlTypeConstraint {
@@ -396,6 +506,173 @@ impl Serializer for BoolSqlTypeSerializer {
     }
 }
 
+struct TimestampWithTimeZoneConstraint;
+
+impl Constraint for TimestampWithTimeZoneConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match parse_timestamp_with_time_zone(in_value, 0) {
+            Some(_) => Ok(()),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+/// values without time zone are serialized as UTC ones, the session time zone is applied by the engine
+struct TimestampWithTimeZoneSerializer;
+
+impl Serializer for TimestampWithTimeZoneSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        match parse_timestamp_with_time_zone(in_value, 0) {
+            Some(micros) => micros.to_be_bytes().to_vec(),
+            None => unreachable!(),
+        }
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        render_timestamp_with_time_zone(i64::from_be_bytes(out_value[0..8].try_into().unwrap()), 0)
+    }
+}
+
+struct IntervalConstraint;
+
+impl Constraint for IntervalConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match Interval::parse(in_value) {
+            Some(_) => Ok(()),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+/// months, days and microseconds are serialized one after another
+struct IntervalSerializer;
+
+impl Serializer for IntervalSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        match Interval::parse(in_value) {
+            Some(interval) => {
+                let mut serialized = interval.months().to_be_bytes().to_vec();
+                serialized.extend_from_slice(&interval.days().to_be_bytes());
+                serialized.extend_from_slice(&interval.micros().to_be_bytes());
+                serialized
+            }
+            None => unreachable!(),
+        }
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        Interval::new(
+            i32::from_be_bytes(out_value[0..4].try_into().unwrap()),
+            i32::from_be_bytes(out_value[4..8].try_into().unwrap()),
+            i64::from_be_bytes(out_value[8..16].try_into().unwrap()),
+        )
+        .to_string()
+    }
+}
+
+struct ByteaConstraint;
+
+impl Constraint for ByteaConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match bytea::decode(in_value) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+/// bytes are stored as they are and rendered in hex format
+struct ByteaSerializer;
+
+impl Serializer for ByteaSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        bytea::decode(in_value).expect("validated bytea")
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        bytea::encode_hex(out_value)
+    }
+}
+
+struct JsonConstraint;
+
+impl Constraint for JsonConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match json::parse(in_value) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+/// json values are stored as they are written
+struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        in_value.as_bytes().to_vec()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
+/// jsonb values are stored without duplicated keys and insignificant whitespaces
+struct JsonbSerializer;
+
+impl Serializer for JsonbSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        json::parse(in_value)
+            .expect("validated json")
+            .normalized()
+            .to_string()
+            .into_bytes()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
+/// every element that is not NULL satisfies the constraint of the element type
+struct ArrayConstraint {
+    element: SqlType,
+}
+
+impl Constraint for ArrayConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        let elements = match array::parse(in_value) {
+            Ok(elements) => elements,
+            Err(_) => return Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        };
+        let constraint = self.element.constraint();
+        for element in elements.iter().flatten() {
+            match constraint.validate(element) {
+                Ok(()) => {}
+                Err(ConstraintError::TypeMismatch(_)) => {
+                    return Err(ConstraintError::TypeMismatch(in_value.to_owned()))
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// arrays are stored in the canonical text form: without whitespaces and with quotes only where they are needed
+struct ArraySerializer;
+
+impl Serializer for ArraySerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        array::render(&array::parse(in_value).expect("validated array")).into_bytes()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -442,6 +719,18 @@ mod tests {
             assert_eq!(pg_type, PostgreSqlType::VarChar);
         }
 
+        #[test]
+        fn text() {
+            let pg_type: PostgreSqlType = (&SqlType::Text).into();
+            assert_eq!(pg_type, PostgreSqlType::Text);
+        }
+
+        #[test]
+        fn bytea() {
+            let pg_type: PostgreSqlType = (&SqlType::Bytea).into();
+            assert_eq!(pg_type, PostgreSqlType::Bytea);
+        }
+
         #[test]
         fn decimal() {
             let pg_type: PostgreSqlType = (&SqlType::Decimal).into();
@@ -834,6 +1123,136 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod text {
+        use super::*;
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(SqlType::try_from(&DataType::Text).ok(), Some(SqlType::Text));
+            assert_eq!(SqlType::try_from(&DataType::Varchar(None)).ok(), Some(SqlType::Text));
+            assert_eq!(
+                SqlType::try_from(&DataType::Varchar(Some(10))).ok(),
+                Some(SqlType::VarChar(10))
+            );
+        }
+
+        #[test]
+        fn serialization() {
+            let serializer = SqlType::Text.serializer();
+            assert_eq!(serializer.des(&serializer.ser("str  ")), "str  ".to_owned());
+        }
+
+        #[test]
+        fn validation() {
+            assert_eq!(SqlType::Text.constraint().validate(&"1".repeat(1_000)), Ok(()));
+        }
+    }
+
+    #[cfg(test)]
+    mod bytea {
+        use super::*;
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(SqlType::try_from(&DataType::Bytea).ok(), Some(SqlType::Bytea));
+        }
+
+        #[test]
+        fn serialization() {
+            let serializer = SqlType::Bytea.serializer();
+            assert_eq!(serializer.ser("\\x00ff"), vec![0, 255]);
+            assert_eq!(serializer.des(&[0, 255]), "\\x00ff".to_owned());
+        }
+
+        #[test]
+        fn validation() {
+            let constraint = SqlType::Bytea.constraint();
+            assert_eq!(constraint.validate("\\x00ff"), Ok(()));
+            assert_eq!(
+                constraint.validate("\\x0g"),
+                Err(ConstraintError::TypeMismatch("\\x0g".to_owned()))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod json {
+        use super::*;
+        use sqlparser::ast::{Ident, ObjectName};
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(
+                SqlType::try_from(&DataType::Custom(ObjectName(vec![Ident::new("json")]))).ok(),
+                Some(SqlType::Json)
+            );
+            assert_eq!(
+                SqlType::try_from(&DataType::Custom(ObjectName(vec![Ident::new("JSONB")]))).ok(),
+                Some(SqlType::Jsonb)
+            );
+        }
+
+        #[test]
+        fn serialization() {
+            let json = r#"{"b": 1,  "a": 2, "b": 3}"#;
+            assert_eq!(SqlType::Json.serializer().ser(json), json.as_bytes().to_vec());
+            assert_eq!(
+                SqlType::Jsonb.serializer().ser(json),
+                r#"{"a": 2, "b": 3}"#.as_bytes().to_vec()
+            );
+        }
+
+        #[test]
+        fn validation() {
+            let constraint = SqlType::Json.constraint();
+            assert_eq!(constraint.validate(r#"[1, "a", null]"#), Ok(()));
+            assert_eq!(
+                constraint.validate("{a: 1}"),
+                Err(ConstraintError::TypeMismatch("{a: 1}".to_owned()))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod arrays {
+        use super::*;
+        use sqlparser::ast::{Ident, ObjectName};
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(
+                SqlType::try_from(&DataType::Array(Box::new(DataType::Text))).ok(),
+                Some(SqlType::TextArray)
+            );
+            assert_eq!(
+                SqlType::try_from(&DataType::Custom(ObjectName(vec![Ident::new("_int4")]))).ok(),
+                Some(SqlType::IntegerArray)
+            );
+        }
+
+        #[test]
+        fn serialization() {
+            let serializer = SqlType::TextArray.serializer();
+            assert_eq!(serializer.ser("{ a , \"b c\", \"d\"}"), b"{a,\"b c\",d}".to_vec());
+        }
+
+        #[test]
+        fn validation() {
+            let constraint = SqlType::SmallIntArray.constraint();
+            assert_eq!(constraint.validate("{1, NULL, -3}"), Ok(()));
+            assert_eq!(
+                constraint.validate("{1, a}"),
+                Err(ConstraintError::TypeMismatch("{1, a}".to_owned()))
+            );
+            assert_eq!(constraint.validate("{1, 32768}"), Err(ConstraintError::OutOfRange));
+            assert_eq!(
+                constraint.validate("1, 2"),
+                Err(ConstraintError::TypeMismatch("1, 2".to_owned()))
+            );
+        }
+    }
+
     mod bool {
         use super::*;
 
@@ -916,4 +1335,64 @@ mod tests {
             }
         }
     }
+
+    #[cfg(test)]
+    mod timestamp_with_time_zone {
+        use super::*;
+        use sqlparser::ast::{Ident, ObjectName};
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(
+                SqlType::try_from(&DataType::Custom(ObjectName(vec![Ident::new("timestamptz")]))).ok(),
+                Some(SqlType::TimestampWithTimeZone)
+            );
+        }
+
+        #[test]
+        fn serialization() {
+            let serializer = SqlType::TimestampWithTimeZone.serializer();
+            let serialized = serializer.ser("2020-08-01 12:30:00+03");
+            assert_eq!(serialized, 1_596_274_200_000_000i64.to_be_bytes().to_vec());
+            assert_eq!(serializer.des(&serialized), "2020-08-01 09:30:00+00".to_owned());
+        }
+
+        #[test]
+        fn validation() {
+            let constraint = SqlType::TimestampWithTimeZone.constraint();
+            assert_eq!(constraint.validate("2020-08-01 12:30:00.5-05:30"), Ok(()));
+            assert_eq!(
+                constraint.validate("2020-13-01"),
+                Err(ConstraintError::TypeMismatch("2020-13-01".to_owned()))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod interval {
+        use super::*;
+
+        #[test]
+        fn from_data_type() {
+            assert_eq!(SqlType::try_from(&DataType::Interval).ok(), Some(SqlType::Interval));
+        }
+
+        #[test]
+        fn serialization() {
+            let serializer = SqlType::Interval.serializer();
+            let serialized = serializer.ser("1 year 2 days 03:00:00");
+            assert_eq!(serialized.len(), 16);
+            assert_eq!(serializer.des(&serialized), "1 year 2 days 03:00:00".to_owned());
+        }
+
+        #[test]
+        fn validation() {
+            let constraint = SqlType::Interval.constraint();
+            assert_eq!(constraint.validate("1.5 hours"), Ok(()));
+            assert_eq!(
+                constraint.validate("soon"),
+                Err(ConstraintError::TypeMismatch("soon".to_owned()))
+            );
+        }
+    }
 }